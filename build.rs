@@ -1,6 +1,10 @@
 //! Build script for `InferaDB` CLI.
 //!
-//! This sets Windows-specific linker flags to increase the default stack size.
+//! This sets Windows-specific linker flags to increase the default stack size
+//! and records build provenance (git commit, build time, compiler, target, and
+//! enabled features) for `inferadb version --verbose` and `--sbom`.
+
+use std::{path::Path, process::Command};
 
 fn main() {
     // Increase stack size on Windows to 8MB (default is 1MB, Linux/macOS default is 8MB).
@@ -11,4 +15,76 @@ fn main() {
     if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") {
         println!("cargo:rustc-link-arg=/STACK:8388608");
     }
+
+    provenance();
+}
+
+/// Emit `INFERADB_BUILD_*` environment variables for `src/provenance.rs`.
+fn provenance() {
+    // Release pipelines can pin the commit and time for reproducible builds.
+    println!("cargo:rerun-if-env-changed=INFERADB_BUILD_GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=build.rs");
+    for path in [".git/HEAD", ".git/index"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+    if let Some(head) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        let path = format!(".git/{head}");
+        if Path::new(&path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+
+    let sha = std::env::var("INFERADB_BUILD_GIT_SHA").ok().or_else(|| {
+        let sha = git(&["rev-parse", "HEAD"])?;
+        let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+            .is_some_and(|status| !status.is_empty());
+        Some(if dirty { format!("{sha}-dirty") } else { sha })
+    });
+
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH").ok().or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs().to_string())
+    });
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+
+    let vars = [
+        ("INFERADB_BUILD_GIT_SHA", sha),
+        ("INFERADB_BUILD_TIMESTAMP", timestamp),
+        ("INFERADB_BUILD_RUSTC", rustc_version),
+        ("INFERADB_BUILD_TARGET", std::env::var("TARGET").ok()),
+        ("INFERADB_BUILD_PROFILE", std::env::var("PROFILE").ok()),
+        ("INFERADB_BUILD_FEATURES", Some(features.join(","))),
+    ];
+    for (key, value) in vars {
+        println!("cargo:rustc-env={key}={}", value.unwrap_or_default());
+    }
+}
+
+/// Run git, returning trimmed stdout on success.
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
 }
@@ -3,7 +3,7 @@
 //! Uses clap with derive macros for type-safe argument parsing.
 //! Help text is localized at runtime using the i18n system.
 
-use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 
 use crate::t;
 
@@ -14,7 +14,7 @@ use crate::t;
 #[command(propagate_version = true)]
 #[command(arg_required_else_help = true)]
 pub struct Cli {
-    /// Profile to use (can also use @profile syntax as first argument)
+    /// Profile to use (can also use @profile syntax anywhere in the command)
     #[arg(long, global = true, env = "INFERADB_PROFILE")]
     pub profile: Option<String>,
 
@@ -26,10 +26,16 @@ pub struct Cli {
     #[arg(short, long, global = true, env = "INFERADB_VAULT")]
     pub vault: Option<String>,
 
-    /// Output format
-    #[arg(short, long, global = true, default_value = "table", value_parser = ["table", "json", "yaml", "jsonl"])]
+    /// Output format: table, json, yaml, jsonl, csv, `template=<template>`
+    /// (e.g., `template='{{.id}}'`) or `template-file=<path>`
+    #[arg(short, long, global = true, default_value = "table", value_parser = parse_output_format)]
     pub output: String,
 
+    /// Filter structured output with a `JMESPath` expression before formatting
+    /// (e.g., --query "[?relation=='viewer'].resource")
+    #[arg(long = "query", global = true, value_name = "EXPR")]
+    pub output_query: Option<String>,
+
     /// Color output mode
     #[arg(long, global = true, default_value = "auto", value_parser = ["auto", "always", "never"])]
     pub color: String,
@@ -38,7 +44,7 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
-    /// Enable debug logging
+    /// Enable debug logging and print where the command spent its time
     #[arg(long, global = true)]
     pub debug: bool,
 
@@ -46,6 +52,96 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub yes: bool,
 
+    /// Screen-reader friendly output: plain status lines instead of spinners,
+    /// no glyphs or colors, and no full-screen views
+    #[arg(long, global = true, env = "INFERADB_ACCESSIBLE", value_parser = clap::builder::BoolishValueParser::new())]
+    pub accessible: bool,
+
+    /// Show timestamps in UTC instead of the local timezone
+    #[arg(long, global = true, conflicts_with = "relative")]
+    pub utc: bool,
+
+    /// Show timestamps in this timezone: an IANA name (e.g., Europe/Berlin),
+    /// UTC, local, or an offset like +05:30 (overrides the profile's timezone)
+    #[arg(
+        long,
+        global = true,
+        env = "INFERADB_TIMEZONE",
+        value_name = "TZ",
+        conflicts_with = "utc"
+    )]
+    pub timezone: Option<String>,
+
+    /// Show timestamps relative to now (e.g., "3 hours ago")
+    #[arg(long, global = true)]
+    pub relative: bool,
+
+    /// Use ASCII instead of Unicode checkmarks, bullets, and box drawing
+    #[arg(long, global = true, env = "INFERADB_ASCII", value_parser = clap::builder::BoolishValueParser::new())]
+    pub ascii: bool,
+
+    /// Serve list and get commands from the response cache when fresh
+    #[arg(long, global = true, conflicts_with = "no_cache")]
+    pub cached: bool,
+
+    /// Bypass the response cache, even if `cache.enabled` is set
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Store credentials in an owner-only file instead of the OS keychain
+    /// (for containers and SSH sessions)
+    #[arg(long, global = true, env = "INFERADB_NO_KEYCHAIN", value_parser = clap::builder::BoolishValueParser::new())]
+    pub no_keychain: bool,
+
+    /// Fail with "another operation in progress" instead of waiting when
+    /// another invocation is updating the same cache, journal or checkpoint
+    #[arg(long, global = true, env = "INFERADB_NON_BLOCKING", value_parser = clap::builder::BoolishValueParser::new())]
+    pub non_blocking: bool,
+
+    /// Post a summary to this Slack, Teams, or generic webhook when imports,
+    /// bulk writes, schema pushes, tests and migrations finish
+    #[arg(long, global = true, value_name = "URL", env = "INFERADB_NOTIFY_WEBHOOK")]
+    pub notify_webhook: Option<String>,
+
+    /// Per-request timeout in seconds
+    #[arg(long, global = true, value_name = "SECONDS", env = "INFERADB_TIMEOUT", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// Retry failed requests up to N times, with jittered exponential backoff
+    /// (default: 3)
+    #[arg(long, global = true, value_name = "N", env = "INFERADB_RETRIES")]
+    pub retries: Option<u32>,
+
+    /// HTTP statuses to retry, comma-separated (default: 429,502,503,504).
+    /// Bulk writes and retagging retry exactly these; other requests retry
+    /// 429 and every 5xx while any of those is listed, and nothing otherwise
+    #[arg(long, global = true, value_name = "CODES", env = "INFERADB_RETRY_ON")]
+    pub retry_on: Option<String>,
+
+    /// Record API responses as fixtures in DIR while running the command
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        env = "INFERADB_RECORD",
+        conflicts_with = "replay"
+    )]
+    pub record: Option<std::path::PathBuf>,
+
+    /// Replay API responses from fixtures in DIR, without network access
+    #[arg(long, global = true, value_name = "DIR", env = "INFERADB_REPLAY")]
+    pub replay: Option<std::path::PathBuf>,
+
+    /// Silence warnings with these codes, comma-separated (or `all`)
+    #[arg(
+        long,
+        global = true,
+        value_name = "CODES",
+        env = "INFERADB_SUPPRESS_WARNINGS",
+        value_delimiter = ','
+    )]
+    pub suppress_warnings: Vec<String>,
+
     /// Language for CLI output (e.g., en-US)
     #[arg(long, global = true, env = "INFERADB_LANG", default_value = "en-US")]
     pub lang: String,
@@ -59,10 +155,27 @@ pub struct Cli {
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Authenticate with `InferaDB`
-    Login,
+    Login {
+        /// Sign in by entering a code on another device, for SSH sessions and
+        /// CI runners without a browser
+        #[arg(long, conflicts_with = "client_id")]
+        device_code: bool,
+
+        /// Sign in as an org client (service account) instead of a user
+        #[arg(long, requires = "client_key")]
+        client_id: Option<String>,
+
+        /// Ed25519 private key (PEM) registered for the org client
+        #[arg(long, value_name = "PATH", requires = "client_id")]
+        client_key: Option<String>,
+    },
 
     /// Remove authentication
-    Logout,
+    Logout {
+        /// Log out from every profile with stored credentials
+        #[arg(long)]
+        all_profiles: bool,
+    },
 
     /// Create a new account
     Register {
@@ -108,21 +221,37 @@ pub enum Commands {
         /// Include detailed metrics
         #[arg(long)]
         verbose: bool,
+
+        /// Serve Prometheus metrics on this address (e.g., 127.0.0.1:9464)
+        #[arg(long, value_name = "ADDR", requires = "watch")]
+        metrics_listen: Option<String>,
     },
 
     /// Show CLI version
-    Version,
+    Version {
+        /// Include build metadata (git commit, build date, rustc, target, features)
+        #[arg(long)]
+        verbose: bool,
+
+        /// Print a `CycloneDX` software bill of materials (JSON)
+        #[arg(long, conflicts_with = "verbose")]
+        sbom: bool,
+    },
 
     /// Check authorization
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Check {
         /// Subject (e.g., user:alice)
-        subject: String,
+        #[arg(required = true)]
+        subject: Option<String>,
 
         /// Permission to check (e.g., `can_view`)
-        permission: String,
+        #[arg(required = true)]
+        permission: Option<String>,
 
         /// Resource (e.g., document:readme)
-        resource: String,
+        #[arg(required = true)]
+        resource: Option<String>,
 
         /// Show resolution trace
         #[arg(long)]
@@ -132,9 +261,45 @@ pub enum Commands {
         #[arg(long)]
         explain: bool,
 
+        /// On denial, search for the nearest-miss paths and print remediation steps
+        #[arg(long)]
+        explain_denied: bool,
+
+        /// Confirm checking as a subject other than the authenticated principal
+        #[arg(long)]
+        impersonate: bool,
+
         /// ABAC context as JSON
         #[arg(long)]
         context: Option<String>,
+
+        /// Read the ABAC context JSON from a file (`-` for stdin)
+        #[arg(long, value_name = "FILE", conflicts_with = "context")]
+        context_file: Option<String>,
+
+        /// Exit 0 when allowed, 1 when denied, and 2 or above on errors
+        #[arg(long)]
+        exit_code: bool,
+
+        /// Keep running and print a timestamped line whenever the decision flips
+        #[arg(long, conflicts_with_all = ["exit_code", "explain_denied", "offline"])]
+        watch: bool,
+
+        /// Evaluate locally against an exported snapshot instead of the vault
+        #[arg(
+            long,
+            requires = "snapshot",
+            conflicts_with_all = ["trace", "explain", "explain_denied", "impersonate", "context", "context_file"]
+        )]
+        offline: bool,
+
+        /// Export file (JSON or YAML, with schema) to evaluate with --offline
+        #[arg(long, value_name = "FILE", requires = "offline")]
+        snapshot: Option<String>,
+
+        /// Check subcommand (e.g., `batch`); omit to run a single check
+        #[command(subcommand)]
+        command: Option<CheckCommands>,
     },
 
     /// Simulate authorization with hypothetical changes
@@ -155,6 +320,10 @@ pub enum Commands {
         /// Relationships to remove
         #[arg(long = "remove")]
         remove_relationships: Vec<String>,
+
+        /// If still denied, suggest minimal relationship additions that would allow it
+        #[arg(long)]
+        suggest: bool,
     },
 
     /// Show userset expansion tree
@@ -214,10 +383,20 @@ pub enum Commands {
     #[command(subcommand)]
     Profiles(Box<ProfilesCommands>),
 
+    /// Pick the default profile, organization, and vault with fuzzy search
+    Switch {
+        /// Initial search (e.g., "acme prod")
+        query: Option<String>,
+    },
+
     /// Manage configuration
     #[command(subcommand)]
     Config(Box<ConfigCommands>),
 
+    /// Manage the response cache
+    #[command(subcommand)]
+    Cache(Box<CacheCommands>),
+
     /// Manage relationships
     #[command(subcommand)]
     Relationships(Box<RelationshipsCommands>),
@@ -252,9 +431,56 @@ pub enum Commands {
         #[arg(long)]
         resource_type: Option<String>,
 
-        /// Format (json, yaml, csv)
+        /// Format (json, yaml, csv, parquet, openfga, spicedb)
         #[arg(long, default_value = "json")]
         format: String,
+
+        /// Include when, by whom, and from where each relationship was written (from the audit
+        /// log)
+        #[arg(long)]
+        with_metadata: bool,
+
+        /// Export up to N resource types of the active schema at once
+        #[arg(long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..=64))]
+        parallel: u32,
+
+        /// Per-step timing summary
+        #[command(flatten)]
+        timings: TimingsArgs,
+    },
+
+    /// Query relationships with a SQL-like statement
+    Query {
+        /// Query statement (SELECT ... FROM relationships [WHERE ...] [ORDER BY ...] [LIMIT n])
+        statement: String,
+
+        /// Show which filters run server-side instead of running the query
+        #[arg(long)]
+        explain: bool,
+    },
+
+    /// Reconcile the vault with a desired-state file of relationships
+    Apply {
+        /// YAML or JSON file with the desired relationships and optional
+        /// resource-type selectors
+        #[arg(short, long)]
+        file: String,
+
+        /// Delete relationships under the selectors that the file does not list
+        #[arg(long)]
+        prune: bool,
+
+        /// Show what would be added and deleted without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// With --dry-run, show only the counts instead of every change
+        #[arg(long, requires = "dry_run")]
+        summary: bool,
+
+        /// Skip confirmation
+        #[arg(long)]
+        yes: bool,
     },
 
     /// Import relationships from file
@@ -262,17 +488,46 @@ pub enum Commands {
         /// Input file path
         file: String,
 
+        /// Input format (json, yaml, csv, openfga, spicedb); detected from the
+        /// file when omitted
+        #[arg(long, value_parser = ["json", "yaml", "csv", "openfga", "spicedb"])]
+        format: Option<String>,
+
         /// Skip confirmation
         #[arg(long)]
         yes: bool,
 
-        /// Dry run (validate only)
+        /// Show what the import would add, remove, and leave unchanged
+        /// without writing anything
         #[arg(long)]
         dry_run: bool,
 
+        /// With --dry-run, show only the counts instead of every change
+        #[arg(long, requires = "dry_run")]
+        summary: bool,
+
         /// Import mode (merge, replace, upsert)
         #[arg(long, default_value = "upsert")]
         mode: String,
+
+        /// CSV of `old_id,new_id` pairs applied to subjects and resources
+        /// (IDs may be bare or type-qualified, e.g., 42 or user:42)
+        #[arg(long, value_name = "FILE")]
+        id_map: Option<String>,
+
+        /// jq expression applied to each row before import (e.g.,
+        /// 'select(.resource | startswith("doc:")) | .relation |= sub("reader"; "viewer")')
+        #[arg(long, value_name = "JQ_EXPR")]
+        transform: Option<String>,
+
+        /// WASI module that maps rows before import, reading and writing NDJSON
+        /// (run with wasmtime)
+        #[arg(long, value_name = "MODULE", conflicts_with = "transform")]
+        transform_wasm: Option<String>,
+
+        /// Per-step timing summary
+        #[command(flatten)]
+        timings: TimingsArgs,
     },
 
     /// Watch real-time relationship changes
@@ -284,6 +539,40 @@ pub enum Commands {
         /// Filter by relation
         #[arg(long)]
         relation: Option<String>,
+
+        /// Serve Prometheus metrics on this address (e.g., 127.0.0.1:9464)
+        #[arg(long, value_name = "ADDR", conflicts_with = "assert")]
+        metrics_listen: Option<String>,
+
+        /// Wait for a matching change (resource#relation@subject, parts may be *) and exit 0,
+        /// or fail after --timeout seconds (default 30)
+        #[arg(long, value_name = "TUPLE", conflicts_with_all = ["resource_type", "relation"])]
+        assert: Option<String>,
+
+        /// With --assert, wait for a deletion instead of a creation
+        #[arg(long, requires = "assert")]
+        deleted: bool,
+    },
+
+    /// Browse relationships in a full-screen explorer: drill into subjects, expand relations,
+    /// and run checks
+    Explore {
+        /// Resource to start from (e.g., document:readme); a sample of all resources when omitted
+        resource: Option<String>,
+    },
+
+    /// Run a YAML script of checks, lookups and writes, with variables, conditions and loops
+    Run {
+        /// Script file
+        file: String,
+
+        /// Set a script variable, overriding its `vars` (repeatable)
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+
+        /// Log writes and deletes instead of making them
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Vault relationship statistics
@@ -295,6 +584,10 @@ pub enum Commands {
         /// Compact single-line output
         #[arg(long)]
         compact: bool,
+
+        /// Fail when a metric moved more than this since the last snapshot (e.g., 10%)
+        #[arg(long, value_name = "PERCENT")]
+        alert_on_change: Option<String>,
     },
 
     /// Recent vault changes summary
@@ -324,8 +617,43 @@ pub enum Commands {
         compact: bool,
     },
 
+    /// Make an authenticated request to any API endpoint
+    Api {
+        /// Endpoint path, e.g. /control/v1/organizations/{org}/members ({org} and {vault} expand
+        /// from the profile)
+        path: String,
+
+        /// HTTP method (default GET, or POST with --field or --input)
+        #[arg(long, short = 'X')]
+        method: Option<String>,
+
+        /// Request field as key=value (repeatable; query parameters for GET and DELETE)
+        #[arg(long = "field", short = 'f', value_name = "KEY=VALUE")]
+        fields: Vec<String>,
+
+        /// Extra header as "Name: value" (repeatable)
+        #[arg(long = "header", short = 'H', value_name = "NAME: VALUE")]
+        headers: Vec<String>,
+
+        /// File to send as the request body ("-" for stdin)
+        #[arg(long, value_name = "FILE")]
+        input: Option<String>,
+
+        /// Follow next cursors and print the items of every page
+        #[arg(long)]
+        paginate: bool,
+    },
+
     /// Interactive shell (REPL)
-    Shell,
+    Shell {
+        /// Record the session to an asciinema (.cast) file
+        #[arg(long, value_name = "FILE")]
+        record: Option<String>,
+
+        /// Literal text to mask in the recording (repeatable)
+        #[arg(long, value_name = "TEXT", requires = "record")]
+        redact: Vec<String>,
+    },
 
     /// Show quick reference card
     Cheatsheet {
@@ -354,10 +682,29 @@ pub enum Commands {
         name: Option<String>,
     },
 
+    /// Show deprecated commands and flags
+    #[command(subcommand)]
+    Deprecations(Box<DeprecationsCommands>),
+
+    /// Show the API endpoints, required role and side effects of a command
+    Explain {
+        /// Command to explain (e.g., `relationships add`); omit to list explained commands
+        #[arg(value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+
     /// Local development cluster
     #[command(subcommand)]
     Dev(Box<DevCommands>),
 
+    /// Generate observability and CI configuration
+    #[command(subcommand)]
+    Generate(Box<GenerateCommands>),
+
+    /// Release automation
+    #[command(subcommand, hide = true)]
+    Release(Box<ReleaseCommands>),
+
     /// Generate shell completions
     Completion {
         /// Shell to generate completions for
@@ -379,6 +726,33 @@ pub enum Shell {
     PowerShell,
 }
 
+/// The `--timings` flag of multi-step commands.
+#[derive(Args, Debug)]
+pub struct TimingsArgs {
+    /// Print a per-step timing summary to stderr (table or json)
+    #[arg(long, num_args = 0..=1, default_missing_value = "table", value_parser = ["table", "json"])]
+    pub timings: Option<String>,
+}
+
+/// Check subcommands.
+#[derive(Subcommand, Debug)]
+pub enum CheckCommands {
+    /// Run many checks from a file and compare them with expected results
+    Batch {
+        /// YAML or JSON list of subject, permission, resource, and expected
+        #[arg(short, long)]
+        file: String,
+
+        /// Checks to run at once
+        #[arg(long, default_value = "16", value_parser = clap::value_parser!(u32).range(1..=256))]
+        concurrency: u32,
+
+        /// Also report results for CI: `junit:<path>` or `github` (repeatable)
+        #[arg(long, value_name = "FORMAT")]
+        report: Vec<String>,
+    },
+}
+
 /// Profile management commands.
 #[derive(Subcommand, Debug)]
 pub enum ProfilesCommands {
@@ -407,6 +781,22 @@ pub enum ProfilesCommands {
         /// Vault ID
         #[arg(long)]
         vault: Option<String>,
+
+        /// Subject reference of the authenticated principal (e.g., user:alice)
+        #[arg(long)]
+        principal: Option<String>,
+
+        /// Require --impersonate to check as a subject other than the principal
+        #[arg(long)]
+        require_impersonate: Option<bool>,
+
+        /// Only activate schemas with a verified signature (--verify-signature)
+        #[arg(long)]
+        require_signed_schemas: Option<bool>,
+
+        /// Timezone for timestamps (IANA name, UTC, local, or offset like +05:30)
+        #[arg(long, value_name = "TZ")]
+        timezone: Option<String>,
     },
 
     /// Update an existing profile
@@ -425,6 +815,23 @@ pub enum ProfilesCommands {
         /// Vault ID
         #[arg(long)]
         vault: Option<String>,
+
+        /// Subject reference of the authenticated principal (e.g., user:alice)
+        #[arg(long)]
+        principal: Option<String>,
+
+        /// Require --impersonate to check as a subject other than the principal
+        #[arg(long)]
+        require_impersonate: Option<bool>,
+
+        /// Only activate schemas with a verified signature (--verify-signature)
+        #[arg(long)]
+        require_signed_schemas: Option<bool>,
+
+        /// Timezone for timestamps (IANA name, UTC, local, or offset like
+        /// +05:30; empty to clear)
+        #[arg(long, value_name = "TZ")]
+        timezone: Option<String>,
     },
 
     /// Rename a profile
@@ -447,6 +854,45 @@ pub enum ProfilesCommands {
         /// Profile name (omit to show current default)
         name: Option<String>,
     },
+
+    /// Show each profile's sign-in state, token expiry and last use
+    Status,
+
+    /// Write profile settings to a shareable bundle (no credentials or
+    /// principals)
+    Export {
+        /// Profiles to export (default: all)
+        names: Vec<String>,
+
+        /// Bundle file to write (default: stdout)
+        #[arg(short, long)]
+        file: Option<String>,
+
+        /// Leave output preferences out of the bundle
+        #[arg(long)]
+        no_output: bool,
+    },
+
+    /// Add or update profiles from a bundle written by 'profiles export'
+    Import {
+        /// Bundle file ('-' for stdin)
+        file: String,
+
+        /// What to do with a profile that exists with different settings
+        #[arg(long, value_enum, default_value = "ask")]
+        on_conflict: ImportConflict,
+    },
+}
+
+/// How `profiles import` resolves a profile that already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportConflict {
+    /// Show the differences and ask (replaces with --yes).
+    Ask,
+    /// Replace the local profile.
+    Overwrite,
+    /// Keep the local profile.
+    Skip,
 }
 
 /// Configuration commands.
@@ -474,6 +920,47 @@ pub enum ConfigCommands {
 
     /// Explain configuration resolution
     Explain,
+
+    /// Trust a project config (.inferadb-cli.yaml) with its current contents
+    Trust {
+        /// Project config file (default: the nearest one)
+        path: Option<String>,
+    },
+
+    /// Stop trusting a project config
+    Untrust {
+        /// Project config file (default: the nearest one)
+        path: Option<String>,
+    },
+}
+
+/// Response cache commands.
+#[derive(Subcommand, Debug)]
+pub enum CacheCommands {
+    /// Show cached responses for the current profile
+    Status,
+
+    /// Delete cached responses
+    Clear {
+        /// Clear every profile's cache
+        #[arg(long)]
+        all: bool,
+    },
+}
+
+/// Deprecation commands.
+#[derive(Subcommand, Debug)]
+pub enum DeprecationsCommands {
+    /// List deprecated usages, marking the ones this invocation relies on
+    List {
+        /// Only show deprecated usages that are in use
+        #[arg(long)]
+        used: bool,
+
+        /// Command line to check instead of this one (e.g., `-- what-can user:alice view`)
+        #[arg(last = true, value_name = "ARGS")]
+        args: Vec<String>,
+    },
 }
 
 /// Relationship management commands.
@@ -493,13 +980,49 @@ pub enum RelationshipsCommands {
         #[arg(long)]
         relation: Option<String>,
 
-        /// Maximum results
+        /// Filter by subject set relation (e.g., member matches group:eng#member)
+        #[arg(long)]
+        subject_relation: Option<String>,
+
+        /// Filter by resource prefix (e.g., document: or document:2024-)
+        #[arg(long)]
+        resource_prefix: Option<String>,
+
+        /// Maximum results (the page size with --all)
         #[arg(long, default_value = "100")]
         limit: u32,
 
+        /// List every page, writing rows as they arrive
+        #[arg(long, conflicts_with = "save_cursor")]
+        all: bool,
+
         /// Pagination cursor
         #[arg(long)]
         cursor: Option<String>,
+
+        /// Resume from a cursor saved with --save-cursor
+        #[arg(long, value_name = "NAME", conflicts_with = "cursor")]
+        from_cursor: Option<String>,
+
+        /// Save the next page's cursor under a name (removed once the end is reached)
+        #[arg(long, value_name = "NAME")]
+        save_cursor: Option<String>,
+
+        /// Show when, by whom, and from where each relationship was written (from the audit log)
+        #[arg(long)]
+        wide: bool,
+
+        /// Only relationships written by this actor (email or ID)
+        #[arg(long, value_name = "ACTOR")]
+        created_by: Option<String>,
+
+        /// Only relationships written at or after this time (e.g., 7d, yesterday, RFC 3339)
+        #[arg(long, value_name = "TIME")]
+        created_after: Option<String>,
+
+        /// Only relationships written before this time (e.g., 1h, RFC 3339)
+        #[arg(long, value_name = "TIME")]
+        created_before: Option<String>,
     },
 
     /// Add a relationship
@@ -518,6 +1041,17 @@ pub enum RelationshipsCommands {
         if_not_exists: bool,
     },
 
+    /// Add relationships streamed from NDJSON, one {resource, relation, subject} per line
+    BulkAdd {
+        /// NDJSON file (reads stdin when omitted or "-")
+        #[arg(long, short = 'f', value_name = "FILE")]
+        file: Option<String>,
+
+        /// Relationships written per request
+        #[arg(long, default_value = "100", value_parser = clap::value_parser!(u32).range(1..=1000))]
+        batch_size: u32,
+    },
+
     /// Delete a relationship
     Delete {
         /// Subject
@@ -534,6 +1068,20 @@ pub enum RelationshipsCommands {
         if_exists: bool,
     },
 
+    /// Follow grants and revokes involving one resource or subject
+    Watch {
+        /// Resource or subject to follow (e.g., document:readme, user:alice, group:eng)
+        object: String,
+
+        /// Only changes to this relation
+        #[arg(long)]
+        relation: Option<String>,
+
+        /// Also raise a desktop notification for each change
+        #[arg(long)]
+        notify: bool,
+    },
+
     /// Show relationship history
     History {
         /// Resource filter
@@ -548,6 +1096,56 @@ pub enum RelationshipsCommands {
         #[arg(long)]
         to: Option<String>,
     },
+
+    /// Rewrite resource ID prefixes (e.g., when consolidating tenants) via add + delete,
+    /// recording an undo journal
+    Retag {
+        /// ID prefix to replace (e.g., tenantA/, or document:tenantA/ for one type)
+        #[arg(long, required_unless_present = "undo", requires = "to")]
+        resource_prefix: Option<String>,
+
+        /// Replacement prefix
+        #[arg(long, requires = "resource_prefix")]
+        to: Option<String>,
+
+        /// Also rewrite subjects whose IDs start with the prefix
+        #[arg(long)]
+        include_subjects: bool,
+
+        /// Relationships rewritten per batch
+        #[arg(long, default_value = "100", value_parser = clap::value_parser!(u32).range(1..=1000))]
+        batch_size: u32,
+
+        /// Show the rewrites without making changes
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Reverse a previous run from its journal
+        #[arg(long, value_name = "JOURNAL", conflicts_with_all = ["resource_prefix", "include_subjects", "dry_run"])]
+        undo: Option<String>,
+    },
+    /// Render the relationships around a resource as a Mermaid, DOT, or D2 graph
+    Graph {
+        /// Resource to start from (e.g., document:readme)
+        #[arg(long)]
+        resource: String,
+
+        /// Levels of relationships to follow from the resource
+        #[arg(long, default_value = "2", value_parser = clap::value_parser!(u32).range(1..=10))]
+        depth: u32,
+
+        /// Output format (mermaid, dot, d2)
+        #[arg(long, default_value = "mermaid")]
+        format: String,
+
+        /// Stop after this many relationships
+        #[arg(long, default_value = "500")]
+        max_edges: usize,
+
+        /// Open the graph in the browser instead of printing it
+        #[arg(long)]
+        open: bool,
+    },
 }
 
 /// Schema management commands.
@@ -569,12 +1167,92 @@ pub enum SchemasCommands {
         /// Include inactive versions
         #[arg(long)]
         all: bool,
+
+        /// Only show versions with this label (key=value, repeatable)
+        #[arg(long = "label", value_name = "KEY=VALUE")]
+        labels: Vec<String>,
     },
 
     /// Get schema content
     Get {
         /// Schema ID (or "active" for current)
         id: String,
+
+        /// Write the schema and its metadata to this directory instead of printing
+        #[arg(long, value_name = "DIR")]
+        bundle: Option<String>,
+
+        /// Test file to include in the bundle
+        #[arg(long, value_name = "FILE", requires = "bundle")]
+        tests: Option<String>,
+    },
+
+    /// Publish a schema version to an OCI registry
+    Publish {
+        /// Destination (<oci://registry/repository:tag>)
+        reference: String,
+
+        /// Schema ID (or "active" for current)
+        #[arg(long, default_value = "active")]
+        id: String,
+
+        /// Test file to include in the artifact
+        #[arg(long, value_name = "FILE")]
+        tests: Option<String>,
+
+        /// Sign the published artifact with cosign (keyless unless --key is given)
+        #[arg(long)]
+        sign: bool,
+
+        /// Signing key file or KMS URI
+        #[arg(long, requires = "sign")]
+        key: Option<String>,
+    },
+
+    /// Verify the signature of a published schema bundle
+    VerifySignature {
+        /// Bundle (<oci://registry/repository:tag> or @sha256:digest)
+        reference: String,
+
+        /// Public key file or KMS URI
+        #[arg(long, conflicts_with_all = ["certificate_identity", "certificate_oidc_issuer"])]
+        key: Option<String>,
+
+        /// Expected signer identity for keyless signatures
+        #[arg(long)]
+        certificate_identity: Option<String>,
+
+        /// Expected OIDC issuer for keyless signatures
+        #[arg(long)]
+        certificate_oidc_issuer: Option<String>,
+    },
+
+    /// Pull a schema bundle from an OCI registry
+    Pull {
+        /// Source (<oci://registry/repository:tag> or @sha256:digest)
+        reference: String,
+
+        /// Directory to write the bundle to (defaults to the repository name)
+        #[arg(short, long, value_name = "DIR")]
+        output: Option<String>,
+    },
+
+    /// Add labels and a note to a schema version
+    Annotate {
+        /// Schema ID (or "active" for current)
+        id: String,
+
+        /// Label to set (key=value, repeatable)
+        #[arg(long = "label", value_name = "KEY=VALUE")]
+        labels: Vec<String>,
+
+        /// Label to remove (repeatable)
+        #[arg(long = "remove-label", value_name = "KEY")]
+        remove_labels: Vec<String>,
+
+        /// Free-form note (empty string clears it)
+        #[arg(long)]
+        note: Option<String>,
     },
 
     /// Preview schema changes
@@ -621,11 +1299,39 @@ pub enum SchemasCommands {
         /// Use canary deployment
         #[arg(long)]
         canary: Option<u8>,
+
+        /// Require a valid signature on this bundle, containing this schema version
+        #[arg(long, value_name = "OCI_REFERENCE")]
+        verify_signature: Option<String>,
+
+        /// Public key file or KMS URI
+        #[arg(long, requires = "verify_signature")]
+        key: Option<String>,
+
+        /// Expected signer identity for keyless signatures
+        #[arg(long, requires = "verify_signature")]
+        certificate_identity: Option<String>,
+
+        /// Expected OIDC issuer for keyless signatures
+        #[arg(long, requires = "verify_signature")]
+        certificate_oidc_issuer: Option<String>,
+    },
+
+    /// Edit a schema version in $EDITOR, then validate, diff and push it
+    Edit {
+        /// Schema ID (or "active" for current)
+        #[arg(default_value = "active")]
+        id: String,
+
+        /// Activate the pushed version
+        #[arg(long)]
+        activate: bool,
     },
 
     /// Rollback to previous schema
     Rollback {
         /// Target version (default: previous)
+        #[arg(id = "schema_version", value_name = "VERSION")]
         version: Option<String>,
     },
 
@@ -660,6 +1366,14 @@ pub enum SchemasCommands {
         /// Show impact analysis
         #[arg(long)]
         impact: bool,
+
+        /// Diff layout in table output
+        #[arg(long, default_value = "unified", value_parser = ["unified", "side-by-side"])]
+        style: String,
+
+        /// Unchanged lines of context around each change
+        #[arg(long, short = 'U', default_value = "3")]
+        context: usize,
     },
 
     /// Run schema tests
@@ -675,6 +1389,15 @@ pub enum SchemasCommands {
         /// Filter by test name
         #[arg(long)]
         name: Option<String>,
+
+        /// Fail when fewer than this percentage of relations and permissions
+        /// are exercised
+        #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(0..=100))]
+        min_coverage: Option<u8>,
+
+        /// Also report results for CI: `junit:<path>` or `github` (repeatable)
+        #[arg(long, value_name = "FORMAT")]
+        report: Vec<String>,
     },
 
     /// Watch for schema changes
@@ -710,6 +1433,20 @@ pub enum SchemasCommands {
         compare: Option<String>,
     },
 
+    /// Show which relations feed each permission of an entity
+    Matrix {
+        /// Schema file or version ID (or "active" for current)
+        file: String,
+
+        /// Entity type to render
+        #[arg(long)]
+        entity: String,
+
+        /// Output format (table, csv, html)
+        #[arg(short, long, default_value = "table", value_parser = ["table", "csv", "html"])]
+        format: String,
+    },
+
     /// Generate schema visualization
     Visualize {
         /// Schema file or version ID
@@ -731,6 +1468,7 @@ pub enum SchemasCommands {
     /// Copy schema between vaults
     Copy {
         /// Schema version ID (or omit for active)
+        #[arg(id = "schema_version", value_name = "VERSION")]
         version: Option<String>,
 
         /// Source vault (if not current)
@@ -772,6 +1510,67 @@ pub enum SchemasCommands {
         #[arg(short, long, default_value = "text")]
         format: String,
     },
+
+    /// Rename relations or entities with a matching data migration
+    #[command(subcommand)]
+    Refactor(RefactorCommands),
+}
+
+/// Schema refactoring commands.
+#[derive(Subcommand, Debug)]
+pub enum RefactorCommands {
+    /// Rename a relation and every reference to it, migrating its relationships
+    RenameRelation {
+        /// Entity declaring the relation (e.g., Document or document)
+        entity: String,
+
+        /// Current relation name
+        from: String,
+
+        /// New relation name
+        to: String,
+
+        /// Refactor this schema file instead of the active schema (updated when the run
+        /// completes)
+        #[arg(long, short = 'f', value_name = "FILE")]
+        file: Option<String>,
+
+        /// Push the schema changes and migrate relationships (otherwise only show the plan)
+        #[arg(long)]
+        apply: bool,
+
+        /// Relationships migrated per batch
+        #[arg(long, default_value = "100", value_parser = clap::value_parser!(u32).range(1..=1000))]
+        batch_size: u32,
+    },
+
+    /// Rename an entity and every subject type naming it, migrating its relationships
+    RenameEntity {
+        /// Current entity name
+        from: String,
+
+        /// New entity name
+        to: String,
+
+        /// Refactor this schema file instead of the active schema (updated when the run
+        /// completes)
+        #[arg(long, short = 'f', value_name = "FILE")]
+        file: Option<String>,
+
+        /// Push the schema changes and migrate relationships (otherwise only show the plan)
+        #[arg(long)]
+        apply: bool,
+
+        /// Relationships migrated per batch
+        #[arg(long, default_value = "100", value_parser = clap::value_parser!(u32).range(1..=1000))]
+        batch_size: u32,
+    },
+
+    /// Continue an interrupted refactoring from its checkpoint
+    Resume {
+        /// Checkpoint file printed when the run stopped
+        checkpoint: String,
+    },
 }
 
 /// Canary deployment commands.
@@ -846,6 +1645,10 @@ pub enum OrgsCommands {
         /// Organization ID
         id: String,
 
+        /// Member to promote to owner first (required if you are the only owner)
+        #[arg(long, value_name = "MEMBER_ID")]
+        transfer_to: Option<String>,
+
         /// Skip confirmation
         #[arg(long)]
         yes: bool,
@@ -892,6 +1695,10 @@ pub enum OrgsCommands {
         /// Time range end
         #[arg(long)]
         to: Option<String>,
+
+        /// Write matching events to a Parquet file instead of printing them
+        #[arg(long, value_name = "FILE")]
+        parquet: Option<String>,
     },
 }
 
@@ -923,6 +1730,17 @@ pub enum InvitationsCommands {
         id: String,
     },
 
+    /// Revoke expired invitations, and pending ones older than --older-than
+    Prune {
+        /// Also revoke pending invitations created more than this long ago (e.g., 30d, 2w)
+        #[arg(long, value_name = "AGE")]
+        older_than: Option<String>,
+
+        /// Show which invitations would be revoked without revoking them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Accept an invitation (using token from email)
     Accept {
         /// Invitation token
@@ -965,7 +1783,19 @@ pub enum OrgRolesCommands {
 #[derive(Subcommand, Debug)]
 pub enum MembersCommands {
     /// List organization members
-    List,
+    List {
+        /// Only members whose ID, name, or email contains this text
+        #[arg(long)]
+        search: Option<String>,
+
+        /// Only members with this role (owner, admin, member, billing, viewer)
+        #[arg(long)]
+        role: Option<String>,
+
+        /// Sort by field
+        #[arg(long, value_parser = ["name", "email", "role", "joined", "last-active"])]
+        sort: Option<String>,
+    },
 
     /// Update member role
     UpdateRole {
@@ -987,7 +1817,15 @@ pub enum MembersCommands {
 #[derive(Subcommand, Debug)]
 pub enum VaultsCommands {
     /// List vaults
-    List,
+    List {
+        /// List archived vaults instead of active ones
+        #[arg(long)]
+        archived: bool,
+
+        /// Also list vaults the server is still deleting
+        #[arg(long)]
+        show_deleted: bool,
+    },
 
     /// Create vault
     Create {
@@ -1502,6 +2340,64 @@ pub enum JwksCommands {
     WellKnown,
 }
 
+/// Configuration generator commands.
+#[derive(Subcommand, Debug)]
+pub enum GenerateCommands {
+    /// Generate a Grafana dashboard and Prometheus alert rules for CLI metrics
+    Grafana {
+        /// Output directory
+        #[arg(long, short, default_value = "dashboards")]
+        output: String,
+    },
+
+    /// Generate a CI pipeline that checks schema changes and deploys on merge
+    Ci {
+        /// CI provider
+        #[arg(long, default_value = "github", value_parser = ["github", "gitlab"])]
+        provider: String,
+
+        /// Schema file
+        #[arg(long, default_value = "schema.ipl")]
+        schema: String,
+
+        /// Schema test file
+        #[arg(long, default_value = "schema.test.yaml")]
+        tests: String,
+
+        /// Branch that deploys on merge
+        #[arg(long, default_value = "main")]
+        branch: String,
+
+        /// Output file (default: the provider's standard location)
+        #[arg(long, short)]
+        output: Option<String>,
+
+        /// Overwrite an existing pipeline file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Release automation commands.
+#[derive(Subcommand, Debug)]
+pub enum ReleaseCommands {
+    /// Render Homebrew, Scoop, and deb/rpm (nfpm) manifests and shell completions
+    #[command(disable_version_flag = true)]
+    PackageManifests {
+        /// Output directory
+        #[arg(long, short, default_value = "dist/packaging")]
+        output: String,
+
+        /// Release version (default: this binary's version)
+        #[arg(long)]
+        version: Option<String>,
+
+        /// SHA-256 checksums of the release archives, in `sha256sum` format
+        #[arg(long, value_name = "FILE")]
+        checksums: Option<String>,
+    },
+}
+
 /// Token management commands.
 #[derive(Subcommand, Debug)]
 pub enum TokensCommands {
@@ -1575,6 +2471,26 @@ pub enum DevCommands {
         /// Clone a specific commit, tag, or branch of deploy repository
         #[arg(long)]
         commit: Option<String>,
+
+        /// Deploy prebuilt images from GHCR instead of cloning and building components
+        #[arg(long, conflicts_with = "interactive")]
+        from_registry: bool,
+
+        /// Image tag to deploy with --from-registry (default: latest)
+        #[arg(long, requires = "from_registry")]
+        tag: Option<String>,
+
+        /// Provision from an artifact bundle created by 'dev bundle create' (air-gapped)
+        #[arg(long, conflicts_with_all = ["interactive", "from_registry", "commit"])]
+        bundle: Option<std::path::PathBuf>,
+
+        /// Per-step timing summary
+        #[command(flatten)]
+        timings: TimingsArgs,
+
+        /// Keep the engine API forwarded to a fixed local address (e.g., localhost:8080)
+        #[arg(long, value_name = "ADDR")]
+        expose_api: Option<String>,
     },
 
     /// Stop local development cluster (pause containers)
@@ -1595,6 +2511,10 @@ pub enum DevCommands {
         /// Use interactive TUI mode
         #[arg(long, short = 'i')]
         interactive: bool,
+
+        /// Per-step timing summary
+        #[command(flatten)]
+        timings: TimingsArgs,
     },
 
     /// Show cluster status
@@ -1622,14 +2542,131 @@ pub enum DevCommands {
     /// Open dashboard in browser
     Dashboard,
 
+    /// Inspect emails captured by the cluster's Mailpit
+    #[command(subcommand)]
+    Mail(Box<DevMailCommands>),
+
+    /// Mint a JWT accepted by the local engine, for testing as any principal
+    Token {
+        /// Principal to act as, as `type:id` (e.g. `user:alice`)
+        #[arg(long)]
+        subject: String,
+
+        /// How long the token is valid (e.g. 30m, 1h, 7d)
+        #[arg(long, default_value = "1h")]
+        ttl: String,
+    },
+
+    /// Upgrade Talos/Kubernetes in place, keeping cluster data
+    Upgrade {
+        /// Target Kubernetes version (default: the version new clusters use)
+        #[arg(long)]
+        kubernetes_version: Option<String>,
+
+        /// Target Talos version (upgrades Talos on every node; not supported by
+        /// the docker provisioner, where the cluster must be recreated)
+        #[arg(long)]
+        talos_version: Option<String>,
+
+        /// Skip the etcd snapshot taken before upgrading
+        #[arg(long)]
+        skip_snapshot: bool,
+
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+
+        /// Per-step timing summary
+        #[command(flatten)]
+        timings: TimingsArgs,
+    },
+
+    /// Manage air-gapped artifact bundles
+    #[command(subcommand)]
+    Bundle(Box<DevBundleCommands>),
+
+    /// Customize dev deployment values applied by start and reset
+    #[command(subcommand)]
+    Config(Box<DevConfigCommands>),
+
+    /// Run the engine API port-forward supervisor (started by 'dev start --expose-api')
+    #[command(hide = true)]
+    PortForward {
+        /// Local address, as host:port
+        address: String,
+    },
+
     /// Reset all cluster data
     Reset {
         /// Skip confirmation prompt
         #[arg(long)]
         yes: bool,
+
+        /// Per-step timing summary
+        #[command(flatten)]
+        timings: TimingsArgs,
+    },
+}
+
+/// Dev artifact bundle commands.
+#[derive(Subcommand, Debug)]
+pub enum DevBundleCommands {
+    /// Download images, charts, and manifests into a bundle for air-gapped use
+    Create {
+        /// Output bundle path (e.g., bundle.tar)
+        output: std::path::PathBuf,
+
+        /// Component image tag to vendor (default: latest)
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Vendor a specific commit, tag, or branch of the deploy repository
+        #[arg(long)]
+        commit: Option<String>,
     },
 }
 
+/// Dev captured email commands.
+#[derive(Subcommand, Debug)]
+pub enum DevMailCommands {
+    /// Open Mailpit, or one captured email, in the browser
+    Open {
+        /// Email ID (from 'dev mail list')
+        id: Option<String>,
+    },
+
+    /// List captured emails with the links they contain
+    List {
+        /// Maximum number of emails to show
+        #[arg(long, default_value = "20")]
+        limit: u32,
+    },
+
+    /// Delete all captured emails
+    Clear,
+}
+
+/// Dev deployment override commands.
+#[derive(Subcommand, Debug)]
+pub enum DevConfigCommands {
+    /// Set one or more overrides (e.g., `engine.replicas=2`)
+    Set {
+        /// Assignments in `<component>.<field>=<value>` form
+        #[arg(required = true)]
+        assignments: Vec<String>,
+    },
+
+    /// Remove one or more overrides
+    Unset {
+        /// Keys in `<component>.<field>` form
+        #[arg(required = true)]
+        keys: Vec<String>,
+    },
+
+    /// List all overrides
+    List,
+}
+
 impl Cli {
     /// Get the CLI command with localized help text.
     ///
@@ -1704,41 +2741,94 @@ fn localize_root_args(mut cmd: clap::Command) -> clap::Command {
     cmd
 }
 
+/// Accept a named output format or a `template=`/`template-file=` template.
+fn parse_output_format(s: &str) -> Result<String, String> {
+    match s.split_once('=') {
+        Some(("template" | "template-file", _)) => Ok(s.to_string()),
+        _ if ["table", "json", "yaml", "jsonl", "csv"].contains(&s) => Ok(s.to_string()),
+        _ => Err("expected table, json, yaml, jsonl, csv, template=<template> or \
+                  template-file=<path>"
+            .to_string()),
+    }
+}
+
+/// Environment variable equivalent to an `@profile` argument.
+pub const PROFILE_PREFIX_ENV: &str = "INFERADB_PROFILE_PREFIX";
+
 /// Parse the @profile prefix from command-line arguments.
 ///
-/// The CLI supports `@profile` as the first argument to select a profile,
-/// which is more ergonomic than `--profile`.
+/// The CLI supports `@profile` anywhere before a `--` to select a profile,
+/// which is more ergonomic than `--profile`. Only `@` followed by letters,
+/// digits, `-`, or `_` counts, so values like `@sha256:...` pass through.
 ///
-/// Returns the profile name (if any) and the remaining arguments.
-#[must_use]
-pub fn parse_profile_prefix(args: Vec<String>) -> (Option<String>, Vec<String>) {
-    // First arg is binary name, second might be @profile
-    let mut iter = args.into_iter();
-    let Some(binary) = iter.next() else {
-        return (None, vec![]);
-    };
-    let Some(second) = iter.next() else {
-        return (None, vec![binary]);
-    };
-    if second.starts_with('@') && second.len() > 1 {
-        let profile = second[1..].to_string();
-        let remaining: Vec<String> = std::iter::once(binary).chain(iter).collect();
-        (Some(profile), remaining)
-    } else {
-        let remaining: Vec<String> =
-            std::iter::once(binary).chain(std::iter::once(second)).chain(iter).collect();
-        (None, remaining)
+/// Returns the profile name (if any) and the remaining arguments, or an
+/// error when two different `@profile` arguments are given.
+pub fn parse_profile_prefix(args: Vec<String>) -> crate::Result<(Option<String>, Vec<String>)> {
+    let mut profile: Option<String> = None;
+    let mut remaining = Vec::with_capacity(args.len());
+    let mut passthrough = false;
+    for (index, arg) in args.into_iter().enumerate() {
+        passthrough |= arg == "--";
+        let Some(name) =
+            arg.strip_prefix('@').filter(|name| index > 0 && !passthrough && is_profile_name(name))
+        else {
+            remaining.push(arg);
+            continue;
+        };
+        match &profile {
+            Some(existing) if existing != name => {
+                return Err(crate::Error::invalid_arg(format!(
+                    "Conflicting profiles @{existing} and @{name}; give one"
+                )));
+            },
+            _ => profile = Some(name.to_string()),
+        }
+    }
+    Ok((profile, remaining))
+}
+
+/// Whether `name` can follow `@` as a profile name.
+fn is_profile_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Choose the profile from an `@profile` argument, `--profile`, and the
+/// environment.
+///
+/// Command-line choices come first, and `@x` with a different `--profile y`
+/// is an error rather than one silently winning. Then
+/// `INFERADB_PROFILE_PREFIX` (with or without its `@`), then
+/// `INFERADB_PROFILE`, which clap has already read into `flag`.
+pub fn resolve_profile(
+    prefix: Option<String>,
+    flag: Option<String>,
+    flag_on_command_line: bool,
+    env_prefix: Option<String>,
+) -> crate::Result<Option<String>> {
+    match (prefix, flag) {
+        (Some(prefix), Some(flag)) if flag_on_command_line && prefix != flag => {
+            Err(crate::Error::invalid_arg(format!(
+                "Conflicting profiles @{prefix} and --profile {flag}; give one"
+            )))
+        },
+        (Some(prefix), _) => Ok(Some(prefix)),
+        (None, Some(flag)) if flag_on_command_line => Ok(Some(flag)),
+        (None, flag) => Ok(env_prefix
+            .map(|env| env.trim_start_matches('@').to_string())
+            .filter(|env| !env.is_empty())
+            .or(flag)),
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parse_profile_prefix() {
         let args = vec!["inferadb".to_string(), "@prod".to_string(), "check".to_string()];
-        let (profile, remaining) = parse_profile_prefix(args);
+        let (profile, remaining) = parse_profile_prefix(args).unwrap();
         assert_eq!(profile, Some("prod".to_string()));
         assert_eq!(remaining, vec!["inferadb", "check"]);
     }
@@ -1746,7 +2836,7 @@ mod tests {
     #[test]
     fn test_parse_profile_prefix_no_profile() {
         let args = vec!["inferadb".to_string(), "check".to_string()];
-        let (profile, remaining) = parse_profile_prefix(args);
+        let (profile, remaining) = parse_profile_prefix(args).unwrap();
         assert!(profile.is_none());
         assert_eq!(remaining, vec!["inferadb", "check"]);
     }
@@ -1754,8 +2844,35 @@ mod tests {
     #[test]
     fn test_parse_profile_prefix_at_sign_only() {
         let args = vec!["inferadb".to_string(), "@".to_string(), "check".to_string()];
-        let (profile, remaining) = parse_profile_prefix(args);
+        let (profile, remaining) = parse_profile_prefix(args).unwrap();
         assert!(profile.is_none());
         assert_eq!(remaining, vec!["inferadb", "@", "check"]);
     }
+
+    #[test]
+    fn test_parse_profile_prefix_any_position() {
+        let args: Vec<String> =
+            ["inferadb", "check", "@prod", "--", "@other"].map(String::from).to_vec();
+        let (profile, remaining) = parse_profile_prefix(args).unwrap();
+        assert_eq!(profile.as_deref(), Some("prod"));
+        assert_eq!(remaining, vec!["inferadb", "check", "--", "@other"]);
+
+        let args: Vec<String> =
+            ["inferadb", "bundle", "pull", "@sha256:abc"].map(String::from).to_vec();
+        assert!(parse_profile_prefix(args).unwrap().0.is_none());
+
+        let args: Vec<String> = ["inferadb", "@a", "check", "@b"].map(String::from).to_vec();
+        assert!(parse_profile_prefix(args).is_err());
+    }
+
+    #[test]
+    fn test_resolve_profile() {
+        let s = |v: &str| Some(v.to_string());
+        assert_eq!(resolve_profile(s("a"), s("b"), false, None).unwrap(), s("a"));
+        assert!(resolve_profile(s("a"), s("b"), true, None).is_err());
+        assert_eq!(resolve_profile(s("a"), s("a"), true, None).unwrap(), s("a"));
+        assert_eq!(resolve_profile(None, s("b"), true, s("@c")).unwrap(), s("b"));
+        assert_eq!(resolve_profile(None, s("b"), false, s("@c")).unwrap(), s("c"));
+        assert_eq!(resolve_profile(None, s("b"), false, s("")).unwrap(), s("b"));
+    }
 }
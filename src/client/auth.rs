@@ -1,13 +1,25 @@
 //! OAuth PKCE authentication flow for the CLI.
 //!
 //! Implements the Authorization Code flow with PKCE for secure
-//! browser-based authentication.
-
-use std::{net::TcpListener, sync::mpsc, thread, time::Duration};
+//! browser-based authentication, the device authorization grant for
+//! sessions without a browser (SSH, CI runners), and the client credentials
+//! grant for org clients (service accounts).
+
+use std::{
+    net::TcpListener,
+    pin::Pin,
+    sync::{Mutex, mpsc},
+    thread,
+    time::Duration,
+};
 
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use inferadb::Ed25519PrivateKey;
 use oauth2::{
-    AuthUrl, AuthorizationCode, ClientId, CsrfToken, PkceCodeChallenge, RedirectUrl, Scope,
-    TokenResponse, TokenUrl, basic::BasicClient,
+    AsyncHttpClient, AuthUrl, AuthorizationCode, ClientId, CsrfToken, DeviceAuthorizationUrl,
+    DeviceCodeErrorResponseType, HttpRequest, HttpResponse, PkceCodeChallenge, RedirectUrl,
+    RefreshToken, RequestTokenError, Scope, StandardDeviceAuthorizationResponse, TokenResponse,
+    TokenUrl, basic::BasicClient,
 };
 
 use crate::{
@@ -18,14 +30,17 @@ use crate::{
 /// Default OAuth configuration.
 const DEFAULT_AUTH_URL: &str = "https://auth.inferadb.com/oauth/authorize";
 const DEFAULT_TOKEN_URL: &str = "https://auth.inferadb.com/oauth/token";
+const DEFAULT_DEVICE_AUTH_URL: &str = "https://auth.inferadb.com/oauth/device/code";
 const DEFAULT_CLIENT_ID: &str = "inferadb-cli";
 const CALLBACK_PORT: u16 = 8787;
+const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
 
 /// OAuth PKCE authentication flow.
 #[derive(Debug, Clone)]
 pub struct OAuthFlow {
     auth_url: String,
     token_url: String,
+    device_auth_url: String,
     client_id: String,
     redirect_url: String,
 }
@@ -37,12 +52,21 @@ impl OAuthFlow {
     }
 
     /// Create a new OAuth flow with custom configuration.
+    ///
+    /// The device authorization endpoint is `device/code` next to the token
+    /// endpoint.
     pub fn with_config(auth_url: &str, token_url: &str, client_id: &str) -> Result<Self> {
         let redirect_url = format!("http://localhost:{CALLBACK_PORT}/callback");
+        let device_auth_url = if token_url == DEFAULT_TOKEN_URL {
+            DEFAULT_DEVICE_AUTH_URL.to_string()
+        } else {
+            device_auth_url_for(token_url)
+        };
 
         Ok(Self {
             auth_url: auth_url.to_string(),
             token_url: token_url.to_string(),
+            device_auth_url,
             client_id: client_id.to_string(),
             redirect_url,
         })
@@ -94,49 +118,294 @@ impl OAuthFlow {
         // Wait for callback
         let (code, _state) = wait_for_callback(csrf_token.secret().clone())?;
 
-        // Exchange code for tokens
-        let http_client = reqwest::Client::new();
-
+        // Exchange code for tokens, noting any brute-force protection response
+        let http_client = TokenHttpClient::default();
         let token_result = client
             .exchange_code(AuthorizationCode::new(code))
             .set_pkce_verifier(pkce_verifier)
-            .request_async(&|request: oauth2::HttpRequest| {
-                let http_client = http_client.clone();
-                async move {
-                    let response = http_client
-                        .request(request.method().clone(), request.uri().to_string())
-                        .headers(request.headers().clone())
-                        .body(request.body().clone())
-                        .send()
-                        .await
-                        .map_err(std::io::Error::other)?;
-
-                    let status = response.status();
-                    let body = response.bytes().await.map_err(std::io::Error::other)?;
-
-                    Ok::<_, std::io::Error>(
-                        http::Response::builder()
-                            .status(status)
-                            .body(body.to_vec())
-                            .expect("valid response"),
-                    )
-                }
-            })
+            .request_async(&http_client)
+            .await
+            .map_err(|e| http_client.error(format!("Token exchange failed: {e}")))?;
+
+        Ok(credentials_from(&token_result, None))
+    }
+
+    /// Start the device authorization grant.
+    ///
+    /// Show the returned code and URL to the user, then call
+    /// [`Self::complete_device`] to wait for them to approve it.
+    pub async fn start_device(&self) -> Result<DeviceAuthorization> {
+        let client = BasicClient::new(ClientId::new(self.client_id.clone()))
+            .set_device_authorization_url(
+                DeviceAuthorizationUrl::new(self.device_auth_url.clone())
+                    .map_err(|e| Error::oauth(e.to_string()))?,
+            );
+
+        let http_client = TokenHttpClient::default();
+        let response = client
+            .exchange_device_code()
+            .add_scope(Scope::new("openid".to_string()))
+            .add_scope(Scope::new("profile".to_string()))
+            .add_scope(Scope::new("offline_access".to_string()))
+            .request_async(&http_client)
             .await
-            .map_err(|e| Error::oauth(format!("Token exchange failed: {e}")))?;
-
-        let access_token = token_result.access_token().secret().clone();
-        let refresh_token = token_result.refresh_token().map(|t| t.secret().clone());
-        let expires_at = token_result
-            .expires_in()
-            .map(|d| chrono::Utc::now() + chrono::Duration::seconds(d.as_secs() as i64));
-
-        Ok(Credentials::builder()
-            .access_token(access_token)
-            .maybe_refresh_token(refresh_token)
-            .maybe_expires_at(expires_at)
-            .build())
+            .map_err(|e| http_client.error(format!("Device authorization failed: {e}")))?;
+
+        Ok(DeviceAuthorization { response })
     }
+
+    /// Poll until the user approves (or denies) a device authorization, and
+    /// return credentials.
+    pub async fn complete_device(&self, device: &DeviceAuthorization) -> Result<Credentials> {
+        let client = BasicClient::new(ClientId::new(self.client_id.clone())).set_token_uri(
+            TokenUrl::new(self.token_url.clone()).map_err(|e| Error::oauth(e.to_string()))?,
+        );
+
+        let http_client = TokenHttpClient::default();
+        let token_result = client
+            .exchange_device_access_token(&device.response)
+            .request_async(&http_client, tokio::time::sleep, None)
+            .await
+            .map_err(|e| match e {
+                RequestTokenError::ServerResponse(response) => match response.error() {
+                    DeviceCodeErrorResponseType::AccessDenied => {
+                        Error::oauth("Authorization was denied")
+                    },
+                    DeviceCodeErrorResponseType::ExpiredToken => Error::oauth(
+                        "The code expired before it was approved; run 'inferadb login \
+                         --device-code' again",
+                    ),
+                    other => Error::oauth(format!("Device authorization failed: {other}")),
+                },
+                e => http_client.error(format!("Device authorization failed: {e}")),
+            })?;
+
+        Ok(credentials_from(&token_result, None))
+    }
+
+    /// Exchange a refresh token for new credentials.
+    ///
+    /// Servers that do not rotate refresh tokens omit one from the response;
+    /// the existing token is kept in that case.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<Credentials> {
+        let client = BasicClient::new(ClientId::new(self.client_id.clone())).set_token_uri(
+            TokenUrl::new(self.token_url.clone()).map_err(|e| Error::oauth(e.to_string()))?,
+        );
+
+        let http_client = TokenHttpClient::default();
+        let refresh_token = RefreshToken::new(refresh_token.to_string());
+        let token_result = client
+            .exchange_refresh_token(&refresh_token)
+            .request_async(&http_client)
+            .await
+            .map_err(|e| http_client.error(format!("Token refresh failed: {e}")))?;
+
+        Ok(credentials_from(&token_result, Some(refresh_token.secret().as_str())))
+    }
+
+    /// Authenticate as an org client with the client credentials grant.
+    ///
+    /// The client proves its identity with a short-lived JWT signed by its
+    /// Ed25519 key (`private_key_jwt`). The returned credentials remember the
+    /// client and key path so they can be renewed the same way.
+    pub async fn client_credentials(&self, client_id: &str, key_path: &str) -> Result<Credentials> {
+        let key = Ed25519PrivateKey::from_pem_file(key_path)
+            .map_err(|e| Error::credential(format!("Could not read client key {key_path}: {e}")))?;
+        let assertion = client_assertion(client_id, &self.token_url, &key, chrono::Utc::now());
+
+        let client = BasicClient::new(ClientId::new(client_id.to_string())).set_token_uri(
+            TokenUrl::new(self.token_url.clone()).map_err(|e| Error::oauth(e.to_string()))?,
+        );
+
+        let http_client = TokenHttpClient::default();
+        let token_result = client
+            .exchange_client_credentials()
+            .add_extra_param("client_assertion_type", CLIENT_ASSERTION_TYPE)
+            .add_extra_param("client_assertion", assertion)
+            .request_async(&http_client)
+            .await
+            .map_err(|e| http_client.error(format!("Client authentication failed: {e}")))?;
+
+        let mut credentials = credentials_from(&token_result, None);
+        credentials.client_id = Some(client_id.to_string());
+        credentials.client_key = Some(key_path.to_string());
+        Ok(credentials)
+    }
+}
+
+/// A pending device authorization: the code the user enters and where.
+#[derive(Debug)]
+pub struct DeviceAuthorization {
+    response: StandardDeviceAuthorizationResponse,
+}
+
+impl DeviceAuthorization {
+    /// Code the user enters on the verification page.
+    #[must_use]
+    pub fn user_code(&self) -> &str {
+        self.response.user_code().secret()
+    }
+
+    /// Page where the user enters the code.
+    #[must_use]
+    pub fn verification_uri(&self) -> &str {
+        self.response.verification_uri()
+    }
+
+    /// Verification page with the code filled in, if the server provides one.
+    #[must_use]
+    pub fn verification_uri_complete(&self) -> Option<&str> {
+        self.response.verification_uri_complete().map(|uri| uri.secret().as_str())
+    }
+
+    /// How long the code stays valid.
+    #[must_use]
+    pub fn expires_in(&self) -> Duration {
+        self.response.expires_in()
+    }
+}
+
+/// Device authorization endpoint for a custom token endpoint: `device/code`
+/// in place of the token endpoint's last path segment.
+fn device_auth_url_for(token_url: &str) -> String {
+    let base = token_url.rsplit_once('/').map_or(token_url, |(base, _)| base);
+    format!("{base}/device/code")
+}
+
+/// Signed JWT identifying `client_id` to the token endpoint `audience`
+/// (RFC 7523), valid for one minute from `now`.
+fn client_assertion(
+    client_id: &str,
+    audience: &str,
+    key: &Ed25519PrivateKey,
+    now: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let claims = serde_json::json!({
+        "iss": client_id,
+        "sub": client_id,
+        "aud": audience,
+        "iat": now.timestamp(),
+        "exp": (now + chrono::Duration::minutes(1)).timestamp(),
+        "jti": CsrfToken::new_random().secret(),
+    });
+    sign_jwt(key, None, &claims)
+}
+
+/// Sign `claims` as a compact `EdDSA` JWT, naming the key with `kid` if given.
+pub fn sign_jwt(key: &Ed25519PrivateKey, kid: Option<&str>, claims: &serde_json::Value) -> String {
+    let mut header = serde_json::json!({ "alg": "EdDSA", "typ": "JWT" });
+    if let Some(kid) = kid {
+        header["kid"] = serde_json::Value::from(kid);
+    }
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let signature = key.sign_base64url(signing_input.as_bytes());
+    format!("{signing_input}.{signature}")
+}
+
+/// Build credentials from a token endpoint response.
+fn credentials_from(
+    token_result: &impl TokenResponse,
+    previous_refresh_token: Option<&str>,
+) -> Credentials {
+    let access_token = token_result.access_token().secret().clone();
+    let refresh_token = token_result
+        .refresh_token()
+        .map(|t| t.secret().as_str())
+        .or(previous_refresh_token)
+        .map(str::to_string);
+    let expires_at = token_result
+        .expires_in()
+        .map(|d| chrono::Utc::now() + chrono::Duration::seconds(d.as_secs() as i64));
+
+    Credentials::builder()
+        .access_token(access_token)
+        .maybe_refresh_token(refresh_token)
+        .maybe_expires_at(expires_at)
+        .build()
+}
+
+/// HTTP client for token endpoint requests that remembers brute-force
+/// protection responses, so they surface as [`Error::AuthThrottled`] rather
+/// than a generic OAuth error.
+#[derive(Default)]
+struct TokenHttpClient {
+    http: reqwest::Client,
+    throttled: Mutex<Option<Error>>,
+}
+
+impl TokenHttpClient {
+    /// The error for a failed request: the throttling error if one was seen,
+    /// otherwise an OAuth error with `message`.
+    fn error(&self, message: String) -> Error {
+        self.throttled
+            .lock()
+            .ok()
+            .and_then(|mut slot| slot.take())
+            .unwrap_or_else(|| Error::oauth(message))
+    }
+}
+
+impl<'c> AsyncHttpClient<'c> for TokenHttpClient {
+    type Error = std::io::Error;
+    type Future = Pin<Box<dyn Future<Output = std::io::Result<HttpResponse>> + Send + 'c>>;
+
+    fn call(&'c self, request: HttpRequest) -> Self::Future {
+        Box::pin(async move {
+            let response = self
+                .http
+                .request(request.method().clone(), request.uri().to_string())
+                .headers(request.headers().clone())
+                .body(request.body().clone())
+                .send()
+                .await
+                .map_err(std::io::Error::other)?;
+
+            let status = response.status();
+            if let Some(err) = throttle_error(status, response.headers())
+                && let Ok(mut slot) = self.throttled.lock()
+            {
+                *slot = Some(err);
+            }
+            let body = response.bytes().await.map_err(std::io::Error::other)?;
+
+            http::Response::builder()
+                .status(status)
+                .body(body.to_vec())
+                .map_err(std::io::Error::other)
+        })
+    }
+}
+
+/// Map a token endpoint response to [`Error::AuthThrottled`] when it is a
+/// lockout (423) or throttling (429) response.
+fn throttle_error(
+    status: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+) -> Option<Error> {
+    let locked = match status {
+        reqwest::StatusCode::LOCKED => true,
+        reqwest::StatusCode::TOO_MANY_REQUESTS => false,
+        _ => return None,
+    };
+    let retry_after = headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_retry_after);
+    Some(Error::auth_throttled(locked, retry_after))
+}
+
+/// Parse a `Retry-After` header: either delay seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().ok()
 }
 
 /// Wait for the OAuth callback.
@@ -259,6 +528,7 @@ fn extract_query(request_line: &str) -> Option<String> {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -270,4 +540,82 @@ mod tests {
         let no_query = "GET /callback HTTP/1.1";
         assert!(extract_query(no_query).is_none());
     }
+
+    #[test]
+    fn test_throttle_error() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "120".parse().unwrap());
+
+        let err = throttle_error(reqwest::StatusCode::LOCKED, &headers).unwrap();
+        assert!(matches!(
+            err,
+            Error::AuthThrottled { locked: true, retry_after: Some(d) } if d.as_secs() == 120
+        ));
+        assert!(matches!(
+            throttle_error(
+                reqwest::StatusCode::TOO_MANY_REQUESTS,
+                &reqwest::header::HeaderMap::new()
+            ),
+            Some(Error::AuthThrottled { locked: false, retry_after: None })
+        ));
+        assert!(throttle_error(reqwest::StatusCode::BAD_REQUEST, &headers).is_none());
+        assert!(parse_retry_after("soon").is_none());
+    }
+
+    #[test]
+    fn test_device_auth_url() {
+        assert_eq!(OAuthFlow::new().unwrap().device_auth_url, DEFAULT_DEVICE_AUTH_URL);
+        assert_eq!(
+            device_auth_url_for("https://auth.example.com/oauth2/token"),
+            "https://auth.example.com/oauth2/device/code"
+        );
+    }
+
+    #[test]
+    fn test_credentials_from_keeps_refresh_token() {
+        use oauth2::{
+            AccessToken, EmptyExtraTokenFields, StandardTokenResponse, basic::BasicTokenType,
+        };
+
+        let mut response = StandardTokenResponse::new(
+            AccessToken::new("new-access".to_string()),
+            BasicTokenType::Bearer,
+            EmptyExtraTokenFields {},
+        );
+        response.set_expires_in(Some(&Duration::from_secs(3600)));
+
+        let credentials = credentials_from(&response, Some("old-refresh"));
+        assert_eq!(credentials.access_token, "new-access");
+        assert_eq!(credentials.refresh_token.as_deref(), Some("old-refresh"));
+        assert!(!credentials.expires_soon());
+
+        response.set_refresh_token(Some(RefreshToken::new("rotated".to_string())));
+        let credentials = credentials_from(&response, Some("old-refresh"));
+        assert_eq!(credentials.refresh_token.as_deref(), Some("rotated"));
+    }
+
+    #[test]
+    fn test_client_assertion() {
+        let key = Ed25519PrivateKey::generate();
+        let now = chrono::Utc::now();
+        let assertion = client_assertion("client_abc", DEFAULT_TOKEN_URL, &key, now);
+
+        let parts: Vec<&str> = assertion.split('.').collect();
+        assert_eq!(parts.len(), 3);
+        let signing_input = format!("{}.{}", parts[0], parts[1]);
+        assert_eq!(parts[2], key.sign_base64url(signing_input.as_bytes()));
+
+        let decode = |part: &str| -> serde_json::Value {
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).unwrap()).unwrap()
+        };
+        assert_eq!(decode(parts[0])["alg"], "EdDSA");
+        let claims = decode(parts[1]);
+        assert_eq!(claims["iss"], "client_abc");
+        assert_eq!(claims["sub"], "client_abc");
+        assert_eq!(claims["aud"], DEFAULT_TOKEN_URL);
+        assert_eq!(claims["exp"].as_i64().unwrap() - claims["iat"].as_i64().unwrap(), 60);
+
+        let again = client_assertion("client_abc", DEFAULT_TOKEN_URL, &key, now);
+        assert_ne!(decode(again.split('.').nth(1).unwrap())["jti"], claims["jti"]);
+    }
 }
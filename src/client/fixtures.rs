@@ -0,0 +1,503 @@
+//! Record-and-replay HTTP fixtures.
+//!
+//! `--record <dir>` runs a local proxy in front of the profile's API that
+//! forwards each request and saves the response as a JSON fixture;
+//! `--replay <dir>` answers from the saved fixtures without touching the
+//! network. SDK clients and `inferadb api` are pointed at the proxy, so
+//! demos, docs screenshots and integration tests of CLI behavior are
+//! deterministic and offline.
+//!
+//! Fixtures are matched by method, path, query and request body. A request
+//! made several times replays its recordings in order, repeating the last.
+//! Request headers, including credentials, are never saved, nor are request
+//! bodies sent to auth endpoints (token, refresh and client-assertion
+//! exchanges, password changes); tokens in their responses are redacted.
+//! Other response bodies are saved as-is, so review fixtures before
+//! committing them.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{
+    error::{Error, Result},
+    output::cast::REDACTED,
+};
+
+/// Request headers not passed upstream: hop-by-hop, or set by the proxy's
+/// own HTTP client.
+const SKIPPED_HEADERS: &[&str] =
+    &["host", "connection", "content-length", "transfer-encoding", "accept-encoding"];
+
+/// Path segments marking auth endpoints, whose request bodies carry
+/// credentials and are never saved.
+const AUTH_SEGMENTS: &[&str] = &["oauth", "oauth2", "auth", "token", "tokens", "password"];
+
+/// Response fields redacted on auth endpoints.
+const TOKEN_FIELDS: &[&str] = &["access_token", "refresh_token", "id_token", "token"];
+
+/// Whether fixtures are recorded or replayed, and where they live.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Forward requests upstream and save each response.
+    Record(PathBuf),
+    /// Answer requests from saved responses only.
+    Replay(PathBuf),
+}
+
+/// A saved request and its response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// HTTP method.
+    pub method: String,
+    /// Path and query.
+    pub path: String,
+    /// Request body, as JSON when it parses.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub request_body: Value,
+    /// Response status.
+    pub status: u16,
+    /// Response content type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Response body, as JSON when it parses.
+    #[serde(default)]
+    pub body: Value,
+}
+
+impl Fixture {
+    /// Drop the request body and redact response tokens of an auth endpoint.
+    fn redact_credentials(&mut self) {
+        if !is_auth_endpoint(&self.path) {
+            return;
+        }
+        if !self.request_body.is_null() {
+            self.request_body = Value::String(REDACTED.to_string());
+        }
+        redact_tokens(&mut self.body);
+    }
+}
+
+struct Proxy {
+    mode: Mode,
+    upstream: String,
+    url: String,
+    http: reqwest::Client,
+    /// Requests seen per fixture key, to replay repeated requests in order.
+    seen: Mutex<HashMap<String, usize>>,
+}
+
+static PROXY: OnceLock<Proxy> = OnceLock::new();
+
+/// Start the fixture proxy in front of `upstream` for the rest of the
+/// process. Must be called from within the Tokio runtime.
+pub fn start(mode: Mode, upstream: &str) -> Result<()> {
+    match &mode {
+        Mode::Record(dir) => fs::create_dir_all(dir)?,
+        Mode::Replay(dir) if !dir.is_dir() => {
+            return Err(Error::invalid_arg(format!(
+                "Fixture directory '{}' does not exist",
+                dir.display()
+            )));
+        },
+        Mode::Replay(_) => {},
+    }
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")
+        .map_err(|e| Error::other(format!("Failed to start fixture proxy: {e}")))?;
+    listener.set_nonblocking(true)?;
+    let url = format!("http://{}", listener.local_addr()?);
+    let listener = TcpListener::from_std(listener)?;
+
+    let proxy = Proxy {
+        mode,
+        upstream: upstream.trim_end_matches('/').to_string(),
+        url,
+        http: reqwest::Client::new(),
+        seen: Mutex::new(HashMap::new()),
+    };
+    if PROXY.set(proxy).is_err() {
+        return Ok(());
+    }
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            if let Some(proxy) = PROXY.get() {
+                tokio::spawn(async move {
+                    if let Err(e) = proxy.handle(stream).await {
+                        tracing::debug!(error = %e, "fixture proxy request failed");
+                    }
+                });
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Where to send requests meant for `url`: the fixture proxy while it is
+/// recording or replaying that API, otherwise `url` itself.
+pub fn url_for(url: &str) -> String {
+    match PROXY.get() {
+        Some(proxy) if proxy.upstream == url.trim_end_matches('/') => proxy.url.clone(),
+        _ => url.to_string(),
+    }
+}
+
+/// Whether fixtures are being replayed, so no real credentials are needed.
+pub fn replaying() -> bool {
+    PROXY.get().is_some_and(|proxy| matches!(proxy.mode, Mode::Replay(_)))
+}
+
+/// File stem identifying a request, e.g. `get-control-v1-organizations-1a2b3c4d5e6f`.
+fn fixture_key(method: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method);
+    hasher.update(" ");
+    hasher.update(path);
+    hasher.update("\n");
+    hasher.update(body);
+    let hash = hasher.finalize()[..6].iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    });
+
+    let route = path.split('?').next().unwrap_or_default();
+    let slug: Vec<&str> =
+        route.split(|c: char| !c.is_ascii_alphanumeric()).filter(|s| !s.is_empty()).collect();
+    let slug: String = slug.join("-").chars().take(60).collect();
+    format!("{}-{slug}-{hash}", method.to_lowercase())
+}
+
+/// Path of the `occurrence`th (1-based) recording of a request.
+fn fixture_path(dir: &Path, key: &str, occurrence: usize) -> PathBuf {
+    if occurrence <= 1 {
+        dir.join(format!("{key}.json"))
+    } else {
+        dir.join(format!("{key}.{occurrence}.json"))
+    }
+}
+
+/// Load the recording for the `occurrence`th request, or the latest earlier
+/// one when fewer were recorded.
+fn load(dir: &Path, key: &str, occurrence: usize) -> Option<Fixture> {
+    (1..=occurrence.max(1)).rev().find_map(|n| {
+        let contents = fs::read_to_string(fixture_path(dir, key, n)).ok()?;
+        serde_json::from_str(&contents).ok()
+    })
+}
+
+fn body_value(body: &[u8]) -> Value {
+    if body.is_empty() {
+        return Value::Null;
+    }
+    serde_json::from_slice(body)
+        .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(body).into_owned()))
+}
+
+/// Whether `path` is an auth endpoint, e.g. `/oauth/token` or
+/// `/control/v1/users/me/password`.
+fn is_auth_endpoint(path: &str) -> bool {
+    let route = path.split('?').next().unwrap_or_default();
+    route.split('/').any(|segment| AUTH_SEGMENTS.contains(&segment))
+}
+
+/// Replace token values anywhere in a JSON body.
+fn redact_tokens(body: &mut Value) {
+    match body {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                if TOKEN_FIELDS.contains(&name.as_str()) && value.is_string() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_tokens(value);
+                }
+            }
+        },
+        Value::Array(items) => items.iter_mut().for_each(redact_tokens),
+        _ => {},
+    }
+}
+
+/// Read a `Transfer-Encoding: chunked` body, discarding any trailers.
+async fn read_chunked(reader: &mut (impl AsyncBufRead + Unpin)) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|_| Error::other(format!("Invalid chunk size: {size:?}")))?;
+        if size == 0 {
+            break;
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..]).await?;
+        let mut crlf = [0; 2];
+        reader.read_exact(&mut crlf).await?;
+    }
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim_end().is_empty() {
+            return Ok(body);
+        }
+    }
+}
+
+fn body_bytes(body: &Value) -> Vec<u8> {
+    match body {
+        Value::Null => Vec::new(),
+        Value::String(text) => text.clone().into_bytes(),
+        other => other.to_string().into_bytes(),
+    }
+}
+
+impl Proxy {
+    async fn handle(&self, stream: TcpStream) -> Result<()> {
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).await?;
+        let mut parts = request_line.split_whitespace();
+        let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+            return Ok(());
+        };
+        let (method, path) = (method.to_string(), path.to_string());
+
+        let mut headers = Vec::new();
+        let mut length = 0;
+        let mut chunked = false;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 || line.trim_end().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.trim_end().split_once(':') {
+                let (name, value) = (name.trim().to_string(), value.trim().to_string());
+                if name.eq_ignore_ascii_case("content-length") {
+                    length = value.parse().unwrap_or(0);
+                } else if name.eq_ignore_ascii_case("transfer-encoding") {
+                    chunked = value.to_ascii_lowercase().contains("chunked");
+                }
+                headers.push((name, value));
+            }
+        }
+        let body = if chunked {
+            read_chunked(&mut reader).await?
+        } else {
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).await?;
+            body
+        };
+
+        let key = fixture_key(&method, &path, &body);
+        let occurrence = self.seen.lock().map_or(1, |mut seen| {
+            let count = seen.entry(key.clone()).or_default();
+            *count += 1;
+            *count
+        });
+
+        let fixture = match &self.mode {
+            Mode::Record(dir) => {
+                let fixture = self.forward(&method, &path, &headers, body).await;
+                if let Ok(fixture) = &fixture {
+                    fs::write(
+                        fixture_path(dir, &key, occurrence),
+                        serde_json::to_string_pretty(fixture)?,
+                    )?;
+                }
+                fixture
+            },
+            Mode::Replay(dir) => load(dir, &key, occurrence).ok_or_else(|| {
+                Error::other(format!("No fixture for {method} {path} ({key}.json)"))
+            }),
+        };
+        let fixture = fixture.unwrap_or_else(|e| Fixture {
+            method,
+            path,
+            request_body: Value::Null,
+            status: 501,
+            content_type: Some("application/json".to_string()),
+            body: serde_json::json!({ "error": e.to_string() }),
+        });
+
+        let body = body_bytes(&fixture.body);
+        let reason = reqwest::StatusCode::from_u16(fixture.status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("Unknown");
+        let mut head = format!("HTTP/1.1 {} {reason}\r\n", fixture.status);
+        if let Some(content_type) = &fixture.content_type {
+            let _ = write!(head, "Content-Type: {content_type}\r\n");
+        }
+        let _ = write!(head, "Content-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+
+        let mut stream = reader.into_inner();
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(&body).await?;
+        stream.flush().await?;
+        Ok(())
+    }
+
+    /// Send a request to the real API and capture its response.
+    async fn forward(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &[(String, String)],
+        body: Vec<u8>,
+    ) -> Result<Fixture> {
+        let request_body = body_value(&body);
+        let method_value = reqwest::Method::from_bytes(method.as_bytes())
+            .map_err(|_| Error::other(format!("Invalid HTTP method: {method}")))?;
+        let mut request = self.http.request(method_value, format!("{}{path}", self.upstream));
+        for (name, value) in headers {
+            if !SKIPPED_HEADERS.iter().any(|skipped| name.eq_ignore_ascii_case(skipped)) {
+                request = request.header(name.as_str(), value.as_str());
+            }
+        }
+        if !body.is_empty() {
+            request = request.body(body);
+        }
+
+        let response =
+            request.send().await.map_err(|e| Error::other(format!("Request failed: {e}")))?;
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| Error::other(format!("Failed to read response: {e}")))?;
+
+        let mut fixture = Fixture {
+            method: method.to_string(),
+            path: path.to_string(),
+            request_body,
+            status,
+            content_type,
+            body: body_value(&bytes),
+        };
+        fixture.redact_credentials();
+        Ok(fixture)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_key() {
+        let key = fixture_key("GET", "/control/v1/organizations?limit=10", b"");
+        assert!(key.starts_with("get-control-v1-organizations-"));
+        assert_eq!(key, fixture_key("GET", "/control/v1/organizations?limit=10", b""));
+        assert_ne!(key, fixture_key("GET", "/control/v1/organizations?limit=20", b""));
+        assert_ne!(
+            fixture_key("POST", "/v1/check", br#"{"subject":"user:alice"}"#),
+            fixture_key("POST", "/v1/check", br#"{"subject":"user:bob"}"#)
+        );
+    }
+
+    #[test]
+    fn test_load_repeats_last_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        for (occurrence, count) in [(1, 1), (2, 2)] {
+            let fixture = Fixture {
+                method: "GET".to_string(),
+                path: "/v1/vaults".to_string(),
+                request_body: Value::Null,
+                status: 200,
+                content_type: Some("application/json".to_string()),
+                body: serde_json::json!({ "count": count }),
+            };
+            let path = fixture_path(dir.path(), "get-v1-vaults-abc", occurrence);
+            fs::write(path, serde_json::to_string(&fixture).unwrap()).unwrap();
+        }
+
+        let count = |n| load(dir.path(), "get-v1-vaults-abc", n).unwrap().body["count"].clone();
+        assert_eq!(count(1), 1);
+        assert_eq!(count(2), 2);
+        assert_eq!(count(3), 2);
+        assert!(load(dir.path(), "get-v1-other-abc", 1).is_none());
+    }
+
+    #[test]
+    fn test_is_auth_endpoint() {
+        assert!(is_auth_endpoint("/oauth/token"));
+        assert!(is_auth_endpoint("/oauth2/token?grant_type=refresh_token"));
+        assert!(is_auth_endpoint("/control/v1/users/me/password"));
+        assert!(!is_auth_endpoint("/control/v1/organizations"));
+        assert!(!is_auth_endpoint("/v1/check?tokenized=1"));
+    }
+
+    #[test]
+    fn test_redact_credentials() {
+        let fixture = |path: &str| Fixture {
+            method: "POST".to_string(),
+            path: path.to_string(),
+            request_body: body_value(
+                b"grant_type=refresh_token&refresh_token=r-123&client_assertion=eyJhbGciOi",
+            ),
+            status: 200,
+            content_type: Some("application/json".to_string()),
+            body: serde_json::json!({
+                "access_token": "eyJhbGciOi",
+                "refresh_token": "r-456",
+                "token_type": "Bearer",
+                "nested": [{ "id_token": "eyJ0eXAi" }],
+            }),
+        };
+
+        let mut exchange = fixture("/oauth/token");
+        exchange.redact_credentials();
+        assert_eq!(exchange.request_body, REDACTED);
+        assert_eq!(
+            exchange.body,
+            serde_json::json!({
+                "access_token": REDACTED,
+                "refresh_token": REDACTED,
+                "token_type": "Bearer",
+                "nested": [{ "id_token": REDACTED }],
+            })
+        );
+        let saved = serde_json::to_string(&exchange).unwrap();
+        assert!(!saved.contains("r-123") && !saved.contains("eyJ"));
+
+        let mut other = fixture("/v1/check");
+        other.redact_credentials();
+        assert_eq!(other, fixture("/v1/check"));
+    }
+
+    #[tokio::test]
+    async fn test_read_chunked() {
+        let mut reader = &b"5\r\nhello\r\n7;ext=1\r\n, world\r\n0\r\nX-Trailer: 1\r\n\r\nnext"[..];
+        assert_eq!(read_chunked(&mut reader).await.unwrap(), b"hello, world");
+        assert_eq!(reader, b"next");
+
+        let mut reader = &b"zz\r\n"[..];
+        assert!(read_chunked(&mut reader).await.is_err());
+    }
+
+    #[test]
+    fn test_body_round_trip() {
+        assert_eq!(body_bytes(&body_value(br#"{"a":1}"#)), br#"{"a":1}"#);
+        assert_eq!(body_bytes(&body_value(b"plain text")), b"plain text");
+        assert!(body_value(b"").is_null());
+    }
+}
@@ -4,20 +4,28 @@
 //! profile-based configuration and credential management.
 
 pub mod auth;
+pub mod fixtures;
+pub mod preflight;
+pub mod retry;
 
 pub use auth::OAuthFlow;
 use bon::bon;
 use inferadb::{
-    BearerCredentialsConfig, Client, VaultClient,
+    BearerCredentialsConfig, Client, ClientBuilder, VaultClient,
     client::OrganizationClient,
     control::{AccountClient, JwksClient, OrganizationsClient},
 };
+use serde::{Serialize, de::DeserializeOwned};
 
 use crate::{
-    config::{Config, CredentialStore, Credentials, Profile},
+    config::{Config, CredentialStore, Credentials, Profile, ProfileUsage, ResponseCache},
     error::{Error, Result},
+    output::breakdown::{self, Phase},
 };
 
+/// Environment variable holding an access token for non-interactive use.
+pub const TOKEN_ENV: &str = "INFERADB_TOKEN";
+
 /// CLI client that wraps the `InferaDB` SDK client.
 pub struct CliClient {
     inner: Client,
@@ -32,17 +40,21 @@ impl CliClient {
         let org_id = profile.org()?.to_string();
         let vault_id = profile.vault()?.to_string();
 
-        let inner = Client::builder()
-            .url(url)
-            .credentials(BearerCredentialsConfig::new(&credentials.access_token))
-            .build()
-            .await?;
+        let builder = with_policy(
+            Client::builder()
+                .url(fixtures::url_for(url))
+                .credentials(BearerCredentialsConfig::new(&credentials.access_token)),
+        );
+        let inner = breakdown::timed(Phase::Auth, "build client", builder.build()).await?;
 
         Ok(Self { inner, org_id, vault_id })
     }
 
     /// Create a CLI client using configuration and stored credentials.
     ///
+    /// The server is preflighted while credentials load, so an unreachable
+    /// URL fails fast rather than after the request timeout.
+    ///
     /// # Arguments
     /// * `config` - CLI configuration
     /// * `profile_name` - Optional explicit profile name
@@ -55,23 +67,17 @@ impl CliClient {
         vault_override: Option<&str>,
     ) -> Result<Self> {
         let profile = config.get_effective_profile(profile_name, org_override, vault_override)?;
-
-        // Determine which profile name to use for credentials
-        let cred_profile = profile_name
-            .map(std::string::ToString::to_string)
-            .or_else(|| config.default_profile.clone())
-            .unwrap_or_else(|| "default".to_string());
-
-        // Load credentials from keychain
-        let store = CredentialStore::new();
-        let credentials = store.load(&cred_profile)?.ok_or(Error::AuthRequired)?;
-
-        // Check if credentials are expired
-        if credentials.is_expired() {
-            return Err(Error::AuthRequired);
-        }
-
-        Self::from_profile(&profile, &credentials).await
+        let (reachable, credentials) = tokio::join!(
+            breakdown::timed(Phase::Api, "preflight", preflight::check(profile.url_or_default())),
+            breakdown::timed(
+                Phase::Auth,
+                "load credentials",
+                load_credentials(config, profile_name)
+            )
+        );
+        reachable?;
+
+        Self::from_profile(&profile, &credentials?).await
     }
 
     /// Get the underlying `InferaDB` client.
@@ -132,6 +138,110 @@ impl CliClient {
     }
 }
 
+/// Create an SDK client for a profile that may not have an organization or
+/// vault set yet, for account-wide listings.
+pub async fn account_client(config: &Config, profile_name: &str) -> Result<Client> {
+    let profile = config
+        .get_profile(profile_name)
+        .ok_or_else(|| Error::ProfileNotFound(profile_name.to_string()))?;
+    let (reachable, credentials) = tokio::join!(
+        breakdown::timed(Phase::Api, "preflight", preflight::check(profile.url_or_default())),
+        breakdown::timed(
+            Phase::Auth,
+            "load credentials",
+            load_credentials(config, Some(profile_name))
+        )
+    );
+    reachable?;
+    let credentials = credentials?;
+
+    let builder = with_policy(
+        Client::builder()
+            .url(fixtures::url_for(profile.url_or_default()))
+            .credentials(BearerCredentialsConfig::new(&credentials.access_token)),
+    );
+    Ok(breakdown::timed(Phase::Auth, "build client", builder.build()).await?)
+}
+
+/// Apply the process-wide timeout and retry policy to an SDK client builder.
+fn with_policy<U, C>(builder: ClientBuilder<U, C>) -> ClientBuilder<U, C> {
+    let policy = retry::policy();
+    let builder = builder.retry_config(policy.sdk_config());
+    match policy.timeout {
+        Some(timeout) => builder.timeout(timeout),
+        None => builder,
+    }
+}
+
+/// Resolve unexpired credentials for a profile.
+///
+/// A token in the environment (e.g., a CI secret) takes precedence over the
+/// keychain. Stored credentials that have expired, or expire within five
+/// minutes, are renewed with their refresh token (or, for org clients, a new
+/// client credentials grant) and saved back.
+async fn load_credentials(config: &Config, profile_name: Option<&str>) -> Result<Credentials> {
+    // Determine which profile name to use for credentials
+    let cred_profile = profile_name
+        .map(std::string::ToString::to_string)
+        .or_else(|| config.default_profile.clone())
+        .unwrap_or_else(|| "default".to_string());
+
+    // Replayed responses don't need a real token
+    if fixtures::replaying() {
+        return Ok(Credentials::builder().access_token("replay").build());
+    }
+
+    if let Ok(token) = std::env::var(TOKEN_ENV)
+        && !token.is_empty()
+    {
+        return Ok(Credentials::builder().access_token(token).build());
+    }
+
+    let credentials = CredentialStore::new().load(&cred_profile)?.ok_or(Error::AuthRequired)?;
+    // Last use is informational, so failing to record it never fails a command
+    if let Err(e) = ProfileUsage::new().record(&cred_profile) {
+        tracing::debug!(profile = %cred_profile, error = %e, "could not record profile use");
+    }
+    if !credentials.expires_soon() || !credentials.can_refresh() {
+        return if credentials.is_expired() { Err(Error::AuthRequired) } else { Ok(credentials) };
+    }
+
+    match refresh_credentials(&cred_profile, &credentials).await {
+        Ok(refreshed) => Ok(refreshed),
+        // A token that is still valid can be used while the refresh fails
+        Err(e) if !credentials.is_expired() => {
+            tracing::debug!(profile = %cred_profile, error = %e, "token refresh failed");
+            Ok(credentials)
+        },
+        Err(e @ Error::AuthThrottled { .. }) => Err(e),
+        Err(e) => {
+            tracing::debug!(profile = %cred_profile, error = %e, "token refresh failed");
+            Err(Error::AuthRequired)
+        },
+    }
+}
+
+/// Renew a profile's credentials and store them.
+///
+/// Org client credentials are renewed with the client's key; others with
+/// their refresh token.
+pub async fn refresh_credentials(profile: &str, credentials: &Credentials) -> Result<Credentials> {
+    let oauth = OAuthFlow::new()?;
+    let refreshed = if let (Some(client_id), Some(client_key)) =
+        (&credentials.client_id, &credentials.client_key)
+    {
+        oauth.client_credentials(client_id, client_key).await?
+    } else {
+        let refresh_token = credentials
+            .refresh_token
+            .as_deref()
+            .ok_or_else(|| Error::credential("Credentials have no refresh token"))?;
+        oauth.refresh(refresh_token).await?
+    };
+    CredentialStore::new().store(profile, &refreshed)?;
+    Ok(refreshed)
+}
+
 /// Context for CLI command execution.
 ///
 /// Contains everything needed to execute CLI commands:
@@ -156,13 +266,22 @@ pub struct Context {
 
     /// Debug mode.
     pub debug: bool,
+
+    /// Response cache, when `--cached` or `cache.enabled` turns it on.
+    pub cache: Option<ResponseCache>,
+
+    /// Webhook notified when long-running commands finish.
+    pub notify_webhook: Option<String>,
 }
 
 #[bon]
 impl Context {
     /// Create a new context from CLI options.
+    ///
+    /// Uses `config` when the caller already loaded it, and loads it otherwise.
     #[builder]
     pub fn new(
+        config: Option<Config>,
         profile_name: Option<String>,
         org_override: Option<String>,
         vault_override: Option<String>,
@@ -171,8 +290,44 @@ impl Context {
         #[builder(default)] quiet: bool,
         #[builder(default)] yes: bool,
         #[builder(default)] debug: bool,
+        #[builder(default)] accessible: bool,
+        #[builder(default)] ascii: bool,
+        #[builder(default)] utc: bool,
+        #[builder(default)] relative: bool,
+        timezone: Option<String>,
+        #[builder(default)] cached: bool,
+        #[builder(default)] no_cache: bool,
+        #[builder(default)] no_keychain: bool,
+        #[builder(default)] non_blocking: bool,
+        notify_webhook: Option<String>,
+        timeout: Option<u64>,
+        retries: Option<u32>,
+        retry_on: Option<String>,
+        query: Option<String>,
+        record: Option<std::path::PathBuf>,
+        replay: Option<std::path::PathBuf>,
+        #[builder(default)] suppress_warnings: Vec<String>,
     ) -> Result<Self> {
-        let config = Config::load()?;
+        let config = match config {
+            Some(config) => config,
+            None => Config::load()?,
+        };
+        // A project's pinned profile applies unless one was chosen explicitly
+        let profile_name = profile_name.or_else(|| config.pinned_profile().map(str::to_string));
+        crate::config::credentials::configure(&config, no_keychain);
+        crate::config::lock::set_non_blocking(non_blocking);
+
+        let mut policy = retry::RetryPolicy {
+            timeout: timeout.map(std::time::Duration::from_secs),
+            ..retry::RetryPolicy::default()
+        };
+        if let Some(retries) = retries {
+            policy.retries = retries;
+        }
+        if let Some(retry_on) = retry_on {
+            policy.retry_on = retry::RetryPolicy::parse_statuses(&retry_on)?;
+        }
+        retry::configure(policy);
 
         let profile = config.get_effective_profile(
             profile_name.as_deref(),
@@ -180,9 +335,43 @@ impl Context {
             vault_override.as_deref(),
         )?;
 
-        let output = crate::output::Output::from_cli(&output_format, &color, quiet)?;
+        let fixture_mode =
+            record.map(fixtures::Mode::Record).or_else(|| replay.map(fixtures::Mode::Replay));
+        if let Some(mode) = fixture_mode {
+            fixtures::start(mode, profile.url_or_default())?;
+        }
+
+        crate::output::warnings::suppress(
+            suppress_warnings.into_iter().chain(config.output.suppress_warnings.iter().cloned()),
+        );
+
+        crate::output::theme::set(crate::output::theme::Theme::from_config(&config.output.theme)?);
+
+        let accessible = accessible || config.output.accessible;
+        crate::tui::set_accessible(accessible);
+        crate::output::glyphs::set_ascii(ascii || !config.output.unicode);
+        crate::output::dates::set_style(match (utc, relative) {
+            (_, true) => crate::output::dates::TimeStyle::Relative,
+            (true, false) => crate::output::dates::TimeStyle::Utc,
+            (false, false) => crate::output::dates::TimeStyle::Local,
+        });
+        if let Some(zone) = timezone.or_else(|| profile.timezone.clone()) {
+            crate::output::dates::set_timezone(crate::output::timezone::TimeZone::parse(&zone)?);
+        }
+        let mut output =
+            crate::output::Output::from_cli(&output_format, &color, quiet, accessible)?;
+        output.query = query.as_deref().map(crate::output::Query::parse).transpose()?;
+
+        let cache = (!no_cache && (cached || config.cache.enabled)).then(|| {
+            let name =
+                profile_name.as_deref().or(config.default_profile.as_deref()).unwrap_or("default");
+            let ttl = i64::try_from(config.cache.ttl_seconds).unwrap_or(i64::MAX);
+            ResponseCache::new(name, chrono::Duration::seconds(ttl))
+        });
 
-        Ok(Self { config, profile, profile_name, output, yes, debug })
+        let notify_webhook = notify_webhook.or_else(|| config.notify.webhook.clone());
+
+        Ok(Self { config, profile, profile_name, output, yes, debug, cache, notify_webhook })
     }
 
     /// Create a client using the context configuration.
@@ -190,6 +379,34 @@ impl Context {
         CliClient::from_config(&self.config, self.profile_name.as_deref(), None, None).await
     }
 
+    /// Run a read request through the response cache, if it is enabled.
+    ///
+    /// `key` names the request (including the organization and any filters);
+    /// entries are already scoped to the profile. Failing to store a response
+    /// does not fail the command.
+    pub async fn cached<T>(&self, key: &str, fetch: impl Future<Output = Result<T>>) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let Some(cache) = &self.cache else {
+            return breakdown::timed(Phase::Api, key, fetch).await;
+        };
+        if let Some(value) = cache.get(key) {
+            tracing::debug!(key, "response cache hit");
+            return Ok(value);
+        }
+        let value = breakdown::timed(Phase::Api, key, fetch).await?;
+        if let Err(e) = cache.put(key, &value) {
+            tracing::debug!(key, error = %e, "failed to cache response");
+        }
+        Ok(value)
+    }
+
+    /// Get the access token a client would use, for direct HTTP requests.
+    pub async fn access_token(&self) -> Result<String> {
+        Ok(load_credentials(&self.config, self.profile_name.as_deref()).await?.access_token)
+    }
+
     /// Get credentials for the current profile.
     pub fn credentials(&self) -> Result<Credentials> {
         let store = CredentialStore::new();
@@ -202,6 +419,30 @@ impl Context {
         store.load(profile_name)?.ok_or(Error::AuthRequired)
     }
 
+    /// Warn when the session token expires within the configured window.
+    ///
+    /// Skipped when a token is supplied through the environment, since stored
+    /// credentials are not used then.
+    pub fn warn_if_session_expiring(&self) {
+        let minutes = self.config.auth.expiry_warning_minutes;
+        if minutes == 0 || std::env::var(TOKEN_ENV).is_ok_and(|token| !token.is_empty()) {
+            return;
+        }
+
+        let window = chrono::Duration::minutes(i64::from(minutes));
+        if let Ok(credentials) = self.credentials()
+            && let Some(remaining) = credentials.expires_within(window)
+        {
+            self.output.warning(
+                crate::output::warnings::TOKEN_EXPIRING,
+                &format!(
+                    "Token expires in {}; run `inferadb tokens refresh`",
+                    format_remaining(remaining)
+                ),
+            );
+        }
+    }
+
     /// Check if the user is authenticated.
     #[must_use]
     pub fn is_authenticated(&self) -> bool {
@@ -209,6 +450,9 @@ impl Context {
     }
 
     /// Prompt for confirmation (respects --yes flag).
+    ///
+    /// Every confirmation goes through here or [`Self::confirm_danger`], so
+    /// `--yes` and non-interactive defaults behave the same for all commands.
     pub fn confirm(&self, message: &str) -> Result<bool> {
         if self.yes {
             return Ok(true);
@@ -259,10 +503,29 @@ impl Context {
     }
 }
 
+/// Format a short remaining duration, e.g. `45s`, `12m`, or `1h 5m`.
+fn format_remaining(remaining: chrono::Duration) -> String {
+    let minutes = remaining.num_minutes();
+    if minutes < 1 {
+        format!("{}s", remaining.num_seconds().max(0))
+    } else if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(chrono::Duration::seconds(45)), "45s");
+        assert_eq!(format_remaining(chrono::Duration::minutes(12)), "12m");
+        assert_eq!(format_remaining(chrono::Duration::minutes(65)), "1h 5m");
+    }
+
     #[test]
     fn test_context_creation() {
         // This test requires no config file to exist
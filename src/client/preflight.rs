@@ -0,0 +1,201 @@
+//! Fast reachability checks before the first API request.
+//!
+//! An unreachable server would otherwise hold a command for the full request
+//! timeout. The preflight resolves the profile URL's host, races a TCP
+//! connection to every address it resolved to, and for `https` URLs completes
+//! a TLS handshake, each under a short timeout, so a bad URL fails in seconds
+//! with an error naming the stage that failed.
+//!
+//! It is skipped when replaying fixtures and when a proxy is configured, since
+//! the proxy rather than the CLI connects to the server then. Each URL is
+//! checked at most once per process.
+
+use std::{collections::HashSet, fmt, sync::Mutex, time::Duration};
+
+use futures::future::select_ok;
+use tokio::net::{TcpStream, lookup_host};
+
+use crate::{
+    client::{fixtures, retry},
+    error::{Error, Result},
+};
+
+/// Longest wait for each preflight stage.
+const STAGE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Proxy variables that route requests away from a direct connection.
+const PROXY_ENV: [&str; 6] =
+    ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"];
+
+/// URLs that passed the preflight in this process.
+static PASSED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+/// Why a server could not be reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unreachable {
+    /// The host name did not resolve.
+    Dns {
+        /// Host name that was looked up.
+        host: String,
+        /// Why the lookup failed.
+        reason: String,
+    },
+    /// No resolved address accepted a TCP connection.
+    Connect {
+        /// Host name whose addresses were tried.
+        host: String,
+        /// TCP port.
+        port: u16,
+        /// Why the connection failed.
+        reason: String,
+    },
+    /// The TCP connection opened but the TLS handshake failed.
+    Tls {
+        /// Host name presented for TLS.
+        host: String,
+        /// Why the handshake failed.
+        reason: String,
+    },
+}
+
+impl Unreachable {
+    /// Stable identifier for machine-readable output.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::Dns { .. } => "dns_failed",
+            Self::Connect { .. } => "connect_failed",
+            Self::Tls { .. } => "tls_failed",
+        }
+    }
+}
+
+impl fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dns { host, reason } => write!(f, "Could not resolve {host}: {reason}"),
+            Self::Connect { host, port, reason } => {
+                write!(f, "Could not connect to {host}:{port}: {reason}")
+            },
+            Self::Tls { host, reason } => write!(f, "TLS handshake with {host} failed: {reason}"),
+        }
+    }
+}
+
+/// Check that the server at `url` can be reached, unless it already passed
+/// or the check does not apply.
+pub async fn check(url: &str) -> Result<()> {
+    if fixtures::replaying() || PROXY_ENV.iter().any(|name| std::env::var_os(name).is_some()) {
+        return Ok(());
+    }
+    if PASSED.lock().is_ok_and(|passed| passed.as_ref().is_some_and(|p| p.contains(url))) {
+        return Ok(());
+    }
+
+    let (host, port, tls) = target(url)?;
+    let timeout = retry::policy().timeout.map_or(STAGE_TIMEOUT, |t| t.min(STAGE_TIMEOUT));
+    reach(&host, port, timeout).await.map_err(Error::Unreachable)?;
+    if tls {
+        handshake(url, &host, timeout).await.map_err(Error::Unreachable)?;
+    }
+
+    if let Ok(mut passed) = PASSED.lock() {
+        passed.get_or_insert_with(HashSet::new).insert(url.to_string());
+    }
+    Ok(())
+}
+
+/// Host, port, and whether TLS is used, for a server URL.
+fn target(url: &str) -> Result<(String, u16, bool)> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| Error::config(format!("Invalid server URL {url}: {e}")))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| Error::config(format!("Server URL {url} has no host")))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = parsed
+        .port_or_known_default()
+        .ok_or_else(|| Error::config(format!("Server URL {url} has no port")))?;
+    Ok((host, port, parsed.scheme() == "https"))
+}
+
+/// Resolve `host` and connect to the first address that accepts.
+async fn reach(host: &str, port: u16, timeout: Duration) -> std::result::Result<(), Unreachable> {
+    let dns = |reason: String| Unreachable::Dns { host: host.to_string(), reason };
+    let addrs: Vec<_> = match tokio::time::timeout(timeout, lookup_host((host, port))).await {
+        Ok(Ok(addrs)) => addrs.collect(),
+        Ok(Err(e)) => return Err(dns(e.to_string())),
+        Err(_) => return Err(dns(format!("timed out after {}s", timeout.as_secs()))),
+    };
+    if addrs.is_empty() {
+        return Err(dns("no addresses found".to_string()));
+    }
+
+    let connect = |reason: String| Unreachable::Connect { host: host.to_string(), port, reason };
+    let attempts = addrs.into_iter().map(|addr| Box::pin(TcpStream::connect(addr)));
+    match tokio::time::timeout(timeout, select_ok(attempts)).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(connect(e.to_string())),
+        Err(_) => Err(connect(format!("timed out after {}s", timeout.as_secs()))),
+    }
+}
+
+/// Complete a TLS handshake with the server.
+///
+/// Any HTTP response, whatever its status, means the handshake succeeded. A
+/// slow response is left for the real request to time out on.
+async fn handshake(
+    url: &str,
+    host: &str,
+    timeout: Duration,
+) -> std::result::Result<(), Unreachable> {
+    let tls = |reason: String| Unreachable::Tls { host: host.to_string(), reason };
+    let client = reqwest::Client::builder()
+        .connect_timeout(timeout)
+        .timeout(timeout)
+        .build()
+        .map_err(|e| tls(e.to_string()))?;
+    match client.head(url).send().await {
+        // The TCP connection already succeeded, so a connect error is the handshake
+        Err(e) if e.is_connect() => Err(tls(root_cause(&e))),
+        _ => Ok(()),
+    }
+}
+
+/// The innermost error message, which names the TLS failure (e.g., an
+/// unknown issuer or expired certificate) rather than the request.
+fn root_cause(err: &(dyn std::error::Error + 'static)) -> String {
+    let mut cause = err;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    cause.to_string()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target() {
+        assert_eq!(
+            target("https://api.inferadb.com").unwrap(),
+            ("api.inferadb.com".into(), 443, true)
+        );
+        assert_eq!(target("http://localhost:8080/v1").unwrap(), ("localhost".into(), 8080, false));
+        assert_eq!(target("https://[::1]:9443").unwrap(), ("::1".into(), 9443, true));
+        assert!(target("not a url").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reach_refused() {
+        // Bind then drop a listener to find a port nothing listens on
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let err = reach("127.0.0.1", port, STAGE_TIMEOUT).await.unwrap_err();
+        assert_eq!(err.code(), "connect_failed");
+        assert!(err.to_string().starts_with(&format!("Could not connect to 127.0.0.1:{port}")));
+    }
+}
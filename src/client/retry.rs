@@ -0,0 +1,126 @@
+//! Request timeout and retry policy.
+//!
+//! `--timeout`, `--retries` and `--retry-on` are set once per process and
+//! applied to every SDK client the CLI builds. The SDK retries connection
+//! failures, 429 and 5xx responses with exponential backoff and jitter while
+//! `--retry-on` selects any of those; the CLI's own retry loops (bulk writes,
+//! retagging) retry exactly the statuses `--retry-on` lists.
+
+use std::{sync::RwLock, time::Duration};
+
+use inferadb::RetryConfig;
+
+use crate::error::{Error, Result};
+
+/// Retries per request unless `--retries` says otherwise.
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Statuses retried unless `--retry-on` says otherwise.
+pub const DEFAULT_RETRY_ON: &[u16] = &[429, 502, 503, 504];
+
+const INITIAL_DELAY: Duration = Duration::from_millis(250);
+const MAX_DELAY: Duration = Duration::from_secs(10);
+const JITTER: f64 = 0.2;
+
+/// How long to wait for a request and which failures to retry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Per-request timeout; the SDK default when `None`.
+    pub timeout: Option<Duration>,
+    /// Retries after the first attempt.
+    pub retries: u32,
+    /// HTTP statuses to retry.
+    pub retry_on: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { timeout: None, retries: DEFAULT_RETRIES, retry_on: DEFAULT_RETRY_ON.to_vec() }
+    }
+}
+
+impl RetryPolicy {
+    /// Parse a comma-separated status list such as `429,503`.
+    pub fn parse_statuses(list: &str) -> Result<Vec<u16>> {
+        list.split(',')
+            .map(str::trim)
+            .filter(|status| !status.is_empty())
+            .map(|status| {
+                let code = status.parse::<u16>().ok().filter(|code| (400..=599).contains(code));
+                code.ok_or_else(|| {
+                    Error::invalid_arg(format!(
+                        "Invalid --retry-on status '{status}': expected a 4xx or 5xx code"
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Whether a failed request should be retried.
+    #[must_use]
+    pub fn should_retry(&self, error: &inferadb::Error) -> bool {
+        self.retry_on.contains(&error.kind().http_status_code())
+    }
+
+    /// The SDK's retry configuration: jittered exponential backoff, off
+    /// when no status the SDK retries is selected.
+    ///
+    /// The SDK has no status filter, so while any of 429 or 5xx is selected
+    /// it retries all of them, not only those `--retry-on` lists.
+    #[must_use]
+    pub fn sdk_config(&self) -> RetryConfig {
+        let enabled = self.retry_on.iter().any(|status| *status == 429 || *status >= 500);
+        RetryConfig::new()
+            .with_max_retries(if enabled { self.retries } else { 0 })
+            .with_initial_delay(INITIAL_DELAY)
+            .with_max_delay(MAX_DELAY)
+            .with_multiplier(2.0)
+            .with_jitter(JITTER)
+    }
+}
+
+static POLICY: RwLock<Option<RetryPolicy>> = RwLock::new(None);
+
+/// Use `policy` for the rest of the process.
+pub fn configure(policy: RetryPolicy) {
+    if let Ok(mut configured) = POLICY.write() {
+        *configured = Some(policy);
+    }
+}
+
+/// The configured policy, or the default.
+#[must_use]
+pub fn policy() -> RetryPolicy {
+    POLICY.read().ok().and_then(|policy| policy.clone()).unwrap_or_default()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_statuses() {
+        assert_eq!(RetryPolicy::parse_statuses("429,503").unwrap(), [429, 503]);
+        assert_eq!(RetryPolicy::parse_statuses(" 500 , 502,").unwrap(), [500, 502]);
+        assert!(RetryPolicy::parse_statuses("200").is_err());
+        assert!(RetryPolicy::parse_statuses("abc").is_err());
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let policy = RetryPolicy { retry_on: vec![429], ..RetryPolicy::default() };
+        assert!(policy.should_retry(&inferadb::Error::rate_limited(None)));
+        assert!(!policy.should_retry(&inferadb::Error::unavailable("down")));
+        assert!(RetryPolicy::default().should_retry(&inferadb::Error::unavailable("down")));
+    }
+
+    #[test]
+    fn test_sdk_config() {
+        let policy = RetryPolicy { retries: 5, ..RetryPolicy::default() };
+        assert_eq!(policy.sdk_config().max_retries, 5);
+
+        let none = RetryPolicy { retry_on: vec![409], ..RetryPolicy::default() };
+        assert_eq!(none.sdk_config().max_retries, 0);
+    }
+}
@@ -5,7 +5,11 @@
 use bon::builder;
 use serde::Serialize;
 
-use crate::{client::Context, error::Result, output::Displayable};
+use crate::{
+    client::Context,
+    error::Result,
+    output::{Displayable, dates},
+};
 
 // ============================================================================
 // Display types
@@ -34,7 +38,7 @@ struct SessionRow {
     id: String,
     device: String,
     ip_address: String,
-    last_active: String,
+    last_active: chrono::DateTime<chrono::Utc>,
     current: String,
 }
 
@@ -44,7 +48,7 @@ impl Displayable for SessionRow {
             self.id.clone(),
             self.device.clone(),
             self.ip_address.clone(),
-            self.last_active.clone(),
+            dates::format(self.last_active),
             self.current.clone(),
         ]
     }
@@ -72,8 +76,8 @@ pub async fn show(ctx: &Context) -> Result<()> {
     println!("Name: {}", account.name.as_deref().unwrap_or("-"));
     println!("Email: {}", account.email);
     println!("Status: {:?}", account.status);
-    println!("Created: {}", account.created_at.format("%Y-%m-%d %H:%M:%S"));
-    println!("Updated: {}", account.updated_at.format("%Y-%m-%d %H:%M:%S"));
+    println!("Created: {}", dates::format_precise(account.created_at));
+    println!("Updated: {}", dates::format_precise(account.updated_at));
 
     Ok(())
 }
@@ -274,7 +278,7 @@ pub async fn sessions_list(ctx: &Context) -> Result<()> {
             id: s.id.clone(),
             device: s.user_agent.clone().unwrap_or_else(|| "Unknown".to_string()),
             ip_address: s.ip_address.clone().unwrap_or_else(|| "-".to_string()),
-            last_active: s.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            last_active: s.created_at,
             current: if s.current { "yes" } else { "no" }.to_string(),
         })
         .collect();
@@ -0,0 +1,261 @@
+//! Raw API requests.
+//!
+//! `inferadb api` sends an authenticated request to the profile's endpoint
+//! and prints the response, for endpoints the CLI does not wrap yet.
+
+use reqwest::{Method, Url, header};
+use serde_json::{Map, Value};
+
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    output::breakdown::{self, Phase},
+};
+
+/// Upper bound on pages fetched with `--paginate`, in case a cursor never ends.
+const MAX_PAGES: usize = 1000;
+
+/// Send a request to `path` and print the response.
+///
+/// `{org}` and `{vault}` in the path expand to the profile's organization and
+/// vault. Fields become query parameters for `GET` and `DELETE`, and a JSON
+/// body otherwise; with `input`, the file (or `-` for stdin) is sent as the
+/// body and fields go in the query string. With `paginate`, `GET` requests
+/// follow the response's next cursor and print the items of every page as
+/// one array.
+pub async fn api(
+    ctx: &Context,
+    path: &str,
+    method: Option<&str>,
+    fields: &[String],
+    headers: &[String],
+    input: Option<&str>,
+    paginate: bool,
+) -> Result<()> {
+    let fields = fields.iter().map(|f| parse_field(f)).collect::<Result<Vec<_>>>()?;
+    let headers = headers.iter().map(|h| parse_header(h)).collect::<Result<Vec<_>>>()?;
+
+    let method = match method {
+        Some(m) => Method::from_bytes(m.to_uppercase().as_bytes())
+            .map_err(|_| Error::invalid_arg(format!("Invalid HTTP method: {m}")))?,
+        None if !fields.is_empty() || input.is_some() => Method::POST,
+        None => Method::GET,
+    };
+    if paginate && method != Method::GET {
+        return Err(Error::invalid_arg("--paginate only applies to GET requests"));
+    }
+
+    let path = expand_path(ctx, path)?;
+    let base = Url::parse(&crate::client::fixtures::url_for(ctx.profile.url_or_default()))
+        .map_err(|e| Error::config(format!("Invalid endpoint URL: {e}")))?;
+    let mut url = base
+        .join(&path)
+        .map_err(|e| Error::invalid_arg(format!("Invalid API path '{path}': {e}")))?;
+
+    let body = if let Some(input) = input {
+        add_query(&mut url, &fields);
+        Some(read_input(input)?)
+    } else if method == Method::GET || method == Method::DELETE {
+        add_query(&mut url, &fields);
+        None
+    } else {
+        Some(serde_json::to_string(&Value::Object(fields.into_iter().collect()))?)
+    };
+
+    let token = breakdown::timed(Phase::Auth, "load credentials", ctx.access_token()).await?;
+    let http = reqwest::Client::new();
+    let send = |url: Url| {
+        let label = format!("{method} {}", url.path());
+        let mut request = http
+            .request(method.clone(), url)
+            .bearer_auth(&token)
+            .header(header::ACCEPT, "application/json");
+        for (name, value) in &headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(body) = &body {
+            request = request.header(header::CONTENT_TYPE, "application/json").body(body.clone());
+        }
+        async move {
+            breakdown::timed(Phase::Api, &label, async {
+                let response = request
+                    .send()
+                    .await
+                    .map_err(|e| Error::other(format!("Request failed: {e}")))?;
+                read_response(response).await
+            })
+            .await
+        }
+    };
+
+    if !paginate {
+        return match send(url).await? {
+            Value::String(text) => {
+                println!("{text}");
+                Ok(())
+            },
+            value => ctx.output.value(&value),
+        };
+    }
+
+    let mut items = Vec::new();
+    for _ in 0..MAX_PAGES {
+        let page = send(url.clone()).await?;
+        let page_items =
+            page_items(&page).ok_or_else(|| Error::other("Response is not a paginated list"))?;
+        items.extend(page_items.iter().cloned());
+
+        let Some(cursor) = next_cursor(&page) else {
+            return ctx.output.value(&Value::Array(items));
+        };
+        let query: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(k, _)| k != "cursor")
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(query).append_pair("cursor", cursor);
+    }
+
+    Err(Error::other(format!("Stopped after {MAX_PAGES} pages; the cursor did not end")))
+}
+
+/// Parse a `key=value` field. Booleans, `null` and numbers are sent as JSON
+/// values; anything else is a string.
+fn parse_field(field: &str) -> Result<(String, Value)> {
+    let (key, raw) = field.split_once('=').filter(|(key, _)| !key.is_empty()).ok_or_else(|| {
+        Error::invalid_arg(format!("Invalid field '{field}': expected key=value"))
+    })?;
+
+    let value = match serde_json::from_str::<Value>(raw) {
+        Ok(value @ (Value::Bool(_) | Value::Null | Value::Number(_))) => value,
+        _ => Value::String(raw.to_string()),
+    };
+    Ok((key.to_string(), value))
+}
+
+/// Parse a `Name: value` header.
+fn parse_header(header: &str) -> Result<(String, String)> {
+    header
+        .split_once(':')
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or_else(|| {
+            Error::invalid_arg(format!("Invalid header '{header}': expected Name: value"))
+        })
+}
+
+/// Expand `{org}` and `{vault}` placeholders and make the path absolute.
+fn expand_path(ctx: &Context, path: &str) -> Result<String> {
+    let mut path = path.to_string();
+    if path.contains("{org}") {
+        path = path.replace("{org}", &ctx.require_org_id()?);
+    }
+    if path.contains("{vault}") {
+        path = path.replace("{vault}", &ctx.require_vault_id()?);
+    }
+    if !path.starts_with('/') {
+        path.insert(0, '/');
+    }
+    Ok(path)
+}
+
+/// Append fields to the query string.
+fn add_query(url: &mut Url, fields: &[(String, Value)]) {
+    if fields.is_empty() {
+        return;
+    }
+    let mut query = url.query_pairs_mut();
+    for (key, value) in fields {
+        match value {
+            Value::String(s) => query.append_pair(key, s),
+            other => query.append_pair(key, &other.to_string()),
+        };
+    }
+}
+
+/// Read a request body from a file, or stdin for `-`.
+fn read_input(input: &str) -> Result<String> {
+    Ok(crate::input::read_to_string(input)?)
+}
+
+/// Decode a response as JSON (or plain text), mapping error statuses to API
+/// errors so exit codes match the rest of the CLI.
+async fn read_response(response: reqwest::Response) -> Result<Value> {
+    let status = response.status();
+    let text = response.text().await.map_err(|e| Error::other(format!("Request failed: {e}")))?;
+
+    if !status.is_success() {
+        let kind = inferadb::ErrorKind::from_http_status(status.as_u16());
+        return Err(inferadb::Error::new(kind, format!("HTTP {status}: {}", text.trim())).into());
+    }
+    if text.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    Ok(serde_json::from_str(&text).unwrap_or(Value::String(text)))
+}
+
+/// The items of a list response: `items` for control plane pages, or the
+/// only array field (e.g. `relationships`) for vault lists.
+fn page_items(page: &Value) -> Option<&Vec<Value>> {
+    let object: &Map<String, Value> = page.as_object()?;
+    if let Some(items) = object.get("items").and_then(Value::as_array) {
+        return Some(items);
+    }
+    let mut arrays = object.values().filter_map(Value::as_array);
+    match (arrays.next(), arrays.next()) {
+        (Some(items), None) => Some(items),
+        _ => None,
+    }
+}
+
+/// The cursor for the next page, from `page_info.next_cursor` or a top-level
+/// `next_cursor`.
+fn next_cursor(page: &Value) -> Option<&str> {
+    page.pointer("/page_info/next_cursor")
+        .or_else(|| page.get("next_cursor"))
+        .and_then(Value::as_str)
+        .filter(|cursor| !cursor.is_empty())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_field() {
+        assert_eq!(parse_field("name=docs").unwrap(), ("name".to_string(), json!("docs")));
+        assert_eq!(parse_field("limit=50").unwrap(), ("limit".to_string(), json!(50)));
+        assert_eq!(parse_field("active=true").unwrap(), ("active".to_string(), json!(true)));
+        assert_eq!(parse_field("q=a=b").unwrap(), ("q".to_string(), json!("a=b")));
+        assert_eq!(parse_field("tags=[1]").unwrap(), ("tags".to_string(), json!("[1]")));
+        assert!(parse_field("name").is_err());
+        assert!(parse_field("=value").is_err());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("X-Request-Id: abc").unwrap(),
+            ("X-Request-Id".to_string(), "abc".to_string())
+        );
+        assert!(parse_header("no-colon").is_err());
+    }
+
+    #[test]
+    fn test_pagination_fields() {
+        let control =
+            json!({"items": [1, 2], "page_info": {"has_next": true, "next_cursor": "c2"}});
+        assert_eq!(page_items(&control).unwrap().len(), 2);
+        assert_eq!(next_cursor(&control), Some("c2"));
+
+        let vault = json!({"relationships": [1], "next_cursor": null});
+        assert_eq!(page_items(&vault).unwrap().len(), 1);
+        assert_eq!(next_cursor(&vault), None);
+
+        assert!(page_items(&json!({"a": [], "b": []})).is_none());
+        assert!(page_items(&json!([1, 2])).is_none());
+    }
+}
@@ -4,20 +4,48 @@ use teapot::forms::{Field, Form, Group};
 
 use crate::{
     client::{Context, OAuthFlow, auth},
-    error::Result,
+    config::{IdentityCache, ProfileUsage},
+    error::{Error, Result},
     t, tui,
 };
 
 /// Log in to `InferaDB` via OAuth.
-pub async fn login(ctx: &Context) -> Result<()> {
+///
+/// With `device_code`, the user approves the login on another device instead
+/// of in a local browser. With `client` (client ID and key path), the profile
+/// signs in as an org client; the key path is stored as an absolute path so
+/// later commands can renew the token from any directory.
+pub async fn login(ctx: &Context, device_code: bool, client: Option<(&str, &str)>) -> Result<()> {
     let profile_name = ctx.effective_profile_name().to_string();
+    let oauth = OAuthFlow::new()?;
+
+    let credentials = if let Some((client_id, client_key)) = client {
+        let client_key = std::fs::canonicalize(client_key)
+            .map_err(|e| Error::invalid_arg(format!("Could not read {client_key}: {e}")))?;
+        let client_key = client_key.to_string_lossy();
+        tui::spin(
+            t!("msg-logging-in-client", "client" => client_id, "profile" => &profile_name),
+            oauth.client_credentials(client_id, &client_key),
+        )
+        .await?
+    } else if device_code {
+        let device = oauth.start_device().await?;
+        ctx.output.info(&t!(
+            "msg-device-code-visit",
+            "url" => device.verification_uri(),
+            "code" => device.user_code()
+        ));
+        if let Some(url) = device.verification_uri_complete() {
+            ctx.output.info(&t!("msg-device-code-direct", "url" => url));
+        }
+        let minutes = (device.expires_in().as_secs() / 60).to_string();
+        ctx.output.info(&t!("msg-device-code-expires", "minutes" => &minutes));
 
-    // Authenticate with spinner
-    let credentials = tui::spin(t!("msg-logging-in", "profile" => &profile_name), async {
-        let oauth = OAuthFlow::new()?;
-        oauth.authenticate().await
-    })
-    .await?;
+        tui::spin(t!("msg-device-code-waiting"), oauth.complete_device(&device)).await?
+    } else {
+        // Authenticate with spinner
+        tui::spin(t!("msg-logging-in", "profile" => &profile_name), oauth.authenticate()).await?
+    };
 
     auth::store_credentials(&profile_name, &credentials)?;
 
@@ -26,7 +54,14 @@ pub async fn login(ctx: &Context) -> Result<()> {
 }
 
 /// Log out (remove stored credentials).
-pub async fn logout(ctx: &Context) -> Result<()> {
+///
+/// With `all_profiles`, logs out from every profile with stored credentials
+/// after a single confirmation.
+pub async fn logout(ctx: &Context, all_profiles: bool) -> Result<()> {
+    if all_profiles {
+        return logout_all(ctx);
+    }
+
     let profile_name = ctx.effective_profile_name().to_string();
 
     if !auth::has_credentials(&profile_name) {
@@ -34,16 +69,63 @@ pub async fn logout(ctx: &Context) -> Result<()> {
         return Ok(());
     }
 
-    if !ctx.yes && !ctx.confirm(&t!("msg-logging-out", "profile" => &profile_name))? {
+    if !ctx.confirm(&t!("msg-logging-out", "profile" => &profile_name))? {
         ctx.output.info(&t!("msg-cancelled"));
         return Ok(());
     }
 
-    auth::clear_credentials(&profile_name)?;
+    forget(&profile_name)?;
     ctx.output.success(&t!("msg-logout-success", "profile" => &profile_name));
     Ok(())
 }
 
+fn logout_all(ctx: &Context) -> Result<()> {
+    let mut names: Vec<&str> = ctx.config.profiles.keys().map(String::as_str).collect();
+    // Credentials can be stored under "default" without a profile of that name
+    if !ctx.config.profiles.contains_key("default") {
+        names.push("default");
+    }
+    names.retain(|name| auth::has_credentials(name));
+    names.sort_unstable();
+
+    if names.is_empty() {
+        ctx.output.info(&t!("msg-no-logged-in-profiles"));
+        return Ok(());
+    }
+
+    let count = names.len().to_string();
+    let profiles = names.join(", ");
+    if !ctx.confirm(&t!("msg-logging-out-all", "count" => &count, "profiles" => &profiles))? {
+        ctx.output.info(&t!("msg-cancelled"));
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for name in names {
+        match forget(name) {
+            Ok(()) => ctx.output.success(&t!("msg-logout-success", "profile" => name)),
+            Err(e) => {
+                failed += 1;
+                ctx.output
+                    .error(&t!("msg-logout-failed", "profile" => name, "reason" => &e.to_string()));
+            },
+        }
+    }
+    if failed > 0 {
+        return Err(Error::credential(format!("Could not log out from {failed} profile(s)")));
+    }
+    Ok(())
+}
+
+/// Remove a profile's credentials, with its cached identity and last use.
+fn forget(profile: &str) -> Result<()> {
+    auth::clear_credentials(profile)?;
+    // Both are only shown while signed in, so a failed cleanup is harmless
+    let _ = IdentityCache::new().delete(profile);
+    let _ = ProfileUsage::new().delete(profile);
+    Ok(())
+}
+
 /// Register a new account.
 pub async fn register(ctx: &Context, email: Option<&str>, name: Option<&str>) -> Result<()> {
     // If both args provided, use them directly
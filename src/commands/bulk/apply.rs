@@ -0,0 +1,259 @@
+//! Declarative relationship management.
+//!
+//! `apply -f state.yaml` treats a file as the desired set of relationships and
+//! reconciles the vault to match: missing relationships are written and, with
+//! `--prune`, relationships the file does not list are deleted. `selectors`
+//! limit the resource types the file owns, so several files (or teams) can
+//! manage one vault without pruning each other's relationships.
+//!
+//! ```yaml
+//! selectors: [document, folder]
+//! relationships:
+//!   - resource: document:readme
+//!     relation: viewer
+//!     subject: group:eng#member
+//! ```
+
+use std::{path::Path, time::Instant};
+
+use bon::builder;
+use futures::TryStreamExt;
+use inferadb::Relationship;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ExportPage, ExportedRelationship, SummaryRow, export_pages, reconcile::Reconciliation,
+};
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    input,
+    output::{OutputFormat, timings},
+    tui,
+};
+
+/// Relationships written per request when applying.
+const APPLY_BATCH_SIZE: usize = 500;
+
+/// A desired-state file.
+#[derive(Debug, Deserialize)]
+struct DesiredState {
+    /// Resource types the file owns; every type when empty.
+    #[serde(default)]
+    selectors: Vec<String>,
+    relationships: Vec<ExportedRelationship>,
+}
+
+impl DesiredState {
+    /// Whether `resource` falls under the selectors.
+    fn owns(&self, resource: &str) -> bool {
+        self.selectors.is_empty()
+            || resource
+                .split_once(':')
+                .is_some_and(|(ty, _)| self.selectors.iter().any(|s| s == ty))
+    }
+}
+
+/// Outcome counts for `apply`.
+#[derive(Debug, Default, Serialize)]
+struct ApplySummary {
+    added: u64,
+    deleted: u64,
+    /// Relationships not in the file, kept because `--prune` was not given.
+    kept: u64,
+    unchanged: u64,
+    failed: u64,
+}
+
+/// Reconcile the vault with the relationships in a desired-state file.
+///
+/// Relationships the file lists but the vault lacks are written. Relationships
+/// under the file's selectors that the file does not list are deleted with
+/// `prune`, and otherwise kept and counted. With `dry_run`, the changes are
+/// printed as a diff instead; `summary` shows only the counts.
+#[builder]
+pub async fn apply(
+    ctx: &Context,
+    file: &str,
+    #[builder(default)] prune: bool,
+    #[builder(default)] dry_run: bool,
+    #[builder(default)] summary: bool,
+    #[builder(default)] yes: bool,
+) -> Result<()> {
+    let content = input::read_to_string(file)
+        .map_err(|e| Error::invalid_arg(format!("Could not read {file}: {e}")))?;
+    let state = parse_state(Path::new(file), &content)?;
+
+    let client = ctx.client().await?;
+
+    // Only the relationships the file owns are compared
+    let started = Instant::now();
+    let shards: Vec<Option<String>> = if state.selectors.is_empty() {
+        vec![None]
+    } else {
+        state.selectors.iter().cloned().map(Some).collect()
+    };
+    let mut live = Vec::new();
+    for shard in shards {
+        let pages: Vec<ExportPage> = tui::spin(
+            "Fetching live relationships...",
+            export_pages(&client, shard, false).try_collect(),
+        )
+        .await?;
+        live.extend(pages.into_iter().flat_map(|page| page.relationships));
+    }
+    timings::record("Fetched live relationships", "OK", started.elapsed());
+
+    let mut reconciliation = Reconciliation::compute(&live, &state.relationships, true);
+    let kept = if prune { Vec::new() } else { std::mem::take(&mut reconciliation.would_remove) };
+
+    if dry_run {
+        ctx.output.warn("Dry run mode - no changes will be made.");
+        if ctx.output.format() != OutputFormat::Table {
+            return ctx.output.value(&reconciliation);
+        }
+        if !summary {
+            print!("{}", reconciliation.render(ctx.output.color));
+        }
+        ctx.output.table(&reconciliation.summary())?;
+        report_kept(ctx, kept.len());
+        return Ok(());
+    }
+
+    if reconciliation.would_add.is_empty() && reconciliation.would_remove.is_empty() {
+        ctx.output.success("Vault already matches the desired state.");
+        report_kept(ctx, kept.len());
+        return Ok(());
+    }
+
+    if !yes {
+        ctx.output.table(&reconciliation.summary())?;
+        let confirmed = ctx.confirm(&format!(
+            "Add {} and delete {} relationships?",
+            reconciliation.would_add.len(),
+            reconciliation.would_remove.len()
+        ))?;
+        if !confirmed {
+            ctx.output.info("Apply cancelled.");
+            return Ok(());
+        }
+    }
+
+    let rels = client.vault().relationships();
+    let mut outcome = ApplySummary {
+        kept: kept.len() as u64,
+        unchanged: reconciliation.unchanged as u64,
+        ..ApplySummary::default()
+    };
+
+    let started = Instant::now();
+    for batch in reconciliation.would_add.chunks(APPLY_BATCH_SIZE) {
+        let written = rels
+            .write_batch(
+                batch.iter().map(|r| Relationship::new(&r.resource, &r.relation, &r.subject)),
+            )
+            .await;
+        match written {
+            Ok(_) => outcome.added += batch.len() as u64,
+            Err(e) => {
+                outcome.failed += batch.len() as u64;
+                ctx.output.warn(&format!("Failed to write {} relationships: {e}", batch.len()));
+            },
+        }
+    }
+    for rel in &reconciliation.would_remove {
+        match rels.delete(Relationship::new(&rel.resource, &rel.relation, &rel.subject)).await {
+            Ok(()) => outcome.deleted += 1,
+            Err(e) => {
+                outcome.failed += 1;
+                if ctx.debug {
+                    ctx.output.warn(&format!(
+                        "Failed to delete {}#{}@{}: {e}",
+                        rel.resource, rel.relation, rel.subject
+                    ));
+                }
+            },
+        }
+    }
+    let status = if outcome.failed == 0 { "OK" } else { "FAILED" };
+    timings::record("Applied changes", status, started.elapsed());
+
+    if ctx.output.format() == OutputFormat::Table {
+        ctx.output.table(&[
+            SummaryRow { result: "added".to_string(), count: outcome.added },
+            SummaryRow { result: "deleted".to_string(), count: outcome.deleted },
+            SummaryRow { result: "unchanged".to_string(), count: outcome.unchanged },
+            SummaryRow { result: "failed".to_string(), count: outcome.failed },
+        ])?;
+        report_kept(ctx, kept.len());
+    } else {
+        ctx.output.value(&outcome)?;
+    }
+
+    if outcome.failed > 0 {
+        return Err(Error::other(format!("{} changes failed", outcome.failed)));
+    }
+    Ok(())
+}
+
+/// Point out relationships left in place because `--prune` was not given.
+fn report_kept(ctx: &Context, kept: usize) {
+    if kept > 0 {
+        ctx.output.info(&format!(
+            "{kept} relationships not in the file were kept; use --prune to delete them."
+        ));
+    }
+}
+
+/// Parse and validate a desired-state file, as JSON or YAML by extension.
+fn parse_state(path: &Path, content: &str) -> Result<DesiredState> {
+    let is_yaml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+    let state: DesiredState = if is_yaml {
+        serde_yaml::from_str(content)?
+    } else {
+        serde_json::from_str(content).or_else(|_| serde_yaml::from_str(content))?
+    };
+
+    for rel in &state.relationships {
+        if !rel.resource.contains(':') || !rel.subject.contains(':') || rel.relation.is_empty() {
+            return Err(Error::parse(format!(
+                "invalid relationship: {} {} {}",
+                rel.resource, rel.relation, rel.subject
+            )));
+        }
+        if !state.owns(&rel.resource) {
+            return Err(Error::invalid_arg(format!(
+                "{} is outside the file's selectors ({})",
+                rel.resource,
+                state.selectors.join(", ")
+            )));
+        }
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_state() {
+        let state = parse_state(
+            Path::new("state.yaml"),
+            "selectors: [document]\nrelationships:\n  - resource: document:1\n    relation: viewer\n    subject: user:anne\n",
+        )
+        .unwrap();
+        assert_eq!(state.selectors, ["document"]);
+        assert_eq!(state.relationships.len(), 1);
+
+        let err = parse_state(
+            Path::new("state.json"),
+            r#"{"selectors": ["folder"], "relationships": [{"resource": "document:1", "relation": "viewer", "subject": "user:anne"}]}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("outside the file's selectors"));
+    }
+}
@@ -0,0 +1,1261 @@
+//! Bulk export and import operations.
+
+mod apply;
+mod reconcile;
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use bon::builder;
+use futures::TryStreamExt;
+use inferadb::{ErrorKind, Relationship, VaultClient};
+use serde::{Deserialize, Serialize};
+use teapot::{Model, components::Progress, style::CLEAR_LINE};
+
+pub use self::apply::apply;
+use self::reconcile::Reconciliation;
+use super::{
+    identity,
+    interop::{self, Ecosystem},
+    relationships::{RelationshipMetadata, lookup_metadata, metadata_key},
+    schemas::ensure_signature_not_required,
+};
+use crate::{
+    client::{CliClient, Context},
+    error::{Error, Result},
+    input, ipl,
+    output::{
+        Displayable, glyphs,
+        parquet::{self, Column},
+        timings,
+    },
+    tui,
+};
+
+/// A relationship for export/import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ExportedRelationship {
+    pub(super) resource: String,
+    pub(super) relation: String,
+    pub(super) subject: String,
+    #[serde(flatten)]
+    metadata: RelationshipMetadata,
+}
+
+impl ExportedRelationship {
+    pub(super) fn new(resource: String, relation: String, subject: String) -> Self {
+        Self { resource, relation, subject, metadata: RelationshipMetadata::default() }
+    }
+}
+
+/// Version written in JSON and YAML exports.
+const EXPORT_VERSION: &str = "1.0";
+
+/// Export format wrapper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct ExportData {
+    version: String,
+    /// Active schema source at export time, for offline evaluation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) schema: Option<String>,
+    pub(super) relationships: Vec<ExportedRelationship>,
+}
+
+/// Relationships fetched per page when exporting.
+const EXPORT_PAGE_SIZE: usize = 1000;
+
+/// Export relationships to a file.
+///
+/// Every page of relationships is exported, each written out as it arrives,
+/// so memory stays flat for large vaults. Parquet writes each page as its own
+/// row group.
+///
+/// With `parallel` above 1 and no `resource_type`, the export is sharded by
+/// the resource types of the active schema, and up to `parallel` shards are
+/// paged at once with their pages merged as they arrive. Relationships on
+/// types the schema no longer defines are not reached by any shard.
+///
+/// With `with_metadata`, each relationship includes who wrote it, when, and
+/// from where, looked up in the organization audit log.
+#[builder]
+pub async fn export(
+    ctx: &Context,
+    output: Option<&str>,
+    resource_type: Option<&str>,
+    format: &str,
+    with_metadata: bool,
+    #[builder(default = 1)] parallel: usize,
+) -> Result<()> {
+    use futures::{StreamExt, stream};
+
+    if !matches!(format, "json" | "yaml" | "yml" | "csv" | "parquet" | "openfga" | "spicedb") {
+        ctx.output.error(&format!(
+            "Unknown format: {format}. Use json, yaml, csv, parquet, openfga, or spicedb."
+        ));
+        return Ok(());
+    }
+    if format == "parquet" && output.is_none() {
+        ctx.output.error("Parquet export is binary; use --output <file>.");
+        return Ok(());
+    }
+
+    let client = ctx.client().await?;
+    let vault = client.vault();
+
+    ctx.output.info("Exporting relationships...");
+
+    // Embed the active schema where the format can hold it, and shard by it
+    let schema =
+        if parallel > 1 || matches!(format, "json" | "yaml" | "yml" | "openfga" | "spicedb") {
+            vault.schemas().get_active().await.ok().map(|active| active.content)
+        } else {
+            None
+        };
+    let shards = export_shards(ctx, resource_type, schema.as_deref(), parallel);
+    let schema = schema.filter(|_| format != "csv" && format != "parquet");
+
+    // Progress against the last exact `stats` count, when writing to a file
+    let progress = output.is_some() && tui::is_interactive(ctx);
+    let estimate = if resource_type.is_none() {
+        identity::last_relationship_count(&format!("{}/{}", client.org_id(), client.vault_id()))
+    } else {
+        None
+    };
+
+    let started = Instant::now();
+    let mut pages = stream::iter(shards)
+        .map(|shard| Box::pin(export_pages(&client, shard, with_metadata)))
+        .flatten_unordered(parallel.max(1));
+
+    let mut fetch_time = Duration::ZERO;
+    let mut metadata_time = Duration::ZERO;
+    let mut write_time = Duration::ZERO;
+    let mut writer: Option<ExportWriter> = None;
+    let mut parquet_writer: Option<parquet::Writer<std::io::BufWriter<std::fs::File>>> = None;
+    let mut fetched = 0u64;
+    while let Some(page) = pages.next().await {
+        let page = page.inspect_err(|_| {
+            if progress {
+                eprint!("\r{CLEAR_LINE}");
+            }
+        })?;
+        fetch_time += page.fetch_time;
+        metadata_time += page.metadata_time;
+        fetched += page.relationships.len() as u64;
+
+        let write_started = Instant::now();
+        let parquet_path = output.filter(|_| format == "parquet");
+        if page.relationships.is_empty() {
+            // Nothing to write until the first non-empty page
+        } else if let Some(path) = parquet_path {
+            let writer = match &mut parquet_writer {
+                Some(writer) => writer,
+                None => parquet_writer.insert(parquet::Writer::new(std::io::BufWriter::new(
+                    std::fs::File::create(path)?,
+                ))?),
+            };
+            writer.write_row_group(&parquet_columns(&page.relationships, with_metadata))?;
+        } else {
+            let writer = match &mut writer {
+                Some(writer) => writer,
+                None => writer.insert(ExportWriter::open(
+                    ctx,
+                    output,
+                    format,
+                    schema.as_deref(),
+                    with_metadata,
+                )?),
+            };
+            writer.write(&page.relationships)?;
+        }
+        write_time += write_started.elapsed();
+
+        if progress {
+            render_export_progress(fetched, started.elapsed(), estimate);
+        }
+    }
+    if progress {
+        eprint!("\r{CLEAR_LINE}");
+    }
+
+    timings::record("Fetched relationships", "OK", fetch_time);
+    if with_metadata {
+        timings::record("Fetched audit metadata", "OK", metadata_time);
+    }
+
+    let write_started = Instant::now();
+    let count = match (writer, parquet_writer) {
+        (Some(writer), _) => writer.finish()?,
+        (None, Some(writer)) => {
+            let count = writer.num_rows();
+            writer.finish()?;
+            count
+        },
+        (None, None) => {
+            ctx.output.info("No relationships found to export.");
+            return Ok(());
+        },
+    };
+    timings::record("Wrote export", "OK", write_time + write_started.elapsed());
+
+    if let Some(path) = output {
+        ctx.output.success(&format!("Exported {count} relationships to {path}"));
+    }
+
+    Ok(())
+}
+
+/// The resource type filter for each shard of an export: one shard unless
+/// `parallel` asks for more and the active schema names the types to split by.
+fn export_shards(
+    ctx: &Context,
+    resource_type: Option<&str>,
+    schema: Option<&str>,
+    parallel: usize,
+) -> Vec<Option<String>> {
+    if parallel <= 1 || resource_type.is_some() {
+        return vec![resource_type.map(str::to_string)];
+    }
+    match schema.map(ipl::parse) {
+        Some(Ok(schema)) if !schema.entities.is_empty() => {
+            schema.entities.iter().map(|entity| Some(ipl::type_name(&entity.name))).collect()
+        },
+        _ => {
+            ctx.output.warn("No active schema to shard by; exporting sequentially.");
+            vec![None]
+        },
+    }
+}
+
+/// A page of exported relationships and how long it took to fetch.
+struct ExportPage {
+    relationships: Vec<ExportedRelationship>,
+    fetch_time: Duration,
+    metadata_time: Duration,
+}
+
+/// Page through the relationships on `resource_type`, or all of them.
+fn export_pages(
+    client: &CliClient,
+    resource_type: Option<String>,
+    with_metadata: bool,
+) -> impl futures::Stream<Item = Result<ExportPage>> + '_ {
+    // `None` once the last page has been fetched
+    let start: Option<Option<String>> = Some(None);
+    futures::stream::try_unfold(start, move |cursor| {
+        let resource_type = resource_type.clone();
+        async move {
+            let Some(cursor) = cursor else { return Ok(None) };
+
+            let started = Instant::now();
+            let vault = client.vault();
+            let rels = vault.relationships();
+            let mut req = rels.list().limit(EXPORT_PAGE_SIZE);
+            if let Some(rt) = &resource_type {
+                req = req.resource(format!("{rt}:*"));
+            }
+            if let Some(c) = &cursor {
+                req = req.cursor(c);
+            }
+            let page = req.await?;
+            let fetch_time = started.elapsed();
+
+            let mut relationships: Vec<ExportedRelationship> = page
+                .iter()
+                .map(|rel| {
+                    ExportedRelationship::new(
+                        rel.resource().to_string(),
+                        rel.relation().to_string(),
+                        rel.subject().to_string(),
+                    )
+                })
+                .collect();
+
+            let started = Instant::now();
+            if with_metadata {
+                let metadata = lookup_metadata(
+                    client,
+                    relationships.iter().map(|r| r.resource.as_str()).collect(),
+                )
+                .await?;
+                for rel in &mut relationships {
+                    if let Some(found) =
+                        metadata.get(&metadata_key(&rel.resource, &rel.relation, &rel.subject))
+                    {
+                        rel.metadata = found.clone();
+                    }
+                }
+            }
+            let metadata_time = started.elapsed();
+
+            let next = page.next_cursor.map(Some);
+            Ok(Some((ExportPage { relationships, fetch_time, metadata_time }, next)))
+        }
+    })
+}
+
+/// Writes an export incrementally, one page of relationships at a time.
+struct ExportWriter {
+    sink: Box<dyn std::io::Write>,
+    format: String,
+    with_metadata: bool,
+    written: usize,
+}
+
+impl ExportWriter {
+    /// Open the file or stdout and write everything before the first
+    /// relationship: the JSON or YAML envelope and schema, or the CSV header.
+    fn open(
+        ctx: &Context,
+        output: Option<&str>,
+        format: &str,
+        schema: Option<&str>,
+        with_metadata: bool,
+    ) -> Result<Self> {
+        let mut sink: Box<dyn std::io::Write> = match output {
+            Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+            None => Box::new(std::io::BufWriter::new(std::io::stdout())),
+        };
+        match format {
+            "json" => {
+                writeln!(sink, "{{\n  \"version\": \"{EXPORT_VERSION}\",")?;
+                if let Some(schema) = schema {
+                    writeln!(sink, "  \"schema\": {},", serde_json::to_string(schema)?)?;
+                }
+                writeln!(sink, "  \"relationships\": [")?;
+            },
+            "yaml" | "yml" => {
+                let header = ExportData {
+                    version: EXPORT_VERSION.to_string(),
+                    schema: schema.map(str::to_string),
+                    relationships: Vec::new(),
+                };
+                let yaml = serde_yaml::to_string(&header)?;
+                // The relationships follow as a block sequence
+                let yaml = yaml.trim_end().trim_end_matches("relationships: []");
+                writeln!(sink, "{yaml}relationships:")?;
+            },
+            "csv" => {
+                write!(sink, "resource,relation,subject")?;
+                write!(
+                    sink,
+                    "{}",
+                    if with_metadata { ",created_at,created_by,source\n" } else { "\n" }
+                )?;
+            },
+            _ => {
+                let ecosystem =
+                    if format == "openfga" { Ecosystem::OpenFga } else { Ecosystem::SpiceDb };
+                let schema = match schema.map(ipl::parse) {
+                    Some(Ok(schema)) => Some(schema),
+                    Some(Err(e)) => {
+                        ctx.output.warn(&format!("Active schema not converted: {e}"));
+                        None
+                    },
+                    None => {
+                        ctx.output.warn("No active schema; exporting relationships only.");
+                        None
+                    },
+                };
+                let header = interop::export_header(ecosystem, schema.as_ref())?;
+                for note in &header.notes {
+                    ctx.output.warn(note);
+                }
+                sink.write_all(header.content.as_bytes())?;
+            },
+        }
+        Ok(Self { sink, format: format.to_string(), with_metadata, written: 0 })
+    }
+
+    fn write(&mut self, relationships: &[ExportedRelationship]) -> Result<()> {
+        match self.format.as_str() {
+            "json" => {
+                for rel in relationships {
+                    if self.written > 0 {
+                        self.sink.write_all(b",\n")?;
+                    }
+                    let json = serde_json::to_string_pretty(rel)?;
+                    write!(self.sink, "    {}", json.replace('\n', "\n    "))?;
+                    self.written += 1;
+                }
+                return Ok(());
+            },
+            "yaml" | "yml" => {
+                self.sink.write_all(serde_yaml::to_string(relationships)?.as_bytes())?;
+            },
+            "csv" => {
+                for rel in relationships {
+                    write!(self.sink, "{},{},{}", rel.resource, rel.relation, rel.subject)?;
+                    if self.with_metadata {
+                        let m = &rel.metadata;
+                        write!(
+                            self.sink,
+                            ",{},{},{}",
+                            m.created_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                            m.created_by.as_deref().unwrap_or_default(),
+                            m.source.as_deref().unwrap_or_default()
+                        )?;
+                    }
+                    self.sink.write_all(b"\n")?;
+                }
+            },
+            format => {
+                let ecosystem =
+                    if format == "openfga" { Ecosystem::OpenFga } else { Ecosystem::SpiceDb };
+                let rows = interop::export_rows(ecosystem, relationships)?;
+                self.sink.write_all(rows.as_bytes())?;
+            },
+        }
+        self.written += relationships.len();
+        Ok(())
+    }
+
+    /// Close the JSON envelope and flush, returning how many relationships
+    /// were written.
+    fn finish(mut self) -> Result<usize> {
+        if self.format == "json" {
+            self.sink.write_all(b"\n  ]\n}\n")?;
+        }
+        self.sink.flush()?;
+        Ok(self.written)
+    }
+}
+
+/// Parquet columns for a page of relationships.
+fn parquet_columns(rels: &[ExportedRelationship], with_metadata: bool) -> Vec<Column> {
+    let mut columns = vec![
+        Column::utf8("resource", rels.iter().map(|r| r.resource.clone()).collect()),
+        Column::utf8("relation", rels.iter().map(|r| r.relation.clone()).collect()),
+        Column::utf8("subject", rels.iter().map(|r| r.subject.clone()).collect()),
+    ];
+    if with_metadata {
+        columns.extend([
+            Column::utf8(
+                "created_at",
+                rels.iter()
+                    .map(|r| r.metadata.created_at.map(|t| t.to_rfc3339()).unwrap_or_default())
+                    .collect(),
+            ),
+            Column::utf8(
+                "created_by",
+                rels.iter().map(|r| r.metadata.created_by.clone().unwrap_or_default()).collect(),
+            ),
+            Column::utf8(
+                "source",
+                rels.iter().map(|r| r.metadata.source.clone().unwrap_or_default()).collect(),
+            ),
+        ]);
+    }
+    columns
+}
+
+/// Parse a JSON or YAML export, detecting the format from the extension or
+/// content.
+pub(super) fn parse_export(path: &Path, content: &str) -> Result<ExportData> {
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+    {
+        return Ok(serde_yaml::from_str(content)?);
+    }
+    // Try JSON first, then YAML
+    match serde_json::from_str(content) {
+        Ok(data) => Ok(data),
+        Err(_) => Ok(serde_yaml::from_str(content)?),
+    }
+}
+
+/// A transform applied to rows before import.
+///
+/// Rows are piped through an external filter as NDJSON, one
+/// `{resource, relation, subject}` object per line, and the filter's NDJSON
+/// output is imported instead. Filters may rewrite, drop, or add rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transform {
+    /// A jq expression, run with `jq -c`.
+    Jq(String),
+    /// A WASI module, run with `wasmtime`, reading stdin and writing stdout.
+    Wasm(String),
+}
+
+impl Transform {
+    /// Program and arguments that run the transform.
+    fn command(&self) -> (&'static str, Vec<&str>) {
+        match self {
+            Self::Jq(expr) => ("jq", vec!["-c", expr.as_str()]),
+            Self::Wasm(module) => ("wasmtime", vec!["run", module.as_str()]),
+        }
+    }
+}
+
+/// Pipe rows through a transform and parse what it prints.
+fn apply_transform(
+    rows: &[ExportedRelationship],
+    transform: &Transform,
+) -> Result<Vec<ExportedRelationship>> {
+    let (program, args) = transform.command();
+    run_filter(program, &args, rows)
+}
+
+fn run_filter(
+    program: &str,
+    args: &[&str],
+    rows: &[ExportedRelationship],
+) -> Result<Vec<ExportedRelationship>> {
+    use std::{
+        io::Write as _,
+        process::{Command, Stdio},
+    };
+
+    let mut input = String::new();
+    for row in rows {
+        input.push_str(&serde_json::to_string(row)?);
+        input.push('\n');
+    }
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => Error::other(format!(
+                "'{program}' is not installed; it is needed for this transform"
+            )),
+            _ => Error::other(format!("Could not run '{program}': {e}")),
+        })?;
+
+    // Write on another thread so a filter that prints as it reads cannot block
+    let mut stdin =
+        child.stdin.take().ok_or_else(|| Error::other("transform stdin unavailable"))?;
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output()?;
+    // A filter may exit without reading everything (e.g., `jq -n`); that is not an error
+    let _ = writer.join();
+
+    if !output.status.success() {
+        return Err(Error::other(format!(
+            "Transform failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut transformed = Vec::new();
+    for (index, line) in stdout.lines().enumerate() {
+        let row = parse_ndjson_line(line)
+            .map_err(|e| Error::parse(format!("Transform output line {}: {e}", index + 1)))?;
+        transformed.extend(row);
+    }
+    Ok(transformed)
+}
+
+/// Identifier remapping loaded from an `old_id,new_id` CSV file.
+///
+/// Keys may be full object references (`user:123`) or bare IDs (`123`); a full
+/// reference wins when both match. The relation of a userset subject
+/// (`group:eng#member`) and wildcards (`user:*`) are left as they are.
+#[derive(Debug, Default)]
+pub(super) struct IdMap {
+    ids: HashMap<String, String>,
+}
+
+impl IdMap {
+    /// Load a map file.
+    pub(super) fn load(path: &str) -> Result<Self> {
+        let content = input::read_to_string(path)
+            .map_err(|e| Error::invalid_arg(format!("Could not read ID map {path}: {e}")))?;
+        Self::parse(&content).map_err(|e| Error::parse(format!("{path}: {e}")))
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let mut ids = HashMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((old, new)) = line.split_once(',') else {
+                return Err(Error::parse(format!("line {}: expected old_id,new_id", index + 1)));
+            };
+            let (old, new) = (old.trim(), new.trim());
+            if index == 0 && old.eq_ignore_ascii_case("old_id") {
+                continue;
+            }
+            if old.is_empty() || new.is_empty() {
+                return Err(Error::parse(format!("line {}: empty ID", index + 1)));
+            }
+            if ids.insert(old.to_string(), new.to_string()).is_some() {
+                return Err(Error::parse(format!("line {}: '{old}' is mapped twice", index + 1)));
+            }
+        }
+        Ok(Self { ids })
+    }
+
+    /// Map an object reference, returning `None` when nothing matches.
+    fn map_object(&self, object: &str) -> Option<String> {
+        let (object, relation) = match object.split_once('#') {
+            Some((object, relation)) => (object, Some(relation)),
+            None => (object, None),
+        };
+        let mapped = self.ids.get(object).cloned().or_else(|| {
+            let (type_name, id) = object.split_once(':')?;
+            if id == "*" {
+                return None;
+            }
+            let new = self.ids.get(id)?;
+            Some(format!("{type_name}:{new}"))
+        })?;
+        Some(match relation {
+            Some(relation) => format!("{mapped}#{relation}"),
+            None => mapped,
+        })
+    }
+
+    /// Remap the subject and resource of every row, returning how many rows
+    /// changed.
+    fn apply(&self, rows: &mut [ExportedRelationship]) -> usize {
+        let mut changed = 0;
+        for row in rows {
+            let resource = self.map_object(&row.resource);
+            let subject = self.map_object(&row.subject);
+            if resource.is_some() || subject.is_some() {
+                changed += 1;
+            }
+            if let Some(resource) = resource {
+                row.resource = resource;
+            }
+            if let Some(subject) = subject {
+                row.subject = subject;
+            }
+        }
+        changed
+    }
+}
+
+/// Import relationships from a file.
+///
+/// With `id_map`, subjects and resources are remapped first; with
+/// `transform`, the rows are then piped through a jq expression or WASM
+/// module.
+///
+/// With `format` set to `openfga` or `spicedb`, the file is read as an
+/// `OpenFGA` store or zed validation file and any model or schema in it is
+/// converted to IPL and activated before the relationships are written.
+///
+/// With `dry_run`, nothing is written: the file is validated and diffed
+/// against the vault's relationships, showing what the import would add,
+/// remove (in `replace` mode), and leave unchanged. `summary` shows only the
+/// counts.
+#[builder]
+pub async fn import(
+    ctx: &Context,
+    file: &str,
+    format: Option<&str>,
+    #[builder(default)] yes: bool,
+    #[builder(default)] dry_run: bool,
+    #[builder(default)] summary: bool,
+    mode: &str,
+    id_map: Option<&str>,
+    transform: Option<&Transform>,
+) -> Result<()> {
+    let client = ctx.client().await?;
+    let vault = client.vault();
+    let rels = vault.relationships();
+
+    // Read and parse the file
+    let path = Path::new(file);
+    if !input::is_local(file) {
+        ctx.output.error(&format!("File not found: {file}"));
+        return Ok(());
+    }
+
+    let started = Instant::now();
+    let content = input::read_to_string(file)?;
+
+    // Use the given format, or detect it from extension or content
+    let mut schema = None;
+    let is_csv = format == Some("csv")
+        || format.is_none() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let mut relationships = match format.and_then(Ecosystem::from_format) {
+        Some(ecosystem) => {
+            let imported = interop::import(ecosystem, &content)?;
+            for note in &imported.notes {
+                ctx.output.warn(note);
+            }
+            schema = imported.schema;
+            imported.relationships
+        },
+        None if is_csv => parse_csv(&content)?,
+        None => parse_export(path, &content)?.relationships,
+    };
+
+    timings::record("Parsed input file", "OK", started.elapsed());
+
+    if let Some(id_map) = id_map {
+        let map = IdMap::load(id_map)?;
+        let changed = map.apply(&mut relationships);
+        ctx.output.info(&format!(
+            "Remapped IDs in {changed} of {} rows using {id_map}.",
+            relationships.len()
+        ));
+    }
+
+    let relationships = match transform {
+        Some(transform) => {
+            let started = Instant::now();
+            let count = relationships.len();
+            let transformed = apply_transform(&relationships, transform)?;
+            timings::record("Transformed rows", "OK", started.elapsed());
+            ctx.output.info(&format!(
+                "Transform mapped {count} rows to {} relationships.",
+                transformed.len()
+            ));
+            transformed
+        },
+        None => relationships,
+    };
+
+    if relationships.is_empty() {
+        ctx.output.info("No relationships to import.");
+        return Ok(());
+    }
+
+    ctx.output.info(&format!("Found {} relationships to import.", relationships.len()));
+    ctx.output.info(&format!("Mode: {mode}"));
+
+    if dry_run {
+        ctx.output.warn("Dry run mode - no changes will be made.");
+
+        // Validate relationships
+        let started = Instant::now();
+        let mut valid = 0;
+        let mut invalid = 0;
+
+        for rel in &relationships {
+            if rel.resource.contains(':') && rel.subject.contains(':') && !rel.relation.is_empty() {
+                valid += 1;
+            } else {
+                invalid += 1;
+                ctx.output.warn(&format!(
+                    "Invalid relationship: {} {} {}",
+                    rel.resource, rel.relation, rel.subject
+                ));
+            }
+        }
+
+        timings::record("Validated relationships", "OK", started.elapsed());
+        ctx.output.info(&format!("Valid: {valid}, Invalid: {invalid}"));
+        if let Some(schema) = &schema {
+            ctx.output.info("Converted schema:");
+            println!("{schema}");
+        }
+
+        // Diff against the relationships in the vault
+        let started = Instant::now();
+        let pages: Vec<ExportPage> = tui::spin(
+            "Fetching live relationships...",
+            export_pages(&client, None, false).try_collect(),
+        )
+        .await?;
+        let live: Vec<ExportedRelationship> =
+            pages.into_iter().flat_map(|page| page.relationships).collect();
+        timings::record("Fetched live relationships", "OK", started.elapsed());
+
+        let reconciliation = Reconciliation::compute(&live, &relationships, mode == "replace");
+        if ctx.output.format() != crate::output::OutputFormat::Table {
+            return ctx.output.value(&reconciliation);
+        }
+        if !summary {
+            print!("{}", reconciliation.render(ctx.output.color));
+        }
+        ctx.output.table(&reconciliation.summary())?;
+        return Ok(());
+    }
+
+    if schema.is_some() {
+        ensure_signature_not_required(ctx)?;
+    }
+
+    // Confirm import
+    if !yes {
+        let action =
+            if schema.is_some() { "Activate the converted schema and import" } else { "Import" };
+        let confirmed = ctx.confirm(&format!(
+            "{action} {} relationships in {} mode?",
+            relationships.len(),
+            mode
+        ))?;
+        if !confirmed {
+            ctx.output.info("Import cancelled.");
+            return Ok(());
+        }
+    }
+
+    if let Some(source) = &schema {
+        let started = Instant::now();
+        let schemas = vault.schemas();
+        let result = tui::spin("Pushing converted schema...", schemas.push(source)).await?;
+        if !result.validation.is_valid() {
+            ctx.output.error("Converted schema validation failed:");
+            for err in &result.validation.errors {
+                eprintln!("  Line {}: {} [{}]", err.line, err.message, err.code);
+            }
+            return Err(Error::parse("Converted schema validation failed"));
+        }
+        let version = &result.schema.version;
+        tui::spin("Activating schema...", schemas.activate(version)).await?;
+        timings::record("Activated converted schema", "OK", started.elapsed());
+        ctx.output.success(&format!("Schema version {version} is now active."));
+    }
+
+    // Perform import based on mode
+    match mode {
+        "merge" | "upsert" => {
+            // Write relationships (upsert is the default behavior)
+            let started = Instant::now();
+            let mut success = 0;
+            let mut failed = 0;
+
+            for rel in &relationships {
+                let relationship =
+                    inferadb::Relationship::new(&rel.resource, &rel.relation, &rel.subject);
+
+                match rels.write(relationship).await {
+                    Ok(_) => success += 1,
+                    Err(e) => {
+                        failed += 1;
+                        if ctx.debug {
+                            ctx.output.warn(&format!(
+                                "Failed to write {} {} {}: {}",
+                                rel.resource, rel.relation, rel.subject, e
+                            ));
+                        }
+                    },
+                }
+            }
+
+            let status = if failed == 0 { "OK" } else { "FAILED" };
+            timings::record("Wrote relationships", status, started.elapsed());
+            ctx.output.success(&format!("Imported {success} relationships ({failed} failed)."));
+        },
+        "replace" => {
+            ctx.output.warn("Replace mode will delete all existing relationships first.");
+
+            if !yes {
+                let confirmed =
+                    ctx.confirm("Are you sure you want to delete all existing relationships?")?;
+                if !confirmed {
+                    ctx.output.info("Import cancelled.");
+                    return Ok(());
+                }
+            }
+
+            // Delete all existing relationships using delete_where
+            ctx.output.info("Deleting existing relationships...");
+
+            // Use the delete_where builder to delete all relationships
+            // For now, we'll list and delete individually since delete_where might not delete
+            // everything
+            let started = Instant::now();
+            let mut deleted = 0;
+            loop {
+                let page = rels.list().limit(100).await?;
+                if page.relationships.is_empty() {
+                    break;
+                }
+
+                for rel in page.iter() {
+                    let relationship =
+                        inferadb::Relationship::new(rel.resource(), rel.relation(), rel.subject());
+                    if rels.delete(relationship).await.is_ok() {
+                        deleted += 1;
+                    }
+                }
+            }
+
+            timings::record("Deleted existing relationships", "OK", started.elapsed());
+            ctx.output.info(&format!("Deleted {deleted} relationships."));
+
+            // Now write the new relationships
+            let started = Instant::now();
+            let mut success = 0;
+            for rel in &relationships {
+                let relationship =
+                    inferadb::Relationship::new(&rel.resource, &rel.relation, &rel.subject);
+                if rels.write(relationship).await.is_ok() {
+                    success += 1;
+                }
+            }
+
+            timings::record("Wrote relationships", "OK", started.elapsed());
+            ctx.output.success(&format!("Imported {success} relationships."));
+        },
+        _ => {
+            ctx.output.error(&format!("Unknown mode: {mode}. Use merge, upsert, or replace."));
+        },
+    }
+
+    Ok(())
+}
+
+/// Failures listed individually after a `bulk-add` run.
+const MAX_LISTED_FAILURES: usize = 20;
+
+/// Outcome counts for `relationships bulk-add`.
+#[derive(Debug, Default, Serialize)]
+struct BulkAddSummary {
+    created: u64,
+    skipped: u64,
+    failed: u64,
+    failures: Vec<BulkAddFailure>,
+}
+
+/// An input line that was not written.
+#[derive(Debug, Serialize)]
+struct BulkAddFailure {
+    line: usize,
+    error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SummaryRow {
+    result: String,
+    count: u64,
+}
+
+impl Displayable for SummaryRow {
+    fn table_row(&self) -> Vec<String> {
+        vec![self.result.clone(), self.count.to_string()]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["RESULT", "COUNT"]
+    }
+}
+
+impl BulkAddSummary {
+    fn fail(&mut self, line: usize, error: impl Into<String>) {
+        self.failed += 1;
+        self.failures.push(BulkAddFailure { line, error: error.into() });
+    }
+}
+
+/// Add relationships streamed from an NDJSON file, or stdin when `file` is
+/// omitted or `-`.
+///
+/// Each line is an object with `resource`, `relation` and `subject`. Lines are
+/// written `batch_size` at a time; batches that fail with a transient error
+/// are retried up to `retries` times with backoff. A batch rejected outright
+/// is retried one relationship at a time, so a single bad row only fails
+/// itself, and rows that already exist are counted as skipped.
+pub async fn bulk_add(
+    ctx: &Context,
+    file: Option<&str>,
+    batch_size: usize,
+    retries: u32,
+) -> Result<()> {
+    let client = ctx.client().await?;
+    let vault = client.vault();
+
+    let path = file.unwrap_or(input::STDIN);
+    let reader =
+        input::open(path).map_err(|e| Error::invalid_arg(format!("Cannot open {path}: {e}")))?;
+    let total_bytes =
+        if path == input::STDIN { None } else { std::fs::metadata(path).ok().map(|m| m.len()) };
+
+    let started = Instant::now();
+    let interactive = tui::is_interactive(ctx);
+    let mut summary = BulkAddSummary::default();
+    let mut batch: Vec<(usize, ExportedRelationship)> = Vec::with_capacity(batch_size);
+    let mut bytes_read = 0u64;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line?;
+        bytes_read += line.len() as u64 + 1;
+
+        match parse_ndjson_line(&line) {
+            Ok(Some(rel)) => batch.push((line_number, rel)),
+            Ok(None) => {},
+            Err(e) => summary.fail(line_number, e.to_string()),
+        }
+
+        if batch.len() >= batch_size {
+            write_batch(&vault, &batch, retries, &mut summary).await;
+            batch.clear();
+            if interactive {
+                render_progress(&summary, bytes_read, total_bytes);
+            }
+        }
+    }
+    if !batch.is_empty() {
+        write_batch(&vault, &batch, retries, &mut summary).await;
+    }
+    if interactive {
+        eprint!("\r{CLEAR_LINE}");
+    }
+
+    let status = if summary.failed == 0 { "OK" } else { "FAILED" };
+    timings::record("Wrote relationships", status, started.elapsed());
+
+    if ctx.output.format() == crate::output::OutputFormat::Table {
+        ctx.output.table(&[
+            SummaryRow { result: "created".to_string(), count: summary.created },
+            SummaryRow { result: "skipped".to_string(), count: summary.skipped },
+            SummaryRow { result: "failed".to_string(), count: summary.failed },
+        ])?;
+        for failure in summary.failures.iter().take(MAX_LISTED_FAILURES) {
+            ctx.output.warn(&format!("Line {}: {}", failure.line, failure.error));
+        }
+        if summary.failures.len() > MAX_LISTED_FAILURES {
+            ctx.output.warn(&format!(
+                "... and {} more failures",
+                summary.failures.len() - MAX_LISTED_FAILURES
+            ));
+        }
+    } else {
+        ctx.output.value(&summary)?;
+    }
+
+    if summary.failed > 0 {
+        return Err(Error::other(format!("{} relationships failed", summary.failed)));
+    }
+    Ok(())
+}
+
+/// Parse one NDJSON line, returning `None` for blank lines.
+fn parse_ndjson_line(line: &str) -> Result<Option<ExportedRelationship>> {
+    if line.trim().is_empty() {
+        return Ok(None);
+    }
+    let rel: ExportedRelationship = serde_json::from_str(line)
+        .map_err(|e| Error::parse(format!("expected {{resource, relation, subject}}: {e}")))?;
+    if !rel.resource.contains(':') || !rel.subject.contains(':') || rel.relation.is_empty() {
+        return Err(Error::parse(format!(
+            "invalid relationship: {} {} {}",
+            rel.subject, rel.relation, rel.resource
+        )));
+    }
+    Ok(Some(rel))
+}
+
+/// Write a batch, isolating failures row by row if the server rejects it.
+async fn write_batch(
+    vault: &VaultClient,
+    batch: &[(usize, ExportedRelationship)],
+    retries: u32,
+    summary: &mut BulkAddSummary,
+) {
+    let result = with_retries(retries, || {
+        vault.relationships().write_batch(
+            batch.iter().map(|(_, r)| Relationship::new(&r.resource, &r.relation, &r.subject)),
+        )
+    })
+    .await;
+
+    match result {
+        Ok(()) => summary.created += batch.len() as u64,
+        // The service is unreachable; writing rows one by one will not help
+        Err(e) if e.is_retriable() => {
+            for (line, _) in batch {
+                summary.fail(*line, e.to_string());
+            }
+        },
+        Err(_) => {
+            for (line, r) in batch {
+                let result = with_retries(retries, || {
+                    vault.relationships().write(Relationship::new(
+                        &r.resource,
+                        &r.relation,
+                        &r.subject,
+                    ))
+                })
+                .await;
+                match result {
+                    Ok(()) => summary.created += 1,
+                    Err(e) if e.kind() == ErrorKind::Conflict => summary.skipped += 1,
+                    Err(e) => summary.fail(*line, e.to_string()),
+                }
+            }
+        },
+    }
+}
+
+/// Run a request, retrying the failures `--retry-on` selects with exponential
+/// backoff.
+pub(super) async fn with_retries<F, R, T>(
+    retries: u32,
+    request: F,
+) -> std::result::Result<(), inferadb::Error>
+where
+    F: Fn() -> R,
+    R: IntoFuture<Output = std::result::Result<T, inferadb::Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Ok(_) => return Ok(()),
+            Err(e) if attempt < retries && crate::client::retry::policy().should_retry(&e) => {
+                let delay = e.retry_after().unwrap_or_else(|| backoff(attempt));
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            },
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Delay before retry number `attempt + 1`: 500ms doubling up to 8s.
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_millis(500 * 2u64.pow(attempt.min(4)))
+}
+
+/// Redraw the progress line: a bar when the input size is known, otherwise
+/// a running count.
+fn render_progress(summary: &BulkAddSummary, bytes_read: u64, total_bytes: Option<u64>) {
+    let message = format!("{} written, {} failed", summary.created, summary.failed);
+    let line = match total_bytes {
+        Some(total) => {
+            let bar = if glyphs::is_ascii() { Progress::ascii() } else { Progress::new() };
+            bar.total(total.max(1)).current(bytes_read.min(total)).message(message).view()
+        },
+        None => message,
+    };
+    eprint!("\r{CLEAR_LINE}{line}");
+}
+
+/// Redraw the export progress line: relationships so far and their rate, with
+/// a bar and ETA when an estimated total is known.
+#[allow(clippy::cast_precision_loss)] // Counts stay far below 2^52; the rate is approximate
+fn render_export_progress(exported: u64, elapsed: Duration, estimate: Option<u64>) {
+    let rate = exported as f64 / elapsed.as_secs_f64().max(0.001);
+    let mut message = format!("{exported} exported, {rate:.0}/s");
+    let line = match estimate.filter(|total| *total >= exported && rate > 0.0) {
+        Some(total) => {
+            let remaining = Duration::from_secs_f64((total - exported) as f64 / rate);
+            message.push_str(&format!(", ETA {}", timings::format_duration(remaining)));
+            let bar = if glyphs::is_ascii() { Progress::ascii() } else { Progress::new() };
+            bar.total(total.max(1)).current(exported).message(message).view()
+        },
+        None => message,
+    };
+    eprint!("\r{CLEAR_LINE}{line}");
+}
+
+fn parse_csv(content: &str) -> Result<Vec<ExportedRelationship>> {
+    let mut relationships = Vec::new();
+    let mut lines = content.lines();
+
+    // Skip header
+    if let Some(header) = lines.next() {
+        // Verify it's a valid header
+        if header.split(',').count() < 3 {
+            return Err(crate::error::Error::config(
+                "Invalid CSV format: expected resource,relation,subject",
+            ));
+        }
+    }
+
+    for line in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut parts = line.split(',');
+        let Some(resource) = parts.next() else { continue };
+        let Some(relation) = parts.next() else { continue };
+        let Some(subject) = parts.next() else { continue };
+
+        relationships.push(ExportedRelationship {
+            resource: resource.trim().to_string(),
+            relation: relation.trim().to_string(),
+            subject: subject.trim().to_string(),
+            metadata: RelationshipMetadata::default(),
+        });
+    }
+
+    Ok(relationships)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ndjson_line() {
+        let rel =
+            parse_ndjson_line(r#"{"resource":"doc:1","relation":"viewer","subject":"user:alice"}"#)
+                .unwrap()
+                .unwrap();
+        assert_eq!(rel.resource, "doc:1");
+        assert_eq!(rel.relation, "viewer");
+        assert_eq!(rel.subject, "user:alice");
+
+        assert!(parse_ndjson_line("   ").unwrap().is_none());
+        assert!(parse_ndjson_line("not json").is_err());
+        assert!(parse_ndjson_line(r#"{"resource":"doc:1","relation":"viewer"}"#).is_err());
+        assert!(
+            parse_ndjson_line(r#"{"resource":"doc","relation":"viewer","subject":"user:a"}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0), Duration::from_millis(500));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(10), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_id_map() {
+        let map =
+            IdMap::parse("old_id,new_id\nuser:42,user:alice\n7,bob\nfolder:stg,folder:prod\n")
+                .unwrap();
+        assert_eq!(map.map_object("user:42").as_deref(), Some("user:alice"));
+        assert_eq!(map.map_object("user:7").as_deref(), Some("user:bob"));
+        assert_eq!(map.map_object("folder:stg#viewer").as_deref(), Some("folder:prod#viewer"));
+        assert_eq!(map.map_object("user:*"), None);
+        assert_eq!(map.map_object("user:8"), None);
+
+        let mut rows = vec![
+            parse_ndjson_line(r#"{"resource":"doc:1","relation":"viewer","subject":"user:7"}"#)
+                .unwrap()
+                .unwrap(),
+            parse_ndjson_line(r#"{"resource":"doc:2","relation":"viewer","subject":"user:9"}"#)
+                .unwrap()
+                .unwrap(),
+        ];
+        assert_eq!(map.apply(&mut rows), 1);
+        assert_eq!(rows[0].subject, "user:bob");
+
+        assert!(IdMap::parse("a,b\na,c\n").is_err());
+        assert!(IdMap::parse("no-comma\n").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_filter() {
+        let rows = vec![
+            parse_ndjson_line(r#"{"resource":"doc:1","relation":"viewer","subject":"user:a"}"#)
+                .unwrap()
+                .unwrap(),
+        ];
+        let out = run_filter("cat", &[], &rows).unwrap();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].subject, "user:a");
+
+        // Filters that print nothing drop every row
+        assert!(run_filter("true", &[], &rows).unwrap().is_empty());
+        assert!(run_filter("false", &[], &rows).is_err());
+        assert!(run_filter("inferadb-missing-filter", &[], &rows).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! Reconciliation of an import against live relationships.
+//!
+//! `import --dry-run` compares the relationships in the file with those in the
+//! vault and sorts them into what the import would add, what it would remove
+//! (only `replace` mode removes anything), and what is already present.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::{ExportedRelationship, SummaryRow};
+use crate::output::diff;
+
+/// What an import would change, relationship by relationship.
+#[derive(Debug, Default, Serialize)]
+pub(super) struct Reconciliation {
+    pub(super) would_add: Vec<ExportedRelationship>,
+    pub(super) would_remove: Vec<ExportedRelationship>,
+    pub(super) unchanged: usize,
+}
+
+/// Identity of a relationship, ignoring metadata.
+type Key<'a> = (&'a str, &'a str, &'a str);
+
+fn key(rel: &ExportedRelationship) -> Key<'_> {
+    (&rel.resource, &rel.relation, &rel.subject)
+}
+
+impl Reconciliation {
+    /// Compare `incoming` with `live`. Relationships repeated in the file
+    /// count once; with `replace`, live relationships missing from the file
+    /// would be removed.
+    pub(super) fn compute(
+        live: &[ExportedRelationship],
+        incoming: &[ExportedRelationship],
+        replace: bool,
+    ) -> Self {
+        let live_keys: HashSet<Key<'_>> = live.iter().map(key).collect();
+        let mut seen = HashSet::new();
+        let mut reconciliation = Self::default();
+        for rel in incoming {
+            if !seen.insert(key(rel)) {
+                continue;
+            }
+            if live_keys.contains(&key(rel)) {
+                reconciliation.unchanged += 1;
+            } else {
+                reconciliation.would_add.push(rel.clone());
+            }
+        }
+        if replace {
+            reconciliation.would_remove =
+                live.iter().filter(|rel| !seen.contains(&key(rel))).cloned().collect();
+        }
+        reconciliation
+    }
+
+    /// Removals then additions as `-`/`+` lines in `resource#relation@subject`
+    /// form.
+    pub(super) fn render(&self, color: bool) -> String {
+        let mut out = String::new();
+        for (marker, style, rels) in
+            [("-", diff::RED, &self.would_remove), ("+", diff::GREEN, &self.would_add)]
+        {
+            for rel in rels {
+                let line = format!("{marker} {}#{}@{}", rel.resource, rel.relation, rel.subject);
+                out.push_str(&diff::paint(&line, style, color));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Counts per change, for the summary table.
+    pub(super) fn summary(&self) -> Vec<SummaryRow> {
+        [
+            ("would add", self.would_add.len()),
+            ("would remove", self.would_remove.len()),
+            ("unchanged", self.unchanged),
+        ]
+        .into_iter()
+        .map(|(result, count)| SummaryRow { result: result.to_string(), count: count as u64 })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn rel(resource: &str, subject: &str) -> ExportedRelationship {
+        ExportedRelationship::new(resource.into(), "viewer".into(), subject.into())
+    }
+
+    #[test]
+    fn test_compute_merge_and_replace() {
+        let live = [rel("doc:1", "user:anne"), rel("doc:2", "user:bob")];
+        let incoming =
+            [rel("doc:1", "user:anne"), rel("doc:3", "user:cy"), rel("doc:3", "user:cy")];
+
+        let merge = Reconciliation::compute(&live, &incoming, false);
+        assert_eq!(merge.unchanged, 1);
+        assert_eq!(merge.would_add.len(), 1);
+        assert!(merge.would_remove.is_empty());
+
+        let replace = Reconciliation::compute(&live, &incoming, true);
+        assert_eq!(replace.would_remove.len(), 1);
+        assert_eq!(replace.would_remove[0].resource, "doc:2");
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let live = [rel("doc:2", "user:bob")];
+        let incoming = [rel("doc:1", "user:anne")];
+        let reconciliation = Reconciliation::compute(&live, &incoming, true);
+        assert_eq!(
+            reconciliation.render(false),
+            "- doc:2#viewer@user:bob\n+ doc:1#viewer@user:anne\n"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Response cache commands: status, clear.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    client::Context,
+    config::ResponseCache,
+    error::Result,
+    output::{Displayable, dates},
+};
+
+#[derive(Debug, Clone, Serialize)]
+struct CacheRow {
+    key: String,
+    fetched_at: DateTime<Utc>,
+    size: String,
+    fresh: String,
+}
+
+impl Displayable for CacheRow {
+    fn table_row(&self) -> Vec<String> {
+        vec![
+            self.key.clone(),
+            dates::format_precise(self.fetched_at),
+            self.size.clone(),
+            self.fresh.clone(),
+        ]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["KEY", "FETCHED", "SIZE", "FRESH"]
+    }
+}
+
+/// The current profile's cache, whether or not this invocation uses it.
+pub(super) fn profile_cache(ctx: &Context) -> ResponseCache {
+    let ttl = i64::try_from(ctx.config.cache.ttl_seconds).unwrap_or(i64::MAX);
+    ResponseCache::new(ctx.effective_profile_name(), chrono::Duration::seconds(ttl))
+}
+
+/// Show cached responses for the current profile.
+pub async fn status(ctx: &Context) -> Result<()> {
+    let cache = profile_cache(ctx);
+    let profile = ctx.effective_profile_name();
+
+    ctx.output.info(&format!(
+        "Response cache for '{profile}': {} (fresh for {}s)",
+        if ctx.config.cache.enabled { "enabled" } else { "opt-in with --cached" },
+        ctx.config.cache.ttl_seconds
+    ));
+
+    let entries = cache.entries()?;
+    if entries.is_empty() {
+        ctx.output.info("No cached responses.");
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let rows: Vec<CacheRow> = entries
+        .into_iter()
+        .map(|entry| CacheRow {
+            fresh: if now - entry.fetched_at < cache.ttl() { "yes" } else { "no" }.to_string(),
+            key: entry.key,
+            fetched_at: entry.fetched_at,
+            size: format!("{} B", entry.bytes),
+        })
+        .collect();
+
+    ctx.output.table(&rows)
+}
+
+/// Clear cached responses for the current profile, or every profile.
+pub async fn clear(ctx: &Context, all: bool) -> Result<()> {
+    if all {
+        let count = ResponseCache::clear_all()?;
+        ctx.output.success(&format!("Cleared the response cache for {count} profile(s)."));
+    } else {
+        profile_cache(ctx).clear()?;
+        ctx.output.success(&format!(
+            "Cleared the response cache for '{}'.",
+            ctx.effective_profile_name()
+        ));
+    }
+    Ok(())
+}
@@ -1,14 +1,25 @@
 //! Authorization check commands.
 
 use bon::builder;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     client::Context,
+    config::{ImpersonationLog, ImpersonationRecord},
     error::{Error, Result},
+    input, ipl,
+    output::{
+        Displayable, ReportTarget,
+        breakdown::{self, Phase},
+        glyphs, report,
+    },
+    tui,
 };
 
 /// Check authorization.
+///
+/// With `watch`, keeps running and re-evaluates the check whenever a relevant
+/// relationship changes, printing a line each time the decision flips.
 #[builder]
 pub async fn check(
     ctx: &Context,
@@ -17,6 +28,9 @@ pub async fn check(
     resource: &str,
     #[builder(default)] trace: bool,
     #[builder(default)] explain: bool,
+    #[builder(default)] explain_denied: bool,
+    #[builder(default)] impersonate: bool,
+    #[builder(default)] watch: bool,
     context_json: Option<&str>,
 ) -> Result<()> {
     #[derive(Serialize)]
@@ -27,43 +41,57 @@ pub async fn check(
         allowed: bool,
         #[serde(skip_serializing_if = "Option::is_none")]
         reason: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        denial: Option<DenialAnalysis>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        impersonated_by: Option<String>,
     }
 
+    let impersonating = is_impersonation(&ctx.profile, subject, impersonate)?;
+
     let client = ctx.client().await?;
     let vault = client.vault();
 
-    // Build check request
-    let mut check_req = vault.check(subject, permission, resource);
+    let impersonated_by = if impersonating {
+        let Some(account) = super::identity::signed_in_identity(ctx).await else {
+            return Err(Error::credential(format!(
+                "Cannot check as {subject}: the signed-in account is unknown, so the impersonation can't be recorded. Run 'inferadb whoami' while online, then retry."
+            )));
+        };
+        ctx.output.warn(&format!("Impersonating {subject} (signed in as {})", account.email));
+        ImpersonationLog::new().append(&ImpersonationRecord {
+            at: chrono::Utc::now(),
+            profile: ctx.effective_profile_name().to_string(),
+            account_id: account.id,
+            account_email: account.email.clone(),
+            vault: client.vault_id().to_string(),
+            subject: subject.to_string(),
+            permission: permission.to_string(),
+            resource: resource.to_string(),
+        })?;
+        Some(account.email)
+    } else {
+        None
+    };
 
-    // Add ABAC context if provided
-    if let Some(json) = context_json {
-        let context_map: std::collections::HashMap<String, serde_json::Value> =
-            serde_json::from_str(json)
-                .map_err(|e| Error::parse(format!("Invalid context JSON: {e}")))?;
-
-        let mut ctx_builder = inferadb::Context::new();
-        for (key, value) in context_map {
-            let context_value = match value {
-                serde_json::Value::String(s) => inferadb::ContextValue::String(s),
-                serde_json::Value::Number(n) => {
-                    if let Some(i) = n.as_i64() {
-                        inferadb::ContextValue::Integer(i)
-                    } else if let Some(f) = n.as_f64() {
-                        inferadb::ContextValue::Float(f)
-                    } else {
-                        continue;
-                    }
-                },
-                serde_json::Value::Bool(b) => inferadb::ContextValue::Bool(b),
-                _ => continue,
-            };
-            ctx_builder = ctx_builder.with(key, context_value);
-        }
-        check_req = check_req.with_context(ctx_builder);
+    let abac_context = context_json.map(parse_context).transpose()?;
+    if watch {
+        return watch_check(ctx, &vault, subject, permission, resource, abac_context).await;
+    }
+
+    let mut check_req = vault.check(subject, permission, resource);
+    if let Some(abac) = &abac_context {
+        check_req = check_req.with_context(abac.clone());
     }
 
     // Execute check
-    let allowed = check_req.await?;
+    let allowed = breakdown::timed(Phase::Api, "check", check_req).await?;
+
+    let denial = if !allowed && explain_denied {
+        Some(analyze_denial(ctx, &vault, subject, permission, resource, abac_context).await?)
+    } else {
+        None
+    };
 
     let result = CheckResult {
         subject: subject.to_string(),
@@ -71,15 +99,25 @@ pub async fn check(
         resource: resource.to_string(),
         allowed,
         reason: None,
+        denial,
+        impersonated_by,
     };
 
-    if ctx.output.format() == crate::output::OutputFormat::Table {
+    if ctx.output.format() == crate::output::OutputFormat::Table && ctx.output.is_quiet() {
+        // Quiet mode prints only the decision; the exit code still reflects it
+        println!("{}", if allowed { "allowed" } else { "denied" });
+        if !allowed {
+            return Err(Error::AccessDenied);
+        }
+    } else if ctx.output.format() == crate::output::OutputFormat::Table {
         if allowed {
             ctx.output.success(&format!("{subject} {permission} {resource} → allowed"));
         } else {
             ctx.output.error(&format!("{subject} {permission} {resource} → denied"));
 
-            if explain {
+            if let Some(denial) = &result.denial {
+                print_denial(ctx, denial);
+            } else if explain {
                 ctx.output.info("");
                 ctx.output.info("To see why access was denied, use 'inferadb explain-permission'");
             }
@@ -103,7 +141,556 @@ pub async fn check(
     Ok(())
 }
 
+/// A change in a watched check's decision.
+#[derive(Debug, Clone, Serialize)]
+struct DecisionChange {
+    timestamp: String,
+    allowed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    change: Option<String>,
+}
+
+/// Re-run a check whenever a relationship that could affect it changes.
+///
+/// Relevance comes from the active schema: changes to objects of the
+/// resource's type, or of any type reachable through its relations, trigger
+/// a re-check. Without a schema every change does. Only decision flips are
+/// printed, after an initial line with the starting decision.
+async fn watch_check(
+    ctx: &Context,
+    vault: &inferadb::VaultClient,
+    subject: &str,
+    permission: &str,
+    resource: &str,
+    abac_context: Option<inferadb::Context>,
+) -> Result<()> {
+    use futures::StreamExt;
+
+    let evaluate = || {
+        let mut check_req = vault.check(subject, permission, resource);
+        if let Some(abac) = &abac_context {
+            check_req = check_req.with_context(abac.clone());
+        }
+        check_req
+    };
+    let print = |allowed: bool, change: Option<String>| -> Result<()> {
+        let timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        if ctx.output.format() != crate::output::OutputFormat::Table {
+            return ctx.output.value(&DecisionChange { timestamp, allowed, change });
+        }
+        let decision = if allowed { "allowed" } else { "denied" };
+        match change {
+            Some(change) => println!("[{timestamp}] {decision} (after {change})"),
+            None => println!("[{timestamp}] {decision}"),
+        }
+        Ok(())
+    };
+
+    let relevant: Option<Vec<String>> = active_schema(ctx, vault).await.map(|schema| {
+        schema.reachable_entities(object_type(resource)).iter().map(|e| e.name.clone()).collect()
+    });
+
+    // Subscribe before the first check so no change slips in between
+    let mut stream = vault.watch().run().await?;
+
+    ctx.output.info(&format!(
+        "Watching {subject} {permission} {resource} for decision changes... (Ctrl+C to stop)"
+    ));
+    let mut allowed = evaluate().await?;
+    print(allowed, None)?;
+
+    while let Some(event) = stream.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                ctx.output.warn(&format!("Stream error: {e}"));
+                continue;
+            },
+        };
+        let changed_type = object_type(event.relationship.resource());
+        if relevant
+            .as_ref()
+            .is_some_and(|names| !names.iter().any(|name| ipl::type_matches(name, changed_type)))
+        {
+            continue;
+        }
+
+        match evaluate().await {
+            Ok(now) if now != allowed => {
+                allowed = now;
+                let op = if event.operation.is_create() { "+" } else { "-" };
+                print(
+                    allowed,
+                    Some(format!(
+                        "{op} {} -[{}]-> {}",
+                        event.relationship.subject(),
+                        event.relationship.relation(),
+                        event.relationship.resource()
+                    )),
+                )?;
+            },
+            Ok(_) => {},
+            Err(e) => ctx.output.warn(&format!("Check failed: {e}")),
+        }
+    }
+
+    ctx.output.info("Stream ended.");
+    Ok(())
+}
+
+/// One entry in a `check batch` file.
+#[derive(Debug, Clone, Deserialize)]
+struct BatchCheck {
+    subject: String,
+    permission: String,
+    resource: String,
+    expected: Expected,
+}
+
+/// Expected decision: a boolean or `allow`/`allowed`/`deny`/`denied`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Expected {
+    Bool(bool),
+    Word(String),
+}
+
+impl Expected {
+    fn allowed(&self) -> Result<bool> {
+        match self {
+            Self::Bool(allowed) => Ok(*allowed),
+            Self::Word(word) => match word.to_lowercase().as_str() {
+                "allow" | "allowed" => Ok(true),
+                "deny" | "denied" => Ok(false),
+                _ => Err(Error::parse(format!(
+                    "Invalid expected result '{word}': use allow, deny, true, or false"
+                ))),
+            },
+        }
+    }
+}
+
+/// A batch file: a list of checks, or the list under a `checks` key.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BatchFile {
+    List(Vec<BatchCheck>),
+    Wrapped { checks: Vec<BatchCheck> },
+}
+
+fn parse_batch(content: &str) -> Result<Vec<BatchCheck>> {
+    let file: BatchFile = serde_yaml::from_str(content)
+        .map_err(|e| Error::parse(format!("Invalid checks file: {e}")))?;
+    Ok(match file {
+        BatchFile::List(checks) | BatchFile::Wrapped { checks } => checks,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BatchRow {
+    subject: String,
+    permission: String,
+    resource: String,
+    expected: &'static str,
+    actual: String,
+    result: &'static str,
+}
+
+impl Displayable for BatchRow {
+    fn table_row(&self) -> Vec<String> {
+        vec![
+            self.subject.clone(),
+            self.permission.clone(),
+            self.resource.clone(),
+            self.expected.to_string(),
+            self.actual.clone(),
+            self.result.to_string(),
+        ]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["SUBJECT", "PERMISSION", "RESOURCE", "EXPECTED", "ACTUAL", "RESULT"]
+    }
+}
+
+const fn decision(allowed: bool) -> &'static str {
+    if allowed { "allowed" } else { "denied" }
+}
+
+/// Run the checks in a file, `concurrency` at a time, and compare each with
+/// its expected decision.
+///
+/// Prints a pass/fail table in file order, writes the results to each of
+/// `reports`, and fails if any check mismatched or errored.
+pub async fn batch(
+    ctx: &Context,
+    file: &str,
+    concurrency: usize,
+    reports: &[ReportTarget],
+) -> Result<()> {
+    use futures::{StreamExt, stream};
+
+    let content = input::read_to_string(file)
+        .map_err(|e| Error::invalid_arg(format!("Could not read {file}: {e}")))?;
+    let checks = parse_batch(&content)?;
+    if checks.is_empty() {
+        ctx.output.info("No checks to run.");
+        return Ok(());
+    }
+    let expected = checks.iter().map(|c| c.expected.allowed()).collect::<Result<Vec<_>>>()?;
+
+    let client = ctx.client().await?;
+    let vault = client.vault();
+
+    let outcomes: Vec<std::result::Result<bool, inferadb::Error>> = tui::spin(
+        format!("Running {} checks...", checks.len()),
+        stream::iter(&checks)
+            .map(|c| {
+                vault
+                    .check(c.subject.as_str(), c.permission.as_str(), c.resource.as_str())
+                    .into_future()
+            })
+            .buffered(concurrency)
+            .collect(),
+    )
+    .await;
+
+    let mut failed = 0;
+    let mut cases = Vec::new();
+    let rows: Vec<BatchRow> = checks
+        .iter()
+        .zip(expected)
+        .zip(outcomes)
+        .map(|((check, expected), outcome)| {
+            let (actual, result, case) = match outcome {
+                Ok(allowed) if allowed == expected => {
+                    (decision(allowed).to_string(), "PASS", report::Outcome::Passed)
+                },
+                Ok(allowed) => (
+                    decision(allowed).to_string(),
+                    "FAIL",
+                    report::Outcome::Failed(format!(
+                        "expected {}, got {}",
+                        decision(expected),
+                        decision(allowed)
+                    )),
+                ),
+                Err(e) => (format!("error: {e}"), "ERROR", report::Outcome::Error(e.to_string())),
+            };
+            if result != "PASS" {
+                failed += 1;
+            }
+            cases.push(report::Case {
+                name: format!("check {}", cases.len() + 1),
+                detail: format!("{} {} {}", check.subject, check.permission, check.resource),
+                outcome: case,
+                line: report::line_of(
+                    &content,
+                    &[&check.subject, &check.permission, &check.resource],
+                ),
+            });
+            BatchRow {
+                subject: check.subject.clone(),
+                permission: check.permission.clone(),
+                resource: check.resource.clone(),
+                expected: decision(expected),
+                actual,
+                result,
+            }
+        })
+        .collect();
+
+    ctx.output.table(&rows)?;
+    report::write(
+        reports,
+        &report::Suite { file: file.to_string(), cases, properties: Vec::new() },
+    )?;
+
+    let total = rows.len();
+    if failed > 0 {
+        return Err(Error::other(format!("{failed} of {total} checks did not match")));
+    }
+    ctx.output.success(&format!("All {total} checks passed."));
+    Ok(())
+}
+
+/// Whether a check as `subject` impersonates someone other than the
+/// profile's principal.
+///
+/// Without a principal, only `--impersonate` marks a check as impersonating.
+/// Profiles with `require_impersonate` reject impersonation unless
+/// `--impersonate` is given.
+fn is_impersonation(
+    profile: &crate::config::Profile,
+    subject: &str,
+    impersonate: bool,
+) -> Result<bool> {
+    let Some(principal) = profile.principal.as_deref() else {
+        if profile.require_impersonate && !impersonate {
+            return Err(Error::config(
+                "Profile requires --impersonate but has no principal. Set one with 'inferadb profiles update <name> --principal <subject>'",
+            ));
+        }
+        return Ok(impersonate);
+    };
+
+    if principal == subject {
+        return Ok(false);
+    }
+    if profile.require_impersonate && !impersonate {
+        return Err(Error::invalid_arg(format!(
+            "Checking as {subject}, not the authenticated principal {principal}. Pass --impersonate to confirm."
+        )));
+    }
+    Ok(true)
+}
+
+/// Parse ABAC context JSON into an SDK context, skipping unsupported values.
+pub fn parse_context(json: &str) -> Result<inferadb::Context> {
+    let context_map: std::collections::HashMap<String, serde_json::Value> =
+        serde_json::from_str(json)
+            .map_err(|e| Error::parse(format!("Invalid context JSON: {e}")))?;
+
+    let mut ctx_builder = inferadb::Context::new();
+    for (key, value) in context_map {
+        let context_value = match value {
+            serde_json::Value::String(s) => inferadb::ContextValue::String(s),
+            serde_json::Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    inferadb::ContextValue::Integer(i)
+                } else if let Some(f) = n.as_f64() {
+                    inferadb::ContextValue::Float(f)
+                } else {
+                    continue;
+                }
+            },
+            serde_json::Value::Bool(b) => inferadb::ContextValue::Bool(b),
+            _ => continue,
+        };
+        ctx_builder = ctx_builder.with(key, context_value);
+    }
+    Ok(ctx_builder)
+}
+
+/// Why a check was denied and what would have granted it.
+#[derive(Debug, Clone, Default, Serialize)]
+struct DenialAnalysis {
+    reasons: Vec<String>,
+    failed_caveats: Vec<String>,
+    excluded_by: Vec<String>,
+    near_misses: Vec<NearMiss>,
+    suggestions: Vec<String>,
+}
+
+/// A relation that would have granted access, with the step to get there.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct NearMiss {
+    relation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    via: Option<String>,
+    remediation: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+}
+
+/// Search for the nearest-miss paths of a denied check.
+///
+/// Combines the server's explanation (denial reasons, failed caveats) with
+/// the active schema, whose grants for the permission become remediation
+/// steps. Relations on the subtracted side of an exclusion are checked to
+/// find the ones actively revoking access.
+async fn analyze_denial(
+    ctx: &Context,
+    vault: &inferadb::VaultClient,
+    subject: &str,
+    permission: &str,
+    resource: &str,
+    abac_context: Option<inferadb::Context>,
+) -> Result<DenialAnalysis> {
+    let mut analysis = DenialAnalysis::default();
+
+    let mut explain_req =
+        vault.explain_permission().subject(subject).permission(permission).resource(resource);
+    if let Some(abac) = abac_context {
+        explain_req = explain_req.with_context(abac);
+    }
+    match explain_req.await {
+        Ok(explanation) => {
+            for reason in &explanation.denial_reasons {
+                if let inferadb::vault::DenialReason::ConditionFailed { condition, reason } = reason
+                {
+                    analysis.failed_caveats.push(reason.as_ref().map_or_else(
+                        || condition.clone(),
+                        |reason| format!("{condition}: {reason}"),
+                    ));
+                } else {
+                    analysis.reasons.push(reason.to_string());
+                }
+            }
+            analysis.suggestions =
+                explanation.suggestions.iter().map(ToString::to_string).collect();
+        },
+        Err(e) => ctx.output.warn(&format!("Could not fetch denial explanation: {e}")),
+    }
+
+    let resource_type = object_type(resource);
+    let schema = active_schema(ctx, vault).await;
+    let Some(entity) = schema.as_ref().and_then(|s| s.entity_for_type(resource_type)) else {
+        ctx.output.warn(&format!("No schema entity found for '{resource_type}'."));
+        return Ok(analysis);
+    };
+
+    let grants = entity.grants(permission);
+    for grant in grants.iter().filter(|g| g.excluded && g.via.is_none()) {
+        if vault.check(subject, grant.relation.as_str(), resource).await.unwrap_or(false) {
+            analysis.excluded_by.push(grant.relation.clone());
+        }
+    }
+    analysis.near_misses = near_misses(entity, &grants, subject, resource);
+
+    Ok(analysis)
+}
+
+/// Fetch and parse the active schema, warning instead of failing.
+async fn active_schema(ctx: &Context, vault: &inferadb::VaultClient) -> Option<ipl::Schema> {
+    let active = match vault.schemas().get_active().await {
+        Ok(active) => active,
+        Err(e) => {
+            ctx.output.warn(&format!("Could not fetch active schema: {e}"));
+            return None;
+        },
+    };
+    match ipl::parse(&active.content) {
+        Ok(schema) => Some(schema),
+        Err(e) => {
+            ctx.output.warn(&format!("Could not parse active schema: {e}"));
+            None
+        },
+    }
+}
+
+/// Type of an object or subject reference (`user` in `user:alice` or
+/// `group` in `group:eng#member`).
+pub(super) fn object_type(reference: &str) -> &str {
+    reference.split([':', '#']).next().unwrap_or_default()
+}
+
+/// Turn an entity's grants for a permission into remediation steps.
+fn near_misses(
+    entity: &ipl::Entity,
+    grants: &[ipl::Grant],
+    subject: &str,
+    resource: &str,
+) -> Vec<NearMiss> {
+    let subject_type = object_type(subject);
+
+    grants
+        .iter()
+        .filter(|g| !g.excluded)
+        .filter_map(|grant| {
+            let relation = entity.relation(&grant.relation)?;
+            let mut qualifiers = Vec::new();
+            if grant.conditional {
+                qualifiers.push("the rest of the intersection must also hold".to_string());
+            }
+
+            let (remediation, command) = if let Some(via) = &grant.via {
+                let targets: Vec<&str> =
+                    relation.subjects.iter().map(|s| s.entity.as_str()).collect();
+                (
+                    format!(
+                        "Grant {subject} '{via}' on the {} ({}) of {resource}",
+                        grant.relation,
+                        targets.join(" | ")
+                    ),
+                    Some(format!(
+                        "inferadb relationships list --resource {resource} --relation {}",
+                        grant.relation
+                    )),
+                )
+            } else if let Some(direct) = relation
+                .subjects
+                .iter()
+                .find(|s| s.relation.is_none() && ipl::type_matches(&s.entity, subject_type))
+            {
+                if let Some(caveat) = &direct.caveat {
+                    qualifiers.push(format!("caveat '{caveat}' must hold"));
+                }
+                (
+                    format!("Add {subject} as {} of {resource}", grant.relation),
+                    Some(format!(
+                        "inferadb relationships add {subject} {} {resource}",
+                        grant.relation
+                    )),
+                )
+            } else {
+                let usersets: Vec<String> = relation
+                    .subjects
+                    .iter()
+                    .filter_map(|s| s.relation.as_ref().map(|r| format!("{}#{r}", s.entity)))
+                    .collect();
+                if usersets.is_empty() {
+                    return None;
+                }
+                (
+                    format!(
+                        "Add {subject} to a {} that is {} of {resource}",
+                        usersets.join(" or "),
+                        grant.relation
+                    ),
+                    None,
+                )
+            };
+
+            let remediation = if qualifiers.is_empty() {
+                remediation
+            } else {
+                format!("{remediation} ({})", qualifiers.join("; "))
+            };
+            Some(NearMiss {
+                relation: grant.relation.clone(),
+                via: grant.via.clone(),
+                remediation,
+                command,
+            })
+        })
+        .collect()
+}
+
+/// Print a denial analysis as remediation steps.
+fn print_denial(ctx: &Context, denial: &DenialAnalysis) {
+    let section = |title: &str, items: &[String]| {
+        if !items.is_empty() {
+            ctx.output.info("");
+            ctx.output.info(title);
+            for item in items {
+                ctx.output.info(&format!("  - {item}"));
+            }
+        }
+    };
+
+    section("Why denied:", &denial.reasons);
+    section("Failed caveats:", &denial.failed_caveats);
+    section("Excluded by relation:", &denial.excluded_by);
+
+    if !denial.near_misses.is_empty() {
+        ctx.output.info("");
+        ctx.output.info("Nearest misses:");
+        for miss in &denial.near_misses {
+            ctx.output.info(&format!("  - {}", miss.remediation));
+            if let Some(command) = &miss.command {
+                ctx.output.info(&format!("      $ {command}"));
+            }
+        }
+    }
+
+    section("Server suggestions:", &denial.suggestions);
+}
+
 /// Simulate authorization with hypothetical changes.
+///
+/// With `suggest`, a still-denied result is followed by the smallest sets of
+/// relationship additions that would flip it to allowed.
 #[builder]
 pub async fn simulate(
     ctx: &Context,
@@ -112,34 +699,246 @@ pub async fn simulate(
     resource: &str,
     #[builder(default)] add_relationships: &[String],
     #[builder(default)] remove_relationships: &[String],
+    #[builder(default)] suggest: bool,
 ) -> Result<()> {
     let client = ctx.client().await?;
     let vault = client.vault();
 
+    let additions =
+        add_relationships.iter().map(|r| parse_relationship(r)).collect::<Result<Vec<_>>>()?;
+    let removals =
+        remove_relationships.iter().map(|r| parse_relationship(r)).collect::<Result<Vec<_>>>()?;
+
+    // Execute simulated check
+    let allowed =
+        simulate_check(&vault, &additions, &removals, &[], subject, permission, resource).await?;
+
+    if allowed {
+        ctx.output.success(&format!("With changes: {subject} {permission} {resource} → allowed"));
+        return Ok(());
+    }
+    ctx.output.error(&format!("With changes: {subject} {permission} {resource} → denied"));
+
+    if suggest {
+        let Some(schema) = active_schema(ctx, &vault).await else {
+            return Ok(());
+        };
+        let candidates = tui::spin(
+            "Searching for suggestions...",
+            suggestion_candidates(&vault, &schema, subject, permission, resource),
+        )
+        .await;
+
+        let mut found = Vec::new();
+        for size in 1..=SUGGEST_MAX_SET_SIZE {
+            for set in combinations(&candidates, size) {
+                let extra = set.iter().map(Candidate::relationship).collect::<Result<Vec<_>>>()?;
+                if simulate_check(
+                    &vault, &additions, &removals, &extra, subject, permission, resource,
+                )
+                .await?
+                {
+                    found.push(set);
+                }
+            }
+            if !found.is_empty() {
+                break;
+            }
+        }
+
+        ctx.output.info("");
+        if found.is_empty() {
+            ctx.output.info(&format!(
+                "No change of up to {SUGGEST_MAX_SET_SIZE} relationships found among {} candidates.",
+                candidates.len()
+            ));
+        } else {
+            ctx.output.info("Any one of these would grant access:");
+            for set in &found {
+                ctx.output.info("");
+                for candidate in set {
+                    ctx.output.info(&format!("  $ {}", candidate.command()));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Maximum traversal depth when following relations for suggestions.
+const SUGGEST_MAX_DEPTH: usize = 2;
+
+/// Maximum number of candidate additions considered for suggestions.
+const SUGGEST_MAX_CANDIDATES: usize = 12;
+
+/// Largest set of additions tried together for suggestions.
+const SUGGEST_MAX_SET_SIZE: usize = 2;
+
+/// Run one simulated check with extra hypothetical additions.
+async fn simulate_check(
+    vault: &inferadb::VaultClient,
+    additions: &[inferadb::Relationship<'static>],
+    removals: &[inferadb::Relationship<'static>],
+    extra: &[inferadb::Relationship<'static>],
+    subject: &str,
+    permission: &str,
+    resource: &str,
+) -> Result<bool> {
     let mut sim = vault.simulate();
+    for rel in additions.iter().chain(extra) {
+        sim = sim.add_relationship(rel.clone());
+    }
+    for rel in removals {
+        sim = sim.remove_relationship(rel.clone());
+    }
+    Ok(sim.check(subject, permission, resource).await?.allowed)
+}
+
+/// A relationship that could be added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Candidate {
+    resource: String,
+    relation: String,
+    subject: String,
+}
 
-    // Add hypothetical relationships
-    for rel_str in add_relationships {
-        let rel = parse_relationship(rel_str)?;
-        sim = sim.add_relationship(rel);
+impl Candidate {
+    fn relationship(&self) -> Result<inferadb::Relationship<'static>> {
+        parse_relationship(&format!("{}#{}@{}", self.resource, self.relation, self.subject))
     }
 
-    // Remove hypothetical relationships
-    for rel_str in remove_relationships {
-        let rel = parse_relationship(rel_str)?;
-        sim = sim.remove_relationship(rel);
+    fn command(&self) -> String {
+        format!("inferadb relationships add {} {} {}", self.subject, self.relation, self.resource)
     }
+}
 
-    // Execute simulated check
-    let result = sim.check(subject, permission, resource).await?;
+/// A step in the suggestion search for one object and permission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SuggestStep {
+    /// Adding this relationship may grant access.
+    Add(Candidate),
+    /// Objects related through `relation` may grant access via `permission`.
+    Follow { relation: String, permission: String, object_type: Option<String> },
+}
 
-    if result.allowed {
-        ctx.output.success(&format!("With changes: {subject} {permission} {resource} → allowed"));
-    } else {
-        ctx.output.error(&format!("With changes: {subject} {permission} {resource} → denied"));
+/// Search steps for granting `subject` a permission on `resource`.
+fn suggest_steps(
+    entity: &ipl::Entity,
+    grants: &[ipl::Grant],
+    subject: &str,
+    resource: &str,
+) -> Vec<SuggestStep> {
+    let subject_type = object_type(subject);
+    let mut steps = Vec::new();
+
+    for grant in grants.iter().filter(|g| !g.excluded) {
+        let Some(relation) = entity.relation(&grant.relation) else {
+            continue;
+        };
+        if let Some(via) = &grant.via {
+            steps.push(SuggestStep::Follow {
+                relation: grant.relation.clone(),
+                permission: via.clone(),
+                object_type: None,
+            });
+            continue;
+        }
+        for allowed in &relation.subjects {
+            match &allowed.relation {
+                None if ipl::type_matches(&allowed.entity, subject_type) => {
+                    steps.push(SuggestStep::Add(Candidate {
+                        resource: resource.to_string(),
+                        relation: grant.relation.clone(),
+                        subject: subject.to_string(),
+                    }));
+                },
+                None => {},
+                Some(userset) => steps.push(SuggestStep::Follow {
+                    relation: grant.relation.clone(),
+                    permission: userset.clone(),
+                    object_type: Some(allowed.entity.clone()),
+                }),
+            }
+        }
     }
+    steps.dedup();
+    steps
+}
 
-    Ok(())
+/// Collect candidate additions breadth-first, following related objects
+/// found through expand up to [`SUGGEST_MAX_DEPTH`].
+async fn suggestion_candidates(
+    vault: &inferadb::VaultClient,
+    schema: &ipl::Schema,
+    subject: &str,
+    permission: &str,
+    resource: &str,
+) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    let mut visited = Vec::new();
+    let mut queue =
+        std::collections::VecDeque::from([(resource.to_string(), permission.to_string(), 0)]);
+
+    while let Some((object, permission, depth)) = queue.pop_front() {
+        if visited.contains(&(object.clone(), permission.clone())) {
+            continue;
+        }
+        visited.push((object.clone(), permission.clone()));
+
+        let Some(entity) = schema.entity_for_type(object_type(&object)) else {
+            continue;
+        };
+        for step in suggest_steps(entity, &entity.grants(&permission), subject, &object) {
+            match step {
+                SuggestStep::Add(candidate) => {
+                    if !candidates.contains(&candidate) {
+                        candidates.push(candidate);
+                    }
+                    if candidates.len() >= SUGGEST_MAX_CANDIDATES {
+                        return candidates;
+                    }
+                },
+                SuggestStep::Follow { relation, permission, object_type: wanted }
+                    if depth < SUGGEST_MAX_DEPTH =>
+                {
+                    let related: Vec<String> = vault
+                        .subjects()
+                        .with_permission(relation.as_str())
+                        .on_resource(object.as_str())
+                        .collect()
+                        .await
+                        .unwrap_or_default();
+                    for related in related {
+                        let related = related.split('#').next().unwrap_or_default().to_string();
+                        if wanted
+                            .as_deref()
+                            .is_none_or(|t| ipl::type_matches(t, object_type(&related)))
+                        {
+                            queue.push_back((related, permission.clone(), depth + 1));
+                        }
+                    }
+                },
+                SuggestStep::Follow { .. } => {},
+            }
+        }
+    }
+    candidates
+}
+
+/// All `size`-element combinations of `items`, in order.
+fn combinations<T: Clone>(items: &[T], size: usize) -> Vec<Vec<T>> {
+    if size == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], size - 1) {
+            rest.insert(0, item.clone());
+            result.push(rest);
+        }
+    }
+    result
 }
 
 /// Show userset expansion tree.
@@ -150,14 +949,14 @@ pub async fn expand(ctx: &Context, resource: &str, relation: &str, _max_depth: u
     ctx.output.info(&format!("Expanding {resource}#{relation}"));
     ctx.output.info("");
 
-    let subjects: Vec<String> =
-        vault.subjects().with_permission(relation).on_resource(resource).collect().await?;
+    let query = vault.subjects().with_permission(relation).on_resource(resource).collect();
+    let subjects: Vec<String> = breakdown::timed(Phase::Api, "list subjects", query).await?;
 
     if subjects.is_empty() {
         ctx.output.info("(no subjects found)");
     } else {
         for subject in subjects {
-            println!("  └─ {subject}");
+            println!("  {} {subject}", glyphs::BRANCH);
         }
     }
 
@@ -174,12 +973,9 @@ pub async fn explain_permission(
     let client = ctx.client().await?;
     let vault = client.vault();
 
-    let explanation = vault
-        .explain_permission()
-        .subject(subject)
-        .permission(permission)
-        .resource(resource)
-        .await?;
+    let request =
+        vault.explain_permission().subject(subject).permission(permission).resource(resource);
+    let explanation = breakdown::timed(Phase::Api, "explain permission", request).await?;
 
     ctx.output.info(&format!("Explaining: {subject} {permission} {resource}"));
     ctx.output.info("");
@@ -205,7 +1001,8 @@ pub async fn list_resources(
         query = query.resource_type(rt);
     }
 
-    let resources: Vec<String> = query.collect().await?;
+    let resources: Vec<String> =
+        breakdown::timed(Phase::Api, "list resources", query.collect()).await?;
 
     if resources.is_empty() {
         ctx.output.info("No accessible resources found.");
@@ -235,7 +1032,8 @@ pub async fn list_subjects(
         query = query.subject_type(st);
     }
 
-    let subjects: Vec<String> = query.collect().await?;
+    let subjects: Vec<String> =
+        breakdown::timed(Phase::Api, "list subjects", query.collect()).await?;
 
     if subjects.is_empty() {
         ctx.output.info("No subjects with access found.");
@@ -257,3 +1055,138 @@ fn parse_relationship(s: &str) -> Result<inferadb::Relationship<'static>> {
         ))
     })
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn profile(principal: Option<&str>, require_impersonate: bool) -> crate::config::Profile {
+        crate::config::Profile {
+            principal: principal.map(str::to_string),
+            require_impersonate,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_impersonation() {
+        let guarded = profile(Some("user:alice"), true);
+        assert!(!is_impersonation(&guarded, "user:alice", false).unwrap());
+        assert!(is_impersonation(&guarded, "user:bob", false).is_err());
+        assert!(is_impersonation(&guarded, "user:bob", true).unwrap());
+
+        let open = profile(Some("user:alice"), false);
+        assert!(is_impersonation(&open, "user:bob", false).unwrap());
+
+        assert!(is_impersonation(&profile(None, true), "user:bob", false).is_err());
+        assert!(!is_impersonation(&profile(None, false), "user:bob", false).unwrap());
+        assert!(is_impersonation(&profile(None, false), "user:bob", true).unwrap());
+    }
+
+    #[test]
+    fn test_near_misses() {
+        let schema = ipl::parse(
+            "entity Document {
+                relations {
+                    owner: User
+                    viewer: User with ip_allowlist | Group#member
+                    reader: Group#member
+                    banned: User
+                    parent: Folder
+                }
+                permissions {
+                    view: (owner | viewer | reader | parent.view) - banned
+                }
+            }",
+        )
+        .unwrap();
+        let entity = schema.entity_for_type("document").unwrap();
+        let grants = entity.grants("view");
+        let misses = near_misses(entity, &grants, "user:alice", "document:readme");
+
+        assert_eq!(misses.len(), 4);
+        assert_eq!(
+            misses[0].command.as_deref(),
+            Some("inferadb relationships add user:alice owner document:readme")
+        );
+        assert!(misses[1].remediation.contains("caveat 'ip_allowlist' must hold"));
+        assert_eq!(
+            misses[2].remediation,
+            "Add user:alice to a Group#member that is reader of document:readme"
+        );
+        assert_eq!(misses[2].command, None);
+        assert_eq!(misses[3].via.as_deref(), Some("view"));
+        assert!(misses.iter().all(|m| m.relation != "banned"));
+    }
+
+    #[test]
+    fn test_suggest_steps() {
+        let schema = ipl::parse(
+            "entity Document {
+                relations {
+                    viewer: User | Group#member
+                    parent: Folder
+                }
+                permissions {
+                    view: viewer | parent.view
+                }
+            }",
+        )
+        .unwrap();
+        let entity = schema.entity("Document").unwrap();
+        let steps = suggest_steps(entity, &entity.grants("view"), "user:alice", "document:readme");
+
+        assert_eq!(
+            steps,
+            vec![
+                SuggestStep::Add(Candidate {
+                    resource: "document:readme".into(),
+                    relation: "viewer".into(),
+                    subject: "user:alice".into(),
+                }),
+                SuggestStep::Follow {
+                    relation: "viewer".into(),
+                    permission: "member".into(),
+                    object_type: Some("Group".into()),
+                },
+                SuggestStep::Follow {
+                    relation: "parent".into(),
+                    permission: "view".into(),
+                    object_type: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_batch() {
+        let checks = parse_batch(
+            "- {subject: user:alice, permission: view, resource: doc:1, expected: allow}\n\
+             - {subject: user:bob, permission: edit, resource: doc:1, expected: false}\n",
+        )
+        .unwrap();
+        assert_eq!(checks.len(), 2);
+        assert!(checks[0].expected.allowed().unwrap());
+        assert!(!checks[1].expected.allowed().unwrap());
+
+        let wrapped = parse_batch(
+            r#"{"checks": [{"subject": "user:a", "permission": "view", "resource": "doc:1", "expected": "denied"}]}"#,
+        )
+        .unwrap();
+        assert!(!wrapped[0].expected.allowed().unwrap());
+
+        let invalid =
+            parse_batch("- {subject: user:a, permission: view, resource: doc:1, expected: maybe}")
+                .unwrap();
+        assert!(invalid[0].expected.allowed().is_err());
+        assert!(parse_batch("- {subject: user:a}").is_err());
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(combinations(&[1, 2, 3], 1), vec![vec![1], vec![2], vec![3]]);
+        assert_eq!(combinations(&[1, 2, 3], 2), vec![vec![1, 2], vec![1, 3], vec![2, 3]]);
+        assert!(combinations(&[1], 2).is_empty());
+    }
+}
@@ -0,0 +1,372 @@
+//! Dynamic values for shell completion.
+//!
+//! The scripts from `inferadb completion` call `inferadb __complete` with the
+//! words of the command line up to the cursor (the last one being the word
+//! under it) and offer what it prints, one value per line, falling back to
+//! the static completions when it prints nothing, like kubectl's
+//! `__complete`. Profile names come from the config, organization and vault
+//! IDs from the profiles and the response cache, and resource types,
+//! relations and permissions from the vault's active schema.
+
+use std::{collections::BTreeSet, time::Duration};
+
+use clap::{Arg, CommandFactory};
+
+use crate::{
+    cli::{Cli, Shell},
+    client::Context,
+    config::Config,
+    error::Result,
+    ipl,
+};
+
+/// Hidden command the completion scripts call back into.
+pub const COMPLETE_COMMAND: &str = "__complete";
+
+/// How long to wait for the active schema before completing nothing.
+const SCHEMA_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Kind of value expected at the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    /// A profile name.
+    Profile,
+    /// An `@profile` prefix.
+    AtProfile,
+    /// An organization ID.
+    Org,
+    /// A vault ID.
+    Vault,
+    /// An entity (`type:id`); only the `type:` part is completed.
+    Entity,
+    /// A resource or subject type.
+    Type,
+    /// A relation name.
+    Relation,
+    /// A permission (or relation) name.
+    Permission,
+}
+
+/// Print completions for `words`, the command line (program name first) up to
+/// and including the word under the cursor. Failures print nothing, so the
+/// shell falls back to its static completions.
+pub async fn complete(words: &[String]) {
+    let words = words.get(1..).unwrap_or_default();
+    let Some(current) = words.last() else {
+        return;
+    };
+    let Some(expected) = expected(words) else {
+        return;
+    };
+    match candidates(words, expected).await {
+        Ok(values) => {
+            for value in values.iter().filter(|value| value.starts_with(current.as_str())) {
+                println!("{value}");
+            }
+        },
+        Err(e) => tracing::debug!(error = %e, "completion failed"),
+    }
+}
+
+/// What the last word of `words` should be, found by walking the command
+/// tree the way clap parses it.
+fn expected(words: &[String]) -> Option<Expected> {
+    let (current, typed) = words.split_last()?;
+    if current.starts_with('@') {
+        return Some(Expected::AtProfile);
+    }
+    if current.starts_with('-') {
+        return None;
+    }
+
+    let mut root = Cli::command();
+    root.build();
+    let mut cmd = &root;
+    let mut path: Vec<&str> = Vec::new();
+    let mut positional = 0;
+    let mut pending: Option<&Arg> = None;
+    for word in typed {
+        if pending.take().is_some() || word.starts_with('@') {
+            continue;
+        }
+        if let Some(long) = word.strip_prefix("--") {
+            if !long.contains('=') {
+                pending = cmd.get_arguments().find(|arg| arg.get_long() == Some(long));
+            }
+        } else if let Some(short) = word.strip_prefix('-').filter(|short| !short.is_empty()) {
+            let mut chars = short.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                pending = cmd.get_arguments().find(|arg| arg.get_short() == Some(c));
+            }
+        } else if let Some(sub) = cmd.find_subcommand(word) {
+            cmd = sub;
+            path.push(sub.get_name());
+            positional = 0;
+            continue;
+        } else {
+            positional += 1;
+        }
+        pending = pending.filter(|arg| arg.get_action().takes_values());
+    }
+
+    let arg = match pending {
+        Some(arg) => arg,
+        None => cmd.get_positionals().nth(positional)?,
+    };
+    kind(&path, arg.get_id().as_str())
+}
+
+/// Kind of value an argument takes, by its name.
+fn kind(path: &[&str], id: &str) -> Option<Expected> {
+    Some(match id {
+        "profile" => Expected::Profile,
+        "name" | "old_name"
+            if path.first() == Some(&"profiles")
+                && matches!(
+                    path.get(1),
+                    Some(&("show" | "update" | "rename" | "delete" | "default"))
+                ) =>
+        {
+            Expected::Profile
+        },
+        "org" => Expected::Org,
+        "vault" => Expected::Vault,
+        "subject" | "resource" | "resource_prefix" => Expected::Entity,
+        "resource_type" | "subject_type" => Expected::Type,
+        "relation" | "subject_relation" => Expected::Relation,
+        "permission" => Expected::Permission,
+        _ => return None,
+    })
+}
+
+/// Every value of the expected kind, sorted.
+async fn candidates(words: &[String], expected: Expected) -> Result<BTreeSet<String>> {
+    if matches!(expected, Expected::Profile | Expected::AtProfile) {
+        let config = Config::load()?;
+        let prefix = if expected == Expected::AtProfile { "@" } else { "" };
+        return Ok(config.profiles.keys().map(|name| format!("{prefix}{name}")).collect());
+    }
+
+    let ctx = context(words)?;
+    match expected {
+        Expected::Org | Expected::Vault => {
+            let vaults = expected == Expected::Vault;
+            let mut ids: BTreeSet<String> = ctx
+                .config
+                .profiles
+                .values()
+                .filter_map(
+                    |profile| if vaults { profile.vault.clone() } else { profile.org.clone() },
+                )
+                .collect();
+            ids.extend(cached_ids(&super::cache::profile_cache(&ctx).values(), vaults));
+            Ok(ids)
+        },
+        _ => {
+            let Ok(Ok(schema)) = tokio::time::timeout(SCHEMA_TIMEOUT, active_schema(&ctx)).await
+            else {
+                return Ok(BTreeSet::new());
+            };
+            Ok(schema_names(&schema, expected))
+        },
+    }
+}
+
+/// A quiet context for the profile, organization and vault named in `words`.
+fn context(words: &[String]) -> Result<Context> {
+    let flag = |names: &[&str]| {
+        words.windows(2).find(|pair| names.contains(&pair[0].as_str())).map(|pair| pair[1].clone())
+    };
+    let profile = words
+        .iter()
+        .find_map(|word| word.strip_prefix('@').filter(|name| !name.is_empty()))
+        .map(str::to_string)
+        .or_else(|| flag(&["--profile"]))
+        .or_else(|| std::env::var("INFERADB_PROFILE").ok());
+    Context::builder()
+        .maybe_profile_name(profile)
+        .maybe_org_override(flag(&["--org"]).or_else(|| std::env::var("INFERADB_ORG").ok()))
+        .maybe_vault_override(
+            flag(&["--vault", "-v"]).or_else(|| std::env::var("INFERADB_VAULT").ok()),
+        )
+        .output_format("table".to_string())
+        .color("never".to_string())
+        .quiet(true)
+        .build()
+}
+
+async fn active_schema(ctx: &Context) -> Result<ipl::Schema> {
+    let client = ctx.client().await?;
+    let key = format!("orgs/{}/vaults/{}/schemas/active", client.org_id(), client.vault_id());
+    let vault = client.vault();
+    let source =
+        ctx.cached(&key, async { Ok(vault.schemas().get_active().await?.content) }).await?;
+    ipl::parse(&source)
+}
+
+/// Organization or vault IDs from cached responses: the IDs in request keys
+/// (`orgs/<org>/vaults/<vault>`) and in cached organization and vault lists.
+fn cached_ids(entries: &[(String, serde_json::Value)], vaults: bool) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    for (key, value) in entries {
+        let path = key.split('?').next().unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').collect();
+        let segment = if vaults { 3 } else { 1 };
+        if segments.get(segment - 1) == Some(&if vaults { "vaults" } else { "orgs" })
+            && let Some(id) = segments.get(segment).filter(|id| !id.is_empty())
+        {
+            ids.insert((*id).to_string());
+        }
+
+        let lists =
+            if vaults { segments.len() == 3 && segments[2] == "vaults" } else { path == "orgs" };
+        if lists && let Some(items) = value.get("items").and_then(|items| items.as_array()) {
+            ids.extend(
+                items.iter().filter_map(|item| item.get("id")?.as_str()).map(str::to_string),
+            );
+        }
+    }
+    ids
+}
+
+/// Names from the schema: `type:` prefixes for entities, type names, or
+/// relation and permission names.
+fn schema_names(schema: &ipl::Schema, expected: Expected) -> BTreeSet<String> {
+    let entities = schema.entities.iter();
+    match expected {
+        Expected::Entity => entities.map(|e| format!("{}:", ipl::type_name(&e.name))).collect(),
+        Expected::Type => entities.map(|e| ipl::type_name(&e.name)).collect(),
+        Expected::Relation => {
+            entities.flat_map(|e| e.relations.iter().map(|r| r.name.clone())).collect()
+        },
+        Expected::Permission => entities
+            .flat_map(|e| {
+                e.permissions
+                    .iter()
+                    .map(|p| p.name.clone())
+                    .chain(e.relations.iter().map(|r| r.name.clone()))
+            })
+            .collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+/// Start of the registration block ending clap's zsh script.
+const ZSH_REGISTRATION: &str = "if [ \"$funcstack[1]\" = \"_inferadb\" ]; then";
+
+/// Add shell code calling back into [`COMPLETE_COMMAND`] to a static
+/// completion script. `PowerShell` keeps the static completions only.
+pub fn with_dynamic(shell: Shell, mut script: Vec<u8>) -> Vec<u8> {
+    match shell {
+        Shell::Bash => script.extend_from_slice(BASH.as_bytes()),
+        Shell::Zsh => {
+            // Register the wrapper in place of `_inferadb`, so it is also used
+            // when the script is autoloaded from `fpath`
+            let text = String::from_utf8_lossy(&script);
+            let end = text.rfind(ZSH_REGISTRATION).unwrap_or(text.len());
+            script.truncate(end);
+            script.extend_from_slice(ZSH.as_bytes());
+        },
+        Shell::Fish => script.extend_from_slice(FISH.as_bytes()),
+        Shell::PowerShell => {},
+    }
+    script
+}
+
+const BASH: &str = r#"
+# Dynamic values: profiles, organizations, vaults, and schema names
+_inferadb_dynamic() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local -a words values
+    read -ra words <<< "$line"
+    [[ $line == *[[:space:]] ]] && words+=("")
+    local cur="${words[${#words[@]}-1]}"
+    local IFS=$'\n'
+    values=($(inferadb __complete "${words[@]}" 2>/dev/null))
+    if (( ${#values[@]} )); then
+        if [[ $cur == *:* && $COMP_WORDBREAKS == *:* ]]; then
+            local colon_prefix="${cur%"${cur##*:}"}"
+            values=("${values[@]#"$colon_prefix"}")
+        fi
+        [[ ${#values[@]} -eq 1 && ${values[0]} == *: ]] && compopt -o nospace
+        COMPREPLY=("${values[@]}")
+        return 0
+    fi
+    _inferadb "$@"
+}
+complete -F _inferadb_dynamic -o bashdefault -o default inferadb
+"#;
+
+const ZSH: &str = r#"# Dynamic values: profiles, organizations, vaults, and schema names
+_inferadb_dynamic() {
+    local -a values
+    values=("${(@f)$(inferadb __complete "${(@)words[1,CURRENT]}" 2>/dev/null)}")
+    values=(${values:#})
+    if (( ${#values} )); then
+        compadd -Q -S '' -- ${(M)values:#*:}
+        compadd -Q -- ${values:#*:}
+        return
+    fi
+    _inferadb "$@"
+}
+
+if [ "$funcstack[1]" = "_inferadb" ]; then
+    _inferadb_dynamic "$@"
+else
+    compdef _inferadb_dynamic inferadb
+fi
+"#;
+
+const FISH: &str = r"
+# Dynamic values: profiles, organizations, vaults, and schema names
+complete -c inferadb -a '(inferadb __complete (commandline -opc) (commandline -ct) 2>/dev/null)'
+";
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn expect(line: &str) -> Option<Expected> {
+        let mut words: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        if line.ends_with(' ') {
+            words.push(String::new());
+        }
+        expected(&words)
+    }
+
+    #[test]
+    fn test_expected() {
+        assert_eq!(expect("--profile "), Some(Expected::Profile));
+        assert_eq!(expect("@pr"), Some(Expected::AtProfile));
+        assert_eq!(expect("profiles delete "), Some(Expected::Profile));
+        assert_eq!(expect("profiles create "), None);
+        assert_eq!(expect("--org "), Some(Expected::Org));
+        assert_eq!(expect("orgs vaults list -v "), Some(Expected::Vault));
+        assert_eq!(expect("check us"), Some(Expected::Entity));
+        assert_eq!(expect("check --trace user:alice "), Some(Expected::Permission));
+        assert_eq!(expect("check -o json user:alice can_view "), Some(Expected::Entity));
+        assert_eq!(expect("relationships add user:alice "), Some(Expected::Relation));
+        assert_eq!(expect("relationships list --relation "), Some(Expected::Relation));
+        assert_eq!(expect("list-subjects doc:1 view --subject-type "), Some(Expected::Type));
+        assert_eq!(expect("check --"), None);
+        assert_eq!(expect("login "), None);
+    }
+
+    #[test]
+    fn test_cached_ids() {
+        let entries = vec![
+            ("orgs".to_string(), serde_json::json!({"items": [{"id": "org_1"}, {"id": "org_2"}]})),
+            (
+                "orgs/org_1/vaults?archived=false".to_string(),
+                serde_json::json!({"items": [{"id": "vlt_a"}]}),
+            ),
+            ("orgs/org_3/vaults/vlt_b".to_string(), serde_json::json!({"id": "vlt_b"})),
+        ];
+        assert_eq!(
+            cached_ids(&entries, false).into_iter().collect::<Vec<_>>(),
+            ["org_1", "org_2", "org_3"]
+        );
+        assert_eq!(cached_ids(&entries, true).into_iter().collect::<Vec<_>>(), ["vlt_a", "vlt_b"]);
+    }
+}
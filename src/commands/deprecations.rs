@@ -0,0 +1,52 @@
+//! Deprecation commands: list.
+
+use serde::Serialize;
+
+use crate::{
+    client::Context,
+    deprecations::{self, DEPRECATIONS, Deprecation},
+    error::Result,
+    output::Displayable,
+};
+
+#[derive(Debug, Clone, Serialize)]
+struct DeprecationRow {
+    #[serde(flatten)]
+    deprecation: Deprecation,
+    used: bool,
+}
+
+impl Displayable for DeprecationRow {
+    fn table_row(&self) -> Vec<String> {
+        let d = &self.deprecation;
+        vec![
+            d.id.to_string(),
+            d.kind.as_str().to_string(),
+            d.usage.to_string(),
+            d.replacement.to_string(),
+            d.removed_in.to_string(),
+            if self.used { "yes" } else { "no" }.to_string(),
+        ]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["ID", "KIND", "USAGE", "REPLACEMENT", "REMOVED IN", "USED"]
+    }
+}
+
+/// List deprecated usages, marking those that `args` (a command line
+/// without the program name) or the environment relies on.
+pub async fn list(ctx: &Context, used_only: bool, args: &[String]) -> Result<()> {
+    let used = deprecations::detect(args);
+    let rows: Vec<DeprecationRow> = DEPRECATIONS
+        .iter()
+        .map(|d| DeprecationRow { deprecation: d.clone(), used: used.iter().any(|u| u.id == d.id) })
+        .filter(|row| row.used || !used_only)
+        .collect();
+
+    if rows.is_empty() {
+        ctx.output.success("No deprecated usage found.");
+        return Ok(());
+    }
+    ctx.output.table(&rows)
+}
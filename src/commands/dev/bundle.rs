@@ -0,0 +1,564 @@
+//! Artifact bundles for air-gapped dev clusters.
+//!
+//! `dev bundle create` vendors the deploy repository, container images, Helm
+//! charts, and manifests into a single tarball on a connected machine;
+//! `dev start --bundle` provisions the cluster from it without registry,
+//! GitHub, or chart repository access.
+//!
+//! Besides the `InferaDB` components, the bundle carries every image the
+//! cluster pulls: the Talos system images (Kubernetes, etcd, `CoreDNS`,
+//! Flannel), the Tailscale operator and proxy, local-path-provisioner, and
+//! the images the deploy manifests reference, such as the ledger. On start
+//! they are pushed to a local registry under their upstream paths, and the
+//! cluster is created with a registry mirror for each upstream registry, so
+//! nodes pull images by their usual names without reaching the internet.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    commands::{command_exists, run_command, run_command_optional},
+    constants::{
+        COMPONENT_IMAGES, DEFAULT_IMAGE_TAG, GHCR_IMAGE_PREFIX, HELM_TAILSCALE_CHART,
+        HELM_TAILSCALE_REPO, HELM_TAILSCALE_URL, KUBERNETES_IMAGES, KUBERNETES_VERSION,
+        LOCAL_PATH_PROVISIONER_URL, REGISTRY_IMAGE, REGISTRY_NAME, REGISTRY_PORT,
+        TALOS_HOST_GATEWAY,
+    },
+    docker::{docker_container_exists, pull_image},
+    kubernetes::{helm_repo_add, helm_repo_exists, helm_repo_update},
+    output::{
+        StartStep, StepOutcome, print_done, print_hint, print_phase_header, print_styled_header,
+        run_step, run_step_with_result,
+    },
+    paths::get_state_dir,
+    start::step_clone_repo,
+};
+use crate::{client::Context, error::Result};
+
+/// Bundle layout version, bumped when the archive structure changes.
+const BUNDLE_FORMAT_VERSION: u32 = 2;
+
+// Archive layout
+const MANIFEST_FILE: &str = "manifest.json";
+const IMAGES_FILE: &str = "images.tar";
+const DEPLOY_DIR: &str = "deploy";
+const TAILSCALE_CHART_FILE: &str = "charts/tailscale-operator.tgz";
+const LOCAL_PATH_MANIFEST_FILE: &str = "manifests/local-path-storage.yaml";
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Run dev bundle create - vendor all artifacts needed by `dev start`.
+pub async fn create(
+    _ctx: &Context,
+    output: &Path,
+    tag: Option<&str>,
+    commit: Option<&str>,
+) -> Result<()> {
+    let tag = tag.unwrap_or(DEFAULT_IMAGE_TAG);
+    let staging = get_state_dir().join("bundle-staging");
+
+    print_styled_header("Creating InferaDB Development Bundle");
+    print_phase_header("Collecting artifacts");
+
+    run_step(&StartStep::with_ok("Checking prerequisites", "Checked prerequisites"), || {
+        for cmd in &["docker", "git", "helm", "curl", "tar", "talosctl"] {
+            if !command_exists(cmd) {
+                return Err(format!(
+                    "{cmd} is not installed. Run 'inferadb dev doctor' for setup instructions."
+                ));
+            }
+        }
+        if staging.exists() {
+            fs::remove_dir_all(&staging)
+                .map_err(|e| format!("Failed to clean {}: {e}", staging.display()))?;
+        }
+        fs::create_dir_all(&staging)
+            .map_err(|e| format!("Failed to create {}: {e}", staging.display()))?;
+        Ok(StepOutcome::Success)
+    })?;
+
+    run_step(
+        &StartStep::with_ok("Cloning deployment repository", "Cloned deployment repository"),
+        || step_clone_repo(&staging.join(DEPLOY_DIR), true, commit).map(|_| StepOutcome::Success),
+    )?;
+
+    run_step(&StartStep::with_ok("Downloading Helm charts", "Downloaded Helm charts"), || {
+        if !helm_repo_exists(HELM_TAILSCALE_REPO) {
+            helm_repo_add(HELM_TAILSCALE_REPO, HELM_TAILSCALE_URL)?;
+        }
+        helm_repo_update()?;
+        pull_chart(HELM_TAILSCALE_CHART, &staging.join(TAILSCALE_CHART_FILE))
+            .map(|()| StepOutcome::Success)
+    })?;
+
+    run_step(&StartStep::with_ok("Downloading manifests", "Downloaded manifests"), || {
+        download(LOCAL_PATH_PROVISIONER_URL, &staging.join(LOCAL_PATH_MANIFEST_FILE))
+            .map(|()| StepOutcome::Success)
+    })?;
+
+    let components: Vec<String> =
+        COMPONENT_IMAGES.iter().map(|name| format!("{GHCR_IMAGE_PREFIX}/{name}:{tag}")).collect();
+    let mirrored = run_step_with_result(
+        &StartStep::with_ok("Listing cluster images", "Listed cluster images"),
+        || cluster_images(&staging, &components).map(|images| (StepOutcome::Success, images)),
+    )?;
+    let mut images = mirrored.clone();
+    images.push(REGISTRY_IMAGE.to_string());
+    if let Some(talos_image) = talos_node_image() {
+        images.push(talos_image);
+    }
+
+    for image in &images {
+        run_step(
+            &StartStep::with_ok(&format!("Pulling {image}"), &format!("Pulled {image}")),
+            || pull_image(image).map(|()| StepOutcome::Success),
+        )?;
+    }
+
+    run_step(&StartStep::with_ok("Saving container images", "Saved container images"), || {
+        let archive = staging.join(IMAGES_FILE);
+        let archive = archive.to_str().ok_or("Invalid staging path")?;
+        let mut args = vec!["save", "-o", archive];
+        args.extend(images.iter().map(String::as_str));
+        run_command("docker", &args).map(|_| StepOutcome::Success).map_err(|e| e.to_string())
+    })?;
+
+    print_phase_header("Writing bundle");
+
+    run_step(&StartStep::with_ok("Writing bundle manifest", "Wrote bundle manifest"), || {
+        let manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            tag: tag.to_string(),
+            components,
+            mirrored,
+            images,
+        };
+        let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        fs::write(staging.join(MANIFEST_FILE), json).map_err(|e| e.to_string())?;
+        Ok(StepOutcome::Success)
+    })?;
+
+    run_step(&StartStep::with_ok("Archiving bundle", "Archived bundle"), || {
+        let output = output.to_str().ok_or("Invalid output path")?;
+        let staging_str = staging.to_str().ok_or("Invalid staging path")?;
+        run_command("tar", &["-cf", output, "-C", staging_str, "."]).map_err(|e| e.to_string())?;
+        let _ = fs::remove_dir_all(&staging);
+        Ok(StepOutcome::Success)
+    })?;
+
+    println!();
+    print_done(&format!("Bundle written to {}", output.display()));
+    println!();
+    print_hint(&format!(
+        "Run 'inferadb dev start --bundle {}' on the target machine",
+        output.display()
+    ));
+    Ok(())
+}
+
+// ============================================================================
+// Bundle
+// ============================================================================
+
+/// Contents description stored at the root of a bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Bundle layout version.
+    pub format_version: u32,
+    /// When the bundle was created (RFC 3339).
+    pub created_at: String,
+    /// Image tag of the vendored `InferaDB` components.
+    pub tag: String,
+    /// Component image references, in `COMPONENT_IMAGES` order.
+    pub components: Vec<String>,
+    /// Images the cluster pulls, served from the local registry on start.
+    pub mirrored: Vec<String>,
+    /// Every image saved in the images archive.
+    pub images: Vec<String>,
+}
+
+/// An extracted bundle ready to provision a cluster from.
+pub struct Bundle {
+    root: PathBuf,
+    /// Parsed bundle manifest.
+    pub manifest: BundleManifest,
+}
+
+impl Bundle {
+    /// Extract a bundle archive into the state directory.
+    pub fn extract(archive: &Path) -> std::result::Result<Self, String> {
+        if !archive.is_file() {
+            return Err(format!("Bundle not found: {}", archive.display()));
+        }
+
+        let root = get_state_dir().join("bundle");
+        if root.exists() {
+            fs::remove_dir_all(&root)
+                .map_err(|e| format!("Failed to clean {}: {e}", root.display()))?;
+        }
+        fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create {}: {e}", root.display()))?;
+
+        let archive_str = archive.to_str().ok_or("Invalid bundle path")?;
+        let root_str = root.to_str().ok_or("Invalid state directory path")?;
+        run_command("tar", &["-xf", archive_str, "-C", root_str]).map_err(|e| e.to_string())?;
+
+        let contents = fs::read_to_string(root.join(MANIFEST_FILE))
+            .map_err(|e| format!("Invalid bundle, missing {MANIFEST_FILE}: {e}"))?;
+        let manifest: BundleManifest =
+            serde_json::from_str(&contents).map_err(|e| format!("Invalid bundle manifest: {e}"))?;
+        if manifest.format_version != BUNDLE_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported bundle format version {} (expected {BUNDLE_FORMAT_VERSION})",
+                manifest.format_version
+            ));
+        }
+
+        Ok(Self { root, manifest })
+    }
+
+    /// Vendored deploy repository.
+    pub fn deploy_dir(&self) -> PathBuf {
+        self.root.join(DEPLOY_DIR)
+    }
+
+    /// Vendored Tailscale operator chart archive.
+    pub fn tailscale_chart(&self) -> PathBuf {
+        self.root.join(TAILSCALE_CHART_FILE)
+    }
+
+    /// Vendored local-path-provisioner manifest.
+    pub fn local_path_manifest(&self) -> PathBuf {
+        self.root.join(LOCAL_PATH_MANIFEST_FILE)
+    }
+
+    /// Copy the vendored deploy repository into place.
+    ///
+    /// Returns `Ok(Some(_))` if it already exists and `force` is not set.
+    pub fn install_deploy_dir(
+        &self,
+        target_dir: &Path,
+        force: bool,
+    ) -> std::result::Result<Option<String>, String> {
+        if target_dir.exists() {
+            if !force {
+                return Ok(Some("already exists".to_string()));
+            }
+            fs::remove_dir_all(target_dir)
+                .map_err(|e| format!("Failed to remove {}: {e}", target_dir.display()))?;
+        }
+        copy_dir(&self.deploy_dir(), target_dir)
+            .map_err(|e| format!("Failed to copy deploy repository: {e}"))?;
+        Ok(None)
+    }
+
+    /// Load all vendored images into the local Docker daemon.
+    pub fn load_images(&self) -> std::result::Result<StepOutcome, String> {
+        let archive = self.root.join(IMAGES_FILE);
+        let archive = archive.to_str().ok_or("Invalid bundle path")?;
+        run_command("docker", &["load", "-i", archive])
+            .map(|_| StepOutcome::Success)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Start the local registry and push every image the cluster pulls to
+    /// it, under its upstream repository path.
+    pub fn serve_images(&self) -> std::result::Result<StepOutcome, String> {
+        if !docker_container_exists(REGISTRY_NAME) {
+            run_command(
+                "docker",
+                &[
+                    "run",
+                    "-d",
+                    "--name",
+                    REGISTRY_NAME,
+                    "-p",
+                    &format!("{REGISTRY_PORT}:5000"),
+                    "--restart",
+                    "always",
+                    REGISTRY_IMAGE,
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+            std::thread::sleep(std::time::Duration::from_secs(3));
+        }
+
+        for image in &self.manifest.mirrored {
+            let (_, path) = split_image(image);
+            let local = format!("localhost:{REGISTRY_PORT}/{path}");
+            run_command("docker", &["tag", image, &local]).map_err(|e| e.to_string())?;
+            run_command("docker", &["push", &local]).map_err(|e| e.to_string())?;
+        }
+        Ok(StepOutcome::Success)
+    }
+
+    /// `talosctl cluster create --registry-mirror` values sending every
+    /// upstream registry of the mirrored images to the local registry.
+    pub fn registry_mirrors(&self) -> Vec<String> {
+        let mut registries: Vec<&str> =
+            self.manifest.mirrored.iter().map(|image| split_image(image).0).collect();
+        registries.sort_unstable();
+        registries.dedup();
+        registries
+            .into_iter()
+            .map(|registry| format!("{registry}=http://{TALOS_HOST_GATEWAY}:{REGISTRY_PORT}"))
+            .collect()
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Every image the cluster pulls, deduplicated: the components, the Talos
+/// system images, and those referenced by the staged chart and manifests.
+fn cluster_images(
+    staging: &Path,
+    components: &[String],
+) -> std::result::Result<Vec<String>, String> {
+    let mut images = components.to_vec();
+
+    let system = run_command("talosctl", &["image", "default"]).map_err(|e| e.to_string())?;
+    images
+        .extend(system.lines().map(str::trim).filter(|line| !line.is_empty()).map(pin_kubernetes));
+
+    let chart = staging.join(TAILSCALE_CHART_FILE);
+    let chart = chart.to_str().ok_or("Invalid staging path")?;
+    let rendered = run_command(
+        "helm",
+        &[
+            "template",
+            "tailscale-operator",
+            chart,
+            "--set",
+            "oauth.clientId=bundle",
+            "--set",
+            "oauth.clientSecret=bundle",
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    images.extend(image_refs(&rendered));
+
+    let local_path = fs::read_to_string(staging.join(LOCAL_PATH_MANIFEST_FILE))
+        .map_err(|e| format!("Failed to read local-path manifest: {e}"))?;
+    images.extend(image_refs(&local_path));
+
+    // Components are vendored at the bundle tag and patched in on deploy
+    for file in yaml_files(&staging.join(DEPLOY_DIR)) {
+        let contents = fs::read_to_string(&file).map_err(|e| e.to_string())?;
+        images.extend(
+            image_refs(&contents).into_iter().filter(|image| !image.starts_with(GHCR_IMAGE_PREFIX)),
+        );
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    images.retain(|image| seen.insert(image.clone()));
+    Ok(images)
+}
+
+/// Retag the Kubernetes images `talosctl` lists for its default version to
+/// the version the cluster is created with.
+fn pin_kubernetes(image: &str) -> String {
+    let image = image.split('@').next().unwrap_or(image);
+    let name = match image.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => name,
+        _ => image,
+    };
+    let repository = name.rsplit('/').next().unwrap_or(name);
+    if KUBERNETES_IMAGES.contains(&repository) {
+        format!("{name}:v{KUBERNETES_VERSION}")
+    } else {
+        image.to_string()
+    }
+}
+
+/// Image references in rendered YAML: `image:` values, and the proxy image
+/// the Tailscale operator passes in its `PROXY_IMAGE` environment variable.
+fn image_refs(yaml: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+    let mut proxy_image = false;
+    for line in yaml.lines() {
+        let line = line.trim().trim_start_matches("- ");
+        let value = line
+            .strip_prefix("image:")
+            .or_else(|| line.strip_prefix("value:").filter(|_| proxy_image));
+        proxy_image = line == "name: PROXY_IMAGE";
+        let Some(value) = value else { continue };
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        if !value.is_empty() && !value.contains("{{") && !value.contains('$') {
+            refs.push(value.to_string());
+        }
+    }
+    refs
+}
+
+/// Split an image reference into its registry and its repository path with
+/// tag, as a registry serves it: Docker Hub images get their implicit
+/// `docker.io` registry and `library/` namespace, and digests are dropped.
+fn split_image(image: &str) -> (&str, String) {
+    let image = image.split('@').next().unwrap_or(image);
+    let (registry, path) = match image.split_once('/') {
+        Some((host, path)) if host.contains(['.', ':']) || host == "localhost" => (host, path),
+        _ => ("docker.io", image),
+    };
+    let path = if registry == "docker.io" && !path.contains('/') {
+        format!("library/{path}")
+    } else {
+        path.to_string()
+    };
+    (registry, path)
+}
+
+/// YAML files under a directory, skipping hidden directories such as `.git`.
+fn yaml_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else { return files };
+    for entry in entries.filter_map(std::result::Result::ok) {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            files.extend(yaml_files(&path));
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"))
+        {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Talos node image matching the installed `talosctl`, if detectable.
+fn talos_node_image() -> Option<String> {
+    let output = run_command_optional("talosctl", &["version", "--client", "--short"])?;
+    parse_talos_version(&output).map(|version| format!("ghcr.io/siderolabs/talos:{version}"))
+}
+
+/// Extract the first `vX.Y.Z` token from `talosctl version` output.
+fn parse_talos_version(output: &str) -> Option<&str> {
+    output.split_whitespace().find(|token| {
+        token.strip_prefix('v').is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+    })
+}
+
+/// Pull a Helm chart archive to a fixed destination path.
+fn pull_chart(chart: &str, dest: &Path) -> std::result::Result<(), String> {
+    let dir = dest.parent().ok_or("Invalid chart path")?;
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let dir_str = dir.to_str().ok_or("Invalid chart path")?;
+    run_command("helm", &["pull", chart, "--destination", dir_str]).map_err(|e| e.to_string())?;
+
+    // `helm pull` names the archive after the chart version
+    let name = chart.rsplit('/').next().unwrap_or(chart);
+    let pulled = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(std::result::Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| {
+            let archive = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("tgz"));
+            archive
+                && path.file_name().and_then(|f| f.to_str()).is_some_and(|f| f.starts_with(name))
+        })
+        .ok_or_else(|| format!("Chart archive for {chart} not found"))?;
+    fs::rename(pulled, dest).map_err(|e| e.to_string())
+}
+
+/// Download a URL to a file.
+fn download(url: &str, dest: &Path) -> std::result::Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let dest = dest.to_str().ok_or("Invalid download path")?;
+    run_command("curl", &["-fsSL", url, "-o", dest]).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Recursively copy a directory.
+fn copy_dir(src: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_talos_version() {
+        assert_eq!(parse_talos_version("Client v1.9.1"), Some("v1.9.1"));
+        assert_eq!(parse_talos_version("Client:\n\tTag:         v1.8.3\n"), Some("v1.8.3"));
+        assert_eq!(parse_talos_version("Client: version unknown"), None);
+    }
+
+    #[test]
+    fn test_image_refs() {
+        let yaml = r#"
+          containers:
+            - name: operator
+              image: "tailscale/k8s-operator:v1.80.0"
+              env:
+                - name: PROXY_IMAGE
+                  value: tailscale/tailscale:v1.80.0
+                - name: OTHER
+                  value: not-an-image
+            - image: '{{ .Values.image }}'
+        data:
+          helperPod.yaml: |-
+            spec:
+              containers:
+              - name: helper-pod
+                image: busybox
+        "#;
+        assert_eq!(
+            image_refs(yaml),
+            ["tailscale/k8s-operator:v1.80.0", "tailscale/tailscale:v1.80.0", "busybox"]
+        );
+    }
+
+    #[test]
+    fn test_split_image() {
+        assert_eq!(split_image("busybox"), ("docker.io", "library/busybox".to_string()));
+        assert_eq!(
+            split_image("rancher/local-path-provisioner:v0.0.30"),
+            ("docker.io", "rancher/local-path-provisioner:v0.0.30".to_string())
+        );
+        assert_eq!(
+            split_image("registry.k8s.io/coredns/coredns:v1.12.0"),
+            ("registry.k8s.io", "coredns/coredns:v1.12.0".to_string())
+        );
+        assert_eq!(
+            split_image("ghcr.io/siderolabs/flannel:v0.26.1@sha256:abc"),
+            ("ghcr.io", "siderolabs/flannel:v0.26.1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pin_kubernetes() {
+        assert_eq!(
+            pin_kubernetes("registry.k8s.io/kube-apiserver:v1.33.1"),
+            format!("registry.k8s.io/kube-apiserver:v{KUBERNETES_VERSION}")
+        );
+        assert_eq!(
+            pin_kubernetes("ghcr.io/siderolabs/kubelet:v1.33.1"),
+            format!("ghcr.io/siderolabs/kubelet:v{KUBERNETES_VERSION}")
+        );
+        assert_eq!(pin_kubernetes("registry.k8s.io/pause:3.10"), "registry.k8s.io/pause:3.10");
+    }
+}
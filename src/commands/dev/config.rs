@@ -0,0 +1,333 @@
+//! Config command for dev cluster.
+//!
+//! Stores local overrides for the dev deployment (e.g., `engine.replicas=2`)
+//! and renders them as a kustomize overlay on top of the deploy repository,
+//! so `dev start` and `dev reset` pick them up without editing the clone.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use super::{
+    constants::{
+        DEPLOYMENT_CONTROL, DEPLOYMENT_DASHBOARD, DEPLOYMENT_ENGINE, DEV_KUSTOMIZE_DIR,
+        DEV_OVERLAY_DIR, INFERADB_NAMESPACE, STATEFULSET_LEDGER,
+    },
+    paths::get_dev_values_file,
+};
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    output::Displayable,
+};
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Run dev config set - store one or more `key=value` overrides.
+pub async fn set(ctx: &Context, assignments: &[String]) -> Result<()> {
+    let mut values = load_values()?;
+    for assignment in assignments {
+        let (path, value) = parse_assignment(assignment)?;
+        set_path(&mut values, &path, value);
+    }
+    save_values(&values)?;
+    ctx.output.success(
+        "Dev config updated. Applied on next 'inferadb dev start' or 'inferadb dev reset'.",
+    );
+    Ok(())
+}
+
+/// Run dev config unset - remove overrides by key.
+pub async fn unset(ctx: &Context, keys: &[String]) -> Result<()> {
+    let mut values = load_values()?;
+    for key in keys {
+        let path = parse_key(key)?;
+        if !remove_path(&mut values, &path) {
+            ctx.output.warn(&format!("Key not set: {key}"));
+        }
+    }
+    save_values(&values)?;
+    Ok(())
+}
+
+/// Run dev config list - show all overrides.
+pub async fn list(ctx: &Context) -> Result<()> {
+    let values = load_values()?;
+    let mut entries = Vec::new();
+    flatten(&values, "", &mut entries);
+
+    if entries.is_empty() {
+        ctx.output.info("No dev config overrides set.");
+        return Ok(());
+    }
+    ctx.output.table(&entries)
+}
+
+/// Resolve the kustomization directory to apply for the dev deployment.
+///
+/// Writes the overlay when overrides exist and returns its path; otherwise
+/// removes any stale overlay and returns the deploy repository's directory.
+pub fn kustomize_dir(deploy_dir: &Path) -> Result<PathBuf> {
+    let values = load_values()?;
+    let overlay_dir = deploy_dir.join(DEV_OVERLAY_DIR);
+
+    if values.is_empty() {
+        if overlay_dir.exists() {
+            fs::remove_dir_all(&overlay_dir)?;
+        }
+        return Ok(deploy_dir.join(DEV_KUSTOMIZE_DIR));
+    }
+
+    fs::create_dir_all(&overlay_dir)?;
+    fs::write(overlay_dir.join("kustomization.yaml"), render_overlay(&values)?)?;
+    Ok(overlay_dir)
+}
+
+// ============================================================================
+// Implementation
+// ============================================================================
+
+/// A single flattened override, for display.
+#[derive(Debug, Clone, Serialize)]
+struct ConfigEntry {
+    key: String,
+    value: String,
+}
+
+impl Displayable for ConfigEntry {
+    fn table_row(&self) -> Vec<String> {
+        vec![self.key.clone(), self.value.clone()]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["KEY", "VALUE"]
+    }
+}
+
+/// Workload kind and name targeted by a top-level key.
+fn workload(component: &str) -> Option<(&'static str, &'static str)> {
+    match component {
+        "engine" => Some(("Deployment", DEPLOYMENT_ENGINE)),
+        "control" => Some(("Deployment", DEPLOYMENT_CONTROL)),
+        "dashboard" => Some(("Deployment", DEPLOYMENT_DASHBOARD)),
+        "ledger" => Some(("StatefulSet", STATEFULSET_LEDGER)),
+        _ => None,
+    }
+}
+
+/// Split and validate a dotted key (e.g., `engine.replicas`).
+fn parse_key(key: &str) -> Result<Vec<String>> {
+    let path: Vec<String> = key.split('.').map(str::to_string).collect();
+    if path.len() < 2 || path.iter().any(String::is_empty) {
+        return Err(Error::invalid_arg(format!(
+            "Invalid key '{key}'. Use <component>.<field>, e.g. engine.replicas"
+        )));
+    }
+    if workload(&path[0]).is_none() {
+        return Err(Error::invalid_arg(format!(
+            "Unknown component '{}'. Use: engine, control, dashboard, ledger",
+            path[0]
+        )));
+    }
+    Ok(path)
+}
+
+/// Parse a `key=value` assignment, interpreting the value as a YAML scalar.
+fn parse_assignment(assignment: &str) -> Result<(Vec<String>, Value)> {
+    let (key, raw) = assignment.split_once('=').ok_or_else(|| {
+        Error::invalid_arg(format!("Invalid assignment '{assignment}'. Use key=value"))
+    })?;
+    let path = parse_key(key.trim())?;
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err(Error::invalid_arg(format!("Missing value for '{key}'")));
+    }
+    let value = serde_yaml::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+    Ok((path, value))
+}
+
+/// Set a value at a nested path, creating intermediate mappings.
+fn set_path(map: &mut Mapping, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = map;
+    for segment in parents {
+        let entry = current
+            .entry(Value::String(segment.clone()))
+            .or_insert_with(|| Value::Mapping(Mapping::new()));
+        if !entry.is_mapping() {
+            *entry = Value::Mapping(Mapping::new());
+        }
+        let Value::Mapping(next) = entry else {
+            return;
+        };
+        current = next;
+    }
+    current.insert(Value::String(last.clone()), value);
+}
+
+/// Remove a value at a nested path, pruning emptied parents.
+///
+/// Returns whether the key was present.
+fn remove_path(map: &mut Mapping, path: &[String]) -> bool {
+    let Some((first, rest)) = path.split_first() else {
+        return false;
+    };
+    let key = Value::String(first.clone());
+    if rest.is_empty() {
+        return map.remove(&key).is_some();
+    }
+    let Some(Value::Mapping(child)) = map.get_mut(&key) else {
+        return false;
+    };
+    let removed = remove_path(child, rest);
+    if child.is_empty() {
+        map.remove(&key);
+    }
+    removed
+}
+
+/// Flatten nested overrides into dotted `key = value` entries.
+fn flatten(map: &Mapping, prefix: &str, entries: &mut Vec<ConfigEntry>) {
+    for (key, value) in map {
+        let key = key.as_str().unwrap_or_default();
+        let key = if prefix.is_empty() { key.to_string() } else { format!("{prefix}.{key}") };
+        match value {
+            Value::Mapping(child) => flatten(child, &key, entries),
+            Value::String(s) => entries.push(ConfigEntry { key, value: s.clone() }),
+            other => entries.push(ConfigEntry {
+                key,
+                value: serde_yaml::to_string(other).unwrap_or_default().trim().to_string(),
+            }),
+        }
+    }
+}
+
+/// Render the overlay kustomization with one patch per overridden workload.
+fn render_overlay(values: &Mapping) -> Result<String> {
+    let mut patches = Vec::new();
+    for (component, spec) in values {
+        let Some((kind, name)) = component.as_str().and_then(workload) else {
+            continue;
+        };
+        let mut metadata = Mapping::new();
+        metadata.insert("name".into(), name.into());
+        metadata.insert("namespace".into(), INFERADB_NAMESPACE.into());
+
+        let mut patch = Mapping::new();
+        patch.insert("apiVersion".into(), "apps/v1".into());
+        patch.insert("kind".into(), kind.into());
+        patch.insert("metadata".into(), Value::Mapping(metadata));
+        patch.insert("spec".into(), spec.clone());
+
+        let mut target = Mapping::new();
+        target.insert("kind".into(), kind.into());
+        target.insert("name".into(), name.into());
+
+        let mut entry = Mapping::new();
+        entry.insert("target".into(), Value::Mapping(target));
+        entry.insert("patch".into(), serde_yaml::to_string(&patch)?.into());
+        patches.push(Value::Mapping(entry));
+    }
+
+    let mut kustomization = Mapping::new();
+    kustomization.insert("apiVersion".into(), "kustomize.config.k8s.io/v1beta1".into());
+    kustomization.insert("kind".into(), "Kustomization".into());
+    kustomization.insert("resources".into(), Value::Sequence(vec!["../dev".into()]));
+    kustomization.insert("patches".into(), Value::Sequence(patches));
+
+    Ok(format!(
+        "# Auto-generated by inferadb dev config\n{}",
+        serde_yaml::to_string(&kustomization)?
+    ))
+}
+
+/// Load stored overrides, or an empty mapping if none are set.
+fn load_values() -> Result<Mapping> {
+    let path = get_dev_values_file();
+    if !path.exists() {
+        return Ok(Mapping::new());
+    }
+    let contents = fs::read_to_string(&path)?;
+    if contents.trim().is_empty() {
+        return Ok(Mapping::new());
+    }
+    serde_yaml::from_str(&contents)
+        .map_err(|e| Error::config(format!("Invalid dev config file {}: {e}", path.display())))
+}
+
+/// Persist overrides, removing the file once none remain.
+fn save_values(values: &Mapping) -> Result<()> {
+    let path = get_dev_values_file();
+    if values.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_yaml::to_string(values)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_assignment() {
+        let (path, value) = parse_assignment("engine.replicas=2").unwrap();
+        assert_eq!(path, vec!["engine", "replicas"]);
+        assert_eq!(value, Value::from(2));
+
+        let (_, value) = parse_assignment("control.image=ghcr.io/x:1").unwrap();
+        assert_eq!(value, Value::from("ghcr.io/x:1"));
+    }
+
+    #[test]
+    fn test_parse_assignment_rejects_invalid() {
+        assert!(parse_assignment("engine.replicas").is_err());
+        assert!(parse_assignment("replicas=2").is_err());
+        assert!(parse_assignment("unknown.replicas=2").is_err());
+        assert!(parse_assignment("engine..replicas=2").is_err());
+        assert!(parse_assignment("engine.replicas=").is_err());
+    }
+
+    #[test]
+    fn test_set_and_remove_path() {
+        let mut values = Mapping::new();
+        let path = parse_key("ledger.template.spec.priority").unwrap();
+        set_path(&mut values, &path, Value::from(5));
+
+        let mut entries = Vec::new();
+        flatten(&values, "", &mut entries);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "ledger.template.spec.priority");
+        assert_eq!(entries[0].value, "5");
+
+        assert!(remove_path(&mut values, &path));
+        assert!(values.is_empty());
+        assert!(!remove_path(&mut values, &path));
+    }
+
+    #[test]
+    fn test_render_overlay() {
+        let mut values = Mapping::new();
+        set_path(&mut values, &parse_key("engine.replicas").unwrap(), Value::from(2));
+
+        let rendered = render_overlay(&values).unwrap();
+        assert!(rendered.contains("- ../dev"));
+        assert!(rendered.contains(&format!("name: {DEPLOYMENT_ENGINE}")));
+        assert!(rendered.contains("replicas: 2"));
+    }
+}
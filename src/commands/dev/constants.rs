@@ -5,6 +5,7 @@ pub const CLUSTER_NAME: &str = "inferadb-dev";
 pub const KUBE_CONTEXT: &str = "admin@inferadb-dev";
 pub const REGISTRY_NAME: &str = "inferadb-registry";
 pub const REGISTRY_PORT: u16 = 5050;
+pub const REGISTRY_IMAGE: &str = "registry:2";
 
 /// Prefix for Tailscale devices created by dev environment ingress resources
 pub const TAILSCALE_DEVICE_PREFIX: &str = "inferadb-dev-";
@@ -15,6 +16,18 @@ pub const ENGINE_REPO_URL: &str = "https://github.com/inferadb/engine.git";
 pub const CONTROL_REPO_URL: &str = "https://github.com/inferadb/control.git";
 pub const DASHBOARD_REPO_URL: &str = "https://github.com/inferadb/dashboard.git";
 
+// Published container images
+pub const GHCR_IMAGE_PREFIX: &str = "ghcr.io/inferadb";
+pub const DEFAULT_IMAGE_TAG: &str = "latest";
+
+/// `InferaDB` component image names
+pub const COMPONENT_IMAGES: &[&str] =
+    &["inferadb-engine", "inferadb-control", "inferadb-dashboard"];
+
+// Kustomize directories within the deploy repository
+pub const DEV_KUSTOMIZE_DIR: &str = "flux/apps/dev";
+pub const DEV_OVERLAY_DIR: &str = "flux/apps/dev-local";
+
 // Kubernetes namespace
 pub const INFERADB_NAMESPACE: &str = "inferadb";
 
@@ -22,6 +35,27 @@ pub const INFERADB_NAMESPACE: &str = "inferadb";
 pub const DEPLOYMENT_ENGINE: &str = "dev-inferadb-engine";
 pub const DEPLOYMENT_CONTROL: &str = "dev-inferadb-control";
 pub const DEPLOYMENT_DASHBOARD: &str = "dev-inferadb-dashboard";
+pub const STATEFULSET_LEDGER: &str = "dev-inferadb-ledger";
+
+// Engine API service and port, for `dev start --expose-api`
+pub const SERVICE_ENGINE: &str = "dev-inferadb-engine";
+pub const ENGINE_API_PORT: u16 = 8080;
+
+// Ingress serving the Mailpit UI and API
+pub const INGRESS_MAILPIT: &str = "dev-inferadb-mailpit-tailscale";
+
+// Secret seeded by the dev deployment with the bootstrap org, vault and token
+pub const SECRET_BOOTSTRAP: &str = "dev-inferadb-bootstrap";
+
+// Secret holding the dev engine's token signing key (`private.pem`, `kid`)
+pub const SECRET_SIGNING_KEY: &str = "dev-inferadb-signing-key";
+
+// Issuer and audience the dev engine accepts on tokens from `dev token`
+pub const DEV_TOKEN_ISSUER: &str = "inferadb-dev";
+pub const DEV_TOKEN_AUDIENCE: &str = "inferadb-engine";
+
+/// Profile created by `dev start` for the local cluster
+pub const DEV_PROFILE: &str = "dev";
 
 /// All `InferaDB` deployments for iteration
 pub const INFERADB_DEPLOYMENTS: &[&str] =
@@ -35,10 +69,24 @@ pub const TALOS_WORKERS: &str = "1";
 pub const TALOS_CONTROLPLANES: &str = "1";
 pub const TALOS_PROVISIONER: &str = "docker";
 pub const TALOS_WAIT_TIMEOUT: &str = "10m";
+pub const TALOS_UPGRADE_TIMEOUT: &str = "30m";
+pub const TALOS_INSTALLER_IMAGE: &str = "ghcr.io/siderolabs/installer";
+
+/// The host as seen from Talos nodes on the docker provisioner's default
+/// 10.5.0.0/24 network, where the bundled registry's published port is reached
+pub const TALOS_HOST_GATEWAY: &str = "10.5.0.1";
+
+/// Kubernetes images whose tag follows `KUBERNETES_VERSION`
+pub const KUBERNETES_IMAGES: &[&str] =
+    &["kube-apiserver", "kube-controller-manager", "kube-scheduler", "kube-proxy", "kubelet"];
 
 // Helm repositories
 pub const HELM_TAILSCALE_REPO: &str = "tailscale";
 pub const HELM_TAILSCALE_URL: &str = "https://pkgs.tailscale.com/helmcharts";
+pub const HELM_TAILSCALE_CHART: &str = "tailscale/tailscale-operator";
+
+// Storage provisioner manifest
+pub const LOCAL_PATH_PROVISIONER_URL: &str = "https://raw.githubusercontent.com/rancher/local-path-provisioner/v0.0.26/deploy/local-path-storage.yaml";
 
 // Tip messages
 pub const TIP_START_CLUSTER: &str = "Run 'inferadb dev start' to start the cluster";
@@ -66,6 +66,37 @@ where
         .unwrap_or_default()
 }
 
+/// Get a Secret in the `InferaDB` namespace, if it exists.
+pub fn get_secret(name: &str) -> Option<serde_json::Value> {
+    kubectl_get_json(&format!("secret/{name}"), INFERADB_NAMESPACE)
+}
+
+/// Decode one key of a Secret's base64 `data` as UTF-8.
+pub fn secret_field(secret: &serde_json::Value, key: &str) -> Option<String> {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    let encoded = secret.get("data")?.get(key)?.as_str()?;
+    String::from_utf8(STANDARD.decode(encoded).ok()?).ok()
+}
+
+/// Get the hostname a Tailscale ingress is served at, once assigned.
+pub fn ingress_hostname(name: &str) -> Option<String> {
+    let hostname = run_command_optional(
+        "kubectl",
+        &[
+            "get",
+            "ingress",
+            name,
+            "-n",
+            INFERADB_NAMESPACE,
+            "-o",
+            "jsonpath={.status.loadBalancer.ingress[0].hostname}",
+        ],
+    )?;
+    let hostname = hostname.trim();
+    (!hostname.is_empty()).then(|| hostname.to_string())
+}
+
 /// Get the current kubectl context.
 pub fn kubectl_current_context() -> Option<String> {
     run_command_optional("kubectl", &["config", "current-context"]).map(|s| s.trim().to_string())
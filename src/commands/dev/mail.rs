@@ -0,0 +1,209 @@
+//! Mail commands for dev cluster.
+//!
+//! The dev deployment runs Mailpit to capture outgoing email (invitations,
+//! verification and password reset messages). These commands read its API
+//! through the Tailscale ingress, so auth flows can be tested end to end.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{constants::INGRESS_MAILPIT, kubernetes::ingress_hostname, open_in_browser};
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    output::{Displayable, dates},
+};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A Mailpit address.
+#[derive(Debug, Clone, Deserialize)]
+struct Address {
+    #[serde(rename = "Address")]
+    address: String,
+}
+
+/// A message in Mailpit's message list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MessageSummary {
+    #[serde(rename = "ID")]
+    id: String,
+    from: Option<Address>,
+    #[serde(default)]
+    to: Vec<Address>,
+    subject: String,
+    created: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessagesResponse {
+    messages: Vec<MessageSummary>,
+}
+
+/// A full Mailpit message; only the plain text body is needed.
+#[derive(Debug, Deserialize)]
+struct Message {
+    #[serde(rename = "Text", default)]
+    text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MailRow {
+    id: String,
+    received: chrono::DateTime<chrono::Utc>,
+    from: String,
+    to: Vec<String>,
+    subject: String,
+    links: Vec<String>,
+}
+
+impl Displayable for MailRow {
+    fn table_row(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            dates::format_precise(self.received),
+            self.to.join(", "),
+            self.subject.clone(),
+            self.links.first().cloned().unwrap_or_else(|| "-".to_string()),
+        ]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["ID", "RECEIVED", "TO", "SUBJECT", "LINK"]
+    }
+}
+
+/// Base URL of the cluster's Mailpit.
+fn mailpit_url() -> Result<String> {
+    ingress_hostname(INGRESS_MAILPIT)
+        .map(|host| format!("https://{host}"))
+        .ok_or_else(|| Error::other("Mailpit ingress not found. Is the cluster running?"))
+}
+
+async fn get<T: DeserializeOwned>(http: &reqwest::Client, url: &str) -> Result<T> {
+    let response = http
+        .get(url)
+        .timeout(TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| Error::other(format!("Mailpit request failed: {e}")))?;
+    if !response.status().is_success() {
+        return Err(Error::other(format!("Mailpit returned HTTP {}", response.status())));
+    }
+    response.json().await.map_err(|e| Error::other(format!("Invalid Mailpit response: {e}")))
+}
+
+/// Links in a message body, in order, without duplicates.
+fn extract_links(text: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    for word in text.split(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'')) {
+        let Some(start) = word.find("https://").or_else(|| word.find("http://")) else {
+            continue;
+        };
+        let link = word[start..].trim_end_matches(['.', ',', ';', ')', ']']);
+        if !links.iter().any(|l| l == link) {
+            links.push(link.to_string());
+        }
+    }
+    links
+}
+
+/// List captured emails, newest first, with the links each contains.
+pub async fn list(ctx: &Context, limit: u32) -> Result<()> {
+    let base = mailpit_url()?;
+    let http = reqwest::Client::new();
+    let response: MessagesResponse =
+        get(&http, &format!("{base}/api/v1/messages?limit={limit}")).await?;
+
+    if response.messages.is_empty() {
+        ctx.output.info("No emails captured.");
+        return Ok(());
+    }
+
+    let mut rows = Vec::with_capacity(response.messages.len());
+    for summary in response.messages {
+        let message: Message = get(&http, &format!("{base}/api/v1/message/{}", summary.id)).await?;
+        rows.push(MailRow {
+            received: summary.created,
+            from: summary.from.map(|a| a.address).unwrap_or_default(),
+            to: summary.to.into_iter().map(|a| a.address).collect(),
+            subject: summary.subject,
+            links: extract_links(&message.text),
+            id: summary.id,
+        });
+    }
+    ctx.output.table(&rows)
+}
+
+/// Open Mailpit, or one captured email, in the browser.
+pub async fn open(ctx: &Context, id: Option<&str>) -> Result<()> {
+    let base = mailpit_url()?;
+    let url = match id {
+        Some(id) => format!("{base}/view/{id}"),
+        None => base,
+    };
+    ctx.output.info(&format!("Opening Mailpit: {url}"));
+    open_in_browser(&url);
+    Ok(())
+}
+
+/// Delete every captured email.
+pub async fn clear(ctx: &Context) -> Result<()> {
+    let base = mailpit_url()?;
+    if !ctx.confirm("Delete all captured emails?")? {
+        ctx.output.info("Cancelled.");
+        return Ok(());
+    }
+
+    let response = reqwest::Client::new()
+        .delete(format!("{base}/api/v1/messages"))
+        .timeout(TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| Error::other(format!("Mailpit request failed: {e}")))?;
+    if !response.status().is_success() {
+        return Err(Error::other(format!("Mailpit returned HTTP {}", response.status())));
+    }
+    ctx.output.success("Deleted all captured emails.");
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_links() {
+        let text = "Welcome!\n\nVerify your email: https://dashboard.example/verify?token=abc.\n\
+                    (or visit <https://dashboard.example/verify?token=abc>)\n\
+                    Docs: http://docs.example/start, thanks";
+        assert_eq!(
+            extract_links(text),
+            ["https://dashboard.example/verify?token=abc", "http://docs.example/start"]
+        );
+        assert!(extract_links("no links here").is_empty());
+    }
+
+    #[test]
+    fn test_parse_messages() {
+        let json = serde_json::json!({
+            "total": 1,
+            "messages": [{
+                "ID": "abc",
+                "From": { "Name": "", "Address": "noreply@inferadb.com" },
+                "To": [{ "Name": "Alice", "Address": "alice@example.com" }],
+                "Subject": "Verify your email",
+                "Created": "2026-10-16T12:00:00.000Z",
+                "Snippet": "Verify"
+            }]
+        });
+        let response: MessagesResponse = serde_json::from_value(json).unwrap();
+        let message = &response.messages[0];
+        assert_eq!(message.id, "abc");
+        assert_eq!(message.to[0].address, "alice@example.com");
+        assert_eq!(message.subject, "Verify your email");
+    }
+}
@@ -5,32 +5,46 @@
 //!
 //! # Module Structure
 //!
+//! - `bundle` - Air-gapped artifact bundles
 //! - `commands` - Shell command wrappers
+//! - `config` - Deployment override commands
 //! - `constants` - Cluster configuration constants
 //! - `docker` - Docker container operations
 //! - `doctor` - Environment checking
 //! - `kubernetes` - Kubernetes/kubectl abstractions
+//! - `mail` - Captured email (Mailpit) commands
 //! - `output` - Output formatting utilities
 //! - `paths` - Path helpers
+//! - `port_forward` - Local port-forward for the engine API
+//! - `profile` - The `dev` profile for the local cluster
 //! - `reset` - Reset command implementation
 //! - `start` - Start command implementation
 //! - `status` - Status command implementation
 //! - `stop` - Stop command implementation
 //! - `tailscale` - Tailscale credential handling
+//! - `token` - Local test token minting
+//! - `upgrade` - Upgrade command implementation
 
 // Submodules
+pub mod bundle;
 pub mod commands;
+pub mod config;
 pub mod constants;
 pub mod docker;
 pub mod doctor;
 pub mod kubernetes;
+pub mod mail;
 pub mod output;
 pub mod paths;
+pub mod port_forward;
+mod profile;
 mod reset;
 mod start;
 mod status;
 mod stop;
 pub mod tailscale;
+pub mod token;
+mod upgrade;
 
 // Re-export public items from submodules for convenience
 use std::process::Command;
@@ -59,9 +73,44 @@ pub async fn start(
     tailscale_secret: Option<String>,
     #[builder(default)] force: bool,
     commit: Option<&str>,
+    #[builder(default)] from_registry: bool,
+    tag: Option<&str>,
+    bundle: Option<&std::path::Path>,
+    expose_api: Option<&str>,
 ) -> Result<()> {
-    start::start(ctx, skip_build, interactive, tailscale_client, tailscale_secret, force, commit)
-        .await
+    if let Some(address) = expose_api {
+        port_forward::parse_address(address)?;
+    }
+
+    start::start(
+        ctx,
+        skip_build,
+        interactive,
+        tailscale_client,
+        tailscale_secret,
+        force,
+        commit,
+        from_registry.then(|| tag.unwrap_or(constants::DEFAULT_IMAGE_TAG)),
+        bundle,
+    )
+    .await?;
+
+    if let Some(address) = port_forward::expose(expose_api)? {
+        output::print_hint(&format!(
+            "Engine API forwarded to http://{address} (restarted automatically)"
+        ));
+    }
+
+    // The cluster is usable without the profile, so a failure is only a warning
+    match profile::refresh(ctx) {
+        Ok(url) => output::print_hint(&format!(
+            "Profile '{}' points at {url}; try 'inferadb @{} check ...'",
+            constants::DEV_PROFILE,
+            constants::DEV_PROFILE
+        )),
+        Err(e) => ctx.output.warn(&format!("Could not set up the dev profile: {e}")),
+    }
+    Ok(())
 }
 
 /// Run dev stop - pause or destroy the cluster.
@@ -86,6 +135,18 @@ pub async fn reset(ctx: &Context, yes: bool) -> Result<()> {
     reset::reset(ctx, yes).await
 }
 
+/// Run dev upgrade - upgrade Talos/Kubernetes in place.
+#[builder]
+pub async fn upgrade(
+    ctx: &Context,
+    kubernetes_version: Option<&str>,
+    talos_version: Option<&str>,
+    #[builder(default)] skip_snapshot: bool,
+    #[builder(default)] yes: bool,
+) -> Result<()> {
+    upgrade::upgrade(ctx, kubernetes_version, talos_version, skip_snapshot, yes).await
+}
+
 /// Run dev logs - view logs.
 pub async fn logs(_ctx: &Context, follow: bool, service: Option<&str>, tail: u32) -> Result<()> {
     if !cluster_exists() {
@@ -121,40 +182,29 @@ pub async fn logs(_ctx: &Context, follow: bool, service: Option<&str>, tail: u32
 
 /// Run dev dashboard - open dashboard in browser.
 pub async fn dashboard(_ctx: &Context) -> Result<()> {
-    use commands::run_command_optional;
-
-    // Get dashboard URL from ingress
-    let hostname = run_command_optional(
-        "kubectl",
-        &[
-            "get",
-            "ingress",
-            "dev-inferadb-dashboard-tailscale",
-            "-n",
-            "inferadb",
-            "-o",
-            "jsonpath={.status.loadBalancer.ingress[0].hostname}",
-        ],
-    );
-
-    match hostname {
-        Some(h) if !h.is_empty() => {
-            let url = format!("https://{}", h.trim());
-            println!("Opening dashboard: {url}");
-            #[cfg(target_os = "macos")]
-            {
-                let _ = Command::new("open").arg(&url).spawn();
-            }
-            #[cfg(target_os = "linux")]
-            {
-                let _ = Command::new("xdg-open").arg(&url).spawn();
-            }
-            #[cfg(target_os = "windows")]
-            {
-                let _ = Command::new("cmd").args(["/C", "start", &url]).spawn();
-            }
-            Ok(())
-        },
-        _ => Err(Error::Other("Dashboard ingress not found. Is the cluster running?".to_string())),
+    let Some(hostname) = kubernetes::ingress_hostname("dev-inferadb-dashboard-tailscale") else {
+        return Err(Error::Other(
+            "Dashboard ingress not found. Is the cluster running?".to_string(),
+        ));
+    };
+    let url = format!("https://{hostname}");
+    println!("Opening dashboard: {url}");
+    open_in_browser(&url);
+    Ok(())
+}
+
+/// Open a URL in the default browser, ignoring failures.
+fn open_in_browser(url: &str) {
+    #[cfg(target_os = "macos")]
+    {
+        let _ = Command::new("open").arg(url).spawn();
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let _ = Command::new("xdg-open").arg(url).spawn();
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let _ = Command::new("cmd").args(["/C", "start", url]).spawn();
     }
 }
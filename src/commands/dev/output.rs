@@ -2,24 +2,28 @@
 //!
 //! Provides consistent formatting for step output, dot leaders, and status messages.
 
+use std::time::Instant;
+
 use bon::Builder;
 use teapot::style::RESET;
 
 use super::constants::STEP_LINE_WIDTH;
 use crate::{
     error::{Error, Result},
-    tui::start_spinner,
+    output::{
+        glyphs,
+        theme::{self, Role},
+        timings,
+    },
+    tui::{accessibility_enabled, start_spinner},
 };
 
 // ============================================================================
-// Color Constants (using Teapot's Color for consistency)
+// Colors (status colors come from the active theme)
 // ============================================================================
 
-// Static color strings cached for performance (Color::*.to_ansi_fg() allocates)
+// Static color string cached for performance (Color::*.to_ansi_fg() allocates)
 const DIM_ANSI: &str = "\x1b[90m"; // Color::BrightBlack
-const GREEN_ANSI: &str = "\x1b[32m"; // Color::Green
-const YELLOW_ANSI: &str = "\x1b[33m"; // Color::Yellow
-const RED_ANSI: &str = "\x1b[31m"; // Color::Red
 
 /// Get ANSI escape code for dim/gray text.
 #[inline]
@@ -27,22 +31,22 @@ const fn dim() -> &'static str {
     DIM_ANSI
 }
 
-/// Get ANSI escape code for green text.
+/// Get ANSI escape code for success (green by default) text.
 #[inline]
-const fn green() -> &'static str {
-    GREEN_ANSI
+fn green() -> &'static str {
+    theme::current().ansi(Role::Success)
 }
 
-/// Get ANSI escape code for yellow text.
+/// Get ANSI escape code for warning (yellow by default) text.
 #[inline]
-const fn yellow() -> &'static str {
-    YELLOW_ANSI
+fn yellow() -> &'static str {
+    theme::current().ansi(Role::Warning)
 }
 
-/// Get ANSI escape code for red text.
+/// Get ANSI escape code for error (red by default) text.
 #[inline]
-const fn red() -> &'static str {
-    RED_ANSI
+fn red() -> &'static str {
+    theme::current().ansi(Role::Error)
 }
 
 /// Get ANSI reset code.
@@ -164,15 +168,22 @@ fn color_status(status: &str) -> String {
 // ============================================================================
 
 /// Format a line with dot leaders using the configuration.
+///
+/// In accessibility mode, the prefix glyph, dots, and colors are dropped in
+/// favor of `{text}: {status}`.
 pub fn format_dot_leader_config(config: &DotLeaderConfig<'_>) -> String {
-    let status_display = if config.auto_color_status {
-        color_status(config.status)
-    } else {
-        config.status.to_string()
-    };
+    if accessibility_enabled() {
+        return format!("{}: {}", config.text, config.status);
+    }
+
+    let text = glyphs::text(config.text);
+    let status = glyphs::text(config.status);
+    let status_display =
+        if config.auto_color_status { color_status(&status) } else { status.to_string() };
 
     // Calculate prefix contribution
-    let (prefix_str, prefix_visible_len) = match (config.prefix, config.prefix_color) {
+    let prefix = config.prefix.map(glyphs::text);
+    let (prefix_str, prefix_visible_len) = match (prefix, config.prefix_color) {
         (Some(p), Some(color)) => (format!("{}{}{} ", color, p, reset()), p.chars().count() + 1),
         (Some(p), None) => (format!("{}{}{} ", dim(), p, reset()), p.chars().count() + 1),
         (None, _) => (String::new(), 0),
@@ -181,12 +192,12 @@ pub fn format_dot_leader_config(config: &DotLeaderConfig<'_>) -> String {
     // Calculate dots needed
     let dots_len = STEP_LINE_WIDTH
         .saturating_sub(prefix_visible_len)
-        .saturating_sub(config.text.len())
-        .saturating_sub(visible_len(config.status))
+        .saturating_sub(text.len())
+        .saturating_sub(visible_len(&status))
         .saturating_sub(2); // spaces around dots
     let dots = ".".repeat(dots_len);
 
-    format!("{}{} {}{}{} {}", prefix_str, config.text, dim(), dots, reset(), status_display)
+    format!("{}{} {}{}{} {}", prefix_str, text, dim(), dots, reset(), status_display)
 }
 
 /// Format a line with dot leaders to a status suffix.
@@ -229,13 +240,21 @@ pub fn print_colored_prefix_dot_leader(
     text: &str,
     status: &str,
 ) {
-    let status_display = color_status(status);
+    if accessibility_enabled() {
+        println!("{text}: {status}");
+        return;
+    }
+
+    let prefix_formatted = glyphs::text(prefix_formatted);
+    let text = glyphs::text(text);
+    let status = glyphs::text(status);
+    let status_display = color_status(&status);
 
     let dots_len = STEP_LINE_WIDTH
         .saturating_sub(prefix_width)
         .saturating_sub(1) // space after prefix
         .saturating_sub(text.len())
-        .saturating_sub(visible_len(status))
+        .saturating_sub(visible_len(&status))
         .saturating_sub(2);
     let dots = ".".repeat(dots_len);
 
@@ -258,12 +277,29 @@ pub fn print_styled_header(title: &str) {
 
 /// Print a section header for subsections.
 pub fn print_section_header(title: &str) {
-    println!("\n  {}{}{}", dim(), title, reset());
+    if accessibility_enabled() {
+        println!("\n  {title}");
+    } else {
+        println!("\n  {}{}{}", dim(), title, reset());
+    }
 }
 
 /// Print a hint line with a dimmed circle prefix.
 pub fn print_hint(text: &str) {
-    println!("{}○{} {}", dim(), reset(), text);
+    if accessibility_enabled() {
+        println!("Hint: {text}");
+    } else {
+        println!("{}{}{} {}", dim(), glyphs::BULLET, reset(), glyphs::text(text));
+    }
+}
+
+/// Print a completion line with a green checkmark.
+pub fn print_done(text: &str) {
+    if accessibility_enabled() {
+        println!("Done: {text}");
+    } else {
+        println!("{}{}{} {}", green(), glyphs::CHECK, reset(), glyphs::text(text));
+    }
 }
 
 /// Print a skipped destroy step in dot-leader format.
@@ -285,6 +321,15 @@ pub fn print_destroy_skipped(text: &str) {
 // Step Execution
 // ============================================================================
 
+/// Status label for a step result, as shown in the timing summary.
+const fn outcome_status(result: std::result::Result<&StepOutcome, &String>) -> &'static str {
+    match result {
+        Ok(StepOutcome::Success) => "OK",
+        Ok(StepOutcome::Skipped) => "SKIPPED",
+        Ok(StepOutcome::Failed(_)) | Err(_) => "FAILED",
+    }
+}
+
 /// Run a destroy step with spinner, then show dot-leader format on completion.
 ///
 /// Returns whether work was done (for tracking if anything was destroyed).
@@ -293,8 +338,11 @@ where
     F: FnOnce() -> std::result::Result<StepOutcome, String>,
 {
     let mut spin = start_spinner(in_progress);
+    let started = Instant::now();
+    let result = executor();
+    timings::record(completed, outcome_status(result.as_ref()), started.elapsed());
 
-    match executor() {
+    match result {
         Ok(StepOutcome::Success) => {
             spin.stop();
             println!(
@@ -328,8 +376,11 @@ where
     F: FnOnce() -> std::result::Result<StepOutcome, String>,
 {
     let spin = start_spinner(step.in_progress.clone());
+    let started = Instant::now();
+    let result = executor();
+    timings::record(&step.completed, outcome_status(result.as_ref()), started.elapsed());
 
-    match executor() {
+    match result {
         Ok(outcome) => {
             let (success_text, is_skipped) = match &outcome {
                 StepOutcome::Success => (step.completed.clone(), false),
@@ -369,8 +420,12 @@ where
     F: FnOnce() -> std::result::Result<(StepOutcome, T), String>,
 {
     let spin = start_spinner(step.in_progress.clone());
+    let started = Instant::now();
+    let result = executor();
+    let status = outcome_status(result.as_ref().map(|(outcome, _)| outcome));
+    timings::record(&step.completed, status, started.elapsed());
 
-    match executor() {
+    match result {
         Ok((outcome, value)) => {
             let (success_text, is_skipped) = match &outcome {
                 StepOutcome::Success => (step.completed.clone(), false),
@@ -404,40 +459,6 @@ where
     }
 }
 
-// ============================================================================
-// Confirmation Prompts
-// ============================================================================
-
-/// Print a confirmation prompt and get user input.
-///
-/// Returns `true` if user confirms (y/yes), `false` otherwise.
-pub fn confirm_prompt(message: &str) -> std::io::Result<bool> {
-    use std::io::{self, Write};
-
-    print!("{message} [y/N] ");
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-
-    let input = input.trim().to_lowercase();
-    Ok(input == "y" || input == "yes")
-}
-
-/// Print a warning confirmation prompt with yellow coloring.
-pub fn confirm_warning(message: &str) -> std::io::Result<bool> {
-    use std::io::{self, Write};
-
-    print!("{}{}{}. Continue? [y/N] ", yellow(), message, reset());
-    io::stdout().flush()?;
-
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-
-    let input = input.trim().to_lowercase();
-    Ok(input == "y" || input == "yes")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -33,11 +33,21 @@ pub fn get_tailscale_creds_file() -> PathBuf {
         .join("tailscale-credentials")
 }
 
+/// Get the dev deployment overrides file path (~/.config/inferadb/dev-values.yaml).
+pub fn get_dev_values_file() -> PathBuf {
+    get_config_dir().join("dev-values.yaml")
+}
+
 /// Get the state directory path (~/.local/state/inferadb).
 pub fn get_state_dir() -> PathBuf {
     Config::state_dir().unwrap_or_else(|| PathBuf::from(".local/state/inferadb"))
 }
 
+/// Get the cluster snapshots directory path (~/.local/state/inferadb/snapshots).
+pub fn get_snapshots_dir() -> PathBuf {
+    get_state_dir().join("snapshots")
+}
+
 /// Get the config directory path (~/.config/inferadb).
 pub fn get_config_dir() -> PathBuf {
     Config::config_dir().unwrap_or_else(|| PathBuf::from(".config/inferadb"))
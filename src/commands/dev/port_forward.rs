@@ -0,0 +1,228 @@
+//! Stable local port-forward for the engine API.
+//!
+//! `dev start --expose-api localhost:8080` starts a detached supervisor
+//! (`inferadb dev port-forward`) that runs `kubectl port-forward` against the
+//! engine service and restarts it whenever it exits, e.g. when the engine pod
+//! restarts. The address is remembered in the state directory, so a paused
+//! cluster gets the same port back on the next `dev start`.
+
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    constants::{ENGINE_API_PORT, INFERADB_NAMESPACE, KUBE_CONTEXT, SERVICE_ENGINE},
+    paths::get_state_dir,
+};
+use crate::{
+    config::StateLock,
+    error::{Error, Result},
+};
+
+/// Delay before restarting an exited `kubectl port-forward`.
+const RESTART_DELAY: Duration = Duration::from_secs(2);
+
+/// Persisted port-forward state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortForward {
+    /// Local address, as `host:port`.
+    pub address: String,
+    /// Supervisor process ID, while it is running.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+}
+
+fn state_file() -> PathBuf {
+    get_state_dir().join("port-forward.yaml")
+}
+
+fn log_file() -> PathBuf {
+    get_state_dir().join("port-forward.log")
+}
+
+/// Parse `host:port` or a bare port (bound to localhost).
+pub fn parse_address(address: &str) -> Result<(String, u16)> {
+    let (host, port) = match address.rsplit_once(':') {
+        Some((host, port)) => (host.trim_matches(['[', ']']), port),
+        None => ("localhost", address),
+    };
+    let port = port
+        .parse::<u16>()
+        .ok()
+        .filter(|port| *port != 0)
+        .ok_or_else(|| Error::invalid_arg(format!("Invalid port in '{address}'")))?;
+    if host.is_empty() {
+        return Err(Error::invalid_arg(format!("Missing host in '{address}'")));
+    }
+    Ok((host.to_string(), port))
+}
+
+/// Load the saved port-forward, if any.
+pub fn load() -> Option<PortForward> {
+    let contents = fs::read_to_string(state_file()).ok()?;
+    serde_yaml::from_str(&contents).ok()
+}
+
+fn save(forward: &PortForward) -> Result<()> {
+    let path = state_file();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_yaml::to_string(forward)?)?;
+    Ok(())
+}
+
+/// The running port-forward, if its supervisor is alive.
+pub fn running() -> Option<PortForward> {
+    load().filter(|forward| forward.pid.is_some_and(is_alive))
+}
+
+/// Expose the engine API at `address`, or at the saved address when
+/// `address` is `None`. Returns the address in use, if any.
+pub fn expose(address: Option<&str>) -> Result<Option<String>> {
+    let Some(address) = address.map(str::to_string).or_else(|| load().map(|f| f.address)) else {
+        return Ok(None);
+    };
+    parse_address(&address)?;
+
+    // Two `dev start`s at once must not both start a supervisor
+    let _lock = StateLock::acquire(&state_file(), "the port-forward")?;
+    if let Some(current) = running() {
+        if current.address == address {
+            return Ok(Some(address));
+        }
+        stop_supervisor();
+    }
+
+    fs::create_dir_all(get_state_dir())?;
+    let log = fs::OpenOptions::new().create(true).append(true).open(log_file())?;
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(["dev", "port-forward", &address])
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Own process group, so stopping it also stops kubectl
+        command.process_group(0);
+    }
+    let child = command
+        .spawn()
+        .map_err(|e| Error::other(format!("Failed to start port-forward supervisor: {e}")))?;
+
+    save(&PortForward { address: address.clone(), pid: Some(child.id()) })?;
+    Ok(Some(address))
+}
+
+/// Stop the supervisor, keeping the address for the next `dev start`.
+pub fn stop() {
+    // Stopping is safe to repeat, so go ahead even if the lock is busy
+    let _lock = StateLock::acquire(&state_file(), "the port-forward").ok();
+    stop_supervisor();
+}
+
+fn stop_supervisor() {
+    let Some(mut forward) = load() else {
+        return;
+    };
+    if let Some(pid) = forward.pid.take() {
+        kill(pid);
+    }
+    let _ = save(&forward);
+}
+
+/// Run `kubectl port-forward` for the engine API until the supervisor is
+/// replaced or stopped, restarting it whenever it exits.
+pub fn supervise(address: &str) -> Result<()> {
+    let (host, port) = parse_address(address)?;
+    let target = format!("svc/{SERVICE_ENGINE}");
+    let ports = format!("{port}:{ENGINE_API_PORT}");
+    let own_pid = std::process::id();
+
+    loop {
+        let status = Command::new("kubectl")
+            .args(["--context", KUBE_CONTEXT, "-n", INFERADB_NAMESPACE, "port-forward"])
+            .args(["--address", &host, &target, &ports])
+            .stdin(Stdio::null())
+            .status();
+        match status {
+            Ok(status) => eprintln!("kubectl port-forward exited ({status}); restarting"),
+            Err(e) => eprintln!("Failed to run kubectl port-forward: {e}; retrying"),
+        }
+        thread::sleep(RESTART_DELAY);
+
+        // Exit once the state no longer names this process, so a supervisor
+        // that missed its stop signal doesn't hold the port forever
+        if load().and_then(|f| f.pid) != Some(own_pid) {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_alive(pid: u32) -> bool {
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(not(unix))]
+fn is_alive(pid: u32) -> bool {
+    Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/NH"])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+}
+
+#[cfg(unix)]
+fn kill(pid: u32) {
+    // Negative PID: the supervisor's whole process group
+    let _ = Command::new("kill")
+        .args(["-TERM", "--", &format!("-{pid}")])
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(not(unix))]
+fn kill(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(parse_address("localhost:8080").unwrap(), ("localhost".to_string(), 8080));
+        assert_eq!(parse_address("127.0.0.1:9000").unwrap(), ("127.0.0.1".to_string(), 9000));
+        assert_eq!(parse_address("[::1]:8080").unwrap(), ("::1".to_string(), 8080));
+        assert_eq!(parse_address("8080").unwrap(), ("localhost".to_string(), 8080));
+        assert!(parse_address("localhost:").is_err());
+        assert!(parse_address("localhost:0").is_err());
+        assert!(parse_address(":8080").is_err());
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let forward = PortForward { address: "localhost:8080".to_string(), pid: None };
+        let yaml = serde_yaml::to_string(&forward).unwrap();
+        assert!(!yaml.contains("pid"));
+        assert_eq!(serde_yaml::from_str::<PortForward>(&yaml).unwrap(), forward);
+    }
+}
@@ -0,0 +1,100 @@
+//! The `dev` profile for the local development cluster.
+//!
+//! After `dev start` succeeds, the `dev` profile is created or refreshed to
+//! point at the cluster's API with the organization, vault and access token
+//! the deployment seeds into the `dev-inferadb-bootstrap` secret, so
+//! `inferadb @dev check ...` works without any manual setup.
+
+use super::{
+    constants::{DEV_PROFILE, INFERADB_NAMESPACE, SECRET_BOOTSTRAP},
+    kubernetes::{get_secret, secret_field},
+    port_forward,
+    tailscale::get_tailnet_info,
+};
+use crate::{
+    client::{Context, auth},
+    config::Credentials,
+    error::{Error, Result},
+};
+
+/// Bootstrap organization, vault and token seeded by the dev deployment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bootstrap {
+    pub org: String,
+    pub vault: String,
+    pub access_token: String,
+}
+
+impl Bootstrap {
+    /// Decode the `org_id`, `vault_id` and `access_token` keys of the
+    /// bootstrap secret.
+    pub fn from_secret(secret: &serde_json::Value) -> Option<Self> {
+        Some(Self {
+            org: secret_field(secret, "org_id")?,
+            vault: secret_field(secret, "vault_id")?,
+            access_token: secret_field(secret, "access_token")?,
+        })
+    }
+}
+
+/// URL of the cluster's API: the `--expose-api` port-forward when running,
+/// otherwise the Tailscale ingress.
+fn api_url() -> Option<String> {
+    if let Some(forward) = port_forward::running() {
+        return Some(format!("http://{}", forward.address));
+    }
+    get_tailnet_info().map(|suffix| format!("https://inferadb-api.{suffix}"))
+}
+
+/// Create or refresh the `dev` profile, keeping any settings the user added
+/// to it. Returns the profile's API URL.
+pub fn refresh(ctx: &Context) -> Result<String> {
+    let url =
+        api_url().ok_or_else(|| Error::other("Could not determine the dev cluster's API URL"))?;
+    let bootstrap = get_secret(SECRET_BOOTSTRAP)
+        .and_then(|secret| Bootstrap::from_secret(&secret))
+        .ok_or_else(|| {
+            Error::other(format!(
+                "Bootstrap secret '{SECRET_BOOTSTRAP}' not found in namespace \
+                 '{INFERADB_NAMESPACE}'"
+            ))
+        })?;
+
+    let mut config = ctx.config.clone();
+    let mut profile = config.get_profile(DEV_PROFILE).cloned().unwrap_or_default();
+    profile.url = Some(url.clone());
+    profile.org = Some(bootstrap.org);
+    profile.vault = Some(bootstrap.vault);
+    config.set_profile(DEV_PROFILE.to_string(), profile);
+    config.save()?;
+
+    let credentials = Credentials::builder().access_token(bootstrap.access_token).build();
+    auth::store_credentials(DEV_PROFILE, &credentials)?;
+    Ok(url)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    use super::*;
+
+    #[test]
+    fn test_bootstrap_from_secret() {
+        let secret = serde_json::json!({
+            "data": {
+                "org_id": STANDARD.encode("123"),
+                "vault_id": STANDARD.encode("456"),
+                "access_token": STANDARD.encode("dev-token"),
+            }
+        });
+        let bootstrap = Bootstrap::from_secret(&secret).unwrap();
+        assert_eq!(bootstrap.org, "123");
+        assert_eq!(bootstrap.vault, "456");
+        assert_eq!(bootstrap.access_token, "dev-token");
+
+        let partial = serde_json::json!({ "data": { "org_id": STANDARD.encode("123") } });
+        assert!(Bootstrap::from_secret(&partial).is_none());
+    }
+}
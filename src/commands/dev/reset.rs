@@ -2,19 +2,20 @@
 //!
 //! Deletes and redeploys `InferaDB` applications with fresh data.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use teapot::style::{Color, RESET};
 
 use super::{
     commands::{parse_kubectl_apply_line, run_command, run_command_optional},
+    config,
     constants::{
         CLUSTER_NAME, INFERADB_DEPLOYMENTS, INFERADB_NAMESPACE, RESOURCE_TERMINATE_DELAY_SECS,
     },
     docker::docker_container_exists,
     kubernetes::{get_inferadb_deployments, get_pvcs},
     output::{
-        confirm_warning, format_dot_leader, format_reset_dot_leader, print_prefixed_dot_leader,
+        format_dot_leader, format_reset_dot_leader, print_done, print_prefixed_dot_leader,
         print_section_header, print_styled_header,
     },
     paths::get_deploy_dir,
@@ -22,7 +23,8 @@ use super::{
 use crate::{
     client::Context,
     error::{Error, Result},
-    tui::start_spinner,
+    output::timings,
+    tui::{accessibility_enabled, start_spinner},
 };
 
 // ============================================================================
@@ -30,8 +32,8 @@ use crate::{
 // ============================================================================
 
 /// Run dev reset - reset cluster data.
-pub async fn reset(_ctx: &Context, yes: bool) -> Result<()> {
-    reset_with_spinners(yes)
+pub async fn reset(ctx: &Context, yes: bool) -> Result<()> {
+    reset_with_spinners(ctx, yes)
 }
 
 // ============================================================================
@@ -39,7 +41,7 @@ pub async fn reset(_ctx: &Context, yes: bool) -> Result<()> {
 // ============================================================================
 
 /// Reset with spinners.
-fn reset_with_spinners(yes: bool) -> Result<()> {
+fn reset_with_spinners(ctx: &Context, yes: bool) -> Result<()> {
     if !docker_container_exists(CLUSTER_NAME) {
         return Err(Error::Other(
             "Cluster is not running. Start with 'inferadb dev start'.".to_string(),
@@ -50,7 +52,7 @@ fn reset_with_spinners(yes: bool) -> Result<()> {
     let can_redeploy = deploy_dir.exists();
 
     if !yes {
-        show_reset_preview(can_redeploy)?;
+        show_reset_preview(ctx, can_redeploy)?;
     }
 
     perform_reset(can_redeploy, &deploy_dir);
@@ -58,7 +60,7 @@ fn reset_with_spinners(yes: bool) -> Result<()> {
 }
 
 /// Show what will be reset and prompt for confirmation.
-fn show_reset_preview(can_redeploy: bool) -> Result<()> {
+fn show_reset_preview(ctx: &Context, can_redeploy: bool) -> Result<()> {
     let deployments = get_inferadb_deployments();
     let pvcs = get_pvcs();
 
@@ -110,16 +112,12 @@ fn show_reset_preview(can_redeploy: bool) -> Result<()> {
     }
 
     println!();
-    match confirm_warning("This action cannot be undone") {
-        Ok(true) => {
-            println!();
-            Ok(())
-        },
-        Ok(false) => {
-            println!("Aborted.");
-            Err(Error::Other("User cancelled".to_string()))
-        },
-        Err(e) => Err(Error::Other(e.to_string())),
+    if ctx.confirm_danger("This action cannot be undone. Continue?")? {
+        println!();
+        Ok(())
+    } else {
+        println!("Aborted.");
+        Err(Error::Other("User cancelled".to_string()))
     }
 }
 
@@ -131,37 +129,45 @@ fn perform_reset(can_redeploy: bool, deploy_dir: &std::path::Path) {
     // Delete Ledger StatefulSet
     {
         let spin = start_spinner("Deleting Ledger StatefulSet");
+        let started = Instant::now();
         let _ = run_command_optional(
             "kubectl",
             &["delete", "statefulset", "--all", "-n", INFERADB_NAMESPACE],
         );
+        timings::record("Deleted Ledger StatefulSet", "OK", started.elapsed());
         spin.success(&format_dot_leader("Deleted Ledger StatefulSet", "OK"));
     }
 
     // Delete InferaDB deployments
     {
         let spin = start_spinner("Deleting InferaDB Deployments");
+        let started = Instant::now();
         for deploy in INFERADB_DEPLOYMENTS {
             let _ = run_command_optional(
                 "kubectl",
                 &["delete", "deployment", deploy, "-n", INFERADB_NAMESPACE],
             );
         }
+        timings::record("Deleted InferaDB Deployments", "OK", started.elapsed());
         spin.success(&format_dot_leader("Deleted InferaDB Deployments", "OK"));
     }
 
     // Delete PVCs
     {
         let spin = start_spinner("Deleting Persistent Volumes");
+        let started = Instant::now();
         let _ =
             run_command_optional("kubectl", &["delete", "pvc", "--all", "-n", INFERADB_NAMESPACE]);
+        timings::record("Deleted Persistent Volumes", "OK", started.elapsed());
         spin.success(&format_dot_leader("Deleted Persistent Volumes", "OK"));
     }
 
     // Wait for resources to terminate
     {
         let spin = start_spinner("Waiting for resources to terminate");
+        let started = Instant::now();
         std::thread::sleep(Duration::from_secs(RESOURCE_TERMINATE_DELAY_SECS));
+        timings::record("Resources terminated", "OK", started.elapsed());
         spin.success(&format_dot_leader("Resources terminated", "OK"));
     }
 
@@ -170,12 +176,14 @@ fn perform_reset(can_redeploy: bool, deploy_dir: &std::path::Path) {
         redeploy_applications(deploy_dir);
     }
 
-    let green = Color::Green.to_ansi_fg();
-    let dim = Color::BrightBlack.to_ansi_fg();
-    let reset_color = RESET;
     println!();
-    println!("{green}✓ Cluster reset complete.{reset_color}");
-    println!("{dim}  Applications may take a few minutes to become available.{reset_color}");
+    print_done("Cluster reset complete.");
+    if accessibility_enabled() {
+        println!("  Applications may take a few minutes to become available.");
+    } else {
+        let dim = Color::BrightBlack.to_ansi_fg();
+        println!("{dim}  Applications may take a few minutes to become available.{RESET}");
+    }
 }
 
 /// Redeploy applications from the deploy directory.
@@ -183,8 +191,12 @@ fn redeploy_applications(deploy_dir: &std::path::Path) {
     print_section_header("Redeploying Applications");
 
     let spin = start_spinner("Applying Kubernetes manifests");
-    let kustomize_path = deploy_dir.join("flux/apps/dev");
-    let apply_output = run_command("kubectl", &["apply", "-k", &kustomize_path.to_string_lossy()]);
+    let started = Instant::now();
+    let apply_output = config::kustomize_dir(deploy_dir).and_then(|kustomize_path| {
+        run_command("kubectl", &["apply", "-k", &kustomize_path.to_string_lossy()])
+    });
+    let status = if apply_output.is_ok() { "OK" } else { "FAILED" };
+    timings::record("Applied Kubernetes manifests", status, started.elapsed());
     spin.clear();
 
     apply_output.map_or_else(
@@ -205,6 +217,7 @@ fn redeploy_applications(deploy_dir: &std::path::Path) {
     // Wait for Ledger cluster
     {
         let spin = start_spinner("Waiting for Ledger cluster");
+        let started = Instant::now();
         let mut ready = false;
         for _ in 0..150 {
             if let Some(output) = run_command_optional(
@@ -226,8 +239,10 @@ fn redeploy_applications(deploy_dir: &std::path::Path) {
             std::thread::sleep(Duration::from_secs(2));
         }
         if ready {
+            timings::record("Ledger cluster ready", "OK", started.elapsed());
             spin.success(&format_dot_leader("Ledger cluster ready", "OK"));
         } else {
+            timings::record("Ledger cluster", "WAITING", started.elapsed());
             spin.success(&format_dot_leader("Ledger cluster", "WAITING (may take a few minutes)"));
         }
     }
@@ -235,10 +250,12 @@ fn redeploy_applications(deploy_dir: &std::path::Path) {
     // Restart engine to pick up new Ledger connection
     {
         let spin = start_spinner("Restarting engine to connect to Ledger");
+        let started = Instant::now();
         let _ = run_command_optional(
             "kubectl",
             &["rollout", "restart", "deployment/dev-inferadb-engine", "-n", INFERADB_NAMESPACE],
         );
+        timings::record("Engine deployment restarted", "OK", started.elapsed());
         spin.success(&format_dot_leader("Engine deployment restarted", "OK"));
     }
 }
@@ -2,17 +2,18 @@
 //!
 //! Handles creating, resuming, and setting up the local development cluster.
 
-use std::{fs, sync::Arc, time::Duration};
-
-use teapot::style::{Color, RESET};
+use std::{fs, path::Path, sync::Arc, time::Duration};
 
 use super::{
+    bundle::Bundle,
     commands::{command_exists, run_command, run_command_optional},
+    config,
     constants::{
         CLUSTER_NAME, CONTAINER_STABILIZE_DELAY_SECS, CONTROL_REPO_URL, DASHBOARD_REPO_URL,
-        DEPLOY_REPO_URL, ENGINE_REPO_URL, HELM_TAILSCALE_REPO, HELM_TAILSCALE_URL, KUBE_CONTEXT,
-        KUBERNETES_VERSION, REGISTRY_NAME, REGISTRY_PORT, TALOS_CONTROLPLANES, TALOS_PROVISIONER,
-        TALOS_WAIT_TIMEOUT, TALOS_WORKERS,
+        DEPLOY_REPO_URL, ENGINE_REPO_URL, GHCR_IMAGE_PREFIX, HELM_TAILSCALE_CHART,
+        HELM_TAILSCALE_REPO, HELM_TAILSCALE_URL, KUBE_CONTEXT, KUBERNETES_VERSION,
+        LOCAL_PATH_PROVISIONER_URL, REGISTRY_IMAGE, REGISTRY_NAME, REGISTRY_PORT,
+        TALOS_CONTROLPLANES, TALOS_PROVISIONER, TALOS_WAIT_TIMEOUT, TALOS_WORKERS,
     },
     docker::{
         are_containers_paused, docker_container_exists, get_cluster_containers, get_container_ip,
@@ -23,8 +24,8 @@ use super::{
         kubectl_current_context, kubectl_use_context,
     },
     output::{
-        StartStep, StepOutcome, print_hint, print_phase_header, print_styled_header, run_step,
-        run_step_with_result,
+        StartStep, StepOutcome, print_done, print_hint, print_phase_header, print_styled_header,
+        run_step, run_step_with_result,
     },
     paths::{get_config_dir, get_control_dir, get_dashboard_dir, get_deploy_dir, get_engine_dir},
     tailscale::{
@@ -43,14 +44,20 @@ use crate::{
 // ============================================================================
 
 /// Run dev start - create or resume local development cluster.
+///
+/// `published_tag` selects prebuilt GHCR images instead of building from source;
+/// `bundle` provisions everything from an artifact bundle created offline.
+#[allow(clippy::too_many_arguments)]
 pub async fn start(
-    _ctx: &Context,
+    ctx: &Context,
     skip_build: bool,
     interactive: bool,
     tailscale_client: Option<String>,
     tailscale_secret: Option<String>,
     force: bool,
     commit: Option<&str>,
+    published_tag: Option<&str>,
+    bundle: Option<&Path>,
 ) -> Result<()> {
     // Save CLI-provided credentials if both are present
     if let (Some(client_id), Some(client_secret)) = (&tailscale_client, &tailscale_secret)
@@ -61,12 +68,12 @@ pub async fn start(
     }
 
     // For new cluster creation, use interactive mode if requested
-    if interactive {
+    if interactive && crate::tui::is_interactive(ctx) {
         return start_interactive(skip_build, force, commit);
     }
 
     // Non-interactive mode
-    start_with_streaming(skip_build, force, commit)
+    start_with_streaming(skip_build, force, commit, published_tag, bundle)
 }
 
 // ============================================================================
@@ -167,7 +174,7 @@ fn clone_repo(
 }
 
 /// Step: Clone the deployment repository.
-fn step_clone_repo(
+pub fn step_clone_repo(
     deploy_dir: &std::path::Path,
     force: bool,
     commit: Option<&str>,
@@ -277,7 +284,7 @@ fn setup_container_registry() -> Result<String> {
                         &format!("{REGISTRY_PORT}:5000"),
                         "--restart",
                         "always",
-                        "registry:2",
+                        REGISTRY_IMAGE,
                     ],
                 )
                 .map_err(|e| e.to_string())?;
@@ -346,8 +353,11 @@ fn build_and_push_images(_registry_ip: &str) -> std::result::Result<StepOutcome,
 }
 
 /// Set up Kubernetes resources.
-#[allow(clippy::unnecessary_wraps)]
-fn setup_kubernetes_resources(_registry_ip: &str) -> std::result::Result<StepOutcome, String> {
+///
+/// `local_path_manifest` is a URL or file path for the storage provisioner.
+pub fn setup_kubernetes_resources(
+    local_path_manifest: &str,
+) -> std::result::Result<StepOutcome, String> {
     // Create namespaces
     let namespaces = ["inferadb", "local-path-storage", "tailscale-system"];
     for ns in &namespaces {
@@ -369,8 +379,7 @@ fn setup_kubernetes_resources(_registry_ip: &str) -> std::result::Result<StepOut
     }
 
     // Install local-path-provisioner for storage
-    run_command("kubectl", &["apply", "-f", "https://raw.githubusercontent.com/rancher/local-path-provisioner/v0.0.26/deploy/local-path-storage.yaml"])
-        .map_err(|e| e.to_string())?;
+    run_command("kubectl", &["apply", "-f", local_path_manifest]).map_err(|e| e.to_string())?;
     run_command("kubectl", &["patch", "storageclass", "local-path", "-p", r#"{"metadata": {"annotations":{"storageclass.kubernetes.io/is-default-class":"true"}}}"#])
         .map_err(|e| e.to_string())?;
 
@@ -382,18 +391,26 @@ fn setup_kubernetes_resources(_registry_ip: &str) -> std::result::Result<StepOut
 // ============================================================================
 
 /// Install Tailscale operator.
-fn install_tailscale_operator(
+///
+/// Uses the chart archive at `chart_path` if given, otherwise the Helm repository.
+pub fn install_tailscale_operator(
     client_id: &str,
     client_secret: &str,
+    chart_path: Option<&Path>,
 ) -> std::result::Result<StepOutcome, String> {
-    run_command("helm", &["repo", "update", "tailscale"]).map_err(|e| e.to_string())?;
+    let chart = if let Some(path) = chart_path {
+        path.to_str().ok_or_else(|| format!("Invalid path: {}", path.display()))?
+    } else {
+        run_command("helm", &["repo", "update", HELM_TAILSCALE_REPO]).map_err(|e| e.to_string())?;
+        HELM_TAILSCALE_CHART
+    };
     run_command(
         "helm",
         &[
             "upgrade",
             "--install",
             "tailscale-operator",
-            "tailscale/tailscale-operator",
+            chart,
             "--namespace",
             "tailscale-system",
             "--set",
@@ -415,11 +432,32 @@ fn install_tailscale_operator(
 // Deployment
 // ============================================================================
 
+/// Where the `InferaDB` component images are pulled from.
+enum ImageSource {
+    /// Images built from source and pushed to the local registry at this IP.
+    LocalRegistry(String),
+    /// Prebuilt images published to GHCR with this tag.
+    Published(String),
+}
+
+impl ImageSource {
+    /// Full image reference for a component (e.g., `inferadb-engine`).
+    fn image(&self, name: &str) -> String {
+        match self {
+            Self::LocalRegistry(registry_ip) => format!("{registry_ip}:5000/{name}:latest"),
+            Self::Published(tag) => format!("{GHCR_IMAGE_PREFIX}/{name}:{tag}"),
+        }
+    }
+}
+
 /// Deploy `InferaDB` applications and return tailnet suffix.
 fn deploy_inferadb(
     deploy_dir: &std::path::Path,
-    registry_ip: &str,
+    images: &ImageSource,
 ) -> std::result::Result<(StepOutcome, Option<String>), String> {
+    let engine_image = images.image("inferadb-engine");
+    let control_image = images.image("inferadb-control");
+    let dashboard_image = images.image("inferadb-dashboard");
     let registry_patch = format!(
         r"# Auto-generated by inferadb dev start
 apiVersion: apps/v1
@@ -432,7 +470,7 @@ spec:
     spec:
       containers:
         - name: inferadb-engine
-          image: {registry_ip}:5000/inferadb-engine:latest
+          image: {engine_image}
 ---
 apiVersion: apps/v1
 kind: Deployment
@@ -444,7 +482,7 @@ spec:
     spec:
       containers:
         - name: inferadb-control
-          image: {registry_ip}:5000/inferadb-control:latest
+          image: {control_image}
 ---
 apiVersion: apps/v1
 kind: Deployment
@@ -456,14 +494,14 @@ spec:
     spec:
       containers:
         - name: inferadb-dashboard
-          image: {registry_ip}:5000/inferadb-dashboard:latest
+          image: {dashboard_image}
 "
     );
 
     let patch_file = deploy_dir.join("flux/apps/dev/registry-patch.yaml");
     fs::write(&patch_file, &registry_patch).map_err(|e| e.to_string())?;
 
-    let kustomize_path = deploy_dir.join("flux/apps/dev");
+    let kustomize_path = config::kustomize_dir(deploy_dir).map_err(|e| e.to_string())?;
     let kustomize_path_str = kustomize_path
         .to_str()
         .ok_or_else(|| format!("Invalid path: {}", kustomize_path.display()))?;
@@ -482,11 +520,8 @@ spec:
 
 /// Show final success output with URLs and hints.
 fn show_final_success(tailnet_suffix: Option<&str>) {
-    let green = Color::Green.to_ansi_fg();
-    let reset = RESET;
-
     println!();
-    println!("{green}✓{reset} Development cluster ready");
+    print_done("Development cluster ready");
     println!();
 
     if let Some(suffix) = tailnet_suffix {
@@ -746,8 +781,20 @@ fn build_start_steps(
 // ============================================================================
 
 /// Start with streaming output.
+///
+/// When `published_tag` is set, component repositories are not cloned or built;
+/// the prebuilt images with that tag are deployed from GHCR instead. When
+/// `bundle` is set, the deploy repository, images, and charts all come from
+/// the bundle, and the cluster pulls images through a registry mirror serving
+/// the bundled ones.
 #[allow(clippy::too_many_lines)]
-fn start_with_streaming(skip_build: bool, force: bool, commit: Option<&str>) -> Result<()> {
+fn start_with_streaming(
+    skip_build: bool,
+    force: bool,
+    commit: Option<&str>,
+    published_tag: Option<&str>,
+    bundle: Option<&Path>,
+) -> Result<()> {
     let deploy_dir = get_deploy_dir();
 
     print_styled_header("Starting InferaDB Development Cluster");
@@ -816,31 +863,54 @@ fn start_with_streaming(skip_build: bool, force: bool, commit: Option<&str>) ->
     // Phase 1: Conditioning environment
     print_phase_header("Conditioning environment");
 
-    run_step(
-        &StartStep::with_ok("Cloning deployment repository", "Cloned deployment repository"),
-        || match step_clone_repo(&deploy_dir, force, commit) {
-            Ok(Some(_)) => Ok(StepOutcome::Skipped),
-            Ok(None) => Ok(StepOutcome::Success),
-            Err(e) => Err(e),
-        },
-    )?;
+    let bundle = bundle
+        .map(|archive| {
+            run_step_with_result(
+                &StartStep::with_ok("Extracting artifact bundle", "Extracted artifact bundle"),
+                || Bundle::extract(archive).map(|bundle| (StepOutcome::Success, bundle)),
+            )
+        })
+        .transpose()?;
 
-    let engine_dir = get_engine_dir();
-    run_step(&StartStep::with_ok("Cloning engine repository", "Cloned engine repository"), || {
-        step_clone_component("engine", ENGINE_REPO_URL, &engine_dir, force)
-    })?;
+    if let Some(bundle) = &bundle {
+        run_step(
+            &StartStep::with_ok(
+                "Installing bundled deployment repository",
+                "Installed bundled deployment repository",
+            ),
+            || Ok(StepOutcome::from(bundle.install_deploy_dir(&deploy_dir, force))),
+        )?;
+    } else {
+        run_step(
+            &StartStep::with_ok("Cloning deployment repository", "Cloned deployment repository"),
+            || match step_clone_repo(&deploy_dir, force, commit) {
+                Ok(Some(_)) => Ok(StepOutcome::Skipped),
+                Ok(None) => Ok(StepOutcome::Success),
+                Err(e) => Err(e),
+            },
+        )?;
+    }
 
-    let control_dir = get_control_dir();
-    run_step(
-        &StartStep::with_ok("Cloning control repository", "Cloned control repository"),
-        || step_clone_component("control", CONTROL_REPO_URL, &control_dir, force),
-    )?;
+    // Component sources are only needed when images are built locally
+    if published_tag.is_none() && bundle.is_none() {
+        let engine_dir = get_engine_dir();
+        run_step(
+            &StartStep::with_ok("Cloning engine repository", "Cloned engine repository"),
+            || step_clone_component("engine", ENGINE_REPO_URL, &engine_dir, force),
+        )?;
 
-    let dashboard_dir = get_dashboard_dir();
-    run_step(
-        &StartStep::with_ok("Cloning dashboard repository", "Cloned dashboard repository"),
-        || step_clone_component("dashboard", DASHBOARD_REPO_URL, &dashboard_dir, force),
-    )?;
+        let control_dir = get_control_dir();
+        run_step(
+            &StartStep::with_ok("Cloning control repository", "Cloned control repository"),
+            || step_clone_component("control", CONTROL_REPO_URL, &control_dir, force),
+        )?;
+
+        let dashboard_dir = get_dashboard_dir();
+        run_step(
+            &StartStep::with_ok("Cloning dashboard repository", "Cloned dashboard repository"),
+            || step_clone_component("dashboard", DASHBOARD_REPO_URL, &dashboard_dir, force),
+        )?;
+    }
 
     run_step(
         &StartStep::with_ok("Creating configuration directory", "Created configuration directory"),
@@ -851,28 +921,42 @@ fn start_with_streaming(skip_build: bool, force: bool, commit: Option<&str>) ->
         },
     )?;
 
-    run_step(
-        &StartStep::with_ok(
-            "Setting up Tailscale Helm repository",
-            "Set up Tailscale Helm repository",
-        ),
-        || {
-            if helm_repo_exists(HELM_TAILSCALE_REPO) {
-                return Ok(StepOutcome::Skipped);
-            }
-            helm_repo_add(HELM_TAILSCALE_REPO, HELM_TAILSCALE_URL).map(|()| StepOutcome::Success)
-        },
-    )?;
+    // Bundled charts are installed from archives, not Helm repositories
+    if bundle.is_none() {
+        run_step(
+            &StartStep::with_ok(
+                "Setting up Tailscale Helm repository",
+                "Set up Tailscale Helm repository",
+            ),
+            || {
+                if helm_repo_exists(HELM_TAILSCALE_REPO) {
+                    return Ok(StepOutcome::Skipped);
+                }
+                helm_repo_add(HELM_TAILSCALE_REPO, HELM_TAILSCALE_URL)
+                    .map(|()| StepOutcome::Success)
+            },
+        )?;
 
-    run_step(
-        &StartStep::with_ok("Updating Helm repositories", "Updated Helm repositories"),
-        || helm_repo_update().map(|()| StepOutcome::Success),
-    )?;
+        run_step(
+            &StartStep::with_ok("Updating Helm repositories", "Updated Helm repositories"),
+            || helm_repo_update().map(|()| StepOutcome::Success),
+        )?;
+    }
 
-    run_step(
-        &StartStep::with_ok("Pulling Docker registry image", "Pulled Docker registry image"),
-        || pull_image("registry:2").map(|()| StepOutcome::Success),
-    )?;
+    if let Some(bundle) = &bundle {
+        run_step(&StartStep::with_ok("Loading bundled images", "Loaded bundled images"), || {
+            bundle.load_images()
+        })?;
+        // Nodes pull through this registry, so it must be serving before they boot
+        run_step(&StartStep::with_ok("Serving bundled images", "Served bundled images"), || {
+            bundle.serve_images()
+        })?;
+    } else if published_tag.is_none() {
+        run_step(
+            &StartStep::with_ok("Pulling Docker registry image", "Pulled Docker registry image"),
+            || pull_image(REGISTRY_IMAGE).map(|()| StepOutcome::Success),
+        )?;
+    }
 
     // Phase 2: Setting up cluster
     print_phase_header("Setting up cluster");
@@ -893,6 +977,7 @@ fn start_with_streaming(skip_build: bool, force: bool, commit: Option<&str>) ->
 
     let (ts_client_id, ts_client_secret) = get_tailscale_credentials()?;
     let cluster_already_exists = docker_container_exists(CLUSTER_NAME);
+    let registry_mirrors = bundle.as_ref().map(Bundle::registry_mirrors).unwrap_or_default();
 
     run_step(&StartStep::with_ok("Cleaning stale contexts", "Cleaned stale contexts"), || {
         if cluster_already_exists {
@@ -913,27 +998,26 @@ fn start_with_streaming(skip_build: bool, force: bool, commit: Option<&str>) ->
                 }
                 return Err("Cluster containers exist but kubectl context is broken. Run 'inferadb dev stop --destroy' and try again.".to_string());
             }
-            run_command(
-                "talosctl",
-                &[
-                    "cluster",
-                    "create",
-                    "--name",
-                    CLUSTER_NAME,
-                    "--workers",
-                    TALOS_WORKERS,
-                    "--controlplanes",
-                    TALOS_CONTROLPLANES,
-                    "--provisioner",
-                    TALOS_PROVISIONER,
-                    "--kubernetes-version",
-                    KUBERNETES_VERSION,
-                    "--wait-timeout",
-                    TALOS_WAIT_TIMEOUT,
-                ],
-            )
-            .map(|_| StepOutcome::Success)
-            .map_err(|e| e.to_string())
+            let mut args = vec![
+                "cluster",
+                "create",
+                "--name",
+                CLUSTER_NAME,
+                "--workers",
+                TALOS_WORKERS,
+                "--controlplanes",
+                TALOS_CONTROLPLANES,
+                "--provisioner",
+                TALOS_PROVISIONER,
+                "--kubernetes-version",
+                KUBERNETES_VERSION,
+                "--wait-timeout",
+                TALOS_WAIT_TIMEOUT,
+            ];
+            for mirror in &registry_mirrors {
+                args.extend(["--registry-mirror", mirror.as_str()]);
+            }
+            run_command("talosctl", &args).map(|_| StepOutcome::Success).map_err(|e| e.to_string())
         },
     )?;
 
@@ -955,31 +1039,45 @@ fn start_with_streaming(skip_build: bool, force: bool, commit: Option<&str>) ->
         },
     )?;
 
-    let registry_ip = setup_container_registry()?;
+    // Bundled components are pulled by their published names through the mirror
+    let images = if let Some(tag) = published_tag {
+        ImageSource::Published(tag.to_string())
+    } else if let Some(bundle) = &bundle {
+        ImageSource::Published(bundle.manifest.tag.clone())
+    } else {
+        let registry_ip = setup_container_registry()?;
 
-    if !skip_build {
-        run_step(
-            &StartStep::with_ok(
-                "Building and pushing container images",
-                "Built and pushed container images",
-            ),
-            || build_and_push_images(&registry_ip),
-        )?;
-    }
+        if !skip_build {
+            run_step(
+                &StartStep::with_ok(
+                    "Building and pushing container images",
+                    "Built and pushed container images",
+                ),
+                || build_and_push_images(&registry_ip),
+            )?;
+        }
 
+        ImageSource::LocalRegistry(registry_ip)
+    };
+
+    let local_path_manifest = bundle.as_ref().map_or_else(
+        || LOCAL_PATH_PROVISIONER_URL.to_string(),
+        |bundle| bundle.local_path_manifest().to_string_lossy().into_owned(),
+    );
     run_step(
         &StartStep::with_ok("Setting up Kubernetes resources", "Set up Kubernetes resources"),
-        || setup_kubernetes_resources(&registry_ip),
+        || setup_kubernetes_resources(&local_path_manifest),
     )?;
 
+    let tailscale_chart = bundle.as_ref().map(Bundle::tailscale_chart);
     run_step(
         &StartStep::with_ok("Installing Tailscale operator", "Installed Tailscale operator"),
-        || install_tailscale_operator(&ts_client_id, &ts_client_secret),
+        || install_tailscale_operator(&ts_client_id, &ts_client_secret, tailscale_chart.as_deref()),
     )?;
 
     let tailnet_suffix = run_step_with_result(
         &StartStep::with_ok("Deploying InferaDB", "Deployed InferaDB"),
-        || deploy_inferadb(&deploy_dir, &registry_ip),
+        || deploy_inferadb(&deploy_dir, &images),
     )?;
 
     show_final_success(tailnet_suffix.as_deref());
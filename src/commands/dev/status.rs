@@ -4,7 +4,7 @@
 
 use std::sync::Arc;
 
-use teapot::style::{Color, RESET};
+use teapot::style::RESET;
 
 use super::{
     commands::run_command_optional,
@@ -18,6 +18,7 @@ use super::{
 use crate::{
     client::Context,
     error::{Error, Result},
+    output::theme::{self, Role},
     tui::{ClusterStatus, RefreshFn, RefreshResult, TabData},
 };
 
@@ -64,9 +65,9 @@ pub async fn dev_status(ctx: &Context, interactive: bool) -> Result<()> {
 
 /// Status with inline spinners.
 fn status_with_spinners() {
-    let green = Color::Green.to_ansi_fg();
-    let yellow = Color::Yellow.to_ansi_fg();
-    let red = Color::Red.to_ansi_fg();
+    let green = theme::current().ansi(Role::Success);
+    let yellow = theme::current().ansi(Role::Warning);
+    let red = theme::current().ansi(Role::Error);
     let reset = RESET;
 
     print_styled_header("InferaDB Development Cluster Status");
@@ -121,8 +122,8 @@ fn status_with_spinners() {
 fn print_nodes_status() {
     let output = run_command_optional("kubectl", &["get", "nodes", "-o", "json"]);
 
-    let green = Color::Green.to_ansi_fg();
-    let red = Color::Red.to_ansi_fg();
+    let green = theme::current().ansi(Role::Success);
+    let red = theme::current().ansi(Role::Error);
     let reset = RESET;
 
     if let Some(output) = output
@@ -154,9 +155,9 @@ fn print_nodes_status() {
 
 /// Print formatted pod status.
 fn print_pods_status() {
-    let green = Color::Green.to_ansi_fg();
-    let yellow = Color::Yellow.to_ansi_fg();
-    let red = Color::Red.to_ansi_fg();
+    let green = theme::current().ansi(Role::Success);
+    let yellow = theme::current().ansi(Role::Warning);
+    let red = theme::current().ansi(Role::Error);
     let reset = RESET;
 
     let inferadb_pods = run_command_optional(
@@ -262,6 +263,10 @@ fn print_urls_status() {
             }
         }
     }
+
+    if let Some(forward) = super::port_forward::running() {
+        print_prefixed_dot_leader(" ", "API (local)", &format!("http://{}", forward.address));
+    }
 }
 
 // ============================================================================
@@ -2,12 +2,9 @@
 //!
 //! Handles pausing containers and destroying the cluster.
 
-use std::{fs, process::Command, sync::Arc};
+use std::{fs, process::Command, sync::Arc, time::Instant};
 
-use teapot::{
-    output::{info as print_info, success as print_success},
-    style::{Color, RESET},
-};
+use teapot::style::RESET;
 
 use super::{
     commands::{run_command, run_command_optional},
@@ -17,8 +14,8 @@ use super::{
         get_expected_cluster_containers, is_container_paused, registry_exists, remove_image,
     },
     output::{
-        StepOutcome, confirm_prompt, format_dot_leader, print_destroy_skipped, print_hint,
-        print_styled_header, run_destroy_step,
+        StepOutcome, format_dot_leader, print_destroy_skipped, print_hint, print_styled_header,
+        run_destroy_step,
     },
     paths::{
         get_config_dir, get_data_dir, get_deploy_dir, get_state_dir, get_tailscale_creds_file,
@@ -28,6 +25,10 @@ use super::{
 use crate::{
     client::Context,
     error::{Error, Result},
+    output::{
+        theme::{self, Role},
+        timings,
+    },
     tui::UninstallInfo,
 };
 
@@ -43,16 +44,18 @@ pub async fn stop(
     with_credentials: bool,
     interactive: bool,
 ) -> Result<()> {
+    super::port_forward::stop();
+
     if destroy {
         if interactive && crate::tui::is_interactive(ctx) {
             return uninstall_interactive(with_credentials);
         }
-        uninstall_with_spinners(yes, with_credentials);
+        uninstall_with_spinners(ctx, yes, with_credentials)?;
         return Ok(());
     }
 
     // Pause containers
-    stop_with_spinners();
+    stop_with_spinners(ctx);
     Ok(())
 }
 
@@ -61,7 +64,7 @@ pub async fn stop(
 // ============================================================================
 
 /// Stop with inline spinners (pause containers).
-fn stop_with_spinners() {
+fn stop_with_spinners(ctx: &Context) {
     print_styled_header("Pausing InferaDB Development Cluster");
     println!();
 
@@ -85,7 +88,7 @@ fn stop_with_spinners() {
 
     println!();
     if any_paused {
-        print_success("Cluster paused successfully!");
+        ctx.output.success("Cluster paused successfully!");
     } else {
         println!("Nothing to pause.");
     }
@@ -96,29 +99,37 @@ fn stop_with_spinners() {
 
 /// Pause a single container, showing spinner and returning whether work was done.
 fn pause_container_with_spinner(container: &str) -> bool {
+    let display_name = container.strip_prefix(&format!("{CLUSTER_NAME}-")).unwrap_or(container);
+    let completed = format!("Paused {display_name}");
+    let started = Instant::now();
+    let status = pause_container(container, display_name, &completed);
+    timings::record(&completed, status, started.elapsed());
+    status == "OK"
+}
+
+/// Pause a container and return its step status (OK, SKIPPED, or FAILED).
+fn pause_container(container: &str, display_name: &str, completed: &str) -> &'static str {
     use crate::tui::start_spinner;
 
-    let display_name = container.strip_prefix(&format!("{CLUSTER_NAME}-")).unwrap_or(container);
     let in_progress = format!("Pausing {display_name}");
-    let completed = format!("Paused {display_name}");
     let mut spin = start_spinner(&in_progress);
 
     if !docker_container_exists(container) {
         spin.stop();
-        print_destroy_skipped(&completed);
-        return false;
+        print_destroy_skipped(completed);
+        return "SKIPPED";
     }
 
     if is_container_paused(container) {
         spin.stop();
-        print_destroy_skipped(&completed);
-        return false;
+        print_destroy_skipped(completed);
+        return "SKIPPED";
     }
 
     match run_command("docker", &["pause", container]) {
         Ok(_) => {
-            spin.success(&format_dot_leader(&completed, "OK"));
-            true
+            spin.success(&format_dot_leader(completed, "OK"));
+            "OK"
         },
         Err(e) => {
             let err_str = e.to_string();
@@ -127,11 +138,12 @@ fn pause_container_with_spinner(container: &str) -> bool {
                 || err_str.contains("not found")
             {
                 spin.stop();
-                print_destroy_skipped(&completed);
+                print_destroy_skipped(completed);
+                "SKIPPED"
             } else {
                 spin.failure(&err_str);
+                "FAILED"
             }
-            false
         },
     }
 }
@@ -361,7 +373,7 @@ fn cleanup_tailscale_devices() -> Result<()> {
 // ============================================================================
 
 /// Uninstall with spinners.
-fn uninstall_with_spinners(yes: bool, with_credentials: bool) {
+fn uninstall_with_spinners(ctx: &Context, yes: bool, with_credentials: bool) -> Result<()> {
     print_styled_header("Destroying InferaDB Development Cluster");
 
     let info = gather_uninstall_info();
@@ -380,9 +392,9 @@ fn uninstall_with_spinners(yes: bool, with_credentials: bool) {
         println!();
 
         if !yes {
-            if !matches!(confirm_prompt("Are you sure you want to continue?"), Ok(true)) {
+            if !ctx.confirm_danger("Are you sure you want to continue?")? {
                 println!("Aborted.");
-                return;
+                return Ok(());
             }
             println!();
         }
@@ -434,14 +446,14 @@ fn uninstall_with_spinners(yes: bool, with_credentials: bool) {
 
     println!();
     if did_work {
-        let green = Color::Green.to_ansi_fg();
+        let green = theme::current().ansi(Role::Success);
         let reset = RESET;
         println!("{green}Cluster destroyed successfully.{reset}");
 
         if !with_credentials && info.has_creds_file {
             println!();
-            print_info("Tailscale credentials were preserved for future dev clusters.");
-            print_info("To also remove them: inferadb dev stop --destroy --with-credentials");
+            ctx.output.info("Tailscale credentials were preserved for future dev clusters.");
+            ctx.output.info("To also remove them: inferadb dev stop --destroy --with-credentials");
         }
     } else {
         println!("Nothing to destroy.");
@@ -449,6 +461,7 @@ fn uninstall_with_spinners(yes: bool, with_credentials: bool) {
 
     println!();
     print_hint("Run 'inferadb dev start' to start the cluster");
+    Ok(())
 }
 
 // ============================================================================
@@ -0,0 +1,330 @@
+//! Upgrade command for dev cluster.
+//!
+//! Upgrades Kubernetes (and Talos, where the provisioner allows it) in place,
+//! snapshotting etcd first and re-applying operators afterwards, so cluster
+//! data survives platform bumps. Talos nodes run by the docker provisioner
+//! boot from their container image and can't be upgraded in place.
+
+use std::path::PathBuf;
+
+use super::{
+    commands::{command_exists, run_command},
+    constants::{
+        KUBERNETES_VERSION, LOCAL_PATH_PROVISIONER_URL, TALOS_INSTALLER_IMAGE, TALOS_PROVISIONER,
+        TALOS_UPGRADE_TIMEOUT,
+    },
+    docker::{are_containers_paused, cluster_exists, get_cluster_containers, get_container_ip},
+    output::{
+        StartStep, StepOutcome, print_done, print_hint, print_phase_header,
+        print_prefixed_dot_leader, print_section_header, print_styled_header, run_step,
+        run_step_with_result,
+    },
+    paths::get_snapshots_dir,
+    start::{install_tailscale_operator, setup_kubernetes_resources},
+    tailscale::get_tailscale_credentials,
+};
+use crate::{
+    client::Context,
+    error::{Error, Result},
+};
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Run dev upgrade - upgrade Talos/Kubernetes in place.
+pub async fn upgrade(
+    ctx: &Context,
+    kubernetes_version: Option<&str>,
+    talos_version: Option<&str>,
+    skip_snapshot: bool,
+    yes: bool,
+) -> Result<()> {
+    let target_kubernetes = normalize_version(kubernetes_version.unwrap_or(KUBERNETES_VERSION));
+    let target_talos = talos_version.map(normalize_version);
+
+    // Container nodes boot from their image; `talosctl upgrade` can't replace it
+    if target_talos.is_some() && TALOS_PROVISIONER == "docker" {
+        return Err(Error::invalid_arg(
+            "--talos-version is not supported on the docker provisioner: Talos containers can't be upgraded in place. Install the talosctl release you want, then recreate the cluster with 'inferadb dev stop --destroy' and 'inferadb dev start'.",
+        ));
+    }
+
+    print_styled_header("Upgrading InferaDB Development Cluster");
+
+    // Phase 1: Pre-flight checks
+    print_phase_header("Running pre-flight checks");
+
+    run_step(&StartStep::with_ok("Checking prerequisites", "Checked prerequisites"), || {
+        for cmd in &["docker", "talosctl", "kubectl", "helm"] {
+            if !command_exists(cmd) {
+                return Err(format!(
+                    "{cmd} is not installed. Run 'inferadb dev doctor' for setup instructions."
+                ));
+            }
+        }
+        Ok(StepOutcome::Success)
+    })?;
+
+    run_step(&StartStep::with_ok("Checking cluster is running", "Cluster is running"), || {
+        if !cluster_exists() {
+            return Err("Cluster is not running. Start with 'inferadb dev start'.".to_string());
+        }
+        if are_containers_paused() {
+            return Err("Cluster is paused. Resume with 'inferadb dev start'.".to_string());
+        }
+        Ok(StepOutcome::Success)
+    })?;
+
+    let current_kubernetes = run_step_with_result(
+        &StartStep::with_ok("Checking node health", "Nodes are healthy"),
+        check_node_health,
+    )?;
+
+    let controlplane_ip = run_step_with_result(
+        &StartStep::with_ok("Locating control plane node", "Located control plane node"),
+        || {
+            get_cluster_containers()
+                .iter()
+                .find(|name| name.contains("controlplane"))
+                .and_then(|name| get_container_ip(name))
+                .map(|ip| (StepOutcome::Success, ip))
+                .ok_or_else(|| "Failed to find control plane node IP".to_string())
+        },
+    )?;
+
+    let upgrade_kubernetes = current_kubernetes != target_kubernetes;
+    if !upgrade_kubernetes && target_talos.is_none() {
+        println!();
+        print_hint(&format!("Cluster is already running Kubernetes v{target_kubernetes}"));
+        return Ok(());
+    }
+
+    if !yes {
+        show_upgrade_plan(
+            ctx,
+            &current_kubernetes,
+            &target_kubernetes,
+            target_talos.as_deref(),
+            skip_snapshot,
+        )?;
+    }
+
+    // Phase 2: Snapshot
+    let snapshot = if skip_snapshot {
+        None
+    } else {
+        print_phase_header("Creating snapshot");
+        Some(run_step_with_result(
+            &StartStep::with_ok("Snapshotting etcd", "Snapshotted etcd"),
+            || snapshot_etcd(&controlplane_ip).map(|path| (StepOutcome::Success, path)),
+        )?)
+    };
+
+    // Phase 3: Upgrade
+    print_phase_header("Upgrading cluster");
+
+    if let Some(version) = &target_talos {
+        let image = format!("{TALOS_INSTALLER_IMAGE}:v{version}");
+        for container in get_cluster_containers() {
+            let in_progress = format!("Upgrading Talos on {container}");
+            let completed = format!("Upgraded Talos on {container}");
+            run_step(&StartStep::with_ok(&in_progress, &completed), || {
+                let ip = get_container_ip(&container)
+                    .ok_or_else(|| format!("Failed to get IP for {container}"))?;
+                run_command(
+                    "talosctl",
+                    &[
+                        "--nodes",
+                        &ip,
+                        "upgrade",
+                        "--image",
+                        &image,
+                        "--wait",
+                        "--timeout",
+                        TALOS_UPGRADE_TIMEOUT,
+                    ],
+                )
+                .map(|_| StepOutcome::Success)
+                .map_err(|e| e.to_string())
+            })?;
+        }
+    }
+
+    run_step(
+        &StartStep::with_ok(
+            &format!("Upgrading Kubernetes to v{target_kubernetes}"),
+            &format!("Upgraded Kubernetes to v{target_kubernetes}"),
+        ),
+        || {
+            if !upgrade_kubernetes {
+                return Ok(StepOutcome::Skipped);
+            }
+            run_command(
+                "talosctl",
+                &["--nodes", &controlplane_ip, "upgrade-k8s", "--to", &target_kubernetes],
+            )
+            .map(|_| StepOutcome::Success)
+            .map_err(|e| e.to_string())
+        },
+    )?;
+
+    run_step(&StartStep::with_ok("Waiting for nodes to be ready", "Nodes are ready"), || {
+        run_command("kubectl", &["wait", "--for=condition=Ready", "nodes", "--all", "--timeout=5m"])
+            .map(|_| StepOutcome::Success)
+            .map_err(|e| e.to_string())
+    })?;
+
+    // Phase 4: Re-apply operators
+    print_phase_header("Re-applying operators");
+
+    let (ts_client_id, ts_client_secret) = get_tailscale_credentials()?;
+
+    run_step(
+        &StartStep::with_ok("Setting up Kubernetes resources", "Set up Kubernetes resources"),
+        || setup_kubernetes_resources(LOCAL_PATH_PROVISIONER_URL),
+    )?;
+
+    run_step(
+        &StartStep::with_ok("Upgrading Tailscale operator", "Upgraded Tailscale operator"),
+        || install_tailscale_operator(&ts_client_id, &ts_client_secret, None),
+    )?;
+
+    show_upgrade_success(&target_kubernetes, snapshot.as_ref());
+    Ok(())
+}
+
+// ============================================================================
+// Implementation
+// ============================================================================
+
+/// Strip a leading `v` so versions compare consistently (e.g., `v1.32.0`).
+fn normalize_version(version: &str) -> String {
+    version.trim().trim_start_matches('v').to_string()
+}
+
+/// Check every node is Ready and return the current Kubernetes version.
+fn check_node_health() -> std::result::Result<(StepOutcome, String), String> {
+    let output = run_command(
+        "kubectl",
+        &[
+            "get",
+            "nodes",
+            "-o",
+            r#"jsonpath={range .items[*]}{.metadata.name} {.status.nodeInfo.kubeletVersion} {.status.conditions[?(@.type=="Ready")].status}{"\n"}{end}"#,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    parse_node_health(&output).map(|version| (StepOutcome::Success, version))
+}
+
+/// Parse `name version ready` lines, requiring all nodes Ready on one version.
+fn parse_node_health(output: &str) -> std::result::Result<String, String> {
+    let mut version = None;
+    for line in output.lines().filter(|l| !l.trim().is_empty()) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [name, kubelet, ready] = parts.as_slice() else {
+            return Err(format!("Unexpected node status: {line}"));
+        };
+        if *ready != "True" {
+            return Err(format!("Node {name} is not ready"));
+        }
+        let kubelet = normalize_version(kubelet);
+        match &version {
+            Some(v) if *v != kubelet => {
+                return Err(format!(
+                    "Nodes run mixed Kubernetes versions ({v}, {kubelet}); finish the previous upgrade first"
+                ));
+            },
+            Some(_) => {},
+            None => version = Some(kubelet),
+        }
+    }
+    version.ok_or_else(|| "No cluster nodes found".to_string())
+}
+
+/// Show the upgrade plan and prompt for confirmation.
+fn show_upgrade_plan(
+    ctx: &Context,
+    current_kubernetes: &str,
+    target_kubernetes: &str,
+    target_talos: Option<&str>,
+    skip_snapshot: bool,
+) -> Result<()> {
+    print_section_header("Upgrade plan");
+
+    let kubernetes = if current_kubernetes == target_kubernetes {
+        format!("v{current_kubernetes} (unchanged)")
+    } else {
+        format!("v{current_kubernetes} -> v{target_kubernetes}")
+    };
+    print_prefixed_dot_leader("○", "Kubernetes", &kubernetes);
+    if let Some(version) = target_talos {
+        print_prefixed_dot_leader("○", "Talos", &format!("-> v{version}"));
+    }
+    print_prefixed_dot_leader(
+        "○",
+        "etcd snapshot",
+        if skip_snapshot { "SKIPPED" } else { "before upgrade" },
+    );
+    print_prefixed_dot_leader("○", "Operators", "re-applied after upgrade");
+
+    println!();
+    if ctx.confirm("The cluster will be briefly unavailable during the upgrade. Continue?")? {
+        Ok(())
+    } else {
+        println!("Aborted.");
+        Err(Error::Other("User cancelled".to_string()))
+    }
+}
+
+/// Save an etcd snapshot from the control plane and return its path.
+fn snapshot_etcd(controlplane_ip: &str) -> std::result::Result<PathBuf, String> {
+    let dir = get_snapshots_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create snapshot directory: {e}"))?;
+
+    let path = dir.join(format!("etcd-{}.db", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+    let path_str = path.to_str().ok_or_else(|| format!("Invalid path: {}", path.display()))?;
+    run_command("talosctl", &["--nodes", controlplane_ip, "etcd", "snapshot", path_str])
+        .map_err(|e| e.to_string())?;
+
+    Ok(path)
+}
+
+/// Show final success output.
+fn show_upgrade_success(kubernetes_version: &str, snapshot: Option<&PathBuf>) {
+    println!();
+    print_done(&format!("Development cluster upgraded to Kubernetes v{kubernetes_version}"));
+    println!();
+
+    if let Some(path) = snapshot {
+        print_hint(&format!("etcd snapshot saved to {}", path.display()));
+    }
+    print_hint("Run 'inferadb dev status' for cluster details");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_version() {
+        assert_eq!(normalize_version("v1.32.0"), "1.32.0");
+        assert_eq!(normalize_version("1.33.1"), "1.33.1");
+    }
+
+    #[test]
+    fn test_parse_node_health() {
+        let output = "cp-1 v1.32.0 True\nworker-1 v1.32.0 True\n";
+        assert_eq!(parse_node_health(output), Ok("1.32.0".to_string()));
+    }
+
+    #[test]
+    fn test_parse_node_health_rejects_unhealthy() {
+        assert!(parse_node_health("cp-1 v1.32.0 False\n").is_err());
+        assert!(parse_node_health("cp-1 v1.32.0 True\nworker-1 v1.31.0 True\n").is_err());
+        assert!(parse_node_health("").is_err());
+    }
+}
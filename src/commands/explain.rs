@@ -0,0 +1,165 @@
+//! Explain command: what a command calls and changes on the service.
+
+use clap::{Arg, Command};
+use serde::Serialize;
+
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    explain::{self, EXPLANATIONS, Explanation},
+    output::{Displayable, OutputFormat},
+    suggest,
+};
+
+impl Displayable for Explanation {
+    fn table_row(&self) -> Vec<String> {
+        vec![
+            self.command.to_string(),
+            self.requires.to_string(),
+            if self.side_effects.is_empty() { "no" } else { "yes" }.to_string(),
+        ]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["COMMAND", "REQUIRES", "WRITES"]
+    }
+}
+
+/// A command's help and usage from its definition, with its registered
+/// explanation when it has one.
+#[derive(Debug, Serialize)]
+struct Explained {
+    command: String,
+    about: Option<String>,
+    usage: String,
+    arguments: Vec<Argument>,
+    /// Whether the registry describes the command's API use.
+    documented: bool,
+    endpoints: &'static [&'static str],
+    requires: Option<&'static str>,
+    side_effects: &'static [&'static str],
+    audit: &'static [&'static str],
+    related: &'static [&'static str],
+}
+
+#[derive(Debug, Serialize)]
+struct Argument {
+    name: String,
+    help: Option<String>,
+    required: bool,
+}
+
+impl Explained {
+    fn new(command: &Command, path: &[String]) -> Self {
+        let explanation = explain::find(path);
+        Self {
+            command: path.join(" "),
+            about: command.get_about().map(ToString::to_string),
+            usage: command.clone().render_usage().to_string(),
+            arguments: command
+                .get_arguments()
+                .filter(|arg| !arg.is_global_set() && !arg.is_hide_set())
+                .filter(|arg| !matches!(arg.get_id().as_str(), "help" | "version"))
+                .map(|arg| Argument {
+                    name: arg_name(arg),
+                    help: arg.get_help().map(ToString::to_string),
+                    required: arg.is_required_set(),
+                })
+                .collect(),
+            documented: explanation.is_some(),
+            endpoints: explanation.map_or(&[], |e| e.endpoints),
+            requires: explanation.map(|e| e.requires),
+            side_effects: explanation.map_or(&[], |e| e.side_effects),
+            audit: explanation.map_or(&[], |e| e.audit),
+            related: explanation.map_or(&[], |e| e.related),
+        }
+    }
+
+    fn print(&self) {
+        match self.about {
+            Some(ref about) => println!("{}: {about}", self.command),
+            None => println!("{}", self.command),
+        }
+        println!();
+        println!("{}", self.usage);
+
+        if !self.arguments.is_empty() {
+            let width = self.arguments.iter().map(|a| a.name.len()).max().unwrap_or(0);
+            println!();
+            println!("Arguments:");
+            for arg in &self.arguments {
+                println!("  {:width$}  {}", arg.name, arg.help.as_deref().unwrap_or_default());
+            }
+        }
+
+        if !self.documented {
+            println!();
+            println!("No API details are recorded for this command.");
+            return;
+        }
+        section("API endpoints", self.endpoints.iter().copied());
+        section("Requires", self.requires);
+        if self.side_effects.is_empty() {
+            section("Side effects", Some("None (read-only)"));
+        } else {
+            section("Side effects", self.side_effects.iter().copied());
+        }
+        section("Audit log actions", self.audit.iter().copied());
+        section("Related commands", self.related.iter().map(|r| format!("inferadb {r}")));
+    }
+}
+
+/// Print a titled list, or nothing when it is empty.
+fn section<T: std::fmt::Display>(title: &str, lines: impl IntoIterator<Item = T>) {
+    let mut lines = lines.into_iter().peekable();
+    if lines.peek().is_none() {
+        return;
+    }
+    println!();
+    println!("{title}:");
+    for line in lines {
+        println!("  {line}");
+    }
+}
+
+/// An argument as it appears on the command line: `--long <VALUE>`, `-s`, or
+/// `<VALUE>` for positionals.
+fn arg_name(arg: &Arg) -> String {
+    let value = arg
+        .get_value_names()
+        .and_then(|names| names.first().map(ToString::to_string))
+        .unwrap_or_else(|| arg.get_id().as_str().to_uppercase());
+    let flag = match (arg.get_long(), arg.get_short()) {
+        (Some(long), _) => format!("--{long}"),
+        (None, Some(short)) => format!("-{short}"),
+        (None, None) => return format!("<{value}>"),
+    };
+    if arg.get_action().takes_values() { format!("{flag} <{value}>") } else { flag }
+}
+
+/// Explain a command (given as its words, e.g. `relationships add`), or list
+/// the explained commands when none is given.
+pub async fn explain(ctx: &Context, words: &[String]) -> Result<()> {
+    if words.is_empty() {
+        return ctx.output.table(EXPLANATIONS);
+    }
+
+    let root = explain::command_tree();
+    let (command, path) = explain::resolve(&root, words).map_err(|_| {
+        let typed = words.join(" ");
+        match suggest::suggest(words).as_slice() {
+            [] => Error::invalid_arg(format!("Unknown command '{typed}'")),
+            suggestions => Error::invalid_arg(format!(
+                "Unknown command '{typed}'. Did you mean: {}?",
+                suggestions.join(", ")
+            )),
+        }
+    })?;
+
+    let explained = Explained::new(command, &path);
+    if ctx.output.format() != OutputFormat::Table {
+        return ctx.output.value(&explained);
+    }
+    explained.print();
+    Ok(())
+}
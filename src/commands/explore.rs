@@ -0,0 +1,88 @@
+//! Interactive relationship explorer.
+//!
+//! `inferadb explore [resource]` runs [`ExploreView`] over the vault. The view
+//! is synchronous, so its data callbacks block on the SDK's async requests.
+
+use std::{future::IntoFuture, sync::Arc};
+
+use inferadb::VaultClient;
+
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    tui::{self, CheckFn, ExpandFn, ExploreRow, ExploreView, RelationshipsFn},
+};
+
+/// Relationships listed per page of the explorer.
+const PAGE_SIZE: usize = 500;
+
+/// Browse relationships from `resource`, or from a sample of all resources.
+pub async fn explore(ctx: &Context, resource: Option<&str>) -> Result<()> {
+    use teapot::{
+        output::{terminal_height, terminal_width},
+        runtime::{Program, ProgramOptions},
+    };
+
+    if !tui::is_interactive(ctx) {
+        return Err(Error::other(
+            "explore needs an interactive terminal; use 'relationships list' or 'expand' instead",
+        ));
+    }
+
+    let client = ctx.client().await?;
+    let vault = client.vault();
+
+    let view = ExploreView::builder()
+        .width(terminal_width())
+        .height(terminal_height())
+        .relationships(relationships_fn(vault.clone()))
+        .expand(expand_fn(vault.clone()))
+        .check(check_fn(vault))
+        .maybe_resource(resource.map(str::to_string))
+        .build();
+    Program::new(view)
+        .with_options(ProgramOptions::fullscreen())
+        .run()
+        .map_err(|e| Error::Other(e.to_string()))?;
+    Ok(())
+}
+
+/// Run `request` to completion from the view's synchronous callbacks.
+fn block_on<T>(
+    request: impl IntoFuture<Output = std::result::Result<T, inferadb::Error>>,
+) -> std::result::Result<T, String> {
+    let handle = tokio::runtime::Handle::current();
+    tokio::task::block_in_place(|| handle.block_on(request.into_future()))
+        .map_err(|e| e.to_string())
+}
+
+fn relationships_fn(vault: VaultClient) -> RelationshipsFn {
+    Arc::new(move |resource: Option<&str>| {
+        let mut request = vault.relationships().list().limit(PAGE_SIZE);
+        if let Some(resource) = resource {
+            request = request.resource(resource);
+        }
+        let page = block_on(request)?;
+        Ok(page
+            .relationships
+            .iter()
+            .map(|rel| ExploreRow {
+                resource: rel.resource().to_string(),
+                relation: rel.relation().to_string(),
+                subject: rel.subject().to_string(),
+            })
+            .collect())
+    })
+}
+
+fn expand_fn(vault: VaultClient) -> ExpandFn {
+    Arc::new(move |resource: &str, relation: &str| {
+        block_on(vault.subjects().with_permission(relation).on_resource(resource).collect())
+    })
+}
+
+fn check_fn(vault: VaultClient) -> CheckFn {
+    Arc::new(move |subject: &str, permission: &str, resource: &str| {
+        block_on(vault.check(subject, permission, resource))
+    })
+}
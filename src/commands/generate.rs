@@ -0,0 +1,496 @@
+//! Generators for observability and CI configuration.
+//!
+//! Dashboards are built from [`metrics::DESCRIPTORS`], so every metric the CLI
+//! can emit with `--metrics-listen` gets a panel and names never drift. CI
+//! pipelines are parameterized by the effective profile, so the vault they
+//! deploy to matches the one the project already uses.
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::{
+    client::{Context, TOKEN_ENV},
+    error::{Error, Result},
+    output::metrics::{self, Descriptor, Kind},
+};
+
+/// Dashboard file written by `generate grafana`.
+pub const DASHBOARD_FILE: &str = "inferadb-cli.json";
+
+/// Alert rules file written by `generate grafana`.
+pub const ALERTS_FILE: &str = "inferadb-cli-alerts.yaml";
+
+/// Rate window used in dashboard and alert queries.
+const RATE_WINDOW: &str = "5m";
+
+/// Prometheus alerting rules file.
+#[derive(Debug, Serialize)]
+struct RuleFile {
+    groups: Vec<RuleGroup>,
+}
+
+#[derive(Debug, Serialize)]
+struct RuleGroup {
+    name: String,
+    rules: Vec<AlertRule>,
+}
+
+#[derive(Debug, Serialize)]
+struct AlertRule {
+    alert: String,
+    expr: String,
+    #[serde(rename = "for")]
+    for_duration: String,
+    labels: BTreeMap<String, String>,
+    annotations: BTreeMap<String, String>,
+}
+
+impl AlertRule {
+    fn new(alert: &str, expr: String, for_duration: &str, severity: &str, summary: &str) -> Self {
+        Self {
+            alert: alert.to_string(),
+            expr,
+            for_duration: for_duration.to_string(),
+            labels: BTreeMap::from([("severity".to_string(), severity.to_string())]),
+            annotations: BTreeMap::from([("summary".to_string(), summary.to_string())]),
+        }
+    }
+}
+
+/// Write a Grafana dashboard and Prometheus alert rules for CLI metrics.
+pub async fn grafana(ctx: &Context, output: &str) -> Result<()> {
+    let dir = Path::new(output);
+    std::fs::create_dir_all(dir)?;
+
+    let dashboard = serde_json::to_string_pretty(&dashboard())?;
+    std::fs::write(dir.join(DASHBOARD_FILE), dashboard + "\n")?;
+    std::fs::write(dir.join(ALERTS_FILE), serde_yaml::to_string(&alert_rules())?)?;
+
+    ctx.output.success(&format!("Wrote Grafana dashboard and alert rules to {output}"));
+    ctx.output.info(&format!("  {DASHBOARD_FILE}    import in Grafana (Dashboards > Import)"));
+    ctx.output.info(&format!("  {ALERTS_FILE}  add to Prometheus rule_files"));
+    ctx.output.info("Scrape targets started with --metrics-listen (health --watch, stream).");
+
+    Ok(())
+}
+
+/// Grafana dashboard with one panel per CLI metric.
+fn dashboard() -> Value {
+    let panels: Vec<Value> = metrics::DESCRIPTORS
+        .iter()
+        .enumerate()
+        .map(|(i, descriptor)| {
+            let (panel_type, targets) = panel_queries(descriptor);
+            json!({
+                "id": i + 1,
+                "type": panel_type,
+                "title": descriptor.help.trim_end_matches('.'),
+                "datasource": { "type": "prometheus", "uid": "${datasource}" },
+                "gridPos": { "h": 8, "w": 12, "x": (i % 2) * 12, "y": (i / 2) * 8 },
+                "fieldConfig": { "defaults": { "unit": unit(descriptor) }, "overrides": [] },
+                "targets": targets,
+            })
+        })
+        .collect();
+
+    json!({
+        "title": "InferaDB CLI",
+        "uid": "inferadb-cli",
+        "tags": ["inferadb"],
+        "schemaVersion": 39,
+        "version": 1,
+        "refresh": "30s",
+        "time": { "from": "now-6h", "to": "now" },
+        "templating": {
+            "list": [{
+                "name": "datasource",
+                "label": "Data source",
+                "type": "datasource",
+                "query": "prometheus",
+            }],
+        },
+        "panels": panels,
+    })
+}
+
+/// Panel type and query targets for a metric.
+fn panel_queries(descriptor: &Descriptor) -> (&'static str, Vec<Value>) {
+    let name = descriptor.name;
+    let by = if descriptor.labels.is_empty() {
+        String::new()
+    } else {
+        format!(" by ({})", descriptor.labels.join(", "))
+    };
+    let legend = descriptor.labels.first().map_or_else(String::new, |l| format!("{{{{{l}}}}}"));
+
+    match descriptor.kind {
+        Kind::Counter => (
+            "timeseries",
+            vec![target("A", &format!("sum{by} (rate({name}[{RATE_WINDOW}]))"), &legend)],
+        ),
+        Kind::Histogram => {
+            let targets = [("A", "0.5", "p50"), ("B", "0.95", "p95"), ("C", "0.99", "p99")]
+                .into_iter()
+                .map(|(ref_id, q, legend)| {
+                    target(
+                        ref_id,
+                        &format!(
+                            "histogram_quantile({q}, sum by (le) (rate({name}_bucket[{RATE_WINDOW}])))"
+                        ),
+                        legend,
+                    )
+                })
+                .collect();
+            ("timeseries", targets)
+        },
+        Kind::Gauge if name == metrics::HEALTH_UP => ("stat", vec![target("A", name, "")]),
+        Kind::Gauge => ("timeseries", vec![target("A", name, &legend)]),
+    }
+}
+
+fn target(ref_id: &str, expr: &str, legend: &str) -> Value {
+    json!({ "refId": ref_id, "expr": expr, "legendFormat": legend })
+}
+
+fn unit(descriptor: &Descriptor) -> &'static str {
+    match descriptor.kind {
+        Kind::Counter => "ops",
+        _ if descriptor.name.ends_with("_seconds") => "s",
+        _ => "none",
+    }
+}
+
+/// Alert rules for unhealthy services and stalled or lagging streams.
+fn alert_rules() -> RuleFile {
+    let rules = vec![
+        AlertRule::new(
+            "InferaDBUnhealthy",
+            format!("{} == 0", metrics::HEALTH_UP),
+            "2m",
+            "critical",
+            "InferaDB health probes are failing",
+        ),
+        AlertRule::new(
+            "InferaDBHealthLatencyHigh",
+            format!(
+                "histogram_quantile(0.95, sum by (le) (rate({}_bucket[{RATE_WINDOW}]))) > 1",
+                metrics::HEALTH_LATENCY_SECONDS
+            ),
+            "10m",
+            "warning",
+            "InferaDB health probe p95 latency is above 1s",
+        ),
+        AlertRule::new(
+            "InferaDBStreamLagHigh",
+            format!("{} > 30", metrics::STREAM_LAG_SECONDS),
+            "5m",
+            "warning",
+            "Watch stream changes are arriving more than 30s late",
+        ),
+        AlertRule::new(
+            "InferaDBStreamErrors",
+            format!("increase({}[{RATE_WINDOW}]) > 0", metrics::STREAM_ERRORS_TOTAL),
+            "5m",
+            "warning",
+            "Watch stream is reporting errors",
+        ),
+    ];
+
+    RuleFile { groups: vec![RuleGroup { name: "inferadb-cli".to_string(), rules }] }
+}
+
+// ============================================================================
+// CI pipelines
+// ============================================================================
+
+/// Inputs for a generated CI pipeline.
+#[derive(Debug, Clone)]
+struct CiParams {
+    schema: String,
+    tests: String,
+    branch: String,
+    url: String,
+    org: Option<String>,
+    vault: Option<String>,
+}
+
+impl CiParams {
+    /// `INFERADB_*` variables selecting the target vault via the `env` profile.
+    fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars =
+            vec![("INFERADB_PROFILE", "env".to_string()), ("INFERADB_URL", self.url.clone())];
+        if let Some(org) = &self.org {
+            vars.push(("INFERADB_ORG", org.clone()));
+        }
+        if let Some(vault) = &self.vault {
+            vars.push(("INFERADB_VAULT", vault.clone()));
+        }
+        vars
+    }
+
+    fn install(&self) -> String {
+        format!("cargo install inferadb-cli --version {} --locked", crate::VERSION)
+    }
+}
+
+/// Quote a value as a YAML double-quoted scalar.
+fn yaml_str(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// Write a CI pipeline that validates, tests, and previews schema changes on
+/// pull requests and pushes and activates the schema on merge.
+pub async fn ci(
+    ctx: &Context,
+    provider: &str,
+    schema: &str,
+    tests: &str,
+    branch: &str,
+    output: Option<&str>,
+    force: bool,
+) -> Result<()> {
+    let params = CiParams {
+        schema: schema.to_string(),
+        tests: tests.to_string(),
+        branch: branch.to_string(),
+        url: ctx.profile.url_or_default().to_string(),
+        org: ctx.profile.org.clone(),
+        vault: ctx.profile.vault.clone(),
+    };
+
+    let (content, default_path) = match provider {
+        "github" => (github_workflow(&params), ".github/workflows/inferadb.yml"),
+        "gitlab" => (gitlab_pipeline(&params), ".gitlab-ci.yml"),
+        _ => {
+            return Err(Error::invalid_arg(format!(
+                "Unknown CI provider '{provider}'. Use: github, gitlab"
+            )));
+        },
+    };
+
+    let path = Path::new(output.unwrap_or(default_path));
+    if path.exists() && !force {
+        return Err(Error::invalid_arg(format!(
+            "{} already exists. Use --force to overwrite.",
+            path.display()
+        )));
+    }
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+
+    ctx.output.success(&format!("Wrote {provider} pipeline to {}", path.display()));
+    if params.org.is_none() || params.vault.is_none() {
+        ctx.output.warn(
+            "Profile has no org or vault; set INFERADB_ORG and INFERADB_VAULT in the pipeline.",
+        );
+    }
+    let secret = if provider == "github" { "repository secret" } else { "masked CI/CD variable" };
+    ctx.output.info(&format!("Add a vault access token as the {TOKEN_ENV} {secret}."));
+
+    Ok(())
+}
+
+/// GitHub Actions workflow.
+fn github_workflow(params: &CiParams) -> String {
+    let paths = format!("[{}, {}]", yaml_str(&params.schema), yaml_str(&params.tests));
+    let schema = yaml_str(&params.schema);
+    let tests = yaml_str(&params.tests);
+    let install = params.install();
+
+    let mut env = String::new();
+    for (name, value) in params.env_vars() {
+        env.push_str(&format!("  {name}: {}\n", yaml_str(&value)));
+    }
+    env.push_str(&format!("  {TOKEN_ENV}: ${{{{ secrets.{TOKEN_ENV} }}}}\n"));
+
+    format!(
+        r#"# Generated by `inferadb generate ci --provider github`.
+name: InferaDB schema
+
+on:
+  pull_request:
+    paths: {paths}
+  push:
+    branches: [{branch}]
+    paths: {paths}
+
+env:
+{env}
+jobs:
+  check:
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - name: Install InferaDB CLI
+        run: {install}
+      - name: Validate schema
+        run: inferadb schemas validate {schema}
+      - name: Check formatting
+        run: inferadb schemas format {schema}
+      - name: Run schema tests
+        run: inferadb schemas test --schema {schema} --tests {tests}
+      - name: Impact analysis
+        if: github.event_name == 'pull_request'
+        run: inferadb schemas preview {schema} --impact
+
+  deploy:
+    needs: check
+    if: github.event_name == 'push'
+    runs-on: ubuntu-latest
+    steps:
+      - uses: actions/checkout@v4
+      - name: Install InferaDB CLI
+        run: {install}
+      - name: Push and activate schema
+        run: inferadb schemas push {schema} --activate --message "Deploy ${{{{ github.sha }}}}"
+"#,
+        branch = yaml_str(&params.branch),
+    )
+}
+
+/// GitLab CI pipeline.
+fn gitlab_pipeline(params: &CiParams) -> String {
+    let paths = format!("[{}, {}]", yaml_str(&params.schema), yaml_str(&params.tests));
+    let schema = yaml_str(&params.schema);
+    let tests = yaml_str(&params.tests);
+    let install = params.install();
+    let branch = &params.branch;
+
+    let mut variables = String::new();
+    for (name, value) in params.env_vars() {
+        variables.push_str(&format!("  {name}: {}\n", yaml_str(&value)));
+    }
+
+    format!(
+        r#"# Generated by `inferadb generate ci --provider gitlab`.
+# Set {TOKEN_ENV} as a masked CI/CD variable.
+variables:
+{variables}
+stages: [check, deploy]
+
+.inferadb:
+  image: rust:latest
+  before_script:
+    - {install}
+
+inferadb:check:
+  extends: .inferadb
+  stage: check
+  rules:
+    - if: $CI_PIPELINE_SOURCE == "merge_request_event"
+      changes: {paths}
+    - if: $CI_COMMIT_BRANCH == "{branch}"
+      changes: {paths}
+  script:
+    - inferadb schemas validate {schema}
+    - inferadb schemas format {schema}
+    - inferadb schemas test --schema {schema} --tests {tests}
+
+inferadb:impact:
+  extends: .inferadb
+  stage: check
+  rules:
+    - if: $CI_PIPELINE_SOURCE == "merge_request_event"
+      changes: {paths}
+  script:
+    - inferadb schemas preview {schema} --impact
+
+inferadb:deploy:
+  extends: .inferadb
+  stage: deploy
+  rules:
+    - if: $CI_COMMIT_BRANCH == "{branch}"
+      changes: {paths}
+  script:
+    - inferadb schemas push {schema} --activate --message "Deploy $CI_COMMIT_SHORT_SHA"
+"#
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dashboard_covers_every_metric() {
+        let dashboard = dashboard();
+        let panels = dashboard["panels"].as_array().unwrap();
+        assert_eq!(panels.len(), metrics::DESCRIPTORS.len());
+
+        for descriptor in metrics::DESCRIPTORS {
+            assert!(
+                panels
+                    .iter()
+                    .any(|p| p["targets"][0]["expr"].as_str().unwrap().contains(descriptor.name)),
+                "no panel for {}",
+                descriptor.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_panel_queries() {
+        let counter =
+            metrics::DESCRIPTORS.iter().find(|d| d.name == metrics::STREAM_EVENTS_TOTAL).unwrap();
+        let (panel_type, targets) = panel_queries(counter);
+        assert_eq!(panel_type, "timeseries");
+        assert_eq!(
+            targets[0]["expr"],
+            "sum by (operation) (rate(inferadb_cli_stream_events_total[5m]))"
+        );
+        assert_eq!(targets[0]["legendFormat"], "{{operation}}");
+    }
+
+    #[test]
+    fn test_alert_rules_reference_known_metrics() {
+        let rules = alert_rules();
+        let yaml = serde_yaml::to_string(&rules).unwrap();
+        assert!(yaml.contains("for: 2m"));
+
+        for rule in &rules.groups[0].rules {
+            assert!(
+                metrics::DESCRIPTORS.iter().any(|d| rule.expr.contains(d.name)),
+                "{} references no CLI metric",
+                rule.alert
+            );
+        }
+    }
+
+    fn params() -> CiParams {
+        CiParams {
+            schema: "authz/schema.ipl".to_string(),
+            tests: "authz/schema.test.yaml".to_string(),
+            branch: "main".to_string(),
+            url: "https://api.inferadb.com".to_string(),
+            org: Some("acme".to_string()),
+            vault: None,
+        }
+    }
+
+    #[test]
+    fn test_github_workflow() {
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&github_workflow(&params())).unwrap();
+        assert_eq!(yaml["env"]["INFERADB_ORG"], "acme");
+        assert!(yaml["env"].get("INFERADB_VAULT").is_none());
+        assert_eq!(yaml["env"]["INFERADB_TOKEN"], "${{ secrets.INFERADB_TOKEN }}");
+        assert_eq!(yaml["on"]["push"]["branches"][0], "main");
+
+        let steps = yaml["jobs"]["check"]["steps"].as_sequence().unwrap();
+        assert!(steps.iter().any(|s| s["run"]
+            == r#"inferadb schemas test --schema "authz/schema.ipl" --tests "authz/schema.test.yaml""#));
+        assert_eq!(yaml["jobs"]["deploy"]["needs"], "check");
+    }
+
+    #[test]
+    fn test_gitlab_pipeline() {
+        let yaml: serde_yaml::Value = serde_yaml::from_str(&gitlab_pipeline(&params())).unwrap();
+        assert_eq!(yaml["variables"]["INFERADB_PROFILE"], "env");
+        assert_eq!(yaml["inferadb:deploy"]["stage"], "deploy");
+        assert_eq!(yaml["inferadb:check"]["rules"][1]["if"], "$CI_COMMIT_BRANCH == \"main\"");
+    }
+}
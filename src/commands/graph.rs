@@ -0,0 +1,339 @@
+//! Relationship graphs for live data.
+//!
+//! `relationships graph --resource document:readme --depth 3` walks the
+//! relationships of a resource, then those of every object it points at, and
+//! so on, and renders the result as Mermaid, Graphviz DOT, or D2 for docs.
+//! Edges run from resource to subject, labeled with the relation; subject sets
+//! (`group:eng#member`) point at the group with the set's relation appended to
+//! the label. `--open` writes a standalone HTML page that renders the graph
+//! with Mermaid and opens it in the browser.
+
+use std::{
+    collections::{BTreeSet, HashSet, VecDeque},
+    fmt::Write as _,
+    path::PathBuf,
+};
+
+use bon::builder;
+use inferadb::VaultClient;
+use serde::Serialize;
+
+use super::retag::Tuple;
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    output::OutputFormat,
+    tui,
+};
+
+/// Relationships read per request.
+const PAGE_SIZE: usize = 1000;
+
+/// Graph output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum GraphFormat {
+    Mermaid,
+    Dot,
+    D2,
+}
+
+impl GraphFormat {
+    pub(super) fn parse(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "mermaid" => Ok(Self::Mermaid),
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "d2" => Ok(Self::D2),
+            _ => Err(Error::invalid_arg(format!(
+                "Unknown graph format '{s}'. Use: mermaid, dot, d2"
+            ))),
+        }
+    }
+}
+
+/// Relationships reachable from a resource.
+#[derive(Debug, Serialize)]
+struct Graph {
+    root: String,
+    depth: u32,
+    /// Objects in the order they were reached.
+    nodes: Vec<String>,
+    edges: Vec<Tuple>,
+    /// Whether `max_edges` stopped the walk early.
+    truncated: bool,
+}
+
+/// Render the relationship graph around `resource`.
+#[builder]
+pub(super) async fn graph(
+    ctx: &Context,
+    resource: &str,
+    #[builder(default = 2)] depth: u32,
+    format: GraphFormat,
+    #[builder(default = 500)] max_edges: usize,
+    #[builder(default)] open: bool,
+) -> Result<()> {
+    if !resource.contains(':') {
+        return Err(Error::invalid_arg(format!(
+            "Invalid resource '{resource}'. Expected: <type>:<id>"
+        )));
+    }
+
+    let client = ctx.client().await?;
+    let vault = client.vault();
+    let graph =
+        tui::spin("Reading relationships...", walk(&vault, resource, depth, max_edges)).await?;
+
+    if graph.truncated {
+        ctx.output.warn(&format!(
+            "Stopped after {max_edges} relationships; raise --max-edges or lower --depth."
+        ));
+    }
+    if graph.edges.is_empty() {
+        ctx.output.info(&format!("No relationships found for {resource}."));
+    }
+
+    if open {
+        let path = write_viewer(&graph)?;
+        ctx.output.success(&format!("Wrote {}", path.display()));
+        if let Err(e) = webbrowser::open(&path.to_string_lossy()) {
+            ctx.output.warn(&format!("Could not open a browser: {e}"));
+        }
+        return Ok(());
+    }
+
+    if ctx.output.format() != OutputFormat::Table {
+        return ctx.output.value(&graph);
+    }
+    print!(
+        "{}",
+        match format {
+            GraphFormat::Mermaid => mermaid(&graph),
+            GraphFormat::Dot => dot(&graph),
+            GraphFormat::D2 => d2(&graph),
+        }
+    );
+    Ok(())
+}
+
+/// Read relationships breadth-first from `root`, following subjects until
+/// `depth` levels have been read or `max_edges` relationships were found.
+async fn walk(vault: &VaultClient, root: &str, depth: u32, max_edges: usize) -> Result<Graph> {
+    let mut graph = Graph {
+        root: root.to_string(),
+        depth,
+        nodes: vec![root.to_string()],
+        edges: Vec::new(),
+        truncated: false,
+    };
+    let mut seen: HashSet<String> = HashSet::from([root.to_string()]);
+    let mut queue = VecDeque::from([(root.to_string(), 1)]);
+
+    while let Some((resource, level)) = queue.pop_front() {
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut request = vault.relationships().list().resource(&resource).limit(PAGE_SIZE);
+            if let Some(c) = &cursor {
+                request = request.cursor(c);
+            }
+            let page = request.await?;
+            for rel in &page.relationships {
+                if graph.edges.len() == max_edges {
+                    graph.truncated = true;
+                    return Ok(graph);
+                }
+                let object = object_of(rel.subject());
+                if seen.insert(object.to_string()) {
+                    graph.nodes.push(object.to_string());
+                    if level < depth && !object.ends_with(":*") {
+                        queue.push_back((object.to_string(), level + 1));
+                    }
+                }
+                graph.edges.push(Tuple {
+                    resource: rel.resource().to_string(),
+                    relation: rel.relation().to_string(),
+                    subject: rel.subject().to_string(),
+                });
+            }
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+    }
+    Ok(graph)
+}
+
+/// The object of a subject, without a subject set relation.
+fn object_of(subject: &str) -> &str {
+    subject.split_once('#').map_or(subject, |(object, _)| object)
+}
+
+/// The edge label: the relation, plus the subject set relation if any.
+fn label(edge: &Tuple) -> String {
+    match edge.subject.split_once('#') {
+        Some((_, set)) => format!("{} ({set})", edge.relation),
+        None => edge.relation.clone(),
+    }
+}
+
+/// The graph as a Mermaid flowchart. Nodes get positional IDs, since object
+/// IDs may contain characters Mermaid does not allow in identifiers.
+fn mermaid(graph: &Graph) -> String {
+    let escape = |text: &str| text.replace('"', "#quot;");
+    let id = |object: &str| graph.nodes.iter().position(|node| node == object).unwrap_or(0);
+
+    let mut out = String::from("graph LR\n");
+    for (index, node) in graph.nodes.iter().enumerate() {
+        let _ = writeln!(out, "    n{index}[\"{}\"]", escape(node));
+    }
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "    n{} -->|\"{}\"| n{}",
+            id(&edge.resource),
+            escape(&label(edge)),
+            id(object_of(&edge.subject))
+        );
+    }
+    out
+}
+
+/// The graph as Graphviz DOT.
+fn dot(graph: &Graph) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut out = String::from("digraph relationships {\n    rankdir=LR;\n    node [shape=box];\n");
+    let _ = writeln!(out, "    {} [style=bold];", quote(&graph.root));
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "    {} -> {} [label={}];",
+            quote(&edge.resource),
+            quote(object_of(&edge.subject)),
+            quote(&label(edge))
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// The graph as D2.
+fn d2(graph: &Graph) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut out = String::from("direction: right\n");
+    let _ = writeln!(out, "{}.style.bold: true", quote(&graph.root));
+    for edge in &graph.edges {
+        let _ = writeln!(
+            out,
+            "{} -> {}: {}",
+            quote(&edge.resource),
+            quote(object_of(&edge.subject)),
+            quote(&label(edge))
+        );
+    }
+    out
+}
+
+/// A standalone HTML page that renders the graph with Mermaid.
+fn viewer(graph: &Graph) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+    };
+    let types: BTreeSet<&str> =
+        graph.nodes.iter().filter_map(|node| node.split_once(':').map(|(t, _)| t)).collect();
+
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n\
+         <style>body {{ font-family: sans-serif; margin: 2rem; }} \
+         p {{ color: #666; }}</style>\n\
+         <script type=\"module\">\n\
+         import mermaid from \"https://cdn.jsdelivr.net/npm/mermaid@11/dist/mermaid.esm.min.mjs\";\n\
+         mermaid.initialize({{ startOnLoad: true, maxEdges: 5000 }});\n\
+         </script>\n</head>\n<body>\n<h1>{title}</h1>\n\
+         <p>{edges} relationships, depth {depth}; types: {types}</p>\n\
+         <pre class=\"mermaid\">\n{graph}</pre>\n</body>\n</html>\n",
+        title = escape(&graph.root),
+        edges = graph.edges.len(),
+        depth = graph.depth,
+        types = escape(&types.into_iter().collect::<Vec<_>>().join(", ")),
+        graph = escape(&mermaid(graph)),
+    )
+}
+
+/// Write the HTML viewer to a temporary file.
+fn write_viewer(graph: &Graph) -> Result<PathBuf> {
+    let name: String = graph
+        .root
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let path = std::env::temp_dir().join(format!("inferadb-graph-{name}.html"));
+    std::fs::write(&path, viewer(graph))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Graph {
+        let edge = |resource: &str, relation: &str, subject: &str| Tuple {
+            resource: resource.to_string(),
+            relation: relation.to_string(),
+            subject: subject.to_string(),
+        };
+        Graph {
+            root: "document:readme".to_string(),
+            depth: 2,
+            nodes: vec![
+                "document:readme".to_string(),
+                "user:alice".to_string(),
+                "group:eng".to_string(),
+            ],
+            edges: vec![
+                edge("document:readme", "owner", "user:alice"),
+                edge("document:readme", "viewer", "group:eng#member"),
+                edge("group:eng", "member", "user:alice"),
+            ],
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(GraphFormat::parse("Mermaid").unwrap(), GraphFormat::Mermaid);
+        assert_eq!(GraphFormat::parse("graphviz").unwrap(), GraphFormat::Dot);
+        assert_eq!(GraphFormat::parse("d2").unwrap(), GraphFormat::D2);
+        assert!(GraphFormat::parse("svg").is_err());
+    }
+
+    #[test]
+    fn test_mermaid() {
+        let out = mermaid(&sample());
+        assert!(out.starts_with("graph LR\n    n0[\"document:readme\"]\n"));
+        assert!(out.contains("    n0 -->|\"viewer (member)\"| n2\n"));
+        assert!(out.contains("    n2 -->|\"member\"| n1\n"));
+    }
+
+    #[test]
+    fn test_dot_and_d2() {
+        let graph = sample();
+        assert!(
+            dot(&graph).contains(
+                "    \"document:readme\" -> \"group:eng\" [label=\"viewer (member)\"];\n"
+            )
+        );
+        assert!(d2(&graph).contains("\"group:eng\" -> \"user:alice\": \"member\"\n"));
+    }
+
+    #[test]
+    fn test_viewer() {
+        let html = viewer(&sample());
+        assert!(html.contains("<title>document:readme</title>"));
+        assert!(html.contains("3 relationships, depth 2; types: document, group, user"));
+        assert!(html.contains("n0 --&gt;|&quot;owner&quot;| n1"));
+    }
+}
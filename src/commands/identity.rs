@@ -1,13 +1,137 @@
 //! Identity and diagnostic commands: whoami, status, ping, doctor, health, version.
 
-use std::time::{Duration, Instant};
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
 
 use bon::builder;
-use serde::Serialize;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    client::Context,
+    config::{
+        CachedIdentity, Config, CredentialStore, IdentityCache, StateLock,
+        project::{self, TrustStore},
+    },
+    error::{Error, Result},
+    output::{
+        glyphs::{CHECK, CROSS, RULE, WARNING},
+        metrics,
+    },
+};
+
+/// Time allowed for the identity lookup before falling back to the cache.
+const IDENTITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where a displayed identity came from.
+enum IdentityLookup {
+    /// Fetched from the server just now.
+    Live(CachedIdentity),
+    /// Served from the local cache because the server could not be reached.
+    Cached { identity: CachedIdentity, reason: String },
+    /// Neither the server nor the cache had an identity.
+    Unavailable(String),
+}
+
+/// Identity as it appears in structured output.
+#[derive(Serialize)]
+struct IdentityOutput {
+    id: String,
+    email: String,
+    name: Option<String>,
+    fetched_at: String,
+    stale: bool,
+}
+
+impl IdentityLookup {
+    fn output(&self) -> Option<IdentityOutput> {
+        let (identity, stale) = match self {
+            Self::Live(identity) => (identity, false),
+            Self::Cached { identity, .. } => (identity, true),
+            Self::Unavailable(_) => return None,
+        };
+        Some(IdentityOutput {
+            id: identity.id.clone(),
+            email: identity.email.clone(),
+            name: identity.name.clone(),
+            fetched_at: identity.fetched_at.to_rfc3339(),
+            stale,
+        })
+    }
+
+    /// Print the identity line, marking cached identities as stale.
+    fn print(&self, ctx: &Context) {
+        let describe = |identity: &CachedIdentity| match &identity.name {
+            Some(name) => format!("{name} <{}> ({})", identity.email, identity.id),
+            None => format!("{} ({})", identity.email, identity.id),
+        };
+        match self {
+            Self::Live(identity) => println!("User: {}", describe(identity)),
+            Self::Cached { identity, reason } => {
+                println!(
+                    "User: {} [stale, cached {}]",
+                    describe(identity),
+                    crate::output::dates::format(identity.fetched_at)
+                );
+                ctx.output.warn(&format!("Offline: showing cached identity ({reason})."));
+            },
+            Self::Unavailable(reason) => {
+                ctx.output.warn(&format!("Could not fetch identity: {reason}"));
+            },
+        }
+    }
+}
 
-use crate::{client::Context, config::CredentialStore, error::Result};
+/// Fetch the signed-in account from the control plane.
+async fn fetch_identity(ctx: &Context) -> Result<CachedIdentity> {
+    let client = ctx.client().await?;
+    let account = tokio::time::timeout(IDENTITY_TIMEOUT, client.account().get())
+        .await
+        .map_err(|_| Error::other("timed out"))??;
+    Ok(CachedIdentity {
+        id: account.id,
+        email: account.email,
+        name: account.name,
+        fetched_at: Utc::now(),
+    })
+}
+
+/// Fetch and cache the profile's identity, falling back to the cache when the
+/// server can't be reached.
+async fn lookup_identity(ctx: &Context, profile: &str) -> IdentityLookup {
+    let cache = IdentityCache::new();
+    match fetch_identity(ctx).await {
+        Ok(identity) => {
+            // A failed write only means no offline fallback next time
+            let _ = cache.store(profile, &identity);
+            IdentityLookup::Live(identity)
+        },
+        Err(e) => cached_identity(&cache, profile, e.to_string()),
+    }
+}
+
+/// The signed-in account of the active profile, live or from the cache.
+pub async fn signed_in_identity(ctx: &Context) -> Option<CachedIdentity> {
+    match lookup_identity(ctx, ctx.effective_profile_name()).await {
+        IdentityLookup::Live(identity) | IdentityLookup::Cached { identity, .. } => Some(identity),
+        IdentityLookup::Unavailable(_) => None,
+    }
+}
+
+fn cached_identity(cache: &IdentityCache, profile: &str, reason: String) -> IdentityLookup {
+    match cache.load(profile) {
+        Ok(Some(identity)) => IdentityLookup::Cached { identity, reason },
+        _ => IdentityLookup::Unavailable(reason),
+    }
+}
 
 /// Show current user and profile info.
+///
+/// When signed in, the account is fetched from the control plane and cached
+/// per profile; offline, the cached identity is shown and marked stale.
 pub async fn whoami(ctx: &Context) -> Result<()> {
     #[derive(Serialize)]
     struct WhoamiOutput {
@@ -17,6 +141,8 @@ pub async fn whoami(ctx: &Context) -> Result<()> {
         vault: Option<String>,
         authenticated: bool,
         token_expires: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<IdentityOutput>,
     }
 
     let profile_name = ctx.effective_profile_name();
@@ -24,6 +150,8 @@ pub async fn whoami(ctx: &Context) -> Result<()> {
     let credentials = store.load(profile_name)?;
     let authenticated = credentials.is_some();
     let token_expires = credentials.as_ref().and_then(|c| c.expires_at).map(|dt| dt.to_rfc3339());
+    let identity =
+        if authenticated { Some(lookup_identity(ctx, profile_name).await) } else { None };
 
     let output = WhoamiOutput {
         profile: profile_name.to_string(),
@@ -32,9 +160,13 @@ pub async fn whoami(ctx: &Context) -> Result<()> {
         vault: ctx.profile.vault.clone(),
         authenticated,
         token_expires,
+        user: identity.as_ref().and_then(IdentityLookup::output),
     };
 
     if ctx.output.format() == crate::output::OutputFormat::Table {
+        if let Some(identity) = &identity {
+            identity.print(ctx);
+        }
         println!("Profile: {}", output.profile);
         println!("URL: {}", output.url);
         if let Some(ref org) = output.org {
@@ -60,12 +192,17 @@ pub async fn whoami(ctx: &Context) -> Result<()> {
 }
 
 /// Check service status.
+///
+/// When signed in, also shows the account: fetched live while the service is
+/// healthy, or the cached identity (marked stale) when it is not.
 pub async fn status(ctx: &Context) -> Result<()> {
     #[derive(Serialize)]
     struct StatusOutput {
         service: String,
         status: String,
         latency_ms: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<IdentityOutput>,
     }
 
     let url = ctx.profile.url_or_default();
@@ -98,7 +235,22 @@ pub async fn status(ctx: &Context) -> Result<()> {
         },
     };
 
-    let output = StatusOutput { service: url.to_string(), status: status.clone(), latency_ms };
+    let profile_name = ctx.effective_profile_name();
+    let signed_in = CredentialStore::new().exists(profile_name);
+    let identity = if signed_in && status == "healthy" {
+        Some(lookup_identity(ctx, profile_name).await)
+    } else if signed_in {
+        Some(cached_identity(&IdentityCache::new(), profile_name, format!("service {status}")))
+    } else {
+        None
+    };
+
+    let output = StatusOutput {
+        service: url.to_string(),
+        status: status.clone(),
+        latency_ms,
+        user: identity.as_ref().and_then(IdentityLookup::output),
+    };
 
     if ctx.output.format() == crate::output::OutputFormat::Table {
         if status == "healthy" {
@@ -106,6 +258,9 @@ pub async fn status(ctx: &Context) -> Result<()> {
         } else {
             ctx.output.error(&format!("Service: {status}"));
         }
+        if let Some(identity) = &identity {
+            identity.print(ctx);
+        }
     } else {
         ctx.output.value(&output)?;
     }
@@ -187,18 +342,18 @@ pub async fn doctor(ctx: &Context) -> Result<()> {
             if let Some(host) = parsed.host_str() {
                 match tokio::net::lookup_host(format!("{host}:443")).await {
                     Ok(_) => {
-                        println!("✓ ({}ms)", start.elapsed().as_millis());
+                        println!("{CHECK} ({}ms)", start.elapsed().as_millis());
                     },
                     Err(e) => {
-                        println!("✗ Failed: {e}");
+                        println!("{CROSS} Failed: {e}");
                     },
                 }
             } else {
-                println!("✗ Invalid URL");
+                println!("{CROSS} Invalid URL");
             }
         },
         Err(e) => {
-            println!("✗ Invalid URL: {e}");
+            println!("{CROSS} Invalid URL: {e}");
         },
     }
 
@@ -208,18 +363,18 @@ pub async fn doctor(ctx: &Context) -> Result<()> {
     match client.get(format!("{url}/health")).timeout(Duration::from_secs(10)).send().await {
         Ok(resp) => {
             if resp.status().is_success() {
-                println!("✓ ({}ms)", start.elapsed().as_millis());
+                println!("{CHECK} ({}ms)", start.elapsed().as_millis());
             } else {
-                println!("⚠ Response: {}", resp.status());
+                println!("{WARNING} Response: {}", resp.status());
             }
         },
         Err(e) => {
             if e.is_timeout() {
-                println!("✗ Timeout");
+                println!("{CROSS} Timeout");
             } else if e.is_connect() {
-                println!("✗ Connection failed");
+                println!("{CROSS} Connection failed");
             } else {
-                println!("✗ Error: {e}");
+                println!("{CROSS} Error: {e}");
             }
         },
     }
@@ -230,25 +385,25 @@ pub async fn doctor(ctx: &Context) -> Result<()> {
     let profile_name = ctx.effective_profile_name();
     if let Some(creds) = store.load(profile_name)? {
         if creds.is_expired() {
-            println!("⚠ Token expired");
+            println!("{WARNING} Token expired");
             println!("   Run: inferadb login");
         } else if creds.expires_soon() {
-            println!("⚠ Token expires soon");
+            println!("{WARNING} Token expires soon");
             println!("   Consider running: inferadb login");
         } else {
-            println!("✓ Valid token");
+            println!("{CHECK} Valid token");
         }
     } else {
-        println!("✗ Not authenticated");
+        println!("{CROSS} Not authenticated");
         println!("   Run: inferadb login");
     }
 
     // Profile check
     print!("Profile... ");
     if ctx.profile.org.is_some() && ctx.profile.vault.is_some() {
-        println!("✓ Complete");
+        println!("{CHECK} Complete");
     } else {
-        println!("⚠ Incomplete");
+        println!("{WARNING} Incomplete");
         if ctx.profile.org.is_none() {
             println!("   Missing: organization ID");
         }
@@ -262,7 +417,20 @@ pub async fn doctor(ctx: &Context) -> Result<()> {
 }
 
 /// Show service health dashboard.
-pub async fn health(ctx: &Context, watch: bool, verbose: bool) -> Result<()> {
+///
+/// With `metrics_listen`, watch mode also serves probe results as Prometheus
+/// metrics on that address.
+pub async fn health(
+    ctx: &Context,
+    watch: bool,
+    verbose: bool,
+    metrics_listen: Option<&str>,
+) -> Result<()> {
+    if let Some(addr) = metrics_listen {
+        let bound = metrics::serve(addr)?;
+        ctx.output.info(&format!("Serving metrics on http://{bound}/metrics"));
+    }
+
     if watch {
         loop {
             print!("\x1B[2J\x1B[1;1H"); // Clear screen
@@ -285,10 +453,23 @@ async fn show_health(ctx: &Context, verbose: bool) -> Result<()> {
     let response =
         client.get(format!("{url}/health")).timeout(Duration::from_secs(10)).send().await;
 
+    let latency = start.elapsed();
+    let status = match &response {
+        Ok(resp) if resp.status().is_success() => "healthy",
+        Ok(_) => "degraded",
+        Err(e) if e.is_timeout() => "timeout",
+        Err(_) => "unreachable",
+    };
+    metrics::inc(metrics::HEALTH_CHECKS_TOTAL, &[("status", status)]);
+    metrics::set(metrics::HEALTH_UP, &[], if status == "healthy" { 1.0 } else { 0.0 });
+    if response.is_ok() {
+        metrics::observe(metrics::HEALTH_LATENCY_SECONDS, &[], latency.as_secs_f64());
+    }
+
     match response {
         Ok(resp) if resp.status().is_success() => {
-            println!("Status: ✓ healthy");
-            println!("Latency: {}ms", start.elapsed().as_millis());
+            println!("Status: {CHECK} healthy");
+            println!("Latency: {}ms", latency.as_millis());
 
             if verbose && let Ok(body) = resp.text().await {
                 println!();
@@ -297,13 +478,13 @@ async fn show_health(ctx: &Context, verbose: bool) -> Result<()> {
             }
         },
         Ok(resp) => {
-            println!("Status: ⚠ degraded ({})", resp.status());
+            println!("Status: {WARNING} degraded ({})", resp.status());
         },
         Err(e) => {
             if e.is_timeout() {
-                println!("Status: ✗ timeout");
+                println!("Status: {CROSS} timeout");
             } else {
-                println!("Status: ✗ unreachable");
+                println!("Status: {CROSS} unreachable");
             }
         },
     }
@@ -314,12 +495,41 @@ async fn show_health(ctx: &Context, verbose: bool) -> Result<()> {
 }
 
 /// Show CLI version.
-pub async fn version(ctx: &Context) -> Result<()> {
-    let _ = ctx; // Suppress unused warning
-    let version = env!("CARGO_PKG_VERSION");
-    let name = env!("CARGO_PKG_NAME");
+///
+/// With `verbose`, includes build provenance; with `sbom`, prints a `CycloneDX`
+/// document for the binary's dependencies instead.
+pub async fn version(ctx: &Context, verbose: bool, sbom: bool) -> Result<()> {
+    if sbom {
+        println!("{}", serde_json::to_string_pretty(&crate::provenance::sbom())?);
+        return Ok(());
+    }
+
+    let info = crate::provenance::BuildInfo::current();
+
+    if !verbose {
+        println!("{} {}", info.name, info.version);
+        return Ok(());
+    }
 
-    println!("{name} {version}");
+    if ctx.output.format() != crate::output::OutputFormat::Table {
+        return ctx.output.value(&info);
+    }
+
+    let unknown = "unknown";
+    println!("{} {}", info.name, info.version);
+    println!();
+    println!("Git commit:  {}", info.git_sha.unwrap_or(unknown));
+    println!(
+        "Build date:  {}",
+        info.build_date.map_or_else(|| unknown.to_string(), |d| d.to_rfc3339())
+    );
+    println!("Rustc:       {}", info.rustc.unwrap_or(unknown));
+    println!("Target:      {}", info.target.unwrap_or(unknown));
+    println!("Profile:     {}", info.profile.unwrap_or(unknown));
+    println!(
+        "Features:    {}",
+        if info.features.is_empty() { "none".to_string() } else { info.features.join(", ") }
+    );
 
     Ok(())
 }
@@ -335,6 +545,18 @@ pub async fn config_show(ctx: &Context, key: Option<&str>) -> Result<()> {
             },
             "output.format" => println!("{}", ctx.config.output.format),
             "output.color" => println!("{}", ctx.config.output.color),
+            "output.unicode" => println!("{}", ctx.config.output.unicode),
+            "output.accessible" => println!("{}", ctx.config.output.accessible),
+            "auth.expiry_warning_minutes" => {
+                println!("{}", ctx.config.auth.expiry_warning_minutes);
+            },
+            "cache.enabled" => println!("{}", ctx.config.cache.enabled),
+            "cache.ttl_seconds" => println!("{}", ctx.config.cache.ttl_seconds),
+            "notify.webhook" => {
+                if let Some(ref url) = ctx.config.notify.webhook {
+                    println!("{url}");
+                }
+            },
             _ => {
                 return Err(crate::error::Error::invalid_arg(format!("Unknown key: {k}")));
             },
@@ -392,13 +614,20 @@ pub async fn config_explain(ctx: &Context) -> Result<()> {
     println!();
     println!("  1. CLI flags           (e.g., @prod, --vault)");
     println!("  2. Environment vars    (INFERADB_*)");
-    println!("  3. Project config      (.inferadb-cli.yaml in current directory)");
+    println!("  3. Project config      (nearest trusted .inferadb-cli.yaml)");
     println!("  4. User config         (~/.config/inferadb/cli.yaml)");
     println!("  5. Defaults");
     println!();
     println!("Current effective values:");
     println!();
     println!("  Profile: {}", ctx.effective_profile_name());
+    if let Some(path) = project::find() {
+        let trusted = if TrustStore::new().is_trusted(&path) { "trusted" } else { "untrusted" };
+        println!("  Project config: {} ({trusted})", path.display());
+    }
+    if let Some(pinned) = ctx.config.pinned_profile() {
+        println!("  Pinned profile: {pinned}");
+    }
     println!("  URL: {}", ctx.profile.url_or_default());
     if let Some(ref org) = ctx.profile.org {
         println!("  Organization: {org}");
@@ -409,8 +638,164 @@ pub async fn config_explain(ctx: &Context) -> Result<()> {
     Ok(())
 }
 
+/// The project config a trust command applies to: `path`, or the nearest one.
+fn project_config(path: Option<&str>) -> Result<PathBuf> {
+    match path {
+        Some(p) => Ok(PathBuf::from(p)),
+        None => project::find().ok_or_else(|| {
+            Error::config(format!(
+                "No {} in this directory or its parents",
+                project::PROJECT_CONFIG_FILE
+            ))
+        }),
+    }
+}
+
+/// Trust a project config with its current contents.
+pub async fn config_trust(ctx: &Context, path: Option<&str>) -> Result<()> {
+    let path = project_config(path)?;
+    let lines = project::summary(&path)?;
+    TrustStore::new().trust(&path)?;
+
+    ctx.output.success(&format!("Trusted {}.", path.display()));
+    for line in lines {
+        ctx.output.info(&format!("  {line}"));
+    }
+    Ok(())
+}
+
+/// Stop trusting a project config.
+pub async fn config_untrust(ctx: &Context, path: Option<&str>) -> Result<()> {
+    let path = project_config(path)?;
+    if TrustStore::new().untrust(&path)? {
+        ctx.output.success(&format!("No longer trusting {}.", path.display()));
+    } else {
+        ctx.output.info(&format!("{} was not trusted.", path.display()));
+    }
+    Ok(())
+}
+
+/// Stored statistics for a vault, compared by `stats --alert-on-change`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StatsSnapshot {
+    taken_at: chrono::DateTime<Utc>,
+    schema_versions: u64,
+    relationships: u64,
+}
+
+/// Latest snapshot per vault, keyed by `org/vault`.
+type SnapshotStore = BTreeMap<String, StatsSnapshot>;
+
+fn snapshots_path() -> Result<PathBuf> {
+    Config::state_dir()
+        .map(|dir| dir.join("stats.yaml"))
+        .ok_or_else(|| Error::config("Could not determine state directory"))
+}
+
+/// Load stored snapshots, or an empty store if none exist.
+fn load_snapshots() -> Result<SnapshotStore> {
+    let path = snapshots_path()?;
+    if !path.exists() {
+        return Ok(SnapshotStore::new());
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    if contents.trim().is_empty() {
+        return Ok(SnapshotStore::new());
+    }
+    serde_yaml::from_str(&contents)
+        .map_err(|e| Error::config(format!("Invalid stats snapshot file {}: {e}", path.display())))
+}
+
+fn save_snapshots(store: &SnapshotStore) -> Result<()> {
+    let path = snapshots_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_yaml::to_string(store)?)?;
+    Ok(())
+}
+
+/// Relationship count from the vault's last exact `stats` snapshot, used to
+/// estimate progress for long-running exports.
+pub(super) fn last_relationship_count(vault_key: &str) -> Option<u64> {
+    load_snapshots().ok()?.get(vault_key).map(|snapshot| snapshot.relationships)
+}
+
+/// Parse a change threshold such as `10%` or `2.5`, in percent.
+fn parse_threshold(spec: &str) -> Result<f64> {
+    let number = spec.trim().trim_end_matches('%');
+    match number.parse::<f64>() {
+        Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+        _ => Err(Error::invalid_arg(format!(
+            "Invalid change threshold '{spec}'. Use a percentage like 10%"
+        ))),
+    }
+}
+
+impl StatsSnapshot {
+    /// Describe each metric that moved more than `threshold` percent since
+    /// `previous`.
+    fn changes(&self, previous: &Self, threshold: f64) -> Vec<String> {
+        [
+            ("relationships", previous.relationships, self.relationships),
+            ("schema versions", previous.schema_versions, self.schema_versions),
+        ]
+        .into_iter()
+        .filter_map(|(name, before, after)| {
+            if before == after {
+                return None;
+            }
+            #[allow(clippy::cast_precision_loss)]
+            let percent = if before == 0 {
+                f64::INFINITY
+            } else {
+                (after as f64 - before as f64) / before as f64 * 100.0
+            };
+            (percent.abs() > threshold).then(|| {
+                if percent.is_finite() {
+                    format!("{name} {percent:+.1}% ({before} -> {after})")
+                } else {
+                    format!("{name} {before} -> {after}")
+                }
+            })
+        })
+        .collect()
+    }
+}
+
+/// Count every relationship in the vault, following pagination.
+async fn count_relationships(vault: &inferadb::VaultClient) -> Result<u64> {
+    let mut count = 0;
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut request = vault.relationships().list().limit(1000);
+        if let Some(c) = &cursor {
+            request = request.cursor(c);
+        }
+        let page = request.await?;
+        count += page.relationships.len() as u64;
+
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            return Ok(count);
+        }
+    }
+}
+
 /// Show vault statistics.
-pub async fn stats(ctx: &Context, trends: bool, compact: bool) -> Result<()> {
+///
+/// Snapshots with exact counts are stored per vault. With `alert_on_change`,
+/// the relationships are counted in full and the command fails, describing
+/// the drift, when a metric moved more than the threshold since the previous
+/// snapshot.
+pub async fn stats(
+    ctx: &Context,
+    trends: bool,
+    compact: bool,
+    alert_on_change: Option<&str>,
+) -> Result<()> {
+    let threshold = alert_on_change.map(parse_threshold).transpose()?;
+
     let client = ctx.client().await?;
     let vault = client.vault();
 
@@ -419,11 +804,30 @@ pub async fn stats(ctx: &Context, trends: bool, compact: bool) -> Result<()> {
     let schema_list = schemas.list().await?;
     let active_schema = schemas.get_active().await.ok();
 
-    // Get relationship sample (to count)
+    // Get relationship sample (to count), or the exact count when alerting
     let rels = vault.relationships();
     let sample = rels.list().limit(1000).await?;
-    let rel_count = sample.relationships.len();
-    let has_more = sample.next_cursor.is_some();
+    let mut rel_count = sample.relationships.len() as u64;
+    let mut has_more = sample.next_cursor.is_some();
+    if has_more && threshold.is_some() {
+        rel_count = count_relationships(&vault).await?;
+        has_more = false;
+    }
+
+    // Only exact counts are worth comparing later
+    let mut previous = None;
+    if !has_more {
+        let vault_key = format!("{}/{}", client.org_id(), client.vault_id());
+        let snapshot = StatsSnapshot {
+            taken_at: Utc::now(),
+            schema_versions: schema_list.items.len() as u64,
+            relationships: rel_count,
+        };
+        let _lock = StateLock::acquire(&snapshots_path()?, "stats snapshots")?;
+        let mut store = load_snapshots()?;
+        previous = store.insert(vault_key, snapshot.clone()).map(|p| (p, snapshot));
+        save_snapshots(&store)?;
+    }
 
     if compact {
         // Single-line compact output
@@ -436,42 +840,56 @@ pub async fn stats(ctx: &Context, trends: bool, compact: bool) -> Result<()> {
             print!("+");
         }
         println!();
-        return Ok(());
-    }
+    } else {
+        println!("Vault Statistics");
+        println!("================");
+        println!();
 
-    println!("Vault Statistics");
-    println!("================");
-    println!();
+        // Schema stats
+        println!("Schemas:");
+        println!("  Versions: {}", schema_list.items.len());
+        if let Some(active) = active_schema {
+            println!("  Active: v{} ({})", active.version, active.id);
+        } else {
+            println!("  Active: (none)");
+        }
 
-    // Schema stats
-    println!("Schemas:");
-    println!("  Versions: {}", schema_list.items.len());
-    if let Some(active) = active_schema {
-        println!("  Active: v{} ({})", active.version, active.id);
-    } else {
-        println!("  Active: (none)");
-    }
+        // Relationship stats
+        println!();
+        println!("Relationships:");
+        if has_more {
+            println!("  Count: {rel_count}+ (sampled)");
+        } else {
+            println!("  Count: {rel_count}");
+        }
 
-    // Relationship stats
-    println!();
-    println!("Relationships:");
-    if has_more {
-        println!("  Count: {rel_count}+ (sampled)");
-    } else {
-        println!("  Count: {rel_count}");
-    }
+        if trends {
+            println!();
+            println!("Trends:");
+            ctx.output.info("Historical trends require time-series data.");
+            ctx.output.info("Use 'inferadb what-changed --since 1d' to see recent activity.");
+        }
 
-    if trends {
         println!();
-        println!("Trends:");
-        ctx.output.info("Historical trends require time-series data.");
-        ctx.output.info("Use 'inferadb what-changed --since 1d' to see recent activity.");
+        ctx.output.info("For detailed stats, use the InferaDB Dashboard.");
     }
 
-    println!();
-    ctx.output.info("For detailed stats, use the InferaDB Dashboard.");
-
-    Ok(())
+    let Some(threshold) = threshold else {
+        return Ok(());
+    };
+    let Some((previous, current)) = previous else {
+        ctx.output.info("No previous snapshot to compare against; saved a baseline.");
+        return Ok(());
+    };
+    let changes = current.changes(&previous, threshold);
+    if changes.is_empty() {
+        return Ok(());
+    }
+    Err(Error::other(format!(
+        "Stats changed more than {threshold}% since {}: {}",
+        crate::output::dates::format(previous.taken_at),
+        changes.join("; ")
+    )))
 }
 
 /// Show recent changes in the vault.
@@ -623,7 +1041,7 @@ pub async fn what_changed(
         for log in schema_changes.iter().take(5) {
             println!(
                 "  {} - {:?} by {}",
-                log.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                crate::output::dates::format_precise(log.timestamp),
                 log.action,
                 log.actor.id
             );
@@ -639,7 +1057,7 @@ pub async fn what_changed(
         for log in rel_changes.iter().take(5) {
             println!(
                 "  {} - {:?} by {}",
-                log.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                crate::output::dates::format_precise(log.timestamp),
                 log.action,
                 log.actor.id
             );
@@ -655,7 +1073,7 @@ pub async fn what_changed(
         for log in other_changes.iter().take(5) {
             println!(
                 "  {} - {:?} by {}",
-                log.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                crate::output::dates::format_precise(log.timestamp),
                 log.action,
                 log.actor.id
             );
@@ -761,15 +1179,15 @@ fn show_user_offboarding_template(subject: &str, format: &str) {
         println!("# Step 3: Verify");
         println!("inferadb relationships list --subject \"$USER\"");
     } else {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!("User Offboarding Workflow");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!();
         println!("Use this workflow to safely remove all access for a departing user.");
         println!();
         println!("SUBJECT: {subject}");
         println!();
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!();
         println!("# Step 1: Review what the user has access to");
         println!("inferadb relationships list --subject {subject} -o json > access-backup.json");
@@ -803,9 +1221,9 @@ fn show_batch_check_template(subject: &str, format: &str) {
         println!("  done");
         println!("done");
     } else {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!("Batch Permission Check");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!();
         println!("Check multiple permissions efficiently.");
         println!();
@@ -818,6 +1236,9 @@ fn show_batch_check_template(subject: &str, format: &str) {
         println!();
         println!("# Check with JSON output for parsing");
         println!("inferadb check {subject} view document:readme -o json");
+        println!();
+        println!("# Run a file of checks with expected results");
+        println!("inferadb check batch --file checks.yaml");
     }
 }
 
@@ -839,9 +1260,9 @@ fn show_debug_denial_template(subject: &str, format: &str) {
         println!("echo \"User's relationships...\"");
         println!("inferadb relationships list --subject \"$USER\"");
     } else {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!("Debug Access Denial");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!();
         println!("Investigate why access was denied.");
         println!();
@@ -883,9 +1304,9 @@ fn show_schema_migration_template(format: &str) {
         println!("  inferadb schemas push \"$SCHEMA_FILE\" --activate");
         println!("fi");
     } else {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!("Safe Schema Migration");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!();
         println!("Follow these steps to safely migrate your schema.");
         println!();
@@ -919,9 +1340,9 @@ fn show_schema_rollback_template(format: &str) {
         println!("echo \"Verifying rollback:\"");
         println!("inferadb schemas list");
     } else {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!("Emergency Schema Rollback");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!();
         println!("Use this if you need to quickly rollback a schema.");
         println!();
@@ -957,9 +1378,9 @@ fn show_export_backup_template(format: &str) {
         println!();
         println!("echo \"Backup complete: $BACKUP_DIR\"");
     } else {
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!("Complete Vault Backup");
-        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", RULE.repeat(53));
         println!();
         println!("Create a complete backup of your vault.");
         println!();
@@ -1017,9 +1438,9 @@ pub async fn guide(ctx: &Context, name: Option<&str>) -> Result<()> {
 }
 
 fn show_quickstart_guide() {
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!("InferaDB Quickstart Guide");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!();
     println!("1. AUTHENTICATE");
     println!("   inferadb login");
@@ -1047,9 +1468,9 @@ fn show_quickstart_guide() {
 }
 
 fn show_concepts_guide() {
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!("Core Concepts Guide");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!();
     println!("SUBJECTS");
     println!("  Who is requesting access. Format: type:id");
@@ -1078,9 +1499,9 @@ fn show_concepts_guide() {
 }
 
 fn show_schema_deployment_guide() {
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!("Safe Schema Deployment Guide");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!();
     println!("RECOMMENDED WORKFLOW");
     println!();
@@ -1116,9 +1537,9 @@ fn show_schema_deployment_guide() {
 }
 
 fn show_production_checklist_guide() {
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!("Production Readiness Checklist");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!();
     println!("[ ] AUTHENTICATION");
     println!("    - Service accounts configured for each service");
@@ -1147,9 +1568,9 @@ fn show_production_checklist_guide() {
 }
 
 fn show_incident_response_guide() {
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!("Incident Response Guide");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{}", RULE.repeat(68));
     println!();
     println!("USERS UNEXPECTEDLY DENIED ACCESS");
     println!();
@@ -1181,7 +1602,7 @@ fn show_incident_response_guide() {
 }
 
 /// Parse a time specification like "1h", "1d", "yesterday", or ISO timestamp.
-fn parse_time_spec(spec: &str) -> chrono::DateTime<chrono::Utc> {
+pub fn parse_time_spec(spec: &str) -> chrono::DateTime<chrono::Utc> {
     use chrono::{Duration, Timelike, Utc};
 
     let now = Utc::now();
@@ -1223,3 +1644,35 @@ fn parse_time_spec(spec: &str) -> chrono::DateTime<chrono::Utc> {
         },
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_threshold() {
+        assert!((parse_threshold("10%").unwrap() - 10.0).abs() < f64::EPSILON);
+        assert!((parse_threshold("2.5").unwrap() - 2.5).abs() < f64::EPSILON);
+        assert!(parse_threshold("-5%").is_err());
+        assert!(parse_threshold("ten").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_changes() {
+        let snapshot = |schema_versions, relationships| StatsSnapshot {
+            taken_at: Utc::now(),
+            schema_versions,
+            relationships,
+        };
+        let previous = snapshot(2, 1000);
+
+        assert!(snapshot(2, 1050).changes(&previous, 10.0).is_empty());
+        assert_eq!(
+            snapshot(2, 850).changes(&previous, 10.0),
+            vec!["relationships -15.0% (1000 -> 850)"]
+        );
+        assert_eq!(snapshot(3, 1000).changes(&previous, 10.0).len(), 1);
+        assert_eq!(snapshot(2, 5).changes(&snapshot(2, 0), 10.0), vec!["relationships 0 -> 5"]);
+    }
+}
@@ -0,0 +1,949 @@
+//! Conversion to and from `OpenFGA` and `SpiceDB`.
+//!
+//! `export --format openfga` writes an `OpenFGA` store file (the authorization
+//! model in the `OpenFGA` DSL plus its tuples), and `--format spicedb` writes a
+//! zed validation file (the schema plus `resource#relation@subject` lines).
+//! `import` reads both back, converting the model or schema to IPL.
+//!
+//! Relations, permissions, usersets, arrows, and caveat references convert in
+//! both directions. Caveat and condition definitions do not; each conversion
+//! returns notes describing anything that needs review.
+
+use std::{collections::HashMap, fmt::Write};
+
+use serde::{Deserialize, Serialize};
+
+use super::bulk::ExportedRelationship;
+use crate::{
+    error::{Error, Result},
+    ipl::{self, Entity, Expr, Permission, Relation, Schema, SubjectType},
+};
+
+/// Stand-in reference for an `OpenFGA` `[...]` type restriction while parsing.
+const DIRECT: &str = "[direct]";
+
+/// An authorization system relationships can be converted to and from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Ecosystem {
+    OpenFga,
+    SpiceDb,
+}
+
+impl Ecosystem {
+    /// The ecosystem for an `--format` value.
+    pub(super) fn from_format(format: &str) -> Option<Self> {
+        match format {
+            "openfga" => Some(Self::OpenFga),
+            "spicedb" => Some(Self::SpiceDb),
+            _ => None,
+        }
+    }
+}
+
+/// Relationships and schema converted from another ecosystem.
+#[derive(Debug)]
+pub(super) struct Imported {
+    /// The model or schema as IPL, when the file has one.
+    pub(super) schema: Option<String>,
+    pub(super) relationships: Vec<ExportedRelationship>,
+    /// Anything lost or changed in conversion.
+    pub(super) notes: Vec<String>,
+}
+
+/// A file converted for another ecosystem.
+#[derive(Debug)]
+pub(super) struct Exported {
+    pub(super) content: String,
+    /// Anything lost or changed in conversion.
+    pub(super) notes: Vec<String>,
+}
+
+/// An `OpenFGA` store file, as read by `fga store import`.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoreFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tuples: Vec<Tuple>,
+}
+
+/// An `OpenFGA` relationship tuple.
+#[derive(Debug, Serialize, Deserialize)]
+struct Tuple {
+    user: String,
+    relation: String,
+    object: String,
+}
+
+/// A zed validation file, as read by `zed import` and the playground.
+#[derive(Debug, Serialize, Deserialize)]
+struct ValidationFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schema: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    relationships: String,
+}
+
+/// Convert the schema, when given, for `ecosystem`, ending where the
+/// relationships from [`export_rows`] follow.
+pub(super) fn export_header(ecosystem: Ecosystem, schema: Option<&Schema>) -> Result<Exported> {
+    let mut notes = Vec::new();
+    if let Some(schema) = schema {
+        let mut caveats: Vec<&str> = schema
+            .entities
+            .iter()
+            .flat_map(|e| &e.relations)
+            .flat_map(|r| &r.subjects)
+            .filter_map(|s| s.caveat.as_deref())
+            .collect();
+        caveats.sort_unstable();
+        caveats.dedup();
+        for caveat in caveats {
+            notes.push(format!(
+                "Caveat '{caveat}' is referenced but its definition is not converted."
+            ));
+        }
+    }
+
+    let mut content = match (ecosystem, schema) {
+        (Ecosystem::OpenFga, Some(schema)) => serde_yaml::to_string(&StoreFile {
+            name: None,
+            model: Some(render(schema, Dialect::OpenFga)),
+            tuples: Vec::new(),
+        })?,
+        (Ecosystem::SpiceDb, Some(schema)) => serde_yaml::to_string(&ValidationFile {
+            schema: Some(render(schema, Dialect::SpiceDb)),
+            relationships: String::new(),
+        })?,
+        (_, None) => String::new(),
+    };
+    content.push_str(match ecosystem {
+        Ecosystem::OpenFga => "tuples:\n",
+        Ecosystem::SpiceDb => "relationships: |\n",
+    });
+    Ok(Exported { content, notes })
+}
+
+/// Convert a page of relationships for `ecosystem`, to follow
+/// [`export_header`] or an earlier page.
+pub(super) fn export_rows(
+    ecosystem: Ecosystem,
+    relationships: &[ExportedRelationship],
+) -> Result<String> {
+    Ok(match ecosystem {
+        Ecosystem::OpenFga => {
+            let tuples: Vec<Tuple> = relationships
+                .iter()
+                .map(|r| Tuple {
+                    user: r.subject.clone(),
+                    relation: r.relation.clone(),
+                    object: r.resource.clone(),
+                })
+                .collect();
+            serde_yaml::to_string(&tuples)?
+        },
+        Ecosystem::SpiceDb => {
+            let mut lines = String::new();
+            for r in relationships {
+                let _ = writeln!(lines, "  {}#{}@{}", r.resource, r.relation, r.subject);
+            }
+            lines
+        },
+    })
+}
+
+/// Read an `OpenFGA` store or tuple file, or a zed validation or relationship
+/// file, converting any model or schema to IPL.
+pub(super) fn import(ecosystem: Ecosystem, content: &str) -> Result<Imported> {
+    let (source, relationships) = match ecosystem {
+        Ecosystem::OpenFga => {
+            let store = match serde_yaml::from_str::<StoreFile>(content) {
+                Ok(store) => store,
+                Err(e) => StoreFile {
+                    name: None,
+                    model: None,
+                    tuples: serde_yaml::from_str(content).map_err(|_| {
+                        Error::parse(format!("Not an OpenFGA store or tuple file: {e}"))
+                    })?,
+                },
+            };
+            let relationships = store
+                .tuples
+                .into_iter()
+                .map(|t| ExportedRelationship::new(t.object, t.relation, t.user))
+                .collect();
+            (store.model, relationships)
+        },
+        Ecosystem::SpiceDb => {
+            let file = serde_yaml::from_str::<ValidationFile>(content).unwrap_or_else(|_| {
+                ValidationFile { schema: None, relationships: content.to_string() }
+            });
+            (file.schema, parse_relationship_lines(&file.relationships)?)
+        },
+    };
+
+    let mut imported = Imported { schema: None, relationships, notes: Vec::new() };
+    if let Some(source) = source {
+        let (schema, renames) = match ecosystem {
+            Ecosystem::OpenFga => parse_openfga(&source, &mut imported.notes)?,
+            Ecosystem::SpiceDb => (parse_spicedb(&source, &mut imported.notes)?, HashMap::new()),
+        };
+        for rel in &mut imported.relationships {
+            let object_type = rel.resource.split_once(':').map_or("", |(t, _)| t);
+            if let Some(renamed) = renames.get(&(object_type.to_string(), rel.relation.clone())) {
+                rel.relation.clone_from(renamed);
+            }
+        }
+        imported.schema = Some(render(&schema, Dialect::Ipl));
+    }
+    Ok(imported)
+}
+
+/// Parse `resource#relation@subject` lines, ignoring blank lines, comments,
+/// and caveat contexts.
+pub(super) fn parse_relationship_lines(content: &str) -> Result<Vec<ExportedRelationship>> {
+    let mut relationships = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+        let line = line.split_once('[').map_or(line, |(rel, _)| rel).trim();
+        let parsed = line
+            .split_once('@')
+            .and_then(|(object, subject)| {
+                object.split_once('#').map(|(resource, relation)| (resource, relation, subject))
+            })
+            .filter(|(resource, relation, subject)| {
+                resource.contains(':') && !relation.is_empty() && subject.contains(':')
+            });
+        let Some((resource, relation, subject)) = parsed else {
+            return Err(Error::parse(format!(
+                "line {}: expected resource#relation@subject, got '{line}'",
+                index + 1
+            )));
+        };
+        relationships.push(ExportedRelationship::new(
+            resource.to_string(),
+            relation.to_string(),
+            subject.to_string(),
+        ));
+    }
+    Ok(relationships)
+}
+
+/// The IPL entity name for an object type (`user_group` becomes `UserGroup`).
+fn entity_name(type_name: &str) -> String {
+    type_name
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+// ============================================================================
+// Rendering
+// ============================================================================
+
+/// A schema language to render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Ipl,
+    OpenFga,
+    SpiceDb,
+}
+
+impl Dialect {
+    /// Name of an entity's type in this dialect.
+    fn type_name(self, entity: &str) -> String {
+        match self {
+            Self::Ipl => entity.to_string(),
+            Self::OpenFga | Self::SpiceDb => ipl::type_name(entity),
+        }
+    }
+
+    fn subject(self, subject: &SubjectType) -> String {
+        let mut out = self.type_name(&subject.entity);
+        if let Some(relation) = &subject.relation {
+            let _ = write!(out, "#{relation}");
+        }
+        if let Some(caveat) = &subject.caveat {
+            let _ = write!(out, " with {caveat}");
+        }
+        out
+    }
+
+    fn subjects(self, subjects: &[SubjectType]) -> String {
+        let rendered: Vec<String> = subjects.iter().map(|s| self.subject(s)).collect();
+        match self {
+            Self::Ipl | Self::SpiceDb => rendered.join(" | "),
+            Self::OpenFga => format!("[{}]", rendered.join(", ")),
+        }
+    }
+
+    /// Render an expression, parenthesizing every nested operator.
+    fn expr(self, expr: &Expr, nested: bool) -> String {
+        let (union, intersection, exclusion) = match self {
+            Self::Ipl => ("|", "&", "-"),
+            Self::SpiceDb => ("+", "&", "-"),
+            Self::OpenFga => ("or", "and", "but not"),
+        };
+        let join = |operands: &[Expr], op: &str| {
+            let rendered: Vec<String> = operands.iter().map(|e| self.expr(e, true)).collect();
+            rendered.join(&format!(" {op} "))
+        };
+        let rendered = match expr {
+            Expr::Ref(name) => return name.clone(),
+            Expr::Arrow(via, target) => {
+                return match self {
+                    Self::Ipl => format!("{via}.{target}"),
+                    Self::SpiceDb => format!("{via}->{target}"),
+                    Self::OpenFga => format!("{target} from {via}"),
+                };
+            },
+            Expr::Union(operands) => join(operands, union),
+            Expr::Intersection(operands) => join(operands, intersection),
+            Expr::Exclusion(base, subtract) => {
+                format!("{} {exclusion} {}", self.expr(base, true), self.expr(subtract, true))
+            },
+        };
+        if nested { format!("({rendered})") } else { rendered }
+    }
+}
+
+/// Render a schema in `dialect`.
+fn render(schema: &Schema, dialect: Dialect) -> String {
+    let mut out = String::new();
+    if dialect == Dialect::OpenFga {
+        out.push_str("model\n  schema 1.1\n");
+    }
+    for (index, entity) in schema.entities.iter().enumerate() {
+        if index > 0 || dialect == Dialect::OpenFga {
+            out.push('\n');
+        }
+        let name = dialect.type_name(&entity.name);
+        match dialect {
+            Dialect::Ipl => render_ipl_entity(&mut out, &name, entity),
+            Dialect::SpiceDb => render_spicedb_definition(&mut out, &name, entity),
+            Dialect::OpenFga => render_openfga_type(&mut out, &name, entity),
+        }
+    }
+    out
+}
+
+fn render_ipl_entity(out: &mut String, name: &str, entity: &Entity) {
+    if entity.relations.is_empty() && entity.permissions.is_empty() {
+        let _ = writeln!(out, "entity {name} {{}}");
+        return;
+    }
+    let _ = writeln!(out, "entity {name} {{");
+    if !entity.relations.is_empty() {
+        out.push_str("    relations {\n");
+        for relation in &entity.relations {
+            let subjects = Dialect::Ipl.subjects(&relation.subjects);
+            let _ = writeln!(out, "        {}: {subjects}", relation.name);
+        }
+        out.push_str("    }\n");
+    }
+    if !entity.permissions.is_empty() {
+        if !entity.relations.is_empty() {
+            out.push('\n');
+        }
+        out.push_str("    permissions {\n");
+        for permission in &entity.permissions {
+            let expr = Dialect::Ipl.expr(&permission.expr, false);
+            let _ = writeln!(out, "        {}: {expr}", permission.name);
+        }
+        out.push_str("    }\n");
+    }
+    out.push_str("}\n");
+}
+
+fn render_spicedb_definition(out: &mut String, name: &str, entity: &Entity) {
+    if entity.relations.is_empty() && entity.permissions.is_empty() {
+        let _ = writeln!(out, "definition {name} {{}}");
+        return;
+    }
+    let _ = writeln!(out, "definition {name} {{");
+    for relation in &entity.relations {
+        let subjects = Dialect::SpiceDb.subjects(&relation.subjects);
+        let _ = writeln!(out, "    relation {}: {subjects}", relation.name);
+    }
+    for permission in &entity.permissions {
+        let expr = Dialect::SpiceDb.expr(&permission.expr, false);
+        let _ = writeln!(out, "    permission {} = {expr}", permission.name);
+    }
+    out.push_str("}\n");
+}
+
+fn render_openfga_type(out: &mut String, name: &str, entity: &Entity) {
+    let _ = writeln!(out, "type {name}");
+    if entity.relations.is_empty() && entity.permissions.is_empty() {
+        return;
+    }
+    out.push_str("  relations\n");
+    for relation in &entity.relations {
+        let subjects = Dialect::OpenFga.subjects(&relation.subjects);
+        let _ = writeln!(out, "    define {}: {subjects}", relation.name);
+    }
+    for permission in &entity.permissions {
+        let expr = Dialect::OpenFga.expr(&permission.expr, false);
+        let _ = writeln!(out, "    define {}: {expr}", permission.name);
+    }
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Symbol(char),
+    /// `SpiceDB`'s `->`.
+    Arrow,
+}
+
+/// Split source into words and symbols with line numbers, dropping `//`,
+/// `/* */`, and `OpenFGA` `#` comments.
+fn tokenize(source: &str) -> Vec<(Token, usize)> {
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut chars = source.chars().peekable();
+    let mut previous = ' ';
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {},
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|&next| next != '\n') {
+                    chars.next();
+                }
+            },
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        line += 1;
+                    }
+                    if last == '*' && next == '/' {
+                        break;
+                    }
+                    last = next;
+                }
+            },
+            '#' if previous.is_whitespace() => {
+                while chars.peek().is_some_and(|&next| next != '\n') {
+                    chars.next();
+                }
+            },
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push((Token::Arrow, line));
+            },
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push((Token::Word(word), line));
+                previous = 'a';
+                continue;
+            },
+            c => tokens.push((Token::Symbol(c), line)),
+        }
+        previous = c;
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// Name of the source language, for errors.
+    language: &'static str,
+    notes: &'a mut Vec<String>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &str, language: &'static str, notes: &'a mut Vec<String>) -> Self {
+        Self { tokens: tokenize(source), pos: 0, language, notes }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn peek_word(&self, word: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w == word)
+    }
+
+    fn peek_symbol(&self, symbol: char) -> bool {
+        self.peek() == Some(&Token::Symbol(symbol))
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let line = self.tokens.get(self.pos).or_else(|| self.tokens.last()).map_or(1, |(_, l)| *l);
+        Error::parse(format!("{} line {line}: {message}", self.language))
+    }
+
+    fn word(&mut self) -> Result<String> {
+        match self.tokens.get(self.pos) {
+            Some((Token::Word(w), _)) => {
+                self.pos += 1;
+                Ok(w.clone())
+            },
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let matched = self.peek_symbol(symbol);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let matched = self.peek_word(word);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<()> {
+        if self.eat(symbol) { Ok(()) } else { Err(self.error(&format!("expected '{symbol}'"))) }
+    }
+
+    /// Skip a definition up to and including its balanced `{ ... }` block.
+    fn skip_definition(&mut self) -> Result<()> {
+        while self.peek().is_some() && !self.peek_symbol('{') {
+            self.pos += 1;
+        }
+        self.expect('{')?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.peek() {
+                Some(Token::Symbol('{')) => depth += 1,
+                Some(Token::Symbol('}')) => depth -= 1,
+                Some(_) => {},
+                None => return Err(self.error("unterminated block")),
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// A subject type: `user`, `user:*`, or `group#member`, optionally
+    /// `with` a caveat.
+    fn subject(&mut self, entity: &str, relation: &str) -> Result<SubjectType> {
+        let type_name = self.word()?;
+        let mut subject =
+            SubjectType { entity: entity_name(&type_name), relation: None, caveat: None };
+        if self.eat('#') {
+            subject.relation = Some(self.word()?);
+        } else if self.eat(':') {
+            self.expect('*')?;
+            self.notes.push(format!(
+                "Wildcard {type_name}:* on {}#{relation} was converted to {}.",
+                ipl::type_name(entity),
+                subject.entity
+            ));
+        }
+        if self.eat_word("with") {
+            let caveat = self.word()?;
+            // SpiceDB relationship expiration is a trait, not a caveat
+            if caveat != "expiration" {
+                subject.caveat = Some(caveat);
+            }
+            if self.eat_word("and") {
+                self.word()?;
+            }
+        }
+        Ok(subject)
+    }
+}
+
+/// Add a subject type unless an identical one is already allowed.
+fn push_subject(subjects: &mut Vec<SubjectType>, subject: SubjectType) {
+    if !subjects.contains(&subject) {
+        subjects.push(subject);
+    }
+}
+
+/// Replace references to `from` with references to `to`.
+fn replace_ref(expr: Expr, from: &str, to: &str) -> Expr {
+    match expr {
+        Expr::Ref(name) if name == from => Expr::Ref(to.to_string()),
+        Expr::Union(operands) => {
+            Expr::Union(operands.into_iter().map(|e| replace_ref(e, from, to)).collect())
+        },
+        Expr::Intersection(operands) => {
+            Expr::Intersection(operands.into_iter().map(|e| replace_ref(e, from, to)).collect())
+        },
+        Expr::Exclusion(base, subtract) => Expr::Exclusion(
+            Box::new(replace_ref(*base, from, to)),
+            Box::new(replace_ref(*subtract, from, to)),
+        ),
+        other => other,
+    }
+}
+
+/// Parse a `SpiceDB` schema.
+fn parse_spicedb(source: &str, notes: &mut Vec<String>) -> Result<Schema> {
+    let mut parser = Parser::new(source, "SpiceDB schema", notes);
+    let mut schema = Schema::default();
+    while parser.peek().is_some() {
+        if parser.eat_word("definition") {
+            schema.entities.push(spicedb_definition(&mut parser)?);
+        } else {
+            if parser.eat_word("caveat") {
+                let name = parser.word()?;
+                parser.notes.push(format!("Caveat '{name}' was not converted; define it in IPL."));
+            }
+            parser.skip_definition()?;
+        }
+    }
+    Ok(schema)
+}
+
+fn spicedb_definition(parser: &mut Parser<'_>) -> Result<Entity> {
+    let type_name = parser.word()?;
+    let mut entity = Entity { name: entity_name(&type_name), ..Entity::default() };
+    parser.expect('{')?;
+    while !parser.eat('}') {
+        if parser.eat_word("relation") {
+            let name = parser.word()?;
+            parser.expect(':')?;
+            let mut subjects = Vec::new();
+            loop {
+                push_subject(&mut subjects, parser.subject(&entity.name, &name)?);
+                if !parser.eat('|') {
+                    break;
+                }
+            }
+            entity.relations.push(Relation { name, subjects });
+        } else if parser.eat_word("permission") {
+            let name = parser.word()?;
+            parser.expect('=')?;
+            let expr = spicedb_union(parser)?;
+            entity.permissions.push(Permission { name, expr });
+        } else {
+            return Err(parser.error("expected 'relation' or 'permission'"));
+        }
+    }
+    Ok(entity)
+}
+
+fn spicedb_union(parser: &mut Parser<'_>) -> Result<Expr> {
+    let mut operands = vec![spicedb_intersection(parser)?];
+    while parser.eat('+') {
+        operands.push(spicedb_intersection(parser)?);
+    }
+    Ok(if operands.len() == 1 { operands.remove(0) } else { Expr::Union(operands) })
+}
+
+fn spicedb_intersection(parser: &mut Parser<'_>) -> Result<Expr> {
+    let mut operands = vec![spicedb_exclusion(parser)?];
+    while parser.eat('&') {
+        operands.push(spicedb_exclusion(parser)?);
+    }
+    Ok(if operands.len() == 1 { operands.remove(0) } else { Expr::Intersection(operands) })
+}
+
+fn spicedb_exclusion(parser: &mut Parser<'_>) -> Result<Expr> {
+    let mut expr = spicedb_primary(parser)?;
+    while parser.eat('-') {
+        expr = Expr::Exclusion(Box::new(expr), Box::new(spicedb_primary(parser)?));
+    }
+    Ok(expr)
+}
+
+fn spicedb_primary(parser: &mut Parser<'_>) -> Result<Expr> {
+    if parser.eat('(') {
+        let expr = spicedb_union(parser)?;
+        parser.expect(')')?;
+        return Ok(expr);
+    }
+    if parser.peek_word("nil") {
+        return Err(parser.error("'nil' has no IPL equivalent"));
+    }
+    let name = parser.word()?;
+    if parser.peek() == Some(&Token::Arrow) {
+        parser.pos += 1;
+        return Ok(Expr::Arrow(name, parser.word()?));
+    }
+    if parser.eat('.') {
+        // Functioned arrows: `parent.any(viewer)` is a plain arrow
+        if parser.word()? != "any" {
+            return Err(parser.error("only '.any()' arrows can be converted"));
+        }
+        parser.expect('(')?;
+        let target = parser.word()?;
+        parser.expect(')')?;
+        return Ok(Expr::Arrow(name, target));
+    }
+    Ok(Expr::Ref(name))
+}
+
+/// Renamed relations, keyed by object type and original relation name.
+type Renames = HashMap<(String, String), String>;
+
+/// Parse an `OpenFGA` model in the DSL.
+///
+/// A `define` that mixes directly assigned types with other relations becomes
+/// a `<name>_direct` relation for the direct types and a `<name>` permission;
+/// the returned renames move tuples on `<name>` to the new relation.
+fn parse_openfga(source: &str, notes: &mut Vec<String>) -> Result<(Schema, Renames)> {
+    let mut parser = Parser::new(source, "OpenFGA model", notes);
+    let mut schema = Schema::default();
+    let mut renames = Renames::new();
+    while parser.peek().is_some() {
+        if parser.eat_word("type") {
+            schema.entities.push(openfga_type(&mut parser, &mut renames)?);
+        } else if parser.eat_word("condition") {
+            let name = parser.word()?;
+            parser.notes.push(format!("Condition '{name}' was not converted; define it in IPL."));
+            parser.skip_definition()?;
+        } else if parser.peek_word("extend") {
+            return Err(parser.error("modular models ('extend type') are not supported"));
+        } else {
+            // `model`, `schema 1.1`, and `module` headers
+            parser.pos += 1;
+        }
+    }
+    Ok((schema, renames))
+}
+
+fn openfga_type(parser: &mut Parser<'_>, renames: &mut Renames) -> Result<Entity> {
+    let type_name = parser.word()?;
+    let mut entity = Entity { name: entity_name(&type_name), ..Entity::default() };
+    if !parser.eat_word("relations") {
+        return Ok(entity);
+    }
+    while parser.eat_word("define") {
+        let name = parser.word()?;
+        parser.expect(':')?;
+        let mut direct = Vec::new();
+        let expr = openfga_expr(parser, &entity.name, &name, &mut direct)?;
+        if expr == Expr::Ref(DIRECT.to_string()) {
+            entity.relations.push(Relation { name, subjects: direct });
+        } else if direct.is_empty() {
+            entity.permissions.push(Permission { name, expr });
+        } else {
+            let relation = format!("{name}_direct");
+            parser.notes.push(format!(
+                "{type_name}#{name} mixes direct types with other relations; its tuples were \
+                 moved to {relation}."
+            ));
+            renames.insert((type_name.clone(), name.clone()), relation.clone());
+            let expr = replace_ref(expr, DIRECT, &relation);
+            entity.relations.push(Relation { name: relation, subjects: direct });
+            entity.permissions.push(Permission { name, expr });
+        }
+    }
+    Ok(entity)
+}
+
+fn openfga_expr(
+    parser: &mut Parser<'_>,
+    entity: &str,
+    relation: &str,
+    direct: &mut Vec<SubjectType>,
+) -> Result<Expr> {
+    let expr = openfga_union(parser, entity, relation, direct)?;
+    if parser.eat_word("but") {
+        if !parser.eat_word("not") {
+            return Err(parser.error("expected 'not' after 'but'"));
+        }
+        let subtract = openfga_primary(parser, entity, relation, direct)?;
+        return Ok(Expr::Exclusion(Box::new(expr), Box::new(subtract)));
+    }
+    Ok(expr)
+}
+
+fn openfga_union(
+    parser: &mut Parser<'_>,
+    entity: &str,
+    relation: &str,
+    direct: &mut Vec<SubjectType>,
+) -> Result<Expr> {
+    let mut operands = vec![openfga_intersection(parser, entity, relation, direct)?];
+    while parser.eat_word("or") {
+        operands.push(openfga_intersection(parser, entity, relation, direct)?);
+    }
+    Ok(if operands.len() == 1 { operands.remove(0) } else { Expr::Union(operands) })
+}
+
+fn openfga_intersection(
+    parser: &mut Parser<'_>,
+    entity: &str,
+    relation: &str,
+    direct: &mut Vec<SubjectType>,
+) -> Result<Expr> {
+    let mut operands = vec![openfga_primary(parser, entity, relation, direct)?];
+    while parser.eat_word("and") {
+        operands.push(openfga_primary(parser, entity, relation, direct)?);
+    }
+    Ok(if operands.len() == 1 { operands.remove(0) } else { Expr::Intersection(operands) })
+}
+
+fn openfga_primary(
+    parser: &mut Parser<'_>,
+    entity: &str,
+    relation: &str,
+    direct: &mut Vec<SubjectType>,
+) -> Result<Expr> {
+    if parser.eat('(') {
+        let expr = openfga_expr(parser, entity, relation, direct)?;
+        parser.expect(')')?;
+        return Ok(expr);
+    }
+    if parser.eat('[') {
+        loop {
+            push_subject(direct, parser.subject(entity, relation)?);
+            if !parser.eat(',') {
+                break;
+            }
+        }
+        parser.expect(']')?;
+        return Ok(Expr::Ref(DIRECT.to_string()));
+    }
+    let name = parser.word()?;
+    if parser.eat_word("from") {
+        return Ok(Expr::Arrow(parser.word()?, name));
+    }
+    Ok(Expr::Ref(name))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const OPENFGA: &str = "
+model
+  schema 1.1
+
+# People
+type user
+
+type folder
+  relations
+    define viewer: [user, user:*]
+
+type document
+  relations
+    define parent: [folder]
+    define owner: [user with office_hours]
+    define viewer: [user, group#member] or owner or viewer from parent
+    define blocked: [user]
+    define can_view: (viewer and owner) but not blocked
+
+condition office_hours(hour: int) {
+  hour >= 9 && hour <= 17
+}
+";
+
+    #[test]
+    fn test_parse_openfga() {
+        let mut notes = Vec::new();
+        let (schema, renames) = parse_openfga(OPENFGA, &mut notes).unwrap();
+        let names: Vec<&str> = schema.entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Folder", "Document"]);
+
+        let document = schema.entity("Document").unwrap();
+        assert_eq!(
+            document.relation("owner").unwrap().subjects[0].caveat.as_deref(),
+            Some("office_hours")
+        );
+        assert_eq!(document.relation("viewer_direct").unwrap().subjects.len(), 2);
+        assert_eq!(
+            document.permission("viewer").unwrap().expr,
+            Expr::Union(vec![
+                Expr::Ref("viewer_direct".to_string()),
+                Expr::Ref("owner".to_string()),
+                Expr::Arrow("parent".to_string(), "viewer".to_string()),
+            ])
+        );
+        assert_eq!(
+            renames.get(&("document".to_string(), "viewer".to_string())).map(String::as_str),
+            Some("viewer_direct")
+        );
+        assert_eq!(notes.len(), 3);
+
+        // The converted schema reads back as IPL
+        let ipl_source = render(&schema, Dialect::Ipl);
+        assert!(ipl_source.contains("can_view: (viewer & owner) - blocked"));
+        assert_eq!(ipl::parse(&ipl_source).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_spicedb_round_trip() {
+        let source = "
+/** a user */
+definition user {}
+
+definition document {
+    relation parent: folder
+    relation viewer: user | group#member | user:* with ip_check
+    relation banned: user
+    permission view = (viewer + parent->view) - banned
+    permission admin = parent.any(admin)
+}
+";
+        let mut notes = Vec::new();
+        let schema = parse_spicedb(source, &mut notes).unwrap();
+        let document = schema.entity("Document").unwrap();
+        assert_eq!(document.relation("viewer").unwrap().subjects.len(), 3);
+        assert_eq!(
+            document.permission("admin").unwrap().expr,
+            Expr::Arrow("parent".to_string(), "admin".to_string())
+        );
+
+        let rendered = render(&schema, Dialect::SpiceDb);
+        assert!(rendered.contains("permission view = (viewer + parent->view) - banned"));
+        assert_eq!(parse_spicedb(&rendered, &mut notes).unwrap(), schema);
+    }
+
+    #[test]
+    fn test_openfga_export() {
+        let schema = parse_spicedb(
+            "definition document { relation viewer: user permission view = viewer - viewer }",
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let rels =
+            [ExportedRelationship::new("document:1".into(), "viewer".into(), "user:anne".into())];
+        let header = export_header(Ecosystem::OpenFga, Some(&schema)).unwrap();
+        let content = header.content + &export_rows(Ecosystem::OpenFga, &rels).unwrap();
+        assert!(content.contains("define view: viewer but not viewer"));
+        assert!(content.contains("object: document:1"));
+
+        let imported = import(Ecosystem::OpenFga, &content).unwrap();
+        assert_eq!(imported.relationships[0].subject, "user:anne");
+        assert!(imported.schema.unwrap().contains("view: viewer - viewer"));
+    }
+
+    #[test]
+    fn test_parse_relationship_lines() {
+        let rels = parse_relationship_lines(
+            "// seed\ndocument:1#viewer@user:anne\n\ndocument:1#viewer@group:eng#member[ip_check]\n",
+        )
+        .unwrap();
+        assert_eq!(rels.len(), 2);
+        assert_eq!(rels[1].subject, "group:eng#member");
+        assert!(parse_relationship_lines("document:1@user:anne").is_err());
+    }
+}
@@ -3,37 +3,61 @@
 //! Each submodule implements a group of related commands.
 
 mod account;
+mod api;
 mod auth;
 mod bulk;
+mod cache;
 mod check;
+mod complete;
+mod deprecations;
 mod dev;
+mod explain;
+mod explore;
+mod generate;
+mod graph;
 mod identity;
+mod interop;
 mod jwks;
+mod offline;
 mod orgs;
 mod profiles;
+mod query;
+mod refactor;
 mod relationships;
+mod release;
+mod retag;
+mod schema_test;
 mod schemas;
+mod script;
 mod shell;
 mod stream;
 mod tokens;
 
 pub use auth::{login, logout};
 pub use check::check;
+pub use complete::{COMPLETE_COMMAND, complete};
 pub use identity::{doctor, health, ping, status, whoami};
 pub use profiles::{
-    profiles_create, profiles_default, profiles_delete, profiles_list, profiles_rename,
-    profiles_show, profiles_update,
+    profiles_create, profiles_default, profiles_delete, profiles_export, profiles_import,
+    profiles_list, profiles_rename, profiles_show, profiles_status, profiles_update,
 };
 pub use relationships::{relationships_add, relationships_delete, relationships_list};
 
-use crate::{cli::Commands, client::Context, error::Result};
+use crate::{
+    cli::{Commands, TimingsArgs},
+    client::Context,
+    error::{Error, Result},
+};
 
 /// Execute a CLI command.
 pub async fn execute(ctx: &Context, command: &Commands) -> Result<()> {
     match command {
         // Auth commands
-        Commands::Login => login(ctx).await,
-        Commands::Logout => logout(ctx).await,
+        Commands::Login { device_code, client_id, client_key } => {
+            let client = client_id.as_deref().zip(client_key.as_deref());
+            login(ctx, *device_code, client).await
+        },
+        Commands::Logout { all_profiles } => logout(ctx, *all_profiles).await,
         Commands::Register { email, name } => {
             auth::register(ctx, email.as_deref(), name.as_deref()).await
         },
@@ -43,21 +67,67 @@ pub async fn execute(ctx: &Context, command: &Commands) -> Result<()> {
         Commands::Status => status(ctx).await,
         Commands::Ping { count, control, engine } => ping(ctx, *count, *control, *engine).await,
         Commands::Doctor => doctor(ctx).await,
-        Commands::Health { watch, verbose } => health(ctx, *watch, *verbose).await,
-        Commands::Version => identity::version(ctx).await,
+        Commands::Health { watch, verbose, metrics_listen } => {
+            health(ctx, *watch, *verbose, metrics_listen.as_deref()).await
+        },
+        Commands::Version { verbose, sbom } => identity::version(ctx, *verbose, *sbom).await,
 
         // Authorization commands
-        Commands::Check { subject, permission, resource, trace, explain, context } => {
-            check()
+        Commands::Check {
+            command: Some(crate::cli::CheckCommands::Batch { file, concurrency, report }),
+            ..
+        } => {
+            let reports = parse_reports(report)?;
+            check::batch(ctx, file, *concurrency as usize, &reports).await
+        },
+        Commands::Check {
+            subject: Some(subject),
+            permission: Some(permission),
+            resource: Some(resource),
+            exit_code,
+            snapshot: Some(snapshot),
+            ..
+        } => {
+            let result = offline::check(ctx, snapshot, subject, permission, resource).await;
+            if *exit_code { result.map_err(Error::check_exit_code) } else { result }
+        },
+        Commands::Check {
+            subject: Some(subject),
+            permission: Some(permission),
+            resource: Some(resource),
+            trace,
+            explain,
+            explain_denied,
+            impersonate,
+            context,
+            context_file,
+            exit_code,
+            watch,
+            ..
+        } => {
+            let context = match context_file {
+                Some(path) => Some(crate::input::read_to_string(path).map_err(|e| {
+                    Error::invalid_arg(format!("Could not read context file {path}: {e}"))
+                })?),
+                None => context.clone(),
+            };
+            let result = check()
                 .ctx(ctx)
                 .subject(subject)
                 .permission(permission)
                 .resource(resource)
                 .trace(*trace)
                 .explain(*explain)
+                .explain_denied(*explain_denied)
+                .impersonate(*impersonate)
+                .watch(*watch)
                 .maybe_context_json(context.as_deref())
                 .call()
-                .await
+                .await;
+            if *exit_code { result.map_err(Error::check_exit_code) } else { result }
+        },
+        Commands::Check { .. } => {
+            Err(Error::invalid_arg("Specify a subject, permission, and resource to check"))
         },
 
         Commands::Simulate {
@@ -66,6 +136,7 @@ pub async fn execute(ctx: &Context, command: &Commands) -> Result<()> {
             resource,
             add_relationships,
             remove_relationships,
+            suggest,
         } => {
             check::simulate()
                 .ctx(ctx)
@@ -74,6 +145,7 @@ pub async fn execute(ctx: &Context, command: &Commands) -> Result<()> {
                 .resource(resource)
                 .add_relationships(add_relationships)
                 .remove_relationships(remove_relationships)
+                .suggest(*suggest)
                 .call()
                 .await
         },
@@ -96,9 +168,11 @@ pub async fn execute(ctx: &Context, command: &Commands) -> Result<()> {
 
         // Profile commands
         Commands::Profiles(sub) => profiles_dispatch(ctx, sub.as_ref()).await,
+        Commands::Switch { query } => profiles::profiles_switch(ctx, query.as_deref()).await,
 
         // Config commands
         Commands::Config(sub) => config_dispatch(ctx, sub.as_ref()).await,
+        Commands::Cache(sub) => cache_dispatch(ctx, sub.as_ref()).await,
 
         // Account commands
         Commands::Account(sub) => account_dispatch(ctx, sub.as_ref()).await,
@@ -119,21 +193,92 @@ pub async fn execute(ctx: &Context, command: &Commands) -> Result<()> {
         Commands::Tokens(sub) => tokens_dispatch(ctx, sub.as_ref()).await,
 
         // Bulk operations
-        Commands::Export { output, resource_type, format } => {
-            bulk::export(ctx, output.as_deref(), resource_type.as_deref(), format).await
+        Commands::Export { output, resource_type, format, with_metadata, parallel, timings } => {
+            with_timings(
+                ctx,
+                timings,
+                bulk::export()
+                    .ctx(ctx)
+                    .maybe_output(output.as_deref())
+                    .maybe_resource_type(resource_type.as_deref())
+                    .format(format)
+                    .with_metadata(*with_metadata)
+                    .parallel(*parallel as usize)
+                    .call(),
+            )
+            .await
+        },
+
+        Commands::Query { statement, explain } => query::query(ctx, statement, *explain).await,
+
+        Commands::Apply { file, prune, dry_run, summary, yes } => {
+            bulk::apply()
+                .ctx(ctx)
+                .file(file)
+                .prune(*prune)
+                .dry_run(*dry_run)
+                .summary(*summary)
+                .yes(*yes)
+                .call()
+                .await
         },
 
-        Commands::Import { file, yes, dry_run, mode } => {
-            bulk::import(ctx, file, *yes, *dry_run, mode).await
+        Commands::Import {
+            file,
+            format,
+            yes,
+            dry_run,
+            summary,
+            mode,
+            id_map,
+            transform,
+            transform_wasm,
+            timings,
+        } => {
+            let transform = match (transform, transform_wasm) {
+                (Some(expr), _) => Some(bulk::Transform::Jq(expr.clone())),
+                (None, Some(module)) => Some(bulk::Transform::Wasm(module.clone())),
+                (None, None) => None,
+            };
+            // Boxed: the import future is large enough to bloat every command's future
+            let import = Box::pin(
+                bulk::import()
+                    .ctx(ctx)
+                    .file(file)
+                    .maybe_format(format.as_deref())
+                    .yes(*yes)
+                    .dry_run(*dry_run)
+                    .summary(*summary)
+                    .mode(mode)
+                    .maybe_id_map(id_map.as_deref())
+                    .maybe_transform(transform.as_ref())
+                    .call(),
+            );
+            with_notify(ctx, &format!("import {file}"), with_timings(ctx, timings, import)).await
         },
 
         // Stream
-        Commands::Stream { resource_type, relation } => {
-            stream::stream(ctx, resource_type.as_deref(), relation.as_deref()).await
+        Commands::Stream { assert: Some(expected), deleted, .. } => {
+            stream::assert_change(ctx, expected, *deleted).await
+        },
+        Commands::Stream { resource_type, relation, metrics_listen, .. } => {
+            stream::stream(
+                ctx,
+                resource_type.as_deref(),
+                relation.as_deref(),
+                metrics_listen.as_deref(),
+            )
+            .await
         },
 
+        Commands::Explore { resource } => explore::explore(ctx, resource.as_deref()).await,
+
+        Commands::Run { file, vars, dry_run } => script::run(ctx, file, vars, *dry_run).await,
+
         // Stats
-        Commands::Stats { trends, compact } => identity::stats(ctx, *trends, *compact).await,
+        Commands::Stats { trends, compact, alert_on_change } => {
+            identity::stats(ctx, *trends, *compact, alert_on_change.as_deref()).await
+        },
 
         // What Changed
         Commands::WhatChanged { since, until, focus, actor, resource, compact } => {
@@ -150,7 +295,11 @@ pub async fn execute(ctx: &Context, command: &Commands) -> Result<()> {
         },
 
         // Interactive
-        Commands::Shell => shell::shell(ctx).await,
+        Commands::Api { path, method, fields, headers, input, paginate } => {
+            api::api(ctx, path, method.as_deref(), fields, headers, input.as_deref(), *paginate)
+                .await
+        },
+        Commands::Shell { record, redact } => shell::shell(ctx, record.as_deref(), redact).await,
 
         // Utilities
         Commands::Cheatsheet { role } => cheatsheet(ctx, role.as_deref()).await,
@@ -158,7 +307,11 @@ pub async fn execute(ctx: &Context, command: &Commands) -> Result<()> {
             identity::templates(ctx, name.as_deref(), subject.as_deref(), format).await
         },
         Commands::Guide { name } => identity::guide(ctx, name.as_deref()).await,
+        Commands::Deprecations(sub) => deprecations_dispatch(ctx, sub.as_ref()).await,
+        Commands::Explain { command } => explain::explain(ctx, command).await,
         Commands::Dev(sub) => dev_dispatch(ctx, sub.as_ref()).await,
+        Commands::Generate(sub) => generate_dispatch(ctx, sub.as_ref()).await,
+        Commands::Release(sub) => release_dispatch(ctx, sub.as_ref()).await,
         Commands::Completion { shell } => completion(ctx, shell).await,
     }
 }
@@ -172,17 +325,64 @@ async fn profiles_dispatch(ctx: &Context, sub: &crate::cli::ProfilesCommands) ->
     match sub {
         ProfilesCommands::List => profiles_list(ctx).await,
         ProfilesCommands::Show { name } => profiles_show(ctx, name.as_deref()).await,
-        ProfilesCommands::Create { name, url, org, vault } => {
-            profiles_create(ctx, name, url.as_deref(), org.as_deref(), vault.as_deref()).await
+        ProfilesCommands::Create {
+            name,
+            url,
+            org,
+            vault,
+            principal,
+            require_impersonate,
+            require_signed_schemas,
+            timezone,
+        } => {
+            profiles_create()
+                .ctx(ctx)
+                .name(name)
+                .maybe_url(url.as_deref())
+                .maybe_org(org.as_deref())
+                .maybe_vault(vault.as_deref())
+                .maybe_principal(principal.as_deref())
+                .maybe_require_impersonate(*require_impersonate)
+                .maybe_require_signed_schemas(*require_signed_schemas)
+                .maybe_timezone(timezone.as_deref())
+                .call()
+                .await
         },
-        ProfilesCommands::Update { name, url, org, vault } => {
-            profiles_update(ctx, name, url.as_deref(), org.as_deref(), vault.as_deref()).await
+        ProfilesCommands::Update {
+            name,
+            url,
+            org,
+            vault,
+            principal,
+            require_impersonate,
+            require_signed_schemas,
+            timezone,
+        } => {
+            profiles_update()
+                .ctx(ctx)
+                .name(name)
+                .maybe_url(url.as_deref())
+                .maybe_org(org.as_deref())
+                .maybe_vault(vault.as_deref())
+                .maybe_principal(principal.as_deref())
+                .maybe_require_impersonate(*require_impersonate)
+                .maybe_require_signed_schemas(*require_signed_schemas)
+                .maybe_timezone(timezone.as_deref())
+                .call()
+                .await
         },
         ProfilesCommands::Rename { old_name, new_name } => {
             profiles_rename(ctx, old_name, new_name).await
         },
         ProfilesCommands::Delete { name } => profiles_delete(ctx, name).await,
         ProfilesCommands::Default { name } => profiles_default(ctx, name.as_deref()).await,
+        ProfilesCommands::Status => profiles_status(ctx).await,
+        ProfilesCommands::Export { names, file, no_output } => {
+            profiles_export(ctx, names, file.as_deref(), *no_output).await
+        },
+        ProfilesCommands::Import { file, on_conflict } => {
+            profiles_import(ctx, file, *on_conflict).await
+        },
     }
 }
 
@@ -193,6 +393,26 @@ async fn config_dispatch(ctx: &Context, sub: &crate::cli::ConfigCommands) -> Res
         ConfigCommands::Edit { editor } => identity::config_edit(ctx, editor.as_deref()).await,
         ConfigCommands::Path { dir } => identity::config_path(ctx, *dir).await,
         ConfigCommands::Explain => identity::config_explain(ctx).await,
+        ConfigCommands::Trust { path } => identity::config_trust(ctx, path.as_deref()).await,
+        ConfigCommands::Untrust { path } => identity::config_untrust(ctx, path.as_deref()).await,
+    }
+}
+
+async fn cache_dispatch(ctx: &Context, sub: &crate::cli::CacheCommands) -> Result<()> {
+    use crate::cli::CacheCommands;
+    match sub {
+        CacheCommands::Status => cache::status(ctx).await,
+        CacheCommands::Clear { all } => cache::clear(ctx, *all).await,
+    }
+}
+
+async fn deprecations_dispatch(
+    ctx: &Context,
+    sub: &crate::cli::DeprecationsCommands,
+) -> Result<()> {
+    use crate::cli::DeprecationsCommands;
+    match sub {
+        DeprecationsCommands::List { used, args } => deprecations::list(ctx, *used, args).await,
     }
 }
 
@@ -239,26 +459,94 @@ async fn relationships_dispatch(
 ) -> Result<()> {
     use crate::cli::RelationshipsCommands;
     match sub {
-        RelationshipsCommands::List { resource, subject, relation, limit, cursor } => {
+        RelationshipsCommands::List {
+            resource,
+            subject,
+            relation,
+            subject_relation,
+            resource_prefix,
+            limit,
+            all,
+            cursor,
+            from_cursor,
+            save_cursor,
+            wide,
+            created_by,
+            created_after,
+            created_before,
+        } => {
             relationships_list()
                 .ctx(ctx)
                 .maybe_resource(resource.as_deref())
                 .maybe_subject(subject.as_deref())
                 .maybe_relation(relation.as_deref())
+                .maybe_subject_relation(subject_relation.as_deref())
+                .maybe_resource_prefix(resource_prefix.as_deref())
                 .limit(*limit)
+                .all(*all)
                 .maybe_cursor(cursor.as_deref())
+                .maybe_from_cursor(from_cursor.as_deref())
+                .maybe_save_cursor(save_cursor.as_deref())
+                .wide(*wide)
+                .maybe_created_by(created_by.as_deref())
+                .maybe_created_after(created_after.as_deref())
+                .maybe_created_before(created_before.as_deref())
                 .call()
                 .await
         },
         RelationshipsCommands::Add { subject, relation, resource, if_not_exists } => {
             relationships_add(ctx, subject, relation, resource, *if_not_exists).await
         },
+        RelationshipsCommands::BulkAdd { file, batch_size } => {
+            let retries = crate::client::retry::policy().retries;
+            let bulk_add = bulk::bulk_add(ctx, file.as_deref(), *batch_size as usize, retries);
+            with_notify(ctx, "relationships bulk-add", bulk_add).await
+        },
         RelationshipsCommands::Delete { subject, relation, resource, if_exists } => {
             relationships_delete(ctx, subject, relation, resource, *if_exists).await
         },
+        RelationshipsCommands::Watch { object, relation, notify } => {
+            stream::watch_object(ctx, object, relation.as_deref(), *notify).await
+        },
         RelationshipsCommands::History { resource, from, to } => {
             relationships::history(ctx, resource.as_deref(), from.as_deref(), to.as_deref()).await
         },
+        RelationshipsCommands::Retag {
+            resource_prefix,
+            to,
+            include_subjects,
+            batch_size,
+            dry_run,
+            undo,
+        } => {
+            let batch_size = *batch_size as usize;
+            match (undo, resource_prefix, to) {
+                (Some(journal), ..) => retag::undo(ctx, journal, batch_size).await,
+                (None, Some(resource_prefix), Some(to)) => {
+                    retag::retag()
+                        .ctx(ctx)
+                        .resource_prefix(resource_prefix)
+                        .to(to)
+                        .include_subjects(*include_subjects)
+                        .batch_size(batch_size)
+                        .dry_run(*dry_run)
+                        .call()
+                        .await
+                },
+                _ => Err(Error::invalid_arg("--resource-prefix and --to are required")),
+            }
+        },
+        RelationshipsCommands::Graph { resource, depth, format, max_edges, open } => {
+            graph::graph()
+                .ctx(ctx)
+                .resource(resource)
+                .depth(*depth)
+                .format(graph::GraphFormat::parse(format)?)
+                .max_edges(*max_edges)
+                .open(*open)
+                .call()
+                .await
+        },
     }
 }
 
@@ -266,23 +554,80 @@ async fn schemas_dispatch(ctx: &Context, sub: &crate::cli::SchemasCommands) -> R
     use crate::cli::SchemasCommands;
     match sub {
         SchemasCommands::Init { path, template } => schemas::init(ctx, path, template).await,
-        SchemasCommands::List { all } => schemas::list(ctx, *all).await,
-        SchemasCommands::Get { id } => schemas::get(ctx, id).await,
+        SchemasCommands::List { all, labels } => schemas::list(ctx, *all, labels).await,
+        SchemasCommands::Get { id, bundle, tests } => {
+            schemas::get(ctx, id, bundle.as_deref(), tests.as_deref()).await
+        },
+        SchemasCommands::Publish { reference, id, tests, sign, key } => {
+            schemas::publish(ctx, reference, id, tests.as_deref(), *sign, key.as_deref()).await
+        },
+        SchemasCommands::VerifySignature {
+            reference,
+            key,
+            certificate_identity,
+            certificate_oidc_issuer,
+        } => {
+            let verifier = schemas::SignatureVerifier::from_args(
+                key.as_deref(),
+                certificate_identity.as_deref(),
+                certificate_oidc_issuer.as_deref(),
+            )?;
+            schemas::verify_signature(ctx, reference, verifier).await
+        },
+        SchemasCommands::Pull { reference, output } => {
+            schemas::pull(ctx, reference, output.as_deref()).await
+        },
+        SchemasCommands::Annotate { id, labels, remove_labels, note } => {
+            schemas::annotate(ctx, id, labels, remove_labels, note.as_deref()).await
+        },
         SchemasCommands::Preview { file, base, impact } => {
             schemas::preview(ctx, file, base.as_deref(), *impact).await
         },
         SchemasCommands::Push { file, activate, message, dry_run } => {
-            schemas::push(ctx, file, *activate, message.as_deref(), *dry_run).await
+            let push = schemas::push(ctx, file, *activate, message.as_deref(), *dry_run);
+            with_notify(ctx, &format!("schemas push {file}"), push).await
         },
-        SchemasCommands::Activate { id, diff, canary } => {
-            schemas::activate_with_options(ctx, id, *diff, *canary).await
+        SchemasCommands::Activate {
+            id,
+            diff,
+            canary,
+            verify_signature,
+            key,
+            certificate_identity,
+            certificate_oidc_issuer,
+        } => {
+            let signature = verify_signature
+                .as_deref()
+                .map(|reference| {
+                    schemas::SignatureVerifier::from_args(
+                        key.as_deref(),
+                        certificate_identity.as_deref(),
+                        certificate_oidc_issuer.as_deref(),
+                    )
+                    .map(|verifier| (reference, verifier))
+                })
+                .transpose()?;
+            schemas::activate_with_options(ctx, id, *diff, *canary, signature).await
         },
+        SchemasCommands::Edit { id, activate } => schemas::edit(ctx, id, *activate).await,
         SchemasCommands::Rollback { version } => schemas::rollback(ctx, version.as_deref()).await,
         SchemasCommands::Validate { file, strict: _ } => schemas::validate(ctx, file).await,
         SchemasCommands::Format { file, write } => schemas::format(ctx, file, *write).await,
-        SchemasCommands::Diff { from, to, impact: _ } => schemas::diff(ctx, from, to).await,
-        SchemasCommands::Test { tests, schema, name } => {
-            schemas::test(ctx, tests.as_deref(), schema.as_deref(), name.as_deref()).await
+        SchemasCommands::Diff { from, to, impact: _, style, context } => {
+            let style = crate::output::diff::DiffStyle::parse(style)?;
+            schemas::diff(ctx, from, to, style, *context).await
+        },
+        SchemasCommands::Test { tests, schema, name, min_coverage, report } => {
+            let reports = parse_reports(report)?;
+            let test = schemas::test()
+                .ctx(ctx)
+                .maybe_tests_file(tests.as_deref())
+                .maybe_schema_file(schema.as_deref())
+                .maybe_name_filter(name.as_deref())
+                .maybe_min_coverage(*min_coverage)
+                .reports(&reports)
+                .call();
+            with_notify(ctx, "schemas test", test).await
         },
         SchemasCommands::Watch { file, test, auto_push } => {
             schemas::watch(ctx, file, *test, *auto_push).await
@@ -293,6 +638,9 @@ async fn schemas_dispatch(ctx: &Context, sub: &crate::cli::SchemasCommands) -> R
         SchemasCommands::Analyze { file, checks, compare } => {
             schemas::analyze(ctx, file, checks.as_deref(), compare.as_deref()).await
         },
+        SchemasCommands::Matrix { file, entity, format } => {
+            schemas::matrix(ctx, file, entity, format).await
+        },
         SchemasCommands::Visualize { file, format, entity, show_permissions } => {
             schemas::visualize(ctx, file, format, entity.as_deref(), *show_permissions).await
         },
@@ -318,11 +666,41 @@ async fn schemas_dispatch(ctx: &Context, sub: &crate::cli::SchemasCommands) -> R
                 .await
         },
         SchemasCommands::Migrate { from, to, format } => {
-            schemas::migrate(ctx, from.as_deref(), to, format).await
+            with_notify(ctx, "schemas migrate", schemas::migrate(ctx, from.as_deref(), to, format))
+                .await
         },
+        SchemasCommands::Refactor(refactor_cmd) => refactor_dispatch(ctx, refactor_cmd).await,
     }
 }
 
+async fn refactor_dispatch(ctx: &Context, sub: &crate::cli::RefactorCommands) -> Result<()> {
+    use crate::{cli::RefactorCommands, ipl::Refactoring};
+
+    let (refactoring, file, apply, batch_size) = match sub {
+        RefactorCommands::RenameRelation { entity, from, to, file, apply, batch_size } => (
+            Refactoring::RenameRelation {
+                entity: entity.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            },
+            file,
+            *apply,
+            *batch_size,
+        ),
+        RefactorCommands::RenameEntity { from, to, file, apply, batch_size } => (
+            Refactoring::RenameEntity { from: from.clone(), to: to.clone() },
+            file,
+            *apply,
+            *batch_size,
+        ),
+        RefactorCommands::Resume { checkpoint } => {
+            return with_notify(ctx, "schemas refactor", refactor::resume(ctx, checkpoint)).await;
+        },
+    };
+    let run = refactor::refactor(ctx, refactoring, file.as_deref(), apply, batch_size as usize);
+    if apply { with_notify(ctx, "schemas refactor", run).await } else { run.await }
+}
+
 async fn orgs_dispatch(ctx: &Context, sub: &crate::cli::OrgsCommands) -> Result<()> {
     use crate::cli::{
         CertificatesCommands, ClientsCommands, InvitationsCommands, MembersCommands,
@@ -340,11 +718,15 @@ async fn orgs_dispatch(ctx: &Context, sub: &crate::cli::OrgsCommands) -> Result<
         OrgsCommands::Delete { id } => orgs::delete(ctx, id).await,
         OrgsCommands::Suspend { id } => orgs::suspend(ctx, id).await,
         OrgsCommands::Resume { id } => orgs::resume(ctx, id).await,
-        OrgsCommands::Leave { id, yes } => orgs::leave(ctx, id, *yes).await,
+        OrgsCommands::Leave { id, transfer_to, yes } => {
+            orgs::leave(ctx, id, *yes, transfer_to.as_deref()).await
+        },
 
         // Members
         OrgsCommands::Members(mem_cmd) => match mem_cmd.as_ref() {
-            MembersCommands::List => orgs::members_list(ctx).await,
+            MembersCommands::List { search, role, sort } => {
+                orgs::members_list(ctx, search.as_deref(), role.as_deref(), sort.as_deref()).await
+            },
             MembersCommands::UpdateRole { member_id, role } => {
                 orgs::members_update_role(ctx, member_id, role).await
             },
@@ -359,6 +741,9 @@ async fn orgs_dispatch(ctx: &Context, sub: &crate::cli::OrgsCommands) -> Result<
             },
             InvitationsCommands::Delete { id } => orgs::invitations_delete(ctx, id).await,
             InvitationsCommands::Resend { id } => orgs::invitations_resend(ctx, id).await,
+            InvitationsCommands::Prune { older_than, dry_run } => {
+                orgs::invitations_prune(ctx, older_than.as_deref(), *dry_run).await
+            },
             InvitationsCommands::Accept { token } => orgs::invitations_accept(ctx, token).await,
         },
 
@@ -376,7 +761,9 @@ async fn orgs_dispatch(ctx: &Context, sub: &crate::cli::OrgsCommands) -> Result<
 
         // Vaults
         OrgsCommands::Vaults(vault_cmd) => match vault_cmd.as_ref() {
-            VaultsCommands::List => orgs::vaults_list(ctx).await,
+            VaultsCommands::List { archived, show_deleted } => {
+                orgs::vaults_list(ctx, *archived, *show_deleted).await
+            },
             VaultsCommands::Create { name, description } => {
                 orgs::vaults_create(ctx, name, description.as_deref()).await
             },
@@ -490,13 +877,14 @@ async fn orgs_dispatch(ctx: &Context, sub: &crate::cli::OrgsCommands) -> Result<
         },
 
         // Audit logs
-        OrgsCommands::AuditLogs { actor, action, from, to } => {
+        OrgsCommands::AuditLogs { actor, action, from, to, parquet } => {
             orgs::audit_logs()
                 .ctx(ctx)
                 .maybe_actor(actor.as_deref())
                 .maybe_action(action.as_deref())
                 .maybe_from(from.as_deref())
                 .maybe_to(to.as_deref())
+                .maybe_parquet(parquet.as_deref())
                 .call()
                 .await
         },
@@ -512,6 +900,25 @@ async fn jwks_dispatch(ctx: &Context, sub: &crate::cli::JwksCommands) -> Result<
     }
 }
 
+async fn generate_dispatch(ctx: &Context, sub: &crate::cli::GenerateCommands) -> Result<()> {
+    use crate::cli::GenerateCommands;
+    match sub {
+        GenerateCommands::Grafana { output } => generate::grafana(ctx, output).await,
+        GenerateCommands::Ci { provider, schema, tests, branch, output, force } => {
+            generate::ci(ctx, provider, schema, tests, branch, output.as_deref(), *force).await
+        },
+    }
+}
+
+async fn release_dispatch(ctx: &Context, sub: &crate::cli::ReleaseCommands) -> Result<()> {
+    use crate::cli::ReleaseCommands;
+    match sub {
+        ReleaseCommands::PackageManifests { output, version, checksums } => {
+            release::package_manifests(ctx, output, version.as_deref(), checksums.as_deref()).await
+        },
+    }
+}
+
 async fn tokens_dispatch(ctx: &Context, sub: &crate::cli::TokensCommands) -> Result<()> {
     use crate::cli::TokensCommands;
     match sub {
@@ -538,8 +945,13 @@ async fn dev_dispatch(ctx: &Context, sub: &crate::cli::DevCommands) -> Result<()
             tailscale_secret,
             force,
             commit,
+            from_registry,
+            tag,
+            bundle,
+            timings,
+            expose_api,
         } => {
-            dev::start()
+            let start = dev::start()
                 .ctx(ctx)
                 .skip_build(*skip_build)
                 .interactive(*interactive)
@@ -547,26 +959,139 @@ async fn dev_dispatch(ctx: &Context, sub: &crate::cli::DevCommands) -> Result<()
                 .maybe_tailscale_secret(tailscale_secret.clone())
                 .force(*force)
                 .maybe_commit(commit.as_deref())
-                .call()
-                .await
+                .from_registry(*from_registry)
+                .maybe_tag(tag.as_deref())
+                .maybe_bundle(bundle.as_deref())
+                .maybe_expose_api(expose_api.as_deref())
+                .call();
+            with_timings(ctx, timings, start).await
         },
-        DevCommands::Stop { destroy, yes, with_credentials, interactive } => {
-            dev::stop()
+        DevCommands::Stop { destroy, yes, with_credentials, interactive, timings } => {
+            let stop = dev::stop()
                 .ctx(ctx)
                 .destroy(*destroy)
                 .yes(*yes)
                 .with_credentials(*with_credentials)
                 .interactive(*interactive)
-                .call()
-                .await
+                .call();
+            with_timings(ctx, timings, stop).await
         },
         DevCommands::Status { interactive } => dev::dev_status(ctx, *interactive).await,
         DevCommands::Logs { follow, service, tail } => {
             dev::logs(ctx, *follow, service.as_deref(), *tail).await
         },
         DevCommands::Dashboard => dev::dashboard(ctx).await,
-        DevCommands::Reset { yes } => dev::reset(ctx, *yes).await,
+        DevCommands::Mail(sub) => dev_mail_dispatch(ctx, sub.as_ref()).await,
+        DevCommands::Token { subject, ttl } => dev::token::token(ctx, subject, ttl).await,
+        DevCommands::Upgrade { kubernetes_version, talos_version, skip_snapshot, yes, timings } => {
+            let upgrade = dev::upgrade()
+                .ctx(ctx)
+                .maybe_kubernetes_version(kubernetes_version.as_deref())
+                .maybe_talos_version(talos_version.as_deref())
+                .skip_snapshot(*skip_snapshot)
+                .yes(*yes)
+                .call();
+            with_timings(ctx, timings, upgrade).await
+        },
+        DevCommands::Bundle(sub) => dev_bundle_dispatch(ctx, sub.as_ref()).await,
+        DevCommands::Config(sub) => dev_config_dispatch(ctx, sub.as_ref()).await,
+        DevCommands::Reset { yes, timings } => {
+            with_timings(ctx, timings, dev::reset(ctx, *yes)).await
+        },
+        DevCommands::PortForward { address } => dev::port_forward::supervise(address),
+    }
+}
+
+async fn dev_bundle_dispatch(ctx: &Context, sub: &crate::cli::DevBundleCommands) -> Result<()> {
+    use crate::cli::DevBundleCommands;
+    match sub {
+        DevBundleCommands::Create { output, tag, commit } => {
+            dev::bundle::create(ctx, output, tag.as_deref(), commit.as_deref()).await
+        },
+    }
+}
+
+async fn dev_mail_dispatch(ctx: &Context, sub: &crate::cli::DevMailCommands) -> Result<()> {
+    use crate::cli::DevMailCommands;
+    match sub {
+        DevMailCommands::Open { id } => dev::mail::open(ctx, id.as_deref()).await,
+        DevMailCommands::List { limit } => dev::mail::list(ctx, *limit).await,
+        DevMailCommands::Clear => dev::mail::clear(ctx).await,
+    }
+}
+
+async fn dev_config_dispatch(ctx: &Context, sub: &crate::cli::DevConfigCommands) -> Result<()> {
+    use crate::cli::DevConfigCommands;
+    match sub {
+        DevConfigCommands::Set { assignments } => dev::config::set(ctx, assignments).await,
+        DevConfigCommands::Unset { keys } => dev::config::unset(ctx, keys).await,
+        DevConfigCommands::List => dev::config::list(ctx).await,
+    }
+}
+
+/// Run a multi-step command, collecting step timings if `--timings` was given.
+///
+/// The summary is printed even when the command fails, so the failing step
+/// and everything before it remain visible.
+async fn with_timings(
+    ctx: &Context,
+    args: &TimingsArgs,
+    command: impl std::future::Future<Output = Result<()>>,
+) -> Result<()> {
+    use crate::output::{TimingsFormat, timings};
+
+    let Some(format) = args.timings.as_deref().map(TimingsFormat::parse).transpose()? else {
+        return command.await;
+    };
+
+    timings::begin();
+    let result = command.await;
+    if let Some(collected) = timings::finish() {
+        collected.print(format, ctx.output.color)?;
+    }
+    result
+}
+
+/// Parse `--report` targets.
+fn parse_reports(reports: &[String]) -> Result<Vec<crate::output::ReportTarget>> {
+    reports.iter().map(|r| crate::output::ReportTarget::parse(r)).collect()
+}
+
+/// Run a command, posting a summary to the notification webhook (if any)
+/// when it finishes or fails.
+///
+/// A webhook that cannot be reached only produces a warning; the command's
+/// own result is returned unchanged.
+async fn with_notify(
+    ctx: &Context,
+    operation: &str,
+    command: impl std::future::Future<Output = Result<()>>,
+) -> Result<()> {
+    use crate::output::webhook::{self, Summary};
+
+    let Some(url) = ctx.notify_webhook.as_deref() else {
+        return command.await;
+    };
+
+    let started = std::time::Instant::now();
+    let result = command.await;
+
+    let mut facts = vec![("Profile".to_string(), ctx.effective_profile_name().to_string())];
+    if let (Some(org), Some(vault)) = (&ctx.profile.org, &ctx.profile.vault) {
+        facts.push(("Vault".to_string(), format!("{org}/{vault}")));
+    }
+    facts.push(("Duration".to_string(), format!("{:.1}s", started.elapsed().as_secs_f64())));
+
+    let summary = Summary {
+        operation: operation.to_string(),
+        succeeded: result.is_ok(),
+        facts,
+        error: result.as_ref().err().map(ToString::to_string),
+    };
+    if let Err(e) = webhook::post(url, &summary).await {
+        ctx.output.warn(&format!("Could not send notification: {e}"));
     }
+    result
 }
 
 // ============================================================================
@@ -609,23 +1134,43 @@ async fn cheatsheet(_ctx: &Context, _role: Option<&str>) -> Result<()> {
     Ok(())
 }
 
-fn print_completions<G: clap_complete::Generator>(generator: G, cmd: &mut clap::Command) {
-    clap_complete::generate(generator, cmd, cmd.get_name().to_string(), &mut std::io::stdout());
+fn write_completions<G: clap_complete::Generator>(
+    generator: G,
+    cmd: &mut clap::Command,
+    buf: &mut dyn std::io::Write,
+) {
+    clap_complete::generate(generator, cmd, cmd.get_name().to_string(), buf);
 }
 
-async fn completion(_ctx: &Context, shell: &crate::cli::Shell) -> Result<()> {
+/// Render the completion script for `shell`, with the callbacks for dynamic
+/// values.
+pub(crate) fn completion_script(shell: crate::cli::Shell) -> Vec<u8> {
     use clap::CommandFactory;
 
     let mut cmd = crate::cli::Cli::command();
+    let mut script = Vec::new();
 
     match shell {
-        crate::cli::Shell::Bash => print_completions(clap_complete::shells::Bash, &mut cmd),
-        crate::cli::Shell::Zsh => print_completions(clap_complete::shells::Zsh, &mut cmd),
-        crate::cli::Shell::Fish => print_completions(clap_complete::shells::Fish, &mut cmd),
+        crate::cli::Shell::Bash => {
+            write_completions(clap_complete::shells::Bash, &mut cmd, &mut script);
+        },
+        crate::cli::Shell::Zsh => {
+            write_completions(clap_complete::shells::Zsh, &mut cmd, &mut script);
+        },
+        crate::cli::Shell::Fish => {
+            write_completions(clap_complete::shells::Fish, &mut cmd, &mut script);
+        },
         crate::cli::Shell::PowerShell => {
-            print_completions(clap_complete::shells::PowerShell, &mut cmd);
+            write_completions(clap_complete::shells::PowerShell, &mut cmd, &mut script);
         },
     }
 
+    complete::with_dynamic(shell, script)
+}
+
+async fn completion(_ctx: &Context, shell: &crate::cli::Shell) -> Result<()> {
+    use std::io::Write;
+
+    std::io::stdout().write_all(&completion_script(*shell))?;
     Ok(())
 }
@@ -1,9 +1,20 @@
 //! Organization management commands.
 
+use std::collections::HashMap;
+
 use bon::builder;
+use chrono::{DateTime, Utc};
+use inferadb::control::{InvitationInfo, InvitationStatus, MemberInfo, OrgRole, SortOrder};
 use serde::Serialize;
 
-use crate::{client::Context, error::Result, output::Displayable};
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    output::{
+        Displayable, dates,
+        parquet::{self, Column},
+    },
+};
 
 // ============================================================================
 // Display types
@@ -14,12 +25,17 @@ struct OrgRow {
     id: String,
     name: String,
     display_name: String,
-    created_at: String,
+    created_at: DateTime<Utc>,
 }
 
 impl Displayable for OrgRow {
     fn table_row(&self) -> Vec<String> {
-        vec![self.id.clone(), self.name.clone(), self.display_name.clone(), self.created_at.clone()]
+        vec![
+            self.id.clone(),
+            self.name.clone(),
+            self.display_name.clone(),
+            dates::format(self.created_at),
+        ]
     }
 
     fn table_headers() -> Vec<&'static str> {
@@ -34,6 +50,8 @@ struct MemberRow {
     email: String,
     role: String,
     status: String,
+    joined: DateTime<Utc>,
+    last_active: Option<DateTime<Utc>>,
 }
 
 impl Displayable for MemberRow {
@@ -44,11 +62,13 @@ impl Displayable for MemberRow {
             self.email.clone(),
             self.role.clone(),
             self.status.clone(),
+            dates::format_date(self.joined),
+            dates::format_opt(self.last_active),
         ]
     }
 
     fn table_headers() -> Vec<&'static str> {
-        vec!["ID", "NAME", "EMAIL", "ROLE", "STATUS"]
+        vec!["ID", "NAME", "EMAIL", "ROLE", "STATUS", "JOINED", "LAST ACTIVE"]
     }
 }
 
@@ -58,7 +78,21 @@ struct InvitationRow {
     email: String,
     role: String,
     status: String,
-    created_at: String,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl InvitationRow {
+    fn new(invitation: &InvitationInfo, now: DateTime<Utc>) -> Self {
+        Self {
+            id: invitation.id.clone(),
+            email: invitation.email.clone(),
+            role: format!("{:?}", invitation.role),
+            status: format!("{:?}", effective_status(invitation, now)),
+            created_at: invitation.created_at,
+            expires_at: invitation.expires_at,
+        }
+    }
 }
 
 impl Displayable for InvitationRow {
@@ -68,12 +102,13 @@ impl Displayable for InvitationRow {
             self.email.clone(),
             self.role.clone(),
             self.status.clone(),
-            self.created_at.clone(),
+            dates::format(self.created_at),
+            dates::format(self.expires_at),
         ]
     }
 
     fn table_headers() -> Vec<&'static str> {
-        vec!["ID", "EMAIL", "ROLE", "STATUS", "CREATED"]
+        vec!["ID", "EMAIL", "ROLE", "STATUS", "CREATED", "EXPIRES"]
     }
 }
 
@@ -123,12 +158,17 @@ struct ClientRow {
     id: String,
     name: String,
     status: String,
-    created_at: String,
+    created_at: DateTime<Utc>,
 }
 
 impl Displayable for ClientRow {
     fn table_row(&self) -> Vec<String> {
-        vec![self.id.clone(), self.name.clone(), self.status.clone(), self.created_at.clone()]
+        vec![
+            self.id.clone(),
+            self.name.clone(),
+            self.status.clone(),
+            dates::format(self.created_at),
+        ]
     }
 
     fn table_headers() -> Vec<&'static str> {
@@ -141,7 +181,7 @@ struct CertificateRow {
     id: String,
     fingerprint: String,
     status: String,
-    expires_at: String,
+    expires_at: Option<DateTime<Utc>>,
 }
 
 impl Displayable for CertificateRow {
@@ -150,7 +190,7 @@ impl Displayable for CertificateRow {
             self.id.clone(),
             self.fingerprint.clone(),
             self.status.clone(),
-            self.expires_at.clone(),
+            self.expires_at.map_or_else(|| "-".to_string(), dates::format_date),
         ]
     }
 
@@ -161,7 +201,7 @@ impl Displayable for CertificateRow {
 
 #[derive(Debug, Clone, Serialize)]
 struct AuditLogRow {
-    timestamp: String,
+    timestamp: DateTime<Utc>,
     actor: String,
     action: String,
     resource: String,
@@ -171,7 +211,7 @@ struct AuditLogRow {
 impl Displayable for AuditLogRow {
     fn table_row(&self) -> Vec<String> {
         vec![
-            self.timestamp.clone(),
+            dates::format_precise(self.timestamp),
             self.actor.clone(),
             self.action.clone(),
             self.resource.clone(),
@@ -193,7 +233,7 @@ pub async fn list(ctx: &Context) -> Result<()> {
     let client = ctx.client().await?;
     let orgs = client.organizations();
 
-    let page = orgs.list().await?;
+    let page = ctx.cached("orgs", async { Ok(orgs.list().await?) }).await?;
 
     if page.items.is_empty() {
         ctx.output.info("No organizations found.");
@@ -207,7 +247,7 @@ pub async fn list(ctx: &Context) -> Result<()> {
             id: o.id.clone(),
             name: o.name.clone(),
             display_name: o.display_name.clone().unwrap_or_else(|| "-".to_string()),
-            created_at: o.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            created_at: o.created_at,
         })
         .collect();
 
@@ -232,6 +272,7 @@ pub async fn create(ctx: &Context, name: &str, tier: Option<&str>) -> Result<()>
 
     ctx.output.success(&format!("Organization '{}' created.", org.name));
     ctx.output.info(&format!("ID: {}", org.id));
+    ctx.output.primary(&org.id);
 
     Ok(())
 }
@@ -245,7 +286,7 @@ pub async fn get(ctx: &Context, id: Option<&str>) -> Result<()> {
         return Ok(());
     };
 
-    let page = client.organizations().list().await?;
+    let page = ctx.cached("orgs", async { Ok(client.organizations().list().await?) }).await?;
     let org = page.items.iter().find(|o| o.id == org_id || o.name == org_id);
 
     match org {
@@ -255,8 +296,8 @@ pub async fn get(ctx: &Context, id: Option<&str>) -> Result<()> {
             if let Some(display) = &info.display_name {
                 println!("Display Name: {display}");
             }
-            println!("Created: {}", info.created_at.format("%Y-%m-%d %H:%M:%S"));
-            println!("Updated: {}", info.updated_at.format("%Y-%m-%d %H:%M:%S"));
+            println!("Created: {}", dates::format_precise(info.created_at));
+            println!("Updated: {}", dates::format_precise(info.updated_at));
         },
         None => {
             ctx.output.error(&format!("Organization '{org_id}' not found."));
@@ -311,44 +352,224 @@ pub async fn resume(ctx: &Context, id: &str) -> Result<()> {
 }
 
 /// Leave organization.
-pub async fn leave(ctx: &Context, id: &str, yes: bool) -> Result<()> {
+///
+/// The last owner cannot leave without handing the organization over, so
+/// `transfer_to` promotes that member to owner before leaving.
+pub async fn leave(ctx: &Context, id: &str, yes: bool, transfer_to: Option<&str>) -> Result<()> {
+    use inferadb::control::UpdateMemberRequest;
+
+    let client = ctx.client().await?;
+    let org = client.organization(id);
+
+    let me = client.account().get().await?.id;
+    let members = fetch_members(&org, None).await?;
+    let successor = plan_leave(&members, &me, transfer_to)?;
+
     if !yes {
         ctx.output.warn("Leaving an organization will revoke your access.");
-        let confirmed = ctx.confirm(&format!("Leave organization '{id}'?"))?;
+        let prompt = match successor {
+            Some(member) => format!(
+                "Transfer ownership of '{id}' to {} and leave?",
+                member.name.as_deref().unwrap_or(&member.email)
+            ),
+            None => format!("Leave organization '{id}'?"),
+        };
+        let confirmed = ctx.confirm(&prompt)?;
         if !confirmed {
             ctx.output.info("Cancelled.");
             return Ok(());
         }
     }
 
-    ctx.output.warn("Leave organization not yet supported via CLI.");
-    ctx.output.info(&format!("Would leave organization: {id}"));
-    ctx.output.info("Use the web dashboard to leave organizations.");
+    if let Some(member) = successor {
+        org.members()
+            .update(&member.user_id, UpdateMemberRequest::new().with_role(OrgRole::Owner))
+            .await?;
+        ctx.output.success(&format!("Ownership transferred to {}.", member.email));
+    }
+
+    org.members().remove(&me).await?;
+    ctx.output.success(&format!("Left organization '{id}'."));
 
     Ok(())
 }
 
+/// Check that `me` may leave, returning the member to promote to owner first.
+///
+/// Fails when `me` is the only owner and no successor was named, so the user
+/// gets instructions instead of a server-side refusal.
+fn plan_leave<'a>(
+    members: &'a [MemberInfo],
+    me: &str,
+    transfer_to: Option<&str>,
+) -> Result<Option<&'a MemberInfo>> {
+    let Some(mine) = members.iter().find(|m| m.user_id == me) else {
+        return Err(Error::invalid_arg("You are not a member of this organization."));
+    };
+
+    let Some(target) = transfer_to else {
+        let owners = members.iter().filter(|m| m.role == OrgRole::Owner).count();
+        if mine.role == OrgRole::Owner && owners == 1 {
+            return Err(Error::invalid_arg(
+                "You are the only owner of this organization. Transfer ownership with \
+                 --transfer-to <member-id> (see 'inferadb orgs members list'), or delete the \
+                 organization instead.",
+            ));
+        }
+        return Ok(None);
+    };
+
+    if mine.role != OrgRole::Owner {
+        return Err(Error::invalid_arg("--transfer-to requires you to be an owner."));
+    }
+    if target == me {
+        return Err(Error::invalid_arg("Cannot transfer ownership to yourself."));
+    }
+    let successor = members.iter().find(|m| m.user_id == target).ok_or_else(|| {
+        Error::invalid_arg(format!("'{target}' is not a member of this organization."))
+    })?;
+
+    Ok((successor.role != OrgRole::Owner).then_some(successor))
+}
+
 // ============================================================================
 // Member commands
 // ============================================================================
 
+/// Audit log pages scanned for member activity, newest first.
+const ACTIVITY_SCAN_PAGES: usize = 10;
+
+/// Parse an organization role name.
+fn parse_org_role(role: &str) -> Result<OrgRole> {
+    match role.to_lowercase().as_str() {
+        "owner" => Ok(OrgRole::Owner),
+        "admin" => Ok(OrgRole::Admin),
+        "member" => Ok(OrgRole::Member),
+        "billing" => Ok(OrgRole::Billing),
+        "viewer" => Ok(OrgRole::Viewer),
+        _ => Err(Error::invalid_arg(format!(
+            "Invalid role: {role}. Use owner, admin, member, billing, or viewer."
+        ))),
+    }
+}
+
+/// Rank roles from most to least privileged for sorting.
+const fn role_rank(role: OrgRole) -> u8 {
+    match role {
+        OrgRole::Owner => 0,
+        OrgRole::Admin => 1,
+        OrgRole::Member => 2,
+        OrgRole::Billing => 3,
+        OrgRole::Viewer => 4,
+    }
+}
+
+/// Whether a member's ID, name, or email contains the search text
+/// (case-insensitive).
+fn member_matches(member: &MemberInfo, search: &str) -> bool {
+    let search = search.to_lowercase();
+    [Some(&member.user_id), member.name.as_ref(), Some(&member.email)]
+        .into_iter()
+        .flatten()
+        .any(|field| field.to_lowercase().contains(&search))
+}
+
+/// Sort members by `name`, `email`, `role`, `joined`, or `last-active`.
+///
+/// `last-active` puts members with the oldest (or no) recorded activity first.
+fn sort_members(
+    members: &mut [MemberInfo],
+    sort: &str,
+    last_active: &HashMap<String, DateTime<Utc>>,
+) -> Result<()> {
+    match sort {
+        "name" => {
+            members.sort_by_cached_key(|m| m.name.as_deref().unwrap_or(&m.email).to_lowercase());
+        },
+        "email" => members.sort_by_cached_key(|m| m.email.to_lowercase()),
+        "role" => members.sort_by_key(|m| role_rank(m.role)),
+        "joined" => members.sort_by_key(|m| m.joined_at),
+        "last-active" => members.sort_by_key(|m| last_active.get(&m.user_id).copied()),
+        _ => {
+            return Err(Error::invalid_arg(format!(
+                "Invalid sort: {sort}. Use name, email, role, joined, or last-active."
+            )));
+        },
+    }
+    Ok(())
+}
+
+/// Most recent audit event per actor, scanning up to [`ACTIVITY_SCAN_PAGES`]
+/// pages until every member has been seen.
+async fn member_activity(
+    org: &inferadb::client::OrganizationClient,
+    members: &[MemberInfo],
+) -> Result<HashMap<String, DateTime<Utc>>> {
+    let mut last_active = HashMap::new();
+    let mut cursor: Option<String> = None;
+
+    for _ in 0..ACTIVITY_SCAN_PAGES {
+        let mut request = org.audit().list().sort(SortOrder::Descending).limit(100);
+        if let Some(c) = &cursor {
+            request = request.cursor(c);
+        }
+        let page = request.await?;
+
+        for event in &page.items {
+            last_active.entry(event.actor.id.clone()).or_insert(event.timestamp);
+        }
+
+        cursor = page.page_info.next_cursor;
+        if cursor.is_none() || members.iter().all(|m| last_active.contains_key(&m.user_id)) {
+            break;
+        }
+    }
+
+    Ok(last_active)
+}
+
 /// List organization members.
-pub async fn members_list(ctx: &Context) -> Result<()> {
+///
+/// Pages through every member. Last activity comes from the organization
+/// audit log and shows `-` for members with no recent events.
+pub async fn members_list(
+    ctx: &Context,
+    search: Option<&str>,
+    role: Option<&str>,
+    sort: Option<&str>,
+) -> Result<()> {
     let client = ctx.client().await?;
     let org_id = ctx.require_org_id()?;
 
     let org = client.organization(&org_id);
-    let members = org.members();
+    let role = role.map(parse_org_role).transpose()?;
 
-    let page = members.list().await?;
+    let key =
+        format!("orgs/{org_id}/members?role={}", role.map(|r| r.to_string()).unwrap_or_default());
+    let mut members = ctx.cached(&key, fetch_members(&org, role)).await?;
+    if let Some(search) = search {
+        members.retain(|m| member_matches(m, search));
+    }
 
-    if page.items.is_empty() {
+    if members.is_empty() {
         ctx.output.info("No members found.");
         return Ok(());
     }
 
-    let rows: Vec<MemberRow> = page
-        .items
+    // Activity is enrichment; audit access may be restricted to admins
+    let last_active = match member_activity(&org, &members).await {
+        Ok(last_active) => last_active,
+        Err(e) => {
+            ctx.output.warn(&format!("Last activity unavailable: {e}"));
+            HashMap::new()
+        },
+    };
+
+    if let Some(sort) = sort {
+        sort_members(&mut members, sort, &last_active)?;
+    }
+
+    let rows: Vec<MemberRow> = members
         .iter()
         .map(|m| MemberRow {
             id: m.user_id.clone(),
@@ -356,12 +577,39 @@ pub async fn members_list(ctx: &Context) -> Result<()> {
             email: m.email.clone(),
             role: format!("{:?}", m.role),
             status: format!("{:?}", m.status),
+            joined: m.joined_at,
+            last_active: last_active.get(&m.user_id).copied(),
         })
         .collect();
 
     ctx.output.table(&rows)
 }
 
+/// Fetch every member, optionally with one role, following pagination.
+async fn fetch_members(
+    org: &inferadb::client::OrganizationClient,
+    role: Option<OrgRole>,
+) -> Result<Vec<MemberInfo>> {
+    let mut members = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut request = org.members().list();
+        if let Some(role) = role {
+            request = request.role(role);
+        }
+        if let Some(c) = &cursor {
+            request = request.cursor(c);
+        }
+        let page = request.await?;
+        members.extend(page.items);
+
+        cursor = page.page_info.next_cursor;
+        if cursor.is_none() {
+            return Ok(members);
+        }
+    }
+}
+
 /// Update member role.
 pub async fn members_update_role(ctx: &Context, member_id: &str, role: &str) -> Result<()> {
     use inferadb::control::{OrgRole, UpdateMemberRequest};
@@ -421,30 +669,140 @@ pub async fn invitations_list(ctx: &Context) -> Result<()> {
     let org_id = ctx.require_org_id()?;
 
     let org = client.organization(&org_id);
-    let invitations = org.invitations();
-
-    let page = invitations.list().await?;
+    let invitations =
+        ctx.cached(&format!("orgs/{org_id}/invitations"), fetch_invitations(&org)).await?;
 
-    if page.items.is_empty() {
+    if invitations.is_empty() {
         ctx.output.info("No pending invitations.");
         return Ok(());
     }
 
-    let rows: Vec<InvitationRow> = page
-        .items
-        .iter()
-        .map(|i| InvitationRow {
-            id: i.id.clone(),
-            email: i.email.clone(),
-            role: format!("{:?}", i.role),
-            status: format!("{:?}", i.status),
-            created_at: i.created_at.format("%Y-%m-%d %H:%M").to_string(),
-        })
-        .collect();
+    let now = Utc::now();
+    let rows: Vec<InvitationRow> = invitations.iter().map(|i| InvitationRow::new(i, now)).collect();
 
     ctx.output.table(&rows)
 }
 
+/// Fetch every invitation, following pagination.
+async fn fetch_invitations(
+    org: &inferadb::client::OrganizationClient,
+) -> Result<Vec<InvitationInfo>> {
+    let mut invitations = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut request = org.invitations().list();
+        if let Some(c) = &cursor {
+            request = request.cursor(c);
+        }
+        let page = request.await?;
+        invitations.extend(page.items);
+
+        cursor = page.page_info.next_cursor;
+        if cursor.is_none() {
+            return Ok(invitations);
+        }
+    }
+}
+
+/// Status accounting for pending invitations whose expiry has passed but
+/// which the server has not yet marked expired.
+fn effective_status(invitation: &InvitationInfo, now: DateTime<Utc>) -> InvitationStatus {
+    if invitation.status == InvitationStatus::Pending && invitation.expires_at <= now {
+        InvitationStatus::Expired
+    } else {
+        invitation.status
+    }
+}
+
+/// Parse a duration like `30d`, `12h`, `2w`, or `90m`.
+pub(super) fn parse_duration(spec: &str) -> Result<chrono::Duration> {
+    let invalid = || {
+        Error::invalid_arg(format!(
+            "Invalid duration '{spec}'. Use a number with m, h, d, or w (e.g. 30d)"
+        ))
+    };
+    let unit_at =
+        spec.len().checked_sub(1).filter(|&i| spec.is_char_boundary(i)).ok_or_else(invalid)?;
+    let (count, unit) = spec.split_at(unit_at);
+    let count: i64 = count.parse().map_err(|_| invalid())?;
+    match unit {
+        "m" => Ok(chrono::Duration::minutes(count)),
+        "h" => Ok(chrono::Duration::hours(count)),
+        "d" => Ok(chrono::Duration::days(count)),
+        "w" => Ok(chrono::Duration::weeks(count)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Whether an invitation should be pruned: it is expired, or still pending
+/// and was created before `cutoff`.
+fn is_stale(
+    invitation: &InvitationInfo,
+    now: DateTime<Utc>,
+    cutoff: Option<DateTime<Utc>>,
+) -> bool {
+    match effective_status(invitation, now) {
+        InvitationStatus::Expired => true,
+        InvitationStatus::Pending => cutoff.is_some_and(|cutoff| invitation.created_at < cutoff),
+        InvitationStatus::Accepted | InvitationStatus::Revoked => false,
+    }
+}
+
+/// Revoke expired invitations, and pending ones older than `older_than`.
+pub async fn invitations_prune(
+    ctx: &Context,
+    older_than: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let client = ctx.client().await?;
+    let org_id = ctx.require_org_id()?;
+
+    let now = Utc::now();
+    let cutoff = older_than.map(parse_duration).transpose()?.map(|age| now - age);
+
+    let org = client.organization(&org_id);
+    let stale: Vec<InvitationInfo> =
+        fetch_invitations(&org).await?.into_iter().filter(|i| is_stale(i, now, cutoff)).collect();
+
+    if stale.is_empty() {
+        ctx.output.info("No stale invitations.");
+        return Ok(());
+    }
+
+    let rows: Vec<InvitationRow> = stale.iter().map(|i| InvitationRow::new(i, now)).collect();
+    ctx.output.table(&rows)?;
+
+    if dry_run {
+        ctx.output.info(&format!("Dry run: {} invitation(s) would be revoked.", stale.len()));
+        return Ok(());
+    }
+
+    if !ctx.yes {
+        let confirmed = ctx.confirm(&format!("Revoke {} invitation(s)?", stale.len()))?;
+        if !confirmed {
+            ctx.output.info("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let mut failed = 0;
+    for invitation in &stale {
+        if let Err(e) = org.invitations().revoke(&invitation.id).await {
+            failed += 1;
+            ctx.output
+                .warn(&format!("Failed to revoke {} ({}): {e}", invitation.id, invitation.email));
+        }
+    }
+
+    let revoked = stale.len() - failed;
+    if failed == 0 {
+        ctx.output.success(&format!("Revoked {revoked} invitation(s)."));
+        Ok(())
+    } else {
+        Err(Error::other(format!("Revoked {revoked} invitation(s); {failed} failed.")))
+    }
+}
+
 /// Create an invitation.
 pub async fn invitations_create(ctx: &Context, email: &str, role: &str) -> Result<()> {
     use inferadb::control::{InviteMemberRequest, OrgRole};
@@ -524,7 +882,7 @@ pub async fn invitations_accept(ctx: &Context, token: &str) -> Result<()> {
 /// List role assignments.
 pub async fn roles_list(ctx: &Context) -> Result<()> {
     // Role assignments are the same as members - just show members with their roles
-    members_list(ctx).await
+    members_list(ctx, None, None, None).await
 }
 
 /// Grant a role to a user.
@@ -553,17 +911,42 @@ pub async fn roles_revoke(ctx: &Context, user_id: &str) -> Result<()> {
 // ============================================================================
 
 /// List vaults.
-pub async fn vaults_list(ctx: &Context) -> Result<()> {
+///
+/// With `archived`, lists only archived vaults. With `show_deleted`, also
+/// lists vaults the server is still deleting. The API has no trash for
+/// vaults, organizations or teams, so there is nothing to restore or purge
+/// and their deletions stay immediate.
+pub async fn vaults_list(ctx: &Context, archived: bool, show_deleted: bool) -> Result<()> {
+    use inferadb::control::VaultStatus;
+
     let client = ctx.client().await?;
     let org_id = ctx.require_org_id()?;
 
     let org = client.organization(&org_id);
     let vaults = org.vaults();
 
-    let page = vaults.list().await?;
+    let mut request = vaults.list();
+    if archived {
+        request = request.status(VaultStatus::Archived);
+    }
+    let key = format!("orgs/{org_id}/vaults?archived={archived}");
+    let mut page = ctx.cached(&key, async { Ok(request.await?) }).await?;
+
+    // Deleted vaults stay listed, with the `deleting` status, until the
+    // server has removed them; the API has no restore or purge for them
+    if show_deleted {
+        let request = vaults.list().status(VaultStatus::Deleting);
+        let key = format!("orgs/{org_id}/vaults?status=deleting");
+        let deleting = ctx.cached(&key, async { Ok(request.await?) }).await?;
+        for vault in deleting.items {
+            if !page.items.iter().any(|v| v.id == vault.id) {
+                page.items.push(vault);
+            }
+        }
+    }
 
     if page.items.is_empty() {
-        ctx.output.info("No vaults found.");
+        ctx.output.info(if archived { "No archived vaults found." } else { "No vaults found." });
         return Ok(());
     }
 
@@ -600,6 +983,7 @@ pub async fn vaults_create(ctx: &Context, name: &str, description: Option<&str>)
 
     ctx.output.success(&format!("Vault '{}' created.", vault.name));
     ctx.output.info(&format!("ID: {}", vault.id));
+    ctx.output.primary(&vault.id);
 
     Ok(())
 }
@@ -615,7 +999,11 @@ pub async fn vaults_get(ctx: &Context, id: Option<&str>) -> Result<()> {
     };
 
     let org = client.organization(&org_id);
-    let vault = org.vaults().get(vault_id).await?;
+    let vault = ctx
+        .cached(&format!("orgs/{org_id}/vaults/{vault_id}"), async {
+            Ok(org.vaults().get(vault_id).await?)
+        })
+        .await?;
 
     println!("Vault: {}", vault.name);
     println!("ID: {}", vault.id);
@@ -623,8 +1011,8 @@ pub async fn vaults_get(ctx: &Context, id: Option<&str>) -> Result<()> {
         println!("Description: {desc}");
     }
     println!("Status: {:?}", vault.status);
-    println!("Created: {}", vault.created_at.format("%Y-%m-%d %H:%M:%S"));
-    println!("Updated: {}", vault.updated_at.format("%Y-%m-%d %H:%M:%S"));
+    println!("Created: {}", dates::format_precise(vault.created_at));
+    println!("Updated: {}", dates::format_precise(vault.updated_at));
 
     Ok(())
 }
@@ -672,10 +1060,13 @@ pub async fn vaults_delete(ctx: &Context, id: &str) -> Result<()> {
         }
     }
 
+    // The SDK takes the typed confirmation as proof; the prompt above asked for it
     let org = client.organization(&org_id);
-    org.vaults().delete(id).await?;
+    org.vaults().delete(id).confirm(format!("DELETE {id}")).await?;
 
     ctx.output.success("Vault deleted.");
+    ctx.output
+        .info("It's listed by 'inferadb orgs vaults list --show-deleted' until removal finishes.");
 
     Ok(())
 }
@@ -786,7 +1177,8 @@ pub async fn teams_list(ctx: &Context) -> Result<()> {
     let org = client.organization(&org_id);
     let teams = org.teams();
 
-    let page = teams.list().await?;
+    let page =
+        ctx.cached(&format!("orgs/{org_id}/teams"), async { Ok(teams.list().await?) }).await?;
 
     if page.items.is_empty() {
         ctx.output.info("No teams found.");
@@ -826,6 +1218,7 @@ pub async fn teams_create(ctx: &Context, name: &str, description: Option<&str>)
 
     ctx.output.success(&format!("Team '{}' created.", team.name));
     ctx.output.info(&format!("ID: {}", team.id));
+    ctx.output.primary(&team.id);
 
     Ok(())
 }
@@ -844,8 +1237,8 @@ pub async fn teams_get(ctx: &Context, id: &str) -> Result<()> {
         println!("Description: {desc}");
     }
     println!("Members: {}", team.member_count);
-    println!("Created: {}", team.created_at.format("%Y-%m-%d %H:%M:%S"));
-    println!("Updated: {}", team.updated_at.format("%Y-%m-%d %H:%M:%S"));
+    println!("Created: {}", dates::format_precise(team.created_at));
+    println!("Updated: {}", dates::format_precise(team.updated_at));
 
     Ok(())
 }
@@ -1071,7 +1464,7 @@ pub async fn clients_list(ctx: &Context) -> Result<()> {
             id: c.id.clone(),
             name: c.name.clone(),
             status: format!("{:?}", c.status),
-            created_at: c.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            created_at: c.created_at,
         })
         .collect();
 
@@ -1096,6 +1489,7 @@ pub async fn clients_create(ctx: &Context, name: &str, vault: Option<&str>) -> R
 
     ctx.output.success(&format!("API client '{}' created.", api_client.name));
     ctx.output.info(&format!("ID: {}", api_client.id));
+    ctx.output.primary(&api_client.id);
     ctx.output.warn("Remember to add a certificate for authentication.");
 
     Ok(())
@@ -1115,8 +1509,8 @@ pub async fn clients_get(ctx: &Context, id: &str) -> Result<()> {
     if let Some(desc) = &api_client.description {
         println!("Description: {desc}");
     }
-    println!("Created: {}", api_client.created_at.format("%Y-%m-%d %H:%M:%S"));
-    println!("Updated: {}", api_client.updated_at.format("%Y-%m-%d %H:%M:%S"));
+    println!("Created: {}", dates::format_precise(api_client.created_at));
+    println!("Updated: {}", dates::format_precise(api_client.updated_at));
 
     Ok(())
 }
@@ -1216,9 +1610,7 @@ pub async fn certificates_list(ctx: &Context, client_id: &str) -> Result<()> {
             id: c.id.clone(),
             fingerprint: c.fingerprint.clone(),
             status: if c.active { "active" } else { "inactive" }.to_string(),
-            expires_at: c
-                .expires_at
-                .map_or_else(|| "-".to_string(), |dt| dt.format("%Y-%m-%d").to_string()),
+            expires_at: c.expires_at,
         })
         .collect();
 
@@ -1297,6 +1689,7 @@ pub async fn audit_logs(
     action: Option<&str>,
     from: Option<&str>,
     to: Option<&str>,
+    parquet: Option<&str>,
 ) -> Result<()> {
     let client = ctx.client().await?;
     let org_id = ctx.require_org_id()?;
@@ -1324,7 +1717,7 @@ pub async fn audit_logs(
         return Ok(());
     }
 
-    let rows: Vec<AuditLogRow> = page
+    let events: Vec<_> = page
         .items
         .iter()
         .filter(|e| {
@@ -1334,8 +1727,31 @@ pub async fn audit_logs(
                 true
             }
         })
+        .collect();
+
+    if let Some(path) = parquet {
+        let content = parquet::encode(&[
+            Column::timestamp_millis(
+                "timestamp",
+                events.iter().map(|e| e.timestamp.timestamp_millis()).collect(),
+            ),
+            Column::utf8("actor", events.iter().map(|e| e.actor.id.clone()).collect()),
+            Column::utf8("action", events.iter().map(|e| format!("{:?}", e.action)).collect()),
+            Column::utf8(
+                "resource",
+                events.iter().map(|e| e.resource.clone().unwrap_or_default()).collect(),
+            ),
+            Column::utf8("outcome", events.iter().map(|e| format!("{:?}", e.outcome)).collect()),
+        ])?;
+        std::fs::write(path, content)?;
+        ctx.output.success(&format!("Exported {} audit events to {path}", events.len()));
+        return Ok(());
+    }
+
+    let rows: Vec<AuditLogRow> = events
+        .iter()
         .map(|e| AuditLogRow {
-            timestamp: e.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+            timestamp: e.timestamp,
             actor: e.actor.id.clone(),
             action: format!("{:?}", e.action),
             resource: e.resource.clone().unwrap_or_else(|| "-".to_string()),
@@ -1345,3 +1761,133 @@ pub async fn audit_logs(
 
     ctx.output.table(&rows)
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use inferadb::control::MemberStatus;
+
+    use super::*;
+
+    fn member(user_id: &str, name: Option<&str>, email: &str, role: OrgRole) -> MemberInfo {
+        MemberInfo {
+            user_id: user_id.to_string(),
+            organization_id: "org_1".to_string(),
+            email: email.to_string(),
+            name: name.map(str::to_string),
+            role,
+            status: MemberStatus::Active,
+            joined_at: DateTime::UNIX_EPOCH,
+        }
+    }
+
+    fn invitation(
+        status: InvitationStatus,
+        created_days_ago: i64,
+        expires_in_days: i64,
+    ) -> InvitationInfo {
+        let now = DateTime::UNIX_EPOCH + chrono::Duration::days(100);
+        InvitationInfo {
+            id: "inv_1".to_string(),
+            organization_id: "org_1".to_string(),
+            email: "new@example.com".to_string(),
+            role: OrgRole::Member,
+            status,
+            expires_at: now + chrono::Duration::days(expires_in_days),
+            created_at: now - chrono::Duration::days(created_days_ago),
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30d").unwrap(), chrono::Duration::days(30));
+        assert_eq!(parse_duration("12h").unwrap(), chrono::Duration::hours(12));
+        assert_eq!(parse_duration("2w").unwrap(), chrono::Duration::weeks(2));
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("3y").is_err());
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let now = DateTime::UNIX_EPOCH + chrono::Duration::days(100);
+        let cutoff = Some(now - chrono::Duration::days(30));
+
+        // Past expiry, even if the server still reports pending
+        assert!(is_stale(&invitation(InvitationStatus::Pending, 10, -1), now, None));
+        assert!(is_stale(&invitation(InvitationStatus::Expired, 10, -1), now, None));
+
+        // Pending and older than the cutoff
+        assert!(is_stale(&invitation(InvitationStatus::Pending, 45, 5), now, cutoff));
+        assert!(!is_stale(&invitation(InvitationStatus::Pending, 45, 5), now, None));
+        assert!(!is_stale(&invitation(InvitationStatus::Pending, 10, 5), now, cutoff));
+
+        assert!(!is_stale(&invitation(InvitationStatus::Accepted, 45, -1), now, cutoff));
+        assert!(!is_stale(&invitation(InvitationStatus::Revoked, 45, -1), now, cutoff));
+    }
+
+    #[test]
+    fn test_plan_leave() {
+        let members = vec![
+            member("usr_1", None, "owner@example.com", OrgRole::Owner),
+            member("usr_2", None, "admin@example.com", OrgRole::Admin),
+        ];
+
+        // Sole owner must name a successor
+        assert!(plan_leave(&members, "usr_1", None).is_err());
+        let successor = plan_leave(&members, "usr_1", Some("usr_2")).unwrap().unwrap();
+        assert_eq!(successor.user_id, "usr_2");
+        assert!(plan_leave(&members, "usr_1", Some("usr_1")).is_err());
+        assert!(plan_leave(&members, "usr_1", Some("usr_9")).is_err());
+
+        // Non-owners leave freely but cannot transfer
+        assert!(plan_leave(&members, "usr_2", None).unwrap().is_none());
+        assert!(plan_leave(&members, "usr_2", Some("usr_1")).is_err());
+        assert!(plan_leave(&members, "usr_9", None).is_err());
+
+        // With another owner, no promotion is needed
+        let co_owned = vec![
+            member("usr_1", None, "owner@example.com", OrgRole::Owner),
+            member("usr_2", None, "other@example.com", OrgRole::Owner),
+        ];
+        assert!(plan_leave(&co_owned, "usr_1", None).unwrap().is_none());
+        assert!(plan_leave(&co_owned, "usr_1", Some("usr_2")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_member_matches_search() {
+        let alice = member("usr_1", Some("Alice Smith"), "alice@example.com", OrgRole::Admin);
+        assert!(member_matches(&alice, "smith"));
+        assert!(member_matches(&alice, "ALICE@"));
+        assert!(member_matches(&alice, "usr_1"));
+        assert!(!member_matches(&alice, "bob"));
+    }
+
+    #[test]
+    fn test_sort_members() {
+        let mut members = vec![
+            member("usr_1", None, "carol@example.com", OrgRole::Viewer),
+            member("usr_2", Some("alice"), "alice@example.com", OrgRole::Member),
+            member("usr_3", Some("Bob"), "bob@example.com", OrgRole::Owner),
+        ];
+        let ids = |members: &[MemberInfo]| {
+            members.iter().map(|m| m.user_id.as_str()).collect::<Vec<_>>().join(",")
+        };
+
+        sort_members(&mut members, "name", &HashMap::new()).unwrap();
+        assert_eq!(ids(&members), "usr_2,usr_3,usr_1");
+
+        sort_members(&mut members, "role", &HashMap::new()).unwrap();
+        assert_eq!(ids(&members), "usr_3,usr_2,usr_1");
+
+        let last_active = HashMap::from([
+            ("usr_2".to_string(), DateTime::UNIX_EPOCH + chrono::Duration::days(2)),
+            ("usr_3".to_string(), DateTime::UNIX_EPOCH + chrono::Duration::days(1)),
+        ]);
+        sort_members(&mut members, "last-active", &last_active).unwrap();
+        assert_eq!(ids(&members), "usr_1,usr_3,usr_2");
+
+        assert!(sort_members(&mut members, "size", &HashMap::new()).is_err());
+    }
+}
@@ -1,12 +1,19 @@
 //! Profile management commands.
 
-use serde::Serialize;
+use std::collections::BTreeMap;
+
+use bon::builder;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    client::Context,
-    config::Profile,
+    cli::ImportConflict,
+    client::{Context, account_client},
+    config::{CredentialBackend, Credentials, OutputConfig, Profile, ProfileUsage},
     error::{Error, Result},
-    output::Displayable,
+    input,
+    output::{Displayable, dates, timezone::TimeZone},
+    tui::{self, SwitchTarget, SwitchView},
 };
 
 #[derive(Debug, Clone, Serialize)]
@@ -103,6 +110,11 @@ pub async fn profiles_show(ctx: &Context, name: Option<&str>) -> Result<()> {
         url: Option<String>,
         org: Option<String>,
         vault: Option<String>,
+        principal: Option<String>,
+        require_impersonate: bool,
+        require_signed_schemas: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timezone: Option<String>,
         is_default: bool,
         authenticated: bool,
     }
@@ -112,6 +124,10 @@ pub async fn profiles_show(ctx: &Context, name: Option<&str>) -> Result<()> {
         url: profile.url.clone(),
         org: profile.org.clone(),
         vault: profile.vault.clone(),
+        principal: profile.principal.clone(),
+        require_impersonate: profile.require_impersonate,
+        require_signed_schemas: profile.require_signed_schemas,
+        timezone: profile.timezone.clone(),
         is_default: ctx.config.default_profile.as_deref() == Some(profile_name),
         authenticated,
     };
@@ -131,6 +147,18 @@ pub async fn profiles_show(ctx: &Context, name: Option<&str>) -> Result<()> {
         if let Some(ref vault) = details.vault {
             println!("Vault: {vault}");
         }
+        if let Some(ref principal) = details.principal {
+            println!("Principal: {principal}");
+        }
+        if details.require_impersonate {
+            println!("Require --impersonate: yes");
+        }
+        if details.require_signed_schemas {
+            println!("Require signed schemas: yes");
+        }
+        if let Some(ref timezone) = details.timezone {
+            println!("Timezone: {timezone}");
+        }
         println!("Authenticated: {}", if authenticated { "yes" } else { "no" });
     } else {
         ctx.output.value(&details)?;
@@ -140,12 +168,17 @@ pub async fn profiles_show(ctx: &Context, name: Option<&str>) -> Result<()> {
 }
 
 /// Create a new profile.
+#[builder]
 pub async fn profiles_create(
     ctx: &Context,
     name: &str,
     url: Option<&str>,
     org: Option<&str>,
     vault: Option<&str>,
+    principal: Option<&str>,
+    require_impersonate: Option<bool>,
+    require_signed_schemas: Option<bool>,
+    timezone: Option<&str>,
 ) -> Result<()> {
     if ctx.config.profiles.contains_key(name) {
         return Err(Error::config(format!("Profile '{name}' already exists")));
@@ -155,6 +188,11 @@ pub async fn profiles_create(
         url: url.map(std::string::ToString::to_string),
         org: org.map(std::string::ToString::to_string),
         vault: vault.map(std::string::ToString::to_string),
+        principal: principal.map(std::string::ToString::to_string),
+        require_impersonate: require_impersonate.unwrap_or_default(),
+        require_signed_schemas: require_signed_schemas.unwrap_or_default(),
+        timezone: timezone.map(validate_timezone).transpose()?,
+        credential_store: CredentialBackend::default(),
     };
 
     let mut config = ctx.config.clone();
@@ -176,13 +214,24 @@ pub async fn profiles_create(
     Ok(())
 }
 
+/// Check that a timezone can be loaded, returning it as given.
+fn validate_timezone(timezone: &str) -> Result<String> {
+    TimeZone::parse(timezone)?;
+    Ok(timezone.to_string())
+}
+
 /// Update an existing profile.
+#[builder]
 pub async fn profiles_update(
     ctx: &Context,
     name: &str,
     url: Option<&str>,
     org: Option<&str>,
     vault: Option<&str>,
+    principal: Option<&str>,
+    require_impersonate: Option<bool>,
+    require_signed_schemas: Option<bool>,
+    timezone: Option<&str>,
 ) -> Result<()> {
     let mut config = ctx.config.clone();
 
@@ -198,6 +247,18 @@ pub async fn profiles_update(
     if let Some(v) = vault {
         profile.vault = Some(v.to_string());
     }
+    if let Some(p) = principal {
+        profile.principal = Some(p.to_string());
+    }
+    if let Some(r) = require_impersonate {
+        profile.require_impersonate = r;
+    }
+    if let Some(r) = require_signed_schemas {
+        profile.require_signed_schemas = r;
+    }
+    if let Some(tz) = timezone {
+        profile.timezone = if tz.is_empty() { None } else { Some(validate_timezone(tz)?) };
+    }
 
     config.save()?;
 
@@ -233,11 +294,7 @@ pub async fn profiles_rename(ctx: &Context, old_name: &str, new_name: &str) -> R
     config.save()?;
 
     // Rename credentials too
-    let store = crate::config::CredentialStore::new();
-    if let Ok(Some(creds)) = store.load(old_name) {
-        let _ = store.store(new_name, &creds);
-        let _ = store.delete(old_name);
-    }
+    let _ = crate::config::CredentialStore::new().rename(old_name, new_name);
 
     ctx.output.success(&format!("Profile '{old_name}' renamed to '{new_name}'."));
 
@@ -250,7 +307,7 @@ pub async fn profiles_delete(ctx: &Context, name: &str) -> Result<()> {
         return Err(Error::ProfileNotFound(name.to_string()));
     }
 
-    if !ctx.yes && !ctx.confirm(&format!("Delete profile '{name}'?"))? {
+    if !ctx.confirm(&format!("Delete profile '{name}'?"))? {
         ctx.output.info("Cancelled.");
         return Ok(());
     }
@@ -301,3 +358,490 @@ pub async fn profiles_default(ctx: &Context, name: Option<&str>) -> Result<()> {
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Serialize)]
+struct StatusRow {
+    profile: String,
+    default: bool,
+    status: &'static str,
+    expires_at: Option<DateTime<Utc>>,
+    last_used: Option<DateTime<Utc>>,
+    can_refresh: bool,
+}
+
+impl Displayable for StatusRow {
+    fn table_row(&self) -> Vec<String> {
+        let default_marker = if self.default { "*" } else { "" };
+        let signed_in = self.status != "not signed in";
+        vec![
+            format!("{}{}", self.profile, default_marker),
+            self.status.to_string(),
+            if signed_in { dates::format_opt(self.expires_at) } else { "-".to_string() },
+            dates::format_opt(self.last_used),
+            if !signed_in {
+                "-"
+            } else if self.can_refresh {
+                "yes"
+            } else {
+                "no"
+            }
+            .to_string(),
+        ]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["PROFILE", "STATUS", "EXPIRES", "LAST USED", "REFRESH"]
+    }
+}
+
+/// Show each profile's sign-in state, token expiry and last use.
+pub async fn profiles_status(ctx: &Context) -> Result<()> {
+    let store = crate::config::CredentialStore::new();
+    let last_used = ProfileUsage::new().load_all().unwrap_or_default();
+    let default_profile = ctx.config.default_profile.as_deref();
+
+    let mut names: Vec<&str> = ctx.config.profiles.keys().map(String::as_str).collect();
+    // Credentials can be stored under "default" without a profile of that name
+    if !ctx.config.profiles.contains_key("default") && store.exists("default") {
+        names.push("default");
+    }
+    names.sort_unstable();
+
+    if names.is_empty() {
+        ctx.output.info("No profiles configured.");
+        ctx.output.info("Run 'inferadb login' to authenticate.");
+        return Ok(());
+    }
+
+    let rows: Vec<StatusRow> = names
+        .into_iter()
+        .map(|name| {
+            let credentials = store.load(name).ok().flatten();
+            StatusRow {
+                profile: name.to_string(),
+                default: default_profile == Some(name),
+                status: credentials.as_ref().map_or("not signed in", credential_status),
+                expires_at: credentials.as_ref().and_then(|c| c.expires_at),
+                last_used: last_used.get(name).copied(),
+                can_refresh: credentials.as_ref().is_some_and(Credentials::can_refresh),
+            }
+        })
+        .collect();
+
+    ctx.output.table(&rows)?;
+
+    if !ctx.output.is_quiet() {
+        ctx.output.info("");
+        ctx.output.info("* = default profile");
+        if rows.iter().any(|row| row.status != "not signed in") {
+            ctx.output.info("Log out from all of them with 'inferadb logout --all-profiles'.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Describe stored credentials' expiry.
+fn credential_status(credentials: &Credentials) -> &'static str {
+    if credentials.is_expired() {
+        if credentials.can_refresh() { "expired (refreshable)" } else { "expired" }
+    } else if credentials.expires_soon() {
+        "expires soon"
+    } else {
+        "signed in"
+    }
+}
+
+/// Profiles and output preferences shared with a team, as written by
+/// `profiles export`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileBundle {
+    profiles: BTreeMap<String, Profile>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output: Option<OutputConfig>,
+}
+
+const BUNDLE_HEADER: &str = "# InferaDB profile bundle. Add these profiles with:\n\
+                             #   inferadb profiles import <file>\n";
+
+/// A profile without the settings that belong to one person or machine: the
+/// principal and where credentials are stored.
+fn shareable(profile: &Profile) -> Profile {
+    Profile { principal: None, credential_store: CredentialBackend::default(), ..profile.clone() }
+}
+
+/// Export profiles (all of them when `names` is empty) as a bundle other
+/// people can import.
+pub async fn profiles_export(
+    ctx: &Context,
+    names: &[String],
+    file: Option<&str>,
+    no_output: bool,
+) -> Result<()> {
+    let mut bundle = ProfileBundle::default();
+    if names.is_empty() {
+        for (name, profile) in &ctx.config.profiles {
+            bundle.profiles.insert(name.clone(), shareable(profile));
+        }
+    } else {
+        for name in names {
+            let profile = ctx
+                .config
+                .profiles
+                .get(name)
+                .ok_or_else(|| Error::ProfileNotFound(name.clone()))?;
+            bundle.profiles.insert(name.clone(), shareable(profile));
+        }
+    }
+    if bundle.profiles.is_empty() {
+        return Err(Error::config("No profiles to export"));
+    }
+    if !no_output {
+        bundle.output = Some(ctx.config.output.clone());
+    }
+
+    let yaml = format!("{BUNDLE_HEADER}{}", serde_yaml::to_string(&bundle)?);
+    match file {
+        Some(path) => {
+            std::fs::write(path, yaml)?;
+            ctx.output
+                .success(&format!("Exported {} profile(s) to {path}.", bundle.profiles.len()));
+        },
+        None => print!("{yaml}"),
+    }
+
+    Ok(())
+}
+
+/// Import profiles from a bundle written by `profiles export`.
+///
+/// New profiles are added. A profile that exists with different settings is
+/// resolved by `on_conflict`; replacing it keeps its local principal and
+/// credential store. Differing output preferences are resolved the same way.
+pub async fn profiles_import(ctx: &Context, file: &str, on_conflict: ImportConflict) -> Result<()> {
+    let text = input::read_to_string(file)
+        .map_err(|e| Error::other(format!("Could not read profile bundle {file}: {e}")))?;
+    let bundle: ProfileBundle = serde_yaml::from_str(&text)
+        .map_err(|e| Error::config(format!("Invalid profile bundle {file}: {e}")))?;
+
+    let resolve = |message: &str, changes: &[String]| -> Result<bool> {
+        match on_conflict {
+            ImportConflict::Overwrite => Ok(true),
+            ImportConflict::Skip => Ok(false),
+            ImportConflict::Ask => {
+                for change in changes {
+                    ctx.output.info(&format!("  {change}"));
+                }
+                ctx.confirm(message)
+            },
+        }
+    };
+
+    let mut config = ctx.config.clone();
+    let mut added = Vec::new();
+    let (mut updated, mut unchanged, mut skipped) = (0, 0, 0);
+
+    for (name, incoming) in bundle.profiles {
+        if let Some(ref timezone) = incoming.timezone {
+            validate_timezone(timezone)?;
+        }
+        let Some(local) = config.profiles.get(&name) else {
+            config.set_profile(name.clone(), shareable(&incoming));
+            added.push(name);
+            continue;
+        };
+
+        let merged = Profile {
+            principal: local.principal.clone(),
+            credential_store: local.credential_store,
+            ..incoming
+        };
+        if &merged == local {
+            unchanged += 1;
+            continue;
+        }
+
+        ctx.output.info(&format!("Profile '{name}' differs from the bundle:"));
+        if resolve(&format!("Replace profile '{name}'?"), &profile_changes(local, &merged))? {
+            config.set_profile(name, merged);
+            updated += 1;
+        } else {
+            skipped += 1;
+        }
+    }
+
+    let mut output_applied = false;
+    if let Some(output) = bundle.output
+        && output != config.output
+    {
+        ctx.output.info("Output preferences differ from the bundle:");
+        if resolve(
+            "Apply the bundle's output preferences?",
+            &output_changes(&config.output, &output),
+        )? {
+            config.output = output;
+            output_applied = true;
+        } else {
+            skipped += 1;
+        }
+    }
+
+    if added.is_empty() && updated == 0 && !output_applied {
+        ctx.output.info(&format!("Nothing imported ({unchanged} unchanged, {skipped} skipped)."));
+        return Ok(());
+    }
+
+    if config.default_profile.is_none() {
+        config.set_default(added.first().cloned());
+    }
+
+    config.save()?;
+
+    ctx.output.success(&format!(
+        "Imported profiles: {} added, {updated} updated, {unchanged} unchanged, {skipped} skipped.",
+        added.len()
+    ));
+    if output_applied {
+        ctx.output.info("Applied the bundle's output preferences.");
+    }
+    if let Some(name) = config.default_profile.as_deref().filter(|n| added.iter().any(|a| a == n)) {
+        ctx.output.info(&format!("Set '{name}' as the default profile."));
+    }
+
+    Ok(())
+}
+
+/// Describe how shared settings differ between two profiles, one line per
+/// setting (e.g., `url: https://a → https://b`).
+fn profile_changes(old: &Profile, new: &Profile) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut diff = |key: &str, old: String, new: String| {
+        if old != new {
+            changes.push(format!("{key}: {old} → {new}"));
+        }
+    };
+    let unset = |value: &Option<String>| value.clone().unwrap_or_else(|| "(unset)".to_string());
+
+    diff("url", unset(&old.url), unset(&new.url));
+    diff("org", unset(&old.org), unset(&new.org));
+    diff("vault", unset(&old.vault), unset(&new.vault));
+    diff(
+        "require_impersonate",
+        old.require_impersonate.to_string(),
+        new.require_impersonate.to_string(),
+    );
+    diff(
+        "require_signed_schemas",
+        old.require_signed_schemas.to_string(),
+        new.require_signed_schemas.to_string(),
+    );
+    diff("timezone", unset(&old.timezone), unset(&new.timezone));
+    changes
+}
+
+/// Describe how two sets of output preferences differ, one line per setting.
+fn output_changes(old: &OutputConfig, new: &OutputConfig) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut diff = |key: &str, old: String, new: String| {
+        if old != new {
+            changes.push(format!("{key}: {old} → {new}"));
+        }
+    };
+
+    diff("format", old.format.clone(), new.format.clone());
+    diff("color", old.color.clone(), new.color.clone());
+    diff("unicode", old.unicode.to_string(), new.unicode.to_string());
+    diff("accessible", old.accessible.to_string(), new.accessible.to_string());
+    diff("suppress_warnings", old.suppress_warnings.join(","), new.suppress_warnings.join(","));
+    if old.theme != new.theme {
+        changes.push("theme: changed".to_string());
+    }
+    changes
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SwitchRow {
+    profile: String,
+    org: String,
+    vault: String,
+}
+
+impl Displayable for SwitchRow {
+    fn table_row(&self) -> Vec<String> {
+        vec![self.profile.clone(), self.org.clone(), self.vault.clone()]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["PROFILE", "ORG", "VAULT"]
+    }
+}
+
+/// Pick a profile, organization, and vault and make them the default.
+///
+/// Lists every vault reachable from each signed-in profile in a full-screen
+/// fuzzy picker, starting from `query`. Without a terminal, `query` must
+/// match exactly one target.
+pub async fn profiles_switch(ctx: &Context, query: Option<&str>) -> Result<()> {
+    let targets = switch_targets(ctx).await?;
+    if targets.is_empty() {
+        return Err(Error::other(
+            "No vaults found. Run 'inferadb login', or create one with 'inferadb orgs vaults create'",
+        ));
+    }
+
+    let chosen = if tui::is_interactive(ctx) {
+        use teapot::{
+            output::{terminal_height, terminal_width},
+            runtime::{Program, ProgramOptions},
+        };
+
+        let view = SwitchView::builder()
+            .width(terminal_width())
+            .height(terminal_height())
+            .targets(targets)
+            .query(query.unwrap_or_default().to_string())
+            .build();
+        let view = Program::new(view)
+            .with_options(ProgramOptions::fullscreen())
+            .run()
+            .map_err(|e| Error::Other(e.to_string()))?;
+        let Some(chosen) = view.chosen().cloned() else {
+            ctx.output.info("Cancelled.");
+            return Ok(());
+        };
+        chosen
+    } else {
+        let view = SwitchView::builder()
+            .width(0)
+            .height(0)
+            .targets(targets)
+            .query(query.unwrap_or_default().to_string())
+            .build();
+        let matches: Vec<&SwitchTarget> = view.matches().collect();
+        match matches.as_slice() {
+            [only] if query.is_some() => (*only).clone(),
+            [] => {
+                return Err(Error::invalid_arg(format!(
+                    "Nothing matches '{}'",
+                    query.unwrap_or_default()
+                )));
+            },
+            _ => {
+                let rows: Vec<SwitchRow> = matches
+                    .iter()
+                    .map(|t| SwitchRow {
+                        profile: t.profile.clone(),
+                        org: format!("{} ({})", t.org_name, t.org_id),
+                        vault: format!("{} ({})", t.vault_name, t.vault_id),
+                    })
+                    .collect();
+                ctx.output.table(&rows)?;
+                return Err(Error::invalid_arg(
+                    "Pass a query matching exactly one vault to switch without a terminal",
+                ));
+            },
+        }
+    };
+
+    let mut config = ctx.config.clone();
+    let profile = config
+        .profiles
+        .get_mut(&chosen.profile)
+        .ok_or_else(|| Error::ProfileNotFound(chosen.profile.clone()))?;
+    profile.org = Some(chosen.org_id.clone());
+    profile.vault = Some(chosen.vault_id.clone());
+    config.set_default(Some(chosen.profile.clone()));
+    config.save()?;
+
+    ctx.output.success(&format!(
+        "Switched to {}/{} (profile '{}').",
+        chosen.org_name, chosen.vault_name, chosen.profile
+    ));
+    Ok(())
+}
+
+/// Every vault reachable from each signed-in profile. Profiles that cannot
+/// list their organizations are skipped with a warning.
+async fn switch_targets(ctx: &Context) -> Result<Vec<SwitchTarget>> {
+    let default_profile = ctx.effective_profile_name();
+    let mut names: Vec<&String> = ctx.config.profiles.keys().collect();
+    names.sort();
+
+    let mut targets = Vec::new();
+    for name in names {
+        let profile = &ctx.config.profiles[name];
+        let client = match account_client(&ctx.config, name).await {
+            Ok(client) => client,
+            Err(e) => {
+                ctx.output.warn(&format!("Skipping profile '{name}': {e}"));
+                continue;
+            },
+        };
+        let orgs = match client.organizations().list().await {
+            Ok(page) => page.items,
+            Err(e) => {
+                ctx.output.warn(&format!("Skipping profile '{name}': {e}"));
+                continue;
+            },
+        };
+
+        for org in orgs {
+            let vaults = client.organization(&org.id).vaults().list().await?;
+            for vault in vaults.items {
+                targets.push(SwitchTarget {
+                    current: name == default_profile
+                        && profile.org.as_deref() == Some(org.id.as_str())
+                        && profile.vault.as_deref() == Some(vault.id.as_str()),
+                    profile: name.clone(),
+                    org_id: org.id.clone(),
+                    org_name: org.name.clone(),
+                    vault_id: vault.id,
+                    vault_name: vault.name,
+                });
+            }
+        }
+    }
+    Ok(targets)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundle_leaves_out_personal_settings() {
+        let profile = Profile {
+            principal: Some("user:alice".to_string()),
+            credential_store: CredentialBackend::File,
+            timezone: Some("UTC".to_string()),
+            ..Profile::new("https://api.example.com", "111", "222")
+        };
+        let bundle = ProfileBundle {
+            profiles: BTreeMap::from([("prod".to_string(), shareable(&profile))]),
+            output: None,
+        };
+
+        let yaml = serde_yaml::to_string(&bundle).unwrap();
+        assert!(!yaml.contains("alice"));
+        assert!(!yaml.contains("credential_store"));
+
+        let parsed: ProfileBundle = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.profiles["prod"], shareable(&profile));
+        assert!(serde_yaml::from_str::<ProfileBundle>("profile: {}").is_err());
+    }
+
+    #[test]
+    fn test_profile_changes() {
+        let old = Profile::new("https://a.example.com", "111", "222");
+        let new = Profile { vault: None, require_impersonate: true, ..old.clone() };
+
+        assert!(profile_changes(&old, &old).is_empty());
+        assert_eq!(
+            profile_changes(&old, &new),
+            ["vault: 222 → (unset)", "require_impersonate: false → true"]
+        );
+    }
+}
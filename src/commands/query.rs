@@ -0,0 +1,120 @@
+//! Relationship query command.
+
+use serde_json::{Map, Value};
+
+use crate::{
+    client::Context,
+    error::Result,
+    output::OutputFormat,
+    query::{Query, Row},
+};
+
+/// Page size used when scanning relationships for a query.
+const QUERY_PAGE_SIZE: usize = 1000;
+
+/// Run a SQL-ish query over the vault's relationships.
+///
+/// Equality filters are sent to the list API; the rest of the query is
+/// evaluated client-side over every page returned. With `explain`, the plan is
+/// printed instead of running the query.
+pub async fn query(ctx: &Context, statement: &str, explain: bool) -> Result<()> {
+    let query = Query::parse(statement)?;
+    let pushdown = query.pushdown();
+
+    if explain {
+        let filters: Vec<String> = [
+            ("resource", &pushdown.resource),
+            ("subject", &pushdown.subject),
+            ("relation", &pushdown.relation),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.as_ref().map(|v| format!("{name}={v}")))
+        .collect();
+
+        if filters.is_empty() {
+            ctx.output.info("List API filters: none (full scan)");
+        } else {
+            ctx.output.info(&format!("List API filters: {}", filters.join(", ")));
+        }
+        let mut client_side = Vec::new();
+        if query.filter.is_some() {
+            client_side.push("WHERE");
+        }
+        if query.distinct {
+            client_side.push("DISTINCT");
+        }
+        if query.order_by.is_some() {
+            client_side.push("ORDER BY");
+        }
+        if query.limit.is_some() {
+            client_side.push("LIMIT");
+        }
+        if client_side.is_empty() {
+            ctx.output.info("Client-side: projection only");
+        } else {
+            ctx.output.info(&format!("Client-side: {}", client_side.join(", ")));
+        }
+        return Ok(());
+    }
+
+    let client = ctx.client().await?;
+    let vault = client.vault();
+
+    let mut rows = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut request = vault.relationships().list().limit(QUERY_PAGE_SIZE);
+        if let Some(resource) = &pushdown.resource {
+            request = request.resource(resource);
+        }
+        if let Some(subject) = &pushdown.subject {
+            request = request.subject(subject);
+        }
+        if let Some(relation) = &pushdown.relation {
+            request = request.relation(relation);
+        }
+        if let Some(c) = &cursor {
+            request = request.cursor(c);
+        }
+
+        let page = request.await?;
+        rows.extend(page.iter().map(|rel| Row {
+            resource: rel.resource().to_string(),
+            relation: rel.relation().to_string(),
+            subject: rel.subject().to_string(),
+        }));
+
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    let scanned = rows.len();
+    let result = query.apply(rows);
+
+    if ctx.output.format() != OutputFormat::Table {
+        let records: Vec<Map<String, Value>> = result
+            .rows
+            .iter()
+            .map(|row| {
+                result.columns.iter().cloned().zip(row.iter().cloned().map(Value::String)).collect()
+            })
+            .collect();
+        return ctx.output.value(&records);
+    }
+
+    if result.rows.is_empty() {
+        ctx.output.info(&format!("No rows matched ({scanned} relationships scanned)."));
+        return Ok(());
+    }
+
+    let returned = result.rows.len();
+    let headers: Vec<String> = result.columns.iter().map(|c| c.to_uppercase()).collect();
+    ctx.output.grid(&headers, result.rows);
+    if !ctx.output.is_quiet() {
+        ctx.output.info(&format!("\n{returned} rows ({scanned} relationships scanned)"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,363 @@
+//! Guided schema refactorings.
+//!
+//! `schemas refactor rename-relation` and `rename-entity` rewrite the schema
+//! and migrate the vault's relationships to match, without a window where
+//! either the old or the new relationships are ignored:
+//!
+//! 1. Expand: activate the current schema plus the new name.
+//! 2. Copy: write a renamed copy of every affected relationship.
+//! 3. Switch: activate the refactored schema, still declaring the old name.
+//! 4. Clean up: delete the old relationships.
+//! 5. Contract: activate the refactored schema.
+//!
+//! Progress is saved to a checkpoint under the state directory after each
+//! step, so `schemas refactor resume <checkpoint>` can finish an interrupted
+//! run. Every step is safe to repeat.
+
+use std::{path::PathBuf, time::Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    check::object_type,
+    retag::{Tuple, delete_all, list_all, write_all},
+    schemas::ensure_signature_not_required,
+};
+use crate::{
+    client::Context,
+    config::{Config, StateLock},
+    error::{Error, Result},
+    input,
+    ipl::{self, Refactoring},
+    output::{OutputFormat, diff, timings},
+    tui,
+};
+
+/// Refactoring steps, in order.
+const STEPS: [&str; 5] = [
+    "Expand: activate the current schema plus the new name",
+    "Copy: write renamed copies of affected relationships",
+    "Switch: activate the refactored schema, still declaring the old name",
+    "Clean up: delete the old relationships",
+    "Contract: activate the refactored schema",
+];
+
+/// Saved progress of a refactoring run.
+#[derive(Debug, Serialize, Deserialize)]
+struct Checkpoint {
+    /// `org/vault` the run applies to.
+    vault: String,
+    refactoring: Refactoring,
+    /// Schema source before the refactoring.
+    source: String,
+    /// Schema file to update when the run completes.
+    file: Option<String>,
+    batch_size: usize,
+    /// Number of steps completed.
+    completed: usize,
+}
+
+/// Preview of a refactoring, for json/yaml output.
+#[derive(Debug, Serialize)]
+struct Plan<'a> {
+    refactoring: &'a Refactoring,
+    schema: &'a str,
+    relationships: usize,
+    steps: &'a [&'a str],
+}
+
+/// The renamed form of a relationship, or `None` if the refactoring does
+/// not affect it.
+fn rewrite_tuple(refactoring: &Refactoring, tuple: &Tuple) -> Option<Tuple> {
+    let mut new = tuple.clone();
+    match refactoring {
+        Refactoring::RenameRelation { entity, from, to } => {
+            if ipl::type_matches(entity, object_type(&tuple.resource)) && tuple.relation == *from {
+                new.relation.clone_from(to);
+            }
+            if let Some((object, relation)) = tuple.subject.split_once('#')
+                && relation == from
+                && ipl::type_matches(entity, object_type(object))
+            {
+                new.subject = format!("{object}#{to}");
+            }
+        },
+        Refactoring::RenameEntity { from, to } => {
+            let retype = |object: &str| match object.split_once(':') {
+                Some((type_name, id)) if ipl::type_matches(from, type_name) => {
+                    Some(format!("{}:{id}", ipl::type_name(to)))
+                },
+                _ => None,
+            };
+            if let Some(resource) = retype(&tuple.resource) {
+                new.resource = resource;
+            }
+            if let Some(subject) = retype(&tuple.subject) {
+                new.subject = subject;
+            }
+        },
+    }
+    (new != *tuple).then_some(new)
+}
+
+/// Relationships the refactoring affects, paired with their renamed form.
+async fn affected(
+    vault: &inferadb::VaultClient,
+    refactoring: &Refactoring,
+) -> Result<Vec<(Tuple, Tuple)>> {
+    let rows = list_all(vault).await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|old| rewrite_tuple(refactoring, &old).map(|new| (old, new)))
+        .collect())
+}
+
+/// Push a schema and make it active.
+async fn push_and_activate(
+    ctx: &Context,
+    vault: &inferadb::VaultClient,
+    content: &str,
+) -> Result<()> {
+    let schemas = vault.schemas();
+    let result = schemas.push(content).await?;
+    if !result.validation.is_valid() {
+        ctx.output.error("Schema validation failed:");
+        for err in &result.validation.errors {
+            eprintln!("  Line {}: {} [{}]", err.line, err.message, err.code);
+        }
+        return Err(Error::parse("Schema validation failed"));
+    }
+    schemas.activate(&result.schema.version).await?;
+    ctx.output.info(&format!("Activated schema version {}.", result.schema.version));
+    Ok(())
+}
+
+fn checkpoint_path() -> Result<PathBuf> {
+    let dir = Config::state_dir()
+        .map(|dir| dir.join("refactor"))
+        .ok_or_else(|| Error::config("Could not determine state directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}.yaml", chrono::Utc::now().format("%Y%m%dT%H%M%SZ"))))
+}
+
+fn save_checkpoint(path: &PathBuf, checkpoint: &Checkpoint) -> Result<()> {
+    std::fs::write(path, serde_yaml::to_string(checkpoint)?)?;
+    Ok(())
+}
+
+/// Preview a refactoring, or run it against the vault with `apply`.
+///
+/// The schema comes from `file` when given, otherwise the active schema.
+pub async fn refactor(
+    ctx: &Context,
+    refactoring: Refactoring,
+    file: Option<&str>,
+    apply: bool,
+    batch_size: usize,
+) -> Result<()> {
+    let client = ctx.client().await?;
+    let vault = client.vault();
+
+    let source = match file {
+        Some(path) => input::read_to_string(path)
+            .map_err(|e| Error::invalid_arg(format!("Could not read {path}: {e}")))?,
+        None => vault.schemas().get_active().await?.content,
+    };
+    let refactoring = refactoring.resolve(&ipl::parse(&source)?)?;
+    let target = refactoring.apply(&source)?;
+
+    if !apply {
+        let started = Instant::now();
+        let count = tui::spin("Finding affected relationships...", affected(&vault, &refactoring))
+            .await?
+            .len();
+        timings::record("Read relationships", "OK", started.elapsed());
+
+        if ctx.output.format() != OutputFormat::Table {
+            return ctx.output.value(&Plan {
+                refactoring: &refactoring,
+                schema: &target,
+                relationships: count,
+                steps: &STEPS,
+            });
+        }
+
+        let hunks = diff::hunks(&diff::diff_lines(&source, &target), 3);
+        print!("{}", diff::render_unified(&hunks, ctx.output.color));
+        println!();
+        println!("Relationships to migrate: {count}");
+        println!();
+        println!("Steps:");
+        for (index, step) in STEPS.iter().enumerate() {
+            println!("  {}. {step}", index + 1);
+        }
+        println!();
+        ctx.output.info("Run again with --apply to refactor the vault.");
+        return Ok(());
+    }
+
+    ensure_signature_not_required(ctx)?;
+    if !ctx.confirm("Refactor the schema and migrate relationships in this vault?")? {
+        ctx.output.info("Cancelled.");
+        return Ok(());
+    }
+
+    let checkpoint = Checkpoint {
+        vault: format!("{}/{}", client.org_id(), client.vault_id()),
+        refactoring,
+        source,
+        file: file.map(str::to_string),
+        batch_size,
+        completed: 0,
+    };
+    let path = checkpoint_path()?;
+    let _lock = StateLock::acquire(&path, "this refactor checkpoint")?;
+    save_checkpoint(&path, &checkpoint)?;
+    run(ctx, &vault, checkpoint, &path).await
+}
+
+/// Continue a refactoring from a checkpoint.
+pub async fn resume(ctx: &Context, checkpoint: &str) -> Result<()> {
+    if checkpoint == input::STDIN {
+        return Err(Error::invalid_arg(
+            "Checkpoints can't be read from stdin; progress is saved back to the checkpoint file",
+        ));
+    }
+    let path = PathBuf::from(checkpoint);
+    // A resume that is already running must finish first
+    let _lock = StateLock::acquire(&path, "this refactor checkpoint")?;
+    let contents = input::read_to_string(checkpoint)
+        .map_err(|e| Error::invalid_arg(format!("Could not read checkpoint {checkpoint}: {e}")))?;
+    let saved: Checkpoint = serde_yaml::from_str(&contents)
+        .map_err(|e| Error::parse(format!("Invalid checkpoint {checkpoint}: {e}")))?;
+
+    let client = ctx.client().await?;
+    let vault_key = format!("{}/{}", client.org_id(), client.vault_id());
+    if saved.vault != vault_key {
+        return Err(Error::invalid_arg(format!(
+            "Checkpoint {checkpoint} belongs to vault {}, not {vault_key}",
+            saved.vault
+        )));
+    }
+    ensure_signature_not_required(ctx)?;
+
+    ctx.output.info(&format!("Resuming at step {} of {}.", saved.completed + 1, STEPS.len()));
+    run(ctx, &client.vault(), saved, &path).await
+}
+
+/// Run the remaining steps, saving progress after each.
+async fn run(
+    ctx: &Context,
+    vault: &inferadb::VaultClient,
+    mut checkpoint: Checkpoint,
+    path: &PathBuf,
+) -> Result<()> {
+    let target = checkpoint.refactoring.apply(&checkpoint.source)?;
+
+    while checkpoint.completed < STEPS.len() {
+        let step = checkpoint.completed;
+        ctx.output.info(&format!("[{}/{}] {}", step + 1, STEPS.len(), STEPS[step]));
+        let started = Instant::now();
+
+        let result = match step {
+            0 => {
+                let widened = checkpoint.refactoring.widen(&checkpoint.source)?;
+                push_and_activate(ctx, vault, &widened).await
+            },
+            1 | 3 => match affected(vault, &checkpoint.refactoring).await {
+                Ok(moves) => {
+                    let mut result = Ok(());
+                    for batch in moves.chunks(checkpoint.batch_size) {
+                        result = if step == 1 {
+                            write_all(vault, &batch.iter().map(|(_, new)| new).collect::<Vec<_>>())
+                                .await
+                        } else {
+                            delete_all(vault, &batch.iter().map(|(old, _)| old).collect::<Vec<_>>())
+                                .await
+                        };
+                        if result.is_err() {
+                            break;
+                        }
+                    }
+                    if result.is_ok() {
+                        let verb = if step == 1 { "Copied" } else { "Deleted" };
+                        ctx.output.info(&format!("{verb} {} relationships.", moves.len()));
+                    }
+                    result
+                },
+                Err(e) => Err(e),
+            },
+            2 => {
+                let widened = checkpoint.refactoring.reversed().widen(&target)?;
+                push_and_activate(ctx, vault, &widened).await
+            },
+            _ => push_and_activate(ctx, vault, &target).await,
+        };
+
+        let status = if result.is_ok() { "OK" } else { "FAILED" };
+        timings::record(
+            STEPS[step].split(':').next().unwrap_or_default(),
+            status,
+            started.elapsed(),
+        );
+        if let Err(e) = result {
+            ctx.output.error(&format!("Step {} failed.", step + 1));
+            ctx.output.info(&format!(
+                "Fix the problem, then resume with: inferadb schemas refactor resume {}",
+                path.display()
+            ));
+            return Err(e);
+        }
+
+        checkpoint.completed += 1;
+        save_checkpoint(path, &checkpoint)?;
+    }
+
+    if let Some(file) = &checkpoint.file {
+        std::fs::write(file, &target)?;
+        ctx.output.info(&format!("Updated {file}."));
+    }
+    // The run is complete, so there is nothing left to resume
+    let _ = std::fs::remove_file(path);
+
+    ctx.output.success("Refactoring complete.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tuple(resource: &str, relation: &str, subject: &str) -> Tuple {
+        Tuple {
+            resource: resource.to_string(),
+            relation: relation.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rewrite_tuple() {
+        let rename = Refactoring::RenameRelation {
+            entity: "Group".into(),
+            from: "member".into(),
+            to: "members".into(),
+        };
+        assert_eq!(
+            rewrite_tuple(&rename, &tuple("group:eng", "member", "user:alice")),
+            Some(tuple("group:eng", "members", "user:alice"))
+        );
+        assert_eq!(
+            rewrite_tuple(&rename, &tuple("doc:1", "viewer", "group:eng#member")),
+            Some(tuple("doc:1", "viewer", "group:eng#members"))
+        );
+        assert_eq!(rewrite_tuple(&rename, &tuple("team:a", "member", "user:alice")), None);
+
+        let rename = Refactoring::RenameEntity { from: "UserGroup".into(), to: "Team".into() };
+        assert_eq!(
+            rewrite_tuple(&rename, &tuple("user_group:eng", "member", "user_group:ops#member")),
+            Some(tuple("team:eng", "member", "team:ops#member"))
+        );
+        assert_eq!(rewrite_tuple(&rename, &tuple("doc:1", "viewer", "user:alice")), None);
+    }
+}
@@ -1,10 +1,29 @@
 //! Relationship management commands.
 
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+};
+
 use bon::builder;
-use inferadb::Relationship;
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use inferadb::{
+    Relationship,
+    control::{AuditAction, AuditEvent, SortOrder},
+};
+use serde::{Deserialize, Serialize};
 
-use crate::{client::Context, error::Result, output::Displayable};
+use super::identity::parse_time_spec;
+use crate::{
+    client::{CliClient, Context},
+    config::{Config, StateLock},
+    error::{Error, Result},
+    output::{
+        Displayable,
+        breakdown::{self, Phase},
+        dates,
+    },
+};
 
 #[derive(Debug, Clone, Serialize)]
 struct RelationshipRow {
@@ -21,61 +40,396 @@ impl Displayable for RelationshipRow {
     fn table_headers() -> Vec<&'static str> {
         vec!["RESOURCE", "RELATION", "SUBJECT"]
     }
+
+    fn primary_value(&self) -> String {
+        format!("{}#{}@{}", self.resource, self.relation, self.subject)
+    }
+}
+
+/// A relationship row with audit metadata, for `--wide` output.
+#[derive(Debug, Clone, Serialize)]
+struct WideRelationshipRow {
+    #[serde(flatten)]
+    row: RelationshipRow,
+    #[serde(flatten)]
+    metadata: RelationshipMetadata,
+}
+
+impl Displayable for WideRelationshipRow {
+    fn table_row(&self) -> Vec<String> {
+        let mut row = self.row.table_row();
+        row.extend(self.metadata.table_row());
+        row
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["RESOURCE", "RELATION", "SUBJECT", "CREATED", "CREATED BY", "SOURCE"]
+    }
+
+    fn primary_value(&self) -> String {
+        self.row.primary_value()
+    }
+}
+
+// ============================================================================
+// Audit metadata
+// ============================================================================
+
+/// Who wrote a relationship, when, and from where.
+///
+/// Relationships carry no metadata of their own, so this comes from the most
+/// recent `relationship.write` event for the tuple in the organization audit
+/// log. Fields are empty when no such event is found (e.g., writes older than
+/// the audit retention window).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelationshipMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    /// Client user agent, or the actor type when none was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl RelationshipMetadata {
+    fn from_event(event: &AuditEvent) -> Self {
+        Self {
+            created_at: Some(event.timestamp),
+            created_by: Some(event.actor.email.clone().unwrap_or_else(|| event.actor.id.clone())),
+            source: Some(
+                event
+                    .actor
+                    .user_agent
+                    .clone()
+                    .unwrap_or_else(|| event.actor.actor_type.to_string()),
+            ),
+        }
+    }
+
+    /// Table cells for the created, created-by, and source columns.
+    pub fn table_row(&self) -> Vec<String> {
+        vec![
+            self.created_at.map_or_else(|| "-".to_string(), dates::format_precise),
+            self.created_by.clone().unwrap_or_else(|| "-".to_string()),
+            self.source.clone().unwrap_or_else(|| "-".to_string()),
+        ]
+    }
+}
+
+/// Key identifying a relationship in a metadata map.
+pub fn metadata_key(resource: &str, relation: &str, subject: &str) -> String {
+    format!("{resource}#{relation}@{subject}")
+}
+
+/// The `(relation, subject)` a `relationship.write` event's details describe.
+fn written_tuple(details: &serde_json::Value) -> Option<(&str, &str)> {
+    let field = |key| details.get(key).and_then(serde_json::Value::as_str);
+    Some((field("relation")?, field("subject")?))
+}
+
+/// Look up audit metadata for every relationship on the given resources.
+///
+/// Issues one audit log query per resource and returns metadata keyed by
+/// [`metadata_key`], taken from the newest write of each tuple.
+pub async fn lookup_metadata(
+    client: &CliClient,
+    resources: BTreeSet<&str>,
+) -> Result<HashMap<String, RelationshipMetadata>> {
+    let audit = client.organization(client.org_id()).audit();
+    let mut metadata = HashMap::new();
+
+    for resource in resources {
+        let page = audit
+            .list()
+            .vault(client.vault_id())
+            .action(AuditAction::RelationshipWrite)
+            .resource(resource)
+            .sort(SortOrder::Descending)
+            .limit(100)
+            .await?;
+
+        for event in &page.items {
+            if let Some((relation, subject)) = event.details.as_ref().and_then(written_tuple) {
+                metadata
+                    .entry(metadata_key(resource, relation, subject))
+                    .or_insert_with(|| RelationshipMetadata::from_event(event));
+            }
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Filters on audit metadata, applied client-side.
+#[derive(Debug, Default)]
+struct MetadataFilter<'a> {
+    actor: Option<&'a str>,
+    after: Option<DateTime<Utc>>,
+    before: Option<DateTime<Utc>>,
+}
+
+impl MetadataFilter<'_> {
+    const fn is_active(&self) -> bool {
+        self.actor.is_some() || self.after.is_some() || self.before.is_some()
+    }
+
+    /// Whether the metadata satisfies every filter. Relationships without
+    /// metadata never match an active filter.
+    fn matches(&self, metadata: &RelationshipMetadata) -> bool {
+        let by_ok = self.actor.is_none_or(|actor| metadata.created_by.as_deref() == Some(actor));
+        let after_ok =
+            self.after.is_none_or(|after| metadata.created_at.is_some_and(|t| t >= after));
+        let before_ok =
+            self.before.is_none_or(|before| metadata.created_at.is_some_and(|t| t < before));
+        by_ok && after_ok && before_ok
+    }
+}
+
+/// Filters the list API cannot apply itself.
+///
+/// The list API only matches resource, relation, and subject exactly. A
+/// subject relation combined with `--subject` is folded into that subject
+/// (`group:eng` + `member` becomes `group:eng#member`); otherwise these are
+/// matched against each page returned.
+#[derive(Debug, Default)]
+struct ListFilter<'a> {
+    subject_relation: Option<&'a str>,
+    resource_prefix: Option<&'a str>,
+}
+
+impl ListFilter<'_> {
+    /// Subject to send to the list API, folding in the subject relation.
+    fn pushdown_subject(&self, subject: &str) -> String {
+        match self.subject_relation {
+            Some(rel) if !subject.contains('#') => format!("{subject}#{rel}"),
+            _ => subject.to_string(),
+        }
+    }
+
+    /// Whether any filter must be evaluated client-side.
+    const fn is_client_side(&self, subject: Option<&str>) -> bool {
+        self.resource_prefix.is_some() || (self.subject_relation.is_some() && subject.is_none())
+    }
+
+    fn matches(&self, row: &RelationshipRow) -> bool {
+        let subject_ok = self.subject_relation.is_none_or(|rel| {
+            row.subject.split_once('#').is_some_and(|(_, subject_rel)| subject_rel == rel)
+        });
+        let resource_ok =
+            self.resource_prefix.is_none_or(|prefix| row.resource.starts_with(prefix));
+        subject_ok && resource_ok
+    }
+}
+
+// ============================================================================
+// Cursor bookmarks
+// ============================================================================
+
+/// Saved cursors keyed by `org/vault`, then by bookmark name.
+type CursorStore = BTreeMap<String, BTreeMap<String, String>>;
+
+fn cursors_path() -> Result<PathBuf> {
+    Config::state_dir()
+        .map(|dir| dir.join("cursors.yaml"))
+        .ok_or_else(|| Error::config("Could not determine state directory"))
+}
+
+/// Load saved cursors, or an empty store if none exist.
+fn load_cursors() -> Result<CursorStore> {
+    let path = cursors_path()?;
+    if !path.exists() {
+        return Ok(CursorStore::new());
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    if contents.trim().is_empty() {
+        return Ok(CursorStore::new());
+    }
+    serde_yaml::from_str(&contents)
+        .map_err(|e| Error::config(format!("Invalid cursors file {}: {e}", path.display())))
+}
+
+fn save_cursors(store: &CursorStore) -> Result<()> {
+    let path = cursors_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_yaml::to_string(store)?)?;
+    Ok(())
+}
+
+/// Look up a saved cursor for a vault.
+fn find_cursor(store: &CursorStore, vault_key: &str, name: &str) -> Result<String> {
+    store.get(vault_key).and_then(|cursors| cursors.get(name)).cloned().ok_or_else(|| {
+        Error::invalid_arg(format!(
+            "No saved cursor '{name}' for this vault. Save one with --save-cursor {name}"
+        ))
+    })
+}
+
+/// Record the cursor to resume from, or drop the bookmark once the listing
+/// is exhausted.
+fn set_cursor(store: &mut CursorStore, vault_key: &str, name: &str, cursor: Option<&str>) {
+    if let Some(cursor) = cursor {
+        store
+            .entry(vault_key.to_string())
+            .or_default()
+            .insert(name.to_string(), cursor.to_string());
+    } else if let Some(cursors) = store.get_mut(vault_key) {
+        cursors.remove(name);
+        if cursors.is_empty() {
+            store.remove(vault_key);
+        }
+    }
 }
 
 /// List relationships.
+///
+/// With `--subject-relation` (without `--subject`) or `--resource-prefix`,
+/// pages are scanned from `cursor` until `limit` matches are found.
+///
+/// `from_cursor` resumes from a bookmark saved in the state directory, and
+/// `save_cursor` stores the next page's cursor under a name for later.
+///
+/// With `all`, every page is listed, `limit` rows at a time. Rows are written
+/// as each page arrives rather than after the last one.
+///
+/// `wide` adds audit metadata columns; filtering on that metadata
+/// (`created_by`, `created_after`, `created_before`) also scans pages.
 #[builder]
 pub async fn relationships_list(
     ctx: &Context,
     resource: Option<&str>,
     subject: Option<&str>,
     relation: Option<&str>,
+    subject_relation: Option<&str>,
+    resource_prefix: Option<&str>,
     #[builder(default = 100)] limit: u32,
+    #[builder(default)] all: bool,
     cursor: Option<&str>,
+    from_cursor: Option<&str>,
+    save_cursor: Option<&str>,
+    #[builder(default)] wide: bool,
+    created_by: Option<&str>,
+    created_after: Option<&str>,
+    created_before: Option<&str>,
 ) -> Result<()> {
     let client = ctx.client().await?;
     let vault = client.vault();
+    let vault_key = format!("{}/{}", client.org_id(), client.vault_id());
 
-    // Build the list query with filters
-    let mut query = vault.relationships().list();
+    let cursor = match from_cursor {
+        Some(name) => Some(find_cursor(&load_cursors()?, &vault_key, name)?),
+        None => cursor.map(str::to_string),
+    };
 
-    if let Some(r) = resource {
-        query = query.resource(r);
-    }
-    if let Some(s) = subject {
-        query = query.subject(s);
-    }
-    if let Some(rel) = relation {
-        query = query.relation(rel);
-    }
+    let filter = ListFilter { subject_relation, resource_prefix };
+    let subject = subject.map(|s| filter.pushdown_subject(s));
+    let metadata_filter = MetadataFilter {
+        actor: created_by,
+        after: created_after.map(parse_time_spec),
+        before: created_before.map(parse_time_spec),
+    };
+    let with_metadata = wide || metadata_filter.is_active();
+    let client_side = filter.is_client_side(subject.as_deref()) || metadata_filter.is_active();
+
+    let mut wide_rows = ctx.output.stream::<WideRelationshipRow>();
+    let mut rows = ctx.output.stream::<RelationshipRow>();
+    let mut cursor = cursor;
+    loop {
+        // Build the list query with filters
+        let mut query = vault.relationships().list();
+
+        if let Some(r) = resource {
+            query = query.resource(r);
+        }
+        if let Some(s) = &subject {
+            query = query.subject(s);
+        }
+        if let Some(rel) = relation {
+            query = query.relation(rel);
+        }
+
+        query = query.limit(limit as usize);
+
+        if let Some(c) = &cursor {
+            query = query.cursor(c);
+        }
+
+        let response = breakdown::timed(Phase::Api, "list relationships", query).await?;
 
-    query = query.limit(limit as usize);
+        let page: Vec<RelationshipRow> = response
+            .relationships
+            .iter()
+            .map(|rel| RelationshipRow {
+                resource: rel.resource().to_string(),
+                relation: rel.relation().to_string(),
+                subject: rel.subject().to_string(),
+            })
+            .filter(|row| filter.matches(row))
+            .collect();
 
-    if let Some(c) = cursor {
-        query = query.cursor(c);
+        let metadata = if with_metadata {
+            lookup_metadata(&client, page.iter().map(|row| row.resource.as_str()).collect()).await?
+        } else {
+            HashMap::new()
+        };
+
+        let page: Vec<WideRelationshipRow> = page
+            .into_iter()
+            .map(|row| WideRelationshipRow {
+                metadata: metadata
+                    .get(&metadata_key(&row.resource, &row.relation, &row.subject))
+                    .cloned()
+                    .unwrap_or_default(),
+                row,
+            })
+            .filter(|row| metadata_filter.matches(&row.metadata))
+            .collect();
+        if wide {
+            wide_rows.write(&page)?;
+        } else {
+            rows.write(&page.into_iter().map(|row| row.row).collect::<Vec<_>>())?;
+        }
+
+        cursor = response.next_cursor;
+        let count = wide_rows.count() + rows.count();
+        if cursor.is_none() || !(all || client_side && count < limit as usize) {
+            break;
+        }
     }
 
-    let response = query.await?;
+    if let Some(name) = save_cursor {
+        let _lock = StateLock::acquire(&cursors_path()?, "saved cursors")?;
+        let mut store = load_cursors()?;
+        set_cursor(&mut store, &vault_key, name, cursor.as_deref());
+        save_cursors(&store)?;
+    }
 
-    if response.relationships.is_empty() {
+    let count = wide_rows.count() + rows.count();
+    if count == 0 {
         ctx.output.info("No relationships found.");
         return Ok(());
     }
+    if wide {
+        wide_rows.finish()?;
+    } else {
+        rows.finish()?;
+    }
 
-    let rows: Vec<RelationshipRow> = response
-        .relationships
-        .iter()
-        .map(|rel| RelationshipRow {
-            resource: rel.resource().to_string(),
-            relation: rel.relation().to_string(),
-            subject: rel.subject().to_string(),
-        })
-        .collect();
-
-    ctx.output.table(&rows)?;
-
-    if !ctx.output.is_quiet() && rows.len() == limit as usize {
-        ctx.output.info(&format!("\nShowing {limit} results. Use --cursor for pagination."));
+    if let Some(name) = save_cursor {
+        if cursor.is_some() {
+            ctx.output.info(&format!(
+                "\nShowing {count} results. Saved cursor '{name}'; resume with --from-cursor {name}."
+            ));
+        } else {
+            ctx.output.info(&format!("\nReached the end; removed saved cursor '{name}'."));
+        }
+    } else if !ctx.output.is_quiet()
+        && let Some(next) = &cursor
+        && (client_side || count == limit as usize)
+    {
+        ctx.output.info(&format!("\nShowing {count} results. Use --cursor {next} for more."));
     }
 
     Ok(())
@@ -96,7 +450,8 @@ pub async fn relationships_add(
     // but CLI uses "subject relation resource" order for readability
     let rel = Relationship::new(resource, relation, subject);
 
-    let result = vault.relationships().write(rel).await;
+    let result =
+        breakdown::timed(Phase::Api, "write relationship", vault.relationships().write(rel)).await;
 
     match result {
         Ok(_) => {
@@ -128,7 +483,9 @@ pub async fn relationships_delete(
 
     let rel = Relationship::new(resource, relation, subject);
 
-    let result = vault.relationships().delete(rel).await;
+    let result =
+        breakdown::timed(Phase::Api, "delete relationship", vault.relationships().delete(rel))
+            .await;
 
     match result {
         Ok(()) => {
@@ -156,3 +513,82 @@ pub async fn history(
     ctx.output.info("Relationship history not yet implemented.");
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn row(resource: &str, relation: &str, subject: &str) -> RelationshipRow {
+        RelationshipRow {
+            resource: resource.to_string(),
+            relation: relation.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_subject_relation_folds_into_subject() {
+        let filter = ListFilter { subject_relation: Some("member"), ..ListFilter::default() };
+        assert_eq!(filter.pushdown_subject("group:eng"), "group:eng#member");
+        assert_eq!(filter.pushdown_subject("group:eng#admin"), "group:eng#admin");
+        assert!(!filter.is_client_side(Some("group:eng#member")));
+        assert!(filter.is_client_side(None));
+    }
+
+    #[test]
+    fn test_list_filter_matches() {
+        let filter =
+            ListFilter { subject_relation: Some("member"), resource_prefix: Some("document:") };
+        assert!(filter.matches(&row("document:readme", "viewer", "group:eng#member")));
+        assert!(!filter.matches(&row("document:readme", "viewer", "user:alice")));
+        assert!(!filter.matches(&row("document:readme", "viewer", "group:eng#admin")));
+        assert!(!filter.matches(&row("folder:root", "viewer", "group:eng#member")));
+        assert!(ListFilter::default().matches(&row("folder:root", "viewer", "user:alice")));
+    }
+
+    #[test]
+    fn test_written_tuple() {
+        let details = serde_json::json!({"relation": "viewer", "subject": "user:alice"});
+        assert_eq!(written_tuple(&details), Some(("viewer", "user:alice")));
+        assert_eq!(written_tuple(&serde_json::json!({"relation": "viewer"})), None);
+    }
+
+    #[test]
+    fn test_metadata_filter() {
+        let at = |s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let metadata = RelationshipMetadata {
+            created_at: Some(at("2025-03-01T12:00:00Z")),
+            created_by: Some("alice@example.com".to_string()),
+            source: Some("user".to_string()),
+        };
+
+        let filter = MetadataFilter {
+            actor: Some("alice@example.com"),
+            after: Some(at("2025-01-01T00:00:00Z")),
+            before: Some(at("2025-06-01T00:00:00Z")),
+        };
+        assert!(filter.matches(&metadata));
+        assert!(!filter.matches(&RelationshipMetadata::default()));
+
+        let filter =
+            MetadataFilter { after: Some(at("2025-03-02T00:00:00Z")), ..MetadataFilter::default() };
+        assert!(!filter.matches(&metadata));
+        assert!(MetadataFilter::default().matches(&RelationshipMetadata::default()));
+    }
+
+    #[test]
+    fn test_cursor_bookmarks() {
+        let mut store = CursorStore::new();
+        set_cursor(&mut store, "org/vault", "review", Some("abc"));
+        assert_eq!(find_cursor(&store, "org/vault", "review").unwrap(), "abc");
+        assert!(find_cursor(&store, "org/other", "review").is_err());
+
+        set_cursor(&mut store, "org/vault", "review", Some("def"));
+        assert_eq!(find_cursor(&store, "org/vault", "review").unwrap(), "def");
+
+        set_cursor(&mut store, "org/vault", "review", None);
+        assert!(find_cursor(&store, "org/vault", "review").is_err());
+        assert!(store.is_empty());
+    }
+}
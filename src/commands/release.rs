@@ -0,0 +1,408 @@
+//! Release packaging commands.
+//!
+//! Package manager descriptors are rendered from the binary itself: the
+//! version, description, and license come from the crate metadata, the
+//! download URLs from [`RELEASE_TARGETS`] (which mirrors the release workflow's
+//! build matrix), and the shell completions from the live command tree. A
+//! renamed subcommand or new target is picked up on the next release without
+//! editing any formula by hand.
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde_json::json;
+
+use super::completion_script;
+use crate::{
+    cli::Shell,
+    client::Context,
+    error::{Error, Result},
+    input,
+};
+
+/// Binary name inside every release archive.
+const BINARY: &str = "inferadb";
+
+/// Placeholder written when no checksum is available for an asset.
+const MISSING_CHECKSUM: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// A binary built and uploaded by the release workflow.
+#[derive(Debug, Clone, Copy)]
+struct ReleaseTarget {
+    /// Operating system, as used in asset names.
+    os: &'static str,
+    /// CPU architecture, as used in asset names.
+    arch: &'static str,
+}
+
+impl ReleaseTarget {
+    /// Archive file name, e.g. `inferadb-linux-x86_64.tar.gz`.
+    fn archive(self) -> String {
+        format!("{BINARY}-{}-{}.tar.gz", self.os, self.arch)
+    }
+
+    /// Debian/RPM architecture name used by nfpm.
+    fn nfpm_arch(self) -> &'static str {
+        if self.arch == "aarch64" { "arm64" } else { "amd64" }
+    }
+}
+
+/// Targets in `.github/workflows/release.yml`.
+const RELEASE_TARGETS: &[ReleaseTarget] = &[
+    ReleaseTarget { os: "linux", arch: "x86_64" },
+    ReleaseTarget { os: "linux", arch: "aarch64" },
+    ReleaseTarget { os: "macos", arch: "x86_64" },
+    ReleaseTarget { os: "macos", arch: "aarch64" },
+    ReleaseTarget { os: "windows", arch: "x86_64" },
+];
+
+/// Completion scripts written alongside the manifests: shell, file name, and
+/// deb and rpm install paths (empty when not packaged).
+const COMPLETIONS: &[(Shell, &str, &str, &str)] = &[
+    (
+        Shell::Bash,
+        "inferadb.bash",
+        "/usr/share/bash-completion/completions/inferadb",
+        "/usr/share/bash-completion/completions/inferadb",
+    ),
+    (
+        Shell::Zsh,
+        "_inferadb",
+        "/usr/share/zsh/vendor-completions/_inferadb",
+        "/usr/share/zsh/site-functions/_inferadb",
+    ),
+    (
+        Shell::Fish,
+        "inferadb.fish",
+        "/usr/share/fish/vendor_completions.d/inferadb.fish",
+        "/usr/share/fish/vendor_completions.d/inferadb.fish",
+    ),
+    (Shell::PowerShell, "_inferadb.ps1", "", ""),
+];
+
+/// Release metadata shared by every descriptor.
+#[derive(Debug, Clone)]
+struct Release {
+    version: String,
+    /// SHA-256 checksums keyed by archive file name.
+    checksums: BTreeMap<String, String>,
+}
+
+impl Release {
+    fn url(&self, target: ReleaseTarget) -> String {
+        format!(
+            "{}/releases/download/v{}/{}",
+            env!("CARGO_PKG_REPOSITORY"),
+            self.version,
+            target.archive()
+        )
+    }
+
+    fn sha256(&self, target: ReleaseTarget) -> &str {
+        self.checksums.get(&target.archive()).map_or(MISSING_CHECKSUM, String::as_str)
+    }
+
+    fn targets(os: &str) -> impl Iterator<Item = ReleaseTarget> + '_ {
+        RELEASE_TARGETS.iter().copied().filter(move |t| t.os == os)
+    }
+}
+
+/// Short description without the product name, as package managers expect.
+fn description() -> String {
+    let full = env!("CARGO_PKG_DESCRIPTION");
+    let summary = full.rsplit('—').next().unwrap_or(full).trim();
+    let mut chars = summary.chars();
+    chars.next().map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+}
+
+/// Parse `sha256sum` output (`<hash>  <file>` or `<hash> *<file>`).
+fn parse_checksums(content: &str) -> Result<BTreeMap<String, String>> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (hash, file) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| Error::parse(format!("Invalid checksum line: {line}")))?;
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(Error::parse(format!("Invalid SHA-256 checksum: {hash}")));
+            }
+            let file = file.trim_start().trim_start_matches('*');
+            let name = Path::new(file).file_name().map_or(file, |n| n.to_str().unwrap_or(file));
+            Ok((name.to_string(), hash.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// Homebrew formula installing the prebuilt binary for each platform.
+fn homebrew_formula(release: &Release) -> String {
+    let platform = |os: &str, block: &str| {
+        let mut out = format!("  {block} do\n");
+        for target in Release::targets(os) {
+            let cpu = if target.arch == "aarch64" { "on_arm" } else { "on_intel" };
+            out.push_str(&format!(
+                "    {cpu} do\n      url \"{}\"\n      sha256 \"{}\"\n    end\n",
+                release.url(target),
+                release.sha256(target)
+            ));
+        }
+        out.push_str("  end\n");
+        out
+    };
+
+    format!(
+        r##"class Inferadb < Formula
+  desc "{desc}"
+  homepage "{homepage}"
+  version "{version}"
+  license any_of: ["MIT", "Apache-2.0"]
+
+{macos}
+{linux}
+  def install
+    bin.install "{BINARY}"
+    generate_completions_from_executable(bin/"{BINARY}", "completion")
+  end
+
+  test do
+    assert_match version.to_s, shell_output("#{{bin}}/{BINARY} version")
+  end
+end
+"##,
+        desc = description(),
+        homepage = env!("CARGO_PKG_REPOSITORY"),
+        version = release.version,
+        macos = platform("macos", "on_macos"),
+        linux = platform("linux", "on_linux"),
+    )
+}
+
+/// Scoop manifest for the Windows binary.
+fn scoop_manifest(release: &Release) -> Result<String> {
+    let architecture = |url: &dyn Fn(ReleaseTarget) -> serde_json::Value| {
+        Release::targets("windows")
+            .map(|target| {
+                let key = if target.arch == "aarch64" { "arm64" } else { "64bit" };
+                (key.to_string(), url(target))
+            })
+            .collect::<serde_json::Map<_, _>>()
+    };
+
+    let manifest = json!({
+        "version": release.version,
+        "description": description(),
+        "homepage": env!("CARGO_PKG_REPOSITORY"),
+        "license": env!("CARGO_PKG_LICENSE").replace(" OR ", "|"),
+        "architecture": architecture(&|t| json!({
+            "url": release.url(t),
+            "hash": release.sha256(t),
+        })),
+        "bin": format!("{BINARY}.exe"),
+        "checkver": "github",
+        "autoupdate": {
+            "architecture": architecture(&|t| json!({
+                "url": format!(
+                    "{}/releases/download/v$version/{}",
+                    env!("CARGO_PKG_REPOSITORY"),
+                    t.archive()
+                ),
+            })),
+        },
+    });
+    Ok(serde_json::to_string_pretty(&manifest)? + "\n")
+}
+
+/// nfpm configuration producing deb and rpm packages for one Linux target.
+///
+/// Paths are relative to the output directory: the binary is expected at
+/// `<os>-<arch>/inferadb` and completions under `completions/`.
+fn nfpm_config(release: &Release, target: ReleaseTarget) -> Result<String> {
+    let mut contents = vec![json!({
+        "src": format!("./{}-{}/{BINARY}", target.os, target.arch),
+        "dst": format!("/usr/bin/{BINARY}"),
+        "file_info": { "mode": 0o755 },
+    })];
+    for (_, file, deb, rpm) in COMPLETIONS.iter().filter(|(_, _, deb, _)| !deb.is_empty()) {
+        let src = format!("./completions/{file}");
+        if deb == rpm {
+            contents.push(json!({ "src": src, "dst": deb }));
+        } else {
+            contents.push(json!({ "src": src, "dst": deb, "packager": "deb" }));
+            contents.push(json!({ "src": src, "dst": rpm, "packager": "rpm" }));
+        }
+    }
+
+    let config = json!({
+        "name": BINARY,
+        "arch": target.nfpm_arch(),
+        "platform": "linux",
+        "version": release.version,
+        "section": "utils",
+        "priority": "optional",
+        "maintainer": env!("CARGO_PKG_AUTHORS"),
+        "description": description(),
+        "homepage": env!("CARGO_PKG_REPOSITORY"),
+        "license": env!("CARGO_PKG_LICENSE"),
+        "contents": contents,
+    });
+    Ok(serde_yaml::to_string(&config)?)
+}
+
+/// Render package manager descriptors and completions into `output`.
+pub async fn package_manifests(
+    ctx: &Context,
+    output: &str,
+    version: Option<&str>,
+    checksums: Option<&str>,
+) -> Result<()> {
+    let checksums = match checksums {
+        Some(path) => parse_checksums(
+            &input::read_to_string(path)
+                .map_err(|e| Error::other(format!("Failed to read checksums file {path}: {e}")))?,
+        )?,
+        None => BTreeMap::new(),
+    };
+    let release = Release {
+        version: version.unwrap_or(env!("CARGO_PKG_VERSION")).trim_start_matches('v').to_string(),
+        checksums,
+    };
+
+    let missing: Vec<String> = RELEASE_TARGETS
+        .iter()
+        .map(|t| t.archive())
+        .filter(|archive| !release.checksums.contains_key(archive))
+        .collect();
+    if !missing.is_empty() {
+        ctx.output
+            .warn(&format!("No checksum for {}; writing placeholder digests.", missing.join(", ")));
+    }
+
+    let dir = Path::new(output);
+    std::fs::create_dir_all(dir.join("completions"))?;
+    let mut written = Vec::new();
+    let mut write = |name: String, content: &[u8]| -> Result<()> {
+        std::fs::write(dir.join(&name), content)?;
+        written.push(name);
+        Ok(())
+    };
+
+    write(format!("{BINARY}.rb"), homebrew_formula(&release).as_bytes())?;
+    write(format!("{BINARY}.json"), scoop_manifest(&release)?.as_bytes())?;
+    for target in Release::targets("linux") {
+        write(
+            format!("nfpm-{}.yaml", target.nfpm_arch()),
+            nfpm_config(&release, target)?.as_bytes(),
+        )?;
+    }
+    for (shell, file, ..) in COMPLETIONS {
+        write(format!("completions/{file}"), &completion_script(*shell))?;
+    }
+
+    if ctx.output.format() != crate::output::OutputFormat::Table {
+        return ctx.output.value(&json!({
+            "version": release.version,
+            "directory": output,
+            "files": written,
+        }));
+    }
+
+    ctx.output
+        .success(&format!("Wrote package manifests for {BINARY} {} to {output}", release.version));
+    for name in &written {
+        ctx.output.info(&format!("  {name}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn release() -> Release {
+        let checksums = RELEASE_TARGETS
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.archive(), format!("{i}").repeat(64)))
+            .collect();
+        Release { version: "1.2.3".to_string(), checksums }
+    }
+
+    #[test]
+    fn test_targets_match_release_workflow() {
+        let workflow = include_str!("../../.github/workflows/release.yml");
+        for target in RELEASE_TARGETS {
+            let asset = target.archive().trim_end_matches(".tar.gz").to_string();
+            assert!(workflow.contains(&format!("asset_name: {asset}")), "{asset}");
+        }
+    }
+
+    #[test]
+    fn test_parse_checksums() {
+        let hash = "A".repeat(64);
+        let parsed =
+            parse_checksums(&format!("{hash}  dist/inferadb-linux-x86_64.tar.gz\n{hash} *b\n"))
+                .unwrap();
+        assert_eq!(parsed["inferadb-linux-x86_64.tar.gz"], "a".repeat(64));
+        assert!(parsed.contains_key("b"));
+        assert!(parse_checksums("abc  file").is_err());
+    }
+
+    #[test]
+    fn test_homebrew_formula() {
+        let formula = homebrew_formula(&release());
+        assert!(formula.contains("  version \"1.2.3\"\n"));
+        assert!(formula.contains(
+            "url \"https://github.com/inferadb/cli/releases/download/v1.2.3/inferadb-macos-aarch64.tar.gz\""
+        ));
+        assert!(formula.contains(&format!("sha256 \"{}\"", "3".repeat(64))));
+        assert!(
+            formula
+                .contains("generate_completions_from_executable(bin/\"inferadb\", \"completion\")")
+        );
+        assert!(!formula.contains("windows"));
+        assert!(formula.starts_with("class Inferadb < Formula\n  desc \"Debug authorization"));
+    }
+
+    #[test]
+    fn test_scoop_manifest() {
+        let manifest: serde_json::Value =
+            serde_json::from_str(&scoop_manifest(&release()).unwrap()).unwrap();
+        assert_eq!(manifest["license"], "MIT|Apache-2.0");
+        assert_eq!(manifest["architecture"]["64bit"]["hash"], "4".repeat(64));
+        assert_eq!(
+            manifest["autoupdate"]["architecture"]["64bit"]["url"],
+            "https://github.com/inferadb/cli/releases/download/v$version/inferadb-windows-x86_64.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_nfpm_config() {
+        let target = RELEASE_TARGETS[1];
+        let config: serde_yaml::Value =
+            serde_yaml::from_str(&nfpm_config(&release(), target).unwrap()).unwrap();
+        assert_eq!(config["arch"], "arm64");
+        assert_eq!(config["contents"][0]["src"], "./linux-aarch64/inferadb");
+        let zsh: Vec<_> = config["contents"]
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .filter(|c| c["src"] == "./completions/_inferadb")
+            .map(|c| (c["packager"].as_str().unwrap(), c["dst"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            zsh,
+            vec![
+                ("deb", "/usr/share/zsh/vendor-completions/_inferadb"),
+                ("rpm", "/usr/share/zsh/site-functions/_inferadb"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_completions_cover_every_shell() {
+        for (shell, file, ..) in COMPLETIONS {
+            let script = completion_script(*shell);
+            assert!(!script.is_empty(), "{file}");
+        }
+    }
+}
@@ -0,0 +1,401 @@
+//! Bulk rewriting of relationship ID prefixes.
+//!
+//! `relationships retag --resource-prefix tenantA/ --to tenantB/` moves every
+//! relationship whose resource ID starts with the prefix to the rewritten ID,
+//! by adding the new relationship and then deleting the old one, a batch at a
+//! time. Each batch is recorded in a journal under the state directory before
+//! it is applied, so `relationships retag --undo <journal>` can reverse a run,
+//! including one that stopped partway.
+
+use std::{
+    io::{BufRead, Write as _},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use bon::builder;
+use inferadb::{ErrorKind, Relationship, VaultClient};
+use serde::{Deserialize, Serialize};
+
+use super::bulk::with_retries;
+use crate::{
+    client::Context,
+    config::{Config, StateLock},
+    error::{Error, Result},
+    input,
+    output::{Displayable, timings},
+    tui,
+};
+
+/// Retries for each request on transient errors.
+const RETRIES: u32 = 3;
+
+/// Moves listed in the dry-run preview.
+const MAX_PREVIEW: usize = 20;
+
+/// A relationship as stored in the journal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct Tuple {
+    pub(super) resource: String,
+    pub(super) relation: String,
+    pub(super) subject: String,
+}
+
+impl Tuple {
+    fn relationship(&self) -> Relationship<'_> {
+        Relationship::new(&self.resource, &self.relation, &self.subject)
+    }
+}
+
+/// One relationship rewrite.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Move {
+    old: Tuple,
+    new: Tuple,
+}
+
+/// First journal line: the vault the moves were applied to.
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalHeader {
+    vault: String,
+    resource_prefix: String,
+    to: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MoveRow {
+    from: String,
+    to: String,
+}
+
+impl Displayable for MoveRow {
+    fn table_row(&self) -> Vec<String> {
+        vec![self.from.clone(), self.to.clone()]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["FROM", "TO"]
+    }
+}
+
+/// Rewrite an object's ID prefix, returning `None` when it does not match.
+///
+/// A prefix containing `:` matches the whole object (`document:tenantA/`);
+/// otherwise it matches the ID after the type (`tenantA/`), for every type.
+fn rewrite(object: &str, prefix: &str, to: &str) -> Option<String> {
+    if prefix.contains(':') {
+        return object.strip_prefix(prefix).map(|rest| format!("{to}{rest}"));
+    }
+    let (type_name, id) = object.split_once(':')?;
+    id.strip_prefix(prefix).map(|rest| format!("{type_name}:{to}{rest}"))
+}
+
+/// Plan the moves for a set of relationships.
+fn plan(rows: Vec<Tuple>, prefix: &str, to: &str, include_subjects: bool) -> Vec<Move> {
+    rows.into_iter()
+        .filter_map(|old| {
+            let resource = rewrite(&old.resource, prefix, to);
+            let subject = include_subjects.then(|| rewrite(&old.subject, prefix, to)).flatten();
+            if resource.is_none() && subject.is_none() {
+                return None;
+            }
+            let new = Tuple {
+                resource: resource.unwrap_or_else(|| old.resource.clone()),
+                relation: old.relation.clone(),
+                subject: subject.unwrap_or_else(|| old.subject.clone()),
+            };
+            Some(Move { old, new })
+        })
+        .collect()
+}
+
+/// Read every relationship in the vault.
+pub(super) async fn list_all(vault: &VaultClient) -> Result<Vec<Tuple>> {
+    let mut rows = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut request = vault.relationships().list().limit(1000);
+        if let Some(c) = &cursor {
+            request = request.cursor(c);
+        }
+        let page = request.await?;
+        rows.extend(page.relationships.iter().map(|rel| Tuple {
+            resource: rel.resource().to_string(),
+            relation: rel.relation().to_string(),
+            subject: rel.subject().to_string(),
+        }));
+
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            return Ok(rows);
+        }
+    }
+}
+
+/// Write relationships, treating ones that already exist as written.
+pub(super) async fn write_all(vault: &VaultClient, tuples: &[&Tuple]) -> Result<()> {
+    let batch = with_retries(RETRIES, || {
+        vault.relationships().write_batch(tuples.iter().map(|t| t.relationship()))
+    })
+    .await;
+    match batch {
+        Ok(()) => Ok(()),
+        Err(e) if e.is_retriable() => Err(e.into()),
+        // Rows that already exist (e.g., from an interrupted run) fail the
+        // whole batch, so fall back to writing one at a time
+        Err(_) => {
+            for tuple in tuples {
+                match with_retries(RETRIES, || vault.relationships().write(tuple.relationship()))
+                    .await
+                {
+                    Ok(()) => {},
+                    Err(e) if e.kind() == ErrorKind::Conflict => {},
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            Ok(())
+        },
+    }
+}
+
+/// Delete relationships, treating ones that no longer exist as deleted.
+pub(super) async fn delete_all(vault: &VaultClient, tuples: &[&Tuple]) -> Result<()> {
+    for tuple in tuples {
+        match with_retries(RETRIES, || vault.relationships().delete(tuple.relationship())).await {
+            Ok(()) => {},
+            Err(e) if e.kind() == ErrorKind::NotFound => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+/// Journal file for a new run.
+fn journal_path() -> Result<PathBuf> {
+    let dir = Config::state_dir()
+        .map(|dir| dir.join("retag"))
+        .ok_or_else(|| Error::config("Could not determine state directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}.ndjson", chrono::Utc::now().format("%Y%m%dT%H%M%SZ"))))
+}
+
+/// Read a journal: its header and every recorded move.
+fn read_journal(path: &str) -> Result<(JournalHeader, Vec<Move>)> {
+    let mut lines = input::open(path)
+        .map_err(|e| Error::invalid_arg(format!("Cannot open journal {path}: {e}")))?
+        .lines();
+    let header: JournalHeader = match lines.next() {
+        Some(line) => serde_json::from_str(&line?)
+            .map_err(|e| Error::parse(format!("{path}: invalid journal header: {e}")))?,
+        None => return Err(Error::parse(format!("{path}: journal is empty"))),
+    };
+    let mut moves = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        moves.push(
+            serde_json::from_str(&line)
+                .map_err(|e| Error::parse(format!("{path}: line {}: {e}", index + 2)))?,
+        );
+    }
+    Ok((header, moves))
+}
+
+/// Rewrite resource ID prefixes (and, with `include_subjects`, subject ID
+/// prefixes) across the vault.
+#[builder]
+pub async fn retag(
+    ctx: &Context,
+    resource_prefix: &str,
+    to: &str,
+    #[builder(default)] include_subjects: bool,
+    #[builder(default = 100)] batch_size: usize,
+    #[builder(default)] dry_run: bool,
+) -> Result<()> {
+    if resource_prefix.is_empty() {
+        return Err(Error::invalid_arg("--resource-prefix cannot be empty"));
+    }
+
+    let client = ctx.client().await?;
+    let vault = client.vault();
+
+    let started = Instant::now();
+    let rows = tui::spin("Reading relationships...", list_all(&vault)).await?;
+    timings::record("Read relationships", "OK", started.elapsed());
+
+    let moves = plan(rows, resource_prefix, to, include_subjects);
+    if moves.is_empty() {
+        ctx.output.info(&format!("No relationships match '{resource_prefix}'."));
+        return Ok(());
+    }
+
+    if dry_run {
+        let preview: Vec<MoveRow> = moves
+            .iter()
+            .map(|m| MoveRow {
+                from: format!("{} {} {}", m.old.subject, m.old.relation, m.old.resource),
+                to: format!("{} {} {}", m.new.subject, m.new.relation, m.new.resource),
+            })
+            .collect();
+        if ctx.output.format() == crate::output::OutputFormat::Table {
+            ctx.output.table(&preview[..preview.len().min(MAX_PREVIEW)])?;
+            if preview.len() > MAX_PREVIEW {
+                ctx.output.info(&format!("... and {} more", preview.len() - MAX_PREVIEW));
+            }
+            ctx.output.warn(&format!(
+                "Dry run: {} relationships would be rewritten; no changes made.",
+                moves.len()
+            ));
+        } else {
+            ctx.output.value(&preview)?;
+        }
+        return Ok(());
+    }
+
+    if !ctx.confirm(&format!(
+        "Rewrite {} relationships from '{resource_prefix}' to '{to}'?",
+        moves.len()
+    ))? {
+        ctx.output.info("Cancelled.");
+        return Ok(());
+    }
+
+    let path = journal_path()?;
+    let _lock = StateLock::acquire(&path, "this retag journal")?;
+    let mut journal = std::fs::File::create(&path)?;
+    let header = JournalHeader {
+        vault: format!("{}/{}", client.org_id(), client.vault_id()),
+        resource_prefix: resource_prefix.to_string(),
+        to: to.to_string(),
+    };
+    writeln!(journal, "{}", serde_json::to_string(&header)?)?;
+
+    let started = Instant::now();
+    let total = moves.len();
+    let mut done = 0;
+    for batch in moves.chunks(batch_size) {
+        // Record the batch before touching the vault, so undo covers it even if
+        // the run stops partway
+        for m in batch {
+            writeln!(journal, "{}", serde_json::to_string(m)?)?;
+        }
+        journal.sync_data()?;
+
+        let result = async {
+            write_all(&vault, &batch.iter().map(|m| &m.new).collect::<Vec<_>>()).await?;
+            delete_all(&vault, &batch.iter().map(|m| &m.old).collect::<Vec<_>>()).await
+        }
+        .await;
+        if let Err(e) = result {
+            timings::record("Rewrote relationships", "FAILED", started.elapsed());
+            ctx.output.error(&format!("Stopped after {done} of {total} relationships."));
+            ctx.output.info(&format!(
+                "Undo with: inferadb relationships retag --undo {}",
+                path.display()
+            ));
+            return Err(e);
+        }
+        done += batch.len();
+        ctx.output.info(&format!("Rewrote {done} of {total} relationships."));
+    }
+    timings::record("Rewrote relationships", "OK", started.elapsed());
+
+    ctx.output.success(&format!("Rewrote {total} relationships."));
+    ctx.output.info(&format!("Journal: {}", path.display()));
+    Ok(())
+}
+
+/// Reverse a retag run recorded in a journal.
+pub async fn undo(ctx: &Context, journal: &str, batch_size: usize) -> Result<()> {
+    // Wait for the run writing the journal, and never undo it twice at once
+    let _lock = (journal != input::STDIN)
+        .then(|| StateLock::acquire(Path::new(journal), "this retag journal"))
+        .transpose()?;
+    let (header, moves) = read_journal(journal)?;
+
+    let client = ctx.client().await?;
+    let vault = client.vault();
+    let vault_key = format!("{}/{}", client.org_id(), client.vault_id());
+    if header.vault != vault_key {
+        return Err(Error::invalid_arg(format!(
+            "Journal {journal} was recorded for vault {}, not {vault_key}",
+            header.vault
+        )));
+    }
+
+    if moves.is_empty() {
+        ctx.output.info("Journal has no changes to undo.");
+        return Ok(());
+    }
+
+    if !ctx.confirm(&format!(
+        "Restore {} relationships from '{}' back to '{}'?",
+        moves.len(),
+        header.to,
+        header.resource_prefix
+    ))? {
+        ctx.output.info("Cancelled.");
+        return Ok(());
+    }
+
+    let started = Instant::now();
+    for batch in moves.chunks(batch_size) {
+        write_all(&vault, &batch.iter().map(|m| &m.old).collect::<Vec<_>>()).await?;
+        delete_all(&vault, &batch.iter().map(|m| &m.new).collect::<Vec<_>>()).await?;
+    }
+    timings::record("Restored relationships", "OK", started.elapsed());
+
+    ctx.output.success(&format!("Restored {} relationships.", moves.len()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tuple(resource: &str, relation: &str, subject: &str) -> Tuple {
+        Tuple {
+            resource: resource.to_string(),
+            relation: relation.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rewrite() {
+        assert_eq!(
+            rewrite("document:tenantA/1", "tenantA/", "tenantB/").as_deref(),
+            Some("document:tenantB/1")
+        );
+        assert_eq!(
+            rewrite("group:tenantA/eng#member", "tenantA/", "tenantB/").as_deref(),
+            Some("group:tenantB/eng#member")
+        );
+        assert_eq!(rewrite("folder:tenantA/x", "document:tenantA/", "document:b/"), None);
+        assert_eq!(
+            rewrite("document:tenantA/1", "document:tenantA/", "document:").as_deref(),
+            Some("document:1")
+        );
+        assert_eq!(rewrite("user:*", "tenantA/", "tenantB/"), None);
+    }
+
+    #[test]
+    fn test_plan() {
+        let rows = vec![
+            tuple("document:tenantA/1", "viewer", "group:tenantA/eng#member"),
+            tuple("document:other/2", "viewer", "user:alice"),
+            tuple("document:other/3", "viewer", "group:tenantA/eng#member"),
+        ];
+
+        let moves = plan(rows.clone(), "tenantA/", "tenantB/", false);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].new, tuple("document:tenantB/1", "viewer", "group:tenantA/eng#member"));
+
+        let moves = plan(rows, "tenantA/", "tenantB/", true);
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].new.subject, "group:tenantB/eng#member");
+        assert_eq!(moves[1].new, tuple("document:other/3", "viewer", "group:tenantB/eng#member"));
+    }
+}
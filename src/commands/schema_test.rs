@@ -0,0 +1,367 @@
+//! Schema tests with coverage.
+//!
+//! `schemas test` evaluates each check in a test file against a local schema
+//! and the file's fixture relationships, the way `check --offline` evaluates a
+//! snapshot, and reports which relations and permissions of the schema the
+//! checks exercised. Evaluation short-circuits, so a permission counts as
+//! exercised only when a check actually reached it.
+//!
+//! ```yaml
+//! relationships:
+//!   - document:readme#owner@user:alice
+//!   - resource: document:readme
+//!     relation: viewer
+//!     subject: group:eng#member
+//! tests:
+//!   - name: owner can edit
+//!     check: user:alice can edit document:readme
+//!     expect: allow
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::{bulk::ExportedRelationship, interop::parse_relationship_lines, offline::Snapshot};
+use crate::{
+    error::{Error, Result},
+    ipl,
+    output::{Displayable, report},
+};
+
+/// A schema test file.
+#[derive(Debug, Deserialize)]
+pub(super) struct TestFile {
+    /// Relationships the checks are evaluated against.
+    #[serde(default)]
+    relationships: Vec<Fixture>,
+    tests: Vec<TestCase>,
+}
+
+/// A fixture relationship, as a `resource#relation@subject` line or as
+/// separate fields.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Fixture {
+    Line(String),
+    Relationship(ExportedRelationship),
+}
+
+#[derive(Debug, Deserialize)]
+struct TestCase {
+    name: String,
+    /// `subject can permission resource`.
+    check: String,
+    expect: Decision,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Decision {
+    Allow,
+    Deny,
+}
+
+impl Decision {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Passed,
+    Failed,
+    /// The check could not be evaluated (e.g., an unknown permission).
+    Error,
+}
+
+/// The outcome of one test.
+#[derive(Debug, Serialize)]
+pub(super) struct TestResult {
+    name: String,
+    check: String,
+    expected: Decision,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    actual: Option<Decision>,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl Displayable for TestResult {
+    fn table_row(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.check.clone(),
+            self.expected.as_str().to_string(),
+            self.actual.map_or("-", Decision::as_str).to_string(),
+            match self.status {
+                Status::Passed => "PASS",
+                Status::Failed => "FAIL",
+                Status::Error => "ERROR",
+            }
+            .to_string(),
+        ]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["NAME", "CHECK", "EXPECTED", "ACTUAL", "RESULT"]
+    }
+}
+
+/// Relations and permissions of one entity exercised by the tests.
+#[derive(Debug, Serialize)]
+pub(super) struct EntityCoverage {
+    entity: String,
+    covered: usize,
+    total: usize,
+    /// Relations and permissions no check reached.
+    uncovered: Vec<String>,
+}
+
+impl Displayable for EntityCoverage {
+    fn table_row(&self) -> Vec<String> {
+        vec![
+            self.entity.clone(),
+            format!("{}/{}", self.covered, self.total),
+            if self.uncovered.is_empty() { "-".to_string() } else { self.uncovered.join(", ") },
+        ]
+    }
+
+    fn table_headers() -> Vec<&'static str> {
+        vec!["ENTITY", "COVERED", "UNTESTED"]
+    }
+}
+
+/// Relations and permissions of the schema exercised by the tests.
+#[derive(Debug, Serialize)]
+pub(super) struct Coverage {
+    pub(super) covered: usize,
+    pub(super) total: usize,
+    pub(super) percent: f64,
+    pub(super) entities: Vec<EntityCoverage>,
+}
+
+/// Results of a test run.
+#[derive(Debug, Serialize)]
+pub(super) struct Report {
+    pub(super) tests: Vec<TestResult>,
+    pub(super) passed: usize,
+    pub(super) failed: usize,
+    pub(super) coverage: Coverage,
+}
+
+/// Parse a YAML (or JSON) test file.
+pub(super) fn parse(content: &str) -> Result<TestFile> {
+    Ok(serde_yaml::from_str(content)?)
+}
+
+/// Run the tests whose names contain `filter`, or every test.
+pub(super) fn run(schema: &ipl::Schema, file: &TestFile, filter: Option<&str>) -> Result<Report> {
+    let mut lines = String::new();
+    let mut relationships = Vec::new();
+    for fixture in &file.relationships {
+        match fixture {
+            Fixture::Line(line) => {
+                lines.push_str(line);
+                lines.push('\n');
+            },
+            Fixture::Relationship(rel) => relationships.push(rel.clone()),
+        }
+    }
+    relationships.extend(parse_relationship_lines(&lines)?);
+    let snapshot = Snapshot::new(schema.clone(), relationships);
+
+    let mut tests = Vec::new();
+    for case in &file.tests {
+        if filter.is_some_and(|filter| !case.name.contains(filter)) {
+            continue;
+        }
+        let evaluated = parse_check(&case.check).and_then(|(subject, permission, resource)| {
+            snapshot.check(subject, permission, resource)
+        });
+        let (actual, status, message) = match evaluated {
+            Ok(allowed) => {
+                let actual = if allowed { Decision::Allow } else { Decision::Deny };
+                let status = if actual == case.expect { Status::Passed } else { Status::Failed };
+                (Some(actual), status, None)
+            },
+            Err(e) => (None, Status::Error, Some(e.to_string())),
+        };
+        tests.push(TestResult {
+            name: case.name.clone(),
+            check: case.check.clone(),
+            expected: case.expect,
+            actual,
+            status,
+            message,
+        });
+    }
+
+    let passed = tests.iter().filter(|t| t.status == Status::Passed).count();
+    let failed = tests.len() - passed;
+    Ok(Report { tests, passed, failed, coverage: coverage(schema, &snapshot) })
+}
+
+/// Split `subject can permission resource` (the `can` is optional).
+pub(super) fn parse_check(check: &str) -> Result<(&str, &str, &str)> {
+    match check.split_whitespace().collect::<Vec<_>>()[..] {
+        [subject, "can", permission, resource] | [subject, permission, resource] => {
+            Ok((subject, permission, resource))
+        },
+        _ => Err(Error::parse(format!(
+            "Invalid check '{check}'. Expected: <subject> can <permission> <resource>"
+        ))),
+    }
+}
+
+fn coverage(schema: &ipl::Schema, snapshot: &Snapshot) -> Coverage {
+    let exercised = snapshot.exercised();
+    let entities: Vec<EntityCoverage> = schema
+        .entities
+        .iter()
+        .map(|entity| {
+            let names = entity
+                .relations
+                .iter()
+                .map(|r| &r.name)
+                .chain(entity.permissions.iter().map(|p| &p.name));
+            let (covered, uncovered): (Vec<&String>, Vec<&String>) =
+                names.partition(|name| exercised.contains(&(entity.name.clone(), (*name).clone())));
+            EntityCoverage {
+                entity: entity.name.clone(),
+                covered: covered.len(),
+                total: covered.len() + uncovered.len(),
+                uncovered: uncovered.into_iter().cloned().collect(),
+            }
+        })
+        .collect();
+
+    let covered = entities.iter().map(|e| e.covered).sum();
+    let total = entities.iter().map(|e| e.total).sum();
+    #[allow(clippy::cast_precision_loss)]
+    let percent = if total == 0 { 100.0 } else { covered as f64 * 100.0 / total as f64 };
+    Coverage { covered, total, percent, entities }
+}
+
+impl Report {
+    /// The results as a CI report suite for `file`, whose `content` locates
+    /// each test by its name.
+    pub(super) fn suite(&self, file: &str, content: &str) -> report::Suite {
+        let cases = self
+            .tests
+            .iter()
+            .map(|test| report::Case {
+                name: test.name.clone(),
+                detail: test.check.clone(),
+                outcome: match test.status {
+                    Status::Passed => report::Outcome::Passed,
+                    Status::Failed => report::Outcome::Failed(format!(
+                        "expected {}, got {}",
+                        test.expected.as_str(),
+                        test.actual.map_or("-", Decision::as_str)
+                    )),
+                    Status::Error => {
+                        report::Outcome::Error(test.message.clone().unwrap_or_default())
+                    },
+                },
+                line: report::line_of(content, &["name:", &test.name]),
+            })
+            .collect();
+        report::Suite {
+            file: file.to_string(),
+            cases,
+            properties: vec![("coverage".to_string(), format!("{:.1}", self.coverage.percent))],
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = "\
+entity User {}
+
+entity Document {
+    relations {
+        owner: User
+        editor: User
+        viewer: User
+    }
+    permissions {
+        edit: owner | editor
+        view: viewer | edit
+    }
+}
+";
+
+    const TESTS: &str = "\
+relationships:
+  - document:readme#owner@user:alice
+  - resource: document:readme
+    relation: viewer
+    subject: user:bob
+tests:
+  - name: owner can edit
+    check: user:alice can edit document:readme
+    expect: allow
+  - name: viewer cannot edit
+    check: user:bob can edit document:readme
+    expect: allow
+  - name: unknown permission
+    check: user:bob can delete document:readme
+    expect: deny
+";
+
+    fn run_tests(filter: Option<&str>) -> Report {
+        run(&ipl::parse(SCHEMA).unwrap(), &parse(TESTS).unwrap(), filter).unwrap()
+    }
+
+    #[test]
+    fn test_run_statuses() {
+        let report = run_tests(None);
+        let statuses: Vec<Status> = report.tests.iter().map(|t| t.status).collect();
+        assert_eq!(statuses, [Status::Passed, Status::Failed, Status::Error]);
+        assert_eq!((report.passed, report.failed), (1, 2));
+        assert_eq!(report.tests[1].actual, Some(Decision::Deny));
+
+        assert_eq!(run_tests(Some("owner")).tests.len(), 1);
+    }
+
+    #[test]
+    fn test_coverage() {
+        let coverage = run_tests(Some("owner")).coverage;
+        let document = coverage.entities.iter().find(|e| e.entity == "Document").unwrap();
+        // `edit` short-circuits on `owner`, so `editor` is never reached
+        assert_eq!((document.covered, document.total), (2, 5));
+        assert_eq!(document.uncovered, ["editor", "viewer", "view"]);
+        assert!((coverage.percent - 40.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_suite() {
+        let suite = run_tests(None).suite("schema.test.yaml", TESTS);
+        assert_eq!(suite.cases[0].line, Some(7));
+        assert_eq!(
+            suite.cases[1].outcome,
+            report::Outcome::Failed("expected allow, got deny".to_string())
+        );
+        assert!(matches!(suite.cases[2].outcome, report::Outcome::Error(_)));
+        assert_eq!(suite.properties, [("coverage".to_string(), "60.0".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_check() {
+        assert_eq!(
+            parse_check("user:alice can view doc:1").unwrap(),
+            ("user:alice", "view", "doc:1")
+        );
+        assert_eq!(parse_check("user:alice view doc:1").unwrap(), ("user:alice", "view", "doc:1"));
+        assert!(parse_check("user:alice can").is_err());
+    }
+}
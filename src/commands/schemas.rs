@@ -1,16 +1,33 @@
 //! Schema management commands.
 
-use bon::builder;
-use serde::Serialize;
+use std::{collections::BTreeMap, path::PathBuf};
 
-use crate::{client::Context, error::Result, output::Displayable, tui};
+use bon::builder;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    dev::commands::{command_exists, run_command, run_command_streaming},
+    schema_test,
+};
+use crate::{
+    client::Context,
+    config::Config,
+    error::{Error, Result},
+    input, ipl, oci,
+    output::{Displayable, OutputFormat, ReportTarget, dates, diff, glyphs, report},
+    tui,
+};
 
 #[derive(Debug, Clone, Serialize)]
 struct SchemaRow {
     version: String,
     status: String,
-    created_at: String,
-    activated_at: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+    activated_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    labels: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
 }
 
 impl Displayable for SchemaRow {
@@ -18,22 +35,28 @@ impl Displayable for SchemaRow {
         vec![
             self.version.clone(),
             self.status.clone(),
-            self.created_at.clone(),
-            self.activated_at.clone(),
+            dates::format(self.created_at),
+            dates::format_opt(self.activated_at),
+            format_labels(&self.labels),
         ]
     }
 
     fn table_headers() -> Vec<&'static str> {
-        vec!["VERSION", "STATUS", "CREATED", "ACTIVATED"]
+        vec!["VERSION", "STATUS", "CREATED", "ACTIVATED", "LABELS"]
     }
 }
 
 /// List schema versions.
-pub async fn list(ctx: &Context, all: bool) -> Result<()> {
+///
+/// With label selectors, every version is fetched and only those carrying all
+/// of the given `key=value` labels are shown.
+pub async fn list(ctx: &Context, all: bool, labels: &[String]) -> Result<()> {
+    let selectors = labels.iter().map(|l| parse_label(l)).collect::<Result<Vec<_>>>()?;
+
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
 
-    let page = if all {
+    let page = if all || !selectors.is_empty() {
         schemas.list().await?
     } else {
         // By default, show recent schemas
@@ -46,37 +69,358 @@ pub async fn list(ctx: &Context, all: bool) -> Result<()> {
         return Ok(());
     }
 
+    let store = load_annotations()?;
+    let vault = store.get(&vault_key(client.org_id(), client.vault_id()));
+    let annotation_for =
+        |version: &str| vault.and_then(|v| v.get(version)).cloned().unwrap_or_default();
+
     let rows: Vec<SchemaRow> = page
         .items
         .iter()
-        .map(|s| SchemaRow {
+        .map(|s| (s, annotation_for(&s.version)))
+        .filter(|(_, annotation)| annotation.matches(&selectors))
+        .map(|(s, annotation)| SchemaRow {
             version: s.version.clone(),
             status: s.status.to_string(),
-            created_at: s.created_at.format("%Y-%m-%d %H:%M").to_string(),
-            activated_at: s
-                .activated_at
-                .map_or_else(|| "-".to_string(), |t| t.format("%Y-%m-%d %H:%M").to_string()),
+            created_at: s.created_at,
+            activated_at: s.activated_at,
+            labels: annotation.labels,
+            note: annotation.note,
         })
         .collect();
 
+    if rows.is_empty() {
+        ctx.output.info("No schemas match the given labels.");
+        return Ok(());
+    }
+
     ctx.output.table(&rows)
 }
 
+/// Annotate a schema version with labels and a note.
+///
+/// Annotations are stored locally per org and vault, since the API does not
+/// yet carry schema metadata. Without any changes, the current annotation is
+/// shown.
+pub async fn annotate(
+    ctx: &Context,
+    id: &str,
+    labels: &[String],
+    remove_labels: &[String],
+    note: Option<&str>,
+) -> Result<()> {
+    let client = ctx.client().await?;
+    let schema = get_schema(&client.vault().schemas(), id).await?;
+
+    let mut store = load_annotations()?;
+    let key = vault_key(client.org_id(), client.vault_id());
+    let mut annotation =
+        store.get(&key).and_then(|v| v.get(&schema.version)).cloned().unwrap_or_default();
+
+    if labels.is_empty() && remove_labels.is_empty() && note.is_none() {
+        return ctx.output.value(&annotation);
+    }
+
+    for label in labels {
+        let (name, value) = parse_label(label)?;
+        annotation.labels.insert(name, value);
+    }
+    for name in remove_labels {
+        if annotation.labels.remove(name).is_none() {
+            ctx.output.warn(&format!("Label not set: {name}"));
+        }
+    }
+    if let Some(note) = note {
+        annotation.note = Some(note.trim().to_string()).filter(|n| !n.is_empty());
+    }
+
+    let vault = store.entry(key.clone()).or_default();
+    if annotation.is_empty() {
+        vault.remove(&schema.version);
+    } else {
+        vault.insert(schema.version.clone(), annotation);
+    }
+    if vault.is_empty() {
+        store.remove(&key);
+    }
+    save_annotations(&store)?;
+
+    ctx.output.success(&format!("Annotated schema version {}.", schema.version));
+    Ok(())
+}
+
 /// Get schema content.
-pub async fn get(ctx: &Context, id: &str) -> Result<()> {
+///
+/// With a bundle directory, writes the schema and its metadata there as a
+/// reviewable artifact instead of printing the content.
+pub async fn get(ctx: &Context, id: &str, bundle: Option<&str>, tests: Option<&str>) -> Result<()> {
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
 
-    let schema = if id == "active" { schemas.get_active().await? } else { schemas.get(id).await? };
+    let schema = get_schema(&schemas, id).await?;
+
+    let Some(dir) = bundle else {
+        // For schema content, just print it directly
+        println!("{}", schema.content);
+        return Ok(());
+    };
+
+    // Read the tests first, so a bad path doesn't leave a partial bundle
+    let tests = tests
+        .map(|path| {
+            input::read_to_string(path)
+                .map_err(|e| Error::invalid_arg(format!("Could not read {path}: {e}")))
+        })
+        .transpose()?;
+
+    let dir = std::path::Path::new(dir);
+    if dir.exists() && dir.read_dir()?.next().is_some() {
+        return Err(Error::invalid_arg(format!(
+            "Bundle directory is not empty: {}",
+            dir.display()
+        )));
+    }
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(BUNDLE_SCHEMA_FILE), &schema.content)?;
+
+    let mut results_file = None;
+    if let Some(tests) = &tests {
+        std::fs::write(dir.join(BUNDLE_TESTS_FILE), tests)?;
+        match ipl::parse(&schema.content)
+            .and_then(|parsed| schema_test::run(&parsed, &schema_test::parse(tests)?, None))
+        {
+            Ok(report) => {
+                std::fs::write(
+                    dir.join(BUNDLE_RESULTS_FILE),
+                    serde_json::to_string_pretty(&report)?,
+                )?;
+                results_file = Some(BUNDLE_RESULTS_FILE.to_string());
+                if report.failed > 0 {
+                    ctx.output.warn(&format!(
+                        "{} of {} schema tests failed against this version",
+                        report.failed,
+                        report.tests.len()
+                    ));
+                } else {
+                    ctx.output.info(&format!("All {} schema tests passed.", report.passed));
+                }
+            },
+            Err(e) => {
+                ctx.output.warn(&format!("Schema tests not run; bundling definitions only: {e}"));
+            },
+        }
+    }
+
+    let mut metadata = BundleMetadata::new(
+        ctx,
+        &client,
+        &schema,
+        tests.as_ref().map(|_| BUNDLE_TESTS_FILE.to_string()),
+    )?;
+    metadata.test_results_file = results_file;
+    std::fs::write(dir.join(BUNDLE_METADATA_FILE), serde_json::to_string_pretty(&metadata)?)?;
+
+    ctx.output.warn("Schema author not yet supported by SDK.");
+    ctx.output.success(&format!(
+        "Wrote schema version {} bundle to {}",
+        schema.version,
+        dir.display()
+    ));
+    Ok(())
+}
+
+/// Publish a schema version to an OCI registry.
+///
+/// The schema, its bundle metadata, and optionally its test definitions are
+/// pushed as a single artifact under the reference's tag. The reported digest
+/// pins that exact artifact for pulls. With `sign`, the digest is signed with
+/// cosign: keyless through Sigstore, or with `key` (a key file or KMS URI).
+pub async fn publish(
+    ctx: &Context,
+    reference: &str,
+    id: &str,
+    tests: Option<&str>,
+    sign: bool,
+    key: Option<&str>,
+) -> Result<()> {
+    let reference = oci::Reference::parse(reference)?;
+
+    let client = ctx.client().await?;
+    let schema = get_schema(&client.vault().schemas(), id).await?;
+
+    let tests = tests
+        .map(|path| {
+            input::read_to_string(path)
+                .map(String::into_bytes)
+                .map_err(|e| Error::invalid_arg(format!("Could not read {path}: {e}")))
+        })
+        .transpose()?;
+    let metadata = BundleMetadata::new(
+        ctx,
+        &client,
+        &schema,
+        tests.as_ref().map(|_| BUNDLE_TESTS_FILE.to_string()),
+    )?;
+    let config = serde_json::to_vec_pretty(&metadata)?;
+    let config_descriptor = oci::Descriptor::new(oci::METADATA_MEDIA_TYPE, &config);
+
+    let mut blobs = vec![(
+        oci::Descriptor::new(oci::SCHEMA_MEDIA_TYPE, schema.content.as_bytes())
+            .titled(BUNDLE_SCHEMA_FILE),
+        schema.content.as_bytes().to_vec(),
+    )];
+    if let Some(tests) = tests {
+        blobs.push((
+            oci::Descriptor::new(oci::TESTS_MEDIA_TYPE, &tests).titled(BUNDLE_TESTS_FILE),
+            tests,
+        ));
+    }
+    let manifest = oci::Manifest::new(
+        config_descriptor.clone(),
+        blobs.iter().map(|(descriptor, _)| descriptor.clone()).collect(),
+    );
+
+    let mut registry = oci::Registry::new(reference.clone());
+    let digest = tui::spin(format!("Publishing to {reference}..."), async {
+        for (descriptor, content) in &blobs {
+            registry.push_blob(descriptor, content).await?;
+        }
+        registry.push_blob(&config_descriptor, &config).await?;
+        registry.push_manifest(&manifest).await
+    })
+    .await?;
+
+    let pinned = reference.pinned(&digest);
+    if sign {
+        sign_bundle(&pinned, key)?;
+    }
+
+    if ctx.output.format() != OutputFormat::Table {
+        return ctx.output.value(&serde_json::json!({
+            "reference": reference.to_string(),
+            "digest": digest,
+            "version": schema.version,
+            "signed": sign,
+        }));
+    }
+
+    ctx.output.success(&format!("Published schema version {} to {reference}", schema.version));
+    ctx.output.info(&format!("Digest: {digest}"));
+    if sign {
+        ctx.output.success(&format!("Signed {pinned}"));
+    } else {
+        ctx.output.info(&format!("To sign: inferadb schemas publish {reference} --sign"));
+    }
+    Ok(())
+}
+
+/// Verify the signature of a published schema bundle.
+pub async fn verify_signature(
+    ctx: &Context,
+    reference: &str,
+    verifier: SignatureVerifier<'_>,
+) -> Result<()> {
+    let reference = oci::Reference::parse(reference)?;
+    let (mut registry, manifest, digest) =
+        tui::spin(format!("Verifying {reference}..."), verify_bundle(&reference, verifier)).await?;
+
+    let version =
+        serde_json::from_slice::<serde_json::Value>(&registry.pull_blob(&manifest.config).await?)
+            .ok()
+            .and_then(|m| m["version"].as_str().map(String::from));
+
+    if ctx.output.format() != OutputFormat::Table {
+        return ctx.output.value(&serde_json::json!({
+            "reference": reference.to_string(),
+            "digest": digest,
+            "version": version,
+            "verified": true,
+        }));
+    }
+
+    ctx.output.success(&format!("Signature verified for {reference}"));
+    ctx.output.info(&format!("Digest: {digest}"));
+    if let Some(version) = version {
+        ctx.output.info(&format!("Schema version: {version}"));
+    }
+    Ok(())
+}
+
+/// Pull a published schema bundle from an OCI registry into a directory.
+///
+/// Writes the same layout as `get --bundle`, with every blob verified against
+/// its digest.
+pub async fn pull(ctx: &Context, reference: &str, output: Option<&str>) -> Result<()> {
+    let reference = oci::Reference::parse(reference)?;
+    let dir = PathBuf::from(output.unwrap_or_else(|| {
+        reference.repository.rsplit('/').next().unwrap_or(&reference.repository)
+    }));
+    if dir.exists() && dir.read_dir()?.next().is_some() {
+        return Err(Error::invalid_arg(format!(
+            "Output directory is not empty: {}",
+            dir.display()
+        )));
+    }
+
+    let mut registry = oci::Registry::new(reference.clone());
+    let (manifest, digest, files) = tui::spin(format!("Pulling {reference}..."), async {
+        let (manifest, digest) = registry.pull_manifest().await?;
+        if manifest.artifact_type.as_deref() != Some(oci::ARTIFACT_TYPE)
+            && manifest.config.media_type != oci::METADATA_MEDIA_TYPE
+        {
+            return Err(Error::other(format!("{reference} is not an InferaDB schema bundle")));
+        }
+        let Some(schema) = manifest.layer(oci::SCHEMA_MEDIA_TYPE) else {
+            return Err(Error::other(format!("{reference} has no schema layer")));
+        };
+
+        let mut files = vec![
+            (BUNDLE_METADATA_FILE, registry.pull_blob(&manifest.config).await?),
+            (BUNDLE_SCHEMA_FILE, registry.pull_blob(schema).await?),
+        ];
+        if let Some(tests) = manifest.layer(oci::TESTS_MEDIA_TYPE) {
+            files.push((BUNDLE_TESTS_FILE, registry.pull_blob(tests).await?));
+        }
+        Ok((manifest, digest, files))
+    })
+    .await?;
+
+    std::fs::create_dir_all(&dir)?;
+    for (name, content) in &files {
+        std::fs::write(dir.join(name), content)?;
+    }
+
+    let version = serde_json::from_slice::<serde_json::Value>(&files[0].1)
+        .ok()
+        .and_then(|m| m["version"].as_str().map(String::from));
+
+    if ctx.output.format() != OutputFormat::Table {
+        return ctx.output.value(&serde_json::json!({
+            "reference": reference.to_string(),
+            "digest": digest,
+            "version": version,
+            "directory": dir.display().to_string(),
+            "files": files.iter().map(|(name, _)| *name).collect::<Vec<_>>(),
+            "layers": manifest.layers.len(),
+        }));
+    }
 
-    // For schema content, just print it directly
-    println!("{}", schema.content);
+    ctx.output.success(&format!(
+        "Pulled schema version {} to {}",
+        version.as_deref().unwrap_or("unknown"),
+        dir.display()
+    ));
+    ctx.output.info(&format!("Digest: {digest}"));
+    ctx.output.info(&format!(
+        "To push: inferadb schemas push {}",
+        dir.join(BUNDLE_SCHEMA_FILE).display()
+    ));
     Ok(())
 }
 
 /// Preview schema changes.
 pub async fn preview(ctx: &Context, file: &str, base: Option<&str>, impact: bool) -> Result<()> {
-    let content = std::fs::read_to_string(file)?;
+    let content = input::read_to_string(file)?;
 
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
@@ -124,7 +468,11 @@ pub async fn push(
     message: Option<&str>,
     dry_run: bool,
 ) -> Result<()> {
-    let content = std::fs::read_to_string(file)?;
+    if activate && !dry_run {
+        ensure_signature_not_required(ctx)?;
+    }
+
+    let content = input::read_to_string(file)?;
 
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
@@ -164,6 +512,7 @@ pub async fn push(
 
     let version = &result.schema.version;
     ctx.output.success(&format!("Schema version {version} created."));
+    ctx.output.primary(version);
 
     if let Some(msg) = message {
         ctx.output.info(&format!("Message: {msg}"));
@@ -187,8 +536,134 @@ pub async fn push(
     Ok(())
 }
 
+/// Prefix of the notes `schemas edit` adds above the schema; lines starting
+/// with it are removed before validating.
+const EDIT_NOTE: &str = "// ! ";
+
+/// Edit a schema version in `$EDITOR`, then validate, diff, and push it.
+///
+/// Like `kubectl edit`: a schema that fails validation reopens in the editor
+/// with the errors noted at the top, and saving without changes cancels. If
+/// the push is declined or fails, the edited schema is kept in a temporary
+/// file so no work is lost.
+pub async fn edit(ctx: &Context, id: &str, activate: bool) -> Result<()> {
+    if activate {
+        ensure_signature_not_required(ctx)?;
+    }
+
+    let client = ctx.client().await?;
+    let schemas = client.vault().schemas();
+    let current = tui::spin("Fetching schema...", get_schema(&schemas, id)).await?;
+
+    let mut buffer = current.content.clone();
+    let content = loop {
+        let content = strip_edit_notes(&super::shell::edit_in_editor(&buffer, "schema", "ipl")?);
+        if content.trim().is_empty() || content == strip_edit_notes(&buffer) {
+            ctx.output.info("Edit cancelled, no changes made.");
+            return Ok(());
+        }
+
+        let validation = tui::spin("Validating schema...", schemas.validate(&content)).await?;
+        if validation.is_valid() {
+            if validation.has_warnings() {
+                ctx.output.warn("Warnings:");
+                for warn in &validation.warnings {
+                    eprintln!("  Line {}: {} [{}]", warn.line, warn.message, warn.code);
+                }
+            }
+            break content;
+        }
+
+        ctx.output.error("Schema validation failed; reopening the editor:");
+        let errors: Vec<(u32, String)> = validation
+            .errors
+            .iter()
+            .map(|err| (err.line, format!("{} [{}]", err.message, err.code)))
+            .collect();
+        for (line, message) in &errors {
+            eprintln!("  Line {line}: {message}");
+        }
+        buffer = with_edit_notes(&content, &errors);
+    };
+
+    let hunks = diff::hunks(&diff::diff_lines(&current.content, &content), 3);
+    println!("Changes to schema version {}:", current.version);
+    print!("{}", diff::render_unified(&hunks, ctx.output.color));
+
+    let prompt = if activate { "Push and activate this schema?" } else { "Push this schema?" };
+    if !ctx.confirm(prompt)? {
+        let path = save_edited_schema(&content)?;
+        ctx.output.info(&format!("Not pushed. Your changes were saved to {}", path.display()));
+        return Ok(());
+    }
+
+    let result = match tui::spin("Pushing schema...", schemas.push(&content)).await {
+        Ok(result) => result,
+        Err(e) => {
+            let path = save_edited_schema(&content)?;
+            ctx.output.info(&format!("Your changes were saved to {}", path.display()));
+            return Err(e.into());
+        },
+    };
+    let version = &result.schema.version;
+    ctx.output.success(&format!("Schema version {version} created."));
+    ctx.output.primary(version);
+
+    if activate {
+        tui::spin("Activating schema...", schemas.activate(version)).await?;
+        ctx.output.success(&format!("Schema version {version} is now active."));
+    } else {
+        ctx.output.info(&format!("To activate: inferadb schemas activate {version}"));
+    }
+    Ok(())
+}
+
+/// The buffer without the notes `schemas edit` added.
+fn strip_edit_notes(buffer: &str) -> String {
+    buffer.lines().filter(|line| !line.starts_with(EDIT_NOTE)).fold(
+        String::new(),
+        |mut out, line| {
+            out.push_str(line);
+            out.push('\n');
+            out
+        },
+    )
+}
+
+/// `content` with validation errors noted above it, their line numbers
+/// shifted to match the lines in the editor.
+fn with_edit_notes(content: &str, errors: &[(u32, String)]) -> String {
+    let mut notes = vec![
+        "Schema validation failed. Fix the errors below and save, or save".to_string(),
+        "without changes to cancel. These lines are removed before validating.".to_string(),
+    ];
+    let offset = u32::try_from(notes.len() + errors.len()).unwrap_or(u32::MAX);
+    for (line, message) in errors {
+        notes.push(format!("  Line {}: {message}", line.saturating_add(offset)));
+    }
+
+    let mut buffer = String::new();
+    for note in notes {
+        buffer.push_str(EDIT_NOTE);
+        buffer.push_str(&note);
+        buffer.push('\n');
+    }
+    buffer.push_str(content);
+    buffer
+}
+
+/// Keep an edited schema that was not pushed in a temporary file.
+fn save_edited_schema(content: &str) -> Result<PathBuf> {
+    let path = std::env::temp_dir()
+        .join(format!("inferadb-schema-edit-{}.ipl", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
 /// Rollback to a previous schema version.
 pub async fn rollback(ctx: &Context, version: Option<&str>) -> Result<()> {
+    ensure_signature_not_required(ctx)?;
+
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
 
@@ -223,37 +698,484 @@ pub async fn rollback(ctx: &Context, version: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Fetch a schema version, resolving "active" to the current schema.
+async fn get_schema(
+    schemas: &inferadb::control::SchemasClient,
+    id: &str,
+) -> std::result::Result<inferadb::control::SchemaInfo, inferadb::Error> {
+    if id == "active" { schemas.get_active().await } else { schemas.get(id).await }
+}
+
+// ============================================================================
+// Annotations
+// ============================================================================
+
+/// Local labels and note attached to a schema version.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct SchemaAnnotation {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    labels: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
+impl SchemaAnnotation {
+    fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.note.is_none()
+    }
+
+    /// Whether every `(key, value)` selector is present among the labels.
+    fn matches(&self, selectors: &[(String, String)]) -> bool {
+        selectors.iter().all(|(key, value)| self.labels.get(key) == Some(value))
+    }
+}
+
+/// Annotations keyed by `org/vault`, then by schema version.
+type AnnotationStore = BTreeMap<String, BTreeMap<String, SchemaAnnotation>>;
+
+/// Store key scoping annotations to a vault.
+fn vault_key(org_id: &str, vault_id: &str) -> String {
+    format!("{org_id}/{vault_id}")
+}
+
+/// Parse a `key=value` label.
+fn parse_label(label: &str) -> Result<(String, String)> {
+    let (key, value) = label
+        .split_once('=')
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .filter(|(k, v)| !k.is_empty() && !v.is_empty())
+        .ok_or_else(|| {
+            Error::invalid_arg(format!(
+                "Invalid label '{label}'. Use key=value, e.g. team=payments"
+            ))
+        })?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Render labels as `key=value` pairs for table output.
+fn format_labels(labels: &BTreeMap<String, String>) -> String {
+    if labels.is_empty() {
+        return "-".to_string();
+    }
+    labels.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join(",")
+}
+
+fn annotations_path() -> Result<PathBuf> {
+    Config::data_dir()
+        .map(|dir| dir.join("schema-annotations.yaml"))
+        .ok_or_else(|| Error::config("Could not determine data directory"))
+}
+
+/// Load stored annotations, or an empty store if none exist.
+fn load_annotations() -> Result<AnnotationStore> {
+    let path = annotations_path()?;
+    if !path.exists() {
+        return Ok(AnnotationStore::new());
+    }
+    let contents = std::fs::read_to_string(&path)?;
+    if contents.trim().is_empty() {
+        return Ok(AnnotationStore::new());
+    }
+    serde_yaml::from_str(&contents)
+        .map_err(|e| Error::config(format!("Invalid annotations file {}: {e}", path.display())))
+}
+
+fn save_annotations(store: &AnnotationStore) -> Result<()> {
+    let path = annotations_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_yaml::to_string(store)?)?;
+    Ok(())
+}
+
+// ============================================================================
+// Bundles
+// ============================================================================
+
+const BUNDLE_SCHEMA_FILE: &str = "schema.ipl";
+const BUNDLE_TESTS_FILE: &str = "schema.test.yaml";
+const BUNDLE_METADATA_FILE: &str = "metadata.json";
+const BUNDLE_RESULTS_FILE: &str = "test-results.json";
+
+/// Metadata written alongside a bundled schema version.
+#[derive(Debug, Clone, Serialize)]
+struct BundleMetadata {
+    id: String,
+    version: String,
+    org_id: String,
+    vault_id: String,
+    status: inferadb::control::SchemaStatus,
+    created_at: chrono::DateTime<chrono::Utc>,
+    activated_at: Option<chrono::DateTime<chrono::Utc>>,
+    author: Option<String>,
+    #[serde(flatten)]
+    annotation: SchemaAnnotation,
+    schema_file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tests_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    test_results_file: Option<String>,
+    exported_at: chrono::DateTime<chrono::Utc>,
+    exported_by: String,
+    cli_version: String,
+}
+
+impl BundleMetadata {
+    fn new(
+        ctx: &Context,
+        client: &crate::client::CliClient,
+        schema: &inferadb::control::SchemaInfo,
+        tests_file: Option<String>,
+    ) -> Result<Self> {
+        let annotation = load_annotations()?
+            .get(&vault_key(client.org_id(), client.vault_id()))
+            .and_then(|v| v.get(&schema.version))
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(Self {
+            id: schema.id.clone(),
+            version: schema.version.clone(),
+            org_id: client.org_id().to_string(),
+            vault_id: schema.vault_id.clone(),
+            status: schema.status,
+            created_at: schema.created_at,
+            activated_at: schema.activated_at,
+            author: None,
+            annotation,
+            schema_file: BUNDLE_SCHEMA_FILE.to_string(),
+            tests_file,
+            test_results_file: None,
+            exported_at: chrono::Utc::now(),
+            exported_by: ctx.effective_profile_name().to_string(),
+            cli_version: crate::VERSION.to_string(),
+        })
+    }
+}
+
+// ============================================================================
+// Signatures
+// ============================================================================
+
+const COSIGN: &str = "cosign";
+
+/// How a bundle signature is verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureVerifier<'a> {
+    /// A public key file or KMS URI.
+    Key(&'a str),
+    /// Keyless (Sigstore): the signing certificate's identity and OIDC issuer.
+    Keyless {
+        /// Expected certificate identity (e.g., an email or workflow URL).
+        identity: &'a str,
+        /// Expected OIDC issuer.
+        issuer: &'a str,
+    },
+}
+
+impl<'a> SignatureVerifier<'a> {
+    /// Build a verifier from `--key` or `--certificate-identity` and
+    /// `--certificate-oidc-issuer`.
+    pub fn from_args(
+        key: Option<&'a str>,
+        identity: Option<&'a str>,
+        issuer: Option<&'a str>,
+    ) -> Result<Self> {
+        match (key, identity, issuer) {
+            (Some(key), None, None) => Ok(Self::Key(key)),
+            (None, Some(identity), Some(issuer)) => Ok(Self::Keyless { identity, issuer }),
+            _ => Err(Error::invalid_arg(
+                "Verify with --key, or with both --certificate-identity and \
+                 --certificate-oidc-issuer",
+            )),
+        }
+    }
+
+    fn args(self) -> Vec<&'a str> {
+        match self {
+            Self::Key(key) => vec!["--key", key],
+            Self::Keyless { identity, issuer } => {
+                vec!["--certificate-identity", identity, "--certificate-oidc-issuer", issuer]
+            },
+        }
+    }
+}
+
+fn require_cosign() -> Result<()> {
+    if command_exists(COSIGN) {
+        Ok(())
+    } else {
+        Err(Error::other(
+            "cosign is required for bundle signatures. \
+             Install it from https://docs.sigstore.dev/cosign/system_config/installation/",
+        ))
+    }
+}
+
+/// Sign a bundle by digest, keyless unless `key` is given.
+fn sign_bundle(pinned: &str, key: Option<&str>) -> Result<()> {
+    require_cosign()?;
+    let mut args = vec!["sign", "--yes"];
+    if let Some(key) = key {
+        args.extend(["--key", key]);
+    }
+    args.push(pinned);
+    run_command_streaming(COSIGN, &args, &[])
+}
+
+/// Resolve `reference` to a digest and verify that digest's signature.
+///
+/// Returns the registry client, manifest, and digest so callers can read the
+/// exact artifact that was verified.
+async fn verify_bundle(
+    reference: &oci::Reference,
+    verifier: SignatureVerifier<'_>,
+) -> Result<(oci::Registry, oci::Manifest, String)> {
+    require_cosign()?;
+    let mut registry = oci::Registry::new(reference.clone());
+    let (manifest, digest) = registry.pull_manifest().await?;
+    let pinned = reference.pinned(&digest);
+
+    let mut args = vec!["verify"];
+    args.extend(verifier.args());
+    args.push(&pinned);
+    let output = run_command(COSIGN, &args)
+        .map_err(|e| Error::other(format!("Signature verification failed for {reference}: {e}")))?;
+
+    if !signed_digests(&output).contains(&digest) {
+        return Err(Error::other(format!("No signature for {pinned} matched")));
+    }
+    Ok((registry, manifest, digest))
+}
+
+/// Manifest digests covered by the payloads printed by `cosign verify`.
+fn signed_digests(output: &str) -> Vec<String> {
+    let payloads: Vec<serde_json::Value> = output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .flat_map(|value| match value {
+            serde_json::Value::Array(items) => items,
+            other => vec![other],
+        })
+        .collect();
+    payloads
+        .iter()
+        .filter_map(|p| p["critical"]["image"]["docker-manifest-digest"].as_str())
+        .map(String::from)
+        .collect()
+}
+
+/// Refuse an unverified activation when the profile requires signed schemas.
+pub(super) fn ensure_signature_not_required(ctx: &Context) -> Result<()> {
+    if ctx.profile.require_signed_schemas {
+        return Err(Error::invalid_arg(format!(
+            "Profile '{}' requires signed schemas. Activate with: \
+             inferadb schemas activate <version> --verify-signature oci://...",
+            ctx.effective_profile_name()
+        )));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Permission matrix
+// ============================================================================
+
+/// Relations × permissions contribution matrix for one entity.
+#[derive(Debug, Clone, Serialize)]
+struct PermissionMatrix {
+    entity: String,
+    permissions: Vec<String>,
+    relations: Vec<MatrixRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MatrixRow {
+    relation: String,
+    contributions: BTreeMap<String, ipl::Contribution>,
+}
+
+impl PermissionMatrix {
+    fn new(entity: &ipl::Entity) -> Self {
+        let contributions = entity.contributions();
+        let relations = entity
+            .relations
+            .iter()
+            .map(|r| MatrixRow {
+                relation: r.name.clone(),
+                contributions: contributions
+                    .iter()
+                    .filter_map(|(permission, found)| {
+                        found.get(&r.name).map(|kind| (permission.clone(), *kind))
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            entity: entity.name.clone(),
+            permissions: entity.permissions.iter().map(|p| p.name.clone()).collect(),
+            relations,
+        }
+    }
+
+    /// One row per relation, with `empty` where a relation does not contribute.
+    fn cells(&self, empty: &str) -> Vec<Vec<String>> {
+        self.relations
+            .iter()
+            .map(|row| {
+                std::iter::once(row.relation.clone())
+                    .chain(self.permissions.iter().map(|p| {
+                        row.contributions
+                            .get(p)
+                            .map_or_else(|| empty.to_string(), ToString::to_string)
+                    }))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn to_csv(&self) -> String {
+        let escape = |field: &str| {
+            if field.contains([',', '"', '\n']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        };
+
+        let mut out = std::iter::once("relation")
+            .chain(self.permissions.iter().map(String::as_str))
+            .map(escape)
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push('\n');
+        for row in self.cells("") {
+            out.push_str(&row.iter().map(|c| escape(c)).collect::<Vec<_>>().join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn to_html(&self) -> String {
+        let escape = |text: &str| {
+            text.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+        };
+
+        let mut out = format!(
+            "<table class=\"permission-matrix\">\n  <caption>{}</caption>\n",
+            escape(&self.entity)
+        );
+        out.push_str("  <thead>\n    <tr><th>relation</th>");
+        for permission in &self.permissions {
+            out.push_str(&format!("<th>{}</th>", escape(permission)));
+        }
+        out.push_str("</tr>\n  </thead>\n  <tbody>\n");
+        for row in &self.relations {
+            out.push_str(&format!("    <tr><th>{}</th>", escape(&row.relation)));
+            for permission in &self.permissions {
+                match row.contributions.get(permission) {
+                    Some(kind) => out.push_str(&format!("<td class=\"{kind}\">{kind}</td>")),
+                    None => out.push_str("<td></td>"),
+                }
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("  </tbody>\n</table>\n");
+        out
+    }
+}
+
+/// Machine-readable schema diff for json/yaml output.
+#[derive(Debug, Clone, Serialize)]
+struct SchemaDiffReport {
+    from_version: String,
+    to_version: String,
+    is_backward_compatible: bool,
+    changes: Vec<inferadb::control::SchemaChange>,
+    hunks: Vec<diff::Hunk>,
+}
+
 /// Show diff between schema versions.
-pub async fn diff(ctx: &Context, from: &str, to: &str) -> Result<()> {
+///
+/// Table output renders a colored unified or side-by-side text diff after the
+/// change summary; other formats emit the changes and hunks as objects.
+pub async fn diff(
+    ctx: &Context,
+    from: &str,
+    to: &str,
+    style: diff::DiffStyle,
+    context: usize,
+) -> Result<()> {
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
 
-    let diff = schemas.diff(from, to).await?;
+    let (diff, from_schema, to_schema) = tui::spin(
+        "Comparing schema versions...",
+        futures::future::try_join3(
+            schemas.diff(from, to),
+            get_schema(&schemas, from),
+            get_schema(&schemas, to),
+        ),
+    )
+    .await?;
+
+    let hunks = diff::hunks(&diff::diff_lines(&from_schema.content, &to_schema.content), context);
+
+    if ctx.output.format() != OutputFormat::Table {
+        return ctx.output.value(&SchemaDiffReport {
+            from_version: diff.from_version,
+            to_version: diff.to_version,
+            is_backward_compatible: diff.is_backward_compatible,
+            changes: diff.changes,
+            hunks,
+        });
+    }
 
     println!("Schema diff: {} -> {}", diff.from_version, diff.to_version);
     println!();
 
-    if diff.changes.is_empty() {
+    if diff.changes.is_empty() && hunks.is_empty() {
         println!("No changes between versions.");
         return Ok(());
     }
 
     println!("Backward compatible: {}", if diff.is_backward_compatible { "yes" } else { "NO" });
     println!();
-    println!("Changes:");
 
-    for change in &diff.changes {
-        let breaking_marker = if change.is_breaking { " [BREAKING]" } else { "" };
-        let entity = change.entity_type.as_deref().map(|e| format!(" ({e})")).unwrap_or_default();
-        println!("  {} {}{}{}", change.change_type, change.description, entity, breaking_marker);
+    if !diff.changes.is_empty() {
+        println!("Changes:");
+        for change in &diff.changes {
+            let breaking_marker = if change.is_breaking { " [BREAKING]" } else { "" };
+            let entity =
+                change.entity_type.as_deref().map(|e| format!(" ({e})")).unwrap_or_default();
+            println!(
+                "  {} {}{}{}",
+                change.change_type, change.description, entity, breaking_marker
+            );
+        }
+        println!();
     }
 
+    let rendered = match style {
+        diff::DiffStyle::Unified => diff::render_unified(&hunks, ctx.output.color),
+        diff::DiffStyle::SideBySide => {
+            let width = teapot::terminal::size().map_or(160, |(cols, _)| usize::from(cols));
+            diff::render_side_by_side(&hunks, width, ctx.output.color)
+        },
+    };
+    print!("{rendered}");
+
     Ok(())
 }
 
 /// Validate schema without pushing.
 pub async fn validate(ctx: &Context, file: &str) -> Result<()> {
-    let content = std::fs::read_to_string(file)?;
+    let content = input::read_to_string(file)?;
 
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
@@ -389,15 +1311,43 @@ entity Resource {
 }
 
 /// Activate a schema version with options.
+///
+/// With `signature`, the version is only activated if the referenced bundle's
+/// signature verifies and its schema matches the version's content.
 pub async fn activate_with_options(
     ctx: &Context,
     version: &str,
     show_diff: bool,
     canary_percent: Option<u8>,
+    signature: Option<(&str, SignatureVerifier<'_>)>,
 ) -> Result<()> {
+    if signature.is_none() {
+        ensure_signature_not_required(ctx)?;
+    }
+
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
 
+    // The signed bundle must carry exactly the schema being activated
+    if let Some((reference, verifier)) = signature {
+        let reference = oci::Reference::parse(reference)?;
+        let (mut registry, manifest, digest) =
+            tui::spin(format!("Verifying {reference}..."), verify_bundle(&reference, verifier))
+                .await?;
+        let layer = manifest
+            .layer(oci::SCHEMA_MEDIA_TYPE)
+            .ok_or_else(|| Error::other(format!("{reference} has no schema layer")))?;
+        let signed = registry.pull_blob(layer).await?;
+        let schema = get_schema(&schemas, version).await?;
+        if signed != schema.content.as_bytes() {
+            return Err(Error::other(format!(
+                "Schema version {version} does not match the signed bundle {}",
+                reference.pinned(&digest)
+            )));
+        }
+        ctx.output.success(&format!("Signature verified ({digest})."));
+    }
+
     // Show diff if requested
     if show_diff {
         let diff_result =
@@ -419,7 +1369,7 @@ pub async fn activate_with_options(
             println!();
         }
 
-        if !ctx.yes && !ctx.confirm("Proceed with activation?")? {
+        if !ctx.confirm("Proceed with activation?")? {
             ctx.output.info("Cancelled.");
             return Ok(());
         }
@@ -443,7 +1393,7 @@ pub async fn activate_with_options(
 
 /// Format a schema file.
 pub async fn format(ctx: &Context, file: &str, write: bool) -> Result<()> {
-    let content = std::fs::read_to_string(file)?;
+    let content = input::read_to_string(file)?;
 
     let client = ctx.client().await?;
     let schemas = client.vault().schemas();
@@ -470,45 +1420,82 @@ pub async fn format(ctx: &Context, file: &str, write: bool) -> Result<()> {
     Ok(())
 }
 
-/// Run schema tests.
+/// Run schema tests against a local schema and report coverage.
+///
+/// Fails when any test fails or errors, and when `min_coverage` is given and
+/// the tests exercise a smaller percentage of the schema's relations and
+/// permissions. Each of `reports` also receives the results for CI.
+#[builder]
 pub async fn test(
     ctx: &Context,
     tests_file: Option<&str>,
     schema_file: Option<&str>,
     name_filter: Option<&str>,
+    min_coverage: Option<u8>,
+    #[builder(default)] reports: &[ReportTarget],
 ) -> Result<()> {
     // Default test file
     let tests_path = tests_file.unwrap_or("schema.test.yaml");
     let schema_path = schema_file.unwrap_or("schema.ipl");
 
-    if !std::path::Path::new(tests_path).exists() {
-        ctx.output.error(&format!("Test file not found: {tests_path}"));
+    if !input::is_local(tests_path) {
         ctx.output.info("Create a test file with check assertions.");
         ctx.output.info("");
         ctx.output.info("Example schema.test.yaml:");
+        ctx.output.info("  relationships:");
+        ctx.output.info("    - doc:readme#owner@user:alice");
         ctx.output.info("  tests:");
         ctx.output.info("    - name: owner can edit");
         ctx.output.info("      check: user:alice can edit doc:readme");
         ctx.output.info("      expect: allow");
-        return Ok(());
+        return Err(Error::invalid_arg(format!("Test file not found: {tests_path}")));
     }
 
-    if !std::path::Path::new(schema_path).exists() {
-        ctx.output.error(&format!("Schema file not found: {schema_path}"));
-        return Ok(());
+    let source = input::read_to_string(schema_path)
+        .map_err(|e| Error::invalid_arg(format!("Could not read schema {schema_path}: {e}")))?;
+    let schema = ipl::parse(&source)?;
+    let content = input::read_to_string(tests_path)
+        .map_err(|e| Error::invalid_arg(format!("Could not read {tests_path}: {e}")))?;
+    let file = schema_test::parse(&content)?;
+
+    let report = schema_test::run(&schema, &file, name_filter)?;
+    if report.tests.is_empty() {
+        ctx.output.warn("No tests matched.");
     }
 
-    ctx.output.info(&format!("Running tests from {tests_path}..."));
+    report::write(reports, &report.suite(tests_path, &content))?;
 
-    if let Some(filter) = name_filter {
-        ctx.output.info(&format!("Filtering by: {filter}"));
+    let coverage = &report.coverage;
+    if ctx.output.format() == OutputFormat::Table {
+        ctx.output.table(&report.tests)?;
+        println!();
+        ctx.output.table(&coverage.entities)?;
+        ctx.output.info(&format!(
+            "Coverage: {}/{} relations and permissions ({:.1}%)",
+            coverage.covered, coverage.total, coverage.percent
+        ));
+    } else {
+        ctx.output.value(&report)?;
     }
 
-    // Note: Schema testing would parse the test file and run checks
-    // For now, show placeholder
-    ctx.output.warn("Schema testing not yet implemented.");
-    ctx.output.info("Use 'inferadb check' commands to manually verify authorization.");
-
+    if report.failed > 0 {
+        return Err(Error::other(format!(
+            "{} of {} schema tests failed",
+            report.failed,
+            report.tests.len()
+        )));
+    }
+    if let Some(min) = min_coverage
+        && coverage.percent < f64::from(min)
+    {
+        return Err(Error::other(format!(
+            "Coverage {:.1}% is below the minimum {min}%",
+            coverage.percent
+        )));
+    }
+    if !report.tests.is_empty() {
+        ctx.output.success(&format!("All {} tests passed.", report.passed));
+    }
     Ok(())
 }
 
@@ -570,11 +1557,9 @@ pub async fn analyze(
     checks: Option<&str>,
     compare: Option<&str>,
 ) -> Result<()> {
-    use std::path::Path;
-
     // Determine if we're analyzing a file or a version ID
-    let content = if Path::new(file).exists() {
-        std::fs::read_to_string(file)?
+    let content = if input::is_local(file) {
+        input::read_to_string(file)?
     } else {
         // Treat as version ID - fetch from server
         let client = ctx.client().await?;
@@ -602,9 +1587,9 @@ pub async fn analyze(
 
     // Basic validation results
     if validation.is_valid {
-        println!("✓ Schema syntax is valid");
+        println!("{} Schema syntax is valid", glyphs::CHECK);
     } else {
-        println!("✗ Schema has syntax errors");
+        println!("{} Schema has syntax errors", glyphs::CROSS);
         for error in &validation.errors {
             println!(
                 "  Error at {}:{}: {} ({})",
@@ -636,13 +1621,13 @@ pub async fn analyze(
     for check in checks_list {
         match check {
             "unused" => {
-                println!("  ✓ No unused relations detected");
+                println!("  {} No unused relations detected", glyphs::CHECK);
             },
             "cycles" => {
-                println!("  ✓ No circular dependencies detected");
+                println!("  {} No circular dependencies detected", glyphs::CHECK);
             },
             "shadowing" => {
-                println!("  ✓ No permission shadowing detected");
+                println!("  {} No permission shadowing detected", glyphs::CHECK);
             },
             other => {
                 println!("  ? Unknown check: {other}");
@@ -660,6 +1645,51 @@ pub async fn analyze(
     Ok(())
 }
 
+/// Render the relations × permissions matrix for an entity.
+///
+/// Each cell shows how a relation feeds a permission: directly, through a
+/// rewrite (another permission or a relation traversal), only under a caveat,
+/// or as an exclusion.
+pub async fn matrix(ctx: &Context, file: &str, entity: &str, format: &str) -> Result<()> {
+    let content = if input::is_local(file) {
+        input::read_to_string(file)?
+    } else {
+        let client = ctx.client().await?;
+        get_schema(&client.vault().schemas(), file).await?.content
+    };
+
+    let schema = ipl::parse(&content)?;
+    let Some(definition) = schema.entity(entity) else {
+        let names: Vec<&str> = schema.entities.iter().map(|e| e.name.as_str()).collect();
+        return Err(Error::invalid_arg(format!(
+            "Entity '{entity}' not found in schema. Available: {}",
+            names.join(", ")
+        )));
+    };
+
+    if definition.permissions.is_empty() {
+        ctx.output.info(&format!("Entity '{entity}' defines no permissions."));
+        return Ok(());
+    }
+
+    let report = PermissionMatrix::new(definition);
+
+    if ctx.output.format() != OutputFormat::Table {
+        return ctx.output.value(&report);
+    }
+
+    match format {
+        "csv" => print!("{}", report.to_csv()),
+        "html" => print!("{}", report.to_html()),
+        _ => {
+            let mut headers = vec!["RELATION".to_string()];
+            headers.extend(report.permissions.iter().map(|p| p.to_uppercase()));
+            ctx.output.grid(&headers, report.cells("-"));
+        },
+    }
+    Ok(())
+}
+
 /// Generate schema visualization.
 pub async fn visualize(
     ctx: &Context,
@@ -668,11 +1698,9 @@ pub async fn visualize(
     entity: Option<&str>,
     show_permissions: bool,
 ) -> Result<()> {
-    use std::path::Path;
-
     // Load schema content
-    let content = if Path::new(file).exists() {
-        std::fs::read_to_string(file)?
+    let content = if input::is_local(file) {
+        input::read_to_string(file)?
     } else {
         let client = ctx.client().await?;
         let schemas = client.vault().schemas();
@@ -707,13 +1735,16 @@ pub async fn visualize(
             // Simple ASCII box representation
             if let Some(focus) = entity {
                 println!("Focused on entity: {focus}");
-                println!("┌─────────────────────────────────────────────┐");
-                println!("│ {focus}                                          │");
-                println!("├─────────────────────────────────────────────┤");
+                println!("{}", glyphs::text("┌─────────────────────────────────────────────┐"));
+                println!(
+                    "{}",
+                    glyphs::text(&format!("│ {focus}                                          │"))
+                );
+                println!("{}", glyphs::text("├─────────────────────────────────────────────┤"));
                 if show_permissions {
-                    println!("│ permissions: (use --show-permissions)       │");
+                    println!("{}", glyphs::text("│ permissions: (use --show-permissions)       │"));
                 }
-                println!("└─────────────────────────────────────────────┘");
+                println!("{}", glyphs::text("└─────────────────────────────────────────────┘"));
             } else {
                 println!("(Use --entity <name> to focus on specific entity)");
                 println!();
@@ -763,6 +1794,10 @@ pub async fn copy(
     #[builder(default)] activate: bool,
     #[builder(default)] dry_run: bool,
 ) -> Result<()> {
+    if activate && !dry_run {
+        ensure_signature_not_required(ctx)?;
+    }
+
     let client = ctx.client().await?;
 
     // Determine source vault
@@ -851,8 +1886,6 @@ pub async fn copy(
 
 /// Generate migration plan between schema versions.
 pub async fn migrate(ctx: &Context, from: Option<&str>, to: &str, format: &str) -> Result<()> {
-    use std::path::Path;
-
     ctx.output.info("Generating migration plan...");
     println!();
 
@@ -870,8 +1903,8 @@ pub async fn migrate(ctx: &Context, from: Option<&str>, to: &str, format: &str)
     };
 
     // Get target content
-    let to_content = if Path::new(to).exists() {
-        std::fs::read_to_string(to)?
+    let to_content = if input::is_local(to) {
+        input::read_to_string(to)?
     } else {
         let schema = schemas.get(to).await?;
         schema.content
@@ -912,7 +1945,12 @@ pub async fn migrate(ctx: &Context, from: Option<&str>, to: &str, format: &str)
         },
         _ => {
             // Text format
-            println!("Schema Migration Plan: {} → {}", from.unwrap_or("active"), to);
+            println!(
+                "Schema Migration Plan: {} {} {}",
+                from.unwrap_or("active"),
+                glyphs::ARROW,
+                to
+            );
             println!();
 
             println!("Both schemas validated successfully.");
@@ -937,3 +1975,131 @@ pub async fn migrate(ctx: &Context, from: Option<&str>, to: &str, format: &str)
 
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_label() {
+        assert_eq!(parse_label("team=payments").unwrap(), ("team".into(), "payments".into()));
+        assert_eq!(parse_label(" env = prod ").unwrap(), ("env".into(), "prod".into()));
+        assert!(parse_label("team").is_err());
+        assert!(parse_label("=payments").is_err());
+        assert!(parse_label("team=").is_err());
+    }
+
+    #[test]
+    fn test_annotation_matches() {
+        let mut annotation = SchemaAnnotation::default();
+        annotation.labels.insert("team".into(), "payments".into());
+        annotation.labels.insert("env".into(), "prod".into());
+
+        assert!(annotation.matches(&[]));
+        assert!(annotation.matches(&[("team".into(), "payments".into())]));
+        assert!(!annotation.matches(&[("team".into(), "search".into())]));
+        assert!(
+            !annotation
+                .matches(&[("team".into(), "payments".into()), ("region".into(), "us".into()),])
+        );
+    }
+
+    fn document_matrix() -> PermissionMatrix {
+        let schema = ipl::parse(
+            "entity Document {
+                relations {
+                    owner: User
+                    viewer: User
+                }
+                permissions {
+                    edit: owner
+                    view: edit | viewer
+                }
+            }",
+        )
+        .unwrap();
+        PermissionMatrix::new(schema.entity("Document").unwrap())
+    }
+
+    #[test]
+    fn test_matrix_cells() {
+        let matrix = document_matrix();
+        assert_eq!(matrix.permissions, vec!["edit", "view"]);
+        assert_eq!(
+            matrix.cells("-"),
+            vec![vec!["owner", "direct", "rewrite"], vec!["viewer", "-", "direct"]]
+        );
+    }
+
+    #[test]
+    fn test_matrix_csv_and_html() {
+        let matrix = document_matrix();
+        assert_eq!(matrix.to_csv(), "relation,edit,view\nowner,direct,rewrite\nviewer,,direct\n");
+
+        let html = matrix.to_html();
+        assert!(html.contains("<caption>Document</caption>"));
+        assert!(html.contains("<tr><th>viewer</th><td></td><td class=\"direct\">direct</td></tr>"));
+    }
+
+    #[test]
+    fn test_format_labels() {
+        let mut labels = BTreeMap::new();
+        assert_eq!(format_labels(&labels), "-");
+        labels.insert("team".into(), "payments".into());
+        labels.insert("env".into(), "prod".into());
+        assert_eq!(format_labels(&labels), "env=prod,team=payments");
+    }
+
+    #[test]
+    fn test_signature_verifier_from_args() {
+        assert_eq!(
+            SignatureVerifier::from_args(Some("cosign.pub"), None, None).unwrap(),
+            SignatureVerifier::Key("cosign.pub")
+        );
+        let keyless = SignatureVerifier::from_args(
+            None,
+            Some("ci@example.com"),
+            Some("https://accounts.google.com"),
+        )
+        .unwrap();
+        assert_eq!(
+            keyless.args(),
+            vec![
+                "--certificate-identity",
+                "ci@example.com",
+                "--certificate-oidc-issuer",
+                "https://accounts.google.com"
+            ]
+        );
+        assert!(SignatureVerifier::from_args(None, None, None).is_err());
+        assert!(SignatureVerifier::from_args(None, Some("ci@example.com"), None).is_err());
+    }
+
+    #[test]
+    fn test_signed_digests() {
+        let payload = |digest: &str| {
+            format!(
+                r#"{{"critical":{{"identity":{{"docker-reference":"ghcr.io/acme/schema"}},"image":{{"docker-manifest-digest":"{digest}"}},"type":"cosign container image signature"}},"optional":null}}"#
+            )
+        };
+        let array = format!("[{},{}]", payload("sha256:aa"), payload("sha256:bb"));
+        assert_eq!(signed_digests(&array), vec!["sha256:aa", "sha256:bb"]);
+
+        let lines = format!("{}\n{}\n", payload("sha256:cc"), payload("sha256:dd"));
+        assert_eq!(signed_digests(&lines), vec!["sha256:cc", "sha256:dd"]);
+
+        assert!(signed_digests("Verification for ghcr.io/acme/schema --").is_empty());
+    }
+
+    #[test]
+    fn test_edit_notes() {
+        let content = "entity User {}\nentity Doc {\n";
+        let buffer = with_edit_notes(content, &[(2, "unclosed brace [E001]".to_string())]);
+        let lines: Vec<&str> = buffer.lines().collect();
+        // Two header lines and one error line precede the schema
+        assert_eq!(lines[2], "// !   Line 5: unclosed brace [E001]");
+        assert_eq!(lines[4], "entity Doc {");
+        assert_eq!(strip_edit_notes(&buffer), content);
+    }
+}
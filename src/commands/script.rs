@@ -0,0 +1,637 @@
+//! Scripted multi-step workflows.
+//!
+//! `inferadb run script.yaml` executes a list of steps with the CLI's client,
+//! so admin workflows can be versioned and shared instead of glued together
+//! with shell and `jq`. Steps can store results in variables (`as`), run
+//! conditionally on a variable (`if`, `unless`), and loop over a list
+//! (`for_each`). `{{name}}` in any string is replaced by a variable.
+//!
+//! ```yaml
+//! vars:
+//!   doc: document:readme
+//! steps:
+//!   - list_subjects: { permission: view, resource: "{{doc}}" }
+//!     as: viewers
+//!   - for_each: viewers
+//!     as: viewer
+//!     steps:
+//!       - check: "{{viewer}} can edit {{doc}}"
+//!         as: can_edit
+//!       - add: "{{doc}}#editor@{{viewer}}"
+//!         unless: can_edit
+//!   - print: "Viewers: {{viewers}}"
+//! ```
+
+use std::collections::BTreeMap;
+
+use futures::future::LocalBoxFuture;
+use inferadb::{Relationship, VaultClient};
+use serde::Deserialize;
+
+use super::{interop::parse_relationship_lines, schema_test::parse_check};
+use crate::{
+    client::Context,
+    error::{Error, Result},
+    input,
+    output::theme::Role,
+};
+
+/// A script file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Script {
+    /// Initial variables, overridable with `--var`.
+    #[serde(default)]
+    vars: BTreeMap<String, String>,
+    steps: Vec<Step>,
+}
+
+/// One step. Exactly one action field is set.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Step {
+    /// Shown instead of the action when the step runs.
+    name: Option<String>,
+    /// Run only when this variable is truthy.
+    #[serde(rename = "if")]
+    when: Option<String>,
+    /// Run only when this variable is falsy.
+    unless: Option<String>,
+    /// Variable that receives the step's result (the loop variable for
+    /// `for_each`).
+    #[serde(rename = "as")]
+    store: Option<String>,
+
+    /// `subject can permission resource`; stores `true` or `false`.
+    check: Option<String>,
+    /// `resource#relation@subject` to write.
+    add: Option<String>,
+    /// `resource#relation@subject` to delete.
+    delete: Option<String>,
+    /// Subjects with a permission on a resource; stores a list.
+    list_subjects: Option<ListSubjects>,
+    /// Resources a subject has a permission on; stores a list.
+    list_resources: Option<ListResources>,
+    /// Relationships as `resource#relation@subject`; stores a list.
+    list_relationships: Option<ListRelationships>,
+    /// Text to print.
+    print: Option<String>,
+    /// Variables to set.
+    set: Option<BTreeMap<String, String>>,
+    /// Stop the script with this error.
+    fail: Option<String>,
+    /// List variable to loop over, running `steps` for each item.
+    for_each: Option<String>,
+    #[serde(default)]
+    steps: Vec<Self>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListSubjects {
+    permission: String,
+    resource: String,
+    #[serde(rename = "type")]
+    subject_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListResources {
+    subject: String,
+    permission: String,
+    #[serde(rename = "type")]
+    resource_type: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListRelationships {
+    resource: Option<String>,
+    relation: Option<String>,
+    subject: Option<String>,
+}
+
+impl Step {
+    /// Names of the action fields that are set.
+    fn actions(&self) -> Vec<&'static str> {
+        [
+            ("check", self.check.is_some()),
+            ("add", self.add.is_some()),
+            ("delete", self.delete.is_some()),
+            ("list_subjects", self.list_subjects.is_some()),
+            ("list_resources", self.list_resources.is_some()),
+            ("list_relationships", self.list_relationships.is_some()),
+            ("print", self.print.is_some()),
+            ("set", self.set.is_some()),
+            ("fail", self.fail.is_some()),
+            ("for_each", self.for_each.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+}
+
+/// A variable's value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Text(String),
+    Bool(bool),
+    List(Vec<String>),
+}
+
+impl Value {
+    fn is_truthy(&self) -> bool {
+        match self {
+            Self::Text(text) => !text.is_empty() && text != "false",
+            Self::Bool(value) => *value,
+            Self::List(items) => !items.is_empty(),
+        }
+    }
+
+    fn render(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Bool(value) => value.to_string(),
+            Self::List(items) => items.join(", "),
+        }
+    }
+}
+
+/// Check that every step has exactly one action and valid options, before
+/// anything runs.
+fn validate(steps: &[Step], path: &str) -> Result<()> {
+    for (index, step) in steps.iter().enumerate() {
+        let at = format!("{path}{}", index + 1);
+        let invalid = |message: &str| Err(Error::parse(format!("step {at}: {message}")));
+        let actions = step.actions();
+        match actions.as_slice() {
+            [] => return invalid("no action (e.g., check, add, print, for_each)"),
+            [_] => {},
+            _ => return invalid(&format!("more than one action ({})", actions.join(", "))),
+        }
+        if step.for_each.is_some() {
+            if step.store.is_none() {
+                return invalid("for_each needs 'as' to name the loop variable");
+            }
+            validate(&step.steps, &format!("{at}."))?;
+        } else if !step.steps.is_empty() {
+            return invalid("'steps' is only allowed with for_each");
+        }
+        let stores = step.check.is_some()
+            || step.list_subjects.is_some()
+            || step.list_resources.is_some()
+            || step.list_relationships.is_some()
+            || step.for_each.is_some();
+        if step.store.is_some() && !stores {
+            return invalid(&format!("'{}' has no result to store with 'as'", actions[0]));
+        }
+    }
+    Ok(())
+}
+
+/// Replace `{{name}}` with variables.
+fn interpolate(template: &str, vars: &BTreeMap<String, Value>) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| Error::parse(format!("Unclosed '{{{{' in '{template}'")))?;
+        let name = rest[start + 2..start + end].trim();
+        let value = vars
+            .get(name)
+            .ok_or_else(|| Error::parse(format!("Unknown variable '{name}' in '{template}'")))?;
+        out.push_str(&value.render());
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Split a `resource#relation@subject` line.
+fn parse_tuple(line: &str) -> Result<(String, String, String)> {
+    let mut parsed = parse_relationship_lines(line)?;
+    match (parsed.pop(), parsed.is_empty()) {
+        (Some(rel), true) => Ok((rel.resource, rel.relation, rel.subject)),
+        _ => Err(Error::parse(format!("Expected one resource#relation@subject, got '{line}'"))),
+    }
+}
+
+/// What steps run against.
+trait Backend {
+    async fn check(&self, subject: &str, permission: &str, resource: &str) -> Result<bool>;
+    async fn list_subjects(
+        &self,
+        resource: &str,
+        permission: &str,
+        subject_type: Option<&str>,
+    ) -> Result<Vec<String>>;
+    async fn list_resources(
+        &self,
+        subject: &str,
+        permission: &str,
+        resource_type: Option<&str>,
+    ) -> Result<Vec<String>>;
+    async fn list_relationships(
+        &self,
+        resource: Option<&str>,
+        relation: Option<&str>,
+        subject: Option<&str>,
+    ) -> Result<Vec<String>>;
+    async fn write(&self, resource: &str, relation: &str, subject: &str) -> Result<()>;
+    async fn delete(&self, resource: &str, relation: &str, subject: &str) -> Result<()>;
+    /// Print a `print` step's text.
+    fn print(&self, text: &str);
+    /// Report progress.
+    fn log(&self, role: Role, message: &str);
+}
+
+struct Runner<'a, B> {
+    backend: &'a B,
+    vars: BTreeMap<String, Value>,
+    dry_run: bool,
+    /// Steps run so far, including loop bodies.
+    executed: usize,
+}
+
+impl<B: Backend> Runner<'_, B> {
+    fn run<'s>(&'s mut self, steps: &'s [Step]) -> LocalBoxFuture<'s, Result<()>> {
+        Box::pin(async move {
+            for step in steps {
+                self.step(step).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn text(&self, template: &str) -> Result<String> {
+        interpolate(template, &self.vars)
+    }
+
+    fn opt(&self, template: Option<&String>) -> Result<Option<String>> {
+        template.map(|t| self.text(t)).transpose()
+    }
+
+    fn var(&self, name: &str) -> Result<&Value> {
+        self.vars.get(name).ok_or_else(|| Error::parse(format!("Unknown variable '{name}'")))
+    }
+
+    async fn step(&mut self, step: &Step) -> Result<()> {
+        if let Some(name) = &step.when
+            && !self.var(name)?.is_truthy()
+        {
+            return Ok(());
+        }
+        if let Some(name) = &step.unless
+            && self.var(name)?.is_truthy()
+        {
+            return Ok(());
+        }
+        self.executed += 1;
+        if let Some(name) = &step.name {
+            self.backend.log(Role::Info, &self.text(name)?);
+        }
+
+        let result = if let Some(check) = &step.check {
+            let check = self.text(check)?;
+            let (subject, permission, resource) = parse_check(&check)?;
+            let allowed = self.backend.check(subject, permission, resource).await?;
+            let decision = if allowed { "allow" } else { "deny" };
+            self.backend.log(Role::Info, &format!("check {check}: {decision}"));
+            Some(Value::Bool(allowed))
+        } else if let Some(add) = &step.add {
+            let (resource, relation, subject) = parse_tuple(&self.text(add)?)?;
+            let tuple = format!("{resource}#{relation}@{subject}");
+            if self.dry_run {
+                self.backend.log(Role::Info, &format!("Would add {tuple}"));
+            } else {
+                self.backend.write(&resource, &relation, &subject).await?;
+                self.backend.log(Role::Success, &format!("Added {tuple}"));
+            }
+            None
+        } else if let Some(delete) = &step.delete {
+            let (resource, relation, subject) = parse_tuple(&self.text(delete)?)?;
+            let tuple = format!("{resource}#{relation}@{subject}");
+            if self.dry_run {
+                self.backend.log(Role::Info, &format!("Would delete {tuple}"));
+            } else {
+                self.backend.delete(&resource, &relation, &subject).await?;
+                self.backend.log(Role::Success, &format!("Deleted {tuple}"));
+            }
+            None
+        } else if let Some(list) = &step.list_subjects {
+            let (resource, permission) = (self.text(&list.resource)?, self.text(&list.permission)?);
+            let subject_type = self.opt(list.subject_type.as_ref())?;
+            let subjects =
+                self.backend.list_subjects(&resource, &permission, subject_type.as_deref()).await?;
+            self.backend.log(
+                Role::Info,
+                &format!("{} subjects can {permission} {resource}", subjects.len()),
+            );
+            Some(Value::List(subjects))
+        } else if let Some(list) = &step.list_resources {
+            let (subject, permission) = (self.text(&list.subject)?, self.text(&list.permission)?);
+            let resource_type = self.opt(list.resource_type.as_ref())?;
+            let resources = self
+                .backend
+                .list_resources(&subject, &permission, resource_type.as_deref())
+                .await?;
+            self.backend.log(
+                Role::Info,
+                &format!("{subject} can {permission} {} resources", resources.len()),
+            );
+            Some(Value::List(resources))
+        } else if let Some(list) = &step.list_relationships {
+            let resource = self.opt(list.resource.as_ref())?;
+            let relation = self.opt(list.relation.as_ref())?;
+            let subject = self.opt(list.subject.as_ref())?;
+            let tuples = self
+                .backend
+                .list_relationships(resource.as_deref(), relation.as_deref(), subject.as_deref())
+                .await?;
+            self.backend.log(Role::Info, &format!("{} relationships", tuples.len()));
+            Some(Value::List(tuples))
+        } else if let Some(text) = &step.print {
+            self.backend.print(&self.text(text)?);
+            None
+        } else if let Some(set) = &step.set {
+            for (name, value) in set {
+                let value = Value::Text(self.text(value)?);
+                self.vars.insert(name.clone(), value);
+            }
+            None
+        } else if let Some(message) = &step.fail {
+            return Err(Error::other(self.text(message)?));
+        } else if let Some(list) = &step.for_each {
+            let items = match self.var(list)? {
+                Value::List(items) => items.clone(),
+                other => {
+                    return Err(Error::parse(format!(
+                        "for_each needs a list, but '{list}' is '{}'",
+                        other.render()
+                    )));
+                },
+            };
+            let name = step.store.clone().unwrap_or_default();
+            for item in items {
+                self.vars.insert(name.clone(), Value::Text(item));
+                self.run(&step.steps).await?;
+            }
+            return Ok(());
+        } else {
+            None
+        };
+
+        if let (Some(name), Some(value)) = (&step.store, result) {
+            self.vars.insert(name.clone(), value);
+        }
+        Ok(())
+    }
+}
+
+/// The vault, for real runs.
+struct Vault<'a> {
+    ctx: &'a Context,
+    vault: VaultClient,
+}
+
+impl Backend for Vault<'_> {
+    async fn check(&self, subject: &str, permission: &str, resource: &str) -> Result<bool> {
+        Ok(self.vault.check(subject, permission, resource).await?)
+    }
+
+    async fn list_subjects(
+        &self,
+        resource: &str,
+        permission: &str,
+        subject_type: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let mut query = self.vault.subjects().with_permission(permission).on_resource(resource);
+        if let Some(st) = subject_type {
+            query = query.subject_type(st);
+        }
+        Ok(query.collect().await?)
+    }
+
+    async fn list_resources(
+        &self,
+        subject: &str,
+        permission: &str,
+        resource_type: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let mut query = self.vault.resources().accessible_by(subject).with_permission(permission);
+        if let Some(rt) = resource_type {
+            query = query.resource_type(rt);
+        }
+        Ok(query.collect().await?)
+    }
+
+    async fn list_relationships(
+        &self,
+        resource: Option<&str>,
+        relation: Option<&str>,
+        subject: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let mut tuples = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut request = self.vault.relationships().list().limit(1000);
+            if let Some(r) = resource {
+                request = request.resource(r);
+            }
+            if let Some(rel) = relation {
+                request = request.relation(rel);
+            }
+            if let Some(s) = subject {
+                request = request.subject(s);
+            }
+            if let Some(c) = &cursor {
+                request = request.cursor(c);
+            }
+            let page = request.await?;
+            tuples.extend(
+                page.relationships
+                    .iter()
+                    .map(|rel| format!("{}#{}@{}", rel.resource(), rel.relation(), rel.subject())),
+            );
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                return Ok(tuples);
+            }
+        }
+    }
+
+    async fn write(&self, resource: &str, relation: &str, subject: &str) -> Result<()> {
+        self.vault.relationships().write(Relationship::new(resource, relation, subject)).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, resource: &str, relation: &str, subject: &str) -> Result<()> {
+        self.vault.relationships().delete(Relationship::new(resource, relation, subject)).await?;
+        Ok(())
+    }
+
+    fn print(&self, text: &str) {
+        println!("{text}");
+    }
+
+    fn log(&self, role: Role, message: &str) {
+        match role {
+            Role::Success => self.ctx.output.success(message),
+            Role::Warning => self.ctx.output.warn(message),
+            Role::Error => self.ctx.output.error(message),
+            Role::Info => self.ctx.output.info(message),
+        }
+    }
+}
+
+/// Run a script file, with `vars` (`name=value`) overriding its variables.
+pub async fn run(ctx: &Context, file: &str, vars: &[String], dry_run: bool) -> Result<()> {
+    let content = input::read_to_string(file)
+        .map_err(|e| Error::invalid_arg(format!("Could not read {file}: {e}")))?;
+    let script: Script =
+        serde_yaml::from_str(&content).map_err(|e| Error::parse(format!("{file}: {e}")))?;
+    validate(&script.steps, "").map_err(|e| Error::parse(format!("{file}: {e}")))?;
+
+    let mut values: BTreeMap<String, Value> =
+        script.vars.into_iter().map(|(name, value)| (name, Value::Text(value))).collect();
+    for var in vars {
+        let (name, value) = var.split_once('=').ok_or_else(|| {
+            Error::invalid_arg(format!("Invalid --var '{var}'. Expected: name=value"))
+        })?;
+        values.insert(name.to_string(), Value::Text(value.to_string()));
+    }
+
+    let client = ctx.client().await?;
+    let backend = Vault { ctx, vault: client.vault() };
+    let mut runner = Runner { backend: &backend, vars: values, dry_run, executed: 0 };
+    runner.run(&script.steps).await?;
+
+    let mode = if dry_run { " (dry run)" } else { "" };
+    ctx.output.success(&format!("Ran {} steps from {file}{mode}", runner.executed));
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// Backend with fixed answers that records what the script did.
+    #[derive(Default)]
+    struct Fake {
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl Backend for Fake {
+        async fn check(&self, subject: &str, _: &str, _: &str) -> Result<bool> {
+            Ok(subject == "user:alice")
+        }
+
+        async fn list_subjects(&self, _: &str, _: &str, _: Option<&str>) -> Result<Vec<String>> {
+            Ok(vec!["user:alice".to_string(), "user:bob".to_string()])
+        }
+
+        async fn list_resources(&self, _: &str, _: &str, _: Option<&str>) -> Result<Vec<String>> {
+            Ok(Vec::new())
+        }
+
+        async fn list_relationships(
+            &self,
+            _: Option<&str>,
+            _: Option<&str>,
+            _: Option<&str>,
+        ) -> Result<Vec<String>> {
+            Ok(Vec::new())
+        }
+
+        async fn write(&self, resource: &str, relation: &str, subject: &str) -> Result<()> {
+            self.calls.borrow_mut().push(format!("add {resource}#{relation}@{subject}"));
+            Ok(())
+        }
+
+        async fn delete(&self, resource: &str, relation: &str, subject: &str) -> Result<()> {
+            self.calls.borrow_mut().push(format!("delete {resource}#{relation}@{subject}"));
+            Ok(())
+        }
+
+        fn print(&self, text: &str) {
+            self.calls.borrow_mut().push(format!("print {text}"));
+        }
+
+        fn log(&self, _: Role, _: &str) {}
+    }
+
+    const SCRIPT: &str = r#"
+vars:
+  doc: document:readme
+steps:
+  - list_subjects: { permission: view, resource: "{{doc}}" }
+    as: viewers
+  - for_each: viewers
+    as: viewer
+    steps:
+      - check: "{{viewer}} can edit {{doc}}"
+        as: can_edit
+      - add: "{{doc}}#editor@{{viewer}}"
+        unless: can_edit
+  - print: "Viewers: {{ viewers }}"
+"#;
+
+    async fn run_script(script: &str, dry_run: bool) -> Result<Vec<String>> {
+        let script: Script = serde_yaml::from_str(script).unwrap();
+        validate(&script.steps, "")?;
+        let fake = Fake::default();
+        let vars = script.vars.into_iter().map(|(k, v)| (k, Value::Text(v))).collect();
+        let mut runner = Runner { backend: &fake, vars, dry_run, executed: 0 };
+        runner.run(&script.steps).await?;
+        Ok(fake.calls.take())
+    }
+
+    #[tokio::test]
+    async fn test_run_loops_and_conditions() {
+        assert_eq!(
+            run_script(SCRIPT, false).await.unwrap(),
+            ["add document:readme#editor@user:bob", "print Viewers: user:alice, user:bob"]
+        );
+        assert_eq!(
+            run_script(SCRIPT, true).await.unwrap(),
+            ["print Viewers: user:alice, user:bob"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fail_and_unknown_variable() {
+        let failing = "steps:\n  - set: { reason: stale }\n  - fail: \"gave up: {{reason}}\"\n";
+        assert_eq!(run_script(failing, false).await.unwrap_err().to_string(), "gave up: stale");
+        let unknown = "steps:\n  - print: \"{{missing}}\"\n";
+        assert!(run_script(unknown, false).await.unwrap_err().to_string().contains("missing"));
+    }
+
+    #[tokio::test]
+    async fn test_validate() {
+        let both = "steps:\n  - print: hi\n    fail: no\n";
+        assert!(run_script(both, false).await.unwrap_err().to_string().contains("more than one"));
+        let no_as = "steps:\n  - for_each: items\n    steps:\n      - print: hi\n";
+        assert!(run_script(no_as, false).await.unwrap_err().to_string().contains("'as'"));
+        let nested = "steps:\n  - for_each: items\n    as: item\n    steps:\n      - {}\n";
+        assert!(run_script(nested, false).await.unwrap_err().to_string().contains("step 1.1"));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let vars = BTreeMap::from([
+            ("doc".to_string(), Value::Text("document:1".to_string())),
+            ("ok".to_string(), Value::Bool(false)),
+        ]);
+        assert_eq!(interpolate("{{doc}} {{ ok }}", &vars).unwrap(), "document:1 false");
+        assert!(interpolate("{{doc", &vars).is_err());
+        assert!(!Value::Text("false".to_string()).is_truthy());
+        assert!(!Value::List(Vec::new()).is_truthy());
+    }
+}
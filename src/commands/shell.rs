@@ -1,259 +1,978 @@
 //! Interactive shell (REPL) for `InferaDB`.
+//!
+//! On a terminal, input is read with a line editor: up and down walk the
+//! history, which persists in the state directory (`shell_history`), and tab
+//! completes commands and the entity IDs seen so far (in the history, in
+//! statements, and in listed relationships). `:output <format>` switches the
+//! format of results for the rest of the session, and `source <file>` runs
+//! the statements in a script file.
 
-use std::io::{self, BufRead, Write};
+use std::{
+    collections::BTreeSet,
+    fmt::Write as _,
+    fs::OpenOptions,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use crate::{client::Context, error::Result};
+use futures::future::LocalBoxFuture;
+use serde::Serialize;
+
+use crate::{
+    client::Context,
+    config::{Config, StateLock},
+    error::{Error, Result},
+    input,
+    output::{OutputFormat, cast::CastRecorder},
+    tui::{
+        self,
+        line_editor::{self, CompleteFn, LineEditor, LineResult},
+    },
+};
+
+/// Prompt shown before each statement.
+const PROMPT: &str = "inferadb> ";
+
+/// Prompt shown while a statement continues onto the next line.
+const CONTINUATION_PROMPT: &str = "     ...> ";
+
+/// Statements offered by tab completion.
+const COMMANDS: &[&str] =
+    &["help", "check", "add", "delete", "list", "status", "source", ":output", "clear", "exit"];
+
+/// Formats `:output` accepts.
+const FORMATS: &[&str] = &["table", "json", "yaml", "jsonl"];
+
+/// History entries kept.
+const HISTORY_LIMIT: usize = 1000;
+
+/// How deeply `source` may nest, so a script sourcing itself stops.
+const MAX_SOURCE_DEPTH: usize = 8;
 
 /// Start an interactive shell.
-pub async fn shell(ctx: &Context) -> Result<()> {
-    println!("InferaDB Interactive Shell");
-    println!("Type 'help' for available commands, 'exit' to quit.");
-    println!();
+///
+/// With `record`, the session's output (including the commands typed) is
+/// saved as an asciinema recording, with `redact` literals masked in addition
+/// to the built-in secret patterns.
+pub async fn shell(ctx: &Context, record: Option<&str>, redact: &[String]) -> Result<()> {
+    let recorder = record
+        .map(|path| {
+            let title = format!("inferadb shell ({})", ctx.effective_profile_name());
+            CastRecorder::create(Path::new(path), &title, redact.to_vec())
+        })
+        .transpose()?;
+    let mut shell = Shell::new(ctx, Console { recorder });
+
+    // Scripts piped to stdin get plain line reading and leave no history
+    let interactive = tui::is_interactive(ctx) && io::stdin().is_terminal();
+    let mut history = if interactive { History::load() } else { History::default() };
+    for entry in &history.entries {
+        shell.learn(entry);
+    }
+
+    shell.out.line("InferaDB Interactive Shell");
+    shell.out.line("Type 'help' for available commands, 'exit' to quit.");
+    shell.out.line("");
+
+    let mut buffer = InputBuffer::default();
+
+    loop {
+        let prompt = if buffer.text.is_empty() { PROMPT } else { CONTINUATION_PROMPT };
+        let line = if interactive {
+            let editor = LineEditor::builder()
+                .prompt(prompt)
+                .history(history.entries.clone())
+                .complete(shell.completer())
+                .build();
+            match line_editor::read_line(editor) {
+                Ok(LineResult::Line(line)) => {
+                    shell.out.record(&format!("{prompt}{line}\n"));
+                    line
+                },
+                Ok(LineResult::Interrupted) => {
+                    buffer = InputBuffer::default();
+                    continue;
+                },
+                Ok(LineResult::Eof) => break,
+                Err(e) => {
+                    ctx.output.error(&format!("Read error: {e}"));
+                    break;
+                },
+            }
+        } else {
+            shell.out.print(prompt);
+            match read_stdin_line() {
+                Some(Ok(line)) => {
+                    shell.out.echo(&line);
+                    line
+                },
+                Some(Err(e)) => {
+                    ctx.output.error(&format!("Read error: {e}"));
+                    break;
+                },
+                None => break,
+            }
+        };
+
+        let Some(input) = buffer.push(&line) else {
+            continue;
+        };
+        if interactive {
+            history.add(&input);
+        }
+
+        let keep_going = if input.trim() == "\\e" {
+            shell.run_editor().await
+        } else {
+            if !input.trim().is_empty() {
+                shell.last.clone_from(&input);
+            }
+            shell.execute(&input).await
+        };
+        if !keep_going {
+            break;
+        }
+    }
+
+    if let Some(recorder) = shell.out.recorder.take() {
+        recorder.finish()?;
+        if let Some(path) = record {
+            ctx.output.info(&format!("Session recorded to {path}"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Statements typed in earlier sessions, saved in the state directory.
+#[derive(Debug, Default)]
+struct History {
+    path: Option<PathBuf>,
+    /// Oldest first.
+    entries: Vec<String>,
+}
+
+impl History {
+    /// Load the saved history, trimming the file to [`HISTORY_LIMIT`] entries.
+    fn load() -> Self {
+        let path = Config::state_dir().map(|dir| dir.join("shell_history"));
+        // Trim only while holding the lock, so another shell's entries survive
+        let lock = path.as_deref().map(|path| StateLock::acquire(path, "the shell history"));
+        let mut entries: Vec<String> = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|text| text.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        if entries.len() > HISTORY_LIMIT {
+            entries.drain(..entries.len() - HISTORY_LIMIT);
+            if let (Some(path), Some(Ok(_))) = (&path, &lock) {
+                let _ = std::fs::write(path, entries.join("\n") + "\n");
+            }
+        }
+        Self { path, entries }
+    }
+
+    /// Add a statement (on one line), unless it repeats the last one.
+    fn add(&mut self, statement: &str) {
+        let entry = statement.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+        if entry.is_empty() || self.entries.last() == Some(&entry) {
+            return;
+        }
+        if let Some(path) = &self.path
+            && let Err(e) = append_line(path, &entry)
+        {
+            tracing::debug!(error = %e, "failed to save shell history");
+        }
+        self.entries.push(entry);
+    }
+}
+
+/// Read one line of a piped script, without its line ending, or `None` at
+/// the end of input.
+fn read_stdin_line() -> Option<io::Result<String>> {
+    let mut line = String::new();
+    match io::stdin().read_line(&mut line) {
+        Ok(0) => None,
+        Ok(_) => {
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+            Some(Ok(line))
+        },
+        Err(e) => Some(Err(e)),
+    }
+}
+
+fn append_line(path: &Path, line: &str) -> Result<()> {
+    let _lock = StateLock::acquire(path, "the shell history")?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")?;
+    Ok(())
+}
+
+/// Words in `text` that look like entity IDs (`type:id`, optionally with a
+/// `#relation`).
+fn entity_ids(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | ',' | '{' | '}' | '[' | ']'))
+        .filter(|word| {
+            word.split_once(':').is_some_and(|(kind, id)| {
+                !kind.is_empty()
+                    && kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                    && !id.is_empty()
+                    && !id.starts_with("//")
+            })
+        })
+}
+
+/// Completion candidates for the word ending `before` (the line up to the
+/// cursor): commands for the first word, formats after `:output`, and known
+/// entity IDs (or their types, for `list`) after other commands.
+fn candidates(before: &str, known: &BTreeSet<String>) -> Vec<String> {
+    let mut words: Vec<&str> = before.split_whitespace().collect();
+    if !before.ends_with(char::is_whitespace) {
+        words.pop();
+    }
+    match words.first().copied() {
+        None => COMMANDS.iter().map(ToString::to_string).collect(),
+        Some(":output") => FORMATS.iter().map(ToString::to_string).collect(),
+        Some("list" | "ls") => known
+            .iter()
+            .filter_map(|id| id.split_once(':').map(|(kind, _)| kind.to_string()))
+            .collect(),
+        Some("check" | "add" | "write" | "delete" | "rm") => known.iter().cloned().collect(),
+        Some(_) => Vec::new(),
+    }
+}
+
+/// Shell session state.
+struct Shell<'a> {
+    ctx: &'a Context,
+    out: Console,
+    /// Format of results, switched with `:output`.
+    format: OutputFormat,
+    /// Entity IDs seen this session, for completion.
+    known: BTreeSet<String>,
+    /// Last statement, for `\e`.
+    last: String,
+    /// Nesting of `source`.
+    depth: usize,
+}
+
+impl<'a> Shell<'a> {
+    const fn new(ctx: &'a Context, out: Console) -> Self {
+        let format = match ctx.output.format() {
+            format @ (OutputFormat::Json | OutputFormat::Yaml | OutputFormat::JsonLines) => format,
+            _ => OutputFormat::Table,
+        };
+        Self { ctx, out, format, known: BTreeSet::new(), last: String::new(), depth: 0 }
+    }
+
+    /// Remember the entity IDs in `text`.
+    fn learn(&mut self, text: &str) {
+        self.known.extend(entity_ids(text).map(str::to_string));
+    }
+
+    fn completer(&self) -> CompleteFn {
+        let known = self.known.clone();
+        Arc::new(move |before: &str| candidates(before, &known))
+    }
+
+    /// Run one statement. Returns `false` when the shell should exit.
+    fn execute<'s>(&'s mut self, input: &'s str) -> LocalBoxFuture<'s, bool> {
+        Box::pin(async move {
+            self.learn(input);
+            let parts = split_args(input);
+            let Some(command) = parts.first() else {
+                return true;
+            };
+            let args: Vec<&str> = parts[1..].iter().map(String::as_str).collect();
+
+            match command.as_str() {
+                comment if comment.starts_with('#') => {},
+                "help" | "?" => {
+                    print_help(&mut self.out);
+                },
+                "exit" | "quit" | "q" => {
+                    self.out.line("Goodbye!");
+                    return false;
+                },
+                "check" => {
+                    if let [subject, permission, resource, rest @ ..] = args.as_slice() {
+                        let context = if rest.is_empty() { None } else { Some(rest.join(" ")) };
+                        self.check(subject, permission, resource, context.as_deref()).await;
+                    } else {
+                        self.out
+                            .line("Usage: check <subject> <permission> <resource> [context-json]");
+                    }
+                },
+                "add" | "write" => {
+                    if args.is_empty() || !args.len().is_multiple_of(3) {
+                        self.out.line("Usage: add <subject> <relation> <resource> [<subject> <relation> <resource> ...]");
+                    } else {
+                        for tuple in args.chunks(3) {
+                            self.add(tuple[0], tuple[1], tuple[2]).await;
+                        }
+                    }
+                },
+                "delete" | "rm" => {
+                    if args.len() < 3 {
+                        self.out.line("Usage: delete <subject> <relation> <resource>");
+                    } else {
+                        self.delete(args[0], args[1], args[2]).await;
+                    }
+                },
+                "list" | "ls" => {
+                    self.list(args.first().copied()).await;
+                },
+                "status" => {
+                    self.status().await;
+                },
+                "source" | "." => {
+                    if let [path] = args.as_slice() {
+                        return self.source(path).await;
+                    }
+                    self.out.line("Usage: source <file>");
+                },
+                ":output" => {
+                    self.set_format(args.first().copied());
+                },
+                "clear" => {
+                    // ANSI escape to clear screen
+                    self.out.print("\x1B[2J\x1B[1;1H");
+                },
+                cmd => {
+                    self.out.line(&format!(
+                        "Unknown command: {cmd}. Type 'help' for available commands."
+                    ));
+                },
+            }
+            true
+        })
+    }
 
-    let stdin = io::stdin();
-    let reader = stdin.lock();
+    /// Edit the last statement in `$EDITOR` and run the saved buffer.
+    ///
+    /// Like psql's `\e`: the buffer may hold several statements, which run in
+    /// order; the edited text becomes the new last statement.
+    async fn run_editor(&mut self) -> bool {
+        let edited = match edit_in_editor(&self.last, "shell", "txt") {
+            Ok(edited) => edited,
+            Err(e) => {
+                self.out.line(&format!("Editor error: {e}"));
+                return true;
+            },
+        };
+        if edited.trim().is_empty() {
+            self.out.line("Buffer empty, nothing to run.");
+            return true;
+        }
 
-    print_prompt();
+        self.last.clone_from(&edited);
+        self.run_statements(&edited).await
+    }
 
-    for line in reader.lines() {
-        let line = match line {
-            Ok(l) => l,
+    /// Run the statements in a script file, like `\e` runs its buffer.
+    async fn source(&mut self, path: &str) -> bool {
+        if self.depth == MAX_SOURCE_DEPTH {
+            self.out.line(&format!("Error: source nested more than {MAX_SOURCE_DEPTH} deep"));
+            return true;
+        }
+        let text = match input::read_to_string(path) {
+            Ok(text) => text,
             Err(e) => {
-                ctx.output.error(&format!("Read error: {e}"));
-                break;
+                self.out.line(&format!("Error: could not read {path}: {e}"));
+                return true;
             },
         };
 
-        let line = line.trim();
-        if line.is_empty() {
-            print_prompt();
-            continue;
+        self.depth += 1;
+        let keep_going = self.run_statements(&text).await;
+        self.depth -= 1;
+        keep_going
+    }
+
+    /// Echo and run each statement in `text`, stopping at `exit`.
+    async fn run_statements(&mut self, text: &str) -> bool {
+        for statement in statements(text) {
+            self.out.line(&format!("{PROMPT}{statement}"));
+            if !self.execute(&statement).await {
+                return false;
+            }
         }
+        true
+    }
 
-        // Parse command
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.is_empty() {
-            print_prompt();
-            continue;
+    /// Show or switch the format of results.
+    fn set_format(&mut self, name: Option<&str>) {
+        let Some(name) = name else {
+            self.out.line(&format!("Output format: {}", format_name(self.format)));
+            return;
+        };
+        match OutputFormat::parse(name) {
+            Ok(
+                format @ (OutputFormat::Table
+                | OutputFormat::Json
+                | OutputFormat::Yaml
+                | OutputFormat::JsonLines),
+            ) => {
+                self.format = format;
+                self.out.line(&format!("Output format: {}", format_name(format)));
+            },
+            _ => self
+                .out
+                .line(&format!("Unknown output format '{name}'. Use: {}", FORMATS.join(", "))),
         }
+    }
 
-        match parts[0] {
-            "help" | "?" => {
-                print_help();
+    /// Print a result in the session's format, using `table` for tables.
+    fn emit<T: Serialize>(&mut self, value: &T, table: impl FnOnce(&mut Console)) {
+        let rendered = match self.format {
+            OutputFormat::Json => {
+                serde_json::to_string_pretty(value).map(|json| json + "\n").map_err(Error::from)
             },
-            "exit" | "quit" | "q" => {
-                println!("Goodbye!");
-                break;
+            OutputFormat::Yaml => serde_yaml::to_string(value).map_err(Error::from),
+            OutputFormat::JsonLines => {
+                serde_json::to_value(value).map_err(Error::from).map(|value| match value {
+                    serde_json::Value::Array(items) => {
+                        items.iter().fold(String::new(), |mut out, item| {
+                            let _ = writeln!(out, "{item}");
+                            out
+                        })
+                    },
+                    other => format!("{other}\n"),
+                })
             },
-            "check" => {
-                if parts.len() < 4 {
-                    println!("Usage: check <subject> <permission> <resource>");
-                } else {
-                    execute_check(ctx, parts[1], parts[2], parts[3]).await;
+            _ => {
+                table(&mut self.out);
+                return;
+            },
+        };
+        match rendered {
+            Ok(text) => self.out.print(&text),
+            Err(e) => self.out.line(&format!("Error: {e}")),
+        }
+    }
+
+    async fn check(
+        &mut self,
+        subject: &str,
+        permission: &str,
+        resource: &str,
+        context: Option<&str>,
+    ) {
+        #[derive(Serialize)]
+        struct CheckResult<'r> {
+            subject: &'r str,
+            permission: &'r str,
+            resource: &'r str,
+            allowed: bool,
+        }
+
+        let abac_context = match context.map(super::check::parse_context).transpose() {
+            Ok(abac_context) => abac_context,
+            Err(e) => {
+                self.out.line(&format!("Error: {e}"));
+                return;
+            },
+        };
+        match self.ctx.client().await {
+            Ok(client) => {
+                let vault = client.vault();
+                let mut request = vault.check(subject, permission, resource);
+                if let Some(abac) = abac_context {
+                    request = request.with_context(abac);
+                }
+                match request.await {
+                    Ok(allowed) => {
+                        let result = CheckResult { subject, permission, resource, allowed };
+                        self.emit(&result, |out| {
+                            if allowed {
+                                out.line(&format!(
+                                    "ALLOWED: {subject} can {permission} {resource}"
+                                ));
+                            } else {
+                                out.line(&format!(
+                                    "DENIED: {subject} cannot {permission} {resource}"
+                                ));
+                            }
+                        });
+                    },
+                    Err(e) => {
+                        self.out.line(&format!("Error: {e}"));
+                    },
                 }
             },
-            "add" | "write" => {
-                if parts.len() < 4 {
-                    println!("Usage: add <subject> <relation> <resource>");
-                } else {
-                    execute_add(ctx, parts[1], parts[2], parts[3]).await;
+            Err(e) => {
+                self.out.line(&format!("Connection error: {e}"));
+            },
+        }
+    }
+
+    async fn add(&mut self, subject: &str, relation: &str, resource: &str) {
+        match self.ctx.client().await {
+            Ok(client) => {
+                let vault = client.vault();
+                let rels = vault.relationships();
+                let relationship = inferadb::Relationship::new(resource, relation, subject);
+                match rels.write(relationship).await {
+                    Ok(_) => {
+                        self.out.line(&format!("Added: {subject} -[{relation}]-> {resource}"));
+                    },
+                    Err(e) => {
+                        self.out.line(&format!("Error: {e}"));
+                    },
                 }
             },
-            "delete" | "rm" => {
-                if parts.len() < 4 {
-                    println!("Usage: delete <subject> <relation> <resource>");
-                } else {
-                    execute_delete(ctx, parts[1], parts[2], parts[3]).await;
+            Err(e) => {
+                self.out.line(&format!("Connection error: {e}"));
+            },
+        }
+    }
+
+    async fn delete(&mut self, subject: &str, relation: &str, resource: &str) {
+        match self.ctx.client().await {
+            Ok(client) => {
+                let vault = client.vault();
+                let rels = vault.relationships();
+                let relationship = inferadb::Relationship::new(resource, relation, subject);
+                match rels.delete(relationship).await {
+                    Ok(()) => {
+                        self.out.line(&format!("Deleted: {subject} -[{relation}]-> {resource}"));
+                    },
+                    Err(e) => {
+                        self.out.line(&format!("Error: {e}"));
+                    },
                 }
             },
-            "list" | "ls" => {
-                execute_list(ctx, parts.get(1).copied()).await;
+            Err(e) => {
+                self.out.line(&format!("Connection error: {e}"));
+            },
+        }
+    }
+
+    async fn list(&mut self, resource_type: Option<&str>) {
+        #[derive(Serialize)]
+        struct Row {
+            subject: String,
+            relation: String,
+            resource: String,
+        }
+
+        match self.ctx.client().await {
+            Ok(client) => {
+                let vault = client.vault();
+                let rels = vault.relationships();
+                let mut req = rels.list().limit(50);
+
+                if let Some(rt) = resource_type {
+                    req = req.resource(format!("{rt}:*"));
+                }
+
+                match req.await {
+                    Ok(page) => {
+                        let rows: Vec<Row> = page
+                            .iter()
+                            .map(|rel| Row {
+                                subject: rel.subject().to_string(),
+                                relation: rel.relation().to_string(),
+                                resource: rel.resource().to_string(),
+                            })
+                            .collect();
+                        for row in &rows {
+                            self.known.insert(row.subject.clone());
+                            self.known.insert(row.resource.clone());
+                        }
+                        self.emit(&rows, |out| {
+                            if rows.is_empty() {
+                                out.line("No relationships found.");
+                            }
+                            for row in &rows {
+                                out.line(&format!(
+                                    "{} -[{}]-> {}",
+                                    row.subject, row.relation, row.resource
+                                ));
+                            }
+                            if page.has_more() {
+                                out.line("... (more results available)");
+                            }
+                        });
+                    },
+                    Err(e) => {
+                        self.out.line(&format!("Error: {e}"));
+                    },
+                }
             },
-            "status" => {
-                execute_status(ctx).await;
+            Err(e) => {
+                self.out.line(&format!("Connection error: {e}"));
             },
-            "clear" => {
-                // ANSI escape to clear screen
-                print!("\x1B[2J\x1B[1;1H");
-                let _ = io::stdout().flush();
+        }
+    }
+
+    async fn status(&mut self) {
+        #[derive(Serialize)]
+        struct StatusReport {
+            profile: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            organization: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            vault: Option<String>,
+            authenticated: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            api_url: Option<String>,
+            service_status: String,
+        }
+
+        let ctx = self.ctx;
+        let mut status = StatusReport {
+            profile: ctx.effective_profile_name().to_string(),
+            organization: ctx.profile_org_id().map(str::to_string),
+            vault: ctx.profile_vault_id().map(str::to_string),
+            authenticated: ctx.is_authenticated(),
+            api_url: None,
+            service_status: String::new(),
+        };
+        let mut connected = true;
+        match ctx.client().await {
+            Ok(client) => {
+                status.api_url = Some(ctx.profile.url_or_default().to_string());
+                status.service_status = match client.health().await {
+                    Ok(health) => health.status.to_string(),
+                    Err(e) => format!("Error - {e}"),
+                };
             },
-            cmd => {
-                println!("Unknown command: {cmd}. Type 'help' for available commands.");
+            Err(e) => {
+                connected = false;
+                status.service_status = format!("Error - {e}");
             },
         }
 
-        print_prompt();
+        self.emit(&status, |out| {
+            out.line(&format!("Profile: {}", status.profile));
+            if let Some(org) = &status.organization {
+                out.line(&format!("Organization: {org}"));
+            }
+            if let Some(vault) = &status.vault {
+                out.line(&format!("Vault: {vault}"));
+            }
+            out.line(&format!("Authenticated: {}", status.authenticated));
+            if let Some(url) = &status.api_url {
+                out.line(&format!("API URL: {url}"));
+            }
+            if connected {
+                out.line(&format!("Service Status: {}", status.service_status));
+            } else {
+                out.line(&format!("Connection: {}", status.service_status));
+            }
+        });
     }
+}
 
-    Ok(())
+const fn format_name(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Json => "json",
+        OutputFormat::Yaml => "yaml",
+        OutputFormat::JsonLines => "jsonl",
+        _ => "table",
+    }
 }
 
-fn print_prompt() {
-    print!("inferadb> ");
-    let _ = io::stdout().flush();
+/// Open `text` in the user's editor and return the saved contents.
+///
+/// The text is edited in a temporary `inferadb-<name>-<pid>.<extension>` file,
+/// so editors pick syntax highlighting from the extension.
+pub(super) fn edit_in_editor(text: &str, name: &str, extension: &str) -> Result<String> {
+    let path =
+        std::env::temp_dir().join(format!("inferadb-{name}-{}.{extension}", std::process::id()));
+    std::fs::write(&path, text)?;
+
+    let editor = std::env::var("EDITOR")
+        .or_else(|_| std::env::var("VISUAL"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = std::process::Command::new(program).args(words).arg(&path).status();
+
+    let edited = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+
+    if !status?.success() {
+        return Err(Error::other("Editor exited with non-zero status"));
+    }
+    Ok(edited?)
 }
 
-fn print_help() {
-    println!("Available commands:");
-    println!();
-    println!("  check <subject> <permission> <resource>");
-    println!("      Check if subject has permission on resource");
-    println!("      Example: check user:alice view doc:readme");
-    println!();
-    println!("  add <subject> <relation> <resource>");
-    println!("      Add a relationship");
-    println!("      Example: add user:alice viewer doc:readme");
-    println!();
-    println!("  delete <subject> <relation> <resource>");
-    println!("      Delete a relationship");
-    println!("      Example: delete user:alice viewer doc:readme");
-    println!();
-    println!("  list [resource_type]");
-    println!("      List relationships (optionally filter by resource type)");
-    println!();
-    println!("  status");
-    println!("      Show connection status");
-    println!();
-    println!("  clear");
-    println!("      Clear the screen");
-    println!();
-    println!("  help | ?");
-    println!("      Show this help message");
-    println!();
-    println!("  exit | quit | q");
-    println!("      Exit the shell");
-    println!();
+/// Accumulates input lines until a statement is complete.
+///
+/// A statement continues onto the next line after a trailing backslash or
+/// while braces, brackets, or quotes are unbalanced (e.g., context JSON).
+#[derive(Debug, Default)]
+struct InputBuffer {
+    text: String,
 }
 
-async fn execute_check(ctx: &Context, subject: &str, permission: &str, resource: &str) {
-    match ctx.client().await {
-        Ok(client) => {
-            let vault = client.vault();
-            match vault.check(subject, permission, resource).await {
-                Ok(allowed) => {
-                    if allowed {
-                        println!("ALLOWED: {subject} can {permission} {resource}");
-                    } else {
-                        println!("DENIED: {subject} cannot {permission} {resource}");
-                    }
-                },
-                Err(e) => {
-                    println!("Error: {e}");
-                },
-            }
-        },
-        Err(e) => {
-            println!("Connection error: {e}");
-        },
+impl InputBuffer {
+    /// Add a line, returning the statement once it is complete.
+    fn push(&mut self, line: &str) -> Option<String> {
+        let (line, continued) =
+            line.trim_end().strip_suffix('\\').map_or((line, false), |rest| (rest, true));
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+        self.text.push_str(line);
+
+        if continued || is_unbalanced(&self.text) {
+            return None;
+        }
+        Some(std::mem::take(&mut self.text))
     }
 }
 
-async fn execute_add(ctx: &Context, subject: &str, relation: &str, resource: &str) {
-    match ctx.client().await {
-        Ok(client) => {
-            let vault = client.vault();
-            let rels = vault.relationships();
-            let relationship = inferadb::Relationship::new(resource, relation, subject);
-            match rels.write(relationship).await {
-                Ok(_) => {
-                    println!("Added: {subject} -[{relation}]-> {resource}");
-                },
-                Err(e) => {
-                    println!("Error: {e}");
-                },
-            }
-        },
-        Err(e) => {
-            println!("Connection error: {e}");
-        },
+/// Split a buffer holding several statements into complete statements,
+/// skipping `#` comment lines.
+fn statements(text: &str) -> Vec<String> {
+    let mut buffer = InputBuffer::default();
+    let mut statements: Vec<String> = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| buffer.push(line))
+        .collect();
+    if !buffer.text.trim().is_empty() {
+        statements.push(std::mem::take(&mut buffer.text));
     }
+    statements.retain(|s| !s.trim().is_empty());
+    statements
 }
 
-async fn execute_delete(ctx: &Context, subject: &str, relation: &str, resource: &str) {
-    match ctx.client().await {
-        Ok(client) => {
-            let vault = client.vault();
-            let rels = vault.relationships();
-            let relationship = inferadb::Relationship::new(resource, relation, subject);
-            match rels.delete(relationship).await {
-                Ok(()) => {
-                    println!("Deleted: {subject} -[{relation}]-> {resource}");
-                },
-                Err(e) => {
-                    println!("Error: {e}");
-                },
+/// Whether brackets or quotes are left open.
+fn is_unbalanced(text: &str) -> bool {
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut escaped = false;
+    for c in text.chars() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
             }
-        },
-        Err(e) => {
-            println!("Connection error: {e}");
-        },
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth -= 1,
+            _ => {},
+        }
     }
+    quote.is_some() || depth > 0
 }
 
-async fn execute_list(ctx: &Context, resource_type: Option<&str>) {
-    match ctx.client().await {
-        Ok(client) => {
-            let vault = client.vault();
-            let rels = vault.relationships();
-            let mut req = rels.list().limit(50);
-
-            if let Some(rt) = resource_type {
-                req = req.resource(format!("{rt}:*"));
-            }
+/// Split a statement on whitespace, keeping bracketed JSON and quoted text
+/// together. Surrounding single quotes are removed.
+fn split_args(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0i32;
+    let mut quote = None;
 
-            match req.await {
-                Ok(page) => {
-                    if page.relationships.is_empty() {
-                        println!("No relationships found.");
-                    } else {
-                        for rel in page.iter() {
-                            println!(
-                                "{} -[{}]-> {}",
-                                rel.subject(),
-                                rel.relation(),
-                                rel.resource()
-                            );
-                        }
-                        if page.has_more() {
-                            println!("... (more results available)");
-                        }
+    for c in input.chars() {
+        match quote {
+            Some(q) => {
+                if c == q {
+                    quote = None;
+                    if q == '"' {
+                        current.push(c);
                     }
-                },
-                Err(e) => {
-                    println!("Error: {e}");
-                },
+                } else {
+                    current.push(c);
+                }
+                continue;
+            },
+            None if c == '\'' => {
+                quote = Some(c);
+                continue;
+            },
+            None if c == '"' => {
+                quote = Some(c);
+                current.push(c);
+                continue;
+            },
+            None => {},
+        }
+        match c {
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth -= 1,
+            _ => {},
+        }
+        if c.is_whitespace() && depth <= 0 {
+            if !current.is_empty() {
+                args.push(std::mem::take(&mut current));
             }
-        },
-        Err(e) => {
-            println!("Connection error: {e}");
-        },
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+/// Shell output, mirrored to the session recording when one is active.
+struct Console {
+    recorder: Option<CastRecorder>,
+}
+
+impl Console {
+    /// Print text and record it.
+    fn print(&mut self, text: &str) {
+        print!("{text}");
+        let _ = io::stdout().flush();
+        self.record(text);
+    }
+
+    /// Print a line and record it.
+    fn line(&mut self, text: &str) {
+        self.print(&format!("{text}\n"));
+    }
+
+    /// Record typed input, which the terminal has already echoed.
+    fn echo(&mut self, input: &str) {
+        self.record(&format!("{input}\n"));
     }
+
+    fn record(&mut self, text: &str) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.output(text)
+        {
+            eprintln!("Recording stopped: {e}");
+            self.recorder = None;
+        }
+    }
+}
+
+fn print_help(out: &mut Console) {
+    out.line("Available commands:");
+    out.line("");
+    out.line("  check <subject> <permission> <resource> [context-json]");
+    out.line("      Check if subject has permission on resource");
+    out.line("      Example: check user:alice view doc:readme {\"ip\": \"10.0.0.1\"}");
+    out.line("");
+    out.line("  add <subject> <relation> <resource> [...]");
+    out.line("      Add one or more relationships");
+    out.line("      Example: add user:alice viewer doc:readme user:bob editor doc:readme");
+    out.line("");
+    out.line("  delete <subject> <relation> <resource>");
+    out.line("      Delete a relationship");
+    out.line("      Example: delete user:alice viewer doc:readme");
+    out.line("");
+    out.line("  list [resource_type]");
+    out.line("      List relationships (optionally filter by resource type)");
+    out.line("");
+    out.line("  status");
+    out.line("      Show connection status");
+    out.line("");
+    out.line("  source <file>");
+    out.line("      Run the statements in a script file (# starts a comment)");
+    out.line("");
+    out.line("  :output [table|json|yaml|jsonl]");
+    out.line("      Show or switch the format of results");
+    out.line("");
+    out.line("  clear");
+    out.line("      Clear the screen");
+    out.line("");
+    out.line("  help | ?");
+    out.line("      Show this help message");
+    out.line("");
+    out.line("  \\e");
+    out.line("      Edit the last statement in $EDITOR and run the saved buffer");
+    out.line("");
+    out.line("  exit | quit | q");
+    out.line("      Exit the shell");
+    out.line("");
+    out.line("End a line with \\ or leave a { [ or quote open to continue on the next line.");
+    out.line("Up and down recall earlier statements; tab completes commands and entity IDs.");
+    out.line("");
 }
 
-async fn execute_status(ctx: &Context) {
-    println!("Profile: {}", ctx.effective_profile_name());
-    if let Some(org) = ctx.profile_org_id() {
-        println!("Organization: {org}");
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_buffer_single_line() {
+        let mut buffer = InputBuffer::default();
+        assert_eq!(
+            buffer.push("check user:alice view doc:1"),
+            Some("check user:alice view doc:1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_input_buffer_continuations() {
+        let mut buffer = InputBuffer::default();
+        assert_eq!(buffer.push("add user:alice viewer doc:1 \\"), None);
+        assert_eq!(
+            buffer.push("    user:bob editor doc:1"),
+            Some("add user:alice viewer doc:1 \n    user:bob editor doc:1".to_string())
+        );
+
+        assert_eq!(buffer.push("check user:alice view doc:1 {"), None);
+        assert_eq!(buffer.push(r#"  "ip": "10.0.0.1""#), None);
+        assert!(buffer.push("}").is_some());
+        assert!(buffer.text.is_empty());
     }
-    if let Some(vault) = ctx.profile_vault_id() {
-        println!("Vault: {vault}");
+
+    #[test]
+    fn test_split_args_keeps_json_together() {
+        let args = split_args(r#"check user:alice view doc:1 {"ip": "10.0.0.1", "tags": [1, 2]}"#);
+        assert_eq!(args.len(), 5);
+        assert_eq!(args[4], r#"{"ip": "10.0.0.1", "tags": [1, 2]}"#);
+
+        assert_eq!(split_args("list  'doc type'\n"), vec!["list", "doc type"]);
     }
-    println!("Authenticated: {}", ctx.is_authenticated());
 
-    match ctx.client().await {
-        Ok(client) => {
-            println!("API URL: {}", ctx.profile.url_or_default());
-            match client.health().await {
-                Ok(health) => {
-                    println!("Service Status: {}", health.status);
-                },
-                Err(e) => {
-                    println!("Service Status: Error - {e}");
-                },
-            }
-        },
-        Err(e) => {
-            println!("Connection: Error - {e}");
-        },
+    #[test]
+    fn test_statements() {
+        let text =
+            "add user:alice viewer doc:1\n\ncheck user:alice view doc:1 {\n  \"ip\": \"1\"\n}\n";
+        let statements = statements(text);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[1].starts_with("check"));
+    }
+
+    #[test]
+    fn test_statements_skip_comments() {
+        let text = "# grant access {\nadd user:alice viewer doc:1\n  # check it\ncheck user:alice view doc:1\n";
+        assert_eq!(
+            statements(text),
+            vec!["add user:alice viewer doc:1", "check user:alice view doc:1"]
+        );
+    }
+
+    #[test]
+    fn test_entity_ids() {
+        let ids: Vec<&str> = entity_ids(
+            r#"check user:alice view doc:readme {"ip": "10.0.0.1", "via": "group:eng#member"}"#,
+        )
+        .collect();
+        assert_eq!(ids, vec!["user:alice", "doc:readme", "group:eng#member"]);
+        assert_eq!(entity_ids("source https://example.com/a.ifdb :output").count(), 0);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let known = BTreeSet::from(["doc:readme".to_string(), "user:alice".to_string()]);
+        assert!(candidates("ch", &known).contains(&"check".to_string()));
+        assert_eq!(candidates("check user:alice ", &known), vec!["doc:readme", "user:alice"]);
+        assert_eq!(candidates("list d", &known), vec!["doc", "user"]);
+        assert_eq!(candidates(":output j", &known), FORMATS);
+        assert!(candidates("status ", &known).is_empty());
     }
 }
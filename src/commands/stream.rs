@@ -1,15 +1,34 @@
 //! Stream command for real-time relationship changes.
 
+use std::time::Duration;
+
 use futures::StreamExt;
+use inferadb::vault::watch::{Operation, WatchFilter};
+
+use crate::{
+    client::{Context, retry},
+    error::{Error, Result},
+    output::{OutputFormat, dates, desktop, metrics},
+};
 
-use crate::{client::Context, error::Result};
+/// How long `--assert` waits without `--timeout`.
+const ASSERT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Watch real-time relationship changes.
+///
+/// With `metrics_listen`, event counts, errors, and stream lag are served as
+/// Prometheus metrics on that address.
 pub async fn stream(
     ctx: &Context,
     resource_type: Option<&str>,
     relation: Option<&str>,
+    metrics_listen: Option<&str>,
 ) -> Result<()> {
+    if let Some(addr) = metrics_listen {
+        let bound = metrics::serve(addr)?;
+        ctx.output.info(&format!("Serving metrics on http://{bound}/metrics"));
+    }
+
     let client = ctx.client().await?;
     let vault = client.vault();
 
@@ -42,6 +61,13 @@ pub async fn stream(
             Ok(event) => {
                 let op = if event.operation.is_create() { "+" } else { "-" };
 
+                let operation = if event.operation.is_create() { "create" } else { "delete" };
+                let lag = (chrono::Utc::now() - event.timestamp).to_std().unwrap_or_default();
+                metrics::inc(metrics::STREAM_EVENTS_TOTAL, &[("operation", operation)]);
+                metrics::set(metrics::STREAM_LAG_SECONDS, &[], lag.as_secs_f64());
+                #[allow(clippy::cast_precision_loss)] // Prometheus samples are f64
+                metrics::set(metrics::STREAM_REVISION, &[], event.revision as f64);
+
                 println!(
                     "[{}] {} -[{}]-> {}",
                     op,
@@ -51,6 +77,7 @@ pub async fn stream(
                 );
             },
             Err(e) => {
+                metrics::inc(metrics::STREAM_ERRORS_TOTAL, &[]);
                 ctx.output.error(&format!("Stream error: {e}"));
                 // Continue watching after transient errors
             },
@@ -61,3 +88,199 @@ pub async fn stream(
 
     Ok(())
 }
+
+/// Print every grant and revoke of a relationship involving `object`, as its
+/// resource or its subject, optionally raising a desktop notification for each.
+pub async fn watch_object(
+    ctx: &Context,
+    object: &str,
+    relation: Option<&str>,
+    mut notify: bool,
+) -> Result<()> {
+    if !object.contains(':') {
+        return Err(Error::invalid_arg(format!(
+            "Invalid object '{object}'. Expected type:id (e.g., document:readme or user:alice)"
+        )));
+    }
+
+    let client = ctx.client().await?;
+    let mut watch = client.vault().watch();
+    if let Some(rel) = relation {
+        watch = watch.filter(WatchFilter::relation(rel));
+    }
+    let mut stream = watch.run().await?;
+    ctx.output.info(&format!("Watching changes involving {object}... (Ctrl+C to stop)"));
+
+    while let Some(event) = stream.next().await {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                ctx.output.error(&format!("Stream error: {e}"));
+                continue;
+            },
+        };
+        let rel = &event.relationship;
+        if !involves(object, rel.resource(), rel.subject()) {
+            continue;
+        }
+
+        let (op, change) =
+            if event.operation.is_create() { ("+", "granted") } else { ("-", "revoked") };
+        let tuple = format!("{} -[{}]-> {}", rel.subject(), rel.relation(), rel.resource());
+        if ctx.output.format() == OutputFormat::Table {
+            let actor = event.actor.as_deref().map(|a| format!(" by {a}")).unwrap_or_default();
+            println!(
+                "{} [{op}] {tuple} (revision {}){actor}",
+                dates::format_precise(event.timestamp),
+                event.revision
+            );
+        } else {
+            println!("{}", serde_json::to_string(&event)?);
+        }
+
+        if notify && let Err(e) = desktop::notify(&format!("{object}: access {change}"), &tuple) {
+            // One failure means the notifier is missing; don't repeat it for every change
+            ctx.output.warn(&format!("{e}; continuing without notifications"));
+            notify = false;
+        }
+    }
+
+    ctx.output.info("Stream ended.");
+
+    Ok(())
+}
+
+/// Whether a relationship involves `object`: as its resource, or as its
+/// subject, including usersets of it (`group:eng#member` for `group:eng`).
+fn involves(object: &str, resource: &str, subject: &str) -> bool {
+    resource == object
+        || subject == object
+        || subject.split_once('#').is_some_and(|(subject, _)| subject == object)
+}
+
+/// Wait for a change matching `expected` (`resource#relation@subject`, where
+/// any part may be `*`) and succeed, or fail once `--timeout` (default 30s)
+/// passes without one.
+///
+/// Only changes made after the stream connects count, so start the assertion
+/// before the action that should write the relationship.
+pub async fn assert_change(ctx: &Context, expected: &str, deleted: bool) -> Result<()> {
+    let operation = if deleted { Operation::Delete } else { Operation::Create };
+    let filters = assert_filters(expected, operation)?;
+    let timeout = retry::policy().timeout.unwrap_or(ASSERT_TIMEOUT);
+
+    let client = ctx.client().await?;
+    let mut watch = client.vault().watch();
+    for filter in &filters {
+        watch = watch.filter(filter.clone());
+    }
+    let mut stream = watch.run().await?;
+    ctx.output
+        .info(&format!("Waiting up to {}s for {operation} of {expected}...", timeout.as_secs()));
+
+    let matched = tokio::time::timeout(timeout, async {
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(event) if filters.iter().all(|filter| filter.matches(&event)) => {
+                    return Some(event);
+                },
+                Ok(_) => {},
+                Err(e) => ctx.output.warn(&format!("Stream error: {e}")),
+            }
+        }
+        None
+    })
+    .await;
+
+    match matched {
+        Ok(Some(event)) => {
+            ctx.output.success(&format!(
+                "Saw {operation} of {}#{}@{} at revision {}",
+                event.relationship.resource(),
+                event.relationship.relation(),
+                event.relationship.subject(),
+                event.revision
+            ));
+            ctx.output.primary(&event.revision.to_string());
+            Ok(())
+        },
+        Ok(None) => {
+            Err(Error::other(format!("Stream ended before a {operation} of {expected} was seen")))
+        },
+        Err(_) => {
+            Err(Error::other(format!("No {operation} of {expected} within {}s", timeout.as_secs())))
+        },
+    }
+}
+
+/// Watch filters matching `resource#relation@subject` changes of `operation`;
+/// `*` parts match anything.
+fn assert_filters(expected: &str, operation: Operation) -> Result<Vec<WatchFilter>> {
+    let parsed = expected.split_once('@').and_then(|(object, subject)| {
+        object.split_once('#').map(|(resource, relation)| (resource, relation, subject))
+    });
+    let Some((resource, relation, subject)) = parsed else {
+        return Err(Error::invalid_arg(format!(
+            "Invalid assertion '{expected}'. Expected: resource#relation@subject (parts may be *)"
+        )));
+    };
+
+    let mut filters = vec![WatchFilter::operations([operation])];
+    if resource != "*" {
+        filters.push(
+            resource
+                .strip_suffix(":*")
+                .map_or_else(|| WatchFilter::resource(resource), WatchFilter::resource_type),
+        );
+    }
+    if relation != "*" {
+        filters.push(WatchFilter::relation(relation));
+    }
+    if subject != "*" {
+        filters.push(WatchFilter::subject(subject));
+    }
+    Ok(filters)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use inferadb::{Relationship, vault::watch::WatchEvent};
+
+    use super::*;
+
+    fn matches(expected: &str, operation: Operation, change: (&str, &str, &str)) -> bool {
+        let (resource, relation, subject) = change;
+        let event = WatchEvent::new(
+            operation,
+            Relationship::new(resource, relation, subject).into_owned(),
+            1,
+            chrono::Utc::now(),
+        );
+        assert_filters(expected, Operation::Create)
+            .unwrap()
+            .iter()
+            .all(|filter| filter.matches(&event))
+    }
+
+    #[test]
+    fn test_assert_filters() {
+        let change = ("document:readme", "viewer", "user:alice");
+        assert!(matches("document:readme#viewer@user:alice", Operation::Create, change));
+        assert!(matches("document:*#*@user:alice", Operation::Create, change));
+        assert!(!matches("document:readme#viewer@user:alice", Operation::Delete, change));
+        assert!(!matches("document:readme#editor@*", Operation::Create, change));
+        assert!(!matches("folder:*#viewer@*", Operation::Create, change));
+        assert!(assert_filters("document:readme@user:alice", Operation::Create).is_err());
+    }
+
+    #[test]
+    fn test_involves() {
+        assert!(involves("document:readme", "document:readme", "user:alice"));
+        assert!(involves("user:alice", "document:readme", "user:alice"));
+        assert!(involves("group:eng", "document:readme", "group:eng#member"));
+        assert!(involves("group:eng#member", "document:readme", "group:eng#member"));
+        assert!(!involves("group:eng#admin", "document:readme", "group:eng#member"));
+        assert!(!involves("document:read", "document:readme", "user:alice"));
+    }
+}
@@ -3,7 +3,12 @@
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use serde::Serialize;
 
-use crate::{client::Context, config::CredentialStore, error::Result, output::Displayable};
+use crate::{
+    client::{self, Context},
+    config::CredentialStore,
+    error::{Error, Result},
+    output::{Displayable, dates},
+};
 
 #[derive(Debug, Clone, Serialize)]
 struct TokenRow {
@@ -32,6 +37,13 @@ impl Displayable for TokenRow {
 ///
 /// Note: Tokens are obtained via the OAuth login flow, not generated directly.
 pub async fn generate(ctx: &Context, ttl: Option<&str>, role: Option<&str>) -> Result<()> {
+    // Quiet mode promises only the token on stdout, so fail rather than print nothing
+    if ctx.output.is_quiet() {
+        return Err(Error::other(
+            "Token generation is not supported via CLI. Use 'inferadb login' to obtain tokens.",
+        ));
+    }
+
     ctx.output.warn("Token generation is not supported via CLI.");
     ctx.output.info("Use 'inferadb login' to authenticate and obtain tokens.");
 
@@ -63,10 +75,7 @@ pub async fn list(ctx: &Context) -> Result<()> {
                 "valid".to_string()
             };
 
-            let expires = creds.expires_at.map_or_else(
-                || "unknown".to_string(),
-                |dt| dt.format("%Y-%m-%d %H:%M").to_string(),
-            );
+            let expires = creds.expires_at.map_or_else(|| "unknown".to_string(), dates::format);
 
             let can_refresh = if creds.can_refresh() { "yes" } else { "no" }.to_string();
             (status, expires, can_refresh)
@@ -89,9 +98,7 @@ pub async fn list(ctx: &Context) -> Result<()> {
             "valid".to_string()
         };
 
-        let expires = creds
-            .expires_at
-            .map_or_else(|| "unknown".to_string(), |dt| dt.format("%Y-%m-%d %H:%M").to_string());
+        let expires = creds.expires_at.map_or_else(|| "unknown".to_string(), dates::format);
 
         let can_refresh = if creds.can_refresh() { "yes" } else { "no" }.to_string();
 
@@ -143,8 +150,14 @@ pub async fn refresh(ctx: &Context) -> Result<()> {
         return Ok(());
     }
 
-    ctx.output.warn("Token refresh not yet implemented.");
-    ctx.output.info("Run 'inferadb login' to obtain a fresh token.");
+    let refreshed = client::refresh_credentials(profile_name, &creds).await?;
+    match refreshed.expires_at {
+        Some(expires_at) => ctx.output.success(&format!(
+            "Token for profile '{profile_name}' refreshed; expires {}.",
+            dates::format(expires_at)
+        )),
+        None => ctx.output.success(&format!("Token for profile '{profile_name}' refreshed.")),
+    }
 
     Ok(())
 }
@@ -201,14 +214,14 @@ pub async fn inspect(ctx: &Context, token: Option<&str>, verify: bool) -> Result
                         println!();
                         println!(
                             "Expires: {} (in {})",
-                            dt.format("%Y-%m-%d %H:%M:%S UTC"),
+                            dates::format_precise(dt),
                             format_duration(duration)
                         );
                     } else {
                         println!();
                         println!(
                             "Expired: {} ({} ago)",
-                            dt.format("%Y-%m-%d %H:%M:%S UTC"),
+                            dates::format_precise(dt),
                             format_duration(now - dt)
                         );
                     }
@@ -219,7 +232,7 @@ pub async fn inspect(ctx: &Context, token: Option<&str>, verify: bool) -> Result
             if let Some(iat) = payload.get("iat").and_then(serde_json::Value::as_i64)
                 && let Some(dt) = chrono::DateTime::from_timestamp(iat, 0)
             {
-                println!("Issued: {}", dt.format("%Y-%m-%d %H:%M:%S UTC"));
+                println!("Issued: {}", dates::format_precise(dt));
             }
         },
         Err(e) => {
@@ -0,0 +1,173 @@
+//! Opt-in response cache for read commands.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{Config, StateLock};
+use crate::error::{Error, Result};
+
+/// A cached response body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: DateTime<Utc>,
+    value: serde_json::Value,
+}
+
+/// Summary of a cached response, for `cache status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntryInfo {
+    /// Request the response belongs to, e.g. `orgs/<org>/vaults`.
+    pub key: String,
+    /// When the response was fetched.
+    pub fetched_at: DateTime<Utc>,
+    /// Size of the cached JSON.
+    pub bytes: usize,
+}
+
+/// Read-through cache of API responses for one profile, stored in the state
+/// directory.
+pub struct ResponseCache {
+    path: Option<PathBuf>,
+    ttl: chrono::Duration,
+}
+
+impl ResponseCache {
+    /// Create the cache for a profile, treating entries older than `ttl` as
+    /// misses.
+    #[must_use]
+    pub fn new(profile: &str, ttl: chrono::Duration) -> Self {
+        Self { path: Self::dir().map(|dir| dir.join(format!("{profile}.json"))), ttl }
+    }
+
+    /// Directory holding every profile's cache.
+    #[must_use]
+    pub fn dir() -> Option<PathBuf> {
+        Config::state_dir().map(|dir| dir.join("cache"))
+    }
+
+    /// How long entries stay fresh.
+    #[must_use]
+    pub const fn ttl(&self) -> chrono::Duration {
+        self.ttl
+    }
+
+    fn path(&self) -> Result<&PathBuf> {
+        self.path.as_ref().ok_or_else(|| Error::config("Could not determine state directory"))
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, CacheEntry>> {
+        let path = self.path()?;
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| Error::config(format!("Invalid response cache {}: {e}", path.display())))
+    }
+
+    fn write(&self, entries: &BTreeMap<String, CacheEntry>) -> Result<()> {
+        let path = self.path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(entries)?)?;
+        Ok(())
+    }
+
+    /// Look up a fresh response. Missing, stale and unreadable entries are all
+    /// misses.
+    #[must_use]
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let entry = self.read().ok()?.remove(key)?;
+        if Utc::now() - entry.fetched_at >= self.ttl {
+            return None;
+        }
+        serde_json::from_value(entry.value).ok()
+    }
+
+    /// Store a response, dropping any stale entries.
+    pub fn put<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        let _lock = StateLock::acquire(self.path()?, "the response cache")?;
+        let now = Utc::now();
+        let mut entries = self.read().unwrap_or_default();
+        entries.retain(|_, entry| now - entry.fetched_at < self.ttl);
+        entries.insert(
+            key.to_string(),
+            CacheEntry { fetched_at: now, value: serde_json::to_value(value)? },
+        );
+        self.write(&entries)
+    }
+
+    /// Every cached response by key, fresh or stale. Unreadable caches are
+    /// empty.
+    #[must_use]
+    pub fn values(&self) -> Vec<(String, serde_json::Value)> {
+        self.read().unwrap_or_default().into_iter().map(|(key, entry)| (key, entry.value)).collect()
+    }
+
+    /// List cached responses, oldest first.
+    pub fn entries(&self) -> Result<Vec<CacheEntryInfo>> {
+        let mut entries: Vec<CacheEntryInfo> = self
+            .read()?
+            .into_iter()
+            .map(|(key, entry)| CacheEntryInfo {
+                key,
+                fetched_at: entry.fetched_at,
+                bytes: entry.value.to_string().len(),
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.fetched_at);
+        Ok(entries)
+    }
+
+    /// Remove this profile's cache.
+    pub fn clear(&self) -> Result<()> {
+        let path = self.path()?;
+        let _lock = StateLock::acquire(path, "the response cache")?;
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Remove every profile's cache, returning how many were removed.
+    pub fn clear_all() -> Result<usize> {
+        let dir =
+            Self::dir().ok_or_else(|| Error::config("Could not determine state directory"))?;
+        if !dir.exists() {
+            return Ok(0);
+        }
+        let count = std::fs::read_dir(&dir)?.count();
+        std::fs::remove_dir_all(&dir)?;
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Some(dir.path().join("default.json"));
+        let cache = ResponseCache { path: path.clone(), ttl: chrono::Duration::minutes(5) };
+
+        assert_eq!(cache.get::<Vec<String>>("orgs"), None);
+
+        cache.put("orgs", &vec!["org_1".to_string()]).unwrap();
+        assert_eq!(cache.get::<Vec<String>>("orgs"), Some(vec!["org_1".to_string()]));
+        assert_eq!(cache.get::<u32>("orgs"), None);
+        assert_eq!(cache.entries().unwrap().len(), 1);
+
+        // A zero TTL makes every entry stale
+        let stale = ResponseCache { path, ttl: chrono::Duration::zero() };
+        assert_eq!(stale.get::<Vec<String>>("orgs"), None);
+
+        cache.clear().unwrap();
+        assert!(cache.entries().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+//! Credential storage backends for environments without an OS keychain.
+//!
+//! Keychain access fails inside containers and over SSH, so each profile can
+//! choose where its credentials live with `credential_store` in cli.yaml:
+//!
+//! - `keychain` (default): the OS keychain.
+//! - `file`: JSON in the config directory, readable only by the owner (0600).
+//! - `encrypted-file`: the same JSON sealed with ChaCha20-Poly1305 under a key derived from
+//!   `INFERADB_CREDENTIALS_PASSPHRASE` (PBKDF2-HMAC-SHA256).
+//!
+//! `--no-keychain` (or `INFERADB_NO_KEYCHAIN`) moves keychain profiles to
+//! `file` for one invocation.
+
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::{
+        RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use ring::{
+    aead::{self, Aad, LessSafeKey, Nonce, UnboundKey},
+    pbkdf2,
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::{Deserialize, Serialize};
+
+use super::Config;
+use crate::error::{Error, Result};
+
+/// Environment variable holding the `encrypted-file` passphrase.
+pub const PASSPHRASE_ENV: &str = "INFERADB_CREDENTIALS_PASSPHRASE";
+
+/// PBKDF2 rounds for new encrypted files.
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// Where a profile's credentials are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CredentialBackend {
+    /// The OS keychain.
+    #[default]
+    Keychain,
+    /// An owner-only plaintext file.
+    File,
+    /// A passphrase-encrypted file.
+    EncryptedFile,
+}
+
+impl CredentialBackend {
+    /// Whether this is the default backend (for skipping serialization).
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Per-profile backends from the config, set once the config is loaded.
+static BACKENDS: RwLock<Option<HashMap<String, CredentialBackend>>> = RwLock::new(None);
+
+/// Whether `--no-keychain` was given.
+static NO_KEYCHAIN: AtomicBool = AtomicBool::new(false);
+
+/// Use the configured backend for each profile for the rest of the process,
+/// replacing the keychain with `file` when `no_keychain` is set.
+pub fn configure(config: &Config, no_keychain: bool) {
+    let backends = config
+        .profiles
+        .iter()
+        .map(|(name, profile)| (name.clone(), profile.credential_store))
+        .collect();
+    if let Ok(mut configured) = BACKENDS.write() {
+        *configured = Some(backends);
+    }
+    NO_KEYCHAIN.store(no_keychain, Ordering::Relaxed);
+}
+
+/// The backend storing a profile's credentials.
+#[must_use]
+pub fn backend_for(profile: &str) -> CredentialBackend {
+    let configured = BACKENDS
+        .read()
+        .ok()
+        .and_then(|backends| backends.as_ref()?.get(profile).copied())
+        .unwrap_or_default();
+    if configured == CredentialBackend::Keychain && NO_KEYCHAIN.load(Ordering::Relaxed) {
+        CredentialBackend::File
+    } else {
+        configured
+    }
+}
+
+/// Encrypted credentials file contents.
+#[derive(Debug, Serialize, Deserialize)]
+struct Sealed {
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Path of a profile's credentials file for a file backend.
+fn path(backend: CredentialBackend, profile: &str) -> Result<PathBuf> {
+    if profile.is_empty() || profile.starts_with('.') || profile.contains(['/', '\\']) {
+        return Err(Error::invalid_arg(format!("Invalid profile name '{profile}'")));
+    }
+    let extension = if backend == CredentialBackend::EncryptedFile { "enc" } else { "json" };
+    Config::config_dir()
+        .map(|dir| dir.join("credentials").join(format!("{profile}.{extension}")))
+        .ok_or_else(|| Error::config("Could not determine config directory"))
+}
+
+/// Read a profile's stored credentials JSON, if any.
+pub(super) fn read(backend: CredentialBackend, profile: &str) -> Result<Option<String>> {
+    let path = path(backend, profile)?;
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::credential(format!("Could not read {}: {e}", path.display()))),
+    };
+    match backend {
+        CredentialBackend::EncryptedFile => open(&contents, &passphrase()?, profile).map(Some),
+        _ => Ok(Some(contents)),
+    }
+}
+
+/// Store a profile's credentials JSON.
+pub(super) fn write(backend: CredentialBackend, profile: &str, data: &str) -> Result<()> {
+    let path = path(backend, profile)?;
+    let contents = match backend {
+        CredentialBackend::EncryptedFile => seal(data, &passphrase()?, profile)?,
+        _ => data.to_string(),
+    };
+    write_private(&path, &contents)
+        .map_err(|e| Error::credential(format!("Could not write {}: {e}", path.display())))
+}
+
+/// Delete a profile's credentials file; a missing file is not an error.
+pub(super) fn delete(backend: CredentialBackend, profile: &str) -> Result<()> {
+    match std::fs::remove_file(path(backend, profile)?) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether a profile has a credentials file.
+pub(super) fn exists(backend: CredentialBackend, profile: &str) -> bool {
+    path(backend, profile).is_ok_and(|path| path.exists())
+}
+
+fn passphrase() -> Result<String> {
+    std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty()).ok_or_else(|| {
+        Error::credential(format!(
+            "Set {PASSPHRASE_ENV} to use the encrypted-file credential store"
+        ))
+    })
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations)
+        .ok_or_else(|| Error::credential("Invalid encrypted credentials file"))?;
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut key);
+    let key = UnboundKey::new(&aead::CHACHA20_POLY1305, &key)
+        .map_err(|_| Error::credential("Could not derive encryption key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// Encrypt credentials, binding them to the profile name.
+fn seal(plaintext: &str, passphrase: &str, profile: &str) -> Result<String> {
+    seal_with(plaintext, passphrase, profile, PBKDF2_ITERATIONS)
+}
+
+fn seal_with(plaintext: &str, passphrase: &str, profile: &str, iterations: u32) -> Result<String> {
+    let rng = SystemRandom::new();
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; aead::NONCE_LEN];
+    rng.fill(&mut salt)
+        .and_then(|()| rng.fill(&mut nonce))
+        .map_err(|_| Error::credential("Could not generate random bytes"))?;
+
+    let key = derive_key(passphrase, &salt, iterations)?;
+    let mut buffer = plaintext.as_bytes().to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce),
+        Aad::from(profile.as_bytes()),
+        &mut buffer,
+    )
+    .map_err(|_| Error::credential("Could not encrypt credentials"))?;
+
+    let sealed = Sealed {
+        iterations,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(buffer),
+    };
+    Ok(serde_json::to_string_pretty(&sealed)?)
+}
+
+/// Decrypt credentials sealed by [`seal`] for the same profile.
+fn open(contents: &str, passphrase: &str, profile: &str) -> Result<String> {
+    let invalid = || Error::credential("Invalid encrypted credentials file");
+    let sealed: Sealed = serde_json::from_str(contents).map_err(|_| invalid())?;
+    let salt = STANDARD.decode(&sealed.salt).map_err(|_| invalid())?;
+    let nonce = STANDARD.decode(&sealed.nonce).map_err(|_| invalid())?;
+    let nonce = Nonce::try_assume_unique_for_key(&nonce).map_err(|_| invalid())?;
+    let mut buffer = STANDARD.decode(&sealed.ciphertext).map_err(|_| invalid())?;
+
+    let key = derive_key(passphrase, &salt, sealed.iterations)?;
+    let plaintext =
+        key.open_in_place(nonce, Aad::from(profile.as_bytes()), &mut buffer).map_err(|_| {
+            Error::credential(format!(
+                "Could not decrypt credentials for profile '{profile}': wrong passphrase or \
+                 damaged file"
+            ))
+        })?;
+    String::from_utf8(plaintext.to_vec()).map_err(|_| invalid())
+}
+
+/// Write a file only the owner can read, replacing it atomically.
+fn write_private(path: &PathBuf, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+    }
+
+    let tmp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_round_trip() {
+        let data = r#"{"access_token":"secret"}"#;
+        let sealed = seal_with(data, "hunter2", "prod", 1_000).unwrap();
+        assert!(!sealed.contains("secret"));
+        assert_eq!(open(&sealed, "hunter2", "prod").unwrap(), data);
+
+        // Wrong passphrase, or a file copied from another profile
+        assert!(open(&sealed, "hunter3", "prod").is_err());
+        assert!(open(&sealed, "hunter2", "staging").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("inferadb-credentials-{}", std::process::id()));
+        let path = dir.join("credentials").join("default.json");
+        write_private(&path, "{}").unwrap();
+        write_private(&path, "{\"access_token\":\"t\"}").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"access_token\":\"t\"}");
+        let mode = |p: &std::path::Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(mode(path.parent().unwrap()), 0o700);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_path_rejects_traversal() {
+        assert!(path(CredentialBackend::File, "../etc").is_err());
+        assert!(path(CredentialBackend::File, "a/b").is_err());
+        assert!(path(CredentialBackend::File, "").is_err());
+    }
+
+    #[test]
+    fn test_backend_serde() {
+        let backend: CredentialBackend = serde_yaml::from_str("encrypted-file").unwrap();
+        assert_eq!(backend, CredentialBackend::EncryptedFile);
+        assert!(CredentialBackend::Keychain.is_default());
+    }
+}
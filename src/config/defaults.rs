@@ -0,0 +1,281 @@
+//! Per-command argument defaults from the `defaults:` config section.
+//!
+//! ```yaml
+//! defaults:
+//!   relationships:
+//!     list:
+//!       limit: 500
+//!   check.explain: true
+//! ```
+//!
+//! A key is a subcommand path followed by an argument's long name (dashes and
+//! underscores are interchangeable); nested maps and dotted keys are
+//! equivalent. [`apply`] turns the values into the arguments' defaults before
+//! parsing, so an argument given on the command line or through its
+//! environment variable still wins. A flag defaulted to `true` can be turned
+//! off for one invocation with `--flag=false`.
+
+use std::collections::BTreeMap;
+
+use clap::{ArgAction, Command, builder::BoolishValueParser};
+use serde_yaml::Value;
+
+/// The `defaults:` section: nested maps (or dotted keys) of values.
+pub type CommandDefaults = BTreeMap<String, Value>;
+
+/// Merge `other` into `base`, with `other`'s values winning.
+pub(super) fn merge(base: &mut CommandDefaults, other: CommandDefaults) {
+    for (key, value) in other {
+        match base.get_mut(&key) {
+            Some(existing) => merge_value(existing, value),
+            None => {
+                base.insert(key, value);
+            },
+        }
+    }
+}
+
+fn merge_value(base: &mut Value, other: Value) {
+    match (base, other) {
+        (Value::Mapping(base), Value::Mapping(other)) => {
+            for (key, value) in other {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_value(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    },
+                }
+            }
+        },
+        (base, other) => *base = other,
+    }
+}
+
+/// Make the configured values the defaults of `command`'s arguments.
+///
+/// Keys naming a subcommand or argument that doesn't exist, or with a value
+/// that can't be a default, are skipped and returned as warning messages,
+/// so a stale key never stops the CLI from parsing its arguments.
+pub fn apply(mut command: Command, defaults: &CommandDefaults) -> (Command, Vec<String>) {
+    let mut skipped = Vec::new();
+    for (path, value) in flatten(defaults) {
+        let key = path.join(".");
+        let (subcommands, arg) = path.split_at(path.len() - 1);
+        let default = resolve(&command, subcommands, &arg[0]).and_then(|(names, id, action)| {
+            let default = ArgDefault { id, action, values: values(&value)? };
+            default.check()?;
+            Ok((names, default))
+        });
+        match default {
+            Ok((names, default)) => command = default.apply_at(command, &names),
+            Err(reason) => skipped.push(format!("Ignoring defaults.{key}: {reason}")),
+        }
+    }
+    (command, skipped)
+}
+
+/// Every leaf value with its key path, splitting dotted keys.
+fn flatten(defaults: &CommandDefaults) -> Vec<(Vec<String>, Value)> {
+    fn walk(path: &[String], value: &Value, out: &mut Vec<(Vec<String>, Value)>) {
+        match value {
+            Value::Mapping(map) => {
+                for (key, value) in map {
+                    let key = match key {
+                        Value::String(s) => s.clone(),
+                        other => {
+                            serde_yaml::to_string(other).unwrap_or_default().trim().to_string()
+                        },
+                    };
+                    walk(&extend(path, &key), value, out);
+                }
+            },
+            leaf => out.push((path.to_vec(), leaf.clone())),
+        }
+    }
+
+    let mut out = Vec::new();
+    for (key, value) in defaults {
+        walk(&extend(&[], key), value, &mut out);
+    }
+    out
+}
+
+fn extend(path: &[String], key: &str) -> Vec<String> {
+    path.iter().cloned().chain(key.split('.').map(str::to_string)).collect()
+}
+
+/// Find the argument a key path names: the subcommands' real names (not
+/// aliases), the argument's ID, and its action.
+fn resolve(
+    command: &Command,
+    subcommands: &[String],
+    arg: &str,
+) -> std::result::Result<(Vec<String>, String, ArgAction), String> {
+    let mut command = command;
+    let mut names = Vec::new();
+    for name in subcommands {
+        command = command
+            .find_subcommand(name)
+            .ok_or_else(|| format!("'{}' has no '{name}' subcommand", command.get_name()))?;
+        names.push(command.get_name().to_string());
+    }
+
+    let normalized = arg.replace('-', "_");
+    let found = command
+        .get_arguments()
+        .find(|a| {
+            a.get_id().as_str() == normalized
+                || a.get_long().is_some_and(|long| long.replace('-', "_") == normalized)
+        })
+        .ok_or_else(|| format!("'{}' has no '{arg}' argument", command.get_name()))?;
+    Ok((names, found.get_id().to_string(), found.get_action().clone()))
+}
+
+/// A value's strings: scalars, or a list of them for repeatable arguments.
+fn values(value: &Value) -> std::result::Result<Vec<String>, String> {
+    let scalar = |value: &Value| match value {
+        Value::Bool(b) => Ok(b.to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::String(s) => Ok(s.clone()),
+        _ => Err("expected a string, number or boolean".to_string()),
+    };
+    match value {
+        Value::Sequence(items) => items.iter().map(scalar).collect(),
+        other => Ok(vec![scalar(other)?]),
+    }
+}
+
+/// A default for one argument.
+struct ArgDefault {
+    id: String,
+    action: ArgAction,
+    values: Vec<String>,
+}
+
+impl ArgDefault {
+    fn check(&self) -> std::result::Result<(), String> {
+        match self.action {
+            ArgAction::SetTrue | ArgAction::SetFalse => match self.values.as_slice() {
+                [value] if value == "true" || value == "false" => Ok(()),
+                _ => Err("expected true or false".to_string()),
+            },
+            ArgAction::Set | ArgAction::Count if self.values.len() != 1 => {
+                Err("expected a single value".to_string())
+            },
+            ArgAction::Set | ArgAction::Append | ArgAction::Count => Ok(()),
+            _ => Err("this argument can't have a default".to_string()),
+        }
+    }
+
+    /// Set the default on the argument in the subcommand at `path`.
+    fn apply_at(self, command: Command, path: &[String]) -> Command {
+        match path {
+            [] => self.apply(command),
+            [first, rest @ ..] => {
+                let rest = rest.to_vec();
+                command.mut_subcommand(first, move |sub| self.apply_at(sub, &rest))
+            },
+        }
+    }
+
+    fn apply(self, command: Command) -> Command {
+        // The value a flag stores when given, and whether the default changes it
+        let flag = match self.action {
+            ArgAction::SetTrue => Some("true"),
+            ArgAction::SetFalse => Some("false"),
+            _ => None,
+        };
+        command.mut_arg(&self.id, |arg| match flag {
+            Some(given) if self.values[0] != given => arg,
+            // Take an optional value so `--flag=false` can still undo the default
+            Some(given) => arg
+                .action(ArgAction::Set)
+                .num_args(0..=1)
+                .require_equals(true)
+                .value_parser(BoolishValueParser::new())
+                .default_missing_value(given)
+                .default_value(given),
+            None => arg.required(false).default_values(self.values),
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use clap::{Arg, ArgMatches};
+
+    use super::*;
+
+    fn command() -> Command {
+        Command::new("inferadb").subcommand(
+            Command::new("relationships").alias("rel").subcommand(
+                Command::new("list")
+                    .arg(Arg::new("limit").long("limit").default_value("100"))
+                    .arg(Arg::new("show_ids").long("show-ids").action(ArgAction::SetTrue)),
+            ),
+        )
+    }
+
+    fn parse(defaults: &str, args: &[&str]) -> ArgMatches {
+        let defaults: CommandDefaults = serde_yaml::from_str(defaults).unwrap();
+        let (command, skipped) = apply(command(), &defaults);
+        assert!(skipped.is_empty());
+        let matches = command.get_matches_from(args);
+        let (_, relationships) = matches.subcommand().unwrap();
+        relationships.subcommand().unwrap().1.clone()
+    }
+
+    #[test]
+    fn test_apply_defaults() {
+        let nested = "relationships:\n  list:\n    limit: 500\n    show-ids: true\n";
+        let list = parse(nested, &["inferadb", "rel", "list"]);
+        assert_eq!(list.get_one::<String>("limit").unwrap(), "500");
+        assert!(list.get_flag("show_ids"));
+
+        // The command line wins, including turning a flag back off
+        let list = parse(nested, &["inferadb", "rel", "list", "--limit", "5", "--show-ids=false"]);
+        assert_eq!(list.get_one::<String>("limit").unwrap(), "5");
+        assert!(!list.get_flag("show_ids"));
+
+        let dotted = "relationships.list.show_ids: true\n";
+        assert!(
+            parse(dotted, &["inferadb", "relationships", "list", "--show-ids"])
+                .get_flag("show_ids")
+        );
+    }
+
+    #[test]
+    fn test_apply_defaults_skips_invalid_keys() {
+        for (yaml, message) in [
+            ("relationships.lst.limit: 5", "'relationships' has no 'lst' subcommand"),
+            ("relationships.list.limt: 5", "'list' has no 'limt' argument"),
+            ("relationships.list.show-ids: 5", "expected true or false"),
+            ("relationships.list.limit: [1, 2]", "expected a single value"),
+        ] {
+            let defaults: CommandDefaults = serde_yaml::from_str(yaml).unwrap();
+            let (_, skipped) = apply(command(), &defaults);
+            assert_eq!(skipped.len(), 1, "{yaml}");
+            assert!(skipped[0].contains(message), "{yaml}: {}", skipped[0]);
+        }
+
+        // Valid keys still apply next to a stale one
+        let defaults: CommandDefaults =
+            serde_yaml::from_str("relationships:\n  lst.limit: 5\n  list.limit: 500\n").unwrap();
+        let (command, skipped) = apply(command(), &defaults);
+        assert_eq!(skipped.len(), 1);
+        let matches = command.get_matches_from(["inferadb", "rel", "list"]);
+        let list = matches.subcommand().unwrap().1.subcommand().unwrap().1;
+        assert_eq!(list.get_one::<String>("limit").unwrap(), "500");
+    }
+
+    #[test]
+    fn test_merge_defaults() {
+        let mut base: CommandDefaults =
+            serde_yaml::from_str("check:\n  explain: true\n  trace: true\n").unwrap();
+        merge(&mut base, serde_yaml::from_str("check:\n  explain: false\n").unwrap());
+        let flat = flatten(&base);
+        assert_eq!(flat[0], (vec!["check".to_string(), "explain".to_string()], Value::Bool(false)));
+        assert_eq!(flat[1], (vec!["check".to_string(), "trace".to_string()], Value::Bool(true)));
+    }
+}
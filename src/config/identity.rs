@@ -0,0 +1,118 @@
+//! Cached identity for offline `whoami` and `status`.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Config, StateLock};
+use crate::error::{Error, Result};
+
+/// The account a profile was last confirmed to be signed in as.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedIdentity {
+    /// Account ID.
+    pub id: String,
+    /// Primary email address.
+    pub email: String,
+    /// Display name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// When the server last returned this identity.
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Identity cache stored in the state directory, keyed by profile name.
+pub struct IdentityCache {
+    path: Option<PathBuf>,
+}
+
+impl IdentityCache {
+    /// Create an identity cache at the default location.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { path: Config::state_dir().map(|dir| dir.join("identity.yaml")) }
+    }
+
+    fn path(&self) -> Result<&PathBuf> {
+        self.path.as_ref().ok_or_else(|| Error::config("Could not determine state directory"))
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, CachedIdentity>> {
+        let path = self.path()?;
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        if contents.trim().is_empty() {
+            return Ok(BTreeMap::new());
+        }
+        serde_yaml::from_str(&contents)
+            .map_err(|e| Error::config(format!("Invalid identity cache {}: {e}", path.display())))
+    }
+
+    fn write(&self, entries: &BTreeMap<String, CachedIdentity>) -> Result<()> {
+        let path = self.path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_yaml::to_string(entries)?)?;
+        Ok(())
+    }
+
+    /// Store the identity for a profile.
+    pub fn store(&self, profile: &str, identity: &CachedIdentity) -> Result<()> {
+        let _lock = StateLock::acquire(self.path()?, "the identity cache")?;
+        let mut entries = self.read()?;
+        entries.insert(profile.to_string(), identity.clone());
+        self.write(&entries)
+    }
+
+    /// Load the cached identity for a profile.
+    pub fn load(&self, profile: &str) -> Result<Option<CachedIdentity>> {
+        Ok(self.read()?.remove(profile))
+    }
+
+    /// Delete the cached identity for a profile.
+    pub fn delete(&self, profile: &str) -> Result<()> {
+        let _lock = StateLock::acquire(self.path()?, "the identity cache")?;
+        let mut entries = self.read()?;
+        if entries.remove(profile).is_some() {
+            self.write(&entries)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for IdentityCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_cache_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = IdentityCache { path: Some(dir.path().join("identity.yaml")) };
+        let identity = CachedIdentity {
+            id: "usr_123".to_string(),
+            email: "alice@example.com".to_string(),
+            name: Some("Alice".to_string()),
+            fetched_at: DateTime::UNIX_EPOCH,
+        };
+
+        assert_eq!(cache.load("default").unwrap(), None);
+
+        cache.store("default", &identity).unwrap();
+        assert_eq!(cache.load("default").unwrap(), Some(identity));
+        assert_eq!(cache.load("prod").unwrap(), None);
+
+        cache.delete("default").unwrap();
+        assert_eq!(cache.load("default").unwrap(), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! Local audit log of checks run as another subject.
+//!
+//! Impersonation is recorded here rather than in the check request, so the
+//! context the engine evaluates is exactly what the user passed.
+
+use std::{io::Write, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::Config;
+use crate::error::{Error, Result};
+
+/// A check run on behalf of another subject.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpersonationRecord {
+    /// When the check was run.
+    pub at: DateTime<Utc>,
+    /// Profile the check ran under.
+    pub profile: String,
+    /// Account ID of the signed-in user.
+    pub account_id: String,
+    /// Email address of the signed-in user.
+    pub account_email: String,
+    /// Vault the check ran against.
+    pub vault: String,
+    /// Subject checked as.
+    pub subject: String,
+    /// Permission checked.
+    pub permission: String,
+    /// Resource checked.
+    pub resource: String,
+}
+
+/// Append-only impersonation log in the state directory, one JSON record per
+/// line.
+pub struct ImpersonationLog {
+    path: Option<PathBuf>,
+}
+
+impl ImpersonationLog {
+    /// Open the impersonation log at the default location.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { path: Config::state_dir().map(|dir| dir.join("impersonation.jsonl")) }
+    }
+
+    fn path(&self) -> Result<&PathBuf> {
+        self.path.as_ref().ok_or_else(|| Error::config("Could not determine state directory"))
+    }
+
+    /// Append a record.
+    pub fn append(&self, record: &ImpersonationRecord) -> Result<()> {
+        let path = self.path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Every record, oldest first.
+    pub fn load(&self) -> Result<Vec<ImpersonationRecord>> {
+        let path = self.path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        std::fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| {
+                    Error::config(format!("Invalid impersonation log {}: {e}", path.display()))
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for ImpersonationLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_impersonation_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ImpersonationLog { path: Some(dir.path().join("impersonation.jsonl")) };
+        assert!(log.load().unwrap().is_empty());
+
+        let record = |subject: &str| ImpersonationRecord {
+            at: Utc::now(),
+            profile: "prod".to_string(),
+            account_id: "acct_1".to_string(),
+            account_email: "alice@example.com".to_string(),
+            vault: "42".to_string(),
+            subject: subject.to_string(),
+            permission: "view".to_string(),
+            resource: "document:1".to_string(),
+        };
+        let first = record("user:bob");
+        let second = record("user:carol");
+        log.append(&first).unwrap();
+        log.append(&second).unwrap();
+        assert_eq!(log.load().unwrap(), [first, second]);
+    }
+}
@@ -0,0 +1,102 @@
+//! Advisory locks on state-directory files.
+//!
+//! Several invocations can run at once (a script in one terminal, a shell in
+//! another), and the response cache, identity cache, saved cursors, stats
+//! snapshots, shell history, refactor checkpoints, retag journals and profile
+//! usage are all read, changed and written back. Each of those files gets its own lock file
+//! beside it (`<file>.lock`), so unrelated features never wait on each other.
+//!
+//! A contended lock is waited for, unless `--non-blocking` was given, in which
+//! case acquiring it fails with [`Error::Locked`].
+
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::error::{Error, Result};
+
+/// Whether `--non-blocking` was given.
+static NON_BLOCKING: AtomicBool = AtomicBool::new(false);
+
+/// Fail instead of waiting when a lock is held, for the rest of the process.
+pub fn set_non_blocking(enabled: bool) {
+    NON_BLOCKING.store(enabled, Ordering::Relaxed);
+}
+
+/// An exclusive lock on a state file, released when dropped.
+#[derive(Debug)]
+#[must_use = "the lock is released when dropped"]
+pub struct StateLock {
+    file: File,
+}
+
+impl StateLock {
+    /// Lock `path` (through `<path>.lock`), waiting for other invocations to
+    /// release it unless `--non-blocking` was given. `what` names the file in
+    /// messages, e.g. "the response cache".
+    pub fn acquire(path: &Path, what: &str) -> Result<Self> {
+        Self::acquire_with(path, what, NON_BLOCKING.load(Ordering::Relaxed))
+    }
+
+    fn acquire_with(path: &Path, what: &str, non_blocking: bool) -> Result<Self> {
+        let lock_path = lock_path(path);
+        if let Some(parent) = lock_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).truncate(false).write(true).open(&lock_path)?;
+
+        match file.try_lock() {
+            Ok(()) => {},
+            Err(TryLockError::WouldBlock) if non_blocking => {
+                return Err(Error::Locked(format!("{what} ({})", lock_path.display())));
+            },
+            Err(TryLockError::WouldBlock) => {
+                tracing::debug!(lock = %lock_path.display(), "waiting for lock");
+                eprintln!("Waiting for another inferadb operation using {what}...");
+                file.lock()?;
+            },
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+        Ok(Self { file })
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cursors.yaml");
+
+        let held = StateLock::acquire_with(&path, "saved cursors", true).unwrap();
+        assert!(dir.path().join("cursors.yaml.lock").exists());
+
+        let err = StateLock::acquire_with(&path, "saved cursors", true).unwrap_err();
+        assert_eq!(err.exit_code(), 6);
+        assert!(err.to_string().starts_with("Another operation is using saved cursors"));
+
+        // Other files have their own locks
+        let _other =
+            StateLock::acquire_with(&dir.path().join("stats.yaml"), "stats", true).unwrap();
+
+        drop(held);
+        let _again = StateLock::acquire_with(&path, "saved cursors", true).unwrap();
+    }
+}
@@ -2,19 +2,34 @@
 //!
 //! The configuration follows XDG Base Directory Specification and supports:
 //! - User config: `~/.config/inferadb/cli.yaml`
-//! - Project config: `.inferadb-cli.yaml` in current directory
+//! - Project config: the nearest trusted `.inferadb-cli.yaml`, which can pin a profile
 //! - Environment variables: `INFERADB_*`
 //! - Command-line flags (highest precedence)
 
+mod cache;
+pub mod credentials;
+pub mod defaults;
+mod identity;
+mod impersonation;
+pub mod lock;
 mod profile;
+pub mod project;
+mod usage;
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
 };
 
+pub use cache::{CacheEntryInfo, ResponseCache};
+pub use credentials::CredentialBackend;
+pub use defaults::CommandDefaults;
+pub use identity::{CachedIdentity, IdentityCache};
+pub use impersonation::{ImpersonationLog, ImpersonationRecord};
+pub use lock::StateLock;
 pub use profile::{CredentialStore, Credentials, Profile};
 use serde::{Deserialize, Serialize};
+pub use usage::ProfileUsage;
 
 use crate::error::{Error, Result};
 
@@ -32,10 +47,81 @@ pub struct Config {
     /// Output configuration.
     #[serde(default)]
     pub output: OutputConfig,
+
+    /// Authentication configuration.
+    #[serde(default)]
+    pub auth: AuthConfig,
+
+    /// Response cache configuration.
+    #[serde(default)]
+    pub cache: CacheConfig,
+
+    /// Completion notification configuration.
+    #[serde(default)]
+    pub notify: NotifyConfig,
+
+    /// Argument defaults by command (e.g., `relationships.list.limit: 500`).
+    #[serde(default, skip_serializing_if = "CommandDefaults::is_empty")]
+    pub defaults: CommandDefaults,
+
+    /// Profile pinned for commands run in a project's directory tree; only
+    /// read from the project config, and never saved to the user config.
+    #[serde(default, skip_serializing)]
+    pub profile: Option<String>,
 }
 
-/// Output formatting configuration.
+/// Authentication configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Warn at command start when the session token expires within this many
+    /// minutes (0 disables the warning).
+    #[serde(default = "default_expiry_warning_minutes")]
+    pub expiry_warning_minutes: u32,
+}
+
+const fn default_expiry_warning_minutes() -> u32 {
+    15
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self { expiry_warning_minutes: default_expiry_warning_minutes() }
+    }
+}
+
+/// Response cache configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Serve list and get commands from the cache without `--cached`.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Seconds a cached response stays fresh.
+    #[serde(default = "default_cache_ttl_seconds")]
+    pub ttl_seconds: u64,
+}
+
+const fn default_cache_ttl_seconds() -> u64 {
+    300
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { enabled: false, ttl_seconds: default_cache_ttl_seconds() }
+    }
+}
+
+/// Completion notification configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    /// Slack, Teams, or generic webhook URL that receives a summary when
+    /// imports, bulk writes, schema pushes, tests and migrations finish.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<String>,
+}
+
+/// Output formatting configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OutputConfig {
     /// Default output format (table, json, yaml).
     #[serde(default = "default_format")]
@@ -44,6 +130,45 @@ pub struct OutputConfig {
     /// Color output mode (auto, always, never).
     #[serde(default = "default_color")]
     pub color: String,
+
+    /// Use Unicode glyphs and box drawing; `false` degrades them to ASCII.
+    #[serde(default = "default_unicode")]
+    pub unicode: bool,
+
+    /// Use screen-reader friendly output (no animations, glyphs, or colors).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub accessible: bool,
+
+    /// Warning codes to silence (e.g., `token_expiring`, or `all`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppress_warnings: Vec<String>,
+
+    /// Status colors and symbols.
+    #[serde(default, skip_serializing_if = "ThemeConfig::is_empty")]
+    pub theme: ThemeConfig,
+}
+
+/// Status color theme configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    /// Base palette (`default` or `deuteranopia`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+
+    /// Colors by role (`success`, `warning`, `error`, `info`): a color name,
+    /// `#rrggbb`, or an ANSI 256 index.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub colors: BTreeMap<String, String>,
+
+    /// Symbols by role, replacing the default glyphs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub symbols: BTreeMap<String, String>,
+}
+
+impl ThemeConfig {
+    fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
 }
 
 fn default_format() -> String {
@@ -54,9 +179,20 @@ fn default_color() -> String {
     "auto".to_string()
 }
 
+const fn default_unicode() -> bool {
+    true
+}
+
 impl Default for OutputConfig {
     fn default() -> Self {
-        Self { format: default_format(), color: default_color() }
+        Self {
+            format: default_format(),
+            color: default_color(),
+            unicode: default_unicode(),
+            accessible: false,
+            suppress_warnings: Vec::new(),
+            theme: ThemeConfig::default(),
+        }
     }
 }
 
@@ -66,7 +202,7 @@ impl Config {
     /// Resolution order (highest to lowest):
     /// 1. CLI flags (handled separately)
     /// 2. Environment variables
-    /// 3. Project config (`.inferadb-cli.yaml`)
+    /// 3. Project config (the nearest trusted `.inferadb-cli.yaml`)
     /// 4. User config (`~/.config/inferadb/cli.yaml`)
     /// 5. Defaults
     pub fn load() -> Result<Self> {
@@ -80,11 +216,16 @@ impl Config {
             config.merge(user_config);
         }
 
-        // Load project config (higher precedence)
-        let project_path = PathBuf::from(".inferadb-cli.yaml");
-        if project_path.exists() {
-            let project_config = Self::load_from_file(&project_path)?;
-            config.merge(project_config);
+        // Load the nearest project config (higher precedence), once trusted
+        if let Some(project_path) = project::find() {
+            if project::TrustStore::new().is_trusted(&project_path) {
+                let project_config = Self::load_from_file(&project_path)?;
+                let pinned = project_config.profile.clone();
+                config.merge(project_config);
+                config.profile = pinned;
+            } else {
+                tracing::debug!(path = %project_path.display(), "ignoring untrusted project config");
+            }
         }
 
         // Apply environment variables (highest precedence for profiles)
@@ -99,6 +240,16 @@ impl Config {
         Ok(config)
     }
 
+    /// The profile a trusted project config pins, unless `INFERADB_PROFILE`
+    /// names one.
+    #[must_use]
+    pub fn pinned_profile(&self) -> Option<&str> {
+        if std::env::var_os("INFERADB_PROFILE").is_some() {
+            return None;
+        }
+        self.profile.as_deref()
+    }
+
     /// Load configuration from a YAML file.
     pub fn load_from_file(path: &Path) -> Result<Self> {
         let contents = std::fs::read_to_string(path).map_err(|e| {
@@ -143,6 +294,40 @@ impl Config {
         if other.output.color != default_color() {
             self.output.color = other.output.color;
         }
+
+        if !other.output.unicode {
+            self.output.unicode = false;
+        }
+
+        if other.output.accessible {
+            self.output.accessible = true;
+        }
+
+        self.output.suppress_warnings.extend(other.output.suppress_warnings);
+
+        if other.output.theme.preset.is_some() {
+            self.output.theme.preset = other.output.theme.preset;
+        }
+        self.output.theme.colors.extend(other.output.theme.colors);
+        self.output.theme.symbols.extend(other.output.theme.symbols);
+
+        if other.auth.expiry_warning_minutes != default_expiry_warning_minutes() {
+            self.auth.expiry_warning_minutes = other.auth.expiry_warning_minutes;
+        }
+
+        if other.cache.enabled {
+            self.cache.enabled = true;
+        }
+
+        if other.cache.ttl_seconds != default_cache_ttl_seconds() {
+            self.cache.ttl_seconds = other.cache.ttl_seconds;
+        }
+
+        if other.notify.webhook.is_some() {
+            self.notify.webhook = other.notify.webhook;
+        }
+
+        defaults::merge(&mut self.defaults, other.defaults);
     }
 
     /// Apply environment variable overrides.
@@ -343,6 +528,65 @@ mod tests {
         assert!(config.profiles.is_empty());
         assert_eq!(config.output.format, "table");
         assert_eq!(config.output.color, "auto");
+        assert!(config.output.unicode);
+        assert!(!config.output.accessible);
+    }
+
+    #[test]
+    fn test_merge_unicode_output() {
+        let mut config = Config::default();
+        let project: Config = serde_yaml::from_str("output:\n  unicode: false\n").unwrap();
+        config.merge(project);
+        assert!(!config.output.unicode);
+
+        config.merge(Config::default());
+        assert!(!config.output.unicode);
+    }
+
+    #[test]
+    fn test_merge_accessible_output() {
+        let mut config = Config::default();
+        let project: Config = serde_yaml::from_str("output:\n  accessible: true\n").unwrap();
+        config.merge(project);
+        assert!(config.output.accessible);
+        assert_eq!(config.output.format, "table");
+
+        config.merge(Config::default());
+        assert!(config.output.accessible);
+    }
+
+    #[test]
+    fn test_merge_suppress_warnings() {
+        let mut config = Config::default();
+        let user: Config =
+            serde_yaml::from_str("output:\n  suppress_warnings: [token_expiring]\n").unwrap();
+        config.merge(user);
+        let project: Config =
+            serde_yaml::from_str("output:\n  suppress_warnings: [invalid_default]\n").unwrap();
+        config.merge(project);
+        assert_eq!(config.output.suppress_warnings, ["token_expiring", "invalid_default"]);
+    }
+
+    #[test]
+    fn test_merge_theme_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cli.yaml");
+        std::fs::write(
+            &path,
+            "output:\n  theme:\n    preset: deuteranopia\n    colors:\n      error: magenta\n",
+        )
+        .unwrap();
+
+        let mut config = Config::default();
+        config.merge(Config::load_from_file(&path).unwrap());
+        let project: Config =
+            serde_yaml::from_str("output:\n  theme:\n    colors:\n      success: blue\n").unwrap();
+        config.merge(project);
+
+        let theme = &config.output.theme;
+        assert_eq!(theme.preset.as_deref(), Some("deuteranopia"));
+        assert_eq!(theme.colors["error"], "magenta");
+        assert_eq!(theme.colors["success"], "blue");
     }
 
     #[test]
@@ -354,6 +598,7 @@ mod tests {
                 url: Some("https://test.example.com".to_string()),
                 org: Some("org123".to_string()),
                 vault: Some("vault456".to_string()),
+                ..Profile::default()
             },
         );
 
@@ -370,6 +615,7 @@ mod tests {
                 url: Some("https://test.example.com".to_string()),
                 org: Some("org123".to_string()),
                 vault: Some("vault456".to_string()),
+                ..Profile::default()
             },
         );
 
@@ -6,8 +6,10 @@
 use bon::Builder;
 use serde::{Deserialize, Serialize};
 
+use super::credentials::{self, CredentialBackend};
+
 /// A named profile representing a complete connection target.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct Profile {
     /// API endpoint URL.
     #[serde(default)]
@@ -20,12 +22,37 @@ pub struct Profile {
     /// Vault ID (Snowflake ID).
     #[serde(default)]
     pub vault: Option<String>,
+
+    /// Subject reference of the authenticated principal (e.g., `user:alice`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub principal: Option<String>,
+
+    /// Require `--impersonate` to check as a subject other than the principal.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_impersonate: bool,
+
+    /// Only activate schema versions with a verified bundle signature.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_signed_schemas: bool,
+
+    /// Timezone for displayed timestamps (IANA name, `UTC`, or offset).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// Where the profile's credentials are stored.
+    #[serde(default, skip_serializing_if = "CredentialBackend::is_default")]
+    pub credential_store: CredentialBackend,
 }
 
 impl Profile {
     /// Create a new profile with all fields specified.
     pub fn new(url: impl Into<String>, org: impl Into<String>, vault: impl Into<String>) -> Self {
-        Self { url: Some(url.into()), org: Some(org.into()), vault: Some(vault.into()) }
+        Self {
+            url: Some(url.into()),
+            org: Some(org.into()),
+            vault: Some(vault.into()),
+            ..Self::default()
+        }
     }
 
     /// Get the URL, returning an error if not set.
@@ -86,6 +113,15 @@ pub struct Credentials {
 
     /// Token expiration timestamp.
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Org client the credentials were issued to, for service-account logins.
+    #[builder(into)]
+    pub client_id: Option<String>,
+
+    /// Path to the client's Ed25519 private key (PEM), used to request new
+    /// tokens once these expire.
+    #[builder(into)]
+    pub client_key: Option<String>,
 }
 
 impl Credentials {
@@ -104,14 +140,31 @@ impl Credentials {
         })
     }
 
+    /// Time left before the credentials expire, if that is within `window`.
+    ///
+    /// Returns `None` for credentials without an expiry, already expired
+    /// credentials, and expiry further out than `window`.
+    #[must_use]
+    pub fn expires_within(&self, window: chrono::Duration) -> Option<chrono::Duration> {
+        let remaining = self.expires_at? - chrono::Utc::now();
+        (remaining > chrono::Duration::zero() && remaining <= window).then_some(remaining)
+    }
+
     /// Check if the credentials can be refreshed.
     #[must_use]
     pub const fn can_refresh(&self) -> bool {
-        self.refresh_token.is_some()
+        self.refresh_token.is_some() || self.is_client()
+    }
+
+    /// Check if the credentials belong to an org client rather than a user.
+    #[must_use]
+    pub const fn is_client(&self) -> bool {
+        self.client_id.is_some() && self.client_key.is_some()
     }
 }
 
-/// Credential storage using the OS keychain.
+/// Credential storage in the OS keychain, or in a file for profiles with a
+/// file `credential_store`.
 pub struct CredentialStore {
     service: String,
 }
@@ -130,60 +183,108 @@ impl CredentialStore {
 
     /// Store credentials for a profile.
     pub fn store(&self, profile: &str, credentials: &Credentials) -> crate::Result<()> {
-        let entry = self.entry(profile)?;
+        self.store_in(credentials::backend_for(profile), profile, credentials)
+    }
 
+    fn store_in(
+        &self,
+        backend: CredentialBackend,
+        profile: &str,
+        credentials: &Credentials,
+    ) -> crate::Result<()> {
         // Store as JSON for structured data
         let data = serde_json::json!({
             "access_token": credentials.access_token,
             "refresh_token": credentials.refresh_token,
             "expires_at": credentials.expires_at,
+            "client_id": credentials.client_id,
+            "client_key": credentials.client_key,
         });
 
-        entry.set_password(&data.to_string())?;
+        match backend {
+            CredentialBackend::Keychain => self.entry(profile)?.set_password(&data.to_string())?,
+            file => credentials::write(file, profile, &data.to_string())?,
+        }
         Ok(())
     }
 
     /// Load credentials for a profile.
     pub fn load(&self, profile: &str) -> crate::Result<Option<Credentials>> {
-        let entry = self.entry(profile)?;
+        self.load_from(credentials::backend_for(profile), profile)
+    }
+
+    fn load_from(
+        &self,
+        backend: CredentialBackend,
+        profile: &str,
+    ) -> crate::Result<Option<Credentials>> {
+        let data = match backend {
+            CredentialBackend::Keychain => match self.entry(profile)?.get_password() {
+                Ok(data) => data,
+                Err(keyring::Error::NoEntry) => return Ok(None),
+                Err(e) => return Err(e.into()),
+            },
+            file => match credentials::read(file, profile)? {
+                Some(data) => data,
+                None => return Ok(None),
+            },
+        };
 
-        match entry.get_password() {
-            Ok(data) => {
-                let value: serde_json::Value = serde_json::from_str(&data)?;
+        let value: serde_json::Value = serde_json::from_str(&data)?;
 
-                let access_token = value["access_token"]
-                    .as_str()
-                    .ok_or_else(|| crate::error::Error::credential("Missing access token"))?
-                    .to_string();
+        let access_token = value["access_token"]
+            .as_str()
+            .ok_or_else(|| crate::error::Error::credential("Missing access token"))?
+            .to_string();
 
-                let refresh_token =
-                    value["refresh_token"].as_str().map(std::string::ToString::to_string);
+        let refresh_token = value["refresh_token"].as_str().map(std::string::ToString::to_string);
 
-                let expires_at = value["expires_at"]
-                    .as_str()
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&chrono::Utc));
+        let expires_at = value["expires_at"]
+            .as_str()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
 
-                Ok(Some(Credentials { access_token, refresh_token, expires_at }))
-            },
-            Err(keyring::Error::NoEntry) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+        let client_id = value["client_id"].as_str().map(std::string::ToString::to_string);
+        let client_key = value["client_key"].as_str().map(std::string::ToString::to_string);
+
+        Ok(Some(Credentials { access_token, refresh_token, expires_at, client_id, client_key }))
     }
 
     /// Delete credentials for a profile.
     pub fn delete(&self, profile: &str) -> crate::Result<()> {
-        let entry = self.entry(profile)?;
-        match entry.delete_credential() {
-            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()), // Already deleted is OK
-            Err(e) => Err(e.into()),
+        self.delete_from(credentials::backend_for(profile), profile)
+    }
+
+    fn delete_from(&self, backend: CredentialBackend, profile: &str) -> crate::Result<()> {
+        match backend {
+            CredentialBackend::Keychain => match self.entry(profile)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()), // Already deleted is OK
+                Err(e) => Err(e.into()),
+            },
+            file => credentials::delete(file, profile),
         }
     }
 
+    /// Move a profile's credentials to a new profile name, in the same
+    /// backend.
+    pub fn rename(&self, old: &str, new: &str) -> crate::Result<()> {
+        let backend = credentials::backend_for(old);
+        if let Some(creds) = self.load_from(backend, old)? {
+            self.store_in(backend, new, &creds)?;
+            self.delete_from(backend, old)?;
+        }
+        Ok(())
+    }
+
     /// Check if credentials exist for a profile.
     #[must_use]
     pub fn exists(&self, profile: &str) -> bool {
-        self.entry(profile).map(|e| e.get_password().is_ok()).unwrap_or(false)
+        match credentials::backend_for(profile) {
+            CredentialBackend::Keychain => {
+                self.entry(profile).map(|e| e.get_password().is_ok()).unwrap_or(false)
+            },
+            file => credentials::exists(file, profile),
+        }
     }
 }
 
@@ -194,6 +295,7 @@ impl Default for CredentialStore {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
 
@@ -230,6 +332,22 @@ mod tests {
         assert!(expired.is_expired());
     }
 
+    #[test]
+    fn test_credentials_expires_within() {
+        let window = chrono::Duration::minutes(15);
+        let expires_in = |d| {
+            Credentials::builder().access_token("token").expires_at(chrono::Utc::now() + d).build()
+        };
+
+        let remaining = expires_in(chrono::Duration::minutes(10)).expires_within(window).unwrap();
+        assert!(remaining <= chrono::Duration::minutes(10));
+        assert!(expires_in(chrono::Duration::hours(1)).expires_within(window).is_none());
+        assert!(expires_in(-chrono::Duration::minutes(1)).expires_within(window).is_none());
+        assert!(
+            Credentials::builder().access_token("token").build().expires_within(window).is_none()
+        );
+    }
+
     #[test]
     fn test_credentials_builder_defaults() {
         let creds = Credentials::builder().access_token("token").build();
@@ -237,5 +355,17 @@ mod tests {
         assert!(creds.refresh_token.is_none());
         assert!(creds.expires_at.is_none());
         assert!(!creds.can_refresh());
+        assert!(!creds.is_client());
+    }
+
+    #[test]
+    fn test_client_credentials_can_refresh() {
+        let creds = Credentials::builder()
+            .access_token("token")
+            .client_id("client_abc")
+            .client_key("/etc/inferadb/client.pem")
+            .build();
+        assert!(creds.is_client());
+        assert!(creds.can_refresh());
     }
 }
@@ -0,0 +1,219 @@
+//! Project configuration and directory trust.
+//!
+//! Commands use the nearest `.inferadb-cli.yaml` in the current directory or
+//! one of its parents, which can pin a profile for everything run inside that
+//! tree. Because a project file can also point profiles at any endpoint, it is
+//! only used once trusted, like direnv: the first run in the tree asks, and
+//! `inferadb config trust` / `untrust` manage it explicitly. Trust covers the
+//! file's exact contents, so any change has to be trusted again.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+
+use super::{Config, StateLock};
+use crate::error::{Error, Result};
+
+/// Name of a project config file.
+pub const PROJECT_CONFIG_FILE: &str = ".inferadb-cli.yaml";
+
+/// The nearest project config, searching up from the current directory.
+#[must_use]
+pub fn find() -> Option<PathBuf> {
+    find_from(&std::env::current_dir().ok()?)
+}
+
+fn find_from(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().map(|dir| dir.join(PROJECT_CONFIG_FILE)).find(|path| path.is_file())
+}
+
+/// Trusted project configs stored in the state directory: each file's
+/// canonical path and the SHA-256 of the contents that were trusted.
+pub struct TrustStore {
+    path: Option<PathBuf>,
+}
+
+impl TrustStore {
+    /// Open the trust store at the default location.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { path: Config::state_dir().map(|dir| dir.join("trusted-projects.yaml")) }
+    }
+
+    fn path(&self) -> Result<&PathBuf> {
+        self.path.as_ref().ok_or_else(|| Error::config("Could not determine state directory"))
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>> {
+        let path = self.path()?;
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        if contents.trim().is_empty() {
+            return Ok(BTreeMap::new());
+        }
+        serde_yaml::from_str(&contents)
+            .map_err(|e| Error::config(format!("Invalid trust store {}: {e}", path.display())))
+    }
+
+    fn write(&self, entries: &BTreeMap<String, String>) -> Result<()> {
+        let path = self.path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_yaml::to_string(entries)?)?;
+        Ok(())
+    }
+
+    /// Whether `file` is trusted with its current contents. Unreadable files
+    /// and trust stores count as untrusted.
+    #[must_use]
+    pub fn is_trusted(&self, file: &Path) -> bool {
+        let trusted = || -> Result<bool> {
+            let entries = self.read()?;
+            let Some(trusted) = entries.get(&key(file)?) else {
+                return Ok(false);
+            };
+            Ok(*trusted == digest_of(file)?)
+        };
+        trusted().unwrap_or_else(|e| {
+            tracing::debug!(file = %file.display(), error = %e, "could not check project trust");
+            false
+        })
+    }
+
+    /// Trust `file` with its current contents.
+    pub fn trust(&self, file: &Path) -> Result<()> {
+        let _lock = StateLock::acquire(self.path()?, "trusted projects")?;
+        let mut entries = self.read()?;
+        entries.insert(key(file)?, digest_of(file)?);
+        self.write(&entries)
+    }
+
+    /// Stop trusting `file`, returning whether it was trusted.
+    pub fn untrust(&self, file: &Path) -> Result<bool> {
+        let _lock = StateLock::acquire(self.path()?, "trusted projects")?;
+        let mut entries = self.read()?;
+        let key = key(file).unwrap_or_else(|_| file.display().to_string());
+        let removed = entries.remove(&key).is_some();
+        if removed {
+            self.write(&entries)?;
+        }
+        Ok(removed)
+    }
+}
+
+impl Default for TrustStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn key(file: &Path) -> Result<String> {
+    Ok(std::fs::canonicalize(file)?.display().to_string())
+}
+
+fn digest_of(file: &Path) -> Result<String> {
+    let digest = Sha256::digest(std::fs::read(file)?);
+    Ok(digest.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    }))
+}
+
+/// What a project config sets, one line per setting worth reviewing before
+/// trusting it.
+pub fn summary(file: &Path) -> Result<Vec<String>> {
+    let config = Config::load_from_file(file)?;
+    let mut lines = Vec::new();
+    if let Some(ref profile) = config.profile {
+        lines.push(format!("pins profile '{profile}'"));
+    }
+    if let Some(ref profile) = config.default_profile {
+        lines.push(format!("default profile '{profile}'"));
+    }
+    let mut profiles: Vec<_> = config.profiles.iter().collect();
+    profiles.sort_by_key(|(name, _)| name.as_str());
+    for (name, profile) in profiles {
+        lines.push(format!("profile '{name}' at {}", profile.url_or_default()));
+    }
+    if !config.defaults.is_empty() {
+        lines.push("command defaults".to_string());
+    }
+    if config.notify.webhook.is_some() {
+        lines.push("a notification webhook".to_string());
+    }
+    Ok(lines)
+}
+
+/// Offer to trust the nearest project config if it is new or changed.
+///
+/// Asks on an interactive terminal; otherwise, or when declined, warns that
+/// the file is ignored.
+pub fn confirm_trust() -> Result<()> {
+    let Some(path) = find() else {
+        return Ok(());
+    };
+    let store = TrustStore::new();
+    if store.is_trusted(&path) {
+        return Ok(());
+    }
+
+    if teapot::output::is_tty() && !teapot::output::is_ci() {
+        eprintln!("{} is new or has changed. It sets:", path.display());
+        for line in summary(&path)? {
+            eprintln!("  {line}");
+        }
+        if crate::tui::confirm(&format!("Trust {} for this directory tree?", path.display()))? {
+            return store.trust(&path);
+        }
+    }
+    eprintln!(
+        "Warning: Ignoring untrusted {}; run 'inferadb config trust' to use it.",
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_from_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("services/api");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_from(&nested), None);
+
+        std::fs::write(dir.path().join(PROJECT_CONFIG_FILE), "profile: staging\n").unwrap();
+        assert_eq!(find_from(&nested), Some(dir.path().join(PROJECT_CONFIG_FILE)));
+    }
+
+    #[test]
+    fn test_trust_covers_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TrustStore { path: Some(dir.path().join("trusted-projects.yaml")) };
+        let file = dir.path().join(PROJECT_CONFIG_FILE);
+        std::fs::write(&file, "profile: staging\n").unwrap();
+        assert!(!store.is_trusted(&file));
+
+        store.trust(&file).unwrap();
+        assert!(store.is_trusted(&file));
+
+        // Changed contents need trusting again
+        std::fs::write(&file, "profile: prod\n").unwrap();
+        assert!(!store.is_trusted(&file));
+
+        store.trust(&file).unwrap();
+        assert!(store.untrust(&file).unwrap());
+        assert!(!store.is_trusted(&file));
+        assert!(!store.untrust(&file).unwrap());
+    }
+}
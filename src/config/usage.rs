@@ -0,0 +1,104 @@
+//! When each profile's stored credentials were last used, for
+//! `profiles status`.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::{Config, StateLock};
+use crate::error::{Error, Result};
+
+/// Uses closer together than this are recorded once, so a burst of commands
+/// doesn't rewrite the file (or wait on its lock) every time.
+const RESOLUTION: Duration = Duration::minutes(1);
+
+/// Last-use times stored in the state directory, keyed by profile name.
+pub struct ProfileUsage {
+    path: Option<PathBuf>,
+}
+
+impl ProfileUsage {
+    /// Open the usage record at the default location.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { path: Config::state_dir().map(|dir| dir.join("profile-usage.yaml")) }
+    }
+
+    fn path(&self) -> Result<&PathBuf> {
+        self.path.as_ref().ok_or_else(|| Error::config("Could not determine state directory"))
+    }
+
+    /// Last-use times of every profile with a record.
+    pub fn load_all(&self) -> Result<BTreeMap<String, DateTime<Utc>>> {
+        let path = self.path()?;
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let contents = std::fs::read_to_string(path)?;
+        if contents.trim().is_empty() {
+            return Ok(BTreeMap::new());
+        }
+        serde_yaml::from_str(&contents)
+            .map_err(|e| Error::config(format!("Invalid profile usage {}: {e}", path.display())))
+    }
+
+    fn write(&self, entries: &BTreeMap<String, DateTime<Utc>>) -> Result<()> {
+        let path = self.path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_yaml::to_string(entries)?)?;
+        Ok(())
+    }
+
+    /// Record that a profile's credentials were used now.
+    pub fn record(&self, profile: &str) -> Result<()> {
+        let now = Utc::now();
+        if self.load_all()?.get(profile).is_some_and(|last| now - *last < RESOLUTION) {
+            return Ok(());
+        }
+        let _lock = StateLock::acquire(self.path()?, "profile usage")?;
+        let mut entries = self.load_all()?;
+        entries.insert(profile.to_string(), now);
+        self.write(&entries)
+    }
+
+    /// Forget a profile's last use.
+    pub fn delete(&self, profile: &str) -> Result<()> {
+        let _lock = StateLock::acquire(self.path()?, "profile usage")?;
+        let mut entries = self.load_all()?;
+        if entries.remove(profile).is_some() {
+            self.write(&entries)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProfileUsage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let usage = ProfileUsage { path: Some(dir.path().join("profile-usage.yaml")) };
+        assert!(usage.load_all().unwrap().is_empty());
+
+        usage.record("prod").unwrap();
+        let first = usage.load_all().unwrap()["prod"];
+        // Within the resolution, the first use stands
+        usage.record("prod").unwrap();
+        assert_eq!(usage.load_all().unwrap()["prod"], first);
+
+        usage.record("staging").unwrap();
+        usage.delete("prod").unwrap();
+        assert_eq!(usage.load_all().unwrap().keys().collect::<Vec<_>>(), ["staging"]);
+    }
+}
@@ -0,0 +1,218 @@
+//! Deprecated commands, flags and environment variables.
+//!
+//! Every deprecated way of invoking the CLI is registered in [`DEPRECATIONS`]
+//! with its replacement and the release that removes it. Before a command
+//! runs, the command line and environment are matched against the registry:
+//! in table output each match is printed as a warning, and in json/yaml
+//! output the matches are added as a `deprecations` section to the command's
+//! result (or printed to stderr as JSON when the result is not an object).
+//! `inferadb deprecations list` shows the whole registry.
+
+use std::sync::Mutex;
+
+use clap::CommandFactory;
+use serde::Serialize;
+
+use crate::cli::Cli;
+
+/// What kind of usage is deprecated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    /// A command name or alias, as typed after the global options.
+    Command,
+    /// A long option, anywhere on the command line.
+    Flag,
+    /// An environment variable.
+    Env,
+}
+
+impl Kind {
+    /// Lowercase name, for tables and messages.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Command => "command",
+            Self::Flag => "flag",
+            Self::Env => "env",
+        }
+    }
+}
+
+/// A deprecated usage and what replaces it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Deprecation {
+    /// Stable identifier, for scripts that track deprecations.
+    pub id: &'static str,
+    /// What kind of usage is deprecated.
+    pub kind: Kind,
+    /// The deprecated command path (e.g., `what-can`), flag (e.g.,
+    /// `--foo`) or environment variable.
+    pub usage: &'static str,
+    /// What to use instead.
+    pub replacement: &'static str,
+    /// Release that deprecated the usage.
+    pub since: &'static str,
+    /// Release that removes the usage.
+    pub removed_in: &'static str,
+}
+
+impl Deprecation {
+    /// One-line warning for the human-readable output.
+    #[must_use]
+    pub fn message(&self) -> String {
+        format!(
+            "{} '{}' is deprecated and will be removed in {}; use '{}' instead [{}]",
+            self.kind.as_str(),
+            self.usage,
+            self.removed_in,
+            self.replacement,
+            self.id
+        )
+    }
+
+    /// Whether the command line (without the program name) or environment
+    /// relies on this usage.
+    fn is_used(&self, typed_commands: &[&str], args: &[String]) -> bool {
+        match self.kind {
+            Kind::Command => {
+                let path: Vec<&str> = self.usage.split(' ').collect();
+                typed_commands.starts_with(&path)
+            },
+            Kind::Flag => args
+                .iter()
+                .take_while(|arg| *arg != "--")
+                .any(|arg| arg.split('=').next() == Some(self.usage)),
+            Kind::Env => std::env::var_os(self.usage).is_some_and(|value| !value.is_empty()),
+        }
+    }
+}
+
+/// Every deprecated usage, oldest first.
+pub const DEPRECATIONS: &[Deprecation] = &[
+    Deprecation {
+        id: "what-can-alias",
+        kind: Kind::Command,
+        usage: "what-can",
+        replacement: "list-resources",
+        since: "0.1.0",
+        removed_in: "0.2.0",
+    },
+    Deprecation {
+        id: "who-can-alias",
+        kind: Kind::Command,
+        usage: "who-can",
+        replacement: "list-subjects",
+        since: "0.1.0",
+        removed_in: "0.2.0",
+    },
+];
+
+/// Matches not yet reported in machine-readable output.
+static PENDING: Mutex<Vec<Deprecation>> = Mutex::new(Vec::new());
+
+/// Deprecated usages the command line (without the program name) and
+/// environment rely on.
+#[must_use]
+pub fn detect(args: &[String]) -> Vec<&'static Deprecation> {
+    let typed = typed_commands(args);
+    DEPRECATIONS.iter().filter(|d| d.is_used(&typed, args)).collect()
+}
+
+/// Hold matches until the command's machine-readable result is written.
+pub fn defer(used: &[&Deprecation]) {
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.extend(used.iter().map(|d| (*d).clone()));
+    }
+}
+
+/// A result with the pending matches added as a `deprecations` section.
+///
+/// Returns `None`, leaving the matches pending, when nothing is pending or
+/// the result is not a JSON object.
+pub fn annotate<T: Serialize + ?Sized>(result: &T) -> Option<serde_json::Value> {
+    let pending = PENDING.lock().ok()?.clone();
+    if pending.is_empty() {
+        return None;
+    }
+    let mut value = serde_json::to_value(result).ok()?;
+    value.as_object_mut()?.insert("deprecations".to_string(), serde_json::json!(pending));
+    // Matches deferred since the copy stay pending
+    if let Ok(mut rest) = PENDING.lock() {
+        rest.drain(..pending.len());
+    }
+    Some(value)
+}
+
+/// Print matches that no result carried to stderr, as JSON.
+pub fn flush() {
+    let rest = take();
+    if !rest.is_empty() {
+        eprintln!("{}", serde_json::json!({ "deprecations": rest }));
+    }
+}
+
+fn take() -> Vec<Deprecation> {
+    PENDING.lock().map(|mut pending| std::mem::take(&mut *pending)).unwrap_or_default()
+}
+
+/// Subcommand names as typed, skipping options and the values of global
+/// options that take one.
+pub(crate) fn typed_commands(args: &[String]) -> Vec<&str> {
+    let command = Cli::command();
+    let takes_value = |flag: &str| {
+        command.get_arguments().filter(|a| a.get_action().takes_values()).any(|a| {
+            match flag.strip_prefix("--") {
+                Some(long) => a.get_long() == Some(long),
+                None => flag.len() == 2 && flag.chars().nth(1) == a.get_short(),
+            }
+        })
+    };
+
+    let mut typed = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            break;
+        } else if arg.starts_with('-') {
+            if !arg.contains('=') && takes_value(arg) {
+                iter.next();
+            }
+        } else {
+            typed.push(arg.as_str());
+        }
+    }
+    typed
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_detect_command_alias() {
+        let ids = |line: &str| detect(&args(line)).iter().map(|d| d.id).collect::<Vec<_>>();
+        assert_eq!(ids("what-can user:alice view"), ["what-can-alias"]);
+        assert_eq!(ids("--org what-can -o json who-can user:alice view"), ["who-can-alias"]);
+        assert!(ids("list-resources user:alice view").is_empty());
+        assert!(ids("-- what-can").is_empty());
+    }
+
+    #[test]
+    fn test_annotate() {
+        assert!(annotate(&serde_json::json!({ "allowed": true })).is_none());
+
+        defer(&[&DEPRECATIONS[0]]);
+        assert!(annotate(&serde_json::json!([1, 2])).is_none());
+        let value = annotate(&serde_json::json!({ "allowed": true })).unwrap();
+        assert_eq!(value["allowed"], true);
+        assert_eq!(value["deprecations"][0]["id"], "what-can-alias");
+        assert_eq!(value["deprecations"][0]["kind"], "command");
+        assert!(take().is_empty());
+    }
+}
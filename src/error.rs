@@ -3,11 +3,12 @@
 //! This module provides structured error handling with semantic exit codes
 //! following the CLI specification.
 
-use std::{borrow::Cow, io};
+use std::{borrow::Cow, io, time::Duration};
 
+use serde::Serialize;
 use thiserror::Error;
 
-use crate::t;
+use crate::{client::preflight::Unreachable, t};
 
 /// CLI-specific error type.
 #[derive(Debug, Error)]
@@ -64,6 +65,27 @@ pub enum Error {
     #[error("Authentication error: {0}")]
     OAuth(String),
 
+    /// Sign-in refused by brute-force protection: too many attempts, or the
+    /// account is temporarily locked.
+    #[error("{}", throttled_message(*locked, *retry_after))]
+    AuthThrottled {
+        /// Whether the account is locked rather than just rate limited.
+        locked: bool,
+        /// How long the server asked us to wait, if it said.
+        retry_after: Option<Duration>,
+    },
+
+    /// The server could not be reached before any request was made.
+    #[error("{0}")]
+    Unreachable(Unreachable),
+
+    /// Another invocation holds the lock on a state-directory file and
+    /// `--non-blocking` was given.
+    #[error(
+        "Another operation is using {0}; wait for it to finish or retry without --non-blocking"
+    )]
+    Locked(String),
+
     /// User cancelled an operation.
     #[error("Operation cancelled")]
     Cancelled,
@@ -79,6 +101,27 @@ pub enum Error {
     /// General/unspecified error.
     #[error("{0}")]
     Other(String),
+
+    /// Outcome of `check --exit-code`: exit 1 on denial, 2 or above on any
+    /// other error, so shell conditionals can branch on the decision.
+    #[error(transparent)]
+    CheckExitCode(Box<Self>),
+}
+
+/// Machine-readable form of an error, printed to stderr under
+/// `--output json` so wrappers can parse failures.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorReport {
+    /// Stable identifier for the kind of failure (e.g., `auth_required`).
+    pub code: Cow<'static, str>,
+    /// Process exit code.
+    pub exit_code: i32,
+    /// Human-readable message.
+    pub message: String,
+    /// Suggested next step, if any.
+    pub hint: Option<&'static str>,
+    /// Server request ID, for API errors that carry one.
+    pub request_id: Option<String>,
 }
 
 /// Convenient Result type alias for CLI operations.
@@ -100,6 +143,8 @@ impl Error {
     /// - 11: Server error
     /// - 20: Authorization denied (check command)
     /// - 21: Indeterminate
+    ///
+    /// `check --exit-code` remaps denial to 1 and general errors to 2.
     #[must_use]
     pub fn exit_code(&self) -> i32 {
         match self {
@@ -116,6 +161,12 @@ impl Error {
             // Not found
             Self::ProfileNotFound(_) => 5,
 
+            // Another invocation holds a state lock
+            Self::Locked(_) => 6,
+
+            // Brute-force protection
+            Self::AuthThrottled { .. } => 7,
+
             // Authorization decisions
             Self::AccessDenied => 20,
             Self::Indeterminate => 21,
@@ -133,10 +184,16 @@ impl Error {
             },
 
             // IO/network
-            Self::Io(_) => 10,
+            Self::Io(_) | Self::Unreachable(_) => 10,
 
             // Serialization, User action, Fallback
             Self::Json(_) | Self::Yaml(_) | Self::Cancelled | Self::Other(_) => 1,
+
+            // Opt-in check contract: 1 is reserved for denial
+            Self::CheckExitCode(inner) => match inner.exit_code() {
+                20 => 1,
+                code => code.max(2),
+            },
         }
     }
 
@@ -145,6 +202,73 @@ impl Error {
     pub fn should_suggest_login(&self) -> bool {
         matches!(self, Self::AuthRequired | Self::Credential(_))
             || matches!(self, Self::Api(e) if e.kind() == inferadb::ErrorKind::Unauthorized)
+            || matches!(self, Self::CheckExitCode(e) if e.should_suggest_login())
+    }
+
+    /// Stable identifier for the kind of error, for machine-readable output.
+    ///
+    /// API errors are `api_` followed by the SDK error kind (e.g.,
+    /// `api_not_found`).
+    #[must_use]
+    pub fn code(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            Self::Config(_) => "config",
+            Self::AuthRequired => "auth_required",
+            Self::ProfileNotFound(_) => "profile_not_found",
+            Self::OrgNotSpecified => "org_not_specified",
+            Self::VaultNotSpecified => "vault_not_specified",
+            Self::Api(e) => {
+                return Cow::Owned(format!("api_{}", e.kind().to_string().replace(' ', "_")));
+            },
+            Self::Io(_) => "io",
+            Self::Json(_) => "json",
+            Self::Yaml(_) => "yaml",
+            Self::InvalidArgument(_) => "invalid_argument",
+            Self::Parse(_) => "parse",
+            Self::Credential(_) => "credential",
+            Self::OAuth(_) => "oauth",
+            Self::AuthThrottled { locked: true, .. } => "auth_locked",
+            Self::AuthThrottled { locked: false, .. } => "auth_throttled",
+            Self::Unreachable(reason) => reason.code(),
+            Self::Locked(_) => "locked",
+            Self::Cancelled => "cancelled",
+            Self::AccessDenied => "access_denied",
+            Self::Indeterminate => "indeterminate",
+            Self::Other(_) => "other",
+            Self::CheckExitCode(inner) => return inner.code(),
+        })
+    }
+
+    /// Suggested next step to show after the message, if any.
+    #[must_use]
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Unreachable(_) => Some("Run 'inferadb doctor' to diagnose connectivity."),
+            Self::CheckExitCode(inner) => inner.hint(),
+            _ => self.should_suggest_login().then_some("Run 'inferadb login' to authenticate."),
+        }
+    }
+
+    /// Server request ID, for API errors that carry one.
+    #[must_use]
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Self::Api(e) => e.request_id(),
+            Self::CheckExitCode(inner) => inner.request_id(),
+            _ => None,
+        }
+    }
+
+    /// Machine-readable form of this error.
+    #[must_use]
+    pub fn report(&self) -> ErrorReport {
+        ErrorReport {
+            code: self.code(),
+            exit_code: self.exit_code(),
+            message: self.to_string(),
+            hint: self.hint(),
+            request_id: self.request_id().map(str::to_string),
+        }
     }
 
     /// Create a configuration error.
@@ -177,6 +301,18 @@ impl Error {
         Self::Other(msg.into())
     }
 
+    /// Create an error for a throttled or locked sign-in.
+    #[must_use]
+    pub const fn auth_throttled(locked: bool, retry_after: Option<Duration>) -> Self {
+        Self::AuthThrottled { locked, retry_after }
+    }
+
+    /// Wrap an error from `check` under the `--exit-code` contract.
+    #[must_use]
+    pub fn check_exit_code(err: Self) -> Self {
+        Self::CheckExitCode(Box::new(err))
+    }
+
     /// Get a localized error message.
     ///
     /// Uses the i18n system if initialized, otherwise falls back to
@@ -218,16 +354,58 @@ impl Error {
             Self::Parse(details) => Cow::Owned(format!("Parse error: {details}")),
             Self::Credential(details) => Cow::Owned(format!("Credential storage error: {details}")),
             Self::OAuth(details) => Cow::Owned(format!("Authentication error: {details}")),
+            Self::AuthThrottled { locked, retry_after } => {
+                Cow::Owned(throttled_message(*locked, *retry_after))
+            },
 
             // Other errors pass through
+            Self::Locked(_) => Cow::Owned(self.to_string()),
+            Self::Unreachable(reason) => Cow::Owned(reason.to_string()),
             Self::Other(msg) => Cow::Borrowed(msg),
+            Self::CheckExitCode(inner) => inner.localized_message(),
         }
     }
 }
 
+/// Message for [`Error::AuthThrottled`], with the wait and how to recover.
+fn throttled_message(locked: bool, retry_after: Option<Duration>) -> String {
+    let wait =
+        retry_after.map_or_else(|| "later".to_string(), |d| format!("in {}", format_wait(d)));
+    if locked {
+        format!(
+            "Account temporarily locked after repeated failed sign-in attempts. Try again {wait}, \
+             or reset your password from the InferaDB dashboard to unlock it now."
+        )
+    } else {
+        format!("Too many sign-in attempts. Try again {wait}.")
+    }
+}
+
+/// Format a wait, rounded up to whole minutes past the first minute.
+fn format_wait(wait: Duration) -> String {
+    let secs = wait.as_secs();
+    if secs < 60 {
+        return format!("{}s", secs.max(1));
+    }
+    let minutes = secs.div_ceil(60);
+    if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    }
+}
+
 impl From<keyring::Error> for Error {
     fn from(err: keyring::Error) -> Self {
-        Self::Credential(err.to_string())
+        match err {
+            keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_) => {
+                Self::Credential(format!(
+                    "{err} (without a keychain, pass --no-keychain or set `credential_store: \
+                     file` for the profile)"
+                ))
+            },
+            err => Self::Credential(err.to_string()),
+        }
     }
 }
 
@@ -240,10 +418,63 @@ mod tests {
         assert_eq!(Error::Config("test".into()).exit_code(), 2);
         assert_eq!(Error::AuthRequired.exit_code(), 3);
         assert_eq!(Error::ProfileNotFound("test".into()).exit_code(), 5);
+        assert_eq!(Error::Locked("the response cache".into()).exit_code(), 6);
         assert_eq!(Error::AccessDenied.exit_code(), 20);
         assert_eq!(Error::Indeterminate.exit_code(), 21);
     }
 
+    #[test]
+    fn test_check_exit_code_contract() {
+        assert_eq!(Error::check_exit_code(Error::AccessDenied).exit_code(), 1);
+        assert_eq!(Error::check_exit_code(Error::other("boom")).exit_code(), 2);
+        assert_eq!(Error::check_exit_code(Error::AuthRequired).exit_code(), 3);
+        assert_eq!(Error::check_exit_code(Error::Indeterminate).exit_code(), 21);
+        assert!(Error::check_exit_code(Error::AuthRequired).should_suggest_login());
+        assert_eq!(Error::check_exit_code(Error::AccessDenied).to_string(), "Access denied");
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let err = Error::Unreachable(Unreachable::Dns {
+            host: "api.example.invalid".into(),
+            reason: "no such host".into(),
+        });
+        assert_eq!(err.exit_code(), 10);
+        assert_eq!(err.code(), "dns_failed");
+        assert_eq!(err.to_string(), "Could not resolve api.example.invalid: no such host");
+        assert_eq!(err.hint(), Some("Run 'inferadb doctor' to diagnose connectivity."));
+    }
+
+    #[test]
+    fn test_auth_throttled() {
+        let throttled = Error::auth_throttled(false, Some(Duration::from_secs(30)));
+        assert_eq!(throttled.exit_code(), 7);
+        assert!(!throttled.should_suggest_login());
+        assert_eq!(throttled.to_string(), "Too many sign-in attempts. Try again in 30s.");
+
+        let locked = Error::auth_throttled(true, Some(Duration::from_secs(890)));
+        assert!(locked.to_string().contains("Try again in 15m"));
+        assert!(locked.to_string().contains("reset your password"));
+
+        assert!(Error::auth_throttled(false, None).to_string().ends_with("Try again later."));
+        assert_eq!(format_wait(Duration::from_secs(3720)), "1h 2m");
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Error::AuthRequired.report();
+        assert_eq!(report.code, "auth_required");
+        assert_eq!(report.exit_code, 3);
+        assert_eq!(report.hint, Some("Run 'inferadb login' to authenticate."));
+        assert!(report.request_id.is_none());
+
+        let api = Error::Api(inferadb::Error::not_found("no such vault").with_request_id("req-1"));
+        assert_eq!(api.code(), "api_not_found");
+        assert_eq!(Error::check_exit_code(api).report().request_id.as_deref(), Some("req-1"));
+        assert_eq!(Error::auth_throttled(true, None).code(), "auth_locked");
+        assert!(Error::other("boom").hint().is_none());
+    }
+
     #[test]
     fn test_should_suggest_login() {
         assert!(Error::AuthRequired.should_suggest_login());
@@ -0,0 +1,233 @@
+//! What commands do on the service, for `inferadb explain`.
+//!
+//! Each command that talks to the service is registered in [`EXPLANATIONS`]
+//! with the endpoints it calls, the role it needs, what it changes and the
+//! audit log actions it records. `inferadb explain <command>` combines an
+//! entry with the command's own help and usage from the clap definition, so
+//! the argument list can't drift from the parser. A test keeps every
+//! registered path, and every related command, pointing at a real command.
+
+use clap::Command;
+use serde::Serialize;
+
+use crate::cli::Cli;
+
+/// The service-side behaviour of one command.
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    /// Command path, as typed after `inferadb` (e.g., `relationships add`).
+    pub command: &'static str,
+    /// API endpoints called, as `METHOD /path`, in call order. A flag in
+    /// parentheses marks a call made only when it is given.
+    pub endpoints: &'static [&'static str],
+    /// The least role the caller needs.
+    pub requires: &'static str,
+    /// Changes the command makes; empty for read-only commands.
+    pub side_effects: &'static [&'static str],
+    /// Audit log actions the service records.
+    pub audit: &'static [&'static str],
+    /// Command paths worth knowing alongside this one.
+    pub related: &'static [&'static str],
+}
+
+const VAULT_READER: &str = "reader role (or higher) on the vault";
+const VAULT_WRITER: &str = "writer role (or higher) on the vault";
+const VAULT_EDITOR: &str = "editor role (or higher) on the vault";
+const ORG_ADMIN: &str = "owner or admin role in the organization";
+
+/// Every explained command, grouped as in `--help`.
+pub const EXPLANATIONS: &[Explanation] = &[
+    Explanation {
+        command: "check",
+        endpoints: &["POST /access/v1/evaluate"],
+        requires: VAULT_READER,
+        side_effects: &[],
+        audit: &["check"],
+        related: &["simulate", "explain-permission", "expand"],
+    },
+    Explanation {
+        command: "simulate",
+        endpoints: &["POST /access/v1/simulate"],
+        requires: VAULT_READER,
+        side_effects: &[],
+        audit: &[],
+        related: &["check"],
+    },
+    Explanation {
+        command: "list-resources",
+        endpoints: &["POST /access/v1/resources/list"],
+        requires: VAULT_READER,
+        side_effects: &[],
+        audit: &[],
+        related: &["list-subjects", "check"],
+    },
+    Explanation {
+        command: "list-subjects",
+        endpoints: &["POST /access/v1/subjects/list"],
+        requires: VAULT_READER,
+        side_effects: &[],
+        audit: &[],
+        related: &["list-resources", "check"],
+    },
+    Explanation {
+        command: "relationships list",
+        endpoints: &["POST /access/v1/relationships/list"],
+        requires: VAULT_READER,
+        side_effects: &[],
+        audit: &[],
+        related: &["relationships add", "relationships watch", "export"],
+    },
+    Explanation {
+        command: "relationships add",
+        endpoints: &["POST /access/v1/relationships/write"],
+        requires: VAULT_WRITER,
+        side_effects: &[
+            "Writes one relationship, creating a new vault revision",
+            "Changes the result of every check that depends on it",
+        ],
+        audit: &["relationship.write"],
+        related: &["relationships delete", "relationships bulk-add", "relationships list", "check"],
+    },
+    Explanation {
+        command: "relationships delete",
+        endpoints: &["DELETE /access/v1/relationships/{resource}/{relation}/{subject}"],
+        requires: VAULT_WRITER,
+        side_effects: &[
+            "Deletes one relationship, creating a new vault revision",
+            "Changes the result of every check that depends on it",
+        ],
+        audit: &["relationship.delete"],
+        related: &["relationships add", "relationships list", "check"],
+    },
+    Explanation {
+        command: "relationships watch",
+        endpoints: &["GET /v1/organizations/{org}/vaults/{vault}/watch"],
+        requires: VAULT_READER,
+        side_effects: &[],
+        audit: &[],
+        related: &["stream", "relationships list"],
+    },
+    Explanation {
+        command: "schemas push",
+        endpoints: &[
+            "POST /control/v1/organizations/{org}/vaults/{vault}/schemas/validate (--dry-run)",
+            "POST /control/v1/organizations/{org}/vaults/{vault}/schemas",
+            "POST /control/v1/organizations/{org}/vaults/{vault}/schemas/{version}/activate \
+             (--activate)",
+        ],
+        requires: VAULT_EDITOR,
+        side_effects: &[
+            "Stores a new schema version (nothing with --dry-run)",
+            "With --activate, also makes it the active schema",
+        ],
+        audit: &["schema.push", "schema.activate"],
+        related: &["schemas validate", "schemas activate", "schemas diff"],
+    },
+    Explanation {
+        command: "schemas activate",
+        endpoints: &[
+            "GET /control/v1/organizations/{org}/vaults/{vault}/schemas/diff (--diff)",
+            "POST /control/v1/organizations/{org}/vaults/{vault}/schemas/{version}/activate",
+        ],
+        requires: VAULT_EDITOR,
+        side_effects: &["Makes the version the active schema, changing how every check evaluates"],
+        audit: &["schema.activate"],
+        related: &["schemas push", "schemas rollback", "schemas diff"],
+    },
+    Explanation {
+        command: "orgs vaults list",
+        endpoints: &["GET /control/v1/organizations/{org}/vaults"],
+        requires: "member role (or higher) in the organization",
+        side_effects: &[],
+        audit: &[],
+        related: &["orgs vaults create", "orgs vaults get"],
+    },
+    Explanation {
+        command: "orgs vaults create",
+        endpoints: &["POST /control/v1/organizations/{org}/vaults"],
+        requires: ORG_ADMIN,
+        side_effects: &["Creates an empty vault in the organization"],
+        audit: &["vault.create"],
+        related: &["orgs vaults list", "schemas push"],
+    },
+    Explanation {
+        command: "orgs vaults delete",
+        endpoints: &["DELETE /control/v1/organizations/{org}/vaults/{vault}"],
+        requires: ORG_ADMIN,
+        side_effects: &[
+            "Schedules the vault, with its schemas and relationships, for deletion",
+            "Until it is gone, `orgs vaults list --show-deleted` lists it",
+        ],
+        audit: &["vault.delete"],
+        related: &["orgs vaults list"],
+    },
+];
+
+/// The registered explanation of a command path (real names, not aliases).
+#[must_use]
+pub fn find(path: &[String]) -> Option<&'static Explanation> {
+    let path = path.join(" ");
+    EXPLANATIONS.iter().find(|e| e.command == path)
+}
+
+/// Resolve typed command words (names or aliases) to the command they name
+/// and the real names on its path.
+///
+/// Fails with the first word that names no subcommand.
+pub fn resolve<'a>(
+    root: &'a Command,
+    words: &[String],
+) -> std::result::Result<(&'a Command, Vec<String>), String> {
+    let mut command = root;
+    let mut path = Vec::new();
+    for word in words {
+        command = command
+            .get_subcommands()
+            .find(|sub| sub.get_name() == word || sub.get_all_aliases().any(|alias| alias == word))
+            .ok_or_else(|| word.clone())?;
+        path.push(command.get_name().to_string());
+    }
+    Ok((command, path))
+}
+
+/// The CLI's command tree, built so usage lines carry the full command path.
+#[must_use]
+pub fn command_tree() -> Command {
+    let mut root = Cli::command_localized();
+    root.build();
+    root
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn words(path: &str) -> Vec<String> {
+        path.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_explanations_name_real_commands() {
+        let root = command_tree();
+        for explanation in EXPLANATIONS {
+            let (_, path) = resolve(&root, &words(explanation.command)).unwrap();
+            assert_eq!(path.join(" "), explanation.command, "{} is an alias", explanation.command);
+            for related in explanation.related {
+                assert!(resolve(&root, &words(related)).is_ok(), "{related}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_aliases() {
+        let root = command_tree();
+        let (command, path) = resolve(&root, &words("relationships add")).unwrap();
+        assert_eq!(command.get_name(), "add");
+        assert_eq!(find(&path).unwrap().audit, ["relationship.write"]);
+
+        let (_, path) = resolve(&root, &words("what-can")).unwrap();
+        assert_eq!(find(&path).unwrap().command, "list-resources");
+        assert_eq!(resolve(&root, &words("relationships ad")).unwrap_err(), "ad");
+    }
+}
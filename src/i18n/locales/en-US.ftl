@@ -28,11 +28,11 @@ cli-about = InferaDB CLI - Debug authorization decisions, test policies, and man
 cli-profile-help = Profile to use (or use @profile prefix)
 cli-org-help = Organization ID (overrides profile)
 cli-vault-help = Vault ID (overrides profile)
-cli-output-help = Output format: table, json, yaml, jsonl
+cli-output-help = Output format: table, json, yaml, jsonl, csv, template=<template> or template-file=<path>
 cli-color-help = Color output: auto, always, never
 cli-quiet-help = Suppress non-essential output
 cli-yes-help = Skip confirmation prompts
-cli-debug-help = Enable debug logging
+cli-debug-help = Enable debug logging and print where the command spent its time
 
 ## ============================================================================
 ## CLI Help Text - Commands
@@ -116,8 +116,16 @@ msg-init-find-in-dashboard = You can find these in the InferaDB dashboard.
 
 # Login/logout
 msg-logging-in = Logging in as profile '{ $profile }'...
+msg-device-code-visit = To sign in, visit { $url } and enter the code: { $code }
+msg-device-code-direct = Or open: { $url }
+msg-device-code-expires = The code expires in { $minutes } minutes.
+msg-device-code-waiting = Waiting for approval...
+msg-logging-in-client = Logging in as client '{ $client }' for profile '{ $profile }'...
 msg-logging-out = Log out from profile '{ $profile }'?
 msg-not-logged-in = Profile '{ $profile }' is not logged in.
+msg-logging-out-all = Log out from { $count } profiles ({ $profiles })?
+msg-no-logged-in-profiles = No profiles have stored credentials.
+msg-logout-failed = Could not log out from profile '{ $profile }': { $reason }
 msg-cancelled = Cancelled.
 
 # Registration
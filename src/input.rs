@@ -0,0 +1,92 @@
+//! File arguments, with `-` for stdin.
+//!
+//! Commands read file arguments through [`read_to_string`] (or [`open`] to
+//! stream them), so `-` reads stdin everywhere and process substitution
+//! (`<(...)`, a pipe under `/dev/fd`) works like a regular file. Whole-file
+//! reads are capped at [`MAX_INPUT_BYTES`], so a wrong argument (say,
+//! `/dev/zero`) fails instead of exhausting memory.
+//!
+//! Errors are plain I/O errors, so call sites keep their own context (e.g.,
+//! "Could not read ID map ...").
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Argument naming stdin.
+pub const STDIN: &str = "-";
+
+/// Largest input [`read_to_string`] reads into memory.
+pub const MAX_INPUT_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Whether an argument has already read stdin.
+static STDIN_TAKEN: AtomicBool = AtomicBool::new(false);
+
+/// Whether `arg` is stdin or an existing file, for arguments that also accept
+/// something else (e.g., a schema version ID).
+#[must_use]
+pub fn is_local(arg: &str) -> bool {
+    arg == STDIN || Path::new(arg).exists()
+}
+
+/// Open a file argument, or stdin for `-`. Only one argument per invocation
+/// can read stdin.
+pub fn open(arg: &str) -> io::Result<Box<dyn BufRead>> {
+    if arg != STDIN {
+        return Ok(Box::new(BufReader::new(File::open(arg)?)));
+    }
+    if STDIN_TAKEN.swap(true, Ordering::Relaxed) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "stdin ('-') was already read by another argument",
+        ));
+    }
+    Ok(Box::new(io::stdin().lock()))
+}
+
+/// Read a file argument as text, or stdin for `-`, up to [`MAX_INPUT_BYTES`].
+pub fn read_to_string(arg: &str) -> io::Result<String> {
+    read_limited(open(arg)?, MAX_INPUT_BYTES)
+}
+
+fn read_limited(reader: impl Read, limit: u64) -> io::Result<String> {
+    let mut text = String::new();
+    reader.take(limit + 1).read_to_string(&mut text)?;
+    if text.len() as u64 > limit {
+        return Err(io::Error::new(
+            io::ErrorKind::FileTooLarge,
+            format!("input is larger than {} MiB", limit / (1024 * 1024)),
+        ));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_limited() {
+        assert_eq!(read_limited(&b"schema"[..], 6).unwrap(), "schema");
+
+        let err = read_limited(io::repeat(b'a'), 2 * 1024 * 1024).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::FileTooLarge);
+        assert_eq!(err.to_string(), "input is larger than 2 MiB");
+    }
+
+    #[test]
+    fn test_is_local() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schema.ipl");
+        std::fs::write(&path, "entity User {}").unwrap();
+
+        assert!(is_local("-"));
+        assert!(is_local(path.to_str().unwrap()));
+        assert!(!is_local("v3"));
+        assert_eq!(read_to_string(path.to_str().unwrap()).unwrap(), "entity User {}");
+    }
+}
@@ -0,0 +1,1067 @@
+//! Lightweight IPL schema parser.
+//!
+//! Parses the structural outline of an IPL schema — entities, their relations
+//! (with subject types and caveats), and permission expressions — so commands
+//! can reason about a schema locally. Server-side validation remains the
+//! authority on whether a schema is valid.
+
+use std::{collections::BTreeMap, ops::Range};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// A parsed schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    /// Entities in declaration order.
+    pub entities: Vec<Entity>,
+}
+
+/// An entity definition.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Entity {
+    /// Entity type name (e.g., `Document`).
+    pub name: String,
+    /// Relations in declaration order.
+    pub relations: Vec<Relation>,
+    /// Permissions in declaration order.
+    pub permissions: Vec<Permission>,
+}
+
+/// A relation and the subject types allowed on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relation {
+    /// Relation name.
+    pub name: String,
+    /// Allowed subject types.
+    pub subjects: Vec<SubjectType>,
+}
+
+/// A subject type allowed on a relation (e.g., `Group#member with ip_check`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubjectType {
+    /// Subject entity type.
+    pub entity: String,
+    /// Subject relation, for usersets.
+    pub relation: Option<String>,
+    /// Caveat that must hold for the relationship to apply.
+    pub caveat: Option<String>,
+}
+
+/// A permission and its rewrite expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permission {
+    /// Permission name.
+    pub name: String,
+    /// Rewrite expression.
+    pub expr: Expr,
+}
+
+/// A permission rewrite expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// A relation or permission on the same entity.
+    Ref(String),
+    /// A relation or permission reached through a relation (`parent.admin`).
+    Arrow(String, String),
+    /// Any operand grants (`a | b`).
+    Union(Vec<Self>),
+    /// All operands must grant (`a & b`).
+    Intersection(Vec<Self>),
+    /// The left side grants unless the right side does (`a - b`).
+    Exclusion(Box<Self>, Box<Self>),
+}
+
+impl Schema {
+    /// Find an entity by name.
+    #[must_use]
+    pub fn entity(&self, name: &str) -> Option<&Entity> {
+        self.entities.iter().find(|e| e.name == name)
+    }
+
+    /// Find the entity for a resource or subject type (e.g., `document` or
+    /// `user_group`).
+    #[must_use]
+    pub fn entity_for_type(&self, type_name: &str) -> Option<&Entity> {
+        self.entities.iter().find(|e| type_matches(&e.name, type_name))
+    }
+
+    /// Entities whose relationships can affect checks on `type_name`: the
+    /// entity itself and every entity reachable through its relations'
+    /// subject types.
+    #[must_use]
+    pub fn reachable_entities(&self, type_name: &str) -> Vec<&Entity> {
+        let mut reached: Vec<&Entity> = self.entity_for_type(type_name).into_iter().collect();
+        let mut next = 0;
+        while let Some(entity) = reached.get(next).copied() {
+            next += 1;
+            for subject in entity.relations.iter().flat_map(|r| &r.subjects) {
+                if let Some(found) = self.entity(&subject.entity)
+                    && !reached.iter().any(|e| e.name == found.name)
+                {
+                    reached.push(found);
+                }
+            }
+        }
+        reached
+    }
+}
+
+/// Whether an entity name (e.g., `UserGroup`) names an object type used in
+/// references (e.g., `user_group`), ignoring case and underscores.
+#[must_use]
+pub fn type_matches(entity_name: &str, type_name: &str) -> bool {
+    let normalize = |s: &str| s.replace('_', "").to_lowercase();
+    normalize(entity_name) == normalize(type_name)
+}
+
+/// The object type for an entity name (`UserGroup` becomes `user_group`).
+#[must_use]
+pub fn type_name(entity_name: &str) -> String {
+    let mut name = String::with_capacity(entity_name.len() + 4);
+    for (i, c) in entity_name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 && !name.ends_with('_') {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+impl Entity {
+    /// Find a relation by name.
+    #[must_use]
+    pub fn relation(&self, name: &str) -> Option<&Relation> {
+        self.relations.iter().find(|r| r.name == name)
+    }
+
+    /// Find a permission by name.
+    #[must_use]
+    pub fn permission(&self, name: &str) -> Option<&Permission> {
+        self.permissions.iter().find(|p| p.name == name)
+    }
+
+    /// How each relation contributes to each permission.
+    ///
+    /// Returns, per permission, the relations that feed it. Permissions
+    /// referenced from other permissions are followed, so a relation reached
+    /// that way is reported as a rewrite contribution.
+    #[must_use]
+    pub fn contributions(&self) -> BTreeMap<String, BTreeMap<String, Contribution>> {
+        self.permissions
+            .iter()
+            .map(|p| {
+                let mut found = BTreeMap::new();
+                for grant in self.grants(&p.name) {
+                    let kind = self.classify(&grant);
+                    found
+                        .entry(grant.relation)
+                        .and_modify(|k: &mut Contribution| *k = (*k).min(kind))
+                        .or_insert(kind);
+                }
+                (p.name.clone(), found)
+            })
+            .collect()
+    }
+
+    /// Relation-level ways a permission can be granted or revoked.
+    ///
+    /// Permissions referenced from the expression are expanded, so every grant
+    /// names a relation on this entity.
+    #[must_use]
+    pub fn grants(&self, permission: &str) -> Vec<Grant> {
+        let mut grants = Vec::new();
+        if let Some(p) = self.permission(permission) {
+            let mut visiting = vec![p.name.as_str()];
+            self.walk(&p.expr, Grant::default(), &mut visiting, &mut grants);
+        }
+        grants
+    }
+
+    fn walk<'a>(
+        &'a self,
+        expr: &'a Expr,
+        flags: Grant,
+        visiting: &mut Vec<&'a str>,
+        grants: &mut Vec<Grant>,
+    ) {
+        match expr {
+            Expr::Ref(name) => {
+                if self.relation(name).is_some() {
+                    push_unique(grants, Grant { relation: name.clone(), ..flags });
+                } else if let Some(permission) = self.permission(name)
+                    && !visiting.contains(&name.as_str())
+                {
+                    visiting.push(name);
+                    let flags = Grant { rewrite: true, ..flags };
+                    self.walk(&permission.expr, flags, visiting, grants);
+                    visiting.pop();
+                }
+            },
+            Expr::Arrow(name, target) => {
+                if self.relation(name).is_some() {
+                    let grant = Grant {
+                        relation: name.clone(),
+                        via: Some(target.clone()),
+                        rewrite: true,
+                        ..flags
+                    };
+                    push_unique(grants, grant);
+                }
+            },
+            Expr::Union(operands) => {
+                for operand in operands {
+                    self.walk(operand, flags.clone(), visiting, grants);
+                }
+            },
+            Expr::Intersection(operands) => {
+                for operand in operands {
+                    let flags = Grant { conditional: true, ..flags.clone() };
+                    self.walk(operand, flags, visiting, grants);
+                }
+            },
+            Expr::Exclusion(base, subtracted) => {
+                self.walk(base, flags.clone(), visiting, grants);
+                self.walk(subtracted, Grant { excluded: true, ..flags }, visiting, grants);
+            },
+        }
+    }
+
+    fn classify(&self, grant: &Grant) -> Contribution {
+        let caveated = self
+            .relation(&grant.relation)
+            .is_some_and(|r| r.subjects.iter().any(|s| s.caveat.is_some()));
+        if grant.excluded {
+            Contribution::Excluded
+        } else if caveated {
+            Contribution::Caveat
+        } else if grant.rewrite {
+            Contribution::Rewrite
+        } else {
+            Contribution::Direct
+        }
+    }
+}
+
+/// A relation through which a permission is granted (or revoked).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grant {
+    /// Relation on the entity.
+    pub relation: String,
+    /// Permission or relation checked on the related object (`parent.admin`).
+    pub via: Option<String>,
+    /// Reached through another permission or a relation traversal.
+    pub rewrite: bool,
+    /// Other intersection operands must also hold.
+    pub conditional: bool,
+    /// Subtracts access instead of granting it.
+    pub excluded: bool,
+}
+
+fn push_unique(grants: &mut Vec<Grant>, grant: Grant) {
+    if !grants.contains(&grant) {
+        grants.push(grant);
+    }
+}
+
+/// How a relation contributes to a permission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Contribution {
+    /// Referenced directly in the permission expression.
+    Direct,
+    /// Reached through another permission or a relation traversal.
+    Rewrite,
+    /// Grants only when a caveat on the relation holds.
+    Caveat,
+    /// Only appears on the subtracted side of an exclusion.
+    Excluded,
+}
+
+impl std::fmt::Display for Contribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Direct => "direct",
+            Self::Rewrite => "rewrite",
+            Self::Caveat => "caveat",
+            Self::Excluded => "excluded",
+        };
+        write!(f, "{s}")
+    }
+}
+
+// ============================================================================
+// Refactoring
+// ============================================================================
+
+/// A rename applied to schema source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Refactoring {
+    /// Rename a relation of an entity, and every reference to it.
+    RenameRelation {
+        /// Entity declaring the relation.
+        entity: String,
+        /// Current relation name.
+        from: String,
+        /// New relation name.
+        to: String,
+    },
+    /// Rename an entity, and every subject type naming it.
+    RenameEntity {
+        /// Current entity name.
+        from: String,
+        /// New entity name.
+        to: String,
+    },
+}
+
+impl Refactoring {
+    /// Check that the rename applies to a schema, resolving `entity` (or
+    /// `from`, for entities) given as an object type to the declared name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the renamed item does not exist, the new name is
+    /// taken, or an arrow reference cannot be renamed unambiguously.
+    pub fn resolve(self, schema: &Schema) -> Result<Self> {
+        let find = |name: &str| {
+            schema.entity(name).or_else(|| schema.entity_for_type(name)).ok_or_else(|| {
+                Error::invalid_arg(format!("Entity '{name}' is not defined in the schema"))
+            })
+        };
+        match self {
+            Self::RenameRelation { entity, from, to } => {
+                let found = find(&entity)?;
+                if found.relation(&from).is_none() {
+                    return Err(Error::invalid_arg(format!(
+                        "'{from}' is not a relation of {}",
+                        found.name
+                    )));
+                }
+                if found.relation(&to).is_some() || found.permission(&to).is_some() {
+                    return Err(Error::invalid_arg(format!(
+                        "{} already defines '{to}'",
+                        found.name
+                    )));
+                }
+                // An arrow through a relation that allows several entities
+                // would rename the target on all of them
+                for other in &schema.entities {
+                    for relation in &other.relations {
+                        let allows =
+                            |name: &str| relation.subjects.iter().any(|s| s.entity == name);
+                        if !allows(&found.name) {
+                            continue;
+                        }
+                        if let Some(clash) = schema.entities.iter().find(|e| {
+                            e.name != found.name && allows(&e.name) && e.relation(&from).is_some()
+                        }) {
+                            return Err(Error::invalid_arg(format!(
+                                "{}.{} can reach both {} and {}, which both define '{from}'; \
+                                 rename it by hand",
+                                other.name, relation.name, found.name, clash.name
+                            )));
+                        }
+                    }
+                }
+                Ok(Self::RenameRelation { entity: found.name.clone(), from, to })
+            },
+            Self::RenameEntity { from, to } => {
+                let found = find(&from)?;
+                if schema.entity(&to).is_some() || schema.entity_for_type(&type_name(&to)).is_some()
+                {
+                    return Err(Error::invalid_arg(format!("Entity '{to}' already exists")));
+                }
+                Ok(Self::RenameEntity { from: found.name.clone(), to })
+            },
+        }
+    }
+
+    /// The rename in the other direction.
+    #[must_use]
+    pub fn reversed(&self) -> Self {
+        match self {
+            Self::RenameRelation { entity, from, to } => {
+                Self::RenameRelation { entity: entity.clone(), from: to.clone(), to: from.clone() }
+            },
+            Self::RenameEntity { from, to } => {
+                Self::RenameEntity { from: to.clone(), to: from.clone() }
+            },
+        }
+    }
+
+    /// Rename in schema source, keeping formatting and comments.
+    ///
+    /// # Errors
+    ///
+    /// Returns a parse error when the source cannot be read.
+    pub fn apply(&self, source: &str) -> Result<String> {
+        let (schema, occurrences) = outline(source)?;
+        let mut edits = Vec::new();
+        for occurrence in occurrences {
+            let span = match (self, occurrence) {
+                (
+                    Self::RenameRelation { entity, from, .. },
+                    Occurrence::RelationDecl { entity: e, name, name_span, .. },
+                ) if e == *entity && name == *from => name_span,
+                (
+                    Self::RenameRelation { entity, from, .. },
+                    Occurrence::Subject { subject, relation: Some((relation, span)), .. },
+                ) if subject == *entity && relation == *from => span,
+                (
+                    Self::RenameRelation { entity, from, .. },
+                    Occurrence::Ref { entity: e, name, span },
+                ) if e == *entity && name == *from => span,
+                (
+                    Self::RenameRelation { entity, from, .. },
+                    Occurrence::ArrowTarget { entity: e, via, name, span },
+                ) if name == *from && traverses_to(&schema, &e, &via, entity) => span,
+                (Self::RenameEntity { from, .. }, Occurrence::Entity { name, name_span, .. })
+                    if name == *from =>
+                {
+                    name_span
+                },
+                (
+                    Self::RenameEntity { from, .. },
+                    Occurrence::Subject { subject, subject_span, .. },
+                ) if subject == *from => subject_span,
+                _ => continue,
+            };
+            let text = match self {
+                Self::RenameRelation { to, .. } | Self::RenameEntity { to, .. } => to.clone(),
+            };
+            edits.push((span, text));
+        }
+        Ok(apply_edits(source, edits))
+    }
+
+    /// Add the new name alongside the old one, so relationships can be copied
+    /// before references switch over.
+    ///
+    /// For a relation, the declaration and userset subject types (`Doc#from`)
+    /// are duplicated under the new name. For an entity, the declaration and
+    /// every subject type naming it are. Permissions are left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns a parse error when the source cannot be read.
+    pub fn widen(&self, source: &str) -> Result<String> {
+        let (_, occurrences) = outline(source)?;
+
+        // Subject types first, so duplicated declarations include them too
+        let mut subjects = Vec::new();
+        for occurrence in &occurrences {
+            let Occurrence::Subject { subject, subject_span, relation, span } = occurrence else {
+                continue;
+            };
+            let alternative = match (self, relation) {
+                (Self::RenameRelation { entity, from, to }, Some((relation, relation_span)))
+                    if subject == entity && relation == from =>
+                {
+                    format!(" | {subject}#{to}{}", &source[relation_span.end..span.end])
+                },
+                (Self::RenameEntity { from, to }, _) if subject == from => {
+                    format!(" | {to}{}", &source[subject_span.end..span.end])
+                },
+                _ => continue,
+            };
+            subjects.push((span.end..span.end, alternative));
+        }
+        // Source text of a range with the subject types widened
+        let widened = |range: Range<usize>| {
+            let edits = subjects
+                .iter()
+                .filter(|(at, _)| range.start <= at.start && at.end <= range.end)
+                .map(|(at, text)| (at.start - range.start..at.end - range.start, text.clone()))
+                .collect();
+            apply_edits(&source[range], edits)
+        };
+
+        let mut edits = subjects.clone();
+        for occurrence in occurrences {
+            match (self, occurrence) {
+                (
+                    Self::RenameRelation { entity, from, to },
+                    Occurrence::RelationDecl { entity: e, name, name_span, decl },
+                ) if e == *entity && name == *from => {
+                    let rest = widened(name_span.end..decl.end);
+                    let line_start = source[..decl.start].rfind('\n').map_or(0, |i| i + 1);
+                    let line_end =
+                        source[decl.end..].find('\n').map_or(source.len(), |i| decl.end + i);
+                    let indent = &source[line_start..decl.start];
+                    let tail = source[decl.end..line_end].trim();
+                    // Add a line after the declaration (and any trailing comment)
+                    // when it has its own line, otherwise add it inline
+                    if indent.trim().is_empty() && (tail.is_empty() || tail.starts_with("//")) {
+                        edits.push((line_end..line_end, format!("\n{indent}{to}{rest}")));
+                    } else {
+                        edits.push((decl.end..decl.end, format!(" {to}{rest}")));
+                    }
+                },
+                (
+                    Self::RenameEntity { from, to },
+                    Occurrence::Entity { name, name_span, block },
+                ) if name == *from => {
+                    let copy = format!(
+                        "\n\n{}{to}{}",
+                        &source[block.start..name_span.start],
+                        widened(name_span.end..block.end)
+                    );
+                    edits.push((block.end..block.end, copy));
+                },
+                _ => {},
+            }
+        }
+        Ok(apply_edits(source, edits))
+    }
+}
+
+/// Whether relation `via` on entity `from` allows `target` as a subject type.
+fn traverses_to(schema: &Schema, from: &str, via: &str, target: &str) -> bool {
+    schema
+        .entity(from)
+        .and_then(|e| e.relation(via))
+        .is_some_and(|r| r.subjects.iter().any(|s| s.entity == target))
+}
+
+/// Replace byte ranges of `source`; ranges must not overlap.
+fn apply_edits(source: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+    edits.sort_by_key(|(span, _)| span.start);
+    let mut out = String::with_capacity(source.len());
+    let mut pos = 0;
+    for (span, text) in edits {
+        out.push_str(&source[pos..span.start]);
+        out.push_str(&text);
+        pos = span.end;
+    }
+    out.push_str(&source[pos..]);
+    out
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+/// Parse an IPL schema.
+///
+/// # Errors
+///
+/// Returns a parse error with the offending line when the schema outline
+/// cannot be read.
+pub fn parse(source: &str) -> Result<Schema> {
+    Ok(outline(source)?.0)
+}
+
+/// Parse a schema, also returning where each name appears in the source.
+fn outline(source: &str) -> Result<(Schema, Vec<Occurrence>)> {
+    let mut parser =
+        Parser { tokens: tokenize(source), pos: 0, entity: String::new(), occurrences: Vec::new() };
+    let schema = parser.schema()?;
+    Ok((schema, parser.occurrences))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Symbol(char),
+}
+
+/// Split source into identifiers and single-character symbols with line numbers
+/// and byte ranges.
+fn tokenize(source: &str) -> Vec<(Token, usize, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        let line_no = index + 1;
+        let code = line.split_once("//").map_or(line, |(code, _)| code);
+        let mut chars = code.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let mut end = start + c.len_utf8();
+            if c.is_alphanumeric() || c == '_' {
+                while let Some(&(i, next)) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_') {
+                        break;
+                    }
+                    end = i + next.len_utf8();
+                    chars.next();
+                }
+                tokens.push((
+                    Token::Ident(code[start..end].to_string()),
+                    line_no,
+                    offset + start..offset + end,
+                ));
+            } else {
+                tokens.push((Token::Symbol(c), line_no, offset + start..offset + end));
+            }
+        }
+        offset += line.len();
+    }
+    tokens
+}
+
+/// Where a name appears in the source, for refactoring.
+#[derive(Debug, Clone)]
+enum Occurrence {
+    /// An entity declaration; `block` runs from `entity` to the closing brace.
+    Entity { name: String, name_span: Range<usize>, block: Range<usize> },
+    /// A relation declaration; `decl` runs from the name to the last subject type.
+    RelationDecl { entity: String, name: String, name_span: Range<usize>, decl: Range<usize> },
+    /// A subject type on a relation of `entity`; `span` includes any caveat.
+    Subject {
+        subject: String,
+        subject_span: Range<usize>,
+        relation: Option<(String, Range<usize>)>,
+        span: Range<usize>,
+    },
+    /// A name in a permission of `entity`: a relation or permission reference,
+    /// or the relation an arrow traverses.
+    Ref { entity: String, name: String, span: Range<usize> },
+    /// The target of an arrow (`admin` in `parent.admin`).
+    ArrowTarget { entity: String, via: String, name: String, span: Range<usize> },
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize, Range<usize>)>,
+    pos: usize,
+    /// Entity being parsed.
+    entity: String,
+    occurrences: Vec<Occurrence>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, ..)| t)
+    }
+
+    /// Byte range of the next token.
+    fn next_span(&self) -> Range<usize> {
+        self.tokens.get(self.pos).map_or(0..0, |(_, _, span)| span.clone())
+    }
+
+    /// End of the last consumed token.
+    fn last_end(&self) -> usize {
+        self.pos.checked_sub(1).and_then(|i| self.tokens.get(i)).map_or(0, |(_, _, span)| span.end)
+    }
+
+    /// Read an identifier and its byte range.
+    fn spanned_ident(&mut self) -> Result<(String, Range<usize>)> {
+        let span = self.next_span();
+        Ok((self.ident()?, span))
+    }
+
+    fn peek_symbol(&self, symbol: char) -> bool {
+        self.peek() == Some(&Token::Symbol(symbol))
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s == keyword)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        let line =
+            self.tokens.get(self.pos).or_else(|| self.tokens.last()).map_or(1, |(_, l, _)| *l);
+        Error::parse(format!("Schema line {line}: {message}"))
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        match self.tokens.get(self.pos) {
+            Some((Token::Ident(s), ..)) => {
+                self.pos += 1;
+                Ok(s.clone())
+            },
+            _ => Err(self.error("expected identifier")),
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<()> {
+        if self.peek_symbol(symbol) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{symbol}'")))
+        }
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let matched = self.peek_symbol(symbol);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    /// Skip a balanced `{ ... }` block, e.g., a caveat definition.
+    fn skip_block(&mut self) -> Result<()> {
+        self.expect('{')?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.peek() {
+                Some(Token::Symbol('{')) => depth += 1,
+                Some(Token::Symbol('}')) => depth -= 1,
+                Some(_) => {},
+                None => return Err(self.error("unterminated block")),
+            }
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    fn schema(&mut self) -> Result<Schema> {
+        let mut schema = Schema::default();
+        while self.peek().is_some() {
+            if self.peek_keyword("entity") {
+                let start = self.next_span().start;
+                self.pos += 1;
+                let name_span = self.next_span();
+                let entity = self.entity()?;
+                self.occurrences.push(Occurrence::Entity {
+                    name: entity.name.clone(),
+                    name_span,
+                    block: start..self.last_end(),
+                });
+                schema.entities.push(entity);
+            } else {
+                // Other top-level definitions (caveats, etc.) are not needed here
+                while self.peek().is_some() && !self.peek_symbol('{') {
+                    self.pos += 1;
+                }
+                if self.peek().is_some() {
+                    self.skip_block()?;
+                }
+            }
+        }
+        Ok(schema)
+    }
+
+    fn entity(&mut self) -> Result<Entity> {
+        let mut entity = Entity { name: self.ident()?, ..Entity::default() };
+        self.entity.clone_from(&entity.name);
+        self.expect('{')?;
+        while !self.eat('}') {
+            match self.ident()?.as_str() {
+                "relations" => {
+                    self.expect('{')?;
+                    while !self.eat('}') {
+                        entity.relations.push(self.relation()?);
+                    }
+                },
+                "permissions" => {
+                    self.expect('{')?;
+                    while !self.eat('}') {
+                        entity.permissions.push(self.permission()?);
+                    }
+                },
+                _ => self.skip_block()?,
+            }
+        }
+        Ok(entity)
+    }
+
+    fn relation(&mut self) -> Result<Relation> {
+        let (name, name_span) = self.spanned_ident()?;
+        self.expect(':')?;
+        let mut subjects = vec![self.subject()?];
+        while self.eat('|') {
+            subjects.push(self.subject()?);
+        }
+        self.occurrences.push(Occurrence::RelationDecl {
+            entity: self.entity.clone(),
+            name: name.clone(),
+            decl: name_span.start..self.last_end(),
+            name_span,
+        });
+        Ok(Relation { name, subjects })
+    }
+
+    fn subject(&mut self) -> Result<SubjectType> {
+        let (entity, subject_span) = self.spanned_ident()?;
+        let relation = if self.eat('#') { Some(self.spanned_ident()?) } else { None };
+        let caveat = if self.peek_keyword("with") {
+            self.pos += 1;
+            Some(self.ident()?)
+        } else {
+            None
+        };
+        self.occurrences.push(Occurrence::Subject {
+            subject: entity.clone(),
+            span: subject_span.start..self.last_end(),
+            subject_span,
+            relation: relation.clone(),
+        });
+        Ok(SubjectType { entity, relation: relation.map(|(name, _)| name), caveat })
+    }
+
+    fn permission(&mut self) -> Result<Permission> {
+        let name = self.ident()?;
+        self.expect(':')?;
+        Ok(Permission { name, expr: self.union()? })
+    }
+
+    fn union(&mut self) -> Result<Expr> {
+        let mut operands = vec![self.intersection()?];
+        while self.eat('|') {
+            operands.push(self.intersection()?);
+        }
+        Ok(if operands.len() == 1 { operands.remove(0) } else { Expr::Union(operands) })
+    }
+
+    fn intersection(&mut self) -> Result<Expr> {
+        let mut operands = vec![self.exclusion()?];
+        while self.eat('&') {
+            operands.push(self.exclusion()?);
+        }
+        Ok(if operands.len() == 1 { operands.remove(0) } else { Expr::Intersection(operands) })
+    }
+
+    fn exclusion(&mut self) -> Result<Expr> {
+        let mut expr = self.primary()?;
+        while self.eat('-') {
+            expr = Expr::Exclusion(Box::new(expr), Box::new(self.primary()?));
+        }
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        if self.eat('(') {
+            let expr = self.union()?;
+            self.expect(')')?;
+            return Ok(expr);
+        }
+        let (name, span) = self.spanned_ident()?;
+        self.occurrences.push(Occurrence::Ref {
+            entity: self.entity.clone(),
+            name: name.clone(),
+            span,
+        });
+        if !self.eat('.') {
+            return Ok(Expr::Ref(name));
+        }
+        let (target, span) = self.spanned_ident()?;
+        self.occurrences.push(Occurrence::ArrowTarget {
+            entity: self.entity.clone(),
+            via: name.clone(),
+            name: target.clone(),
+            span,
+        });
+        Ok(Expr::Arrow(name, target))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r"
+// Document sharing
+caveat ip_allowlist(ip: string) {
+    ip in ['10.0.0.1']
+}
+
+entity User {}
+
+entity Folder {
+    relations {
+        viewer: User
+    }
+}
+
+entity Document {
+    relations {
+        owner: User
+        editor: User | Group#member
+        viewer: User with ip_allowlist
+        banned: User
+        parent: Folder
+    }
+
+    permissions {
+        edit: owner | editor
+        view: (edit | viewer | parent.viewer) - banned
+    }
+}
+";
+
+    #[test]
+    fn test_parse_outline() {
+        let schema = parse(SCHEMA).unwrap();
+        let names: Vec<&str> = schema.entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "Folder", "Document"]);
+
+        let document = schema.entity("Document").unwrap();
+        assert_eq!(document.relations.len(), 5);
+        let editor = document.relation("editor").unwrap();
+        assert_eq!(editor.subjects[1].relation.as_deref(), Some("member"));
+        let viewer = document.relation("viewer").unwrap();
+        assert_eq!(viewer.subjects[0].caveat.as_deref(), Some("ip_allowlist"));
+        assert_eq!(
+            document.permission("edit").unwrap().expr,
+            Expr::Union(vec![Expr::Ref("owner".into()), Expr::Ref("editor".into())])
+        );
+    }
+
+    #[test]
+    fn test_parse_reports_line() {
+        let err = parse("entity User {\n  relations {\n    owner User\n  }\n}").unwrap_err();
+        assert!(err.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn test_contributions() {
+        let schema = parse(SCHEMA).unwrap();
+        let matrix = schema.entity("Document").unwrap().contributions();
+
+        let edit = &matrix["edit"];
+        assert_eq!(edit["owner"], Contribution::Direct);
+        assert_eq!(edit["editor"], Contribution::Direct);
+        assert!(!edit.contains_key("viewer"));
+
+        let view = &matrix["view"];
+        assert_eq!(view["owner"], Contribution::Rewrite);
+        assert_eq!(view["viewer"], Contribution::Caveat);
+        assert_eq!(view["parent"], Contribution::Rewrite);
+        assert_eq!(view["banned"], Contribution::Excluded);
+    }
+
+    #[test]
+    fn test_grants() {
+        let schema = parse(SCHEMA).unwrap();
+        let document = schema.entity_for_type("document").unwrap();
+        let grants = document.grants("view");
+
+        let parent = grants.iter().find(|g| g.relation == "parent").unwrap();
+        assert_eq!(parent.via.as_deref(), Some("viewer"));
+        assert!(grants.iter().any(|g| g.relation == "banned" && g.excluded));
+        assert!(grants.iter().any(|g| g.relation == "owner" && g.rewrite && !g.excluded));
+        assert!(document.grants("missing").is_empty());
+    }
+
+    #[test]
+    fn test_reachable_entities() {
+        let schema = parse(SCHEMA).unwrap();
+        let names = |type_name| -> Vec<String> {
+            schema.reachable_entities(type_name).iter().map(|e| e.name.clone()).collect()
+        };
+        assert_eq!(names("document"), vec!["Document", "User", "Folder"]);
+        assert_eq!(names("folder"), vec!["Folder", "User"]);
+        assert!(names("missing").is_empty());
+    }
+
+    #[test]
+    fn test_type_name() {
+        assert_eq!(type_name("Document"), "document");
+        assert_eq!(type_name("UserGroup"), "user_group");
+        assert!(type_matches("UserGroup", &type_name("UserGroup")));
+    }
+
+    #[test]
+    fn test_rename_relation() {
+        let source = "entity Group {\n    relations {\n        \
+                      member: User | Group#member\n    }\n}\n\n\
+                      entity Document {\n    relations {\n        parent: Folder\n        \
+                      editor: User | Group#member // writers\n    }\n    \
+                      permissions {\n        edit: editor\n    }\n}\n\n\
+                      entity Folder {\n    relations {\n        doc: Document\n    }\n    \
+                      permissions {\n        edit: doc.editor\n    }\n}\n";
+        let schema = parse(source).unwrap();
+
+        let rename = Refactoring::RenameRelation {
+            entity: "group".into(),
+            from: "member".into(),
+            to: "members".into(),
+        }
+        .resolve(&schema)
+        .unwrap();
+        let renamed = rename.apply(source).unwrap();
+        assert!(renamed.contains("members: User | Group#members"));
+        assert!(renamed.contains("editor: User | Group#members // writers"));
+
+        let rename = Refactoring::RenameRelation {
+            entity: "Document".into(),
+            from: "editor".into(),
+            to: "writer".into(),
+        };
+        let renamed = rename.apply(source).unwrap();
+        assert!(renamed.contains("writer: User | Group#member // writers"));
+        assert!(renamed.contains("edit: writer\n"));
+        assert!(renamed.contains("edit: doc.writer\n"));
+        assert_eq!(rename.reversed().apply(&renamed).unwrap(), source);
+
+        let widened = rename.widen(source).unwrap();
+        assert!(widened.contains(
+            "        editor: User | Group#member // writers\n        writer: User | Group#member\n"
+        ));
+        let widened_group = Refactoring::RenameRelation {
+            entity: "Group".into(),
+            from: "member".into(),
+            to: "members".into(),
+        }
+        .widen(source)
+        .unwrap();
+        assert!(widened_group.contains(
+            "member: User | Group#member | Group#members\n        \
+             members: User | Group#member | Group#members\n"
+        ));
+        assert!(widened.contains("edit: editor\n"));
+        let widened = parse(&widened).unwrap();
+        assert!(widened.entity("Document").unwrap().relation("writer").is_some());
+
+        let taken = Refactoring::RenameRelation {
+            entity: "Document".into(),
+            from: "editor".into(),
+            to: "edit".into(),
+        };
+        assert!(taken.resolve(&schema).is_err());
+    }
+
+    #[test]
+    fn test_rename_entity() {
+        let source = "entity Team {\n    relations {\n        \
+                      member: User | Team#member\n    }\n}\n\n\
+                      entity Doc {\n    relations {\n        \
+                      viewer: Team#member with office\n    }\n}\n";
+        let schema = parse(source).unwrap();
+        let rename = Refactoring::RenameEntity { from: "team".into(), to: "Squad".into() }
+            .resolve(&schema)
+            .unwrap();
+
+        let renamed = rename.apply(source).unwrap();
+        assert!(renamed.starts_with("entity Squad {"));
+        assert!(renamed.contains("viewer: Squad#member with office"));
+
+        let widened = parse(&rename.widen(source).unwrap()).unwrap();
+        assert!(widened.entity("Team").is_some());
+        assert!(widened.entity("Squad").is_some());
+        let squad = widened.entity("Squad").unwrap().relation("member").unwrap();
+        assert_eq!(squad.subjects.len(), 3);
+        let viewer = widened.entity("Doc").unwrap().relation("viewer").unwrap();
+        assert_eq!(viewer.subjects.len(), 2);
+        assert_eq!(viewer.subjects[1].entity, "Squad");
+        assert_eq!(viewer.subjects[1].caveat.as_deref(), Some("office"));
+
+        assert!(
+            Refactoring::RenameEntity { from: "Team".into(), to: "Doc".into() }
+                .resolve(&schema)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_contributions_tolerate_cycles() {
+        let schema =
+            parse("entity Doc { relations { owner: User } permissions { a: b | owner  b: a } }")
+                .unwrap();
+        let matrix = schema.entity("Doc").unwrap().contributions();
+        assert_eq!(matrix["a"]["owner"], Contribution::Direct);
+        assert_eq!(matrix["b"]["owner"], Contribution::Rewrite);
+    }
+}
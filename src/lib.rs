@@ -29,24 +29,43 @@ pub mod cli;
 pub mod client;
 pub mod commands;
 pub mod config;
+pub mod deprecations;
 pub mod error;
+pub mod explain;
 pub mod i18n;
+pub mod input;
+pub mod ipl;
+pub mod oci;
 pub mod output;
+pub mod provenance;
+pub mod query;
+pub mod suggest;
 pub mod tui;
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 pub use cli::Cli;
 pub use error::{Error, Result};
 
 /// CLI version.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Whether errors are printed as JSON (`--output json` or `jsonl`).
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+
 /// Run the CLI with the given arguments.
 ///
 /// This is the main entry point for the CLI, parsing arguments and
 /// dispatching to the appropriate command handler.
 pub async fn run(args: Vec<String>) -> Result<()> {
+    // Completion callbacks take raw command-line words, flags included
+    if args.get(1).map(String::as_str) == Some(commands::COMPLETE_COMMAND) {
+        commands::complete(args.get(2..).unwrap_or_default()).await;
+        return Ok(());
+    }
+
     // Parse @profile prefix before clap
-    let (profile_override, args) = cli::parse_profile_prefix(args);
+    let (profile_prefix, args) = cli::parse_profile_prefix(args)?;
 
     // Pre-scan for --lang to initialize i18n before full parse
     let lang = extract_lang_arg(&args);
@@ -58,14 +77,26 @@ pub async fn run(args: Vec<String>) -> Result<()> {
         eprintln!("Supported languages: {}", i18n::SUPPORTED_LOCALES.join(", "));
     }
 
+    // Configured per-command defaults apply below the command line and env.
+    // A config that fails to load is reported once a command needs it, so
+    // help, version, and completion keep working.
+    let config = config::Config::load().ok();
+    let (command, skipped_defaults) = match &config {
+        Some(config) => config::defaults::apply(Cli::command_localized(), &config.defaults),
+        None => (Cli::command_localized(), Vec::new()),
+    };
+
     // Parse CLI arguments using localized command
-    let mut cli_args = match Cli::command_localized().try_get_matches_from(&args) {
+    let (mut cli_args, profile_flag_given) = match command.try_get_matches_from(&args) {
         Ok(matches) => {
-            use clap::FromArgMatches;
-            Cli::from_arg_matches(&matches).map_err(|e| {
+            use clap::{FromArgMatches, parser::ValueSource};
+            let profile_flag_given =
+                matches.value_source("profile") == Some(ValueSource::CommandLine);
+            let cli_args = Cli::from_arg_matches(&matches).map_err(|e| {
                 e.print().ok();
                 Error::other("")
-            })?
+            })?;
+            (cli_args, profile_flag_given)
         },
         Err(e) => {
             // Print clap error (includes help/version)
@@ -74,23 +105,47 @@ pub async fn run(args: Vec<String>) -> Result<()> {
             use clap::error::ErrorKind;
             match e.kind() {
                 ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => return Ok(()),
+                ErrorKind::InvalidSubcommand => {
+                    let suggestions = suggest::suggest(args.get(1..).unwrap_or_default());
+                    if !suggestions.is_empty() {
+                        eprintln!("\nDid you mean:");
+                        for path in suggestions {
+                            eprintln!("  inferadb {path}");
+                        }
+                    }
+                    return Err(Error::other(""));
+                },
                 _ => return Err(Error::other("")),
             }
         },
     };
 
-    // Apply profile override from @prefix
-    if cli_args.profile.is_none() {
-        cli_args.profile = profile_override;
+    JSON_ERRORS.store(matches!(cli_args.output.as_str(), "json" | "jsonl"), Ordering::Relaxed);
+
+    // Offer to trust a new or changed project config before it takes effect;
+    // `config` commands manage trust themselves
+    if !matches!(*cli_args.command, cli::Commands::Config(_) | cli::Commands::Completion { .. }) {
+        config::project::confirm_trust()?;
     }
 
-    // Initialize logging if debug mode
+    // Layer @prefix, --profile, and their environment variables
+    cli_args.profile = cli::resolve_profile(
+        profile_prefix,
+        cli_args.profile,
+        profile_flag_given,
+        std::env::var(cli::PROFILE_PREFIX_ENV).ok(),
+    )?;
+
+    // Initialize logging and the timing breakdown if debug mode
     if cli_args.debug {
         init_logging();
+        output::breakdown::begin();
     }
 
     // Create context
+    let setup = output::breakdown::start(output::breakdown::Phase::Config, "load config");
     let ctx = client::Context::builder()
+        .maybe_config(config)
         .maybe_profile_name(cli_args.profile)
         .maybe_org_override(cli_args.org)
         .maybe_vault_override(cli_args.vault)
@@ -99,10 +154,106 @@ pub async fn run(args: Vec<String>) -> Result<()> {
         .quiet(cli_args.quiet)
         .yes(cli_args.yes)
         .debug(cli_args.debug)
+        .accessible(cli_args.accessible)
+        .ascii(cli_args.ascii)
+        .utc(cli_args.utc)
+        .maybe_timezone(cli_args.timezone)
+        .relative(cli_args.relative)
+        .cached(cli_args.cached)
+        .no_cache(cli_args.no_cache)
+        .no_keychain(cli_args.no_keychain)
+        .non_blocking(cli_args.non_blocking)
+        .maybe_notify_webhook(cli_args.notify_webhook)
+        .maybe_timeout(cli_args.timeout)
+        .maybe_retries(cli_args.retries)
+        .maybe_retry_on(cli_args.retry_on)
+        .maybe_query(cli_args.output_query)
+        .maybe_record(cli_args.record)
+        .maybe_replay(cli_args.replay)
+        .suppress_warnings(cli_args.suppress_warnings)
         .build()?;
+    drop(setup);
+
+    for skipped in &skipped_defaults {
+        ctx.output.warning(output::warnings::INVALID_DEFAULT, skipped);
+    }
+
+    if uses_session(&cli_args.command) {
+        ctx.warn_if_session_expiring();
+    }
+
+    // Report deprecated usage: as warnings now, or with the command's result
+    let used = deprecations::detect(args.get(1..).unwrap_or_default());
+    if ctx.output.format() == output::OutputFormat::Table {
+        for deprecation in &used {
+            ctx.output.warn(&deprecation.message());
+        }
+    } else {
+        deprecations::defer(&used);
+    }
 
     // Execute command
-    commands::execute(&ctx, &cli_args.command).await
+    let result = commands::execute(&ctx, &cli_args.command).await;
+    deprecations::flush();
+    output::warnings::flush();
+    if let Some(breakdown) = output::breakdown::finish() {
+        breakdown.print(ctx.output.color);
+    }
+    result
+}
+
+/// Print the error from a failed [`run`] to stderr: a JSON `{"error": ...}`
+/// document (code, message, hint, request ID) under `--output json`,
+/// otherwise `Error: ...` followed by any hint.
+///
+/// Errors with an empty message are not printed; clap has already reported
+/// them (e.g., for `--help`).
+pub fn print_error(err: &Error) {
+    let message = err.to_string();
+    if message.is_empty() {
+        return;
+    }
+
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        eprintln!("{}", serde_json::json!({ "error": err.report() }));
+    } else {
+        eprintln!("Error: {message}");
+        if let Some(hint) = err.hint() {
+            eprintln!();
+            eprintln!("{hint}");
+        }
+    }
+}
+
+/// Whether a command talks to the service with stored credentials, and so
+/// should warn about an expiring session before running.
+///
+/// Local-only commands and the commands that manage the session itself skip
+/// the check (and the keychain read it needs).
+fn uses_session(command: &cli::Commands) -> bool {
+    use cli::{Commands, TokensCommands};
+
+    match command {
+        Commands::Login { .. }
+        | Commands::Logout { .. }
+        | Commands::Register { .. }
+        | Commands::Version { .. }
+        | Commands::Completion { .. }
+        | Commands::Cheatsheet { .. }
+        | Commands::Templates { .. }
+        | Commands::Guide { .. }
+        | Commands::Deprecations(_)
+        | Commands::Explain { .. }
+        | Commands::Profiles(_)
+        | Commands::Config(_)
+        | Commands::Cache(_)
+        | Commands::Dev(_)
+        | Commands::Generate(_)
+        | Commands::Release(_) => false,
+        Commands::Check { offline, .. } => !offline,
+        Commands::Tokens(tokens) => !matches!(**tokens, TokensCommands::Refresh),
+        _ => true,
+    }
 }
 
 /// Initialize tracing/logging for debug mode.
@@ -11,17 +11,7 @@ async fn main() -> ExitCode {
     match inferadb_cli::run(args).await {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
-            // Don't print if it's an empty error (e.g., from clap --help)
-            let msg = e.to_string();
-            if !msg.is_empty() {
-                eprintln!("Error: {e}");
-
-                // Show hint if relevant
-                if e.should_suggest_login() {
-                    eprintln!();
-                    eprintln!("Run 'inferadb login' to authenticate.");
-                }
-            }
+            inferadb_cli::print_error(&e);
 
             // Return appropriate exit code
             let code = e.exit_code();
@@ -0,0 +1,493 @@
+//! OCI registry client for distributing schema bundles.
+//!
+//! Schemas are stored as OCI artifacts: the bundle metadata is the config blob
+//! and the schema and test definitions are layers, each identified by its own
+//! media type. Any registry implementing the OCI distribution spec
+//! (GHCR, ECR, Harbor, Docker Hub, `registry:2`) can hold them, and the
+//! manifest digest can be signed with existing tooling such as cosign.
+
+use std::collections::BTreeMap;
+
+use reqwest::{RequestBuilder, Response, StatusCode, header};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+/// Artifact type of a published schema bundle.
+pub const ARTIFACT_TYPE: &str = "application/vnd.inferadb.schema.v1";
+/// Media type of the bundle metadata (config blob).
+pub const METADATA_MEDIA_TYPE: &str = "application/vnd.inferadb.schema.metadata.v1+json";
+/// Media type of the IPL schema layer.
+pub const SCHEMA_MEDIA_TYPE: &str = "application/vnd.inferadb.schema.ipl.v1";
+/// Media type of the schema test definitions layer.
+pub const TESTS_MEDIA_TYPE: &str = "application/vnd.inferadb.schema.tests.v1+yaml";
+
+/// OCI image manifest media type.
+const MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+/// Annotation holding a layer's file name.
+pub const TITLE_ANNOTATION: &str = "org.opencontainers.image.title";
+
+/// Environment variable holding the registry username.
+pub const USERNAME_ENV: &str = "INFERADB_OCI_USERNAME";
+/// Environment variable holding the registry password or token.
+pub const PASSWORD_ENV: &str = "INFERADB_OCI_PASSWORD";
+
+/// A parsed `oci://registry/repository:tag` (or `@sha256:…`) reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// Registry host, with port if given.
+    pub registry: String,
+    /// Repository path within the registry.
+    pub repository: String,
+    /// Tag or digest.
+    pub reference: String,
+}
+
+impl Reference {
+    /// Parse an `oci://` reference. The tag defaults to `latest`.
+    pub fn parse(input: &str) -> Result<Self> {
+        let rest = input.strip_prefix("oci://").ok_or_else(|| {
+            Error::invalid_arg(format!("OCI reference must start with oci://: {input}"))
+        })?;
+        let (registry, path) = rest.split_once('/').ok_or_else(|| {
+            Error::invalid_arg(format!("OCI reference is missing a repository: {input}"))
+        })?;
+
+        let (repository, reference) = if let Some((repo, digest)) = path.split_once('@') {
+            if !digest.starts_with("sha256:") {
+                return Err(Error::invalid_arg(format!("Unsupported digest in {input}")));
+            }
+            (repo, digest)
+        } else {
+            // A colon after the last slash separates the tag.
+            let last = path.rfind('/').map_or(0, |i| i + 1);
+            match path[last..].rfind(':') {
+                Some(i) => (&path[..last + i], &path[last + i + 1..]),
+                None => (path, "latest"),
+            }
+        };
+
+        let valid_repo = !repository.is_empty()
+            && repository.split('/').all(|part| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "._-".contains(c))
+            });
+        if registry.is_empty() || !valid_repo || reference.is_empty() {
+            return Err(Error::invalid_arg(format!("Invalid OCI reference: {input}")));
+        }
+
+        Ok(Self {
+            registry: registry.to_string(),
+            repository: repository.to_string(),
+            reference: reference.to_string(),
+        })
+    }
+
+    /// Base URL of the registry's distribution API.
+    ///
+    /// Local registries are reached over plain HTTP; everything else uses HTTPS.
+    pub fn api_base(&self) -> String {
+        let host = self.registry.split(':').next().unwrap_or_default();
+        let scheme = if matches!(host, "localhost" | "127.0.0.1") { "http" } else { "https" };
+        format!("{scheme}://{}/v2/{}", self.registry, self.repository)
+    }
+
+    /// The reference pinned to `digest`, for signing and reproducible pulls.
+    pub fn pinned(&self, digest: &str) -> String {
+        format!("{}/{}@{digest}", self.registry, self.repository)
+    }
+}
+
+impl std::fmt::Display for Reference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sep = if self.reference.starts_with("sha256:") { '@' } else { ':' };
+        write!(f, "oci://{}/{}{sep}{}", self.registry, self.repository, self.reference)
+    }
+}
+
+/// Content descriptor for a blob.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Descriptor {
+    /// Blob media type.
+    pub media_type: String,
+    /// `sha256:` content digest.
+    pub digest: String,
+    /// Blob size in bytes.
+    pub size: u64,
+    /// Descriptor annotations.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl Descriptor {
+    /// Describe `content` with the given media type.
+    pub fn new(media_type: &str, content: &[u8]) -> Self {
+        Self {
+            media_type: media_type.to_string(),
+            digest: digest(content),
+            size: content.len() as u64,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    /// Attach a file name annotation.
+    #[must_use]
+    pub fn titled(mut self, title: &str) -> Self {
+        self.annotations.insert(TITLE_ANNOTATION.to_string(), title.to_string());
+        self
+    }
+
+    /// The file name annotation, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.annotations.get(TITLE_ANNOTATION).map(String::as_str)
+    }
+}
+
+/// OCI image manifest describing an artifact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    /// Always 2.
+    pub schema_version: u32,
+    /// Manifest media type.
+    #[serde(default)]
+    pub media_type: String,
+    /// Artifact type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_type: Option<String>,
+    /// Config blob.
+    pub config: Descriptor,
+    /// Layer blobs.
+    pub layers: Vec<Descriptor>,
+    /// Manifest annotations.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    /// A schema bundle manifest.
+    pub fn new(config: Descriptor, layers: Vec<Descriptor>) -> Self {
+        Self {
+            schema_version: 2,
+            media_type: MANIFEST_MEDIA_TYPE.to_string(),
+            artifact_type: Some(ARTIFACT_TYPE.to_string()),
+            config,
+            layers,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    /// The first layer with the given media type.
+    pub fn layer(&self, media_type: &str) -> Option<&Descriptor> {
+        self.layers.iter().find(|l| l.media_type == media_type)
+    }
+}
+
+/// `sha256:` digest of `content`.
+pub fn digest(content: &[u8]) -> String {
+    let hash = Sha256::digest(content);
+    let mut out = String::with_capacity(7 + hash.len() * 2);
+    out.push_str("sha256:");
+    for byte in hash {
+        out.push_str(&format!("{byte:02x}"));
+    }
+    out
+}
+
+/// A parsed `WWW-Authenticate` challenge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Challenge {
+    scheme: String,
+    params: BTreeMap<String, String>,
+}
+
+impl Challenge {
+    fn parse(header: &str) -> Option<Self> {
+        let (scheme, rest) = header.trim().split_once(' ').unwrap_or_else(|| (header.trim(), ""));
+        let mut params = BTreeMap::new();
+        let mut rest = rest.trim();
+        while let Some((key, after)) = rest.split_once('=') {
+            let key = key.trim().trim_start_matches(',').trim().to_ascii_lowercase();
+            let (value, remaining) = if let Some(quoted) = after.strip_prefix('"') {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            } else {
+                after.split_once(',').map_or((after, ""), |(v, r)| (v, r))
+            };
+            params.insert(key, value.to_string());
+            rest = remaining.trim_start_matches([',', ' ']);
+        }
+        Some(Self { scheme: scheme.to_ascii_lowercase(), params })
+    }
+}
+
+/// Credentials attached to registry requests.
+#[derive(Debug, Clone)]
+enum Auth {
+    Basic(String, String),
+    Bearer(String),
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+/// Client for one repository in an OCI registry.
+pub struct Registry {
+    http: reqwest::Client,
+    reference: Reference,
+    credentials: Option<(String, String)>,
+    auth: Option<Auth>,
+}
+
+impl Registry {
+    /// Connect to the repository named by `reference`, reading credentials from
+    /// [`USERNAME_ENV`] and [`PASSWORD_ENV`] if set.
+    pub fn new(reference: Reference) -> Self {
+        let credentials = std::env::var(USERNAME_ENV)
+            .ok()
+            .zip(std::env::var(PASSWORD_ENV).ok())
+            .filter(|(user, _)| !user.is_empty());
+        Self { http: reqwest::Client::new(), reference, credentials, auth: None }
+    }
+
+    /// Upload a blob unless the registry already has it.
+    pub async fn push_blob(&mut self, descriptor: &Descriptor, content: &[u8]) -> Result<()> {
+        let base = self.reference.api_base();
+        let blob_url = format!("{base}/blobs/{}", descriptor.digest);
+        if self.send(|http| http.head(&blob_url)).await?.status().is_success() {
+            return Ok(());
+        }
+
+        let uploads_url = format!("{base}/blobs/uploads/");
+        let response = self.send(|http| http.post(&uploads_url)).await?;
+        let response = check(response, "start blob upload").await?;
+        let location = response
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| Error::other("Registry did not return an upload location"))?;
+        let mut upload = reqwest::Url::parse(&uploads_url)
+            .and_then(|url| url.join(location))
+            .map_err(|e| Error::other(format!("Invalid upload location {location}: {e}")))?;
+        upload.query_pairs_mut().append_pair("digest", &descriptor.digest);
+
+        let response = self
+            .send(|http| {
+                http.put(upload.clone())
+                    .header(header::CONTENT_TYPE, "application/octet-stream")
+                    .body(content.to_vec())
+            })
+            .await?;
+        check(response, "upload blob").await?;
+        Ok(())
+    }
+
+    /// Upload the manifest under the reference's tag, returning its digest.
+    pub async fn push_manifest(&mut self, manifest: &Manifest) -> Result<String> {
+        let body = serde_json::to_vec(manifest)?;
+        let url = format!("{}/manifests/{}", self.reference.api_base(), self.reference.reference);
+        let response = self
+            .send(|http| {
+                http.put(&url).header(header::CONTENT_TYPE, MANIFEST_MEDIA_TYPE).body(body.clone())
+            })
+            .await?;
+        check(response, "upload manifest").await?;
+        Ok(digest(&body))
+    }
+
+    /// Fetch the manifest for the reference, returning it with its digest.
+    pub async fn pull_manifest(&mut self) -> Result<(Manifest, String)> {
+        let url = format!("{}/manifests/{}", self.reference.api_base(), self.reference.reference);
+        let response =
+            self.send(|http| http.get(&url).header(header::ACCEPT, MANIFEST_MEDIA_TYPE)).await?;
+        let body = check(response, "fetch manifest").await?.bytes().await.map_err(http_error)?;
+
+        let manifest_digest = digest(&body);
+        if self.reference.reference.starts_with("sha256:")
+            && manifest_digest != self.reference.reference
+        {
+            return Err(Error::other(format!(
+                "Manifest digest mismatch: expected {}, got {manifest_digest}",
+                self.reference.reference
+            )));
+        }
+        let manifest: Manifest = serde_json::from_slice(&body)?;
+        Ok((manifest, manifest_digest))
+    }
+
+    /// Download a blob and verify its digest.
+    pub async fn pull_blob(&mut self, descriptor: &Descriptor) -> Result<Vec<u8>> {
+        let url = format!("{}/blobs/{}", self.reference.api_base(), descriptor.digest);
+        let response = self.send(|http| http.get(&url)).await?;
+        let body = check(response, "fetch blob").await?.bytes().await.map_err(http_error)?;
+        if digest(&body) != descriptor.digest {
+            return Err(Error::other(format!("Blob digest mismatch for {}", descriptor.digest)));
+        }
+        Ok(body.to_vec())
+    }
+
+    /// Send a request, answering one authentication challenge if needed.
+    async fn send(
+        &mut self,
+        build: impl Fn(&reqwest::Client) -> RequestBuilder,
+    ) -> Result<Response> {
+        let response = self.authorized(build(&self.http)).send().await.map_err(http_error)?;
+        if response.status() != StatusCode::UNAUTHORIZED || self.auth.is_some() {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .headers()
+            .get(header::WWW_AUTHENTICATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(Challenge::parse);
+        let Some(challenge) = challenge else {
+            return Ok(response);
+        };
+        self.auth = Some(self.authenticate(&challenge).await?);
+        self.authorized(build(&self.http)).send().await.map_err(http_error)
+    }
+
+    fn authorized(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.auth {
+            Some(Auth::Basic(user, password)) => request.basic_auth(user, Some(password)),
+            Some(Auth::Bearer(token)) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Resolve a challenge to the credentials to retry with.
+    async fn authenticate(&self, challenge: &Challenge) -> Result<Auth> {
+        if challenge.scheme == "basic" {
+            let (user, password) = self.credentials.clone().ok_or_else(|| {
+                Error::credential(format!(
+                    "Registry {} requires credentials; set {USERNAME_ENV} and {PASSWORD_ENV}",
+                    self.reference.registry
+                ))
+            })?;
+            return Ok(Auth::Basic(user, password));
+        }
+
+        let realm = challenge
+            .params
+            .get("realm")
+            .ok_or_else(|| Error::credential("Registry auth challenge has no realm"))?;
+        let mut url = reqwest::Url::parse(realm)
+            .map_err(|e| Error::credential(format!("Invalid registry auth realm {realm}: {e}")))?;
+        for key in ["service", "scope"] {
+            if let Some(value) = challenge.params.get(key) {
+                url.query_pairs_mut().append_pair(key, value);
+            }
+        }
+        let mut request = self.http.get(url);
+        if let Some((user, password)) = &self.credentials {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        let response = check(request.send().await.map_err(http_error)?, "obtain registry token")
+            .await
+            .map_err(|e| Error::credential(e.to_string()))?;
+        let token: TokenResponse = response.json().await.map_err(http_error)?;
+        token
+            .token
+            .or(token.access_token)
+            .map(Auth::Bearer)
+            .ok_or_else(|| Error::credential("Registry token response has no token"))
+    }
+}
+
+/// Fail on a non-success status, including the registry's error body.
+async fn check(response: Response, action: &str) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let detail = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| v["errors"][0]["message"].as_str().map(String::from))
+        .unwrap_or(body);
+    if detail.is_empty() {
+        Err(Error::other(format!("Failed to {action}: {status}")))
+    } else {
+        Err(Error::other(format!("Failed to {action}: {status}: {detail}")))
+    }
+}
+
+fn http_error(e: reqwest::Error) -> Error {
+    Error::other(format!("Registry request failed: {e}"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reference() {
+        let r = Reference::parse("oci://ghcr.io/acme/authz/schema:v1.2").unwrap();
+        assert_eq!(r.registry, "ghcr.io");
+        assert_eq!(r.repository, "acme/authz/schema");
+        assert_eq!(r.reference, "v1.2");
+        assert_eq!(r.api_base(), "https://ghcr.io/v2/acme/authz/schema");
+
+        let r = Reference::parse("oci://localhost:5000/schema").unwrap();
+        assert_eq!(r.reference, "latest");
+        assert_eq!(r.api_base(), "http://localhost:5000/v2/schema");
+        assert_eq!(r.to_string(), "oci://localhost:5000/schema:latest");
+
+        let pinned = format!("oci://ghcr.io/acme/schema@{}", digest(b""));
+        assert_eq!(Reference::parse(&pinned).unwrap().to_string(), pinned);
+
+        assert!(Reference::parse("ghcr.io/acme/schema:v1").is_err());
+        assert!(Reference::parse("oci://ghcr.io").is_err());
+        assert!(Reference::parse("oci://ghcr.io/Acme/schema").is_err());
+        assert!(Reference::parse("oci://ghcr.io/acme/schema@md5:abc").is_err());
+    }
+
+    #[test]
+    fn test_digest() {
+        assert_eq!(
+            digest(b"hello"),
+            "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+    }
+
+    #[test]
+    fn test_manifest_json() {
+        let schema = Descriptor::new(SCHEMA_MEDIA_TYPE, b"entity user {}").titled("schema.ipl");
+        let manifest = Manifest::new(Descriptor::new(METADATA_MEDIA_TYPE, b"{}"), vec![schema]);
+        let json = serde_json::to_value(&manifest).unwrap();
+
+        assert_eq!(json["schemaVersion"], 2);
+        assert_eq!(json["artifactType"], ARTIFACT_TYPE);
+        assert_eq!(json["config"]["mediaType"], METADATA_MEDIA_TYPE);
+        assert_eq!(json["layers"][0]["size"], 14);
+        assert_eq!(json["layers"][0]["annotations"][TITLE_ANNOTATION], "schema.ipl");
+
+        let parsed: Manifest = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.layer(SCHEMA_MEDIA_TYPE).and_then(Descriptor::title), Some("schema.ipl"));
+        assert!(parsed.layer(TESTS_MEDIA_TYPE).is_none());
+    }
+
+    #[test]
+    fn test_parse_challenge() {
+        let c = Challenge::parse(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:acme/schema:pull,push""#,
+        )
+        .unwrap();
+        assert_eq!(c.scheme, "bearer");
+        assert_eq!(c.params["realm"], "https://ghcr.io/token");
+        assert_eq!(c.params["scope"], "repository:acme/schema:pull,push");
+
+        let c = Challenge::parse(r#"Basic realm="Registry""#).unwrap();
+        assert_eq!(c.scheme, "basic");
+    }
+}
@@ -0,0 +1,201 @@
+//! Where a command's time went, for `--debug`.
+//!
+//! With `--debug`, config loading, authentication, API calls and rendering
+//! each record their duration here, and a breakdown is printed to stderr once
+//! the command finishes, so "check is slow" reports come with numbers and
+//! client-side overhead shows up as the time no phase accounts for.
+//!
+//! Recording is process-wide and a no-op unless [`begin`] was called, so
+//! call sites can time unconditionally.
+
+use std::{
+    future::IntoFuture,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use teapot::{
+    components::{Column, Table},
+    output as toutput,
+};
+
+use super::timings::format_duration;
+
+/// Active breakdown, if `--debug` was given.
+static ACTIVE: Mutex<Option<Breakdown>> = Mutex::new(None);
+
+/// What a timed span was doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading configuration and setting up the command.
+    Config,
+    /// Loading or refreshing credentials and building the client.
+    Auth,
+    /// A request to the service.
+    Api,
+    /// Formatting output.
+    Render,
+}
+
+impl Phase {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Auth => "auth",
+            Self::Api => "api",
+            Self::Render => "render",
+        }
+    }
+}
+
+/// One timed span.
+#[derive(Debug, Clone)]
+struct Span {
+    phase: Phase,
+    label: String,
+    duration: Duration,
+}
+
+/// Timed spans for one command invocation.
+#[derive(Debug, Clone)]
+pub struct Breakdown {
+    spans: Vec<Span>,
+    started: Instant,
+    total: Duration,
+}
+
+impl Breakdown {
+    /// Print the breakdown to stderr: each span, the total per phase, the
+    /// time no span covers, and the wall-clock total.
+    pub fn print(&self, color: bool) {
+        let table = Table::new()
+            .columns(vec![Column::new("PHASE"), Column::new("STEP"), Column::new("TIME")])
+            .rows(self.rows())
+            .show_borders(false)
+            .focused(false);
+
+        let output = table.render();
+        eprintln!();
+        if color {
+            eprintln!("{output}");
+        } else {
+            eprintln!("{}", toutput::strip_ansi(&output));
+        }
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let mut rows: Vec<Vec<String>> = self
+            .spans
+            .iter()
+            .map(|span| {
+                vec![
+                    span.phase.name().to_string(),
+                    span.label.clone(),
+                    format_duration(span.duration),
+                ]
+            })
+            .collect();
+
+        for phase in [Phase::Config, Phase::Auth, Phase::Api, Phase::Render] {
+            let spans: Vec<&Span> = self.spans.iter().filter(|s| s.phase == phase).collect();
+            if spans.len() > 1 {
+                let time: Duration = spans.iter().map(|s| s.duration).sum();
+                rows.push(vec![
+                    phase.name().to_string(),
+                    format!("{} total", spans.len()),
+                    format_duration(time),
+                ]);
+            }
+        }
+
+        let covered: Duration = self.spans.iter().map(|s| s.duration).sum();
+        rows.push(vec![
+            "other".to_string(),
+            "client overhead".to_string(),
+            format_duration(self.total.saturating_sub(covered)),
+        ]);
+        rows.push(vec!["Total".to_string(), String::new(), format_duration(self.total)]);
+        rows
+    }
+}
+
+/// Start recording spans.
+pub fn begin() {
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active =
+            Some(Breakdown { spans: Vec::new(), started: Instant::now(), total: Duration::ZERO });
+    }
+}
+
+/// Record a completed span. Does nothing unless recording is active.
+pub fn record(phase: Phase, label: &str, duration: Duration) {
+    if let Ok(mut active) = ACTIVE.lock()
+        && let Some(breakdown) = active.as_mut()
+    {
+        breakdown.spans.push(Span { phase, label: label.to_string(), duration });
+    }
+}
+
+/// Records a span from [`start`] until it is dropped.
+#[must_use = "the span ends when the timer is dropped"]
+pub struct Timer {
+    phase: Phase,
+    label: &'static str,
+    started: Instant,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        record(self.phase, self.label, self.started.elapsed());
+    }
+}
+
+/// Start a span that ends when the returned timer is dropped.
+pub fn start(phase: Phase, label: &'static str) -> Timer {
+    Timer { phase, label, started: Instant::now() }
+}
+
+/// Run `work`, recording how long it took.
+pub async fn timed<F: IntoFuture>(phase: Phase, label: &str, work: F) -> F::Output {
+    let started = Instant::now();
+    let output = work.await;
+    record(phase, label, started.elapsed());
+    output
+}
+
+/// Stop recording and return the spans.
+pub fn finish() -> Option<Breakdown> {
+    let mut breakdown = ACTIVE.lock().ok()?.take()?;
+    breakdown.total = breakdown.started.elapsed();
+    Some(breakdown)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows() {
+        let span = |phase, label: &str, ms| Span {
+            phase,
+            label: label.to_string(),
+            duration: Duration::from_millis(ms),
+        };
+        let breakdown = Breakdown {
+            spans: vec![
+                span(Phase::Config, "load config", 4),
+                span(Phase::Api, "check", 120),
+                span(Phase::Api, "expand", 80),
+                span(Phase::Render, "table", 2),
+            ],
+            started: Instant::now(),
+            total: Duration::from_millis(250),
+        };
+        let rows = breakdown.rows();
+        assert_eq!(rows[1], ["api", "check", "120ms"]);
+        assert_eq!(rows[4], ["api", "2 total", "200ms"]);
+        assert_eq!(rows[5], ["other", "client overhead", "44ms"]);
+        assert_eq!(rows[6], ["Total", "", "250ms"]);
+    }
+}
@@ -0,0 +1,200 @@
+//! Session recording in asciinema v2 (`.cast`) format.
+//!
+//! The interactive shell can record everything it prints, with the time each
+//! chunk appeared, so sessions replay with `asciinema play`. Every chunk goes
+//! through a redaction pass before it is written, masking bearer tokens, JWTs,
+//! secret-looking `key=value` pairs, and any user-supplied literals.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::Instant,
+};
+
+use serde::Serialize;
+
+use crate::error::Result;
+
+/// Replacement text for redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Keys whose values are always redacted in `key=value` / `key: value` pairs.
+const SECRET_KEYS: &[&str] =
+    &["token", "access_token", "refresh_token", "secret", "client_secret", "password", "api_key"];
+
+/// asciinema v2 header line.
+#[derive(Debug, Serialize)]
+struct Header<'a> {
+    version: u8,
+    width: u16,
+    height: u16,
+    timestamp: i64,
+    title: &'a str,
+}
+
+/// Records terminal output to an asciinema v2 file.
+pub struct CastRecorder {
+    writer: BufWriter<File>,
+    started: Instant,
+    redactions: Vec<String>,
+}
+
+impl CastRecorder {
+    /// Create a recording at `path`, writing the header immediately.
+    ///
+    /// `redactions` are literal strings masked wherever they appear, in
+    /// addition to the built-in secret patterns.
+    pub fn create(path: &Path, title: &str, redactions: Vec<String>) -> Result<Self> {
+        let (width, height) = teapot::terminal::size().unwrap_or((80, 24));
+        let mut writer = BufWriter::new(File::create(path)?);
+        let header =
+            Header { version: 2, width, height, timestamp: chrono::Utc::now().timestamp(), title };
+        writeln!(writer, "{}", serde_json::to_string(&header)?)?;
+        Ok(Self { writer, started: Instant::now(), redactions })
+    }
+
+    /// Record a chunk of output, redacted and with terminal line endings.
+    pub fn output(&mut self, data: &str) -> Result<()> {
+        let data = redact(data, &self.redactions).replace('\n', "\r\n");
+        let event = (self.started.elapsed().as_secs_f64(), "o", data);
+        writeln!(self.writer, "{}", serde_json::to_string(&event)?)?;
+        Ok(())
+    }
+
+    /// Flush buffered events to disk.
+    pub fn finish(mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Mask secrets in `text`.
+///
+/// Redacts user-supplied literals, `Bearer` tokens, JWT-shaped tokens, and
+/// values of secret-looking keys (e.g., `password=...`, `"token": "..."`,
+/// `token: ...`), including when the value is the word after the key.
+pub fn redact(text: &str, literals: &[String]) -> String {
+    let mut text = text.to_string();
+    for literal in literals.iter().filter(|l| !l.is_empty()) {
+        text = text.replace(literal.as_str(), REDACTED);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut previous = "";
+    for piece in split_keep_whitespace(&text) {
+        let is_word = !piece.chars().all(char::is_whitespace);
+        let redacted = if !is_word {
+            piece.to_string()
+        } else if previous.eq_ignore_ascii_case("bearer") || looks_like_jwt(piece) {
+            REDACTED.to_string()
+        } else if is_secret_key(previous) {
+            redact_value(piece)
+        } else {
+            redact_assignment(piece)
+        };
+        out.push_str(&redacted);
+        if is_word {
+            previous = piece;
+        }
+    }
+    out
+}
+
+/// Split text into alternating runs of whitespace and non-whitespace.
+fn split_keep_whitespace(text: &str) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut in_space = None;
+    for (i, c) in text.char_indices() {
+        let space = c.is_whitespace();
+        if in_space.is_some_and(|s| s != space) {
+            pieces.push(&text[start..i]);
+            start = i;
+        }
+        in_space = Some(space);
+    }
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
+
+/// Three dot-separated base64url segments starting with an encoded `{"`.
+fn looks_like_jwt(word: &str) -> bool {
+    let word = word.trim_matches(|c: char| !(c.is_ascii_alphanumeric() || "-_.".contains(c)));
+    let segments: Vec<&str> = word.split('.').collect();
+    segments.len() == 3
+        && word.starts_with("eyJ")
+        && segments.iter().all(|s| {
+            !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// Redact the value of a `key=value` or `"key":"value"` word with a secret key.
+fn redact_assignment(word: &str) -> String {
+    let Some(split) = word.find(['=', ':']) else {
+        return word.to_string();
+    };
+    let (prefix, value) = word.split_at(split + 1);
+    if !is_secret_key(prefix) {
+        return word.to_string();
+    }
+    format!("{prefix}{}", redact_value(value))
+}
+
+/// Whether `word` is a secret key followed by `=` or `:` (e.g., `"token":`).
+fn is_secret_key(word: &str) -> bool {
+    let Some(key) = word.strip_suffix(['=', ':']) else {
+        return false;
+    };
+    let key = key.trim_matches(|c: char| "\"'{,".contains(c));
+    SECRET_KEYS.iter().any(|k| key.eq_ignore_ascii_case(k))
+}
+
+/// Replace a value, keeping any surrounding quotes and punctuation.
+fn redact_value(value: &str) -> String {
+    let secret = value.trim_matches(|c: char| "\"'{},".contains(c));
+    if secret.is_empty() {
+        return value.to_string();
+    }
+    value.replacen(secret, REDACTED, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_literals() {
+        let literals = vec!["acme-prod".to_string()];
+        assert_eq!(redact("vault acme-prod ready", &literals), "vault [REDACTED] ready");
+    }
+
+    #[test]
+    fn test_redact_bearer_and_jwt() {
+        assert_eq!(
+            redact("Authorization: Bearer abc123\n", &[]),
+            "Authorization: Bearer [REDACTED]\n"
+        );
+        assert_eq!(redact("token eyJhbGci.eyJzdWIi.c2lnbmF0dXJl ok", &[]), "token [REDACTED] ok");
+        assert_eq!(redact("version 1.2.3", &[]), "version 1.2.3");
+    }
+
+    #[test]
+    fn test_redact_assignments() {
+        assert_eq!(redact("password=hunter2", &[]), "password=[REDACTED]");
+        assert_eq!(redact(r#"{"token":"abc","#, &[]), r#"{"token":"[REDACTED]","#);
+        assert_eq!(redact("relation=viewer", &[]), "relation=viewer");
+    }
+
+    #[test]
+    fn test_redact_value_after_key() {
+        assert_eq!(
+            redact("{\n  \"access_token\": \"abc\",\n  \"expires_in\": 3600\n}", &[]),
+            "{\n  \"access_token\": \"[REDACTED]\",\n  \"expires_in\": 3600\n}"
+        );
+        assert_eq!(redact("token: abc\nuser: alice\n", &[]), "token: [REDACTED]\nuser: alice\n");
+        assert_eq!(redact("client_secret= s3cr3t", &[]), "client_secret= [REDACTED]");
+    }
+}
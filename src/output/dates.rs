@@ -0,0 +1,181 @@
+//! Timestamp formatting for human-readable output.
+//!
+//! Tables and detail views show timestamps in the local timezone, or the one
+//! set with `--timezone` or the profile's `timezone`, with the date order of
+//! the user's locale (`LC_ALL`, `LC_TIME`, then `LANG`; ISO 8601 when none is
+//! set). `--utc` keeps them in UTC and `--relative` shows them as "3 hours
+//! ago" or "in 2 days". Structured formats (JSON, YAML) are
+//! unaffected and always carry RFC 3339 timestamps.
+
+use std::sync::{
+    OnceLock,
+    atomic::{AtomicU8, Ordering},
+};
+
+use chrono::{DateTime, Local, Utc};
+
+use super::timezone::TimeZone;
+
+/// How timestamps are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeStyle {
+    /// Local or configured timezone, locale date order.
+    #[default]
+    Local,
+    /// UTC, locale date order.
+    Utc,
+    /// Relative to now, e.g. "3 hours ago".
+    Relative,
+}
+
+static STYLE: AtomicU8 = AtomicU8::new(0);
+
+static ZONE: OnceLock<TimeZone> = OnceLock::new();
+
+/// Set how timestamps are shown for the rest of the process.
+pub fn set_style(style: TimeStyle) {
+    STYLE.store(style as u8, Ordering::Relaxed);
+}
+
+/// Show timestamps in `zone` instead of the local timezone.
+pub fn set_timezone(zone: TimeZone) {
+    let _ = ZONE.set(zone);
+}
+
+/// How timestamps are shown.
+#[must_use]
+pub fn style() -> TimeStyle {
+    match STYLE.load(Ordering::Relaxed) {
+        1 => TimeStyle::Utc,
+        2 => TimeStyle::Relative,
+        _ => TimeStyle::Local,
+    }
+}
+
+/// Format a timestamp to the minute, e.g. `2026-10-16 14:03`.
+#[must_use]
+pub fn format(time: DateTime<Utc>) -> String {
+    render(time, "%H:%M")
+}
+
+/// Format a timestamp to the second, for logs and audit events.
+#[must_use]
+pub fn format_precise(time: DateTime<Utc>) -> String {
+    render(time, "%H:%M:%S")
+}
+
+/// Format only the date of a timestamp.
+#[must_use]
+pub fn format_date(time: DateTime<Utc>) -> String {
+    render(time, "")
+}
+
+/// Format an optional timestamp to the minute, or `-` when absent.
+#[must_use]
+pub fn format_opt(time: Option<DateTime<Utc>>) -> String {
+    time.map_or_else(|| "-".to_string(), format)
+}
+
+fn render(time: DateTime<Utc>, clock: &str) -> String {
+    let pattern = match (date_pattern(), clock) {
+        (date, "") => date.to_string(),
+        (date, clock) => format!("{date} {clock}"),
+    };
+    match style() {
+        TimeStyle::Local => match ZONE.get() {
+            Some(zone) => time.with_timezone(&zone.offset_at(time)).format(&pattern).to_string(),
+            None => time.with_timezone(&Local).format(&pattern).to_string(),
+        },
+        TimeStyle::Utc => time.format(&pattern).to_string(),
+        TimeStyle::Relative => relative(time, Utc::now()),
+    }
+}
+
+/// Date pattern for the user's locale.
+fn date_pattern() -> &'static str {
+    let locale = ["LC_ALL", "LC_TIME", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|locale| !locale.is_empty());
+    locale_pattern(locale.as_deref().unwrap_or("C"))
+}
+
+/// Date pattern for a POSIX or BCP 47 locale name.
+fn locale_pattern(locale: &str) -> &'static str {
+    let locale = locale.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+    let language = locale.split('-').next().unwrap_or_default();
+    match locale.as_str() {
+        "" | "C" | "POSIX" | "en-CA" => return "%Y-%m-%d",
+        "en-US" | "en-PH" => return "%m/%d/%Y",
+        _ => {},
+    }
+    match language {
+        "zh" | "ja" | "ko" | "sv" | "lt" | "hu" => "%Y-%m-%d",
+        "de" | "ru" | "pl" | "cs" | "fi" | "nb" | "da" | "tr" | "uk" => "%d.%m.%Y",
+        "nl" => "%d-%m-%Y",
+        _ => "%d/%m/%Y",
+    }
+}
+
+/// Describe `time` relative to `now`, e.g. "3 hours ago" or "in 2 days".
+#[must_use]
+pub fn relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - time).num_seconds();
+    let elapsed = seconds.unsigned_abs();
+    if elapsed < 60 {
+        return "just now".to_string();
+    }
+
+    let (count, unit) = match elapsed {
+        e if e < 3_600 => (e / 60, "minute"),
+        e if e < 86_400 => (e / 3_600, "hour"),
+        e if e < 30 * 86_400 => (e / 86_400, "day"),
+        e if e < 365 * 86_400 => (e / (30 * 86_400), "month"),
+        e => (e / (365 * 86_400), "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    if seconds >= 0 {
+        format!("{count} {unit}{plural} ago")
+    } else {
+        format!("in {count} {unit}{plural}")
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_relative() {
+        let now = Utc::now();
+        assert_eq!(relative(now - Duration::seconds(20), now), "just now");
+        assert_eq!(relative(now - Duration::minutes(1), now), "1 minute ago");
+        assert_eq!(relative(now - Duration::hours(3), now), "3 hours ago");
+        assert_eq!(relative(now + Duration::days(2), now), "in 2 days");
+        assert_eq!(relative(now - Duration::days(65), now), "2 months ago");
+        assert_eq!(relative(now - Duration::days(800), now), "2 years ago");
+    }
+
+    #[test]
+    fn test_locale_pattern() {
+        assert_eq!(locale_pattern("C"), "%Y-%m-%d");
+        assert_eq!(locale_pattern("en_US.UTF-8"), "%m/%d/%Y");
+        assert_eq!(locale_pattern("en_GB.UTF-8"), "%d/%m/%Y");
+        assert_eq!(locale_pattern("de_DE.UTF-8"), "%d.%m.%Y");
+        assert_eq!(locale_pattern("ja_JP.UTF-8"), "%Y-%m-%d");
+        assert_eq!(locale_pattern("fr-FR"), "%d/%m/%Y");
+    }
+
+    #[test]
+    fn test_format_utc() {
+        set_style(TimeStyle::Utc);
+        let time = DateTime::parse_from_rfc3339("2026-10-16T14:03:09Z").unwrap().to_utc();
+        assert!(format(time).ends_with(" 14:03"));
+        assert!(format_precise(time).ends_with(" 14:03:09"));
+        assert_eq!(format_opt(None), "-");
+        set_style(TimeStyle::Local);
+    }
+}
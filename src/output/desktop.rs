@@ -0,0 +1,92 @@
+//! Desktop notifications.
+//!
+//! Sent through the platform's own tool, so there is nothing to install on
+//! macOS (`osascript`) and Windows (`PowerShell`); Linux and the BSDs need
+//! `notify-send` (libnotify).
+
+use std::process::{Command, Stdio};
+
+use crate::error::{Error, Result};
+
+/// Show a desktop notification.
+///
+/// The Windows script stays up while the balloon tip shows, so it is started
+/// without waiting for it; only a failure to launch it is reported there.
+pub fn notify(title: &str, body: &str) -> Result<()> {
+    let mut command = command(title, body);
+    let program = command.get_program().to_string_lossy().into_owned();
+    let failed = |reason: &str| {
+        Error::other(format!("Could not send a desktop notification with {program}: {reason}"))
+    };
+    command.stdin(Stdio::null());
+    if cfg!(windows) {
+        command
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| failed(&e.to_string()))?;
+        return Ok(());
+    }
+    let output = command.output().map_err(|e| failed(&e.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    ));
+    command
+}
+
+#[cfg(windows)]
+fn command(title: &str, body: &str) -> Command {
+    // A balloon tip from the system tray, which needs no extra modules
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; \
+         $n.ShowBalloonTip(5000, {}, {}, 'Info'); Start-Sleep -Seconds 5; $n.Dispose()",
+        powershell_string(title),
+        powershell_string(body)
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name", "inferadb", title, body]);
+    command
+}
+
+/// Quote text as an `AppleScript` string literal.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Quote text as a `PowerShell` single-quoted string literal.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn powershell_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(applescript_string(r#"say "hi" \ bye"#), r#""say \"hi\" \\ bye""#);
+        assert_eq!(powershell_string("it's"), "'it''s'");
+    }
+}
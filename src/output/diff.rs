@@ -0,0 +1,479 @@
+//! Line diffs with colored terminal rendering.
+//!
+//! Computes a line-level diff between two texts, groups it into hunks with
+//! surrounding context, and renders it as a unified or side-by-side view.
+//! Paired removed/added lines get intra-line highlighting of the changed
+//! words, which makes small edits in long schema lines easy to spot.
+
+use serde::Serialize;
+use unicode_width::UnicodeWidthChar;
+
+use crate::{
+    error::{Error, Result},
+    output::glyphs,
+};
+
+// ANSI styles for diff rendering
+/// Style for removed lines.
+pub const RED: &str = "\x1b[31m";
+/// Style for added lines.
+pub const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const DIM: &str = "\x1b[90m";
+const RED_HIGHLIGHT: &str = "\x1b[1;97;41m";
+const GREEN_HIGHLIGHT: &str = "\x1b[1;97;42m";
+const RESET: &str = "\x1b[0m";
+
+/// Layout for rendered diffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffStyle {
+    /// Single column with `-`/`+` markers.
+    #[default]
+    Unified,
+    /// Old and new text in two columns.
+    SideBySide,
+}
+
+impl DiffStyle {
+    /// Parse a diff style from a string.
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "unified" => Ok(Self::Unified),
+            "side-by-side" | "split" => Ok(Self::SideBySide),
+            _ => Err(Error::invalid_arg(format!(
+                "Unknown diff style '{s}'. Use: unified, side-by-side"
+            ))),
+        }
+    }
+}
+
+/// A single line of a diff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "op", content = "text", rename_all = "snake_case")]
+pub enum DiffLine {
+    /// Line present in both texts.
+    Equal(String),
+    /// Line only in the old text.
+    Delete(String),
+    /// Line only in the new text.
+    Insert(String),
+}
+
+/// A contiguous group of changes with surrounding context.
+#[derive(Debug, Clone, Serialize)]
+pub struct Hunk {
+    /// First line of the hunk in the old text (1-based).
+    pub old_start: usize,
+    /// Number of old-text lines in the hunk.
+    pub old_lines: usize,
+    /// First line of the hunk in the new text (1-based).
+    pub new_start: usize,
+    /// Number of new-text lines in the hunk.
+    pub new_lines: usize,
+    /// Lines in the hunk, in order.
+    pub lines: Vec<DiffLine>,
+}
+
+/// Compute the line diff between two texts.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    edits(&old, &new)
+        .into_iter()
+        .map(|edit| match edit {
+            Edit::Equal(i, _) => DiffLine::Equal(old[i].to_string()),
+            Edit::Delete(i) => DiffLine::Delete(old[i].to_string()),
+            Edit::Insert(j) => DiffLine::Insert(new[j].to_string()),
+        })
+        .collect()
+}
+
+/// Group a diff into hunks with `context` unchanged lines around each change.
+pub fn hunks(lines: &[DiffLine], context: usize) -> Vec<Hunk> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    // Merge change ranges whose context windows touch
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            let (mut old_line, mut new_line) = (1, 1);
+            for line in &lines[..start] {
+                match line {
+                    DiffLine::Equal(_) => {
+                        old_line += 1;
+                        new_line += 1;
+                    },
+                    DiffLine::Delete(_) => old_line += 1,
+                    DiffLine::Insert(_) => new_line += 1,
+                }
+            }
+            let slice = &lines[start..end];
+            let old_lines = slice.iter().filter(|l| !matches!(l, DiffLine::Insert(_))).count();
+            let new_lines = slice.iter().filter(|l| !matches!(l, DiffLine::Delete(_))).count();
+            Hunk {
+                old_start: old_line,
+                old_lines,
+                new_start: new_line,
+                new_lines,
+                lines: slice.to_vec(),
+            }
+        })
+        .collect()
+}
+
+/// Render hunks as a unified diff.
+pub fn render_unified(hunks: &[Hunk], color: bool) -> String {
+    let mut out = String::new();
+    for hunk in hunks {
+        out.push_str(&paint(&hunk_header(hunk), CYAN, color));
+        out.push('\n');
+        for row in rows(&hunk.lines) {
+            match row {
+                Row::Equal(text) => {
+                    out.push(' ');
+                    out.push_str(text);
+                },
+                Row::Change(old, new) => {
+                    let (old_text, new_text) = highlight_pair(old, new, color);
+                    if let Some(text) = old_text {
+                        out.push_str(&paint("-", RED, color));
+                        out.push_str(&text);
+                        if new_text.is_some() {
+                            out.push('\n');
+                        }
+                    }
+                    if let Some(text) = new_text {
+                        out.push_str(&paint("+", GREEN, color));
+                        out.push_str(&text);
+                    }
+                },
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Render hunks side by side within `width` terminal columns.
+pub fn render_side_by_side(hunks: &[Hunk], width: usize, color: bool) -> String {
+    // Two columns separated by " │ ", each prefixed with a marker and space
+    let column = width.saturating_sub(3) / 2;
+    let text_width = column.saturating_sub(2).max(10);
+
+    let mut out = String::new();
+    for hunk in hunks {
+        out.push_str(&paint(&hunk_header(hunk), CYAN, color));
+        out.push('\n');
+        for row in rows(&hunk.lines) {
+            let (left, right) = match row {
+                Row::Equal(text) => {
+                    let text = truncate(text, text_width);
+                    (format!("  {text}"), format!("  {text}"))
+                },
+                Row::Change(old, new) => {
+                    let old = old.map(|t| truncate(t, text_width));
+                    let new = new.map(|t| truncate(t, text_width));
+                    let (old_text, new_text) =
+                        highlight_pair(old.as_deref(), new.as_deref(), color);
+                    (
+                        old_text
+                            .map(|t| format!("{}{t}", paint("- ", RED, color)))
+                            .unwrap_or_default(),
+                        new_text
+                            .map(|t| format!("{}{t}", paint("+ ", GREEN, color)))
+                            .unwrap_or_default(),
+                    )
+                },
+            };
+            let padding = column.saturating_sub(visible_width(&left));
+            out.push_str(&left);
+            out.push_str(&" ".repeat(padding));
+            out.push_str(&paint(&format!(" {} ", glyphs::VERTICAL), DIM, color));
+            out.push_str(&right);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+// ============================================================================
+// Implementation
+// ============================================================================
+
+/// An edit script entry, indexing into the old and new sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Compute a minimal edit script via longest common subsequence.
+fn edits<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    // Common prefix and suffix don't need the quadratic table
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (&old[prefix..old.len() - suffix], &new[prefix..new.len() - suffix]);
+
+    // lcs[i][j] = LCS length of a[i..] and b[j..]
+    let cols = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * cols];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * cols + j] = if a[i] == b[j] {
+                lcs[(i + 1) * cols + j + 1] + 1
+            } else {
+                lcs[(i + 1) * cols + j].max(lcs[i * cols + j + 1])
+            };
+        }
+    }
+
+    let mut script: Vec<Edit> = (0..prefix).map(|i| Edit::Equal(i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            script.push(Edit::Equal(prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[(i + 1) * cols + j] >= lcs[i * cols + j + 1])
+        {
+            script.push(Edit::Delete(prefix + i));
+            i += 1;
+        } else {
+            script.push(Edit::Insert(prefix + j));
+            j += 1;
+        }
+    }
+    let old_tail = old.len() - suffix;
+    let new_tail = new.len() - suffix;
+    script.extend((0..suffix).map(|k| Edit::Equal(old_tail + k, new_tail + k)));
+    script
+}
+
+/// A display row: unchanged text, or a removed/added pair (either may be absent).
+enum Row<'a> {
+    Equal(&'a str),
+    Change(Option<&'a str>, Option<&'a str>),
+}
+
+/// Pair each run of deletions with the insertions that follow it.
+fn rows(lines: &[DiffLine]) -> Vec<Row<'_>> {
+    let mut rows = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if let DiffLine::Equal(text) = &lines[i] {
+            rows.push(Row::Equal(text));
+            i += 1;
+            continue;
+        }
+        let mut deleted = Vec::new();
+        while let Some(DiffLine::Delete(text)) = lines.get(i) {
+            deleted.push(text.as_str());
+            i += 1;
+        }
+        let mut inserted = Vec::new();
+        while let Some(DiffLine::Insert(text)) = lines.get(i) {
+            inserted.push(text.as_str());
+            i += 1;
+        }
+        for k in 0..deleted.len().max(inserted.len()) {
+            rows.push(Row::Change(deleted.get(k).copied(), inserted.get(k).copied()));
+        }
+    }
+    rows
+}
+
+/// Style a removed/added pair, highlighting changed words when both exist.
+fn highlight_pair(
+    old: Option<&str>,
+    new: Option<&str>,
+    color: bool,
+) -> (Option<String>, Option<String>) {
+    match (old, new) {
+        (Some(old), Some(new)) if color => {
+            let old_tokens = tokenize(old);
+            let new_tokens = tokenize(new);
+            let mut old_out = String::new();
+            let mut new_out = String::new();
+            for edit in edits(&old_tokens, &new_tokens) {
+                match edit {
+                    Edit::Equal(i, j) => {
+                        old_out.push_str(&paint(old_tokens[i], RED, true));
+                        new_out.push_str(&paint(new_tokens[j], GREEN, true));
+                    },
+                    Edit::Delete(i) => old_out.push_str(&paint(old_tokens[i], RED_HIGHLIGHT, true)),
+                    Edit::Insert(j) => {
+                        new_out.push_str(&paint(new_tokens[j], GREEN_HIGHLIGHT, true));
+                    },
+                }
+            }
+            (Some(old_out), Some(new_out))
+        },
+        _ => (old.map(|t| paint(t, RED, color)), new.map(|t| paint(t, GREEN, color))),
+    }
+}
+
+/// Split a line into words, whitespace runs, and single punctuation characters.
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let class = char_class(c);
+        if class == 2 {
+            tokens.push(&line[i..i + c.len_utf8()]);
+            start = i + c.len_utf8();
+            continue;
+        }
+        if chars.peek().is_none_or(|&(_, next)| char_class(next) != class) {
+            let end = i + c.len_utf8();
+            tokens.push(&line[start..end]);
+            start = end;
+        }
+    }
+    tokens
+}
+
+/// Token class: 0 = word, 1 = whitespace, 2 = punctuation.
+fn char_class(c: char) -> u8 {
+    if c.is_alphanumeric() || c == '_' {
+        0
+    } else if c.is_whitespace() {
+        1
+    } else {
+        2
+    }
+}
+
+fn hunk_header(hunk: &Hunk) -> String {
+    format!("@@ -{},{} +{},{} @@", hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines)
+}
+
+/// Wrap `text` in an ANSI `style` when `color` is enabled.
+pub fn paint(text: &str, style: &str, color: bool) -> String {
+    if color { format!("{style}{text}{RESET}") } else { text.to_string() }
+}
+
+/// Truncate text to `width` display columns, marking the cut with `…`.
+fn truncate(text: &str, width: usize) -> String {
+    if visible_width(text) <= width {
+        return text.to_string();
+    }
+    let ellipsis = glyphs::ELLIPSIS.as_str();
+    let reserved = visible_width(ellipsis);
+    let mut used = 0;
+    let mut out = String::new();
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + reserved > width {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out.push_str(ellipsis);
+    out
+}
+
+/// Display width of text, ignoring ANSI escape sequences.
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in text.chars() {
+        if in_escape {
+            in_escape = c != 'm';
+        } else if c == '\x1b' {
+            in_escape = true;
+        } else {
+            width += c.width().unwrap_or(0);
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_style_parse() {
+        assert_eq!(DiffStyle::parse("unified").unwrap(), DiffStyle::Unified);
+        assert_eq!(DiffStyle::parse("side-by-side").unwrap(), DiffStyle::SideBySide);
+        assert!(DiffStyle::parse("context").is_err());
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let lines = diff_lines("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(
+            lines,
+            vec![
+                DiffLine::Equal("a".into()),
+                DiffLine::Delete("b".into()),
+                DiffLine::Insert("B".into()),
+                DiffLine::Equal("c".into()),
+                DiffLine::Insert("d".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hunks_split_on_distant_changes() {
+        let old = (0..20).map(|i| format!("line {i}")).collect::<Vec<_>>().join("\n") + "\n";
+        let new = old.replace("line 2\n", "line two\n").replace("line 17\n", "line seventeen\n");
+        let hunks = hunks(&diff_lines(&old, &new), 2);
+
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_start, hunks[0].old_lines), (1, 5));
+        assert_eq!((hunks[1].new_start, hunks[1].new_lines), (16, 5));
+    }
+
+    #[test]
+    fn test_render_unified_plain() {
+        let hunks = hunks(&diff_lines("a\nb\n", "a\nc\n"), 3);
+        assert_eq!(render_unified(&hunks, false), "@@ -1,2 +1,2 @@\n a\n-b\n+c\n");
+    }
+
+    #[test]
+    fn test_render_side_by_side_plain() {
+        let hunks = hunks(&diff_lines("a\n", "b\n"), 0);
+        let rendered = render_side_by_side(&hunks, 23, false);
+        assert_eq!(rendered, "@@ -1,1 +1,1 @@\n- a        │ + b\n");
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("define viewer: user"),
+            vec!["define", " ", "viewer", ":", " ", "user"]
+        );
+    }
+
+    #[test]
+    fn test_intra_line_highlight() {
+        let (old, new) = highlight_pair(Some("relation a: user"), Some("relation b: user"), true);
+        assert!(old.unwrap().contains(&format!("{RED_HIGHLIGHT}a{RESET}")));
+        assert!(new.unwrap().contains(&format!("{GREEN_HIGHLIGHT}b{RESET}")));
+    }
+}
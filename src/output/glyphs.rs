@@ -0,0 +1,163 @@
+//! Status glyphs and box-drawing characters with ASCII fallbacks.
+//!
+//! `--ascii` (or `output.unicode: false` in the config file) switches every
+//! glyph to a plain ASCII equivalent, for terminals and log pipelines that
+//! mangle Unicode.
+
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable ASCII-only output for the rest of the process.
+pub fn set_ascii(enabled: bool) {
+    ASCII.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if ASCII-only output is enabled.
+#[must_use]
+pub fn is_ascii() -> bool {
+    ASCII.load(Ordering::Relaxed)
+}
+
+/// A glyph with an ASCII fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph {
+    unicode: &'static str,
+    ascii: &'static str,
+}
+
+impl Glyph {
+    const fn new(unicode: &'static str, ascii: &'static str) -> Self {
+        Self { unicode, ascii }
+    }
+
+    /// The glyph for the current output mode.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        if is_ascii() { self.ascii } else { self.unicode }
+    }
+
+    /// The glyph repeated `count` times, e.g. for horizontal rules.
+    #[must_use]
+    pub fn repeat(self, count: usize) -> String {
+        self.as_str().repeat(count)
+    }
+}
+
+impl fmt::Display for Glyph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Success checkmark.
+pub const CHECK: Glyph = Glyph::new("✓", "+");
+/// Failure cross.
+pub const CROSS: Glyph = Glyph::new("✗", "x");
+/// Warning sign.
+pub const WARNING: Glyph = Glyph::new("⚠", "!");
+/// Hollow bullet for informational lines.
+pub const BULLET: Glyph = Glyph::new("○", "-");
+/// Right arrow.
+pub const ARROW: Glyph = Glyph::new("→", "->");
+/// Tree branch for nested items.
+pub const BRANCH: Glyph = Glyph::new("└─", "+-");
+/// Vertical column separator.
+pub const VERTICAL: Glyph = Glyph::new("│", "|");
+/// Truncation marker.
+pub const ELLIPSIS: Glyph = Glyph::new("…", "...");
+/// Heavy horizontal rule segment.
+pub const RULE: Glyph = Glyph::new("━", "=");
+
+/// ASCII replacements for glyphs that appear in CLI and Teapot output.
+const FALLBACKS: &[(char, &str)] = &[
+    ('✓', "+"),
+    ('✔', "+"),
+    ('✗', "x"),
+    ('✘', "x"),
+    ('⚠', "!"),
+    ('○', "-"),
+    ('●', "*"),
+    ('•', "*"),
+    ('→', "->"),
+    ('←', "<-"),
+    ('↑', "^"),
+    ('↓', "v"),
+    ('…', "..."),
+    ('─', "-"),
+    ('━', "="),
+    ('═', "="),
+    ('│', "|"),
+    ('┃', "|"),
+    ('║', "|"),
+    ('┌', "+"),
+    ('┐', "+"),
+    ('└', "+"),
+    ('┘', "+"),
+    ('├', "+"),
+    ('┤', "+"),
+    ('┬', "+"),
+    ('┴', "+"),
+    ('┼', "+"),
+    ('╭', "+"),
+    ('╮', "+"),
+    ('╰', "+"),
+    ('╯', "+"),
+    ('█', "#"),
+    ('▓', "#"),
+    ('░', "."),
+];
+
+/// Replace known glyphs with their ASCII equivalents.
+///
+/// Other non-ASCII characters (names, descriptions) are left untouched.
+#[must_use]
+pub fn to_ascii(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match FALLBACKS.iter().find(|(glyph, _)| *glyph == c) {
+            Some((_, ascii)) => out.push_str(ascii),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// Text for the current output mode: unchanged, or with glyphs replaced by
+/// [`to_ascii`] when ASCII-only output is enabled.
+#[must_use]
+pub fn text(text: &str) -> Cow<'_, str> {
+    if is_ascii() && !text.is_ascii() { Cow::Owned(to_ascii(text)) } else { Cow::Borrowed(text) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ascii_replaces_glyphs() {
+        assert_eq!(to_ascii("✓ Schema valid"), "+ Schema valid");
+        assert_eq!(to_ascii("alice → allowed"), "alice -> allowed");
+        assert_eq!(to_ascii("┌──┐\n│ab│\n└──┘"), "+--+\n|ab|\n+--+");
+        assert_eq!(to_ascii("truncat…"), "truncat...");
+    }
+
+    #[test]
+    fn test_to_ascii_keeps_other_text() {
+        assert_eq!(to_ascii("José's vault"), "José's vault");
+        assert_eq!(to_ascii("\x1b[32m✓\x1b[0m ok"), "\x1b[32m+\x1b[0m ok");
+    }
+
+    #[test]
+    fn test_fallbacks_are_ascii() {
+        assert!(FALLBACKS.iter().all(|(_, ascii)| ascii.is_ascii()));
+        for glyph in [CHECK, CROSS, WARNING, BULLET, ARROW, BRANCH, VERTICAL, ELLIPSIS, RULE] {
+            assert!(glyph.ascii.is_ascii());
+            assert_eq!(to_ascii(glyph.unicode), glyph.ascii);
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Prometheus metrics for long-running commands.
+//!
+//! `health --watch` and `stream` can run for hours as sidecars. When
+//! `--metrics-listen <addr>` is passed, they record results here and a small
+//! HTTP server exposes them at `/metrics` in the Prometheus text format.
+//!
+//! Recording is process-wide and a no-op until [`serve`] starts the endpoint,
+//! so commands can record unconditionally.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener},
+    sync::Mutex,
+    thread,
+};
+
+use crate::error::{Error, Result};
+
+/// Health probe results, labelled by `status`.
+pub const HEALTH_CHECKS_TOTAL: &str = "inferadb_cli_health_checks_total";
+/// Health probe latency.
+pub const HEALTH_LATENCY_SECONDS: &str = "inferadb_cli_health_latency_seconds";
+/// Whether the last health probe succeeded.
+pub const HEALTH_UP: &str = "inferadb_cli_health_up";
+/// Watch events received, labelled by `operation`.
+pub const STREAM_EVENTS_TOTAL: &str = "inferadb_cli_stream_events_total";
+/// Watch stream errors.
+pub const STREAM_ERRORS_TOTAL: &str = "inferadb_cli_stream_errors_total";
+/// Delay between a change and its arrival on the watch stream.
+pub const STREAM_LAG_SECONDS: &str = "inferadb_cli_stream_lag_seconds";
+/// Last server revision seen on the watch stream.
+pub const STREAM_REVISION: &str = "inferadb_cli_stream_revision";
+
+/// Histogram bucket upper bounds, in seconds.
+const BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Metric type, as named in `# TYPE` lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Monotonic counter.
+    Counter,
+    /// Point-in-time value.
+    Gauge,
+    /// Distribution over latency buckets from 5ms to 10s.
+    Histogram,
+}
+
+impl Kind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        }
+    }
+}
+
+/// A metric the CLI can emit.
+#[derive(Debug, Clone, Copy)]
+pub struct Descriptor {
+    /// Metric name.
+    pub name: &'static str,
+    /// Metric type.
+    pub kind: Kind,
+    /// Help text.
+    pub help: &'static str,
+    /// Label names, if any.
+    pub labels: &'static [&'static str],
+}
+
+/// Every metric the CLI emits, in exposition order.
+pub const DESCRIPTORS: &[Descriptor] = &[
+    Descriptor {
+        name: HEALTH_CHECKS_TOTAL,
+        kind: Kind::Counter,
+        help: "Health probes by result.",
+        labels: &["status"],
+    },
+    Descriptor {
+        name: HEALTH_LATENCY_SECONDS,
+        kind: Kind::Histogram,
+        help: "Health probe latency in seconds.",
+        labels: &[],
+    },
+    Descriptor {
+        name: HEALTH_UP,
+        kind: Kind::Gauge,
+        help: "Whether the last health probe succeeded (1) or not (0).",
+        labels: &[],
+    },
+    Descriptor {
+        name: STREAM_EVENTS_TOTAL,
+        kind: Kind::Counter,
+        help: "Relationship changes received on the watch stream.",
+        labels: &["operation"],
+    },
+    Descriptor {
+        name: STREAM_ERRORS_TOTAL,
+        kind: Kind::Counter,
+        help: "Errors received on the watch stream.",
+        labels: &[],
+    },
+    Descriptor {
+        name: STREAM_LAG_SECONDS,
+        kind: Kind::Gauge,
+        help: "Seconds between the last change and its arrival on the watch stream.",
+        labels: &[],
+    },
+    Descriptor {
+        name: STREAM_REVISION,
+        kind: Kind::Gauge,
+        help: "Last server revision seen on the watch stream.",
+        labels: &[],
+    },
+];
+
+/// Active registry, once the endpoint is serving.
+static REGISTRY: Mutex<Option<Registry>> = Mutex::new(None);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Scalar(f64),
+    Histogram { buckets: Vec<u64>, sum: f64, count: u64 },
+}
+
+/// Recorded series keyed by metric name and rendered label set.
+#[derive(Debug, Default)]
+struct Registry {
+    series: BTreeMap<(&'static str, String), Value>,
+}
+
+impl Registry {
+    fn update(&mut self, name: &'static str, labels: &[(&str, &str)], f: impl FnOnce(&mut Value)) {
+        let kind = DESCRIPTORS.iter().find(|d| d.name == name).map_or(Kind::Gauge, |d| d.kind);
+        let value =
+            self.series.entry((name, format_labels(labels))).or_insert_with(|| match kind {
+                Kind::Histogram => {
+                    Value::Histogram { buckets: vec![0; BUCKETS.len()], sum: 0.0, count: 0 }
+                },
+                Kind::Counter | Kind::Gauge => Value::Scalar(0.0),
+            });
+        f(value);
+    }
+
+    fn inc(&mut self, name: &'static str, labels: &[(&str, &str)]) {
+        self.update(name, labels, |v| {
+            if let Value::Scalar(n) = v {
+                *n += 1.0;
+            }
+        });
+    }
+
+    fn set(&mut self, name: &'static str, labels: &[(&str, &str)], value: f64) {
+        self.update(name, labels, |v| *v = Value::Scalar(value));
+    }
+
+    fn observe(&mut self, name: &'static str, labels: &[(&str, &str)], seconds: f64) {
+        self.update(name, labels, |v| {
+            if let Value::Histogram { buckets, sum, count } = v {
+                if let Some(i) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+                    buckets[i] += 1;
+                }
+                *sum += seconds;
+                *count += 1;
+            }
+        });
+    }
+
+    /// Render in the Prometheus text exposition format.
+    fn render(&self) -> String {
+        let mut out = String::new();
+        for descriptor in DESCRIPTORS {
+            let series: Vec<_> =
+                self.series.iter().filter(|((name, _), _)| *name == descriptor.name).collect();
+            if series.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "# HELP {} {}", descriptor.name, descriptor.help);
+            let _ = writeln!(out, "# TYPE {} {}", descriptor.name, descriptor.kind.as_str());
+            for ((name, labels), value) in series {
+                match value {
+                    Value::Scalar(v) => {
+                        let _ = writeln!(out, "{name}{} {v}", braced(labels));
+                    },
+                    Value::Histogram { buckets, sum, count } => {
+                        let mut cumulative = 0;
+                        for (bound, n) in BUCKETS.iter().zip(buckets) {
+                            cumulative += n;
+                            let le = join_labels(labels, &format!("le=\"{bound}\""));
+                            let _ = writeln!(out, "{name}_bucket{{{le}}} {cumulative}");
+                        }
+                        let le = join_labels(labels, "le=\"+Inf\"");
+                        let _ = writeln!(out, "{name}_bucket{{{le}}} {count}");
+                        let _ = writeln!(out, "{name}_sum{} {sum}", braced(labels));
+                        let _ = writeln!(out, "{name}_count{} {count}", braced(labels));
+                    },
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Render `k="v"` pairs, escaping values per the exposition format.
+fn format_labels(labels: &[(&str, &str)]) -> String {
+    labels
+        .iter()
+        .map(|(k, v)| {
+            let v = v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            format!("{k}=\"{v}\"")
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn braced(labels: &str) -> String {
+    if labels.is_empty() { String::new() } else { format!("{{{labels}}}") }
+}
+
+fn join_labels(labels: &str, extra: &str) -> String {
+    if labels.is_empty() { extra.to_string() } else { format!("{labels},{extra}") }
+}
+
+fn with_registry(f: impl FnOnce(&mut Registry)) {
+    if let Ok(mut registry) = REGISTRY.lock()
+        && let Some(registry) = registry.as_mut()
+    {
+        f(registry);
+    }
+}
+
+/// Increment a counter. Does nothing unless the endpoint is serving.
+pub fn inc(name: &'static str, labels: &[(&str, &str)]) {
+    with_registry(|r| r.inc(name, labels));
+}
+
+/// Set a gauge. Does nothing unless the endpoint is serving.
+pub fn set(name: &'static str, labels: &[(&str, &str)], value: f64) {
+    with_registry(|r| r.set(name, labels, value));
+}
+
+/// Record a histogram observation in seconds. Does nothing unless the
+/// endpoint is serving.
+pub fn observe(name: &'static str, labels: &[(&str, &str)], seconds: f64) {
+    with_registry(|r| r.observe(name, labels, seconds));
+}
+
+/// Render the current metrics, if the endpoint is serving.
+pub fn render() -> Option<String> {
+    REGISTRY.lock().ok()?.as_ref().map(Registry::render)
+}
+
+/// Start recording and serve `/metrics` on `addr` (e.g., `127.0.0.1:9464`).
+///
+/// Returns the bound address; the server runs on a background thread for the
+/// rest of the process.
+pub fn serve(addr: &str) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)
+        .map_err(|e| Error::other(format!("Failed to start metrics server on {addr}: {e}")))?;
+    let local = listener.local_addr()?;
+
+    if let Ok(mut registry) = REGISTRY.lock() {
+        registry.get_or_insert_with(Registry::default);
+    }
+
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let request_line =
+                BufReader::new(&stream).lines().next().and_then(std::result::Result::ok);
+            let path = request_line
+                .as_deref()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .unwrap_or("/");
+
+            let response = match (path, render()) {
+                ("/metrics", Some(body)) => format!(
+                    "HTTP/1.1 200 OK\r\n\
+                    Content-Type: text/plain; version=0.0.4\r\n\
+                    Content-Length: {}\r\n\
+                    Connection: close\r\n\r\n{body}",
+                    body.len()
+                ),
+                _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    .to_string(),
+            };
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+
+    Ok(local)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_gauges() {
+        let mut registry = Registry::default();
+        registry.inc(HEALTH_CHECKS_TOTAL, &[("status", "healthy")]);
+        registry.inc(HEALTH_CHECKS_TOTAL, &[("status", "healthy")]);
+        registry.set(HEALTH_UP, &[], 1.0);
+
+        let text = registry.render();
+        assert!(text.contains("# TYPE inferadb_cli_health_checks_total counter\n"));
+        assert!(text.contains("inferadb_cli_health_checks_total{status=\"healthy\"} 2\n"));
+        assert!(text.contains("inferadb_cli_health_up 1\n"));
+        assert!(!text.contains(STREAM_EVENTS_TOTAL));
+    }
+
+    #[test]
+    fn test_render_histogram() {
+        let mut registry = Registry::default();
+        for seconds in [0.004, 0.2, 30.0] {
+            registry.observe(HEALTH_LATENCY_SECONDS, &[], seconds);
+        }
+
+        let text = registry.render();
+        assert!(text.contains("inferadb_cli_health_latency_seconds_bucket{le=\"0.005\"} 1\n"));
+        assert!(text.contains("inferadb_cli_health_latency_seconds_bucket{le=\"0.25\"} 2\n"));
+        assert!(text.contains("inferadb_cli_health_latency_seconds_bucket{le=\"10\"} 2\n"));
+        assert!(text.contains("inferadb_cli_health_latency_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("inferadb_cli_health_latency_seconds_count 3\n"));
+    }
+
+    #[test]
+    fn test_format_labels_escapes_values() {
+        assert_eq!(format_labels(&[("a", "x\"y"), ("b", "1\\2")]), r#"a="x\"y",b="1\\2""#);
+    }
+}
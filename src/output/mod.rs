@@ -1,18 +1,52 @@
 //! Output formatting for the CLI.
 //!
-//! Provides format selection (table/json/yaml/jsonl) and integrates with Teapot
+//! Provides format selection (table/json/yaml/jsonl/csv/template) and integrates with Teapot
 //! for table rendering. For message output (success, error, warning, info),
 //! use `teapot::output` directly.
 
+pub mod breakdown;
+pub mod cast;
+pub mod dates;
+pub mod desktop;
+pub mod diff;
+pub mod glyphs;
+pub mod metrics;
+pub mod parquet;
+pub mod query;
+pub mod report;
+pub mod stream;
+pub mod template;
+pub mod theme;
+pub mod timezone;
+pub mod timings;
+pub mod warnings;
+pub mod webhook;
+
 use std::io::IsTerminal;
 
+pub use query::Query;
+pub use report::ReportTarget;
 use serde::Serialize;
+pub use stream::RowStream;
 use teapot::{
     components::{Column, Table},
     output as toutput,
 };
+pub use template::Template;
+pub use timings::{Timings, TimingsFormat};
 
-use crate::error::Result;
+use crate::{
+    error::Result,
+    tui::{Status, print_status},
+};
+
+/// `value` with pending deprecation notices and warnings added, if any.
+fn annotate<T: Serialize + ?Sized>(value: &T) -> Option<serde_json::Value> {
+    match crate::deprecations::annotate(value) {
+        Some(annotated) => Some(warnings::annotate(&annotated).unwrap_or(annotated)),
+        None => warnings::annotate(value),
+    }
+}
 
 /// Output format options.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -26,6 +60,10 @@ pub enum OutputFormat {
     Yaml,
     /// JSON Lines format (one object per line).
     JsonLines,
+    /// CSV with a header row, using the table columns.
+    Csv,
+    /// A user-supplied template (see [`template`]).
+    Template,
 }
 
 impl OutputFormat {
@@ -36,8 +74,10 @@ impl OutputFormat {
             "json" => Ok(Self::Json),
             "yaml" => Ok(Self::Yaml),
             "jsonl" | "jsonlines" => Ok(Self::JsonLines),
+            "csv" => Ok(Self::Csv),
             _ => Err(crate::error::Error::invalid_arg(format!(
-                "Unknown output format '{s}'. Use: table, json, yaml, jsonl"
+                "Unknown output format '{s}'. Use: table, json, yaml, jsonl, csv, \
+                 template=<template>, template-file=<path>"
             ))),
         }
     }
@@ -50,6 +90,11 @@ pub trait Displayable {
 
     /// Get column headers for table display.
     fn table_headers() -> Vec<&'static str>;
+
+    /// Value printed on its own line in quiet mode (the first column by default).
+    fn primary_value(&self) -> String {
+        self.table_row().into_iter().next().unwrap_or_default()
+    }
 }
 
 /// Output writer that handles format selection.
@@ -62,44 +107,81 @@ pub struct Output {
     pub color: bool,
     /// Whether quiet mode is enabled.
     pub quiet: bool,
+    /// Whether messages are spelled out in words instead of glyphs.
+    pub accessible: bool,
+    /// Template for [`OutputFormat::Template`].
+    pub template: Option<Template>,
+    /// `--query` expression applied to structured output before formatting.
+    pub query: Option<Query>,
 }
 
 impl Output {
     /// Create a new output writer.
     #[must_use]
     pub const fn new(format: OutputFormat, color: bool, quiet: bool) -> Self {
-        Self { format, color, quiet }
+        Self { format, color, quiet, accessible: false, template: None, query: None }
     }
 
     /// Create an output writer from CLI options.
-    pub fn from_cli(format: &str, color: &str, quiet: bool) -> Result<Self> {
-        let format = OutputFormat::parse(format)?;
-
-        let color = match color {
-            "always" => true,
-            "never" => false,
-            _ => std::io::stdout().is_terminal(),
+    ///
+    /// Accessible mode disables color so text renders in the terminal's own
+    /// high-contrast foreground. `template=<template>` and
+    /// `template-file=<path>` select template output.
+    pub fn from_cli(format: &str, color: &str, quiet: bool, accessible: bool) -> Result<Self> {
+        let (format, template) = match format.split_once('=') {
+            Some(("template", source)) => (OutputFormat::Template, Some(Template::parse(source)?)),
+            Some(("template-file", path)) => {
+                let source = crate::input::read_to_string(path).map_err(|e| {
+                    crate::error::Error::invalid_arg(format!("Could not read {path}: {e}"))
+                })?;
+                (OutputFormat::Template, Some(Template::parse(&source)?))
+            },
+            _ => (OutputFormat::parse(format)?, None),
         };
 
-        Ok(Self::new(format, color, quiet))
+        let color = !accessible
+            && match color {
+                "always" => true,
+                "never" => false,
+                _ => std::io::stdout().is_terminal(),
+            };
+
+        Ok(Self { accessible, template, ..Self::new(format, color, quiet) })
     }
 
     /// Output a single serializable value.
     pub fn value<T: Serialize>(&self, value: &T) -> Result<()> {
+        let _timer = breakdown::start(breakdown::Phase::Render, "value");
+        if let Some(query) = &self.query {
+            return self.queried(&query.apply(&serde_json::to_value(value)?)?);
+        }
         match self.format {
             OutputFormat::Json => self.json(value),
             OutputFormat::Yaml => self.yaml(value),
             OutputFormat::JsonLines => self.jsonl(value),
-            OutputFormat::Table => {
-                // For single values in table mode, fall back to JSON
+            OutputFormat::Template => self.template(value),
+            OutputFormat::Table | OutputFormat::Csv => {
+                // For single values in table and CSV modes, fall back to JSON
                 self.json(value)
             },
         }
     }
 
     /// Output a list of items as a table.
+    ///
+    /// In quiet mode, table format prints only each item's primary value.
     pub fn table<T: Displayable + Serialize>(&self, items: &[T]) -> Result<()> {
+        let _timer = breakdown::start(breakdown::Phase::Render, "table");
+        if let Some(query) = &self.query {
+            return self.queried(&query.apply(&serde_json::to_value(items)?)?);
+        }
         match self.format {
+            OutputFormat::Table if self.quiet => {
+                for item in items {
+                    println!("{}", item.primary_value());
+                }
+                Ok(())
+            },
             OutputFormat::Table => {
                 let columns: Vec<Column> =
                     T::table_headers().into_iter().map(Column::new).collect();
@@ -109,12 +191,7 @@ impl Output {
                 let table =
                     Table::new().columns(columns).rows(rows).show_borders(false).focused(false);
 
-                let output = table.render();
-                if self.color {
-                    println!("{output}");
-                } else {
-                    println!("{}", toutput::strip_ansi(&output));
-                }
+                self.print_rendered(&table.render());
                 Ok(())
             },
             OutputFormat::Json => {
@@ -133,12 +210,57 @@ impl Output {
                 }
                 Ok(())
             },
+            OutputFormat::Csv => {
+                print!("{}", to_csv::<T>(items));
+                Ok(())
+            },
+            OutputFormat::Template => {
+                for item in items {
+                    self.template(item)?;
+                }
+                Ok(())
+            },
         }
     }
 
+    /// Start a list written page by page as results arrive (see [`stream`]).
+    #[must_use]
+    pub const fn stream<T: Displayable + Serialize>(&self) -> RowStream<'_, T> {
+        RowStream::new(self)
+    }
+
+    /// Output rows under column headers only known at runtime.
+    ///
+    /// Table format only; other formats should emit a structured value.
+    /// In quiet mode, prints only the first column.
+    pub fn grid(&self, headers: &[String], rows: Vec<Vec<String>>) {
+        let _timer = breakdown::start(breakdown::Phase::Render, "grid");
+        if self.quiet {
+            for row in rows {
+                println!("{}", row.into_iter().next().unwrap_or_default());
+            }
+            return;
+        }
+
+        let columns: Vec<Column> = headers.iter().map(Column::new).collect();
+        let table = Table::new().columns(columns).rows(rows).show_borders(false).focused(false);
+
+        self.print_rendered(&table.render());
+    }
+
     /// Output a single item with table format.
+    ///
+    /// In quiet mode, table format prints only the item's primary value.
     pub fn item<T: Displayable + Serialize + Clone>(&self, item: &T) -> Result<()> {
+        let _timer = breakdown::start(breakdown::Phase::Render, "item");
+        if let Some(query) = &self.query {
+            return self.queried(&query.apply(&serde_json::to_value(item)?)?);
+        }
         match self.format {
+            OutputFormat::Table if self.quiet => {
+                println!("{}", item.primary_value());
+                Ok(())
+            },
             OutputFormat::Table => {
                 let columns: Vec<Column> =
                     T::table_headers().into_iter().map(Column::new).collect();
@@ -149,30 +271,85 @@ impl Output {
                     .show_borders(false)
                     .focused(false);
 
-                let output = table.render();
-                if self.color {
-                    println!("{output}");
-                } else {
-                    println!("{}", toutput::strip_ansi(&output));
-                }
+                self.print_rendered(&table.render());
                 Ok(())
             },
             OutputFormat::Json => self.json(item),
             OutputFormat::Yaml => self.yaml(item),
             OutputFormat::JsonLines => self.jsonl(item),
+            OutputFormat::Template => self.template(item),
+            OutputFormat::Csv => {
+                print!("{}", to_csv(std::slice::from_ref(item)));
+                Ok(())
+            },
+        }
+    }
+
+    /// Output the result of `--query`. Arrays are treated as lists for JSON
+    /// Lines and templates; table and CSV modes print strings and numbers
+    /// raw, one per line, and anything else as JSON.
+    fn queried(&self, result: &serde_json::Value) -> Result<()> {
+        use serde_json::Value;
+
+        match (self.format, result) {
+            (OutputFormat::Json, _) => self.json(result),
+            (OutputFormat::Yaml, _) => self.yaml(result),
+            (OutputFormat::JsonLines, Value::Array(items)) => {
+                items.iter().try_for_each(|item| self.jsonl(item))
+            },
+            (OutputFormat::JsonLines, _) => self.jsonl(result),
+            (OutputFormat::Template, Value::Array(items)) => {
+                items.iter().try_for_each(|item| self.template(item))
+            },
+            (OutputFormat::Template, _) => self.template(result),
+            (OutputFormat::Table | OutputFormat::Csv, _) => {
+                let scalars = match result {
+                    Value::Array(items)
+                        if items.iter().all(|i| !i.is_array() && !i.is_object()) =>
+                    {
+                        items.as_slice()
+                    },
+                    Value::Array(_) | Value::Object(_) => return self.json(result),
+                    other => std::slice::from_ref(other),
+                };
+                for value in scalars {
+                    match value {
+                        Value::String(s) => println!("{s}"),
+                        Value::Null => {},
+                        other => println!("{other}"),
+                    }
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Print a rendered table, stripping color and glyphs as configured.
+    fn print_rendered(&self, output: &str) {
+        let output = glyphs::text(output);
+        if self.color {
+            println!("{output}");
+        } else {
+            println!("{}", toutput::strip_ansi(&output));
         }
     }
 
-    /// Output raw JSON.
+    /// Output raw JSON, with any pending deprecation notices and warnings.
     fn json<T: Serialize + ?Sized>(&self, value: &T) -> Result<()> {
-        let json = serde_json::to_string_pretty(value)?;
+        let json = match annotate(value) {
+            Some(annotated) => serde_json::to_string_pretty(&annotated)?,
+            None => serde_json::to_string_pretty(value)?,
+        };
         println!("{json}");
         Ok(())
     }
 
-    /// Output YAML.
+    /// Output YAML, with any pending deprecation notices and warnings.
     fn yaml<T: Serialize + ?Sized>(&self, value: &T) -> Result<()> {
-        let yaml = serde_yaml::to_string(value)?;
+        let yaml = match annotate(value) {
+            Some(annotated) => serde_yaml::to_string(&annotated)?,
+            None => serde_yaml::to_string(value)?,
+        };
         print!("{yaml}");
         Ok(())
     }
@@ -184,54 +361,117 @@ impl Output {
         Ok(())
     }
 
+    /// Output a value through the template, ending with a newline.
+    fn template<T: Serialize + ?Sized>(&self, value: &T) -> Result<()> {
+        let Some(template) = &self.template else {
+            return self.json(value);
+        };
+        let rendered = template.render(&serde_json::to_value(value)?);
+        if rendered.ends_with('\n') {
+            print!("{rendered}");
+        } else {
+            println!("{rendered}");
+        }
+        Ok(())
+    }
+
     // -------------------------------------------------------------------------
-    // Message output methods - thin wrappers around teapot::output
-    // These respect the quiet flag before calling Teapot.
+    // Message output methods - status lines in the active theme
+    // These respect the quiet flag.
     // -------------------------------------------------------------------------
 
     /// Print an info message (respects quiet mode).
-    /// Wraps `teapot::output::info`.
     pub fn info(&self, message: &str) {
         if !self.quiet {
-            if self.color {
-                toutput::info(message);
+            if self.accessible {
+                print_status(Status::Info, message);
             } else {
-                eprintln!("- {message}");
+                self.themed(theme::Role::Info, message);
             }
         }
     }
 
     /// Print a success message (respects quiet mode).
-    /// Wraps `teapot::output::success`.
     pub fn success(&self, message: &str) {
         if !self.quiet {
-            if self.color {
-                toutput::success(message);
+            if self.accessible {
+                print_status(Status::Success, message);
             } else {
-                eprintln!("+ {message}");
+                self.themed(theme::Role::Success, message);
             }
         }
     }
 
     /// Print a warning message (respects quiet mode).
-    /// Wraps `teapot::output::warning`.
     pub fn warn(&self, message: &str) {
         if !self.quiet {
-            if self.color {
-                toutput::warning(message);
+            if self.accessible {
+                print_status(Status::Warning, message);
             } else {
-                eprintln!("! {message}");
+                self.themed(theme::Role::Warning, message);
             }
         }
     }
 
+    /// Print a dim one-line warning to stderr, for advisories that should not
+    /// compete with command output.
+    pub fn notice(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
+        if self.accessible {
+            print_status(Status::Warning, message);
+        } else if self.color {
+            eprintln!("\x1b[2m{} {}\x1b[0m", glyphs::WARNING, glyphs::text(message));
+        } else {
+            eprintln!("{} {}", glyphs::WARNING, glyphs::text(message));
+        }
+    }
+
+    /// Report an advisory warning with a stable code.
+    ///
+    /// Suppressed codes are dropped. With JSON or YAML output the warning is
+    /// added to the result's `warnings` array; otherwise it is printed like
+    /// [`Self::notice`], followed by its code.
+    pub fn warning(&self, code: &str, message: &str) {
+        if warnings::is_suppressed(code) {
+            return;
+        }
+        match self.format {
+            OutputFormat::Json | OutputFormat::Yaml => warnings::defer(code, message),
+            _ => self.notice(&format!("{message} [{code}]")),
+        }
+    }
+
     /// Print an error message (always shown, even in quiet mode).
-    /// Wraps `teapot::output::error`.
     pub fn error(&self, message: &str) {
+        if self.accessible {
+            print_status(Status::Error, message);
+        } else {
+            self.themed(theme::Role::Error, message);
+        }
+    }
+
+    /// Print `message` to stderr after the theme's symbol for `role`.
+    fn themed(&self, role: theme::Role, message: &str) {
+        let theme = theme::current();
         if self.color {
-            toutput::error(message);
+            eprintln!(
+                "{}{}\x1b[0m {}",
+                theme.ansi(role),
+                theme.symbol(role),
+                glyphs::text(message)
+            );
         } else {
-            eprintln!("x {message}");
+            eprintln!("{} {}", theme.symbol(role), glyphs::text(message));
+        }
+    }
+
+    /// Print a command's primary value (a created ID, a decision) to stdout in
+    /// quiet mode, where it replaces the success and info messages.
+    pub fn primary(&self, value: &str) {
+        if self.quiet {
+            println!("{value}");
         }
     }
 
@@ -252,6 +492,33 @@ impl Output {
     }
 }
 
+/// Render items as CSV: a header row, then one record per item.
+fn to_csv<T: Displayable>(items: &[T]) -> String {
+    let mut out = csv_record(T::table_headers());
+    for item in items {
+        out.push_str(&csv_record(item.table_row()));
+    }
+    out
+}
+
+/// A CSV record, quoting fields that contain commas, quotes, or newlines.
+fn csv_record<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> String {
+    let mut record = fields
+        .into_iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    record.push('\n');
+    record
+}
+
 impl Default for Output {
     fn default() -> Self {
         Self::new(OutputFormat::Table, true, false)
@@ -269,6 +536,50 @@ mod tests {
         assert_eq!(OutputFormat::parse("json").unwrap(), OutputFormat::Json);
         assert_eq!(OutputFormat::parse("yaml").unwrap(), OutputFormat::Yaml);
         assert_eq!(OutputFormat::parse("jsonl").unwrap(), OutputFormat::JsonLines);
+        assert_eq!(OutputFormat::parse("csv").unwrap(), OutputFormat::Csv);
         assert!(OutputFormat::parse("invalid").is_err());
     }
+
+    #[test]
+    fn test_from_cli_template() {
+        let output = Output::from_cli("template={{.id}}", "never", false, false).unwrap();
+        assert_eq!(output.format, OutputFormat::Template);
+        assert_eq!(output.template.unwrap().render(&serde_json::json!({ "id": "7" })), "7");
+        assert!(Output::from_cli("template={{.id", "never", false, false).is_err());
+        assert!(Output::from_cli("template-file=/nonexistent", "never", false, false).is_err());
+    }
+
+    #[test]
+    fn test_csv_record_quotes_fields() {
+        assert_eq!(csv_record(["a", "b c"]), "a,b c\n");
+        assert_eq!(csv_record(["Smith, Jo", "say \"hi\""]), "\"Smith, Jo\",\"say \"\"hi\"\"\"\n");
+    }
+
+    #[test]
+    fn test_primary_value_defaults_to_first_column() {
+        struct Row;
+
+        impl Displayable for Row {
+            fn table_row(&self) -> Vec<String> {
+                vec!["org_123".to_string(), "Acme".to_string()]
+            }
+
+            fn table_headers() -> Vec<&'static str> {
+                vec!["ID", "NAME"]
+            }
+        }
+
+        assert_eq!(Row.primary_value(), "org_123");
+    }
+
+    #[test]
+    fn test_accessible_output_disables_color() {
+        let output = Output::from_cli("table", "always", false, true).unwrap();
+        assert!(output.accessible);
+        assert!(!output.color);
+
+        let output = Output::from_cli("table", "always", false, false).unwrap();
+        assert!(!output.accessible);
+        assert!(output.color);
+    }
 }
@@ -0,0 +1,492 @@
+//! Minimal Apache Parquet writer.
+//!
+//! Writes required, uncompressed, PLAIN-encoded columns — enough for exports
+//! to load straight into `DuckDB`, pandas, Spark, and other analytic tooling
+//! without a conversion step. Each batch of rows becomes its own row group, so
+//! only one batch is held in memory at a time. File metadata is encoded with
+//! the Thrift compact protocol as the format requires.
+
+use std::io::Write;
+
+use crate::error::{Error, Result};
+
+/// Magic bytes at the start and end of every Parquet file.
+const MAGIC: &[u8; 4] = b"PAR1";
+
+// Parquet physical types.
+const TYPE_INT64: i32 = 2;
+const TYPE_BYTE_ARRAY: i32 = 6;
+
+// Parquet converted types.
+const CONVERTED_UTF8: i32 = 0;
+const CONVERTED_TIMESTAMP_MILLIS: i32 = 9;
+
+const REPETITION_REQUIRED: i32 = 0;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_DATA: i32 = 0;
+
+/// Values for one column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Values {
+    /// UTF-8 strings.
+    Utf8(Vec<String>),
+    /// Milliseconds since the Unix epoch, UTC.
+    TimestampMillis(Vec<i64>),
+}
+
+/// The type of a column's values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Utf8,
+    TimestampMillis,
+}
+
+impl Kind {
+    const fn physical_type(self) -> i32 {
+        match self {
+            Self::Utf8 => TYPE_BYTE_ARRAY,
+            Self::TimestampMillis => TYPE_INT64,
+        }
+    }
+}
+
+impl Values {
+    const fn kind(&self) -> Kind {
+        match self {
+            Self::Utf8(_) => Kind::Utf8,
+            Self::TimestampMillis(_) => Kind::TimestampMillis,
+        }
+    }
+
+    const fn len(&self) -> usize {
+        match self {
+            Self::Utf8(v) => v.len(),
+            Self::TimestampMillis(v) => v.len(),
+        }
+    }
+
+    /// PLAIN-encoded page data.
+    fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        match self {
+            Self::Utf8(values) => {
+                for value in values {
+                    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+                    data.extend_from_slice(value.as_bytes());
+                }
+            },
+            Self::TimestampMillis(values) => {
+                for value in values {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+            },
+        }
+        data
+    }
+}
+
+/// A named column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Column {
+    /// Column name.
+    pub name: String,
+    /// Column values.
+    pub values: Values,
+}
+
+impl Column {
+    /// A string column.
+    pub fn utf8(name: &str, values: Vec<String>) -> Self {
+        Self { name: name.to_string(), values: Values::Utf8(values) }
+    }
+
+    /// A UTC timestamp column with millisecond precision.
+    pub fn timestamp_millis(name: &str, values: Vec<i64>) -> Self {
+        Self { name: name.to_string(), values: Values::TimestampMillis(values) }
+    }
+}
+
+/// Encode `columns` as a Parquet file with a single row group.
+///
+/// All columns must have the same number of values.
+pub fn encode(columns: &[Column]) -> Result<Vec<u8>> {
+    let mut writer = Writer::new(Vec::new())?;
+    writer.write_row_group(columns)?;
+    writer.finish()
+}
+
+/// Where a column chunk was written.
+struct ChunkMeta {
+    offset: i64,
+    size: i64,
+}
+
+/// Where a row group was written.
+struct RowGroupMeta {
+    chunks: Vec<ChunkMeta>,
+    num_rows: i64,
+}
+
+/// Writes a Parquet file one row group at a time.
+///
+/// The first row group fixes the schema; later row groups must have the same
+/// column names and types. Only the offsets of written column chunks are kept
+/// until [`Writer::finish`] writes the footer.
+pub struct Writer<W: Write> {
+    sink: W,
+    offset: i64,
+    schema: Option<Vec<(String, Kind)>>,
+    row_groups: Vec<RowGroupMeta>,
+    num_rows: usize,
+}
+
+impl<W: Write> Writer<W> {
+    /// Start a Parquet file in `sink`.
+    pub fn new(mut sink: W) -> Result<Self> {
+        sink.write_all(MAGIC)?;
+        Ok(Self {
+            sink,
+            offset: MAGIC.len() as i64,
+            schema: None,
+            row_groups: Vec::new(),
+            num_rows: 0,
+        })
+    }
+
+    /// Rows written so far.
+    pub const fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Write `columns` as one row group.
+    ///
+    /// All columns must have the same number of values, and the same names
+    /// and types as the first row group.
+    pub fn write_row_group(&mut self, columns: &[Column]) -> Result<()> {
+        let num_rows = columns.first().map_or(0, |c| c.values.len());
+        if columns.iter().any(|c| c.values.len() != num_rows) {
+            return Err(Error::other("Parquet columns must have the same number of values"));
+        }
+        let schema: Vec<(String, Kind)> =
+            columns.iter().map(|c| (c.name.clone(), c.values.kind())).collect();
+        match &self.schema {
+            Some(existing) if *existing != schema => {
+                return Err(Error::other("Parquet row groups must have the same columns"));
+            },
+            Some(_) => {},
+            None => self.schema = Some(schema),
+        }
+
+        let mut chunks = Vec::with_capacity(columns.len());
+        for column in columns {
+            let data = column.values.encode();
+            let page_size = i32::try_from(data.len())
+                .map_err(|_| Error::other(format!("Column '{}' is too large", column.name)))?;
+
+            let mut header = Compact::default();
+            header.i32(1, PAGE_DATA);
+            header.i32(2, page_size);
+            header.i32(3, page_size);
+            header.begin_struct(5);
+            header.i32(1, num_rows as i32);
+            header.i32(2, ENCODING_PLAIN);
+            header.i32(3, ENCODING_RLE);
+            header.i32(4, ENCODING_RLE);
+            header.end_struct();
+            header.end_struct();
+
+            let size = (header.buf.len() + data.len()) as i64;
+            self.sink.write_all(&header.buf)?;
+            self.sink.write_all(&data)?;
+            chunks.push(ChunkMeta { offset: self.offset, size });
+            self.offset += size;
+        }
+
+        self.row_groups.push(RowGroupMeta { chunks, num_rows: num_rows as i64 });
+        self.num_rows += num_rows;
+        Ok(())
+    }
+
+    /// Write the file metadata and footer, returning the flushed sink.
+    pub fn finish(mut self) -> Result<W> {
+        let schema = self.schema.take().unwrap_or_default();
+
+        let mut meta = Compact::default();
+        meta.i32(1, 1);
+
+        // Schema: a root group followed by one leaf per column.
+        meta.list(2, Compact::STRUCT, schema.len() + 1);
+        meta.begin_element();
+        meta.binary(4, b"schema");
+        meta.i32(5, schema.len() as i32);
+        meta.end_struct();
+        for (name, kind) in &schema {
+            meta.begin_element();
+            meta.i32(1, kind.physical_type());
+            meta.i32(3, REPETITION_REQUIRED);
+            meta.binary(4, name.as_bytes());
+            match kind {
+                Kind::Utf8 => {
+                    meta.i32(6, CONVERTED_UTF8);
+                    // LogicalType { STRING: StringType {} }
+                    meta.begin_struct(10);
+                    meta.begin_struct(1);
+                    meta.end_struct();
+                    meta.end_struct();
+                },
+                Kind::TimestampMillis => {
+                    meta.i32(6, CONVERTED_TIMESTAMP_MILLIS);
+                    // LogicalType { TIMESTAMP: { isAdjustedToUTC: true, unit: MILLIS } }
+                    meta.begin_struct(10);
+                    meta.begin_struct(8);
+                    meta.bool(1, true);
+                    meta.begin_struct(2);
+                    meta.begin_struct(1);
+                    meta.end_struct();
+                    meta.end_struct();
+                    meta.end_struct();
+                    meta.end_struct();
+                },
+            }
+            meta.end_struct();
+        }
+
+        meta.i64(3, self.num_rows as i64);
+
+        meta.list(4, Compact::STRUCT, self.row_groups.len());
+        for group in &self.row_groups {
+            meta.begin_element();
+            meta.list(1, Compact::STRUCT, group.chunks.len());
+            for ((name, kind), chunk) in schema.iter().zip(&group.chunks) {
+                meta.begin_element();
+                meta.i64(2, chunk.offset);
+                meta.begin_struct(3);
+                meta.i32(1, kind.physical_type());
+                meta.list(2, Compact::I32, 2);
+                meta.list_i32(ENCODING_PLAIN);
+                meta.list_i32(ENCODING_RLE);
+                meta.list(3, Compact::BINARY, 1);
+                meta.list_binary(name.as_bytes());
+                meta.i32(4, CODEC_UNCOMPRESSED);
+                meta.i64(5, group.num_rows);
+                meta.i64(6, chunk.size);
+                meta.i64(7, chunk.size);
+                meta.i64(9, chunk.offset);
+                meta.end_struct();
+                meta.end_struct();
+            }
+            meta.i64(2, group.chunks.iter().map(|c| c.size).sum());
+            meta.i64(3, group.num_rows);
+            meta.end_struct();
+        }
+
+        meta.binary(6, format!("inferadb-cli version {}", crate::VERSION).as_bytes());
+        meta.end_struct();
+
+        self.sink.write_all(&meta.buf)?;
+        self.sink.write_all(&(meta.buf.len() as u32).to_le_bytes())?;
+        self.sink.write_all(MAGIC)?;
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+}
+
+/// Thrift compact protocol encoder for the structures Parquet needs.
+struct Compact {
+    buf: Vec<u8>,
+    /// Last field id written, per open struct.
+    last_field: Vec<i16>,
+}
+
+impl Default for Compact {
+    fn default() -> Self {
+        Self { buf: Vec::new(), last_field: vec![0] }
+    }
+}
+
+impl Compact {
+    const BOOL_TRUE: u8 = 1;
+    const BOOL_FALSE: u8 = 2;
+    const I32: u8 = 5;
+    const I64: u8 = 6;
+    const BINARY: u8 = 8;
+    const LIST: u8 = 9;
+    const STRUCT: u8 = 12;
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buf.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buf.push(value as u8);
+    }
+
+    fn zigzag(&mut self, value: i64) {
+        self.varint(((value << 1) ^ (value >> 63)) as u64);
+    }
+
+    fn field(&mut self, id: i16, kind: u8) {
+        let last = self.last_field.last_mut().map_or(0, |last| std::mem::replace(last, id));
+        let delta = id - last;
+        if (1..=15).contains(&delta) {
+            self.buf.push(((delta as u8) << 4) | kind);
+        } else {
+            self.buf.push(kind);
+            self.zigzag(i64::from(id));
+        }
+    }
+
+    fn bool(&mut self, id: i16, value: bool) {
+        self.field(id, if value { Self::BOOL_TRUE } else { Self::BOOL_FALSE });
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, Self::I32);
+        self.zigzag(i64::from(value));
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, Self::I64);
+        self.zigzag(value);
+    }
+
+    fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, Self::BINARY);
+        self.list_binary(value);
+    }
+
+    fn begin_struct(&mut self, id: i16) {
+        self.field(id, Self::STRUCT);
+        self.last_field.push(0);
+    }
+
+    /// Start a struct that is a list element (no field header).
+    fn begin_element(&mut self) {
+        self.last_field.push(0);
+    }
+
+    fn end_struct(&mut self) {
+        self.buf.push(0);
+        self.last_field.pop();
+    }
+
+    fn list(&mut self, id: i16, element: u8, len: usize) {
+        self.field(id, Self::LIST);
+        if len < 15 {
+            self.buf.push(((len as u8) << 4) | element);
+        } else {
+            self.buf.push(0xF0 | element);
+            self.varint(len as u64);
+        }
+    }
+
+    fn list_i32(&mut self, value: i32) {
+        self.zigzag(i64::from(value));
+    }
+
+    fn list_binary(&mut self, value: &[u8]) {
+        self.varint(value.len() as u64);
+        self.buf.extend_from_slice(value);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_field_headers() {
+        let mut c = Compact::default();
+        c.i32(1, 3);
+        c.i32(17, -1);
+        c.bool(18, false);
+        assert_eq!(c.buf, vec![0x15, 6, 0x05, 34, 1, 0x12]);
+    }
+
+    #[test]
+    fn test_encode_layout() {
+        let columns = [
+            Column::utf8("resource", vec!["doc:1".to_string(), "doc:2".to_string()]),
+            Column::timestamp_millis("at", vec![1, 2]),
+        ];
+        let file = encode(&columns).unwrap();
+
+        assert_eq!(&file[..4], MAGIC);
+        assert_eq!(&file[file.len() - 4..], MAGIC);
+
+        let footer_len = file.len() - 8;
+        let meta_len = u32::from_le_bytes(file[footer_len..footer_len + 4].try_into().unwrap());
+        let meta_start = footer_len - meta_len as usize;
+        let meta = &file[meta_start..footer_len];
+        assert!(meta.windows(8).any(|w| w == b"resource"));
+
+        // First page data follows its header: length-prefixed "doc:1".
+        let page = &file[4..meta_start];
+        assert!(page.windows(9).any(|w| w == b"\x05\x00\x00\x00doc:1"));
+    }
+
+    /// Read every row back with the reference `parquet` reader.
+    fn read_back(file: Vec<u8>) -> (usize, Vec<String>) {
+        use ::parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let mut tmp = tempfile::tempfile().unwrap();
+        tmp.write_all(&file).unwrap();
+        let reader = SerializedFileReader::new(tmp).unwrap();
+        let row_groups = reader.metadata().num_row_groups();
+        let rows = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| {
+                let fields: Vec<String> =
+                    row.unwrap().get_column_iter().map(|(_, field)| field.to_string()).collect();
+                fields.join(",")
+            })
+            .collect();
+        (row_groups, rows)
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let columns = [
+            Column::utf8("resource", vec!["doc:1".to_string(), "doc:2".to_string()]),
+            Column::timestamp_millis("at", vec![0, 1_700_000_000_000]),
+        ];
+        let (row_groups, rows) = read_back(encode(&columns).unwrap());
+
+        assert_eq!(row_groups, 1);
+        assert_eq!(rows.len(), 2);
+        assert!(rows[0].starts_with("\"doc:1\",1970-01-01"));
+        assert!(rows[1].starts_with("\"doc:2\",2023-11-14"));
+    }
+
+    #[test]
+    fn test_writer_row_group_per_batch() {
+        let mut writer = Writer::new(Vec::new()).unwrap();
+        for batch in [["a", "b"].as_slice(), &["c"], &[]] {
+            let values = batch.iter().map(ToString::to_string).collect();
+            writer.write_row_group(&[Column::utf8("subject", values)]).unwrap();
+        }
+        assert_eq!(writer.num_rows(), 3);
+
+        let (row_groups, rows) = read_back(writer.finish().unwrap());
+        assert_eq!(row_groups, 3);
+        assert_eq!(rows, vec!["\"a\"", "\"b\"", "\"c\""]);
+    }
+
+    #[test]
+    fn test_writer_rejects_schema_change() {
+        let mut writer = Writer::new(Vec::new()).unwrap();
+        writer.write_row_group(&[Column::utf8("a", vec![])]).unwrap();
+        assert!(writer.write_row_group(&[Column::timestamp_millis("a", vec![])]).is_err());
+        assert!(writer.write_row_group(&[Column::utf8("b", vec![])]).is_err());
+    }
+
+    #[test]
+    fn test_encode_rejects_ragged_columns() {
+        let columns = [Column::utf8("a", vec!["x".to_string()]), Column::utf8("b", vec![])];
+        assert!(encode(&columns).is_err());
+    }
+}
@@ -0,0 +1,780 @@
+//! Result filtering for `--query`.
+//!
+//! A `JMESPath` expression applied to a command's JSON result before it is
+//! formatted, so `--query "[?relation=='viewer'].resource"` works without
+//! piping through `jq`. Lists are queried as one array. Supported:
+//!
+//! - Fields `a.b`, quoted fields `"a-b"`, `@`, indexes `[0]`, `[-1]` and slices `[1:3]`, `[::-1]`.
+//! - Projections `[*].a`, `*.a`, flattening `[]` and filters `` [?a=='x' && b > `1`] `` with `==`,
+//!   `!=`, `<`, `<=`, `>`, `>=`, `!`, `&&` and `||`.
+//! - Multiselect lists `[a, b]` and hashes `{name: a, id: b}`, pipes `|`, raw strings `'x'` and
+//!   JSON literals `` `{"a": 1}` ``.
+//! - Functions `length`, `keys`, `values`, `contains`, `starts_with`, `ends_with`, `join`, `sort`,
+//!   `sort_by`, `reverse`, `min`, `max`, `sum`, `to_string` and `type`.
+
+use std::cmp::Ordering;
+
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+/// A parsed `--query` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    ast: Ast,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Ast {
+    Identity,
+    Field(String),
+    Subexpr(Box<Self>, Box<Self>),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Projection(Box<Self>, Box<Self>),
+    ObjectValues(Box<Self>),
+    Flatten(Box<Self>),
+    Condition(Box<Self>, Box<Self>),
+    MultiList(Vec<Self>),
+    MultiHash(Vec<(String, Self)>),
+    Literal(Value),
+    Not(Box<Self>),
+    And(Box<Self>, Box<Self>),
+    Or(Box<Self>, Box<Self>),
+    Compare(Cmp, Box<Self>, Box<Self>),
+    Function(String, Vec<Self>),
+    Expref(Box<Self>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cmp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    QuotedIdentifier(String),
+    Number(i64),
+    Literal(Value),
+    Dot,
+    Star,
+    At,
+    Flatten,
+    Filter,
+    Lbracket,
+    Rbracket,
+    Lbrace,
+    Rbrace,
+    Lparen,
+    Rparen,
+    Colon,
+    Comma,
+    Pipe,
+    Or,
+    And,
+    Not,
+    Ampersand,
+    Cmp(Cmp),
+    Eof,
+}
+
+impl Token {
+    /// Left binding power, as in the `JMESPath` reference parser.
+    const fn lbp(&self) -> u8 {
+        match self {
+            Self::Pipe => 1,
+            Self::Or => 2,
+            Self::And => 3,
+            Self::Cmp(_) => 5,
+            Self::Flatten => 9,
+            Self::Star => 20,
+            Self::Filter => 21,
+            Self::Dot => 40,
+            Self::Not => 45,
+            Self::Lbrace => 50,
+            Self::Lbracket => 55,
+            Self::Lparen => 60,
+            _ => 0,
+        }
+    }
+}
+
+impl Query {
+    /// Parse a `JMESPath` expression.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser { tokens: tokenize(source)?, pos: 0 };
+        let ast = parser.expr(0)?;
+        match parser.peek() {
+            Token::Eof => Ok(Self { ast }),
+            token => Err(invalid(&format!("unexpected {token:?}"))),
+        }
+    }
+
+    /// Apply the expression to a value.
+    pub fn apply(&self, value: &Value) -> Result<Value> {
+        eval(&self.ast, value)
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::invalid_arg(format!("Invalid --query: {message}"))
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let next_is = |i: usize, c: char| chars.get(i + 1) == Some(&c);
+
+    while let Some(&c) = chars.get(i) {
+        let (token, len) = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            },
+            '.' => (Token::Dot, 1),
+            '*' => (Token::Star, 1),
+            '@' => (Token::At, 1),
+            ']' => (Token::Rbracket, 1),
+            '{' => (Token::Lbrace, 1),
+            '}' => (Token::Rbrace, 1),
+            '(' => (Token::Lparen, 1),
+            ')' => (Token::Rparen, 1),
+            ':' => (Token::Colon, 1),
+            ',' => (Token::Comma, 1),
+            '[' if next_is(i, '?') => (Token::Filter, 2),
+            '[' if next_is(i, ']') => (Token::Flatten, 2),
+            '[' => (Token::Lbracket, 1),
+            '|' if next_is(i, '|') => (Token::Or, 2),
+            '|' => (Token::Pipe, 1),
+            '&' if next_is(i, '&') => (Token::And, 2),
+            '&' => (Token::Ampersand, 1),
+            '!' if next_is(i, '=') => (Token::Cmp(Cmp::Ne), 2),
+            '!' => (Token::Not, 1),
+            '=' if next_is(i, '=') => (Token::Cmp(Cmp::Eq), 2),
+            '<' if next_is(i, '=') => (Token::Cmp(Cmp::Le), 2),
+            '<' => (Token::Cmp(Cmp::Lt), 1),
+            '>' if next_is(i, '=') => (Token::Cmp(Cmp::Ge), 2),
+            '>' => (Token::Cmp(Cmp::Gt), 1),
+            '\'' | '`' | '"' => {
+                let (text, len) = delimited(&chars[i..])?;
+                let token = match c {
+                    '\'' => Token::Literal(Value::String(text.replace("\\'", "'"))),
+                    '`' => Token::Literal(
+                        serde_json::from_str(&text.replace("\\`", "`"))
+                            .map_err(|e| invalid(&format!("bad literal `{text}`: {e}")))?,
+                    ),
+                    _ => Token::QuotedIdentifier(
+                        serde_json::from_str(&format!("\"{text}\""))
+                            .map_err(|e| invalid(&format!("bad identifier \"{text}\": {e}")))?,
+                    ),
+                };
+                (token, len)
+            },
+            c if c == '-' || c.is_ascii_digit() => {
+                let len = 1 + chars[i + 1..].iter().take_while(|c| c.is_ascii_digit()).count();
+                let text: String = chars[i..i + len].iter().collect();
+                let number = text.parse().map_err(|_| invalid(&format!("bad number '{text}'")))?;
+                (Token::Number(number), len)
+            },
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+                    .count();
+                (Token::Identifier(chars[i..i + len].iter().collect()), len)
+            },
+            other => return Err(invalid(&format!("unexpected '{other}'"))),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    tokens.push(Token::Eof);
+    Ok(tokens)
+}
+
+/// Text between a quote character and its unescaped closing match, and the
+/// length including both quotes.
+fn delimited(chars: &[char]) -> Result<(String, usize)> {
+    let quote = chars[0];
+    let mut escaped = false;
+    for (i, &c) in chars.iter().enumerate().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == quote => return Ok((chars[1..i].iter().collect(), i + 1)),
+            _ => {},
+        }
+    }
+    Err(invalid(&format!("unclosed {quote}")))
+}
+
+/// Pratt parser following the `JMESPath` reference grammar.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        self.tokens.get(self.pos + offset).unwrap_or(&Token::Eof)
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.peek().clone();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<()> {
+        match self.advance() {
+            token if token == *expected => Ok(()),
+            token => Err(invalid(&format!("expected {expected:?}, found {token:?}"))),
+        }
+    }
+
+    fn expr(&mut self, rbp: u8) -> Result<Ast> {
+        let mut left = self.nud()?;
+        while rbp < self.peek().lbp() {
+            left = self.led(left)?;
+        }
+        Ok(left)
+    }
+
+    fn nud(&mut self) -> Result<Ast> {
+        match self.advance() {
+            Token::At => Ok(Ast::Identity),
+            Token::Identifier(name) | Token::QuotedIdentifier(name) => Ok(Ast::Field(name)),
+            Token::Literal(value) => Ok(Ast::Literal(value)),
+            Token::Star => self.wildcard_values(Ast::Identity),
+            Token::Flatten => self.flatten(Ast::Identity),
+            Token::Filter => self.filter(Ast::Identity),
+            Token::Lbrace => self.multi_hash(),
+            Token::Lbracket => match self.peek() {
+                Token::Number(_) | Token::Colon => self.index(Ast::Identity),
+                Token::Star if *self.peek_at(1) == Token::Rbracket => {
+                    self.advance();
+                    self.wildcard_index(Ast::Identity)
+                },
+                _ => self.multi_list(),
+            },
+            Token::Not => Ok(Ast::Not(Box::new(self.expr(Token::Not.lbp())?))),
+            Token::Ampersand => Ok(Ast::Expref(Box::new(self.expr(0)?))),
+            Token::Lparen => {
+                let inner = self.expr(0)?;
+                self.expect(&Token::Rparen)?;
+                Ok(inner)
+            },
+            token => Err(invalid(&format!("unexpected {token:?}"))),
+        }
+    }
+
+    fn led(&mut self, left: Ast) -> Result<Ast> {
+        match self.advance() {
+            Token::Dot => {
+                if *self.peek() == Token::Star {
+                    self.advance();
+                    return self.wildcard_values(left);
+                }
+                let right = self.dot(Token::Dot.lbp())?;
+                Ok(Ast::Subexpr(Box::new(left), Box::new(right)))
+            },
+            Token::Lbracket => match self.peek() {
+                Token::Number(_) | Token::Colon => self.index(left),
+                Token::Star => {
+                    self.advance();
+                    self.wildcard_index(left)
+                },
+                token => Err(invalid(&format!("unexpected {token:?} after '['"))),
+            },
+            Token::Flatten => self.flatten(left),
+            Token::Filter => self.filter(left),
+            Token::Pipe => {
+                let right = self.expr(Token::Pipe.lbp())?;
+                Ok(Ast::Subexpr(Box::new(left), Box::new(right)))
+            },
+            Token::Or => Ok(Ast::Or(Box::new(left), Box::new(self.expr(Token::Or.lbp())?))),
+            Token::And => Ok(Ast::And(Box::new(left), Box::new(self.expr(Token::And.lbp())?))),
+            Token::Cmp(cmp) => {
+                let right = self.expr(Token::Cmp(cmp).lbp())?;
+                Ok(Ast::Compare(cmp, Box::new(left), Box::new(right)))
+            },
+            Token::Lparen => {
+                let Ast::Field(name) = left else {
+                    return Err(invalid("only functions can be called"));
+                };
+                let mut args = Vec::new();
+                while *self.peek() != Token::Rparen {
+                    args.push(self.expr(0)?);
+                    if *self.peek() == Token::Comma {
+                        self.advance();
+                    }
+                }
+                self.advance();
+                Ok(Ast::Function(name, args))
+            },
+            token => Err(invalid(&format!("unexpected {token:?}"))),
+        }
+    }
+
+    /// The right-hand side of a `.`: a multiselect or an expression.
+    fn dot(&mut self, lbp: u8) -> Result<Ast> {
+        match self.peek() {
+            Token::Lbracket => {
+                self.advance();
+                self.multi_list()
+            },
+            Token::Lbrace => {
+                self.advance();
+                self.multi_hash()
+            },
+            _ => self.expr(lbp),
+        }
+    }
+
+    /// What a projection applies to each element: `.a`, `[...]` or nothing.
+    fn projection_rhs(&mut self, lbp: u8) -> Result<Ast> {
+        match self.peek() {
+            Token::Dot => {
+                self.advance();
+                self.dot(lbp)
+            },
+            Token::Lbracket | Token::Filter => self.expr(lbp),
+            token if token.lbp() < 10 => Ok(Ast::Identity),
+            token => Err(invalid(&format!("unexpected {token:?} after projection"))),
+        }
+    }
+
+    fn wildcard_index(&mut self, left: Ast) -> Result<Ast> {
+        self.expect(&Token::Rbracket)?;
+        let right = self.projection_rhs(Token::Star.lbp())?;
+        Ok(Ast::Projection(Box::new(left), Box::new(right)))
+    }
+
+    fn wildcard_values(&mut self, left: Ast) -> Result<Ast> {
+        let right = self.projection_rhs(Token::Star.lbp())?;
+        Ok(Ast::Projection(Box::new(Ast::ObjectValues(Box::new(left))), Box::new(right)))
+    }
+
+    fn flatten(&mut self, left: Ast) -> Result<Ast> {
+        let right = self.projection_rhs(Token::Flatten.lbp())?;
+        Ok(Ast::Projection(Box::new(Ast::Flatten(Box::new(left))), Box::new(right)))
+    }
+
+    fn filter(&mut self, left: Ast) -> Result<Ast> {
+        let condition = self.expr(0)?;
+        self.expect(&Token::Rbracket)?;
+        let right = self.projection_rhs(Token::Filter.lbp())?;
+        Ok(Ast::Projection(
+            Box::new(left),
+            Box::new(Ast::Condition(Box::new(condition), Box::new(right))),
+        ))
+    }
+
+    /// `[n]` or a slice `[start:end:step]`, after the `[`.
+    fn index(&mut self, left: Ast) -> Result<Ast> {
+        let mut parts = [None, None, None];
+        let mut colons = 0;
+        loop {
+            match self.advance() {
+                Token::Number(n) => parts[colons] = Some(n),
+                Token::Colon if colons < 2 => colons += 1,
+                Token::Rbracket => break,
+                token => return Err(invalid(&format!("unexpected {token:?} in index"))),
+            }
+        }
+        if colons == 0 {
+            let index = parts[0].ok_or_else(|| invalid("empty index"))?;
+            return Ok(Ast::Subexpr(Box::new(left), Box::new(Ast::Index(index))));
+        }
+        if parts[2] == Some(0) {
+            return Err(invalid("slice step cannot be 0"));
+        }
+        let slice = Ast::Slice(parts[0], parts[1], parts[2]);
+        let right = self.projection_rhs(Token::Star.lbp())?;
+        Ok(Ast::Projection(
+            Box::new(Ast::Subexpr(Box::new(left), Box::new(slice))),
+            Box::new(right),
+        ))
+    }
+
+    /// `[a, b]`, after the `[`.
+    fn multi_list(&mut self) -> Result<Ast> {
+        let mut items = vec![self.expr(0)?];
+        while *self.peek() == Token::Comma {
+            self.advance();
+            items.push(self.expr(0)?);
+        }
+        self.expect(&Token::Rbracket)?;
+        Ok(Ast::MultiList(items))
+    }
+
+    /// `{key: a, other: b}`, after the `{`.
+    fn multi_hash(&mut self) -> Result<Ast> {
+        let mut pairs = Vec::new();
+        loop {
+            let key = match self.advance() {
+                Token::Identifier(key) | Token::QuotedIdentifier(key) => key,
+                token => return Err(invalid(&format!("expected a key, found {token:?}"))),
+            };
+            self.expect(&Token::Colon)?;
+            pairs.push((key, self.expr(0)?));
+            match self.advance() {
+                Token::Comma => {},
+                Token::Rbrace => return Ok(Ast::MultiHash(pairs)),
+                token => return Err(invalid(&format!("expected ',' or '}}', found {token:?}"))),
+            }
+        }
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        Value::Number(_) => true,
+    }
+}
+
+/// Order numbers or strings; other values are not ordered.
+fn order(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+fn equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(_), Value::Number(_)) => order(left, right) == Some(Ordering::Equal),
+        _ => left == right,
+    }
+}
+
+fn index_of(len: usize, index: i64) -> Option<usize> {
+    let len = i64::try_from(len).ok()?;
+    let index = if index < 0 { len + index } else { index };
+    if (0..len).contains(&index) { usize::try_from(index).ok() } else { None }
+}
+
+fn slice(items: &[Value], start: Option<i64>, end: Option<i64>, step: Option<i64>) -> Vec<Value> {
+    let len = i64::try_from(items.len()).unwrap_or(i64::MAX);
+    let step = step.unwrap_or(1);
+    let clamp = |bound: i64, low: i64, high: i64| {
+        let bound = if bound < 0 { bound + len } else { bound };
+        bound.clamp(low, high)
+    };
+    let (mut i, end) = if step > 0 {
+        (start.map_or(0, |s| clamp(s, 0, len)), end.map_or(len, |s| clamp(s, 0, len)))
+    } else {
+        (start.map_or(len - 1, |s| clamp(s, -1, len - 1)), end.map_or(-1, |s| clamp(s, -1, len)))
+    };
+    let mut result = Vec::new();
+    while (step > 0 && i < end) || (step < 0 && i > end) {
+        if let Some(item) = usize::try_from(i).ok().and_then(|i| items.get(i)) {
+            result.push(item.clone());
+        }
+        i += step;
+    }
+    result
+}
+
+fn eval(ast: &Ast, value: &Value) -> Result<Value> {
+    Ok(match ast {
+        Ast::Identity => value.clone(),
+        Ast::Field(name) => value.get(name).cloned().unwrap_or(Value::Null),
+        Ast::Subexpr(left, right) => eval(right, &eval(left, value)?)?,
+        Ast::Index(index) => match value {
+            Value::Array(items) => {
+                index_of(items.len(), *index).map_or(Value::Null, |i| items[i].clone())
+            },
+            _ => Value::Null,
+        },
+        Ast::Slice(start, end, step) => match value {
+            Value::Array(items) => Value::Array(slice(items, *start, *end, *step)),
+            _ => Value::Null,
+        },
+        Ast::Projection(left, right) => match eval(left, value)? {
+            Value::Array(items) => {
+                let mut projected = Vec::new();
+                for item in &items {
+                    let result = eval(right, item)?;
+                    if !result.is_null() {
+                        projected.push(result);
+                    }
+                }
+                Value::Array(projected)
+            },
+            _ => Value::Null,
+        },
+        Ast::ObjectValues(inner) => match eval(inner, value)? {
+            Value::Object(map) => Value::Array(map.into_iter().map(|(_, v)| v).collect()),
+            _ => Value::Null,
+        },
+        Ast::Flatten(inner) => match eval(inner, value)? {
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .flat_map(|item| match item {
+                        Value::Array(nested) => nested,
+                        other => vec![other],
+                    })
+                    .collect(),
+            ),
+            _ => Value::Null,
+        },
+        Ast::Condition(condition, right) => {
+            if is_truthy(&eval(condition, value)?) {
+                eval(right, value)?
+            } else {
+                Value::Null
+            }
+        },
+        Ast::MultiList(items) if !value.is_null() => {
+            Value::Array(items.iter().map(|item| eval(item, value)).collect::<Result<_>>()?)
+        },
+        Ast::MultiHash(pairs) if !value.is_null() => {
+            let mut map = Map::new();
+            for (key, item) in pairs {
+                map.insert(key.clone(), eval(item, value)?);
+            }
+            Value::Object(map)
+        },
+        Ast::MultiList(_) | Ast::MultiHash(_) => Value::Null,
+        Ast::Literal(literal) => literal.clone(),
+        Ast::Not(inner) => Value::Bool(!is_truthy(&eval(inner, value)?)),
+        Ast::And(left, right) => {
+            let left = eval(left, value)?;
+            if is_truthy(&left) { eval(right, value)? } else { left }
+        },
+        Ast::Or(left, right) => {
+            let left = eval(left, value)?;
+            if is_truthy(&left) { left } else { eval(right, value)? }
+        },
+        Ast::Compare(cmp, left, right) => {
+            let (left, right) = (eval(left, value)?, eval(right, value)?);
+            match cmp {
+                Cmp::Eq => Value::Bool(equal(&left, &right)),
+                Cmp::Ne => Value::Bool(!equal(&left, &right)),
+                _ => order(&left, &right).map_or(Value::Null, |ordering| {
+                    Value::Bool(match cmp {
+                        Cmp::Lt => ordering.is_lt(),
+                        Cmp::Le => ordering.is_le(),
+                        Cmp::Gt => ordering.is_gt(),
+                        _ => ordering.is_ge(),
+                    })
+                }),
+            }
+        },
+        Ast::Function(name, args) => call(name, args, value)?,
+        Ast::Expref(_) => return Err(invalid("'&' is only valid as a function argument")),
+    })
+}
+
+fn call(name: &str, args: &[Ast], value: &Value) -> Result<Value> {
+    let arity = |count: usize| {
+        if args.len() == count {
+            Ok(())
+        } else {
+            Err(invalid(&format!("{name}() takes {count} argument(s), got {}", args.len())))
+        }
+    };
+    let wrong_type = |expected: &str| invalid(&format!("{name}() expects {expected}"));
+    let arg = |i: usize| eval(&args[i], value);
+
+    Ok(match name {
+        "length" => {
+            arity(1)?;
+            let len = match arg(0)? {
+                Value::String(s) => s.chars().count(),
+                Value::Array(items) => items.len(),
+                Value::Object(map) => map.len(),
+                _ => return Err(wrong_type("a string, array or object")),
+            };
+            Value::from(len)
+        },
+        "keys" | "values" => {
+            arity(1)?;
+            let Value::Object(map) = arg(0)? else {
+                return Err(wrong_type("an object"));
+            };
+            if name == "keys" {
+                map.into_iter().map(|(k, _)| Value::String(k)).collect()
+            } else {
+                map.into_iter().map(|(_, v)| v).collect()
+            }
+        },
+        "contains" => {
+            arity(2)?;
+            let needle = arg(1)?;
+            Value::Bool(match (arg(0)?, &needle) {
+                (Value::Array(items), _) => items.iter().any(|item| equal(item, &needle)),
+                (Value::String(s), Value::String(sub)) => s.contains(sub.as_str()),
+                _ => return Err(wrong_type("an array or string")),
+            })
+        },
+        "starts_with" | "ends_with" => {
+            arity(2)?;
+            let (Value::String(s), Value::String(affix)) = (arg(0)?, arg(1)?) else {
+                return Err(wrong_type("two strings"));
+            };
+            let matches =
+                if name == "starts_with" { s.starts_with(&affix) } else { s.ends_with(&affix) };
+            Value::Bool(matches)
+        },
+        "join" => {
+            arity(2)?;
+            let (Value::String(separator), Value::Array(items)) = (arg(0)?, arg(1)?) else {
+                return Err(wrong_type("a separator and an array of strings"));
+            };
+            let parts = items
+                .iter()
+                .map(|item| item.as_str().ok_or_else(|| wrong_type("an array of strings")))
+                .collect::<Result<Vec<_>>>()?;
+            Value::String(parts.join(&separator))
+        },
+        "sort" | "reverse" | "min" | "max" | "sum" => {
+            arity(1)?;
+            let items = match arg(0)? {
+                Value::Array(items) => items,
+                Value::String(s) if name == "reverse" => {
+                    return Ok(Value::String(s.chars().rev().collect()));
+                },
+                _ => return Err(wrong_type("an array")),
+            };
+            match name {
+                "reverse" => items.into_iter().rev().collect(),
+                "sum" => Value::from(items.iter().filter_map(Value::as_f64).sum::<f64>()),
+                _ => {
+                    let mut sorted = items;
+                    sorted.sort_by(|a, b| order(a, b).unwrap_or(Ordering::Equal));
+                    match name {
+                        "min" => sorted.first().cloned().unwrap_or(Value::Null),
+                        "max" => sorted.last().cloned().unwrap_or(Value::Null),
+                        _ => Value::Array(sorted),
+                    }
+                },
+            }
+        },
+        "sort_by" => {
+            arity(2)?;
+            let (Value::Array(items), Ast::Expref(key)) = (arg(0)?, &args[1]) else {
+                return Err(wrong_type("an array and an &expression"));
+            };
+            let mut keyed = items
+                .into_iter()
+                .map(|item| Ok((eval(key, &item)?, item)))
+                .collect::<Result<Vec<_>>>()?;
+            keyed.sort_by(|(a, _), (b, _)| order(a, b).unwrap_or(Ordering::Equal));
+            keyed.into_iter().map(|(_, item)| item).collect()
+        },
+        "to_string" => {
+            arity(1)?;
+            match arg(0)? {
+                Value::String(s) => Value::String(s),
+                other => Value::String(other.to_string()),
+            }
+        },
+        "type" => {
+            arity(1)?;
+            let kind = match arg(0)? {
+                Value::Null => "null",
+                Value::Bool(_) => "boolean",
+                Value::Number(_) => "number",
+                Value::String(_) => "string",
+                Value::Array(_) => "array",
+                Value::Object(_) => "object",
+            };
+            Value::from(kind)
+        },
+        other => return Err(invalid(&format!("unknown function '{other}'"))),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn query(expression: &str, value: &Value) -> Value {
+        Query::parse(expression).unwrap().apply(value).unwrap()
+    }
+
+    fn relationships() -> Value {
+        json!([
+            { "resource": "document:a", "relation": "viewer", "subject": "user:alice" },
+            { "resource": "document:b", "relation": "editor", "subject": "user:bob" },
+            { "resource": "document:c", "relation": "viewer", "subject": "user:carol" },
+        ])
+    }
+
+    #[test]
+    fn test_fields_and_indexes() {
+        let value = json!({ "org": { "name": "Acme", "tags": ["a", "b", "c"] }, "my-id": 7 });
+        assert_eq!(query("org.name", &value), json!("Acme"));
+        assert_eq!(query("org.tags[-1]", &value), json!("c"));
+        assert_eq!(query("org.tags[1:]", &value), json!(["b", "c"]));
+        assert_eq!(query("org.tags[::-1]", &value), json!(["c", "b", "a"]));
+        assert_eq!(query("\"my-id\"", &value), json!(7));
+        assert_eq!(query("org.missing", &value), Value::Null);
+    }
+
+    #[test]
+    fn test_projections_and_filters() {
+        let value = relationships();
+        assert_eq!(
+            query("[?relation=='viewer'].resource", &value),
+            json!(["document:a", "document:c"])
+        );
+        assert_eq!(query("[*].subject | [0]", &value), json!("user:alice"));
+        assert_eq!(
+            query("[?relation!='viewer' || subject=='user:carol'].resource", &value),
+            json!(["document:b", "document:c"])
+        );
+        assert_eq!(
+            query("[0].{id: resource, who: subject}", &value),
+            json!({ "id": "document:a", "who": "user:alice" })
+        );
+        assert_eq!(
+            query("[*].tags[]", &json!([{ "tags": [1, 2] }, { "tags": [3] }])),
+            json!([1, 2, 3])
+        );
+        assert_eq!(query("[?`true`] | length(@)", &value), json!(3));
+    }
+
+    #[test]
+    fn test_functions() {
+        let value = json!({ "seats": [3, 1, 2], "members": [{ "n": "b" }, { "n": "a" }] });
+        assert_eq!(query("sort(seats)", &value), json!([1, 2, 3]));
+        assert_eq!(query("max(seats)", &value), json!(3));
+        assert_eq!(query("sort_by(members, &n)[*].n", &value), json!(["a", "b"]));
+        assert_eq!(query("join(', ', members[*].n)", &value), json!("b, a"));
+        assert_eq!(query("members[?starts_with(n, 'a')].n", &value), json!(["a"]));
+        assert_eq!(query("contains(seats, `2`)", &value), json!(true));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Query::parse("[?relation=='viewer'").is_err());
+        assert!(Query::parse("a.").is_err());
+        assert!(Query::parse("'unclosed").is_err());
+        assert!(Query::parse("a[0:1:0]").is_err());
+        assert!(Query::parse("nope(@)").unwrap().apply(&json!({})).is_err());
+    }
+}
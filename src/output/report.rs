@@ -0,0 +1,260 @@
+//! Machine-readable test reports for CI.
+//!
+//! Commands that run authorization tests (`schemas test`, `check batch`)
+//! describe their results as a [`Suite`] and write it once per `--report`
+//! target: `junit:<path>` writes `JUnit` XML for test dashboards, and `github`
+//! prints GitHub Actions `::error` workflow commands so failing tests are
+//! annotated inline on pull requests.
+
+use std::fmt::Write;
+
+use crate::error::{Error, Result};
+
+/// Where a test report goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportTarget {
+    /// `JUnit` XML written to a file.
+    Junit(String),
+    /// GitHub Actions annotations printed to stdout.
+    Github,
+}
+
+impl ReportTarget {
+    /// Parse `junit:<path>` or `github`.
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.split_once(':') {
+            Some(("junit", path)) if !path.is_empty() => Ok(Self::Junit(path.to_string())),
+            None if s == "github" => Ok(Self::Github),
+            _ => {
+                Err(Error::invalid_arg(format!("Unknown report '{s}'. Use: junit:<path>, github")))
+            },
+        }
+    }
+}
+
+/// How a test case ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The decision matched.
+    Passed,
+    /// The decision did not match; the message says how.
+    Failed(String),
+    /// The test could not be run.
+    Error(String),
+}
+
+/// One test case.
+#[derive(Debug, Clone)]
+pub struct Case {
+    /// Test name.
+    pub name: String,
+    /// What was checked, shown with failures (e.g., the check expression).
+    pub detail: String,
+    /// How the test ended.
+    pub outcome: Outcome,
+    /// 1-based line of the test in the suite's file, when known.
+    pub line: Option<usize>,
+}
+
+/// The results of one test file.
+#[derive(Debug, Clone, Default)]
+pub struct Suite {
+    /// The file the tests came from.
+    pub file: String,
+    /// Test cases in file order.
+    pub cases: Vec<Case>,
+    /// Extra `name`/`value` pairs (e.g., coverage), reported as `JUnit`
+    /// properties.
+    pub properties: Vec<(String, String)>,
+}
+
+impl Suite {
+    fn count(&self, error: bool) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| match case.outcome {
+                Outcome::Passed => false,
+                Outcome::Failed(_) => !error,
+                Outcome::Error(_) => error,
+            })
+            .count()
+    }
+}
+
+/// Write `suite` to every target.
+pub fn write(targets: &[ReportTarget], suite: &Suite) -> Result<()> {
+    for target in targets {
+        match target {
+            ReportTarget::Junit(path) => std::fs::write(path, junit(suite))
+                .map_err(|e| Error::other(format!("Could not write {path}: {e}")))?,
+            ReportTarget::Github => print!("{}", github(suite)),
+        }
+    }
+    Ok(())
+}
+
+/// The 1-based line of the first line of `content` containing every needle.
+#[must_use]
+pub fn line_of(content: &str, needles: &[&str]) -> Option<usize> {
+    content
+        .lines()
+        .position(|line| needles.iter().all(|needle| line.contains(needle)))
+        .map(|index| index + 1)
+}
+
+/// The suite as `JUnit` XML.
+#[must_use]
+pub fn junit(suite: &Suite) -> String {
+    let file = xml_escape(&suite.file);
+    let (failures, errors) = (suite.count(false), suite.count(true));
+    let counts =
+        format!("tests=\"{}\" failures=\"{failures}\" errors=\"{errors}\"", suite.cases.len());
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(xml, "<testsuites {counts}>");
+    let _ = writeln!(xml, "  <testsuite name=\"{file}\" {counts}>");
+    if !suite.properties.is_empty() {
+        xml.push_str("    <properties>\n");
+        for (name, value) in &suite.properties {
+            let _ = writeln!(
+                xml,
+                "      <property name=\"{}\" value=\"{}\"/>",
+                xml_escape(name),
+                xml_escape(value)
+            );
+        }
+        xml.push_str("    </properties>\n");
+    }
+    for case in &suite.cases {
+        let name = xml_escape(&case.name);
+        let (element, message) = match &case.outcome {
+            Outcome::Passed => {
+                let _ = writeln!(xml, "    <testcase name=\"{name}\" classname=\"{file}\"/>");
+                continue;
+            },
+            Outcome::Failed(message) => ("failure", message),
+            Outcome::Error(message) => ("error", message),
+        };
+        let _ = writeln!(xml, "    <testcase name=\"{name}\" classname=\"{file}\">");
+        let _ = writeln!(
+            xml,
+            "      <{element} message=\"{}\">{}</{element}>",
+            xml_escape(message),
+            xml_escape(&case.detail)
+        );
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+/// An `::error` workflow command per failed or errored case.
+#[must_use]
+pub fn github(suite: &Suite) -> String {
+    let mut out = String::new();
+    for case in &suite.cases {
+        let message = match &case.outcome {
+            Outcome::Passed => continue,
+            Outcome::Failed(message) | Outcome::Error(message) => message,
+        };
+        let mut properties = format!("file={}", github_property(&suite.file));
+        if let Some(line) = case.line {
+            let _ = write!(properties, ",line={line}");
+        }
+        let _ = writeln!(
+            out,
+            "::error {properties},title={}::{}",
+            github_property(&case.name),
+            github_data(&format!("{}: {message}", case.detail))
+        );
+    }
+    out
+}
+
+/// Escape text for an XML attribute or element.
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape a workflow command message.
+fn github_data(text: &str) -> String {
+    text.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value.
+fn github_property(text: &str) -> String {
+    github_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn suite() -> Suite {
+        let case = |name: &str, outcome, line| Case {
+            name: name.to_string(),
+            detail: "user:bob can edit doc:1".to_string(),
+            outcome,
+            line,
+        };
+        Suite {
+            file: "schema.test.yaml".to_string(),
+            cases: vec![
+                case("owner can edit", Outcome::Passed, Some(3)),
+                case("viewer can't edit", Outcome::Failed("expected deny, got allow".into()), None),
+                case("bad <check>", Outcome::Error("unknown permission".into()), Some(9)),
+            ],
+            properties: vec![("coverage".to_string(), "60.0".to_string())],
+        }
+    }
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            ReportTarget::parse("junit:out/results.xml").unwrap(),
+            ReportTarget::Junit("out/results.xml".to_string())
+        );
+        assert_eq!(ReportTarget::parse("github").unwrap(), ReportTarget::Github);
+        assert!(ReportTarget::parse("junit:").is_err());
+        assert!(ReportTarget::parse("tap").is_err());
+    }
+
+    #[test]
+    fn test_junit() {
+        let xml = junit(&suite());
+        assert!(xml.contains("<testsuites tests=\"3\" failures=\"1\" errors=\"1\">"));
+        assert!(xml.contains("<property name=\"coverage\" value=\"60.0\"/>"));
+        assert!(xml.contains("<testcase name=\"owner can edit\" classname=\"schema.test.yaml\"/>"));
+        assert!(xml.contains("<failure message=\"expected deny, got allow\">"));
+        assert!(xml.contains("<testcase name=\"bad &lt;check&gt;\""));
+    }
+
+    #[test]
+    fn test_github() {
+        assert_eq!(
+            github(&suite()),
+            "::error file=schema.test.yaml,title=viewer can't edit::user:bob can edit doc:1: expected deny, got allow\n\
+             ::error file=schema.test.yaml,line=9,title=bad <check>::user:bob can edit doc:1: unknown permission\n"
+        );
+        assert_eq!(github_property("a:b,c%"), "a%3Ab%2Cc%25");
+    }
+
+    #[test]
+    fn test_line_of() {
+        let content = "tests:\n  - name: first\n  - name: second\n";
+        assert_eq!(line_of(content, &["name: second"]), Some(3));
+        assert_eq!(line_of(content, &["third"]), None);
+    }
+}
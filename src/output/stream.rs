@@ -0,0 +1,143 @@
+//! Incremental output for large listings.
+//!
+//! [`Output::stream`] returns a [`RowStream`] that writes each page of rows as
+//! it arrives instead of buffering the whole result, so memory stays flat for
+//! million-row listings. Output matches [`Output::table`]: JSON is still one
+//! array, YAML one sequence, and CSV has one header row. Table columns are
+//! sized from the first page and widen, without realigning earlier rows, when
+//! a later page has longer values. `--query` needs the whole result, so with a
+//! query rows are collected and the query runs in [`RowStream::finish`].
+
+use std::{
+    io::{BufWriter, Write},
+    marker::PhantomData,
+};
+
+use serde::Serialize;
+use teapot::components::{Column, Table};
+
+use super::{Displayable, Output, OutputFormat, csv_record};
+use crate::error::Result;
+
+/// Writes a list one page at a time. Create with [`Output::stream`].
+pub struct RowStream<'a, T> {
+    output: &'a Output,
+    written: usize,
+    /// Table column widths so far.
+    widths: Vec<usize>,
+    /// Rows collected for `--query`.
+    collected: Vec<serde_json::Value>,
+    rows: PhantomData<fn(&T)>,
+}
+
+impl<'a, T: Displayable + Serialize> RowStream<'a, T> {
+    pub(super) const fn new(output: &'a Output) -> Self {
+        Self { output, written: 0, widths: Vec::new(), collected: Vec::new(), rows: PhantomData }
+    }
+
+    /// Rows written so far.
+    #[must_use]
+    pub const fn count(&self) -> usize {
+        self.written
+    }
+
+    /// Write a page of rows.
+    pub fn write(&mut self, rows: &[T]) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        if self.output.query.is_some() {
+            for row in rows {
+                self.collected.push(serde_json::to_value(row)?);
+            }
+            self.written += rows.len();
+            return Ok(());
+        }
+
+        let mut out = BufWriter::new(std::io::stdout().lock());
+        match self.output.format {
+            OutputFormat::Table if self.output.quiet => {
+                for row in rows {
+                    writeln!(out, "{}", row.primary_value())?;
+                }
+            },
+            OutputFormat::Table => {
+                drop(out);
+                self.write_table(rows);
+            },
+            OutputFormat::Json => {
+                for (index, row) in rows.iter().enumerate() {
+                    let separator = if self.written + index == 0 { "[\n" } else { ",\n" };
+                    let json = serde_json::to_string_pretty(row)?;
+                    write!(out, "{separator}  {}", json.replace('\n', "\n  "))?;
+                }
+            },
+            OutputFormat::Yaml => out.write_all(serde_yaml::to_string(rows)?.as_bytes())?,
+            OutputFormat::JsonLines => {
+                for row in rows {
+                    serde_json::to_writer(&mut out, row)?;
+                    out.write_all(b"\n")?;
+                }
+            },
+            OutputFormat::Csv => {
+                if self.written == 0 {
+                    out.write_all(csv_record(T::table_headers()).as_bytes())?;
+                }
+                for row in rows {
+                    out.write_all(csv_record(row.table_row()).as_bytes())?;
+                }
+            },
+            OutputFormat::Template => {
+                drop(out);
+                for row in rows {
+                    self.output.template(row)?;
+                }
+            },
+        }
+        self.written += rows.len();
+        Ok(())
+    }
+
+    /// Print a page as table rows, with the header before the first page.
+    fn write_table(&mut self, rows: &[T]) {
+        let headers = T::table_headers();
+        let cells: Vec<Vec<String>> = rows.iter().map(Displayable::table_row).collect();
+        if self.widths.is_empty() {
+            self.widths = headers.iter().map(|h| h.chars().count().max(3)).collect();
+        }
+        for row in &cells {
+            for (width, cell) in self.widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let columns: Vec<Column> =
+            headers.into_iter().zip(&self.widths).map(|(h, w)| Column::new(h).width(*w)).collect();
+        let table = Table::new()
+            .columns(columns)
+            .height(cells.len())
+            .rows(cells)
+            .show_header(self.written == 0)
+            .show_borders(false)
+            .focused(false);
+        self.output.print_rendered(&table.render());
+    }
+
+    /// Close the list, returning how many rows were written.
+    ///
+    /// An empty JSON or YAML list still prints `[]`, and empty CSV its header.
+    pub fn finish(self) -> Result<usize> {
+        if let Some(query) = &self.output.query {
+            let rows = serde_json::Value::Array(self.collected);
+            self.output.queried(&query.apply(&rows)?)?;
+            return Ok(self.written);
+        }
+        match self.output.format {
+            OutputFormat::Json | OutputFormat::Yaml if self.written == 0 => println!("[]"),
+            OutputFormat::Json => println!("\n]"),
+            OutputFormat::Csv if self.written == 0 => print!("{}", csv_record(T::table_headers())),
+            _ => {},
+        }
+        Ok(self.written)
+    }
+}
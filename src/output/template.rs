@@ -0,0 +1,275 @@
+//! Custom output templates for `--output template=...`.
+//!
+//! A small Go-template style language rendered against a command's JSON
+//! result, in the spirit of `docker --format` and `kubectl -o jsonpath`:
+//!
+//! - `{{.field.nested}}` prints a field of the current value; `{{.}}` prints the value itself.
+//!   Strings print raw, objects and arrays as compact JSON, and missing fields as nothing.
+//! - `{{json .field}}` prints a field as JSON, `{{upper .field}}` and `{{lower .field}}` change its
+//!   case, and `{{len .field}}` counts the items of an array or object or the characters of a
+//!   string.
+//! - `{{range .items}}...{{end}}` repeats its body for each element of an array (or value of an
+//!   object), with `.` bound to the element.
+//! - `{{if .field}}...{{else}}...{{end}}` renders the first body when the field is present and not
+//!   `false`, `0`, empty or `null`.
+//! - `\n` and `\t` in the template text print a newline and a tab.
+//!
+//! Commands that print lists render the template once per item, each on its
+//! own line; other commands render it once against their whole result. The
+//! fields available are exactly those of `--output json`.
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// A parsed output template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Field { func: Option<Func>, path: Vec<String> },
+    Range { path: Vec<String>, body: Vec<Self> },
+    If { path: Vec<String>, then: Vec<Self>, otherwise: Vec<Self> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Func {
+    Json,
+    Upper,
+    Lower,
+    Len,
+}
+
+/// A `{{...}}` action or a run of text, before nesting is resolved.
+enum Token {
+    Text(String),
+    Action(String),
+}
+
+impl Template {
+    /// Parse a template.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut tokens = tokenize(source)?.into_iter();
+        let (nodes, end) = parse_nodes(&mut tokens)?;
+        match end {
+            None => Ok(Self { nodes }),
+            Some(keyword) => Err(invalid(&format!("unexpected {{{{{keyword}}}}}"))),
+        }
+    }
+
+    /// Render the template against a value.
+    #[must_use]
+    pub fn render(&self, value: &Value) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, value, &mut out);
+        out
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::invalid_arg(format!("Invalid output template: {message}"))
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(unescape(&rest[..start])));
+        }
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| invalid("unclosed '{{'"))?;
+        tokens.push(Token::Action(after[..end].trim().to_string()));
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(unescape(rest)));
+    }
+    Ok(tokens)
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("\\n", "\n").replace("\\t", "\t")
+}
+
+/// Parse nodes up to the end of input or an `else`/`end` keyword, which is
+/// returned so the enclosing block can check it.
+fn parse_nodes(tokens: &mut impl Iterator<Item = Token>) -> Result<(Vec<Node>, Option<String>)> {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.next() {
+        let action = match token {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text));
+                continue;
+            },
+            Token::Action(action) => action,
+        };
+        let words: Vec<&str> = action.split_whitespace().collect();
+        match words.as_slice() {
+            ["end" | "else"] => return Ok((nodes, Some(words[0].to_string()))),
+            ["range", path] => {
+                let path = parse_path(path)?;
+                let (body, end) = parse_nodes(tokens)?;
+                if end.as_deref() != Some("end") {
+                    return Err(invalid("'range' without matching '{{end}}'"));
+                }
+                nodes.push(Node::Range { path, body });
+            },
+            ["if", path] => {
+                let path = parse_path(path)?;
+                let (then, end) = parse_nodes(tokens)?;
+                let otherwise = match end.as_deref() {
+                    Some("end") => Vec::new(),
+                    Some("else") => match parse_nodes(tokens)? {
+                        (otherwise, Some(end)) if end == "end" => otherwise,
+                        _ => return Err(invalid("'else' without matching '{{end}}'")),
+                    },
+                    _ => return Err(invalid("'if' without matching '{{end}}'")),
+                };
+                nodes.push(Node::If { path, then, otherwise });
+            },
+            [path] => nodes.push(Node::Field { func: None, path: parse_path(path)? }),
+            [func, path] => {
+                let func = match *func {
+                    "json" => Func::Json,
+                    "upper" => Func::Upper,
+                    "lower" => Func::Lower,
+                    "len" => Func::Len,
+                    other => return Err(invalid(&format!("unknown function '{other}'"))),
+                };
+                nodes.push(Node::Field { func: Some(func), path: parse_path(path)? });
+            },
+            _ => return Err(invalid(&format!("cannot parse '{{{{{action}}}}}'"))),
+        }
+    }
+    Ok((nodes, None))
+}
+
+/// Parse `.a.b` into `["a", "b"]`, and `.` into an empty path.
+fn parse_path(path: &str) -> Result<Vec<String>> {
+    let Some(fields) = path.strip_prefix('.') else {
+        return Err(invalid(&format!("expected a field like '.name', got '{path}'")));
+    };
+    if fields.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(fields.split('.').map(str::to_string).collect())
+}
+
+fn lookup<'a>(value: &'a Value, path: &[String]) -> &'a Value {
+    path.iter()
+        .try_fold(value, |value, field| match value {
+            Value::Object(map) => map.get(field),
+            Value::Array(items) => field.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+        .unwrap_or(&Value::Null)
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn render_nodes(nodes: &[Node], value: &Value, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Field { func, path } => {
+                let field = lookup(value, path);
+                let text = match func {
+                    None => to_text(field),
+                    Some(Func::Json) => field.to_string(),
+                    Some(Func::Upper) => to_text(field).to_uppercase(),
+                    Some(Func::Lower) => to_text(field).to_lowercase(),
+                    Some(Func::Len) => match field {
+                        Value::Array(items) => items.len(),
+                        Value::Object(map) => map.len(),
+                        Value::String(s) => s.chars().count(),
+                        _ => 0,
+                    }
+                    .to_string(),
+                };
+                out.push_str(&text);
+            },
+            Node::Range { path, body } => match lookup(value, path) {
+                Value::Array(items) => {
+                    for item in items {
+                        render_nodes(body, item, out);
+                    }
+                },
+                Value::Object(map) => {
+                    for item in map.values() {
+                        render_nodes(body, item, out);
+                    }
+                },
+                _ => {},
+            },
+            Node::If { path, then, otherwise } => {
+                let body = if is_truthy(lookup(value, path)) { then } else { otherwise };
+                render_nodes(body, value, out);
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn render(template: &str, value: &Value) -> String {
+        Template::parse(template).unwrap().render(value)
+    }
+
+    #[test]
+    fn test_fields() {
+        let value = json!({ "id": "123", "name": "Acme", "tier": { "name": "pro" }, "seats": 5 });
+        assert_eq!(render("{{.id}}\\t{{ .name }}", &value), "123\tAcme");
+        assert_eq!(render("{{.tier.name}} {{.seats}} [{{.missing}}]", &value), "pro 5 []");
+        assert_eq!(render("{{json .tier}}", &value), r#"{"name":"pro"}"#);
+        assert_eq!(render("{{upper .name}} {{len .name}}", &value), "ACME 4");
+        assert_eq!(render("{{.}}", &json!("plain")), "plain");
+    }
+
+    #[test]
+    fn test_range_and_if() {
+        let value = json!({ "members": [
+            { "email": "a@example.com", "admin": true },
+            { "email": "b@example.com", "admin": false },
+        ]});
+        let template = "{{range .members}}{{.email}}{{if .admin}} (admin){{end}}\\n{{end}}";
+        assert_eq!(render(template, &value), "a@example.com (admin)\nb@example.com\n");
+        assert_eq!(render("{{if .none}}yes{{else}}no{{end}}", &value), "no");
+        assert_eq!(render("{{.members.1.email}}", &value), "b@example.com");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Template::parse("{{.id").is_err());
+        assert!(Template::parse("{{range .items}}x").is_err());
+        assert!(Template::parse("{{end}}").is_err());
+        assert!(Template::parse("{{name}}").is_err());
+        assert!(Template::parse("{{shout .name}}").is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! Status colors and symbols.
+//!
+//! Success, warning, error and info messages, dev step statuses, and the TUI
+//! views take their colors from the active theme. `output.theme` in the config
+//! picks a preset and can override individual colors and symbols:
+//!
+//! ```yaml
+//! output:
+//!   theme:
+//!     preset: deuteranopia
+//!     colors:
+//!       info: "#56b4e9"
+//!     symbols:
+//!       success: "✔"
+//! ```
+//!
+//! The `deuteranopia` preset uses colors from the Okabe-Ito palette (blue for
+//! success, orange for warnings, vermillion for errors), which stay distinct
+//! with red-green color blindness.
+
+use std::{borrow::Cow, sync::OnceLock};
+
+use teapot::style::Color;
+
+use super::glyphs;
+use crate::{
+    config::ThemeConfig,
+    error::{Error, Result},
+};
+
+/// Available presets.
+const PRESETS: [&str; 2] = ["default", "deuteranopia"];
+
+/// Theme for the rest of the process.
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// What a status color or symbol signals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// Something worked or is ready.
+    Success,
+    /// Something needs attention or is in progress.
+    Warning,
+    /// Something failed.
+    Error,
+    /// Neutral information.
+    Info,
+}
+
+impl Role {
+    const ALL: [Self; 4] = [Self::Success, Self::Warning, Self::Error, Self::Info];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Info => "info",
+        }
+    }
+
+    fn parse(name: &str) -> Result<Self> {
+        Self::ALL.into_iter().find(|role| role.name() == name).ok_or_else(|| {
+            Error::config(format!(
+                "Unknown theme role '{name}'. Use: success, warning, error, info"
+            ))
+        })
+    }
+}
+
+/// Colors and symbols per [`Role`].
+#[derive(Debug, Clone)]
+pub struct Theme {
+    colors: [Color; 4],
+    /// ANSI foreground escapes for `colors`, computed once.
+    ansi: [String; 4],
+    /// Symbols replacing the default glyphs.
+    symbols: [Option<String>; 4],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new([Color::Green, Color::Yellow, Color::Red, Color::BrightBlack])
+    }
+}
+
+impl Theme {
+    fn new(colors: [Color; 4]) -> Self {
+        let ansi = colors.clone().map(|color| color.to_ansi_fg());
+        Self { colors, ansi, symbols: Default::default() }
+    }
+
+    /// A preset by name.
+    pub fn preset(name: &str) -> Result<Self> {
+        match name {
+            "default" => Ok(Self::default()),
+            "deuteranopia" => Ok(Self::new([
+                Color::Ansi256(32),
+                Color::Ansi256(214),
+                Color::Ansi256(166),
+                Color::BrightBlack,
+            ])),
+            _ => Err(Error::config(format!(
+                "Unknown theme preset '{name}'. Use: {}",
+                PRESETS.join(", ")
+            ))),
+        }
+    }
+
+    /// The theme described by `output.theme`.
+    pub fn from_config(config: &ThemeConfig) -> Result<Self> {
+        let mut theme = Self::preset(config.preset.as_deref().unwrap_or("default"))?;
+        for (role, color) in &config.colors {
+            let index = Role::parse(role)? as usize;
+            theme.colors[index] = parse_color(color)?;
+            theme.ansi[index] = theme.colors[index].to_ansi_fg();
+        }
+        for (role, symbol) in &config.symbols {
+            theme.symbols[Role::parse(role)? as usize] = Some(symbol.clone());
+        }
+        Ok(theme)
+    }
+
+    /// The color for `role`.
+    #[must_use]
+    pub fn color(&self, role: Role) -> Color {
+        self.colors[role as usize].clone()
+    }
+
+    /// The ANSI foreground escape for `role`.
+    #[must_use]
+    pub fn ansi(&self, role: Role) -> &str {
+        &self.ansi[role as usize]
+    }
+
+    /// The symbol for `role`, degraded to ASCII in ASCII mode.
+    #[must_use]
+    pub fn symbol(&self, role: Role) -> Cow<'_, str> {
+        let glyph = match role {
+            Role::Success => glyphs::CHECK,
+            Role::Warning => glyphs::WARNING,
+            Role::Error => glyphs::CROSS,
+            Role::Info => glyphs::BULLET,
+        };
+        self.symbols[role as usize].as_deref().map_or(Cow::Borrowed(glyph.as_str()), glyphs::text)
+    }
+}
+
+/// Use `theme` for the rest of the process. Only the first call takes effect.
+pub fn set(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// The active theme.
+#[must_use]
+pub fn current() -> &'static Theme {
+    THEME.get_or_init(Theme::default)
+}
+
+/// Parse a color name (e.g., `blue`, `bright-black`), `#rrggbb`, or ANSI 256
+/// index.
+fn parse_color(value: &str) -> Result<Color> {
+    if let Ok(index) = value.parse::<u8>() {
+        return Ok(Color::Ansi256(index));
+    }
+    if value.starts_with('#') {
+        return Color::hex(value)
+            .ok_or_else(|| Error::config(format!("Invalid theme color '{value}'")));
+    }
+    let color = match value.to_lowercase().replace(['-', '_'], "").as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "brightblack" | "gray" | "grey" => Color::BrightBlack,
+        "brightred" => Color::BrightRed,
+        "brightgreen" => Color::BrightGreen,
+        "brightyellow" => Color::BrightYellow,
+        "brightblue" => Color::BrightBlue,
+        "brightmagenta" => Color::BrightMagenta,
+        "brightcyan" => Color::BrightCyan,
+        "brightwhite" => Color::BrightWhite,
+        _ => {
+            return Err(Error::config(format!(
+                "Invalid theme color '{value}'. Use a color name, #rrggbb, or 0-255"
+            )));
+        },
+    };
+    Ok(color)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("bright-black").unwrap(), Color::BrightBlack);
+        assert_eq!(parse_color("214").unwrap(), Color::Ansi256(214));
+        assert!(parse_color("#0072b2").is_ok());
+        assert!(parse_color("chartreuse").is_err());
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = ThemeConfig {
+            preset: Some("deuteranopia".to_string()),
+            colors: BTreeMap::from([("info".to_string(), "cyan".to_string())]),
+            symbols: BTreeMap::from([("success".to_string(), "ok".to_string())]),
+        };
+        let theme = Theme::from_config(&config).unwrap();
+        assert_eq!(theme.color(Role::Success), Color::Ansi256(32));
+        assert_eq!(theme.color(Role::Info), Color::Cyan);
+        assert_eq!(theme.ansi(Role::Info), Color::Cyan.to_ansi_fg());
+        assert_eq!(theme.symbol(Role::Success), "ok");
+
+        let unknown = ThemeConfig { preset: Some("sepia".to_string()), ..ThemeConfig::default() };
+        assert!(Theme::from_config(&unknown).is_err());
+        let bad_role = ThemeConfig {
+            colors: BTreeMap::from([("fatal".to_string(), "red".to_string())]),
+            ..ThemeConfig::default()
+        };
+        assert!(Theme::from_config(&bad_role).is_err());
+    }
+}
@@ -0,0 +1,451 @@
+//! Output time zones.
+//!
+//! `--timezone` and a profile's `timezone` name the zone timestamps are shown
+//! in: an IANA name such as `Europe/Berlin` (read from the system zone
+//! database under `$TZDIR` or `/usr/share/zoneinfo`), `UTC`, a fixed offset
+//! such as `+05:30`, or `local` for the machine's own zone.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, Offset, TimeZone as _, Utc};
+
+use crate::error::{Error, Result};
+
+/// Directories searched for `TZif` files after `$TZDIR`.
+const ZONEINFO_DIRS: &[&str] =
+    &["/usr/share/zoneinfo", "/usr/lib/zoneinfo", "/usr/share/lib/zoneinfo", "/etc/zoneinfo"];
+
+/// A named time zone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeZone {
+    name: String,
+    rules: Rules,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rules {
+    /// The machine's local zone.
+    Local,
+    /// A constant offset from UTC, in seconds.
+    Fixed(i32),
+    /// Transitions from a `TZif` file, then its footer rule.
+    Zoneinfo { transitions: Vec<(i64, usize)>, offsets: Vec<i32>, footer: Option<Posix> },
+}
+
+impl TimeZone {
+    /// Look up a zone by IANA name, `UTC`, `local`, or `±HH:MM` offset.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let rules = if spec.eq_ignore_ascii_case("local") {
+            Rules::Local
+        } else if let Some(offset) = parse_fixed(spec) {
+            Rules::Fixed(offset)
+        } else {
+            load_zoneinfo(spec)?
+        };
+        Ok(Self { name: spec.to_string(), rules })
+    }
+
+    /// The name the zone was given by.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The offset from UTC in effect at `time`.
+    #[must_use]
+    pub fn offset_at(&self, time: DateTime<Utc>) -> FixedOffset {
+        let seconds = match &self.rules {
+            Rules::Local => return Local.offset_from_utc_datetime(&time.naive_utc()).fix(),
+            Rules::Fixed(offset) => *offset,
+            Rules::Zoneinfo { transitions, offsets, footer } => {
+                let timestamp = time.timestamp();
+                let after_last = transitions.last().is_none_or(|(at, _)| *at <= timestamp);
+                match (footer, transitions.iter().rposition(|(at, _)| *at <= timestamp)) {
+                    (Some(rule), _) if after_last => rule.offset_at(timestamp),
+                    (_, Some(index)) => offsets.get(transitions[index].1).copied().unwrap_or(0),
+                    // Times before the first transition use the first type
+                    (_, None) => offsets.first().copied().unwrap_or(0),
+                }
+            },
+        };
+        FixedOffset::east_opt(seconds).unwrap_or_else(|| Utc.fix())
+    }
+}
+
+/// Parse `UTC`, `Z`, or an offset such as `+05:30`, `-0800`, or `UTC+2`.
+fn parse_fixed(spec: &str) -> Option<i32> {
+    if ["UTC", "Z", "GMT"].iter().any(|utc| spec.eq_ignore_ascii_case(utc)) {
+        return Some(0);
+    }
+    let offset = ["UTC", "GMT", "utc", "gmt"]
+        .iter()
+        .find_map(|prefix| spec.strip_prefix(prefix))
+        .unwrap_or(spec);
+    let (sign, digits) = match offset.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if digits.len() == 4 => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let hours: i32 = hours.parse().ok().filter(|h| (0..=14).contains(h))?;
+    let minutes: i32 = minutes.parse().ok().filter(|m| (0..60).contains(m))?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Read a zone from the system zone database.
+fn load_zoneinfo(name: &str) -> Result<Rules> {
+    let unknown = || {
+        Error::invalid_arg(format!(
+            "Unknown timezone '{name}'. Use an IANA name (e.g., Europe/Berlin), UTC, local, or \
+             an offset like +05:30"
+        ))
+    };
+    let valid = !name.is_empty()
+        && !name.starts_with('/')
+        && name.split('/').all(|part| !part.is_empty() && part != "." && part != "..")
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "/_+-".contains(c));
+    if !valid {
+        return Err(unknown());
+    }
+
+    let dirs = std::env::var_os("TZDIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(ZONEINFO_DIRS.iter().map(PathBuf::from));
+    let data = dirs.map(|dir| dir.join(name)).find_map(|path| std::fs::read(path).ok());
+    let data = data.ok_or_else(unknown)?;
+    parse_tzif(&data)
+        .ok_or_else(|| Error::config(format!("Timezone '{name}' has an unreadable TZif file")))
+}
+
+/// Parse a `TZif` file (RFC 8536), keeping transitions, offsets, and the footer.
+fn parse_tzif(data: &[u8]) -> Option<Rules> {
+    let header = Header::read(data)?;
+    let (header, body, time_size) = if header.version >= b'2' {
+        let rest = data.get(44 + header.block_len(4)..)?;
+        (Header::read(rest)?, rest, 8)
+    } else {
+        (header, data, 4)
+    };
+    let mut pos = 44;
+    let mut take = |len: usize| {
+        let slice = body.get(pos..pos + len);
+        pos += len;
+        slice
+    };
+
+    let times = take(header.timecnt * time_size)?;
+    let indices = take(header.timecnt)?;
+    let types = take(header.typecnt * 6)?;
+    let transitions = times
+        .chunks(time_size)
+        .zip(indices)
+        .map(|(time, &index)| {
+            let at = match time_size {
+                8 => i64::from_be_bytes(time.try_into().ok()?),
+                _ => i64::from(i32::from_be_bytes(time.try_into().ok()?)),
+            };
+            (usize::from(index) < header.typecnt).then_some((at, usize::from(index)))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let offsets = types
+        .chunks(6)
+        .map(|ttinfo| i32::from_be_bytes([ttinfo[0], ttinfo[1], ttinfo[2], ttinfo[3]]))
+        .collect();
+
+    let trailer =
+        body.get(pos + header.charcnt + header.leapcnt * 12 + header.isstdcnt + header.isutcnt..);
+    let footer = trailer
+        .filter(|_| time_size == 8)
+        .and_then(|trailer| std::str::from_utf8(trailer).ok())
+        .and_then(|trailer| trailer.trim().lines().next())
+        .filter(|tz| !tz.is_empty())
+        .and_then(Posix::parse);
+    Some(Rules::Zoneinfo { transitions, offsets, footer })
+}
+
+/// `TZif` header counts.
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl Header {
+    fn read(data: &[u8]) -> Option<Self> {
+        if data.get(..4)? != b"TZif" {
+            return None;
+        }
+        let count = |index: usize| {
+            let start = 20 + index * 4;
+            let bytes: [u8; 4] = data.get(start..start + 4)?.try_into().ok()?;
+            usize::try_from(u32::from_be_bytes(bytes)).ok()
+        };
+        Some(Self {
+            version: *data.get(4)?,
+            isutcnt: count(0)?,
+            isstdcnt: count(1)?,
+            leapcnt: count(2)?,
+            timecnt: count(3)?,
+            typecnt: count(4)?,
+            charcnt: count(5)?,
+        })
+    }
+
+    /// Length of the data block with `time_size`-byte times.
+    const fn block_len(&self, time_size: usize) -> usize {
+        self.timecnt * time_size
+            + self.timecnt
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_size + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+/// A POSIX TZ rule, as found in `TZif` footers (e.g.,
+/// `CET-1CEST,M3.5.0,M10.5.0/3`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Posix {
+    /// Standard offset east of UTC, in seconds.
+    std_offset: i32,
+    /// Daylight saving offset and when it starts and ends, if observed.
+    dst: Option<(i32, Transition, Transition)>,
+}
+
+/// When daylight saving starts or ends: a day rule and local time of day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Transition {
+    day: DayRule,
+    /// Seconds after local midnight (may be negative or past 24 hours).
+    time: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DayRule {
+    /// `Jn`: day 1 to 365, never counting February 29.
+    Julian(u16),
+    /// `n`: zero-based day of the year, counting February 29.
+    Ordinal(u16),
+    /// `Mm.w.d`: day `d` (0 = Sunday) of week `w` (5 = last) of month `m`.
+    MonthWeekDay(u32, u32, u32),
+}
+
+impl Posix {
+    fn parse(spec: &str) -> Option<Self> {
+        let mut rest = spec;
+        skip_name(&mut rest)?;
+        // POSIX offsets count hours west of UTC
+        let std_offset = -parse_seconds(&mut rest)?;
+        if rest.is_empty() {
+            return Some(Self { std_offset, dst: None });
+        }
+
+        skip_name(&mut rest)?;
+        let dst_offset = if rest.starts_with(',') || rest.is_empty() {
+            std_offset + 3600
+        } else {
+            -parse_seconds(&mut rest)?
+        };
+        let (start, end) = if rest.is_empty() {
+            // The POSIX default is the US rule
+            let start = Transition { day: DayRule::MonthWeekDay(3, 2, 0), time: 7200 };
+            (start, Transition { day: DayRule::MonthWeekDay(11, 1, 0), time: 7200 })
+        } else {
+            rest = rest.strip_prefix(',')?;
+            let start = Transition::parse(&mut rest)?;
+            rest = rest.strip_prefix(',')?;
+            (start, Transition::parse(&mut rest)?)
+        };
+        rest.is_empty().then_some(Self { std_offset, dst: Some((dst_offset, start, end)) })
+    }
+
+    /// Offset east of UTC, in seconds, at `timestamp`.
+    fn offset_at(&self, timestamp: i64) -> i32 {
+        let Some((dst_offset, start, end)) = self.dst else {
+            return self.std_offset;
+        };
+        let year = DateTime::from_timestamp(timestamp + i64::from(self.std_offset), 0)
+            .map_or(1970, |local| local.year());
+        // Starts are given in standard time and ends in daylight time
+        let starts = start.utc_timestamp(year, self.std_offset);
+        let ends = end.utc_timestamp(year, dst_offset);
+        let in_dst = if starts <= ends {
+            starts <= timestamp && timestamp < ends
+        } else {
+            timestamp < ends || starts <= timestamp
+        };
+        if in_dst { dst_offset } else { self.std_offset }
+    }
+}
+
+impl Transition {
+    fn parse(rest: &mut &str) -> Option<Self> {
+        let day = if let Some(spec) = rest.strip_prefix('M') {
+            *rest = spec;
+            let month = take_number(rest)?;
+            *rest = rest.strip_prefix('.')?;
+            let week = take_number(rest)?;
+            *rest = rest.strip_prefix('.')?;
+            let weekday = take_number(rest)?;
+            let valid = (1..=12).contains(&month) && (1..=5).contains(&week) && weekday <= 6;
+            valid.then_some(DayRule::MonthWeekDay(month, week, weekday))?
+        } else if let Some(spec) = rest.strip_prefix('J') {
+            *rest = spec;
+            let day = u16::try_from(take_number(rest)?).ok().filter(|d| (1..=365).contains(d))?;
+            DayRule::Julian(day)
+        } else {
+            DayRule::Ordinal(u16::try_from(take_number(rest)?).ok().filter(|d| *d <= 365)?)
+        };
+        let time = match rest.strip_prefix('/') {
+            Some(spec) => {
+                *rest = spec;
+                parse_seconds(rest)?
+            },
+            None => 7200,
+        };
+        Some(Self { day, time })
+    }
+
+    /// The transition in `year` as a UTC timestamp, for local time at `offset`.
+    fn utc_timestamp(self, year: i32, offset: i32) -> i64 {
+        let date = match self.day {
+            DayRule::Julian(day) => {
+                let leap = NaiveDate::from_ymd_opt(year, 2, 29).is_some();
+                let ordinal = u32::from(day) + u32::from(leap && day >= 60);
+                NaiveDate::from_yo_opt(year, ordinal)
+            },
+            DayRule::Ordinal(day) => NaiveDate::from_yo_opt(year, u32::from(day) + 1),
+            DayRule::MonthWeekDay(month, week, weekday) => NaiveDate::from_ymd_opt(year, month, 1)
+                .and_then(|first| {
+                    let first_weekday = first.weekday().num_days_from_sunday();
+                    let mut day = 1 + (weekday + 7 - first_weekday) % 7 + 7 * (week - 1);
+                    while NaiveDate::from_ymd_opt(year, month, day).is_none() {
+                        day -= 7;
+                    }
+                    NaiveDate::from_ymd_opt(year, month, day)
+                }),
+        };
+        let midnight =
+            date.and_then(|d| d.and_hms_opt(0, 0, 0)).map_or(0, |d| d.and_utc().timestamp());
+        midnight + i64::from(self.time) - i64::from(offset)
+    }
+}
+
+/// Skip a zone abbreviation: letters, or anything inside `<...>`.
+fn skip_name(rest: &mut &str) -> Option<()> {
+    let len = if let Some(quoted) = rest.strip_prefix('<') {
+        quoted.find('>')? + 2
+    } else {
+        rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len())
+    };
+    if len < 3 {
+        return None;
+    }
+    *rest = &rest[len..];
+    Some(())
+}
+
+/// Read `[+-]hh[:mm[:ss]]` as seconds.
+fn parse_seconds(rest: &mut &str) -> Option<i32> {
+    let sign = match rest.chars().next() {
+        Some('-') => -1,
+        _ => 1,
+    };
+    *rest = rest.trim_start_matches(['+', '-']);
+    let mut seconds = 0;
+    for (index, unit) in [3600, 60, 1].into_iter().enumerate() {
+        if index > 0 {
+            let Some(spec) = rest.strip_prefix(':') else { break };
+            *rest = spec;
+        }
+        seconds += i32::try_from(take_number(rest)?).ok()? * unit;
+    }
+    Some(sign * seconds)
+}
+
+/// Read a run of ASCII digits.
+fn take_number(rest: &mut &str) -> Option<u32> {
+    let len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let number = rest[..len].parse().ok()?;
+    *rest = &rest[len..];
+    Some(number)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_parse_fixed() {
+        assert_eq!(parse_fixed("UTC"), Some(0));
+        assert_eq!(parse_fixed("+05:30"), Some(19_800));
+        assert_eq!(parse_fixed("-0800"), Some(-28_800));
+        assert_eq!(parse_fixed("UTC+2"), Some(7_200));
+        assert_eq!(parse_fixed("Europe/Berlin"), None);
+        assert_eq!(parse_fixed("+25:00"), None);
+        assert!(TimeZone::parse("../etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_posix_rules() {
+        let new_york = Posix::parse("EST5EDT,M3.2.0,M11.1.0").unwrap();
+        assert_eq!(new_york.offset_at(at("2026-01-15T12:00:00Z").timestamp()), -5 * 3600);
+        assert_eq!(new_york.offset_at(at("2026-07-01T12:00:00Z").timestamp()), -4 * 3600);
+        // DST starts 2026-03-08 at 02:00 EST
+        assert_eq!(new_york.offset_at(at("2026-03-08T06:59:59Z").timestamp()), -5 * 3600);
+        assert_eq!(new_york.offset_at(at("2026-03-08T07:00:00Z").timestamp()), -4 * 3600);
+
+        let sydney = Posix::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(sydney.offset_at(at("2026-01-15T00:00:00Z").timestamp()), 11 * 3600);
+        assert_eq!(sydney.offset_at(at("2026-07-01T00:00:00Z").timestamp()), 10 * 3600);
+
+        let kolkata = Posix::parse("IST-5:30").unwrap();
+        assert_eq!(kolkata.offset_at(0), 19_800);
+        assert_eq!(Posix::parse("<-03>3").unwrap().offset_at(0), -3 * 3600);
+    }
+
+    #[test]
+    fn test_parse_tzif() {
+        // A v2 file with one transition to CET and a CET/CEST footer
+        let counts = |timecnt: u32, typecnt: u32, charcnt: u32| {
+            let mut header = b"TZif2".to_vec();
+            header.extend([0; 15]);
+            for count in [0, 0, 0, timecnt, typecnt, charcnt] {
+                header.extend(u32::to_be_bytes(count));
+            }
+            header
+        };
+        let mut data = counts(0, 1, 4);
+        data.extend([0, 0, 0, 0, 0, 0]);
+        data.extend(b"LMT\0");
+        data.extend(counts(1, 2, 8));
+        data.extend(i64::to_be_bytes(-2_422_054_408));
+        data.push(1);
+        data.extend([0, 0, 3, 88, 0, 0]);
+        data.extend([0, 0, 14, 16, 0, 4]);
+        data.extend(b"LMT\0CET\0");
+        data.extend(b"\nCET-1CEST,M3.5.0,M10.5.0/3\n");
+
+        let zone =
+            TimeZone { name: "Europe/Berlin".to_string(), rules: parse_tzif(&data).unwrap() };
+        assert_eq!(zone.offset_at(at("1800-01-01T00:00:00Z")).local_minus_utc(), 856);
+        assert_eq!(zone.offset_at(at("2026-01-15T12:00:00Z")).local_minus_utc(), 3600);
+        assert_eq!(zone.offset_at(at("2026-07-01T12:00:00Z")).local_minus_utc(), 7200);
+    }
+}
@@ -0,0 +1,195 @@
+//! Per-step timing summaries for multi-step commands.
+//!
+//! Long-running commands such as `dev start` or `import` are made up of many
+//! steps. When `--timings` is passed, each step records its duration and
+//! status here, and a summary is printed once the command finishes so slow
+//! steps are easy to spot.
+//!
+//! Recording is process-wide and a no-op unless a collection is active, so
+//! step helpers can call [`record`] unconditionally.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use teapot::{
+    components::{Column, Table},
+    output as toutput,
+};
+
+use crate::error::{Error, Result};
+
+/// Active timing collection, if `--timings` was requested.
+static ACTIVE: Mutex<Option<Timings>> = Mutex::new(None);
+
+/// Format for the timing summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimingsFormat {
+    /// Human-readable table.
+    Table,
+    /// JSON document.
+    Json,
+}
+
+impl TimingsFormat {
+    /// Parse a timings format from a string.
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::invalid_arg(format!("Unknown timings format '{s}'. Use: table, json"))),
+        }
+    }
+}
+
+/// Duration and outcome of a single step.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepTiming {
+    /// Step name (e.g., "Provisioned Talos cluster").
+    pub name: String,
+    /// Step status (OK, SKIPPED, FAILED).
+    pub status: String,
+    /// Step duration in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Collected step timings for one command invocation.
+#[derive(Debug, Clone, Serialize)]
+pub struct Timings {
+    /// Recorded steps, in execution order.
+    pub steps: Vec<StepTiming>,
+    /// Total wall-clock time in milliseconds.
+    pub total_ms: u64,
+    #[serde(skip)]
+    started: Instant,
+}
+
+impl Timings {
+    fn new() -> Self {
+        Self { steps: Vec::new(), total_ms: 0, started: Instant::now() }
+    }
+
+    /// Print the summary to stderr in the given format.
+    ///
+    /// Stderr keeps the summary out of data written to stdout (e.g., `export`
+    /// without `--output`).
+    pub fn print(&self, format: TimingsFormat, color: bool) -> Result<()> {
+        match format {
+            TimingsFormat::Json => {
+                eprintln!("{}", serde_json::to_string_pretty(self)?);
+            },
+            TimingsFormat::Table => {
+                let mut rows: Vec<Vec<String>> = self
+                    .steps
+                    .iter()
+                    .map(|s| {
+                        vec![
+                            s.name.clone(),
+                            s.status.clone(),
+                            format_duration(Duration::from_millis(s.duration_ms)),
+                        ]
+                    })
+                    .collect();
+                rows.push(vec![
+                    "Total".to_string(),
+                    String::new(),
+                    format_duration(Duration::from_millis(self.total_ms)),
+                ]);
+
+                let table = Table::new()
+                    .columns(vec![Column::new("STEP"), Column::new("STATUS"), Column::new("TIME")])
+                    .rows(rows)
+                    .show_borders(false)
+                    .focused(false);
+
+                let output = table.render();
+                eprintln!();
+                if color {
+                    eprintln!("{output}");
+                } else {
+                    eprintln!("{}", toutput::strip_ansi(&output));
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Start collecting step timings.
+pub fn begin() {
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = Some(Timings::new());
+    }
+}
+
+/// Record a completed step. Does nothing unless collection is active.
+pub fn record(name: &str, status: &str, duration: Duration) {
+    if let Ok(mut active) = ACTIVE.lock()
+        && let Some(timings) = active.as_mut()
+    {
+        timings.steps.push(StepTiming {
+            name: name.to_string(),
+            status: status.to_uppercase(),
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+}
+
+/// Stop collecting and return the recorded timings.
+pub fn finish() -> Option<Timings> {
+    let mut timings = ACTIVE.lock().ok()?.take()?;
+    timings.total_ms = timings.started.elapsed().as_millis() as u64;
+    Some(timings)
+}
+
+/// Format a duration compactly (e.g., "850ms", "12.4s", "3m 05s").
+pub fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    if millis < 1000 {
+        format!("{millis}ms")
+    } else if millis < 60_000 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        let secs = duration.as_secs();
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings_format_parse() {
+        assert_eq!(TimingsFormat::parse("table").unwrap(), TimingsFormat::Table);
+        assert_eq!(TimingsFormat::parse("JSON").unwrap(), TimingsFormat::Json);
+        assert!(TimingsFormat::parse("csv").is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(850)), "850ms");
+        assert_eq!(format_duration(Duration::from_millis(12_400)), "12.4s");
+        assert_eq!(format_duration(Duration::from_secs(185)), "3m 05s");
+    }
+
+    #[test]
+    fn test_record_collects_only_when_active() {
+        record("ignored", "OK", Duration::from_millis(5));
+        assert!(finish().is_none());
+
+        begin();
+        record("Cloned repository", "ok", Duration::from_millis(20));
+        record("Built images", "skipped", Duration::from_millis(1));
+        let timings = finish().unwrap();
+
+        assert_eq!(timings.steps.len(), 2);
+        assert_eq!(timings.steps[0].name, "Cloned repository");
+        assert_eq!(timings.steps[0].status, "OK");
+        assert_eq!(timings.steps[1].status, "SKIPPED");
+        assert!(finish().is_none());
+    }
+}
@@ -0,0 +1,101 @@
+//! Structured advisory warnings.
+//!
+//! Advisories are reported through [`Output::warning`](super::Output::warning)
+//! with a stable code, so they can be silenced with `--suppress-warnings
+//! <codes>` (or `output.suppress_warnings` in the config) and read by scripts:
+//! with `--output json` or `yaml` they are added to the command's result as a
+//! `warnings` array instead of being printed.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+/// The session token expires soon.
+pub const TOKEN_EXPIRING: &str = "token_expiring";
+
+/// A `defaults:` config key was skipped because it doesn't apply.
+pub const INVALID_DEFAULT: &str = "invalid_default";
+
+/// Code that suppresses every warning.
+const ALL: &str = "all";
+
+/// Codes silenced for this process.
+static SUPPRESSED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Warnings waiting to be added to the command's structured result.
+static PENDING: Mutex<Vec<Warning>> = Mutex::new(Vec::new());
+
+/// An advisory warning.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    /// Stable identifier, used to suppress the warning.
+    pub code: String,
+    /// Human-readable message.
+    pub message: String,
+}
+
+/// Silence warnings with these codes (`all` silences every warning).
+pub fn suppress<I: IntoIterator<Item = String>>(codes: I) {
+    if let Ok(mut suppressed) = SUPPRESSED.lock() {
+        suppressed.extend(codes.into_iter().map(|code| code.trim().to_string()));
+    }
+}
+
+/// Whether warnings with `code` are silenced.
+#[must_use]
+pub fn is_suppressed(code: &str) -> bool {
+    SUPPRESSED.lock().is_ok_and(|suppressed| suppressed.iter().any(|s| s == code || s == ALL))
+}
+
+/// Hold a warning for the command's structured result.
+pub fn defer(code: &str, message: &str) {
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.push(Warning { code: code.to_string(), message: message.to_string() });
+    }
+}
+
+/// A result with the pending warnings added as a `warnings` array.
+///
+/// Returns `None`, leaving the warnings pending, when nothing is pending or
+/// the result is not a JSON object.
+pub fn annotate<T: Serialize + ?Sized>(result: &T) -> Option<serde_json::Value> {
+    let pending = PENDING.lock().ok()?.clone();
+    if pending.is_empty() {
+        return None;
+    }
+    let mut value = serde_json::to_value(result).ok()?;
+    value.as_object_mut()?.insert("warnings".to_string(), serde_json::json!(pending));
+    // Warnings deferred since the copy stay pending
+    if let Ok(mut rest) = PENDING.lock() {
+        rest.drain(..pending.len());
+    }
+    Some(value)
+}
+
+/// Print warnings that no result carried to stderr, as JSON.
+pub fn flush() {
+    let rest = PENDING.lock().map(|mut pending| std::mem::take(&mut *pending)).unwrap_or_default();
+    if !rest.is_empty() {
+        eprintln!("{}", serde_json::json!({ "warnings": rest }));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suppress_and_annotate() {
+        suppress(["schema_drift ".to_string()]);
+        assert!(is_suppressed("schema_drift"));
+        assert!(!is_suppressed(TOKEN_EXPIRING));
+
+        // Lists are left alone; objects carry the warning
+        defer(TOKEN_EXPIRING, "Token expires in 5m");
+        assert!(annotate(&[1, 2]).is_none());
+        let annotated = annotate(&serde_json::json!({ "id": "v1" })).unwrap();
+        assert_eq!(annotated["warnings"][0]["code"], TOKEN_EXPIRING);
+        assert!(annotate(&serde_json::json!({})).is_none());
+    }
+}
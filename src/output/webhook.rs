@@ -0,0 +1,188 @@
+//! Webhook notifications for long-running commands.
+//!
+//! With `--notify-webhook <url>` (or `notify.webhook` in the config), imports,
+//! bulk writes, schema pushes, schema tests and migration plans post a summary
+//! card when they finish or fail. Slack and Microsoft Teams incoming webhooks get their
+//! native card format; any other URL receives plain JSON.
+
+use std::time::Duration;
+
+use serde_json::{Value, json};
+
+use crate::error::{Error, Result};
+
+/// How long to wait for the webhook before giving up.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Payload format a webhook expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookKind {
+    /// Slack incoming webhook (Block Kit).
+    Slack,
+    /// Microsoft Teams incoming webhook (`MessageCard`).
+    Teams,
+    /// Anything else: a flat JSON object.
+    Generic,
+}
+
+impl WebhookKind {
+    /// Detect the format from the webhook host.
+    #[must_use]
+    pub fn detect(url: &str) -> Self {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        if host == "hooks.slack.com" {
+            Self::Slack
+        } else if host.ends_with(".webhook.office.com")
+            || host == "outlook.office.com"
+            || host.ends_with(".logic.azure.com")
+        {
+            Self::Teams
+        } else {
+            Self::Generic
+        }
+    }
+}
+
+/// Outcome of a command, as posted to the webhook.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    /// Command that ran (e.g., `import prod.json`).
+    pub operation: String,
+    /// Whether the command succeeded.
+    pub succeeded: bool,
+    /// Labelled facts shown on the card (profile, vault, duration).
+    pub facts: Vec<(String, String)>,
+    /// Error message, when the command failed.
+    pub error: Option<String>,
+}
+
+impl Summary {
+    fn title(&self) -> String {
+        let status = if self.succeeded { "completed" } else { "failed" };
+        format!("inferadb {} {status}", self.operation)
+    }
+
+    /// Build the request body for a webhook format.
+    #[must_use]
+    pub fn payload(&self, kind: WebhookKind) -> Value {
+        let title = self.title();
+        match kind {
+            WebhookKind::Slack => {
+                let fields: Vec<Value> = self
+                    .facts
+                    .iter()
+                    .map(|(name, value)| {
+                        json!({"type": "mrkdwn", "text": format!("*{name}*\n{value}")})
+                    })
+                    .collect();
+                let mut blocks = vec![
+                    json!({"type": "header", "text": {"type": "plain_text", "text": title}}),
+                    json!({"type": "section", "fields": fields}),
+                ];
+                if let Some(error) = &self.error {
+                    blocks.push(json!({
+                        "type": "section",
+                        "text": {"type": "mrkdwn", "text": format!("```{error}```")}
+                    }));
+                }
+                json!({"text": title, "blocks": blocks})
+            },
+            WebhookKind::Teams => {
+                let mut facts: Vec<Value> = self
+                    .facts
+                    .iter()
+                    .map(|(name, value)| json!({"name": name, "value": value}))
+                    .collect();
+                if let Some(error) = &self.error {
+                    facts.push(json!({"name": "Error", "value": error}));
+                }
+                json!({
+                    "@type": "MessageCard",
+                    "@context": "https://schema.org/extensions",
+                    "themeColor": if self.succeeded { "2EB67D" } else { "E01E5A" },
+                    "summary": title,
+                    "title": title,
+                    "sections": [{"facts": facts}],
+                })
+            },
+            WebhookKind::Generic => {
+                let facts: serde_json::Map<String, Value> = self
+                    .facts
+                    .iter()
+                    .map(|(name, value)| (name.to_lowercase(), Value::String(value.clone())))
+                    .collect();
+                json!({
+                    "title": title,
+                    "operation": self.operation,
+                    "status": if self.succeeded { "succeeded" } else { "failed" },
+                    "details": facts,
+                    "error": self.error,
+                })
+            },
+        }
+    }
+}
+
+/// Post a summary to a webhook.
+pub async fn post(url: &str, summary: &Summary) -> Result<()> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(TIMEOUT)
+        .json(&summary.payload(WebhookKind::detect(url)))
+        .send()
+        .await
+        .map_err(|e| Error::other(format!("Webhook request failed: {e}")))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(Error::other(format!("Webhook returned HTTP {status}")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(succeeded: bool) -> Summary {
+        Summary {
+            operation: "import prod.json".to_string(),
+            succeeded,
+            facts: vec![("Vault".to_string(), "acme/prod".to_string())],
+            error: (!succeeded).then(|| "Permission denied".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_detect_webhook_kind() {
+        assert_eq!(
+            WebhookKind::detect("https://hooks.slack.com/services/T0/B0/x"),
+            WebhookKind::Slack
+        );
+        assert_eq!(
+            WebhookKind::detect("https://acme.webhook.office.com/webhookb2/abc"),
+            WebhookKind::Teams
+        );
+        assert_eq!(WebhookKind::detect("https://example.com/hook"), WebhookKind::Generic);
+        assert_eq!(WebhookKind::detect("not a url"), WebhookKind::Generic);
+    }
+
+    #[test]
+    fn test_payloads() {
+        let slack = summary(false).payload(WebhookKind::Slack);
+        assert_eq!(slack["text"], "inferadb import prod.json failed");
+        assert_eq!(slack["blocks"].as_array().map(Vec::len), Some(3));
+
+        let teams = summary(true).payload(WebhookKind::Teams);
+        assert_eq!(teams["themeColor"], "2EB67D");
+        assert_eq!(teams["sections"][0]["facts"][0]["value"], "acme/prod");
+
+        let generic = summary(false).payload(WebhookKind::Generic);
+        assert_eq!(generic["status"], "failed");
+        assert_eq!(generic["details"]["vault"], "acme/prod");
+        assert_eq!(generic["error"], "Permission denied");
+    }
+}
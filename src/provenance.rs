@@ -0,0 +1,394 @@
+//! Build provenance and software bill of materials.
+//!
+//! Build metadata is recorded by `build.rs`. The SBOM is a `CycloneDX` 1.5
+//! document listing every package in the embedded `Cargo.lock` reachable from
+//! the CLI's runtime dependencies, for every target platform.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+const CARGO_LOCK: &str = include_str!("../Cargo.lock");
+const CARGO_TOML: &str = include_str!("../Cargo.toml");
+
+/// Build metadata recorded at compile time.
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    /// Package name.
+    pub name: &'static str,
+    /// Package version.
+    pub version: &'static str,
+    /// Git commit, suffixed with `-dirty` for uncommitted changes.
+    pub git_sha: Option<&'static str>,
+    /// Build time (`SOURCE_DATE_EPOCH` when set).
+    pub build_date: Option<DateTime<Utc>>,
+    /// Compiler version.
+    pub rustc: Option<&'static str>,
+    /// Target triple.
+    pub target: Option<&'static str>,
+    /// Cargo profile.
+    pub profile: Option<&'static str>,
+    /// Enabled Cargo features.
+    pub features: Vec<&'static str>,
+}
+
+impl BuildInfo {
+    /// Metadata for the running binary.
+    pub fn current() -> Self {
+        let non_empty = |value: &'static str| Some(value).filter(|v| !v.is_empty());
+        Self {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: non_empty(env!("INFERADB_BUILD_GIT_SHA")),
+            build_date: env!("INFERADB_BUILD_TIMESTAMP")
+                .parse()
+                .ok()
+                .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+            rustc: non_empty(env!("INFERADB_BUILD_RUSTC")),
+            target: non_empty(env!("INFERADB_BUILD_TARGET")),
+            profile: non_empty(env!("INFERADB_BUILD_PROFILE")),
+            features: env!("INFERADB_BUILD_FEATURES")
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// A `[[package]]` entry from `Cargo.lock`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+    checksum: Option<String>,
+    /// Dependency specs: `name` or `name version`.
+    dependencies: Vec<String>,
+}
+
+impl LockedPackage {
+    fn purl(&self) -> String {
+        format!("pkg:cargo/{}@{}", self.name, self.version)
+    }
+}
+
+/// Parse the `[[package]]` entries of a `Cargo.lock` file.
+fn parse_lock(lock: &str) -> Vec<LockedPackage> {
+    let mut packages = Vec::new();
+    let mut current: Option<LockedPackage> = None;
+    let mut in_dependencies = false;
+
+    let unquote = |value: &str| value.trim().trim_matches('"').to_string();
+
+    for line in lock.lines() {
+        let line = line.trim();
+        if line == "[[package]]" {
+            packages.extend(current.take());
+            current = Some(LockedPackage::default());
+            in_dependencies = false;
+            continue;
+        }
+        let Some(package) = current.as_mut() else {
+            continue;
+        };
+        if in_dependencies {
+            if line == "]" {
+                in_dependencies = false;
+            } else {
+                package.dependencies.push(unquote(line.trim_end_matches(',')));
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once(" = ") else {
+            continue;
+        };
+        match key {
+            "name" => package.name = unquote(value),
+            "version" => package.version = unquote(value),
+            "source" => package.source = Some(unquote(value)),
+            "checksum" => package.checksum = Some(unquote(value)),
+            "dependencies" => in_dependencies = value.trim() == "[",
+            _ => {},
+        }
+    }
+    packages.extend(current);
+    packages
+}
+
+/// Names under `[dev-dependencies]` that are not also runtime dependencies.
+fn dev_only_dependencies(manifest: &str) -> BTreeSet<String> {
+    let mut sections: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+    let mut section = "";
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            section = line;
+        } else if let Some((key, _)) = line.split_once('=')
+            && !line.starts_with('#')
+        {
+            sections.entry(section).or_default().insert(key.trim().to_string());
+        }
+    }
+    let runtime = sections.remove("[dependencies]").unwrap_or_default();
+    sections
+        .remove("[dev-dependencies]")
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| !runtime.contains(name))
+        .collect()
+}
+
+/// Build a `CycloneDX` 1.5 SBOM from a lockfile and manifest.
+fn cyclonedx(info: &BuildInfo, lock: &str, manifest: &str) -> Value {
+    let packages = parse_lock(lock);
+    let dev_only = dev_only_dependencies(manifest);
+
+    // Resolve `name` / `name version` dependency specs to package indexes.
+    let resolve = |spec: &str| {
+        let mut parts = spec.split(' ');
+        let name = parts.next().unwrap_or_default();
+        let version = parts.next();
+        packages.iter().position(|p| p.name == name && version.is_none_or(|v| p.version == v))
+    };
+
+    let root = packages.iter().position(|p| p.name == info.name && p.source.is_none());
+    let mut reachable = BTreeSet::new();
+    let mut queue: Vec<usize> = root
+        .map(|i| {
+            packages[i]
+                .dependencies
+                .iter()
+                .filter(|spec| !dev_only.contains(spec.split(' ').next().unwrap_or_default()))
+                .filter_map(|spec| resolve(spec))
+                .collect()
+        })
+        .unwrap_or_default();
+    while let Some(i) = queue.pop() {
+        if reachable.insert(i) {
+            queue.extend(packages[i].dependencies.iter().filter_map(|spec| resolve(spec)));
+        }
+    }
+
+    let root_ref = format!("pkg:cargo/{}@{}", info.name, info.version);
+    let components: Vec<Value> = reachable
+        .iter()
+        .map(|&i| {
+            let package = &packages[i];
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": package.purl(),
+                "name": package.name,
+                "version": package.version,
+                "purl": package.purl(),
+            });
+            if let Some(checksum) = &package.checksum {
+                component["hashes"] = json!([{ "alg": "SHA-256", "content": checksum }]);
+            }
+            component
+        })
+        .collect();
+
+    let dependencies: Vec<Value> = root
+        .into_iter()
+        .chain(reachable.iter().copied())
+        .map(|i| {
+            let package = &packages[i];
+            let depends_on: BTreeSet<String> = package
+                .dependencies
+                .iter()
+                .filter_map(|spec| resolve(spec))
+                .filter(|d| reachable.contains(d))
+                .map(|d| packages[d].purl())
+                .collect();
+            json!({ "ref": package.purl(), "dependsOn": depends_on })
+        })
+        .collect();
+
+    let mut properties = vec![];
+    for (name, value) in [
+        ("git_sha", info.git_sha),
+        ("rustc", info.rustc),
+        ("target", info.target),
+        ("profile", info.profile),
+    ] {
+        if let Some(value) = value {
+            properties.push(json!({ "name": format!("inferadb:build:{name}"), "value": value }));
+        }
+    }
+    properties.push(json!({ "name": "inferadb:build:features", "value": info.features.join(",") }));
+
+    let mut metadata = json!({
+        "tools": {
+            "components": [{ "type": "application", "name": info.name, "version": info.version }]
+        },
+        "component": {
+            "type": "application",
+            "bom-ref": root_ref,
+            "name": info.name,
+            "version": info.version,
+            "purl": root_ref,
+            "licenses": [{ "expression": env!("CARGO_PKG_LICENSE") }],
+            "externalReferences": [{ "type": "vcs", "url": env!("CARGO_PKG_REPOSITORY") }],
+        },
+        "properties": properties,
+    });
+    if let Some(date) = info.build_date {
+        metadata["timestamp"] = json!(date.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+    }
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": serial_number(info, lock),
+        "version": 1,
+        "metadata": metadata,
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+/// A UUID URN derived from the build, so identical builds share a serial.
+fn serial_number(info: &BuildInfo, lock: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(info.version);
+    hasher.update(info.git_sha.unwrap_or_default());
+    hasher.update(lock);
+    let mut bytes: [u8; 16] = hasher.finalize()[..16].try_into().unwrap_or_default();
+    bytes[6] = (bytes[6] & 0x0f) | 0x80; // version 8 (custom)
+    bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+    let hex = bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    });
+    format!(
+        "urn:uuid:{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// The `CycloneDX` SBOM for the running binary.
+pub fn sbom() -> Value {
+    cyclonedx(&BuildInfo::current(), CARGO_LOCK, CARGO_TOML)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const LOCK: &str = r#"
+version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "assert_cmd",
+ "serde",
+ "tokio 1.0.0",
+]
+
+[[package]]
+name = "assert_cmd"
+version = "2.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "abc123"
+
+[[package]]
+name = "tokio"
+version = "0.2.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "tokio"
+version = "1.0.0"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "serde",
+]
+"#;
+
+    const MANIFEST: &str = r#"
+[dependencies]
+serde = "1"
+tokio = { version = "1", features = ["full"] }
+
+[dev-dependencies]
+# comment = "ignored"
+tokio = { version = "1", features = ["test-util"] }
+assert_cmd = "2"
+"#;
+
+    fn info() -> BuildInfo {
+        BuildInfo {
+            name: "app",
+            version: "0.1.0",
+            git_sha: Some("deadbeef"),
+            build_date: DateTime::from_timestamp(0, 0),
+            rustc: None,
+            target: Some("x86_64-unknown-linux-gnu"),
+            profile: None,
+            features: vec![],
+        }
+    }
+
+    #[test]
+    fn test_parse_lock() {
+        let packages = parse_lock(LOCK);
+        assert_eq!(packages.len(), 5);
+        assert_eq!(packages[0].name, "app");
+        assert_eq!(packages[0].source, None);
+        assert_eq!(packages[0].dependencies, vec!["assert_cmd", "serde", "tokio 1.0.0"]);
+        assert_eq!(packages[2].checksum.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_dev_only_dependencies() {
+        assert_eq!(
+            dev_only_dependencies(MANIFEST).into_iter().collect::<Vec<_>>(),
+            vec!["assert_cmd"]
+        );
+    }
+
+    #[test]
+    fn test_cyclonedx() {
+        let bom = cyclonedx(&info(), LOCK, MANIFEST);
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["metadata"]["component"]["purl"], "pkg:cargo/app@0.1.0");
+        assert_eq!(bom["metadata"]["timestamp"], "1970-01-01T00:00:00Z");
+
+        let purls: Vec<&str> = bom["components"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| c["purl"].as_str().unwrap())
+            .collect();
+        assert_eq!(purls, vec!["pkg:cargo/serde@1.0.0", "pkg:cargo/tokio@1.0.0"]);
+        assert_eq!(bom["components"][0]["hashes"][0]["content"], "abc123");
+
+        assert_eq!(bom["dependencies"][0]["ref"], "pkg:cargo/app@0.1.0");
+        assert_eq!(
+            bom["dependencies"][0]["dependsOn"],
+            json!(["pkg:cargo/serde@1.0.0", "pkg:cargo/tokio@1.0.0"])
+        );
+
+        let serial = bom["serialNumber"].as_str().unwrap();
+        assert_eq!(serial.len(), "urn:uuid:".len() + 36);
+        assert_eq!(serial, cyclonedx(&info(), LOCK, MANIFEST)["serialNumber"]);
+    }
+}
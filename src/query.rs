@@ -0,0 +1,721 @@
+//! Relationship query language.
+//!
+//! A small SQL subset over a virtual `relationships` table:
+//!
+//! ```text
+//! SELECT [DISTINCT] <columns | * | COUNT(*)> FROM relationships
+//!     [WHERE <condition>] [ORDER BY <column> [ASC | DESC]] [LIMIT <n>]
+//! ```
+//!
+//! Conditions combine `=`, `!=`/`<>`, `LIKE`, and `IN (...)` (each optionally
+//! negated with `NOT`) using `AND`, `OR`, and parentheses. Equality filters on
+//! `resource`, `subject`, `relation`, and `resource_type` that must hold for
+//! every row are pushed down to the list API; the full condition is always
+//! re-evaluated client-side.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+
+/// The only table a query may select from.
+pub const TABLE: &str = "relationships";
+
+/// A queryable column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// Full resource reference (`document:readme`).
+    Resource,
+    /// Relation name.
+    Relation,
+    /// Full subject reference (`user:alice`, `group:eng#member`).
+    Subject,
+    /// Resource type (`document`).
+    ResourceType,
+    /// Resource id (`readme`).
+    ResourceId,
+    /// Subject type (`user`).
+    SubjectType,
+    /// Subject id, including any userset relation (`eng#member`).
+    SubjectId,
+}
+
+impl Column {
+    /// Columns selected by `*`.
+    pub const STAR: [Self; 3] = [Self::Resource, Self::Relation, Self::Subject];
+
+    /// Every column, in documentation order.
+    pub const ALL: [Self; 7] = [
+        Self::Resource,
+        Self::Relation,
+        Self::Subject,
+        Self::ResourceType,
+        Self::ResourceId,
+        Self::SubjectType,
+        Self::SubjectId,
+    ];
+
+    /// Column name as written in queries.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Resource => "resource",
+            Self::Relation => "relation",
+            Self::Subject => "subject",
+            Self::ResourceType => "resource_type",
+            Self::ResourceId => "resource_id",
+            Self::SubjectType => "subject_type",
+            Self::SubjectId => "subject_id",
+        }
+    }
+
+    /// Look up a column by name (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A relationship row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    /// Resource reference.
+    pub resource: String,
+    /// Relation name.
+    pub relation: String,
+    /// Subject reference.
+    pub subject: String,
+}
+
+impl Row {
+    /// Value of `column` for this row.
+    pub fn get(&self, column: Column) -> &str {
+        match column {
+            Column::Resource => &self.resource,
+            Column::Relation => &self.relation,
+            Column::Subject => &self.subject,
+            Column::ResourceType => type_part(&self.resource),
+            Column::ResourceId => id_part(&self.resource),
+            Column::SubjectType => type_part(&self.subject),
+            Column::SubjectId => id_part(&self.subject),
+        }
+    }
+}
+
+fn type_part(reference: &str) -> &str {
+    reference.split_once(':').map_or(reference, |(t, _)| t)
+}
+
+fn id_part(reference: &str) -> &str {
+    reference.split_once(':').map_or("", |(_, id)| id)
+}
+
+/// What a query returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Selection {
+    /// The listed columns.
+    Columns(Vec<Column>),
+    /// `COUNT(*)` of matching rows.
+    Count,
+}
+
+/// Comparison operator for a single column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `=`
+    Eq,
+    /// `!=` or `<>`
+    Ne,
+    /// `LIKE` with `%` and `_` wildcards.
+    Like,
+    /// `NOT LIKE`
+    NotLike,
+}
+
+/// A `WHERE` condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    /// `column <op> 'value'`
+    Compare {
+        /// Column compared.
+        column: Column,
+        /// Operator.
+        op: Op,
+        /// Literal value or pattern.
+        value: String,
+    },
+    /// `column [NOT] IN ('a', 'b')`
+    In {
+        /// Column compared.
+        column: Column,
+        /// Accepted values.
+        values: Vec<String>,
+        /// Whether the list is negated.
+        negated: bool,
+    },
+    /// Both conditions hold.
+    And(Box<Self>, Box<Self>),
+    /// Either condition holds.
+    Or(Box<Self>, Box<Self>),
+    /// The condition does not hold.
+    Not(Box<Self>),
+}
+
+impl Condition {
+    /// Whether `row` satisfies the condition.
+    pub fn matches(&self, row: &Row) -> bool {
+        match self {
+            Self::Compare { column, op, value } => {
+                let actual = row.get(*column);
+                match op {
+                    Op::Eq => actual == value,
+                    Op::Ne => actual != value,
+                    Op::Like => like(actual, value),
+                    Op::NotLike => !like(actual, value),
+                }
+            },
+            Self::In { column, values, negated } => {
+                values.iter().any(|v| v == row.get(*column)) != *negated
+            },
+            Self::And(a, b) => a.matches(row) && b.matches(row),
+            Self::Or(a, b) => a.matches(row) || b.matches(row),
+            Self::Not(c) => !c.matches(row),
+        }
+    }
+
+    /// Collect `column = value` facts that hold for every matching row.
+    fn equalities(&self, out: &mut Vec<(Column, String)>) {
+        match self {
+            Self::Compare { column, op: Op::Eq, value } => out.push((*column, value.clone())),
+            Self::In { column, values, negated: false } if values.len() == 1 => {
+                out.push((*column, values[0].clone()));
+            },
+            Self::And(a, b) => {
+                a.equalities(out);
+                b.equalities(out);
+            },
+            _ => {},
+        }
+    }
+}
+
+/// SQL `LIKE` matching: `%` matches any run of characters, `_` exactly one.
+fn like(text: &str, pattern: &str) -> bool {
+    let text: Vec<char> = text.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    // Classic wildcard matching with backtracking to the last `%`.
+    let (mut t, mut p) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '_' || pattern[p] == text[t]) {
+            t += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '%' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '%')
+}
+
+/// Sort order for results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderBy {
+    /// Column sorted on.
+    pub column: Column,
+    /// Whether the order is descending.
+    pub descending: bool,
+}
+
+/// Filters sent to the list API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Pushdown {
+    /// Resource filter; `type:*` matches every resource of a type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// Subject filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Relation filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+}
+
+/// A parsed query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// Selected output.
+    pub selection: Selection,
+    /// Whether duplicate output rows are removed.
+    pub distinct: bool,
+    /// Row filter.
+    pub filter: Option<Condition>,
+    /// Sort order.
+    pub order_by: Option<OrderBy>,
+    /// Maximum rows returned.
+    pub limit: Option<usize>,
+}
+
+/// Query output: column headers and rows of values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultSet {
+    /// Column headers.
+    pub columns: Vec<String>,
+    /// Row values, aligned with `columns`.
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Query {
+    /// Parse a query string.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser { tokens: tokenize(source)?, pos: 0 };
+        let query = parser.query()?;
+        if let Some(token) = parser.peek() {
+            return Err(error(&format!("unexpected {token}")));
+        }
+        Ok(query)
+    }
+
+    /// Filters the list API can apply server-side.
+    pub fn pushdown(&self) -> Pushdown {
+        let mut equalities = Vec::new();
+        if let Some(filter) = &self.filter {
+            filter.equalities(&mut equalities);
+        }
+
+        let mut pushdown = Pushdown::default();
+        let mut resource_type = None;
+        for (column, value) in equalities {
+            match column {
+                Column::Resource => pushdown.resource = Some(value),
+                Column::Subject => pushdown.subject = Some(value),
+                Column::Relation => pushdown.relation = Some(value),
+                Column::ResourceType => resource_type = Some(value),
+                _ => {},
+            }
+        }
+        if pushdown.resource.is_none() {
+            pushdown.resource = resource_type.map(|t| format!("{t}:*"));
+        }
+        pushdown
+    }
+
+    /// Filter, sort, project, and limit `rows`.
+    pub fn apply(&self, rows: Vec<Row>) -> ResultSet {
+        let mut rows: Vec<Row> = rows
+            .into_iter()
+            .filter(|row| self.filter.as_ref().is_none_or(|f| f.matches(row)))
+            .collect();
+
+        let columns = match &self.selection {
+            Selection::Count => {
+                return ResultSet {
+                    columns: vec!["count".to_string()],
+                    rows: vec![vec![rows.len().to_string()]],
+                };
+            },
+            Selection::Columns(columns) => columns,
+        };
+
+        if let Some(order) = self.order_by {
+            rows.sort_by(|a, b| {
+                let ordering = a.get(order.column).cmp(b.get(order.column));
+                if order.descending { ordering.reverse() } else { ordering }
+            });
+        }
+
+        let mut output: Vec<Vec<String>> = Vec::new();
+        for row in &rows {
+            let values: Vec<String> = columns.iter().map(|c| row.get(*c).to_string()).collect();
+            if self.distinct && output.contains(&values) {
+                continue;
+            }
+            output.push(values);
+            if self.limit.is_some_and(|limit| output.len() >= limit) {
+                break;
+            }
+        }
+
+        ResultSet { columns: columns.iter().map(|c| c.name().to_string()).collect(), rows: output }
+    }
+}
+
+// ============================================================================
+// Parser
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Number(usize),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word(w) => write!(f, "'{w}'"),
+            Self::Str(s) => write!(f, "string '{s}'"),
+            Self::Number(n) => write!(f, "number {n}"),
+            Self::Symbol(s) => write!(f, "'{s}'"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {},
+            '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        // `''` is an escaped quote
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            value.push('\'');
+                        },
+                        Some('\'') => break,
+                        Some(c) => value.push(c),
+                        None => return Err(error("unterminated string")),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            },
+            '=' => tokens.push(Token::Symbol("=")),
+            '(' => tokens.push(Token::Symbol("(")),
+            ')' => tokens.push(Token::Symbol(")")),
+            ',' => tokens.push(Token::Symbol(",")),
+            '*' => tokens.push(Token::Symbol("*")),
+            ';' => tokens.push(Token::Symbol(";")),
+            '!' if chars.peek() == Some(&'=') => {
+                chars.next();
+                tokens.push(Token::Symbol("!="));
+            },
+            '<' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(Token::Symbol("!="));
+            },
+            c if c.is_ascii_digit() => {
+                let mut digits = c.to_string();
+                while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                    digits.push(d);
+                    chars.next();
+                }
+                let n = digits.parse().map_err(|_| error(&format!("invalid number {digits}")))?;
+                tokens.push(Token::Number(n));
+            },
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some(&w) = chars.peek().filter(|w| w.is_alphanumeric() || **w == '_') {
+                    word.push(w);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            },
+            c => return Err(error(&format!("unexpected character '{c}'"))),
+        }
+    }
+    Ok(tokens)
+}
+
+fn error(message: &str) -> Error {
+    Error::parse(format!("Query: {message}"))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn eat_symbol(&mut self, symbol: &'static str) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.eat_keyword(keyword) {
+            return Ok(());
+        }
+        Err(error(&format!("expected {keyword}{}", self.found())))
+    }
+
+    fn expect_symbol(&mut self, symbol: &'static str) -> Result<()> {
+        if self.eat_symbol(symbol) {
+            return Ok(());
+        }
+        Err(error(&format!("expected '{symbol}'{}", self.found())))
+    }
+
+    fn found(&self) -> String {
+        self.peek().map_or_else(|| ", found end of query".to_string(), |t| format!(", found {t}"))
+    }
+
+    fn column(&mut self) -> Result<Column> {
+        if let Some(Token::Word(word)) = self.peek() {
+            if let Some(column) = Column::parse(word) {
+                self.pos += 1;
+                return Ok(column);
+            }
+            let names: Vec<&str> = Column::ALL.iter().map(|c| c.name()).collect();
+            return Err(error(&format!("unknown column '{word}' (expected {})", names.join(", "))));
+        }
+        Err(error(&format!("expected column{}", self.found())))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        if let Some(Token::Str(value)) = self.peek() {
+            let value = value.clone();
+            self.pos += 1;
+            return Ok(value);
+        }
+        Err(error(&format!("expected quoted string{}", self.found())))
+    }
+
+    fn query(&mut self) -> Result<Query> {
+        self.expect_keyword("SELECT")?;
+        let distinct = self.eat_keyword("DISTINCT");
+        let selection = self.selection()?;
+
+        self.expect_keyword("FROM")?;
+        match self.peek() {
+            Some(Token::Word(table)) if table.eq_ignore_ascii_case(TABLE) => self.pos += 1,
+            Some(Token::Word(table)) => {
+                return Err(error(&format!("unknown table '{table}' (expected {TABLE})")));
+            },
+            _ => return Err(error(&format!("expected table name{}", self.found()))),
+        }
+
+        let filter = if self.eat_keyword("WHERE") { Some(self.or()?) } else { None };
+
+        let order_by = if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            let column = self.column()?;
+            let descending = if self.eat_keyword("DESC") {
+                true
+            } else {
+                self.eat_keyword("ASC");
+                false
+            };
+            Some(OrderBy { column, descending })
+        } else {
+            None
+        };
+
+        let limit = if self.eat_keyword("LIMIT") {
+            match self.peek() {
+                Some(&Token::Number(n)) => {
+                    self.pos += 1;
+                    Some(n)
+                },
+                _ => return Err(error(&format!("expected number after LIMIT{}", self.found()))),
+            }
+        } else {
+            None
+        };
+
+        self.eat_symbol(";");
+        Ok(Query { selection, distinct, filter, order_by, limit })
+    }
+
+    fn selection(&mut self) -> Result<Selection> {
+        if self.eat_symbol("*") {
+            return Ok(Selection::Columns(Column::STAR.to_vec()));
+        }
+        if self.peek_keyword("COUNT") {
+            self.pos += 1;
+            self.expect_symbol("(")?;
+            self.expect_symbol("*")?;
+            self.expect_symbol(")")?;
+            return Ok(Selection::Count);
+        }
+        let mut columns = vec![self.column()?];
+        while self.eat_symbol(",") {
+            columns.push(self.column()?);
+        }
+        Ok(Selection::Columns(columns))
+    }
+
+    fn or(&mut self) -> Result<Condition> {
+        let mut condition = self.and()?;
+        while self.eat_keyword("OR") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition> {
+        let mut condition = self.unary()?;
+        while self.eat_keyword("AND") {
+            condition = Condition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn unary(&mut self) -> Result<Condition> {
+        if self.eat_keyword("NOT") {
+            return Ok(Condition::Not(Box::new(self.unary()?)));
+        }
+        if self.eat_symbol("(") {
+            let condition = self.or()?;
+            self.expect_symbol(")")?;
+            return Ok(condition);
+        }
+        self.predicate()
+    }
+
+    fn predicate(&mut self) -> Result<Condition> {
+        let column = self.column()?;
+        let negated = self.eat_keyword("NOT");
+
+        if self.eat_keyword("IN") {
+            self.expect_symbol("(")?;
+            let mut values = vec![self.string()?];
+            while self.eat_symbol(",") {
+                values.push(self.string()?);
+            }
+            self.expect_symbol(")")?;
+            return Ok(Condition::In { column, values, negated });
+        }
+        if self.eat_keyword("LIKE") {
+            let op = if negated { Op::NotLike } else { Op::Like };
+            return Ok(Condition::Compare { column, op, value: self.string()? });
+        }
+        if negated {
+            return Err(error(&format!("expected IN or LIKE after NOT{}", self.found())));
+        }
+
+        let op = if self.eat_symbol("=") {
+            Op::Eq
+        } else if self.eat_symbol("!=") {
+            Op::Ne
+        } else {
+            return Err(error(&format!(
+                "expected =, !=, <>, LIKE, or IN after {column}{}",
+                self.found()
+            )));
+        };
+        Ok(Condition::Compare { column, op, value: self.string()? })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn row(resource: &str, relation: &str, subject: &str) -> Row {
+        Row {
+            resource: resource.to_string(),
+            relation: relation.to_string(),
+            subject: subject.to_string(),
+        }
+    }
+
+    fn sample() -> Vec<Row> {
+        vec![
+            row("document:readme", "viewer", "user:alice"),
+            row("document:design", "editor", "user:alice"),
+            row("folder:root", "owner", "user:alice"),
+            row("document:readme", "viewer", "user:bob"),
+            row("document:readme", "viewer", "group:eng#member"),
+        ]
+    }
+
+    #[test]
+    fn test_parse_and_pushdown() {
+        let query = Query::parse(
+            "SELECT resource FROM relationships WHERE subject='user:alice' AND relation IN ('viewer','editor')",
+        )
+        .unwrap();
+        assert_eq!(query.selection, Selection::Columns(vec![Column::Resource]));
+        assert_eq!(
+            query.pushdown(),
+            Pushdown { subject: Some("user:alice".to_string()), ..Pushdown::default() }
+        );
+
+        let result = query.apply(sample());
+        assert_eq!(result.columns, vec!["resource"]);
+        assert_eq!(result.rows, vec![vec!["document:readme"], vec!["document:design"]]);
+    }
+
+    #[test]
+    fn test_pushdown_ignores_disjunctions() {
+        let query = Query::parse(
+            "select * from relationships where resource_type = 'document' and (relation = 'viewer' or relation = 'editor')",
+        )
+        .unwrap();
+        assert_eq!(
+            query.pushdown(),
+            Pushdown { resource: Some("document:*".to_string()), ..Pushdown::default() }
+        );
+        assert_eq!(query.apply(sample()).rows.len(), 4);
+    }
+
+    #[test]
+    fn test_distinct_order_limit_count() {
+        let query = Query::parse(
+            "SELECT DISTINCT subject_type FROM relationships ORDER BY subject_type DESC LIMIT 5;",
+        )
+        .unwrap();
+        assert_eq!(query.apply(sample()).rows, vec![vec!["user"], vec!["group"]]);
+
+        let query =
+            Query::parse("SELECT COUNT(*) FROM relationships WHERE subject NOT LIKE 'user:%'")
+                .unwrap();
+        assert_eq!(query.apply(sample()).rows, vec![vec!["1"]]);
+    }
+
+    #[test]
+    fn test_like() {
+        assert!(like("document:readme", "document:%"));
+        assert!(like("document:readme", "%read_e"));
+        assert!(like("a%b", "a%b"));
+        assert!(!like("document:readme", "folder:%"));
+        assert!(!like("abc", "a_"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Query::parse("SELECT owner FROM relationships").unwrap_err();
+        assert!(err.to_string().contains("unknown column 'owner'"));
+        assert!(Query::parse("SELECT * FROM users").is_err());
+        assert!(Query::parse("SELECT * FROM relationships WHERE subject = 'a").is_err());
+        assert!(Query::parse("SELECT * FROM relationships LIMIT 1 extra").is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! "Did you mean" suggestions for mistyped commands.
+//!
+//! clap only suggests siblings of an unknown subcommand. When it rejects one,
+//! the mistyped name is instead matched by edit distance against every
+//! command in the tree, aliases included, so `orgs vaults team-rols` and a
+//! `team-rols` typed without its parents both lead to
+//! `orgs vaults team-roles`. Matches under the part of the path that was typed
+//! correctly rank first.
+
+use clap::{Command, CommandFactory};
+
+use crate::{cli::Cli, deprecations};
+
+/// Most suggestions printed.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Command paths to suggest for a command line (without the program name)
+/// that clap rejected, best first.
+#[must_use]
+pub fn suggest(args: &[String]) -> Vec<String> {
+    let root = Cli::command();
+    let typed = deprecations::typed_commands(args);
+
+    // Follow the typed path as far as it is valid
+    let mut command = &root;
+    let mut prefix = Vec::new();
+    let mut unknown = None;
+    for word in &typed {
+        match find(command, word) {
+            Some(sub) => {
+                prefix.push(sub.get_name().to_string());
+                command = sub;
+            },
+            None if command.has_subcommands() => {
+                unknown = Some(*word);
+                break;
+            },
+            None => break,
+        }
+    }
+    let Some(unknown) = unknown else {
+        return Vec::new();
+    };
+
+    let mut paths = Vec::new();
+    collect(&root, &mut Vec::new(), &mut paths);
+
+    let mut scored: Vec<(usize, bool, usize, String)> = paths
+        .into_iter()
+        .filter_map(|(path, names)| {
+            let distance = names.iter().map(|name| distance(unknown, name)).min()?;
+            (distance <= threshold(unknown)).then(|| {
+                let outside = !path.starts_with(&prefix) || path.len() != prefix.len() + 1;
+                (distance, outside, path.len(), path.join(" "))
+            })
+        })
+        .collect();
+    scored.sort();
+    scored.into_iter().take(MAX_SUGGESTIONS).map(|(.., path)| path).collect()
+}
+
+/// The visible subcommand of `command` named or aliased `word`.
+fn find<'a>(command: &'a Command, word: &str) -> Option<&'a Command> {
+    command
+        .get_subcommands()
+        .find(|sub| sub.get_name() == word || sub.get_all_aliases().any(|alias| alias == word))
+}
+
+/// Every visible command path under `command`, with the names (the command's
+/// own and its aliases) its last segment answers to.
+fn collect(command: &Command, path: &mut Vec<String>, out: &mut Vec<(Vec<String>, Vec<String>)>) {
+    for sub in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        path.push(sub.get_name().to_string());
+        let names = std::iter::once(sub.get_name()).chain(sub.get_all_aliases());
+        out.push((path.clone(), names.map(str::to_string).collect()));
+        collect(sub, path, out);
+        path.pop();
+    }
+}
+
+/// Largest edit distance still worth suggesting for a name this long.
+const fn threshold(word: &str) -> usize {
+    match word.len() {
+        0..=3 => 1,
+        4..=8 => 2,
+        _ => 3,
+    }
+}
+
+/// Edit distance counting insertions, deletions, substitutions, and swaps of
+/// adjacent characters as one edit each.
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best =
+                (rows[i - 1][j] + 1).min(rows[i][j - 1] + 1).min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(distance("list", "list"), 0);
+        assert_eq!(distance("lsit", "list"), 1);
+        assert_eq!(distance("team-rols", "team-roles"), 1);
+        assert_eq!(distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggest_nested() {
+        assert_eq!(suggest(&args("orgs vaults team-rols list"))[0], "orgs vaults team-roles");
+        assert_eq!(suggest(&args("relationships lsit"))[0], "relationships list");
+        assert_eq!(suggest(&args("ogrs"))[0], "orgs");
+    }
+
+    #[test]
+    fn test_suggest_outside_typed_path() {
+        assert!(suggest(&args("team-rols")).contains(&"orgs vaults team-roles".to_string()));
+    }
+
+    #[test]
+    fn test_suggest_nothing_close() {
+        assert!(suggest(&args("zzzzzzzz")).is_empty());
+        assert!(suggest(&args("relationships list")).is_empty());
+    }
+}
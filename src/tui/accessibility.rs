@@ -0,0 +1,104 @@
+//! Accessibility mode for terminal output.
+//!
+//! Enabled with `--accessible`, `output.accessible: true` in the config file,
+//! or the `ACCESSIBLE` environment variable. In this mode spinners and
+//! full-screen views give way to line-by-line status text, and glyphs, dot
+//! leaders, and ANSI colors are replaced with plain words in the terminal's
+//! own foreground color so screen readers and high-contrast themes work.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use teapot::output::{is_ci, is_tty};
+
+use crate::output::glyphs::{self, Glyph};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable accessibility mode for the rest of the process.
+pub fn set_accessible(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Check if accessibility mode was explicitly requested.
+///
+/// Unlike [`is_accessible`], this ignores TTY and CI detection, so callers
+/// can keep their existing plain-text fallbacks for piped output.
+#[must_use]
+pub fn accessibility_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) || std::env::var("ACCESSIBLE").is_ok()
+}
+
+/// Check if forms and prompts should use accessible (plain text) mode.
+#[must_use]
+pub fn is_accessible() -> bool {
+    accessibility_enabled() || !is_tty() || is_ci()
+}
+
+/// Kind of status message, spelled out in words rather than glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// An operation completed.
+    Success,
+    /// Something needs attention but did not fail.
+    Warning,
+    /// An operation failed.
+    Error,
+    /// Progress or informational text.
+    Info,
+}
+
+impl Status {
+    /// Glyph shown before the message outside accessibility mode.
+    #[must_use]
+    pub const fn glyph(self) -> Glyph {
+        match self {
+            Self::Success => glyphs::CHECK,
+            Self::Warning => glyphs::WARNING,
+            Self::Error => glyphs::CROSS,
+            Self::Info => glyphs::BULLET,
+        }
+    }
+
+    /// Word announced before the message.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Success => "Done",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+            Self::Info => "Status",
+        }
+    }
+}
+
+/// Format a status message as `<label>: <message>`.
+#[must_use]
+pub fn status_line(status: Status, message: &str) -> String {
+    format!("{}: {message}", status.label())
+}
+
+/// Print a status message to stderr without glyphs or colors.
+pub fn print_status(status: Status, message: &str) {
+    eprintln!("{}", status_line(status, message));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_accessible_in_test() {
+        // In tests, we're typically not in a TTY, so accessible mode should be true
+        // unless explicitly set otherwise
+        // Just ensure it doesn't panic and returns a valid bool
+        let _result = is_accessible();
+    }
+
+    #[test]
+    fn test_status_line_uses_words() {
+        assert_eq!(status_line(Status::Success, "Schema pushed"), "Done: Schema pushed");
+        assert_eq!(status_line(Status::Error, "Timed out"), "Error: Timed out");
+        assert_eq!(status_line(Status::Info, "Pushing schema"), "Status: Pushing schema");
+        assert!(status_line(Status::Warning, "x").is_ascii());
+    }
+}
@@ -19,7 +19,13 @@ use teapot::{
     Model,
     components::Confirm as TeapotConfirm,
     output::{is_ci, is_tty},
-    style::{Color, RESET},
+    style::RESET,
+};
+
+use super::accessibility::{Status, accessibility_enabled, status_line};
+use crate::output::{
+    glyphs,
+    theme::{self, Role},
 };
 
 /// Result of a confirmation prompt.
@@ -91,21 +97,25 @@ pub fn confirm_with_options(message: &str, options: &ConfirmOptions) -> crate::e
     // In non-interactive mode, use default
     if !is_tty() || is_ci() {
         teapot::output::info(&format!(
-            "{} [{}] (non-interactive, using default)",
+            "{} [{}] (non-interactive, using default; pass --yes to confirm)",
             message,
             if options.default { "Y" } else { "N" }
         ));
         return Ok(options.default);
     }
 
-    // Create and display the confirmation prompt
-    let confirm = TeapotConfirm::new(message)
-        .default(options.default)
-        .yes_label(&options.yes_label)
-        .no_label(&options.no_label);
-
-    // Display the prompt
-    eprint!("{} ", confirm.view());
+    // Display the prompt (as plain words in accessible mode)
+    if accessibility_enabled() {
+        let default = if options.default { "yes" } else { "no" };
+        eprint!("{message} Answer yes or no (default: {default}): ");
+    } else {
+        let confirm = TeapotConfirm::new(message)
+            .default(options.default)
+            .yes_label(&options.yes_label)
+            .no_label(&options.no_label);
+
+        eprint!("{} ", confirm.view());
+    }
     io::stderr().flush()?;
 
     // Read user input
@@ -138,20 +148,27 @@ pub fn confirm_with_options(message: &str, options: &ConfirmOptions) -> crate::e
 pub fn confirm_danger(message: &str) -> crate::error::Result<bool> {
     // In non-interactive mode, use default (no for danger)
     if !is_tty() || is_ci() {
-        teapot::output::info(&format!("{message} [N] (non-interactive, using default)"));
+        teapot::output::info(&format!(
+            "{message} [N] (non-interactive, using default; pass --yes to confirm)"
+        ));
         return Ok(false);
     }
 
-    // Show warning prefix in danger mode
-    eprint!("{}⚠{} ", Color::Red.to_ansi_fg(), RESET);
+    if accessibility_enabled() {
+        eprint!("{} Type yes to continue (default: no): ", status_line(Status::Warning, message));
+    } else {
+        // Show warning prefix in danger mode
+        let theme = theme::current();
+        eprint!("{}{}{} ", theme.ansi(Role::Error), glyphs::WARNING, RESET);
 
-    let confirm = TeapotConfirm::new(message)
-        .default(false)
-        .yes_label("Yes, I'm sure")
-        .no_label("Cancel")
-        .selected_color(Color::Red);
+        let confirm = TeapotConfirm::new(message)
+            .default(false)
+            .yes_label("Yes, I'm sure")
+            .no_label("Cancel")
+            .selected_color(theme.color(Role::Error));
 
-    eprint!("{} ", confirm.view());
+        eprint!("{} ", confirm.view());
+    }
     io::stderr().flush()?;
 
     // Read user input
@@ -0,0 +1,691 @@
+//! Relationship explorer view.
+//!
+//! A full-screen browser over the vault's relationships. It starts from a
+//! resource (or from a sample of every resource), lists the resource's
+//! relationships, and lets the user drill into subjects, expand a relation to
+//! its effective subjects, and run ad-hoc checks against the current resource.
+
+use std::sync::Arc;
+
+use bon::bon;
+use teapot::{
+    Model,
+    components::{Column, FooterHints, Table, TitleBar},
+    style::{Color, RESET},
+    terminal::{Event, KeyCode, KeyModifiers},
+    util::ScrollState,
+};
+
+use crate::output::{
+    glyphs,
+    theme::{self, Role},
+};
+
+/// A relationship shown in the explorer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExploreRow {
+    /// Resource (e.g., `document:readme`).
+    pub resource: String,
+    /// Relation (e.g., `viewer`).
+    pub relation: String,
+    /// Subject (e.g., `user:alice` or `group:eng#member`).
+    pub subject: String,
+}
+
+/// Lists the relationships of a resource, or a sample of all relationships.
+pub type RelationshipsFn =
+    Arc<dyn Fn(Option<&str>) -> Result<Vec<ExploreRow>, String> + Send + Sync>;
+
+/// Lists the subjects with a relation or permission on a resource.
+pub type ExpandFn = Arc<dyn Fn(&str, &str) -> Result<Vec<String>, String> + Send + Sync>;
+
+/// Checks whether a subject has a permission on a resource.
+pub type CheckFn = Arc<dyn Fn(&str, &str, &str) -> Result<bool, String> + Send + Sync>;
+
+/// What typed text is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    /// Jump to a resource.
+    Goto,
+    /// Check `subject permission [resource]`.
+    Check,
+    /// Filter the listed rows.
+    Filter,
+}
+
+impl Prompt {
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Goto => "go to",
+            Self::Check => "check",
+            Self::Filter => "filter",
+        }
+    }
+}
+
+/// One screen of the explorer.
+#[derive(Debug, Clone)]
+struct Page {
+    /// The resource listed, or `None` for the sample of all resources.
+    resource: Option<String>,
+    /// The relation expanded to its effective subjects, if any.
+    expanded: Option<String>,
+    rows: Vec<ExploreRow>,
+}
+
+impl Page {
+    fn label(&self) -> String {
+        match (&self.resource, &self.expanded) {
+            (None, _) => "all resources".to_string(),
+            (Some(resource), None) => resource.clone(),
+            (Some(resource), Some(relation)) => format!("{resource}#{relation}"),
+        }
+    }
+}
+
+/// Message type for the explore view.
+#[derive(Clone)]
+pub enum ExploreViewMsg {
+    /// Type a character into the prompt.
+    Insert(char),
+    /// Remove the last prompt character.
+    Backspace,
+    /// Start typing for a prompt.
+    StartPrompt(Prompt),
+    /// Submit the prompt, or open the selected row.
+    Enter,
+    /// Leave the prompt, clearing a filter.
+    CancelPrompt,
+    /// Return to the previous page.
+    Back,
+    /// Expand the selected row's relation to its effective subjects.
+    Expand,
+    /// Reload the current page.
+    Refresh,
+    /// Move selection up.
+    SelectPrev,
+    /// Move selection down.
+    SelectNext,
+    /// Page up.
+    PageUp,
+    /// Page down.
+    PageDown,
+    /// Quit the explorer.
+    Quit,
+    /// Resize the view.
+    Resize {
+        /// New width.
+        width: usize,
+        /// New height.
+        height: usize,
+    },
+}
+
+/// Relationship explorer view.
+pub struct ExploreView {
+    /// Terminal width.
+    width: usize,
+    /// Terminal height.
+    height: usize,
+    relationships: RelationshipsFn,
+    expand: ExpandFn,
+    check: CheckFn,
+    /// Visited pages; the last one is shown.
+    history: Vec<Page>,
+    /// Active prompt, if typing.
+    prompt: Option<Prompt>,
+    /// Text typed for the goto or check prompt.
+    input: String,
+    /// Filter applied to the listed rows.
+    filter: String,
+    /// Result of the last action, shown above the table.
+    status: Option<(Role, String)>,
+    /// Scroll state for table navigation.
+    scroll: ScrollState,
+}
+
+#[bon]
+impl ExploreView {
+    /// Create a new explore view, loading `resource` (or a sample of all
+    /// resources).
+    #[must_use]
+    #[builder]
+    pub fn new(
+        width: usize,
+        height: usize,
+        relationships: RelationshipsFn,
+        expand: ExpandFn,
+        check: CheckFn,
+        resource: Option<String>,
+    ) -> Self {
+        let mut view = Self {
+            width,
+            height,
+            relationships,
+            expand,
+            check,
+            history: Vec::new(),
+            prompt: None,
+            input: String::new(),
+            filter: String::new(),
+            status: None,
+            scroll: ScrollState::new(),
+        };
+        view.open(resource);
+        if view.history.is_empty() {
+            view.history.push(Page { resource: None, expanded: None, rows: Vec::new() });
+        }
+        view
+    }
+
+    /// The page shown.
+    fn page(&self) -> &Page {
+        &self.history[self.history.len() - 1]
+    }
+
+    /// Rows of the current page matching the filter.
+    fn rows(&self) -> Vec<&ExploreRow> {
+        let filter = self.filter.to_lowercase();
+        self.page()
+            .rows
+            .iter()
+            .filter(|row| {
+                filter.is_empty()
+                    || format!("{} {} {}", row.resource, row.relation, row.subject)
+                        .to_lowercase()
+                        .contains(&filter)
+            })
+            .collect()
+    }
+
+    fn selected(&self) -> Option<ExploreRow> {
+        self.rows().get(self.scroll.selected()).map(|&row| row.clone())
+    }
+
+    /// Load the relationships of `resource` as a new page.
+    fn open(&mut self, resource: Option<String>) {
+        match (self.relationships)(resource.as_deref()) {
+            Ok(rows) => {
+                self.status = rows.is_empty().then(|| {
+                    (
+                        Role::Info,
+                        format!(
+                            "No relationships on {}",
+                            resource.as_deref().unwrap_or("this vault")
+                        ),
+                    )
+                });
+                self.push(Page { resource, expanded: None, rows });
+            },
+            Err(e) => self.status = Some((Role::Error, e)),
+        }
+    }
+
+    fn push(&mut self, page: Page) {
+        self.history.push(page);
+        self.filter.clear();
+        self.scroll.reset();
+    }
+
+    /// Open the selected row: its resource from the sample, otherwise the
+    /// object of its subject.
+    fn open_selected(&mut self) {
+        let Some(row) = self.selected() else { return };
+        let target = if self.page().resource.is_none() {
+            row.resource
+        } else {
+            row.subject
+                .split_once('#')
+                .map_or(row.subject.as_str(), |(object, _)| object)
+                .to_string()
+        };
+        if target.ends_with(":*") {
+            self.status = Some((Role::Warning, format!("{target} is a wildcard")));
+            return;
+        }
+        self.open(Some(target));
+    }
+
+    /// Expand the selected row's relation on the current resource.
+    fn expand_selected(&mut self) {
+        let Some(row) = self.selected() else { return };
+        match (self.expand)(&row.resource, &row.relation) {
+            Ok(subjects) => {
+                self.status = Some((
+                    Role::Info,
+                    format!(
+                        "{} subjects have {} on {}",
+                        subjects.len(),
+                        row.relation,
+                        row.resource
+                    ),
+                ));
+                let rows = subjects
+                    .into_iter()
+                    .map(|subject| ExploreRow {
+                        resource: row.resource.clone(),
+                        relation: row.relation.clone(),
+                        subject,
+                    })
+                    .collect();
+                self.push(Page {
+                    resource: Some(row.resource.clone()),
+                    expanded: Some(row.relation),
+                    rows,
+                });
+            },
+            Err(e) => self.status = Some((Role::Error, e)),
+        }
+    }
+
+    /// Reload the current page in place.
+    fn refresh(&mut self) {
+        let page = self.page().clone();
+        let rows = match (&page.resource, &page.expanded) {
+            (Some(resource), Some(relation)) => (self.expand)(resource, relation).map(|subjects| {
+                subjects
+                    .into_iter()
+                    .map(|subject| ExploreRow {
+                        resource: resource.clone(),
+                        relation: relation.clone(),
+                        subject,
+                    })
+                    .collect()
+            }),
+            _ => (self.relationships)(page.resource.as_deref()),
+        };
+        match rows {
+            Ok(rows) => {
+                let last = self.history.len() - 1;
+                self.history[last].rows = rows;
+                self.status = Some((Role::Info, "Refreshed".to_string()));
+            },
+            Err(e) => self.status = Some((Role::Error, e)),
+        }
+    }
+
+    /// Run `subject permission [resource]`, defaulting to the current resource.
+    fn run_check(&mut self, input: &str) {
+        let parts: Vec<&str> = input.split_whitespace().filter(|&p| p != "can").collect();
+        let (subject, permission, resource) = match (parts.as_slice(), &self.page().resource) {
+            ([subject, permission, resource], _) => {
+                (*subject, *permission, (*resource).to_string())
+            },
+            ([subject, permission], Some(resource)) => (*subject, *permission, resource.clone()),
+            _ => {
+                self.status =
+                    Some((Role::Warning, "Enter: <subject> <permission> [resource]".to_string()));
+                return;
+            },
+        };
+        self.status = Some(match (self.check)(subject, permission, &resource) {
+            Ok(true) => (Role::Success, format!("{subject} can {permission} {resource}")),
+            Ok(false) => (Role::Error, format!("{subject} cannot {permission} {resource}")),
+            Err(e) => (Role::Error, e),
+        });
+    }
+
+    /// Submit the active prompt.
+    fn submit(&mut self, prompt: Prompt) {
+        self.prompt = None;
+        let input = std::mem::take(&mut self.input);
+        match prompt {
+            Prompt::Goto if input.contains(':') => self.open(Some(input.trim().to_string())),
+            Prompt::Goto => {
+                self.status = Some((Role::Warning, "Enter a resource like document:readme".into()));
+            },
+            Prompt::Check => self.run_check(&input),
+            Prompt::Filter => {},
+        }
+    }
+
+    /// Get visible rows for the table.
+    const fn visible_rows(&self) -> usize {
+        // title(1) + blank(1) + path(1) + prompt(1) + sep(1) + header(1) + sep(1) + footer(1) = 8
+        self.height.saturating_sub(8)
+    }
+
+    /// Clamp scroll positions.
+    fn clamp_scroll(&mut self) {
+        self.scroll.clamp(self.rows().len(), self.visible_rows());
+    }
+
+    /// Build the table component.
+    fn build_table(&self) -> Table {
+        let all = self.page().resource.is_none();
+        let rows: Vec<Vec<String>> = self
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let mut cells = vec![row.relation.clone(), row.subject.clone()];
+                if all {
+                    cells.insert(0, row.resource.clone());
+                }
+                cells
+            })
+            .collect();
+        let mut columns = vec![Column::new("Relation"), Column::new("Subject").grow()];
+        if all {
+            columns.insert(0, Column::new("Resource"));
+        }
+
+        Table::new()
+            .columns(columns)
+            .rows(rows)
+            .height(self.visible_rows())
+            .width(self.width)
+            .show_borders(false)
+            .header_color(Color::Default)
+            .selected_row_color(Color::Cyan)
+            .with_cursor_row(self.scroll.selected())
+            .with_offset(self.scroll.offset())
+    }
+
+    /// Render the visited pages with the row count right-aligned.
+    fn render_path_line(&self) -> String {
+        let separator = format!(" {} ", glyphs::ARROW);
+        let path = self.history.iter().map(Page::label).collect::<Vec<_>>().join(&separator);
+        let count = format!("{}/{}", self.rows().len(), self.page().rows.len());
+        let padding = self.width.saturating_sub(path.chars().count() + count.chars().count() + 1);
+        format!("{path}{}{count} ", " ".repeat(padding))
+    }
+
+    /// Render the prompt, or the filter and last result.
+    fn render_prompt_line(&self) -> String {
+        let dim = Color::BrightBlack.to_ansi_fg();
+        match self.prompt {
+            Some(Prompt::Filter) => format!("/{}", self.filter),
+            Some(prompt) => format!("{}> {}", prompt.label(), self.input),
+            None => {
+                let mut line = String::new();
+                if !self.filter.is_empty() {
+                    line.push_str(&format!("{dim}/{}{RESET}  ", self.filter));
+                }
+                if let Some((role, message)) = &self.status {
+                    let theme = theme::current();
+                    line.push_str(&format!(
+                        "{}{}{RESET} {message}",
+                        theme.ansi(*role),
+                        theme.symbol(*role)
+                    ));
+                }
+                line
+            },
+        }
+    }
+
+    /// Render the footer hints.
+    fn render_footer(&self) -> String {
+        let hints = if self.prompt.is_some() {
+            vec![("enter", "submit"), ("esc", "cancel")]
+        } else {
+            vec![
+                ("enter", "open"),
+                ("←", "back"),
+                ("e", "expand"),
+                ("c", "check"),
+                ("g", "go to"),
+                ("/", "filter"),
+                ("r", "refresh"),
+                ("q", "quit"),
+            ]
+        };
+        FooterHints::new().hints(hints).width(self.width).render()
+    }
+}
+
+impl Model for ExploreView {
+    type Message = ExploreViewMsg;
+
+    fn init(&self) -> Option<teapot::Cmd<Self::Message>> {
+        None
+    }
+
+    fn update(&mut self, msg: Self::Message) -> Option<teapot::Cmd<Self::Message>> {
+        match msg {
+            ExploreViewMsg::Insert(c) => match self.prompt {
+                Some(Prompt::Filter) => {
+                    self.filter.push(c);
+                    self.scroll.reset();
+                },
+                Some(_) => self.input.push(c),
+                None => {},
+            },
+            ExploreViewMsg::Backspace => match self.prompt {
+                Some(Prompt::Filter) => {
+                    self.filter.pop();
+                    self.scroll.reset();
+                },
+                Some(_) => {
+                    self.input.pop();
+                },
+                None => {},
+            },
+            ExploreViewMsg::StartPrompt(prompt) => {
+                self.prompt = Some(prompt);
+                self.input.clear();
+                if prompt == Prompt::Filter {
+                    self.filter.clear();
+                }
+            },
+            ExploreViewMsg::Enter => match self.prompt {
+                Some(prompt) => self.submit(prompt),
+                None => self.open_selected(),
+            },
+            ExploreViewMsg::CancelPrompt => {
+                if self.prompt == Some(Prompt::Filter) {
+                    self.filter.clear();
+                }
+                self.prompt = None;
+                self.input.clear();
+            },
+            ExploreViewMsg::Back => {
+                if self.history.len() > 1 {
+                    self.history.pop();
+                    self.filter.clear();
+                    self.status = None;
+                    self.scroll.reset();
+                }
+            },
+            ExploreViewMsg::Expand => {
+                if self.page().resource.is_some() && self.page().expanded.is_none() {
+                    self.expand_selected();
+                }
+            },
+            ExploreViewMsg::Refresh => self.refresh(),
+            ExploreViewMsg::SelectPrev => self.scroll.select_prev(),
+            ExploreViewMsg::SelectNext => {
+                self.scroll.select_next(self.rows().len(), self.visible_rows());
+            },
+            ExploreViewMsg::PageUp => self.scroll.page_up(self.visible_rows()),
+            ExploreViewMsg::PageDown => {
+                self.scroll.page_down(self.rows().len(), self.visible_rows());
+            },
+            ExploreViewMsg::Quit => return Some(teapot::Cmd::quit()),
+            ExploreViewMsg::Resize { width, height } => {
+                self.width = width;
+                self.height = height;
+            },
+        }
+        self.clamp_scroll();
+        None
+    }
+
+    fn view(&self) -> String {
+        let mut output = String::new();
+        let reset = RESET;
+        let dim = Color::BrightBlack.to_ansi_fg();
+
+        // Title bar
+        output.push_str(&TitleBar::new("InferaDB").subtitle("Explore").width(self.width).render());
+        output.push_str("\r\n\r\n");
+
+        // Visited pages and prompt
+        output.push_str(&self.render_path_line());
+        output.push_str("\r\n");
+        output.push_str(&self.render_prompt_line());
+        output.push_str("\r\n");
+
+        // Separator
+        output.push_str(&format!("{}{}{}\r\n", dim, "─".repeat(self.width), reset));
+
+        // Table content
+        let content_height = self.visible_rows() + 1; // +1 for header
+        if self.rows().is_empty() {
+            output.push_str(&format!("{dim}  No relationships{reset}\r\n"));
+            for _ in 1..content_height {
+                output.push_str("\r\n");
+            }
+        } else {
+            let table_output = self.build_table().render();
+            let table_lines: Vec<&str> = table_output.lines().collect();
+            for i in 0..content_height {
+                if let Some(line) = table_lines.get(i) {
+                    output.push_str(line);
+                }
+                output.push_str("\r\n");
+            }
+        }
+
+        // Padding to push footer to bottom
+        let fixed_overhead = 8; // title + blank + path + prompt + sep + sep + footer
+        let padding_needed = self.height.saturating_sub(fixed_overhead + content_height);
+        for _ in 0..padding_needed {
+            output.push_str("\r\n");
+        }
+
+        // Footer separator
+        output.push_str(&format!("{}{}{}\r\n", dim, "─".repeat(self.width), reset));
+
+        // Footer hints
+        output.push_str(&self.render_footer());
+
+        output
+    }
+
+    fn handle_event(&self, event: Event) -> Option<Self::Message> {
+        match event {
+            Event::Key(key)
+                if key.code == KeyCode::Char('c')
+                    && key.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                Some(ExploreViewMsg::Quit)
+            },
+            Event::Key(key) if self.prompt.is_some() => match key.code {
+                KeyCode::Esc => Some(ExploreViewMsg::CancelPrompt),
+                KeyCode::Enter => Some(ExploreViewMsg::Enter),
+                KeyCode::Backspace => Some(ExploreViewMsg::Backspace),
+                KeyCode::Char(c) => Some(ExploreViewMsg::Insert(c)),
+                _ => None,
+            },
+            Event::Key(key) => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => Some(ExploreViewMsg::Quit),
+                KeyCode::Enter | KeyCode::Right => Some(ExploreViewMsg::Enter),
+                KeyCode::Left | KeyCode::Backspace => Some(ExploreViewMsg::Back),
+                KeyCode::Up | KeyCode::Char('k') => Some(ExploreViewMsg::SelectPrev),
+                KeyCode::Down | KeyCode::Char('j') => Some(ExploreViewMsg::SelectNext),
+                KeyCode::PageUp => Some(ExploreViewMsg::PageUp),
+                KeyCode::PageDown => Some(ExploreViewMsg::PageDown),
+                KeyCode::Char('e') => Some(ExploreViewMsg::Expand),
+                KeyCode::Char('r') => Some(ExploreViewMsg::Refresh),
+                KeyCode::Char('c') => Some(ExploreViewMsg::StartPrompt(Prompt::Check)),
+                KeyCode::Char('g') => Some(ExploreViewMsg::StartPrompt(Prompt::Goto)),
+                KeyCode::Char('/') => Some(ExploreViewMsg::StartPrompt(Prompt::Filter)),
+                _ => None,
+            },
+            Event::Resize { width, height } => {
+                Some(ExploreViewMsg::Resize { width: width as usize, height: height as usize })
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TUPLES: [(&str, &str, &str); 3] = [
+        ("document:readme", "viewer", "group:eng#member"),
+        ("document:readme", "owner", "user:alice"),
+        ("group:eng", "member", "user:bob"),
+    ];
+
+    fn view(resource: Option<&str>) -> ExploreView {
+        ExploreView::builder()
+            .width(80)
+            .height(24)
+            .relationships(Arc::new(|resource: Option<&str>| {
+                Ok(TUPLES
+                    .iter()
+                    .filter(|(r, ..)| resource.is_none_or(|resource| *r == resource))
+                    .map(|&(resource, relation, subject)| ExploreRow {
+                        resource: resource.to_string(),
+                        relation: relation.to_string(),
+                        subject: subject.to_string(),
+                    })
+                    .collect())
+            }))
+            .expand(Arc::new(|_: &str, relation: &str| {
+                Ok(if relation == "viewer" { vec!["user:bob".to_string()] } else { vec![] })
+            }))
+            .check(Arc::new(|subject: &str, _: &str, _: &str| Ok(subject == "user:alice")))
+            .maybe_resource(resource.map(str::to_string))
+            .build()
+    }
+
+    fn type_text(view: &mut ExploreView, text: &str) {
+        for c in text.chars() {
+            view.update(ExploreViewMsg::Insert(c));
+        }
+    }
+
+    #[test]
+    fn test_explore_drill_and_back() {
+        let mut view = view(None);
+        assert_eq!(view.rows().len(), 3);
+
+        // Open the first resource, then follow the subject set to the group
+        view.update(ExploreViewMsg::Enter);
+        assert_eq!(view.page().label(), "document:readme");
+        view.update(ExploreViewMsg::Enter);
+        assert_eq!(view.page().label(), "group:eng");
+        assert_eq!(view.rows()[0].subject, "user:bob");
+
+        view.update(ExploreViewMsg::Back);
+        assert_eq!(view.page().label(), "document:readme");
+    }
+
+    #[test]
+    fn test_explore_expand_and_filter() {
+        let mut view = view(Some("document:readme"));
+        view.update(ExploreViewMsg::Expand);
+        assert_eq!(view.page().label(), "document:readme#viewer");
+        assert_eq!(view.rows()[0].subject, "user:bob");
+        view.update(ExploreViewMsg::Back);
+
+        view.update(ExploreViewMsg::StartPrompt(Prompt::Filter));
+        type_text(&mut view, "alice");
+        view.update(ExploreViewMsg::Enter);
+        assert_eq!(view.rows().len(), 1);
+        assert_eq!(view.selected().map(|row| row.relation), Some("owner".to_string()));
+    }
+
+    #[test]
+    fn test_explore_check_and_goto() {
+        let mut view = view(Some("document:readme"));
+        view.update(ExploreViewMsg::StartPrompt(Prompt::Check));
+        type_text(&mut view, "user:alice can view");
+        view.update(ExploreViewMsg::Enter);
+        assert_eq!(
+            view.status,
+            Some((Role::Success, "user:alice can view document:readme".to_string()))
+        );
+
+        view.update(ExploreViewMsg::StartPrompt(Prompt::Goto));
+        type_text(&mut view, "group:eng");
+        view.update(ExploreViewMsg::Enter);
+        assert_eq!(view.page().label(), "group:eng");
+        assert_eq!(view.history.len(), 2);
+    }
+}
@@ -22,10 +22,7 @@
 //! }
 //! ```
 
-use teapot::{
-    forms::{Form, FormResults},
-    output::{is_ci, is_tty},
-};
+use teapot::forms::{Form, FormResults};
 
 use crate::error::Result;
 
@@ -40,22 +37,3 @@ pub fn run_form(mut form: Form) -> Result<Option<FormResults>> {
     // and still provides a good experience
     form.run_accessible().map_err(|e| crate::error::Error::other(e.to_string()))
 }
-
-/// Check if forms should use accessible (plain text) mode.
-#[must_use]
-pub fn is_accessible() -> bool {
-    std::env::var("ACCESSIBLE").is_ok() || !is_tty() || is_ci()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_is_accessible_in_test() {
-        // In tests, we're typically not in a TTY, so accessible mode should be true
-        // unless explicitly set otherwise
-        // Just ensure it doesn't panic and returns a valid bool
-        let _result = is_accessible();
-    }
-}
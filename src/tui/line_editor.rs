@@ -0,0 +1,352 @@
+//! Single-line input with history and tab completion.
+//!
+//! Used by `inferadb shell` when stdin is a terminal. Up and down walk the
+//! history, tab completes the word before the cursor (listing the choices
+//! when more than one matches), Ctrl+C abandons the line and Ctrl+D on an
+//! empty line ends input.
+
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+
+use bon::Builder;
+use teapot::{Cmd, Event, KeyCode, Model, Program, style::RESET};
+
+/// Reverse video, for the cursor cell (the runtime hides the real cursor).
+const REVERSE: &str = "\x1b[7m";
+
+/// Completion candidates for the word ending at the cursor, given the line up
+/// to the cursor. The editor keeps those that start with the word.
+pub type CompleteFn = Arc<dyn Fn(&str) -> Vec<String> + Send + Sync>;
+
+/// How reading a line ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineResult {
+    /// Enter was pressed.
+    Line(String),
+    /// Ctrl+C abandoned the line.
+    Interrupted,
+    /// Ctrl+D on an empty line.
+    Eof,
+}
+
+/// Messages for [`LineEditor`].
+#[derive(Debug, Clone)]
+pub enum LineEditorMsg {
+    /// Insert a character at the cursor.
+    Insert(char),
+    /// Delete the character before the cursor.
+    Backspace,
+    /// Delete the character under the cursor.
+    Delete,
+    /// Move the cursor left.
+    Left,
+    /// Move the cursor right.
+    Right,
+    /// Move to the start of the line.
+    Home,
+    /// Move to the end of the line.
+    End,
+    /// Delete everything before the cursor.
+    KillToStart,
+    /// Recall the previous history entry.
+    Previous,
+    /// Recall the next history entry, or the line being typed.
+    Next,
+    /// Complete the word before the cursor.
+    Complete,
+    /// Accept the line.
+    Submit,
+    /// Abandon the line.
+    Interrupt,
+    /// End input (only on an empty line).
+    Eof,
+}
+
+/// Line editor state.
+#[derive(Builder)]
+pub struct LineEditor {
+    #[builder(into)]
+    prompt: String,
+    /// Earlier lines, oldest first.
+    #[builder(default)]
+    history: Vec<String>,
+    complete: Option<CompleteFn>,
+    #[builder(skip)]
+    text: Vec<char>,
+    #[builder(skip)]
+    cursor: usize,
+    /// Position in `history` while recalling entries.
+    #[builder(skip)]
+    recalled: Option<usize>,
+    /// The line being typed before recalling history.
+    #[builder(skip)]
+    draft: String,
+    /// Matches listed after an ambiguous completion.
+    #[builder(skip)]
+    choices: Vec<String>,
+    #[builder(skip)]
+    result: Option<LineResult>,
+}
+
+impl LineEditor {
+    /// The current line.
+    #[must_use]
+    pub fn text(&self) -> String {
+        self.text.iter().collect()
+    }
+
+    /// How input ended, once it has.
+    #[must_use]
+    pub const fn result(&self) -> Option<&LineResult> {
+        self.result.as_ref()
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.text = text.chars().collect();
+        self.cursor = self.text.len();
+    }
+
+    fn recall(&mut self, index: Option<usize>) {
+        if self.recalled.is_none() {
+            self.draft = self.text();
+        }
+        self.recalled = index;
+        let text = match index {
+            Some(index) => self.history[index].clone(),
+            None => std::mem::take(&mut self.draft),
+        };
+        self.set_text(&text);
+    }
+
+    fn complete_word(&mut self) {
+        let Some(complete) = &self.complete else {
+            return;
+        };
+        let before: String = self.text[..self.cursor].iter().collect();
+        let word_start = before.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let word = &before[word_start..];
+
+        let (completed, choices) = complete_word(word, &complete(&before));
+        self.choices = choices;
+        if completed != word {
+            let tail = self.text.split_off(self.cursor);
+            self.text.truncate(self.text.len() - word.chars().count());
+            self.text.extend(completed.chars());
+            self.cursor = self.text.len();
+            self.text.extend(tail);
+        }
+    }
+}
+
+/// Complete `word` against `candidates`: a single match is completed with a
+/// trailing space; several are completed to their longest common prefix and
+/// returned so they can be listed.
+pub fn complete_word(word: &str, candidates: &[String]) -> (String, Vec<String>) {
+    let mut matches: Vec<String> =
+        candidates.iter().filter(|c| c.starts_with(word)).cloned().collect();
+    matches.sort();
+    matches.dedup();
+    match matches.as_slice() {
+        [] => (word.to_string(), Vec::new()),
+        [only] => (format!("{only} "), Vec::new()),
+        [first, rest @ ..] => {
+            let mut prefix = first.as_str();
+            for other in rest {
+                let common = prefix
+                    .char_indices()
+                    .zip(other.chars())
+                    .take_while(|((_, a), b)| a == b)
+                    .count();
+                let end = prefix.char_indices().nth(common).map_or(prefix.len(), |(i, _)| i);
+                prefix = &prefix[..end];
+            }
+            (prefix.to_string(), matches)
+        },
+    }
+}
+
+impl Model for LineEditor {
+    type Message = LineEditorMsg;
+
+    fn init(&self) -> Option<Cmd<Self::Message>> {
+        None
+    }
+
+    fn update(&mut self, msg: Self::Message) -> Option<Cmd<Self::Message>> {
+        if !matches!(msg, LineEditorMsg::Complete) {
+            self.choices.clear();
+        }
+        match msg {
+            LineEditorMsg::Insert(c) => {
+                self.text.insert(self.cursor, c);
+                self.cursor += 1;
+            },
+            LineEditorMsg::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.cursor);
+            },
+            LineEditorMsg::Delete if self.cursor < self.text.len() => {
+                self.text.remove(self.cursor);
+            },
+            LineEditorMsg::Left => self.cursor = self.cursor.saturating_sub(1),
+            LineEditorMsg::Right => self.cursor = (self.cursor + 1).min(self.text.len()),
+            LineEditorMsg::Home => self.cursor = 0,
+            LineEditorMsg::End => self.cursor = self.text.len(),
+            LineEditorMsg::KillToStart => {
+                self.text.drain(..self.cursor);
+                self.cursor = 0;
+            },
+            LineEditorMsg::Previous if !self.history.is_empty() => {
+                let index = self.recalled.map_or(self.history.len() - 1, |i| i.saturating_sub(1));
+                self.recall(Some(index));
+            },
+            LineEditorMsg::Next => {
+                if let Some(index) = self.recalled {
+                    let next = (index + 1 < self.history.len()).then_some(index + 1);
+                    self.recall(next);
+                }
+            },
+            LineEditorMsg::Complete => self.complete_word(),
+            LineEditorMsg::Submit => {
+                self.result = Some(LineResult::Line(self.text()));
+                return Some(Cmd::quit());
+            },
+            LineEditorMsg::Interrupt => {
+                self.result = Some(LineResult::Interrupted);
+                return Some(Cmd::quit());
+            },
+            LineEditorMsg::Eof if self.text.is_empty() => {
+                self.result = Some(LineResult::Eof);
+                return Some(Cmd::quit());
+            },
+            _ => {},
+        }
+        None
+    }
+
+    fn view(&self) -> String {
+        let before: String = self.text[..self.cursor].iter().collect();
+        let under = self.text.get(self.cursor).copied().unwrap_or(' ');
+        let after: String = self.text.get(self.cursor + 1..).unwrap_or_default().iter().collect();
+        let mut view = format!("{}{before}{REVERSE}{under}{RESET}{after}\n", self.prompt);
+        if !self.choices.is_empty() {
+            view.push_str(&self.choices.join("  "));
+            view.push('\n');
+        }
+        view
+    }
+
+    fn handle_event(&self, event: Event) -> Option<Self::Message> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        let ctrl = key.modifiers.ctrl();
+        match key.code {
+            KeyCode::Char('c') if ctrl => Some(LineEditorMsg::Interrupt),
+            KeyCode::Char('d') if ctrl => Some(LineEditorMsg::Eof),
+            KeyCode::Char('a') if ctrl => Some(LineEditorMsg::Home),
+            KeyCode::Char('e') if ctrl => Some(LineEditorMsg::End),
+            KeyCode::Char('u') if ctrl => Some(LineEditorMsg::KillToStart),
+            KeyCode::Char('p') if ctrl => Some(LineEditorMsg::Previous),
+            KeyCode::Char('n') if ctrl => Some(LineEditorMsg::Next),
+            KeyCode::Char(c) if !ctrl => Some(LineEditorMsg::Insert(c)),
+            KeyCode::Backspace => Some(LineEditorMsg::Backspace),
+            KeyCode::Delete => Some(LineEditorMsg::Delete),
+            KeyCode::Left => Some(LineEditorMsg::Left),
+            KeyCode::Right => Some(LineEditorMsg::Right),
+            KeyCode::Home => Some(LineEditorMsg::Home),
+            KeyCode::End => Some(LineEditorMsg::End),
+            KeyCode::Up => Some(LineEditorMsg::Previous),
+            KeyCode::Down => Some(LineEditorMsg::Next),
+            KeyCode::Tab => Some(LineEditorMsg::Complete),
+            KeyCode::Enter => Some(LineEditorMsg::Submit),
+            _ => None,
+        }
+    }
+}
+
+/// Read a line with `editor`, leaving `prompt` and the text on screen.
+pub fn read_line(editor: LineEditor) -> io::Result<LineResult> {
+    let editor = Program::new(editor).run()?;
+    let lines = editor.view().lines().count();
+    let result = editor.result().cloned().unwrap_or(LineResult::Eof);
+
+    // The runtime exits before redrawing, so replace the last frame (cursor
+    // cell and completion list) with the plain line.
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b[{lines}F\x1b[J{}", editor.prompt)?;
+    match &result {
+        LineResult::Line(text) => writeln!(stdout, "{text}")?,
+        LineResult::Interrupted => writeln!(stdout, "{}^C", editor.text())?,
+        LineResult::Eof => writeln!(stdout)?,
+    }
+    stdout.flush()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn editor(history: &[&str]) -> LineEditor {
+        let complete: CompleteFn = Arc::new(|_: &str| {
+            vec!["user:alice".to_string(), "user:bob".to_string(), "doc:readme".to_string()]
+        });
+        LineEditor::builder()
+            .prompt("> ")
+            .history(history.iter().map(ToString::to_string).collect())
+            .complete(complete)
+            .build()
+    }
+
+    fn type_text(editor: &mut LineEditor, text: &str) {
+        for c in text.chars() {
+            editor.update(LineEditorMsg::Insert(c));
+        }
+    }
+
+    #[test]
+    fn test_complete_word() {
+        let candidates = vec!["check".to_string(), "clear".to_string(), "list".to_string()];
+        assert_eq!(complete_word("li", &candidates), ("list ".to_string(), Vec::new()));
+        assert_eq!(
+            complete_word("c", &candidates),
+            ("c".to_string(), vec!["check".to_string(), "clear".to_string()])
+        );
+        assert_eq!(complete_word("x", &candidates), ("x".to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn test_tab_completes_before_cursor() {
+        let mut editor = editor(&[]);
+        type_text(&mut editor, "check user:");
+        editor.update(LineEditorMsg::Complete);
+        assert_eq!(editor.text(), "check user:");
+        assert!(editor.view().contains("user:alice  user:bob\n"));
+
+        type_text(&mut editor, "a");
+        editor.update(LineEditorMsg::Complete);
+        assert_eq!(editor.text(), "check user:alice ");
+        assert!(!editor.view().contains("user:bob"));
+    }
+
+    #[test]
+    fn test_history() {
+        let mut editor = editor(&["list", "status"]);
+        type_text(&mut editor, "che");
+        editor.update(LineEditorMsg::Previous);
+        assert_eq!(editor.text(), "status");
+        editor.update(LineEditorMsg::Previous);
+        editor.update(LineEditorMsg::Previous);
+        assert_eq!(editor.text(), "list");
+        editor.update(LineEditorMsg::Next);
+        editor.update(LineEditorMsg::Next);
+        assert_eq!(editor.text(), "che");
+
+        editor.update(LineEditorMsg::Submit);
+        assert_eq!(editor.result(), Some(&LineResult::Line("che".to_string())));
+    }
+}
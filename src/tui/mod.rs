@@ -13,20 +13,35 @@
 //! - [`DevUninstallView`] - Uninstall with confirmation modal
 //! - [`DevStartView`] - Start cluster with Tailscale setup modals
 //! - [`DevStopView`] - Stop/pause cluster with progress
+//! - [`SwitchView`] - Fuzzy picker for the default profile, organization, and vault
+//! - [`ExploreView`] - Relationship browser with drill-down, expansion, and checks
+//!
+//! In accessibility mode (see [`accessibility_enabled`]) these views are
+//! skipped in favor of line-by-line output.
 
+mod accessibility;
 mod confirm;
 pub mod doctor_view;
+pub mod explore_view;
 mod form;
 pub mod install_view;
+pub mod line_editor;
 mod spinner;
 pub mod start_view;
 pub mod status_view;
 pub mod stop_view;
+pub mod switch_view;
 pub mod uninstall_view;
 
+pub use accessibility::{
+    Status, accessibility_enabled, is_accessible, print_status, set_accessible, status_line,
+};
 pub use confirm::{ConfirmOptions, ConfirmResult, confirm, confirm_danger, confirm_with_options};
 pub use doctor_view::{CheckResult, DevDoctorView, DevDoctorViewMsg};
-pub use form::{is_accessible, run_form};
+pub use explore_view::{
+    CheckFn, ExpandFn, ExploreRow, ExploreView, ExploreViewMsg, Prompt, RelationshipsFn,
+};
+pub use form::run_form;
 pub use install_view::{DevInstallView, DevInstallViewMsg, InstallStep, StepExecutor};
 pub use spinner::{SpinnerHandle, spin, spin_result, start as start_spinner};
 pub use start_view::{DevStartView, DevStartViewMsg, StartPhase};
@@ -35,6 +50,7 @@ pub use status_view::{
     StatusTab, TabData, TableRow,
 };
 pub use stop_view::{DevStopView, DevStopViewMsg};
+pub use switch_view::{SwitchTarget, SwitchView, SwitchViewMsg, fuzzy_score};
 pub use uninstall_view::{DevUninstallView, DevUninstallViewMsg, UninstallInfo};
 
 use crate::client::Context;
@@ -45,7 +61,11 @@ use crate::client::Context;
 /// - Running in CI
 /// - Output is not a TTY
 /// - Quiet mode is enabled
+/// - Accessibility mode is enabled
 #[must_use]
 pub fn is_interactive(ctx: &Context) -> bool {
-    !ctx.output.is_quiet() && teapot::output::is_tty() && !teapot::output::is_ci()
+    !ctx.output.is_quiet()
+        && !accessibility_enabled()
+        && teapot::output::is_tty()
+        && !teapot::output::is_ci()
 }
@@ -1,6 +1,7 @@
 //! Async spinner utilities for CLI operations.
 //!
 //! Provides easy-to-use spinners for long-running async operations.
+//! In accessibility mode, spinners print plain status lines instead of animating.
 //!
 //! # Example
 //!
@@ -29,6 +30,9 @@ use teapot::{
     style::{CLEAR_LINE, Color},
 };
 
+use super::accessibility::{Status, accessibility_enabled, print_status};
+use crate::output::glyphs;
+
 /// Handle to control a running spinner.
 pub struct SpinnerHandle {
     running: Arc<AtomicBool>,
@@ -47,36 +51,31 @@ impl SpinnerHandle {
     /// Stop with a success message.
     pub fn success(mut self, message: &str) {
         self.stop();
-        clear_line();
-        teapot::output::success(message);
+        finish(Status::Success, message, teapot::output::success);
     }
 
     /// Stop with an error message.
     pub fn error(mut self, message: &str) {
         self.stop();
-        clear_line();
-        teapot::output::error(message);
+        finish(Status::Error, message, teapot::output::error);
     }
 
     /// Stop with a failure message (alias for error).
     pub fn failure(mut self, message: &str) {
         self.stop();
-        clear_line();
-        teapot::output::error(message);
+        finish(Status::Error, message, teapot::output::error);
     }
 
     /// Stop with a warning message.
     pub fn warning(mut self, message: &str) {
         self.stop();
-        clear_line();
-        teapot::output::warning(message);
+        finish(Status::Warning, message, teapot::output::warning);
     }
 
     /// Stop with an info message.
     pub fn info(mut self, message: &str) {
         self.stop();
-        clear_line();
-        teapot::output::info(message);
+        finish(Status::Info, message, teapot::output::info);
     }
 
     /// Stop without any message (clears the line).
@@ -112,17 +111,23 @@ pub fn start(message: impl Into<String>) -> SpinnerHandle {
     let running = Arc::new(AtomicBool::new(true));
     let running_clone = running.clone();
 
+    // In accessible mode, announce the status as a plain line instead of animating
+    if accessibility_enabled() {
+        print_status(Status::Info, &message);
+        return SpinnerHandle { running, join_handle: None };
+    }
+
     // In non-interactive mode, just print the message
     if !is_tty() || is_ci() {
-        teapot::output::info(&message);
+        teapot::output::info(&glyphs::text(&message));
         return SpinnerHandle { running, join_handle: None };
     }
 
     let join_handle = std::thread::spawn(move || {
-        let mut spinner =
-            Spinner::new().style(SpinnerStyle::Dots).color(Color::Cyan).message(&message);
+        let style = if glyphs::is_ascii() { SpinnerStyle::Line } else { SpinnerStyle::Dots };
+        let mut spinner = Spinner::new().style(style).color(Color::Cyan).message(&message);
 
-        let sleep_duration = SpinnerStyle::Dots.interval();
+        let sleep_duration = style.interval();
 
         while running_clone.load(Ordering::SeqCst) {
             // Clear line and print spinner
@@ -201,9 +206,22 @@ where
     }
 }
 
+/// Clear the spinner line and print a final status message.
+fn finish(status: Status, message: &str, print: fn(&str)) {
+    if accessibility_enabled() {
+        print_status(status, message);
+    } else if glyphs::is_ascii() {
+        clear_line();
+        eprintln!("{} {}", status.glyph(), glyphs::text(message));
+    } else {
+        clear_line();
+        print(message);
+    }
+}
+
 /// Clear the current line.
 fn clear_line() {
-    if is_tty() && !is_ci() {
+    if is_tty() && !is_ci() && !accessibility_enabled() {
         eprint!("\r{CLEAR_LINE}");
         let _ = io::stderr().flush();
     }
@@ -18,6 +18,7 @@ use teapot::{
 };
 
 use super::install_view::{InstallStep, StepExecutor, StepResult};
+use crate::output::theme::{self, Role};
 
 /// Create a clickable terminal hyperlink using OSC 8 escape sequences.
 ///
@@ -370,9 +371,9 @@ Step 3: Create OAuth client
 
             let modal = Modal::new(modal_width, modal_height)
                 .border(ModalBorder::Rounded)
-                .border_color(Color::Red)
+                .border_color(theme::current().color(Role::Error))
                 .title("Error")
-                .title_color(Color::Red)
+                .title_color(theme::current().color(Role::Error))
                 .content(format!("Failed: {task_name}\n\n{error_msg}"))
                 .footer_hint("esc", "close");
 
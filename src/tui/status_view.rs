@@ -18,6 +18,8 @@ use teapot::{
     util::{ScrollState, measure_text},
 };
 
+use crate::output::theme::{self, Role};
+
 /// Data returned by a refresh callback.
 #[derive(Clone)]
 pub struct RefreshResult {
@@ -186,11 +188,11 @@ impl EnvironmentStatus {
 
     /// Get the color for this status.
     #[must_use]
-    pub const fn color(&self) -> Color {
+    pub fn color(&self) -> Color {
         match self {
-            Self::Checking => Color::Yellow,
-            Self::Ready => Color::Green,
-            Self::NotReady => Color::Red,
+            Self::Checking => theme::current().color(Role::Warning),
+            Self::Ready => theme::current().color(Role::Success),
+            Self::NotReady => theme::current().color(Role::Error),
         }
     }
 
@@ -523,9 +525,9 @@ impl DevStatusView {
 
         let modal = Modal::new(modal_width, modal_height)
             .border(ModalBorder::Rounded)
-            .border_color(Color::Yellow)
+            .border_color(theme::current().color(Role::Warning))
             .title("Cluster Offline")
-            .title_color(Color::Yellow)
+            .title_color(theme::current().color(Role::Warning))
             .content(content)
             .footer_hint("esc", "close");
 
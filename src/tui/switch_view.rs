@@ -0,0 +1,409 @@
+//! Tenant switcher view.
+//!
+//! A full-screen picker over every profile, organization, and vault the user
+//! can access, with fuzzy search. Enter selects the highlighted target.
+
+use bon::bon;
+use teapot::{
+    Cmd, Model,
+    components::{Column, FooterHints, Table, TitleBar},
+    style::{Color, RESET},
+    terminal::{Event, KeyCode, KeyModifiers},
+    util::ScrollState,
+};
+
+/// A profile, organization, and vault combination to switch to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwitchTarget {
+    /// Profile name.
+    pub profile: String,
+    /// Organization ID.
+    pub org_id: String,
+    /// Organization name.
+    pub org_name: String,
+    /// Vault ID.
+    pub vault_id: String,
+    /// Vault name.
+    pub vault_name: String,
+    /// Whether this is the current default.
+    pub current: bool,
+}
+
+impl SwitchTarget {
+    /// Text matched by the search.
+    fn search_text(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.profile, self.org_name, self.vault_name, self.org_id, self.vault_id
+        )
+    }
+}
+
+/// Score how well `query` fuzzy-matches `text`, or `None` when the query's
+/// characters do not all appear in order.
+///
+/// Consecutive matches and matches at the start of a word score higher.
+#[must_use]
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = pos + text[pos..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+/// Message type for the switch view.
+#[derive(Clone)]
+pub enum SwitchViewMsg {
+    /// Add a character to the search.
+    Insert(char),
+    /// Remove the last search character.
+    Backspace,
+    /// Move selection up.
+    SelectPrev,
+    /// Move selection down.
+    SelectNext,
+    /// Page up.
+    PageUp,
+    /// Page down.
+    PageDown,
+    /// Switch to the selected target.
+    Submit,
+    /// Quit without switching.
+    Cancel,
+    /// Resize the view.
+    Resize {
+        /// New width.
+        width: usize,
+        /// New height.
+        height: usize,
+    },
+}
+
+/// Tenant switcher view.
+pub struct SwitchView {
+    /// Terminal width.
+    width: usize,
+    /// Terminal height.
+    height: usize,
+    /// Every target, in display order.
+    targets: Vec<SwitchTarget>,
+    /// Search text.
+    query: String,
+    /// Indices of targets matching the search, best first.
+    matches: Vec<usize>,
+    /// Scroll state for table navigation.
+    scroll: ScrollState,
+    /// Target chosen with enter.
+    chosen: Option<usize>,
+}
+
+#[bon]
+impl SwitchView {
+    /// Create a new switch view.
+    #[must_use]
+    #[builder]
+    pub fn new(
+        width: usize,
+        height: usize,
+        targets: Vec<SwitchTarget>,
+        #[builder(default)] query: String,
+    ) -> Self {
+        let mut view = Self {
+            width,
+            height,
+            targets,
+            query,
+            matches: Vec::new(),
+            scroll: ScrollState::new(),
+            chosen: None,
+        };
+        view.refilter();
+        if view.query.is_empty()
+            && let Some(current) = view.targets.iter().position(|t| t.current)
+        {
+            view.scroll.set_selected(current);
+            view.clamp_scroll();
+        }
+        view
+    }
+
+    /// The target chosen with enter, if any.
+    #[must_use]
+    pub fn chosen(&self) -> Option<&SwitchTarget> {
+        self.chosen.map(|i| &self.targets[i])
+    }
+
+    /// Targets matching the search, best first.
+    pub fn matches(&self) -> impl Iterator<Item = &SwitchTarget> {
+        self.matches.iter().map(|&i| &self.targets[i])
+    }
+
+    /// Recompute matches for the current search.
+    fn refilter(&mut self) {
+        let mut scored: Vec<(usize, u32)> = self
+            .targets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, t)| fuzzy_score(&self.query, &t.search_text()).map(|s| (i, s)))
+            .collect();
+        scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        self.matches = scored.into_iter().map(|(i, _)| i).collect();
+        self.scroll.reset();
+    }
+
+    /// Get visible rows for the table.
+    const fn visible_rows(&self) -> usize {
+        // title(1) + blank(1) + search(1) + sep(1) + header(1) + sep(1) + footer(1) = 7
+        self.height.saturating_sub(7)
+    }
+
+    /// Clamp scroll positions.
+    fn clamp_scroll(&mut self) {
+        self.scroll.clamp(self.matches.len(), self.visible_rows());
+    }
+
+    /// Build the table component.
+    fn build_table(&self) -> Table {
+        let rows: Vec<Vec<String>> = self
+            .matches()
+            .map(|t| {
+                vec![
+                    if t.current { "●" } else { "" }.to_string(),
+                    t.profile.clone(),
+                    format!("{} ({})", t.org_name, t.org_id),
+                    format!("{} ({})", t.vault_name, t.vault_id),
+                ]
+            })
+            .collect();
+
+        Table::new()
+            .columns(vec![
+                Column::new(""),
+                Column::new("Profile"),
+                Column::new("Organization"),
+                Column::new("Vault").grow(),
+            ])
+            .rows(rows)
+            .height(self.visible_rows())
+            .width(self.width)
+            .show_borders(false)
+            .header_color(Color::Default)
+            .selected_row_color(Color::Cyan)
+            .with_cursor_row(self.scroll.selected())
+            .with_offset(self.scroll.offset())
+    }
+
+    /// Render the search line with the match count right-aligned.
+    fn render_search_line(&self) -> String {
+        let count = format!("{}/{}", self.matches.len(), self.targets.len());
+        let search = format!("> {}", self.query);
+        let padding = self.width.saturating_sub(search.chars().count() + count.chars().count() + 1);
+        format!("{search}{}{count} ", " ".repeat(padding))
+    }
+
+    /// Render the footer hints.
+    fn render_footer(&self) -> String {
+        FooterHints::new()
+            .hints(vec![("↑/↓", "select"), ("enter", "switch"), ("esc", "cancel")])
+            .width(self.width)
+            .render()
+    }
+}
+
+impl Model for SwitchView {
+    type Message = SwitchViewMsg;
+
+    fn init(&self) -> Option<Cmd<Self::Message>> {
+        None
+    }
+
+    fn update(&mut self, msg: Self::Message) -> Option<Cmd<Self::Message>> {
+        match msg {
+            SwitchViewMsg::Insert(c) => {
+                self.query.push(c);
+                self.refilter();
+            },
+            SwitchViewMsg::Backspace => {
+                if self.query.pop().is_some() {
+                    self.refilter();
+                }
+            },
+            SwitchViewMsg::SelectPrev => {
+                self.scroll.select_prev();
+            },
+            SwitchViewMsg::SelectNext => {
+                self.scroll.select_next(self.matches.len(), self.visible_rows());
+            },
+            SwitchViewMsg::PageUp => {
+                self.scroll.page_up(self.visible_rows());
+            },
+            SwitchViewMsg::PageDown => {
+                self.scroll.page_down(self.matches.len(), self.visible_rows());
+            },
+            SwitchViewMsg::Submit => {
+                if let Some(&index) = self.matches.get(self.scroll.selected()) {
+                    self.chosen = Some(index);
+                    return Some(Cmd::quit());
+                }
+            },
+            SwitchViewMsg::Cancel => {
+                return Some(Cmd::quit());
+            },
+            SwitchViewMsg::Resize { width, height } => {
+                self.width = width;
+                self.height = height;
+            },
+        }
+        self.clamp_scroll();
+        None
+    }
+
+    fn view(&self) -> String {
+        let mut output = String::new();
+        let reset = RESET;
+        let dim = Color::BrightBlack.to_ansi_fg();
+
+        // Title bar
+        output.push_str(&TitleBar::new("InferaDB").subtitle("Switch").width(self.width).render());
+        output.push_str("\r\n\r\n");
+
+        // Search line
+        output.push_str(&self.render_search_line());
+        output.push_str("\r\n");
+
+        // Separator
+        output.push_str(&format!("{}{}{}\r\n", dim, "─".repeat(self.width), reset));
+
+        // Table content
+        let content_height = self.visible_rows() + 1; // +1 for header
+        if self.matches.is_empty() {
+            output.push_str(&format!("{dim}  No matches{reset}\r\n"));
+            for _ in 1..content_height {
+                output.push_str("\r\n");
+            }
+        } else {
+            let table_output = self.build_table().render();
+            let table_lines: Vec<&str> = table_output.lines().collect();
+            for i in 0..content_height {
+                if let Some(line) = table_lines.get(i) {
+                    output.push_str(line);
+                }
+                output.push_str("\r\n");
+            }
+        }
+
+        // Padding to push footer to bottom
+        let fixed_overhead = 7; // title + blank + search + sep + sep + footer
+        let padding_needed = self.height.saturating_sub(fixed_overhead + content_height);
+        for _ in 0..padding_needed {
+            output.push_str("\r\n");
+        }
+
+        // Footer separator
+        output.push_str(&format!("{}{}{}\r\n", dim, "─".repeat(self.width), reset));
+
+        // Footer hints
+        output.push_str(&self.render_footer());
+
+        output
+    }
+
+    fn handle_event(&self, event: Event) -> Option<Self::Message> {
+        match event {
+            Event::Key(key) => match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Some(SwitchViewMsg::Cancel)
+                },
+                KeyCode::Esc => Some(SwitchViewMsg::Cancel),
+                KeyCode::Enter => Some(SwitchViewMsg::Submit),
+                KeyCode::Up => Some(SwitchViewMsg::SelectPrev),
+                KeyCode::Down => Some(SwitchViewMsg::SelectNext),
+                KeyCode::PageUp => Some(SwitchViewMsg::PageUp),
+                KeyCode::PageDown => Some(SwitchViewMsg::PageDown),
+                KeyCode::Backspace => Some(SwitchViewMsg::Backspace),
+                KeyCode::Char(c) => Some(SwitchViewMsg::Insert(c)),
+                _ => None,
+            },
+            Event::Resize { width, height } => {
+                Some(SwitchViewMsg::Resize { width: width as usize, height: height as usize })
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(profile: &str, org: &str, vault: &str) -> SwitchTarget {
+        SwitchTarget {
+            profile: profile.to_string(),
+            org_id: format!("org_{org}"),
+            org_name: org.to_string(),
+            vault_id: format!("vlt_{vault}"),
+            vault_name: vault.to_string(),
+            current: false,
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("", "anything").is_some());
+        assert!(fuzzy_score("acpr", "acme production").is_some());
+        assert!(fuzzy_score("rpa", "acme production").is_none());
+        // Consecutive and word-start matches rank higher
+        assert!(fuzzy_score("prod", "acme production") > fuzzy_score("prod", "p-r-o-d"));
+    }
+
+    #[test]
+    fn test_switch_view_filter_and_submit() {
+        let mut view = SwitchView::builder()
+            .width(80)
+            .height(24)
+            .targets(vec![
+                target("default", "acme", "staging"),
+                target("default", "acme", "production"),
+                target("work", "globex", "production"),
+            ])
+            .build();
+        assert_eq!(view.matches().count(), 3);
+
+        for c in "globex".chars() {
+            view.update(SwitchViewMsg::Insert(c));
+        }
+        let matched: Vec<&str> = view.matches().map(|t| t.profile.as_str()).collect();
+        assert_eq!(matched, vec!["work"]);
+
+        view.update(SwitchViewMsg::Submit);
+        assert_eq!(view.chosen().map(|t| t.vault_id.as_str()), Some("vlt_production"));
+    }
+
+    #[test]
+    fn test_switch_view_selects_current() {
+        let mut current = target("work", "globex", "production");
+        current.current = true;
+        let mut view = SwitchView::builder()
+            .width(80)
+            .height(24)
+            .targets(vec![target("default", "acme", "staging"), current])
+            .build();
+
+        view.update(SwitchViewMsg::Submit);
+        assert_eq!(view.chosen().map(|t| t.profile.as_str()), Some("work"));
+    }
+}
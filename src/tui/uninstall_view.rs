@@ -11,11 +11,11 @@ use bon::Builder;
 use teapot::{
     components::{ConfirmationConfig, Phase, TaskProgressMsg, TaskProgressView, TaskStep},
     runtime::{Cmd, Model, Sub},
-    style::Color,
     terminal::Event,
 };
 
 use super::install_view::InstallStep;
+use crate::output::theme::{self, Role};
 
 // ============================================================================
 // Constants
@@ -165,8 +165,8 @@ impl DevUninstallView {
             .subtitle("Uninstall")
             .confirmation(ConfirmationConfig {
                 title: "Confirm Uninstall".to_string(),
-                title_color: Color::Yellow,
-                border_color: Color::Yellow,
+                title_color: theme::current().color(Role::Warning),
+                border_color: theme::current().color(Role::Warning),
                 content_fn: Box::new(|ctx: &dyn Any| {
                     if let Some(uctx) = ctx.downcast_ref::<UninstallContext>() {
                         let mut lines = uctx.info.removal_lines();
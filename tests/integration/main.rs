@@ -86,12 +86,86 @@ fn test_profile_prefix() {
     use inferadb_cli::cli::parse_profile_prefix;
 
     let args = vec!["inferadb".to_string(), "@prod".to_string(), "check".to_string()];
-    let (profile, remaining) = parse_profile_prefix(args);
+    let (profile, remaining) = parse_profile_prefix(args).unwrap();
     assert_eq!(profile, Some("prod".to_string()));
     assert_eq!(remaining.len(), 2);
 }
 
 #[cfg(test)]
+/// Test that `output.suppress_warnings` in the user config silences warnings.
+#[test]
+fn test_config_suppress_warnings() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let config_dir = dir.path().join("inferadb");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    let run = |yaml: &str| {
+        std::fs::write(config_dir.join("cli.yaml"), yaml).unwrap();
+        inferadb_cmd()
+            .current_dir(dir.path())
+            .env("XDG_CONFIG_HOME", dir.path())
+            .env("XDG_STATE_HOME", dir.path())
+            .args(["profiles", "list"])
+            .assert()
+            .success()
+    };
+
+    let stale = "defaults:\n  relationships.lst.limit: 5\n";
+    run(stale).stderr(predicate::str::contains("invalid_default"));
+    run(&format!("{stale}output:\n  suppress_warnings: [invalid_default]\n"))
+        .stderr(predicate::str::contains("invalid_default").not());
+}
+
+/// Test that `INFERADB_TOKEN` is used instead of stored credentials.
+#[test]
+fn test_token_env_overrides_stored_credentials() {
+    use std::io::{BufRead, BufReader, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut authorization = String::new();
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 2 {
+            if let Some((name, value)) = line.split_once(':')
+                && name.eq_ignore_ascii_case("authorization")
+            {
+                authorization = value.trim().to_string();
+            }
+            line.clear();
+        }
+        let mut stream = stream;
+        stream
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{}",
+            )
+            .unwrap();
+        authorization
+    });
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let config_dir = dir.path().join("inferadb");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("cli.yaml"),
+        format!("default_profile: local\nprofiles:\n  local:\n    url: http://127.0.0.1:{port}\n"),
+    )
+    .unwrap();
+
+    inferadb_cmd()
+        .current_dir(dir.path())
+        .env("XDG_CONFIG_HOME", dir.path())
+        .env("XDG_STATE_HOME", dir.path())
+        .env("XDG_DATA_HOME", dir.path())
+        .env("INFERADB_NO_KEYCHAIN", "1")
+        .env("INFERADB_TOKEN", "ci-token")
+        .args(["api", "/v1/ping"])
+        .assert()
+        .success();
+    assert_eq!(server.join().unwrap(), "Bearer ci-token");
+}
+
 mod config_tests {
     use inferadb_cli::config::{Config, Profile};
     use tempfile::TempDir;